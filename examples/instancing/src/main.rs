@@ -12,9 +12,6 @@ const ORIGIN_X: f32 = 30.0;
 const ORIGIN_Y: f32 = 30.0;
 const RADIUS: f32 = 3.0;
 
-// Darker-than-DeepSky: SteelBlue (0..1)
-const STEEL_BLUE: (f32, f32, f32) = (0.274510, 0.509804, 0.705882);
-
 fn main() {
     let mut window = Window::new("Instancing Demo", WIDTH, HEIGHT, Color::from_rgb(0.07, 0.13, 0.17));
     window.on_resize(|w, h| println!("Window resized: {}x{}", w, h));
@@ -25,7 +22,7 @@ fn main() {
     let mut dots = ShapeRenderable::from_shape(
         ShapeKind::Circle(Circle::new(RADIUS)),
         ShapeStyle {
-            fill: Some(Color::from_rgb(STEEL_BLUE.0, STEEL_BLUE.1, STEEL_BLUE.2)),
+            fill: Some(Color::STEEL_BLUE),
             ..Default::default()
         },
     );
@@ -48,8 +45,7 @@ fn main() {
         }
     }
 
-    let mut positions = base_positions.clone();
-    dots.set_instance_positions(&positions);
+    dots.set_instance_positions(&base_positions);
     dots.set_instance_colors(&colors);
 
     app.add_shape(dots);
@@ -58,11 +54,12 @@ fn main() {
         let t = renderer.get_time() as f32;
         let wiggle = (t * 2.0).sin() * 3.0;
 
-        for (dst, base) in positions.iter_mut().zip(base_positions.iter()) {
-            *dst = Vec2::new(base.x + wiggle, base.y + wiggle);
-        }
-
-        shapes[0].set_instance_positions(&positions);
+        // Write into the shape's instance buffer in place, no per-frame Vec.
+        shapes[0].write_instance_positions(base_positions.len(), |positions| {
+            for (dst, base) in positions.iter_mut().zip(base_positions.iter()) {
+                *dst = Vec2::new(base.x + wiggle, base.y + wiggle);
+            }
+        });
     });
 
     app.run();
@@ -4,9 +4,9 @@
 //! to screen coordinates via Mercator + Camera2D. Waypoints are rendered
 //! as small triangles with labels using ShapeRenderable.
 //!
-//! Waypoints are composites (marker + label) managed outside App and
-//! rendered via `on_render`. This avoids interleaved indexing and keeps
-//! the label offset logic encapsulated in the Waypoint struct.
+//! Waypoints are composites (marker + label) held in a `Group` managed
+//! outside App and rendered via `on_render`. The label offset is a child
+//! position inside the group, so only the group is moved each frame.
 //!
 //! - Scroll wheel: zoom in/out (zooms toward cursor)
 //! - Left mouse button drag: pan the view
@@ -16,7 +16,7 @@ extern crate wilhelm_renderer;
 use wilhelm_renderer::core::{
     App, Camera2D, Color, Projection, Renderable, Vec2, Window
 };
-use wilhelm_renderer::graphics2d::shapes::{ShapeKind, ShapeRenderable, ShapeStyle, Text, Triangle};
+use wilhelm_renderer::graphics2d::shapes::{Group, ShapeKind, ShapeRenderable, ShapeStyle, Text, Triangle};
 
 const FONT_PATH: &str = "../../fonts/DejaVuSans.ttf";
 const FONT_SIZE: u32 = 11;
@@ -45,30 +45,33 @@ pub fn wgs84_to_mercator(coords: Vec2) -> Vec2 {
 
 struct Waypoint {
     mercator: Vec2,
-    marker: ShapeRenderable,
-    label: ShapeRenderable,
+    group: Group,
 }
 
 impl Waypoint {
     fn new(lon: f32, lat: f32, name: &str, color: Color, triangle: &Triangle) -> Self {
         let m = wgs84_to_mercator(Vec2::new(lon, lat));
-        let marker = ShapeRenderable::from_shape(
+        let mut marker = ShapeRenderable::from_shape(
             ShapeKind::Triangle(triangle.clone()),
             ShapeStyle::fill(color),
         );
-        let label = ShapeRenderable::from_shape(
+        marker.set_position(0.0, 0.0);
+        let mut label = ShapeRenderable::from_shape(
             ShapeKind::Text(Text::new(name, FONT_PATH, FONT_SIZE)),
             ShapeStyle::fill(color),
         );
-        Self { mercator: Vec2::new(m.x, -m.y), marker, label }
+        label.set_position(LABEL_OFFSET_X, LABEL_OFFSET_Y);
+
+        let mut group = Group::new();
+        group.add_shape(marker);
+        group.add_shape(label);
+        Self { mercator: Vec2::new(m.x, -m.y), group }
     }
 
     fn update_and_render(&mut self, camera: &Camera2D, renderer: &wilhelm_renderer::core::Renderer) {
         let screen_pos = camera.world_to_screen(self.mercator);
-        self.marker.set_position(screen_pos.x, screen_pos.y);
-        self.label.set_position(screen_pos.x + LABEL_OFFSET_X, screen_pos.y + LABEL_OFFSET_Y);
-        self.marker.render(renderer);
-        self.label.render(renderer);
+        self.group.set_position(screen_pos.x, screen_pos.y);
+        self.group.render(renderer);
     }
 }
 
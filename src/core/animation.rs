@@ -0,0 +1,346 @@
+//! Keyframe animation: tracks of timed values bound to properties.
+//!
+//! A [`Track`] maps time to a value through keyframes, with an [`Easing`]
+//! per segment. An [`Animation`] binds tracks to setters and plays them
+//! with play/pause/seek. Register one with
+//! [`App::add_animation`](super::App::add_animation) to have it advanced
+//! every frame, or call [`Animation::advance`] yourself.
+
+use crate::core::color::Color;
+use crate::core::engine::opengl::Vec2;
+use crate::graphics2d::shapes::ShapeRenderable;
+
+/// Easing curve applied to the progress through one keyframe segment.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Hold the start value until the next keyframe.
+    Step,
+    /// Cubic, starting slow.
+    EaseIn,
+    /// Cubic, ending slow.
+    EaseOut,
+    /// Cubic, slow at both ends.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map progress `t` (clamped to `0..=1`) through the curve.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Step => if t < 1.0 { 0.0 } else { 1.0 },
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+                }
+            }
+        }
+    }
+}
+
+/// A value that can be blended between two keyframes.
+pub trait Animatable: Clone {
+    fn interpolate(a: &Self, b: &Self, t: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn interpolate(a: &Self, b: &Self, t: f32) -> Self {
+        a + (b - a) * t
+    }
+}
+
+impl Animatable for (f32, f32) {
+    fn interpolate(a: &Self, b: &Self, t: f32) -> Self {
+        (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+    }
+}
+
+impl Animatable for Vec2 {
+    fn interpolate(a: &Self, b: &Self, t: f32) -> Self {
+        Vec2::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+    }
+}
+
+impl Animatable for Color {
+    fn interpolate(a: &Self, b: &Self, t: f32) -> Self {
+        Color::lerp(*a, *b, t)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Keyframe<T> {
+    time: f32,
+    value: T,
+    easing: Easing,
+}
+
+/// Keyframes of one value over time, in seconds.
+///
+/// The easing of a keyframe shapes the segment that starts at it. Before
+/// the first keyframe the track holds the first value; after the last, the
+/// last value.
+///
+/// ```ignore
+/// let fade = Track::new()
+///     .key(0.0, 0.0)
+///     .key_eased(0.5, 1.0, Easing::EaseOut)
+///     .key(3.0, 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Track<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T: Animatable> Default for Track<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Animatable> Track<T> {
+    pub fn new() -> Self {
+        Self { keys: Vec::new() }
+    }
+
+    /// Add a keyframe with linear easing to the next one.
+    pub fn key(self, time: f32, value: T) -> Self {
+        self.key_eased(time, value, Easing::Linear)
+    }
+
+    /// Add a keyframe whose segment to the next keyframe uses `easing`. A
+    /// keyframe at an existing time replaces it.
+    pub fn key_eased(mut self, time: f32, value: T, easing: Easing) -> Self {
+        let frame = Keyframe { time, value, easing };
+        match self.keys.binary_search_by(|k| k.time.total_cmp(&time)) {
+            Ok(i) => self.keys[i] = frame,
+            Err(i) => self.keys.insert(i, frame),
+        }
+        self
+    }
+
+    /// Time of the last keyframe, or 0 for an empty track.
+    pub fn duration(&self) -> f32 {
+        self.keys.last().map_or(0.0, |k| k.time)
+    }
+
+    /// The value at `time`, or `None` for an empty track.
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let next = self.keys.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keys.first().map(|k| k.value.clone());
+        }
+        let a = &self.keys[next - 1];
+        let Some(b) = self.keys.get(next) else {
+            return Some(a.value.clone());
+        };
+        let t = a.easing.apply((time - a.time) / (b.time - a.time));
+        Some(T::interpolate(&a.value, &b.value, t))
+    }
+}
+
+/// A track bound to the setter that applies its value to a target.
+trait Binding<S: ?Sized> {
+    fn apply(&mut self, time: f32, target: &mut S);
+    fn duration(&self) -> f32;
+}
+
+struct BoundTrack<T, F> {
+    track: Track<T>,
+    setter: F,
+}
+
+impl<S: ?Sized, T: Animatable, F: FnMut(&mut S, T)> Binding<S> for BoundTrack<T, F> {
+    fn apply(&mut self, time: f32, target: &mut S) {
+        if let Some(value) = self.track.sample(time) {
+            (self.setter)(target, value);
+        }
+    }
+
+    fn duration(&self) -> f32 {
+        self.track.duration()
+    }
+}
+
+/// A set of tracks played together against a target, by default the
+/// shapes of an [`App`](super::App).
+///
+/// A new animation is playing from time 0. Without looping it stops at its
+/// duration (the latest keyframe of any track) and holds the final values.
+///
+/// ```ignore
+/// let mut intro = Animation::new();
+/// intro.bind(Track::new().key(0.0, (100.0, 300.0)).key_eased(2.0, (600.0, 300.0), Easing::EaseInOut),
+///     move |shapes: &mut [ShapeRenderable], (x, y)| { shapes[logo].set_position(x, y); });
+/// intro.bind(Track::new().key(0.0, 0.0).key(0.5, 1.0),
+///     move |shapes: &mut [ShapeRenderable], a| { shapes[logo].set_opacity(a); });
+/// let id = app.add_animation(intro);
+/// ```
+pub struct Animation<'a, S: ?Sized = [ShapeRenderable]> {
+    bindings: Vec<Box<dyn Binding<S> + 'a>>,
+    time: f32,
+    playing: bool,
+    looping: bool,
+    /// Seeked since the last advance, so values must be re-applied.
+    seeked: bool,
+}
+
+impl<S: ?Sized> Default for Animation<'_, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S: ?Sized> Animation<'a, S> {
+    pub fn new() -> Self {
+        Self { bindings: Vec::new(), time: 0.0, playing: true, looping: false, seeked: false }
+    }
+
+    /// Drive a property with `track`: `setter` receives the target and the
+    /// track's value whenever the animation is applied.
+    pub fn bind<T, F>(&mut self, track: Track<T>, setter: F) -> &mut Self
+    where
+        T: Animatable + 'a,
+        F: FnMut(&mut S, T) + 'a,
+    {
+        self.bindings.push(Box::new(BoundTrack { track, setter }));
+        self
+    }
+
+    /// Restart from the beginning on reaching the end (default off).
+    pub fn set_looping(&mut self, looping: bool) -> &mut Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Resume playing. A finished, non-looping animation restarts.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.time = 0.0;
+        }
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Jump to `time` seconds (clamped to `0..=duration`). Values are
+    /// applied on the next [`advance`](Self::advance), even while paused.
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.duration());
+        self.seeked = true;
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Time of the latest keyframe across all tracks.
+    pub fn duration(&self) -> f32 {
+        self.bindings.iter().map(|b| b.duration()).fold(0.0, f32::max)
+    }
+
+    /// Reached the end without looping.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.time >= self.duration()
+    }
+
+    /// Move time forward by `dt` seconds and apply every track's value to
+    /// `target`. Paused and finished animations leave the target alone
+    /// (so other code may change the same properties), except right after
+    /// a [`seek`](Self::seek).
+    pub fn advance(&mut self, dt: f32, target: &mut S) {
+        if !self.playing && !self.seeked {
+            return;
+        }
+        self.seeked = false;
+        if self.playing {
+            let duration = self.duration();
+            self.time += dt.max(0.0);
+            if self.time >= duration {
+                if self.looping && duration > 0.0 {
+                    self.time %= duration;
+                } else {
+                    self.time = duration;
+                    self.playing = false;
+                }
+            }
+        }
+        self.apply(target);
+    }
+
+    /// Apply every track's value at the current time without moving time.
+    pub fn apply(&mut self, target: &mut S) {
+        for binding in &mut self.bindings {
+            binding.apply(self.time, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_curves_hit_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::Step.apply(0.99), 0.0);
+    }
+
+    #[test]
+    fn track_interpolates_per_segment_and_clamps() {
+        let track = Track::new().key(2.0, 10.0).key(0.0, 0.0).key_eased(4.0, 0.0, Easing::Step);
+        assert_eq!(track.duration(), 4.0);
+        assert_eq!(track.sample(-1.0), Some(0.0));
+        assert_eq!(track.sample(1.0), Some(5.0));
+        assert_eq!(track.sample(3.0), Some(10.0 + (0.0 - 10.0) * 0.5));
+        assert_eq!(track.sample(9.0), Some(0.0));
+        assert_eq!(Track::<f32>::new().sample(1.0), None);
+    }
+
+    #[test]
+    fn animation_plays_pauses_seeks_and_loops() {
+        let mut value = 0.0f32;
+        let mut anim: Animation<f32> = Animation::new();
+        anim.bind(Track::new().key(0.0, 0.0).key(2.0, 20.0), |v: &mut f32, x| *v = x);
+
+        anim.advance(0.5, &mut value);
+        assert_eq!(value, 5.0);
+        anim.pause();
+        anim.advance(1.0, &mut value);
+        assert_eq!(value, 5.0);
+        anim.seek(1.5);
+        anim.advance(1.0, &mut value);
+        assert_eq!(value, 15.0);
+
+        anim.play();
+        anim.advance(5.0, &mut value);
+        assert_eq!(value, 20.0);
+        assert!(anim.is_finished() && !anim.is_playing());
+
+        anim.set_looping(true).play();
+        anim.seek(1.5);
+        anim.advance(1.0, &mut value);
+        assert_eq!(value, 5.0);
+    }
+}
@@ -1,18 +1,140 @@
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
 
+use image::RgbaImage;
+
+use crate::core::animation::Animation;
+use crate::core::assets::AssetLoader;
 use crate::core::camera::{Camera2D, CameraController};
+use crate::core::clock::Clock;
+use crate::core::commands::{CommandBuffer, CommandQueue};
+use crate::core::gpu::flush_deleted_resources;
+use crate::core::input_record::InputRecording;
+use crate::core::recorder::FrameRecorder;
+#[cfg(feature = "serde")]
+use crate::core::scene::Scene;
+#[cfg(feature = "serde")]
+use crate::core::WilhelmError;
+use crate::core::Vec2;
+#[cfg(feature = "script")]
+use crate::script::Script;
+#[cfg(all(feature = "net", feature = "serde"))]
+use crate::net::remote::{screenshot_path, Command, RemoteControl, Request};
 use crate::core::renderer::{Renderable, Renderer};
+use crate::core::timer::{TimerId, Timers};
+use crate::core::layer::{LayerId, Layers};
+use crate::core::engine::glfw::{GLFW_MOD_SHIFT, GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS, GLFW_RELEASE};
+use crate::core::window::MouseButtonEvent;
 use crate::core::Window;
-use crate::graphics2d::shapes::ShapeRenderable;
+#[cfg(feature = "egui")]
+use crate::ui::egui::{egui, EguiOverlay};
+#[cfg(feature = "imgui")]
+use crate::ui::imgui::{imgui, ImguiOverlay};
+use crate::graphics2d::background::{Background, BackgroundGradient};
+use crate::graphics2d::debug_overlay::DebugOverlay;
+use crate::graphics2d::shapes::{merge_selection, BoundsOverlay, EditEvent, RubberBand, ShapeEditor, ShapeRenderable};
 
 pub struct App<'a> {
-    pub window: Box<Window>,
     renderer: Renderer,
     shapes: Vec<ShapeRenderable>,
+    /// Indices into `shapes` sorted by z-order, rebuilt each frame so the
+    /// `shapes` vector itself keeps insertion order for index-based access.
+    draw_order: Vec<usize>,
+    layers: Layers,
+    update_callback: Option<UpdateCallback<'a>>,
     pre_render_callback: Option<Box<dyn FnMut(&mut [ShapeRenderable], &Renderer) + 'a>>,
     render_callback: Option<Box<dyn FnMut(&Renderer, Option<&Camera2D>) + 'a>>,
     camera_controller: Option<Rc<RefCell<CameraController>>>,
+    /// Skip shapes outside the camera's view; see `set_culling`.
+    culling: bool,
+    /// Drawn over the clear color before any shape.
+    background: Option<Background>,
+    /// Drawn over the shapes, with its visibility flag shared with the
+    /// caller; see `enable_debug_overlay`.
+    debug_overlay: Option<(DebugOverlay, Rc<Cell<bool>>)>,
+    /// Outlines drawn shapes' bounds; see `set_debug_bounds`.
+    bounds_overlay: Option<BoundsOverlay>,
+    picking: bool,
+    shape_events: HashMap<usize, ShapeEventHandlers<'a>>,
+    hovered: Option<usize>,
+    pressed: Option<usize>,
+    editing: Option<Editing<'a>>,
+    rubber_band: Option<RubberBand>,
+    selection: Vec<usize>,
+    on_selection_change: Option<SelectionCallback<'a>>,
+    timers: Timers<TimerTask<'a>>,
+    animations: Vec<Animation<'a>>,
+    clock: Rc<RefCell<Clock>>,
+    /// File to write captured input to when `run` returns, and the capture.
+    input_record: Option<(PathBuf, InputRecording)>,
+    input_replay: Option<InputRecording>,
+    frame_recorder: Option<FrameRecorder>,
+    /// Queue handed out by `command_queue` and its receiving end.
+    commands: Option<(CommandQueue, Receiver<CommandBuffer>)>,
+    assets: Option<AssetLoader<'a>>,
+    /// Time of the previous `run_frame`, `None` before the first.
+    last_frame_time: Option<f64>,
+    frame: u64,
+    #[cfg(feature = "egui")]
+    egui: Option<(EguiOverlay, EguiCallback<'a>)>,
+    #[cfg(feature = "imgui")]
+    imgui: Option<(ImguiOverlay, ImguiCallback<'a>)>,
+    /// The remote control listener and the screenshot requests waiting
+    /// for the frame being rendered.
+    #[cfg(all(feature = "net", feature = "serde"))]
+    remote: Option<(RemoteControl, Vec<Request>)>,
+    /// Where remote screenshots may be written; `None` refuses them.
+    #[cfg(all(feature = "net", feature = "serde"))]
+    remote_screenshot_dir: Option<PathBuf>,
+    #[cfg(feature = "script")]
+    script: Option<Script>,
+    /// Declared last so it is dropped last: everything above deletes its
+    /// GL objects while the context still exists.
+    pub window: Box<Window>,
+}
+
+/// A scheduled callback: one-shot tasks are taken when they fire.
+enum TimerTask<'a> {
+    Once(Option<OnceCallback<'a>>),
+    Repeat(ShapesCallback<'a>),
+}
+
+/// Editing-mode state: the editor, the selected shape index and the
+/// change callback.
+struct Editing<'a> {
+    editor: ShapeEditor,
+    selected: Option<usize>,
+    on_edit: Option<EditCallback<'a>>,
+}
+
+type ShapeCallback<'a> = Box<dyn FnMut(&mut ShapeRenderable) + 'a>;
+type OnceCallback<'a> = Box<dyn FnOnce(&mut [ShapeRenderable]) + 'a>;
+type ShapesCallback<'a> = Box<dyn FnMut(&mut [ShapeRenderable]) + 'a>;
+type UpdateCallback<'a> = Box<dyn FnMut(&mut [ShapeRenderable], &Clock) + 'a>;
+type SelectionCallback<'a> = Box<dyn FnMut(&[usize]) + 'a>;
+type EditCallback<'a> = Box<dyn FnMut(usize, &ShapeRenderable, EditEvent) + 'a>;
+#[cfg(feature = "egui")]
+type EguiCallback<'a> = Box<dyn FnMut(&egui::Context) + 'a>;
+#[cfg(feature = "imgui")]
+type ImguiCallback<'a> = Box<dyn FnMut(&imgui::Ui) + 'a>;
+
+#[derive(Clone, Copy)]
+enum ShapeEvent {
+    Click,
+    HoverEnter,
+    HoverExit,
+}
+
+/// Mouse callbacks registered for one shape index.
+#[derive(Default)]
+struct ShapeEventHandlers<'a> {
+    on_click: Option<ShapeCallback<'a>>,
+    on_hover_enter: Option<ShapeCallback<'a>>,
+    on_hover_exit: Option<ShapeCallback<'a>>,
 }
 
 impl<'a> App<'a> {
@@ -22,9 +144,44 @@ impl<'a> App<'a> {
             window,
             renderer,
             shapes: Vec::new(),
+            draw_order: Vec::new(),
+            layers: Layers::new(),
+            update_callback: None,
             pre_render_callback: None,
             render_callback: None,
             camera_controller: None,
+            culling: true,
+            background: None,
+            debug_overlay: None,
+            bounds_overlay: None,
+            picking: false,
+            shape_events: HashMap::new(),
+            hovered: None,
+            pressed: None,
+            editing: None,
+            rubber_band: None,
+            selection: Vec::new(),
+            on_selection_change: None,
+            timers: Timers::new(),
+            animations: Vec::new(),
+            clock: Rc::new(RefCell::new(Clock::new())),
+            input_record: None,
+            input_replay: None,
+            frame_recorder: None,
+            commands: None,
+            assets: None,
+            last_frame_time: None,
+            frame: 0,
+            #[cfg(feature = "egui")]
+            egui: None,
+            #[cfg(feature = "imgui")]
+            imgui: None,
+            #[cfg(all(feature = "net", feature = "serde"))]
+            remote: None,
+            #[cfg(all(feature = "net", feature = "serde"))]
+            remote_screenshot_dir: None,
+            #[cfg(feature = "script")]
+            script: None,
         }
     }
 
@@ -32,14 +189,87 @@ impl<'a> App<'a> {
         &self.renderer
     }
 
-    pub fn add_shape(&mut self, shape: ShapeRenderable) {
+    /// Add a shape and return its index, used to address it in
+    /// [`shapes_mut`](Self::shapes_mut) and the `on_click` / `on_hover_*`
+    /// registrations.
+    pub fn add_shape(&mut self, shape: ShapeRenderable) -> usize {
         self.shapes.push(shape);
+        self.shapes.len() - 1
     }
 
     pub fn add_shapes(&mut self, shapes: Vec<ShapeRenderable>) {
         self.shapes.extend(shapes);
     }
 
+    /// Remove the shape at `index` and return it. Later shapes move down by
+    /// one; their event handlers, hover, selection and editing state move
+    /// with them. Indices captured by callbacks, timers and animations are
+    /// not updated.
+    pub fn remove_shape(&mut self, index: usize) -> ShapeRenderable {
+        let shape = self.shapes.remove(index);
+        self.layers.remove_shape(index);
+        let shift = |i: usize| if i > index { Some(i - 1) } else if i == index { None } else { Some(i) };
+        self.shape_events = std::mem::take(&mut self.shape_events)
+            .into_iter()
+            .filter_map(|(i, handlers)| shift(i).map(|i| (i, handlers)))
+            .collect();
+        self.hovered = self.hovered.and_then(shift);
+        self.pressed = self.pressed.and_then(shift);
+        if let Some(editing) = &mut self.editing {
+            editing.selected = editing.selected.and_then(shift);
+        }
+        if self.selection.contains(&index) || self.selection.iter().any(|&i| i > index) {
+            let selection = self.selection.iter().filter_map(|&i| shift(i)).collect();
+            self.update_selection(selection);
+        }
+        shape
+    }
+
+    /// Add an empty layer drawn at `z_index`. Layers draw in z-index order,
+    /// with shapes not added to any layer at z-index 0; within a layer,
+    /// shapes draw by their own [`z_order`](ShapeRenderable::z_order).
+    pub fn add_layer(&mut self, z_index: i32) -> LayerId {
+        self.layers.add(z_index)
+    }
+
+    /// Add a shape to `layer` and return its index, as with
+    /// [`add_shape`](Self::add_shape).
+    pub fn add_shape_to_layer(&mut self, shape: ShapeRenderable, layer: LayerId) -> usize {
+        let index = self.add_shape(shape);
+        self.layers.assign(index, Some(layer));
+        index
+    }
+
+    /// Move the shape at `index` into `layer`, or out of any layer with
+    /// `None`.
+    pub fn set_shape_layer(&mut self, index: usize, layer: Option<LayerId>) {
+        self.layers.assign(index, layer);
+    }
+
+    pub fn shape_layer(&self, index: usize) -> Option<LayerId> {
+        self.layers.layer_of(index)
+    }
+
+    /// Indices of the shapes in `layer`, in insertion order.
+    pub fn layer_shapes(&self, layer: LayerId) -> Vec<usize> {
+        self.layers.shapes_in(layer)
+    }
+
+    pub fn set_layer_z_index(&mut self, layer: LayerId, z_index: i32) {
+        self.layers.set_z_index(layer, z_index);
+    }
+
+    /// Show or hide every shape in `layer` at once. Shapes in a hidden
+    /// layer are neither drawn nor hit by the mouse, whatever their own
+    /// visibility.
+    pub fn set_layer_visible(&mut self, layer: LayerId, visible: bool) {
+        self.layers.set_visible(layer, visible);
+    }
+
+    pub fn is_layer_visible(&self, layer: LayerId) -> bool {
+        self.layers.is_visible(layer)
+    }
+
     pub fn shapes(&self) -> &[ShapeRenderable] {
         &self.shapes
     }
@@ -48,6 +278,66 @@ impl<'a> App<'a> {
         &mut self.shapes
     }
 
+    /// Index of the topmost visible shape under `(x, y)`, for click
+    /// selection. Shapes are tested exactly with
+    /// [`ShapeRenderable::contains`], in reverse drawing order, so layers
+    /// and z-order decide which of overlapping shapes wins; shapes in
+    /// hidden layers are skipped.
+    pub fn pick(&self, x: f32, y: f32) -> Option<usize> {
+        self.pick_all(x, y).into_iter().next()
+    }
+
+    /// Indices of every visible shape under `(x, y)`, topmost first.
+    pub fn pick_all(&self, x: f32, y: f32) -> Vec<usize> {
+        self.shapes_under(x, y, |_| true).collect()
+    }
+
+    /// Visible shapes in shown layers under `(x, y)` that `keep` accepts,
+    /// topmost first.
+    fn shapes_under<'s>(&'s self, x: f32, y: f32, keep: impl Fn(usize) -> bool + 's) -> impl Iterator<Item = usize> + 's {
+        let shapes = &self.shapes;
+        topmost_first(&self.layers, shapes.len(), |i| shapes[i].z_order(), move |i| {
+            shapes[i].is_visible() && keep(i) && shapes[i].contains(x, y)
+        })
+    }
+
+    /// A queue for [`CommandBuffer`]s recorded on other threads. Buffers
+    /// submitted to it (or any clone) are executed against the shapes at
+    /// the start of each frame, before timers and `on_update`.
+    pub fn command_queue(&mut self) -> CommandQueue {
+        let (queue, _) = self.commands.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            (CommandQueue { sender }, receiver)
+        });
+        queue.clone()
+    }
+
+    /// The app's [`AssetLoader`], started on first use with one worker per
+    /// core (up to four). It is polled at the start of each frame, after
+    /// queued commands, so `on_ready` callbacks can add shapes.
+    pub fn assets(&mut self) -> &mut AssetLoader<'a> {
+        self.assets.get_or_insert_with(|| {
+            let workers = std::thread::available_parallelism().map_or(1, |n| n.get().min(4));
+            AssetLoader::new(workers)
+        })
+    }
+
+    /// Call `callback` once per frame, before `on_pre_render`, with the
+    /// shapes and the [`Clock`]. Use `clock.dt()` to step simulations so
+    /// they follow pause, speed and single-stepping.
+    pub fn on_update<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut [ShapeRenderable], &Clock) + 'a,
+    {
+        self.update_callback = Some(Box::new(callback));
+    }
+
+    /// Shared handle to the app's simulation [`Clock`], for pausing,
+    /// slowing down or single-stepping from input callbacks.
+    pub fn clock(&self) -> Rc<RefCell<Clock>> {
+        Rc::clone(&self.clock)
+    }
+
     pub fn on_pre_render<F>(&mut self, callback: F)
     where
         F: FnMut(&mut [ShapeRenderable], &Renderer) + 'a,
@@ -62,6 +352,441 @@ impl<'a> App<'a> {
         self.render_callback = Some(Box::new(callback));
     }
 
+    /// Build an egui UI each frame, drawn over everything after
+    /// `on_render`. The window's input is forwarded to egui; shape and
+    /// camera callbacks still see it too, so block the camera with
+    /// `ctx.wants_pointer_input()` (see [`enable_camera`](Self::enable_camera))
+    /// where panels overlap the scene. Requires the `egui` feature.
+    ///
+    /// ```ignore
+    /// app.on_egui(move |ctx| {
+    ///     egui::Window::new("Controls").show(ctx, |ui| {
+    ///         ui.add(egui::Slider::new(&mut speed, 0.0..=10.0).text("speed"));
+    ///     });
+    ///     blocked.set(ctx.wants_pointer_input());
+    /// });
+    /// ```
+    #[cfg(feature = "egui")]
+    pub fn on_egui<F>(&mut self, callback: F)
+    where
+        F: FnMut(&egui::Context) + 'a,
+    {
+        self.window.record_ui_input_events();
+        self.egui = Some((EguiOverlay::new(), Box::new(callback)));
+    }
+
+    /// Build Dear ImGui windows each frame, drawn over everything after
+    /// `on_render` (and after egui, if both are used). The window's input
+    /// is forwarded to ImGui; shape and camera callbacks still see it too,
+    /// so block the camera with `ui.io().want_capture_mouse` where windows
+    /// overlap the scene. Requires the `imgui` feature.
+    ///
+    /// ```ignore
+    /// app.on_imgui(move |ui| {
+    ///     ui.window("Controls").build(|| {
+    ///         ui.slider("speed", 0.0, 10.0, &mut speed);
+    ///     });
+    ///     blocked.set(ui.io().want_capture_mouse);
+    /// });
+    /// ```
+    #[cfg(feature = "imgui")]
+    pub fn on_imgui<F>(&mut self, callback: F)
+    where
+        F: FnMut(&imgui::Ui) + 'a,
+    {
+        self.window.record_ui_input_events();
+        self.imgui = Some((ImguiOverlay::new(), Box::new(callback)));
+    }
+
+    /// Call `callback` when the shape at `index` is clicked with the left
+    /// mouse button (pressed and released over it).
+    ///
+    /// Only shapes with at least one mouse callback take part in routing:
+    /// the topmost visible one under the cursor (by z-order, then insertion
+    /// order) receives the event, and shapes without callbacks never block
+    /// the ones below. Hits use [`ShapeRenderable::contains`] in window
+    /// coordinates.
+    pub fn on_click<F>(&mut self, index: usize, callback: F)
+    where
+        F: FnMut(&mut ShapeRenderable) + 'a,
+    {
+        self.shape_handlers(index).on_click = Some(Box::new(callback));
+    }
+
+    /// Call `callback` when the cursor moves onto the shape at `index`.
+    /// See [`on_click`](Self::on_click) for how overlapping shapes resolve.
+    pub fn on_hover_enter<F>(&mut self, index: usize, callback: F)
+    where
+        F: FnMut(&mut ShapeRenderable) + 'a,
+    {
+        self.shape_handlers(index).on_hover_enter = Some(Box::new(callback));
+    }
+
+    /// Call `callback` when the cursor leaves the shape at `index`, another
+    /// shape above it takes the hover, or it is hidden while hovered.
+    pub fn on_hover_exit<F>(&mut self, index: usize, callback: F)
+    where
+        F: FnMut(&mut ShapeRenderable) + 'a,
+    {
+        self.shape_handlers(index).on_hover_exit = Some(Box::new(callback));
+    }
+
+    /// Turn on editing mode for shapes marked
+    /// [`set_editable`](ShapeRenderable::set_editable).
+    ///
+    /// Pressing on an editable shape selects it and shows its grab handles;
+    /// dragging the body moves it and dragging a handle reshapes it (see
+    /// [`ShapeEditor`]). Pressing on empty space clears the selection. A
+    /// press the editor handles is not also routed to `on_click`.
+    pub fn enable_editing(&mut self) -> &mut ShapeEditor {
+        self.window.record_mouse_button_events();
+        &mut self
+            .editing
+            .get_or_insert_with(|| Editing { editor: ShapeEditor::new(), selected: None, on_edit: None })
+            .editor
+    }
+
+    /// Called with the shape index, the shape and the change whenever
+    /// editing selects, moves or reshapes a shape. No-op if
+    /// [`enable_editing`](Self::enable_editing) has not been called.
+    pub fn on_edit<F>(&mut self, callback: F)
+    where
+        F: FnMut(usize, &ShapeRenderable, EditEvent) + 'a,
+    {
+        if let Some(editing) = &mut self.editing {
+            editing.on_edit = Some(Box::new(callback));
+        }
+    }
+
+    /// Index of the shape selected in editing mode.
+    pub fn selected(&self) -> Option<usize> {
+        self.editing.as_ref().and_then(|e| e.selected)
+    }
+
+    /// Select a shape for editing (or clear the selection with `None`),
+    /// firing `Selected` / `Deselected`. No-op if editing is not enabled.
+    pub fn select(&mut self, index: Option<usize>) {
+        let Some(editing) = &mut self.editing else {
+            return;
+        };
+        if editing.selected == index {
+            return;
+        }
+        editing.editor.end_drag();
+        if let Some(old) = editing.selected.take() {
+            Self::emit(editing, &self.shapes, old, EditEvent::Deselected);
+        }
+        if let Some(new) = index.filter(|&i| i < self.shapes.len()) {
+            editing.selected = Some(new);
+            Self::emit(editing, &self.shapes, new, EditEvent::Selected);
+        }
+    }
+
+    fn emit(editing: &mut Editing<'a>, shapes: &[ShapeRenderable], index: usize, event: EditEvent) {
+        if let (Some(callback), Some(shape)) = (editing.on_edit.as_mut(), shapes.get(index)) {
+            callback(index, shape, event);
+        }
+    }
+
+    /// Start an editing drag for a left press at `(x, y)`. Returns `true` if
+    /// the press grabbed a shape or handle and should not be routed further.
+    fn editing_press(&mut self, x: f32, y: f32) -> bool {
+        let Some(editing) = &mut self.editing else {
+            return false;
+        };
+        if let Some(i) = editing.selected {
+            if editing.editor.begin_drag(&self.shapes[i], x, y) {
+                self.refresh_snap_edges(i);
+                return true;
+            }
+        }
+        let hit = self.shapes_under(x, y, |i| self.shapes[i].is_editable()).next();
+        self.select(hit);
+        match (hit, &mut self.editing) {
+            (Some(i), Some(editing)) => {
+                editing.editor.begin_drag(&self.shapes[i], x, y);
+                self.refresh_snap_edges(i);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Point the editor's snapper (if any) at the edges of every visible
+    /// shape except the one being dragged.
+    fn refresh_snap_edges(&mut self, dragged: usize) {
+        let Some(snapper) = self.editing.as_mut().and_then(|e| e.editor.snapper_mut()) else {
+            return;
+        };
+        snapper.set_edges(
+            self.shapes
+                .iter()
+                .enumerate()
+                .filter(|(i, s)| *i != dragged && s.is_visible() && self.layers.shows(*i))
+                .map(|(_, s)| s.bounds()),
+        );
+    }
+
+    fn editing_release(&mut self) {
+        let Some(editing) = &mut self.editing else {
+            return;
+        };
+        if let (Some(EditEvent::Finished), Some(i)) = (editing.editor.end_drag(), editing.selected) {
+            Self::emit(editing, &self.shapes, i, EditEvent::Finished);
+        }
+    }
+
+    /// Whether an editing drag is in progress.
+    fn selected_drag(&self) -> Option<usize> {
+        self.editing.as_ref().filter(|e| e.editor.is_dragging()).and_then(|e| e.selected)
+    }
+
+    /// Apply the in-progress editing drag at `(x, y)`.
+    fn editing_drag(&mut self, x: f32, y: f32) {
+        let Some(editing) = &mut self.editing else {
+            return;
+        };
+        let Some(i) = editing.selected.filter(|_| editing.editor.is_dragging()) else {
+            return;
+        };
+        if let Some(event) = editing.editor.drag_to(&mut self.shapes[i], x, y) {
+            Self::emit(editing, &self.shapes, i, event);
+        }
+    }
+
+    /// Turn on rubber-band selection: a left drag starting where no shape
+    /// takes the press draws a [`RubberBand`] and, on release, selects the
+    /// visible shapes whose bounds intersect it. Holding Shift adds to the
+    /// current selection instead of replacing it.
+    ///
+    /// With [`enable_camera`](Self::enable_camera) the same drag also pans;
+    /// set the returned block flag while Shift or a tool mode is active.
+    pub fn enable_rubber_band_selection(&mut self) -> &mut RubberBand {
+        self.window.record_mouse_button_events();
+        self.rubber_band.get_or_insert_with(RubberBand::new)
+    }
+
+    /// Called with the selected shape indices (sorted) whenever a rubber
+    /// band or [`set_selection`](Self::set_selection) changes them.
+    pub fn on_selection_change<F>(&mut self, callback: F)
+    where
+        F: FnMut(&[usize]) + 'a,
+    {
+        self.on_selection_change = Some(Box::new(callback));
+    }
+
+    /// Indices of the shapes selected by rubber band, sorted.
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    /// Replace the rubber-band selection.
+    pub fn set_selection(&mut self, indices: &[usize]) {
+        let mut selection = Vec::new();
+        merge_selection(&mut selection, indices.iter().copied().filter(|&i| i < self.shapes.len()).collect(), false);
+        self.update_selection(selection);
+    }
+
+    fn update_selection(&mut self, selection: Vec<usize>) {
+        if selection == self.selection {
+            return;
+        }
+        self.selection = selection;
+        if let Some(callback) = self.on_selection_change.as_mut() {
+            callback(&self.selection);
+        }
+    }
+
+    fn rubber_band_release(&mut self) {
+        let Some((rect, additive)) = self.rubber_band.as_mut().and_then(RubberBand::finish) else {
+            return;
+        };
+        let mut hits = RubberBand::shapes_in(rect, &self.shapes);
+        hits.retain(|&i| self.layers.shows(i));
+        let mut selection = self.selection.clone();
+        merge_selection(&mut selection, hits, additive);
+        self.update_selection(selection);
+    }
+
+    fn shape_handlers(&mut self, index: usize) -> &mut ShapeEventHandlers<'a> {
+        self.window.record_mouse_button_events();
+        self.shape_events.entry(index).or_default()
+    }
+
+    /// Topmost visible shape with mouse callbacks that contains `(x, y)`.
+    fn shape_at(&self, x: f64, y: f64) -> Option<usize> {
+        self.shapes_under(x as f32, y as f32, |i| self.shape_events.contains_key(&i)).next()
+    }
+
+    fn set_hovered(&mut self, hit: Option<usize>) {
+        if hit == self.hovered {
+            return;
+        }
+        if let Some(i) = self.hovered.take() {
+            self.fire(i, ShapeEvent::HoverExit);
+        }
+        if let Some(i) = hit {
+            self.fire(i, ShapeEvent::HoverEnter);
+        }
+        self.hovered = hit;
+    }
+
+    fn fire(&mut self, index: usize, event: ShapeEvent) {
+        let (Some(handlers), Some(shape)) = (self.shape_events.get_mut(&index), self.shapes.get_mut(index)) else {
+            return;
+        };
+        let callback = match event {
+            ShapeEvent::Click => &mut handlers.on_click,
+            ShapeEvent::HoverEnter => &mut handlers.on_hover_enter,
+            ShapeEvent::HoverExit => &mut handlers.on_hover_exit,
+        };
+        if let Some(callback) = callback {
+            callback(shape);
+        }
+        #[cfg(feature = "script")]
+        {
+            let hook = match event {
+                ShapeEvent::Click => "on_click",
+                ShapeEvent::HoverEnter => "on_hover_enter",
+                ShapeEvent::HoverExit => "on_hover_exit",
+            };
+            self.call_script(hook, (index as rhai::INT,));
+        }
+    }
+
+    /// Run `script` against this app, replacing any earlier one: its
+    /// top-level statements and `init()` now, then `update(time, dt)` each
+    /// frame after [`on_update`](Self::on_update), and the mouse hooks of
+    /// the shapes it marks `clickable`. See [`crate::script`].
+    #[cfg(feature = "script")]
+    pub fn load_script(&mut self, mut script: Script) -> Result<(), String> {
+        let mut clickable = script.run(&mut self.shapes)?;
+        clickable.extend(script.call(&mut self.shapes, "init", ())?);
+        for index in clickable {
+            self.shape_handlers(index);
+        }
+        self.script = Some(script);
+        Ok(())
+    }
+
+    /// Call a script hook, reporting script errors without stopping.
+    #[cfg(feature = "script")]
+    fn call_script(&mut self, hook: &str, args: impl rhai::FuncArgs) {
+        let Some(script) = &mut self.script else {
+            return;
+        };
+        match script.call(&mut self.shapes, hook, args) {
+            Ok(clickable) => {
+                for index in clickable {
+                    self.shape_handlers(index);
+                }
+            }
+            Err(err) => log::error!("script error: {err}"),
+        }
+    }
+
+    /// Route the mouse events queued since last frame, then refresh hover
+    /// for the current cursor position.
+    fn dispatch_shape_events(&mut self, events: Vec<MouseButtonEvent>) {
+        for event in events {
+            let (x, y) = (event.x as f32, event.y as f32);
+            self.editing_drag(x, y);
+            let hit = self.shape_at(event.x, event.y);
+            self.set_hovered(hit);
+            if event.button != GLFW_MOUSE_BUTTON_LEFT {
+                continue;
+            }
+            if event.action == GLFW_PRESS {
+                self.pressed = if self.editing_press(x, y) { None } else { hit };
+                if let (None, None, Some(band)) = (self.pressed, self.selected_drag(), &mut self.rubber_band) {
+                    band.begin(x, y, event.mods & GLFW_MOD_SHIFT != 0);
+                }
+            } else if event.action == GLFW_RELEASE {
+                self.editing_release();
+                if let Some(band) = &mut self.rubber_band {
+                    band.update(x, y);
+                }
+                self.rubber_band_release();
+                if let (Some(i), true) = (hit, hit == self.pressed) {
+                    self.fire(i, ShapeEvent::Click);
+                }
+                self.pressed = None;
+            }
+        }
+        let (x, y) = self.window.cursor_position();
+        self.editing_drag(x as f32, y as f32);
+        if let Some(band) = &mut self.rubber_band {
+            band.update(x as f32, y as f32);
+        }
+        let hit = self.shape_at(x, y);
+        self.set_hovered(hit);
+    }
+
+    /// Run `callback` once, `delay` from now, with mutable access to the
+    /// shapes. Timers run on the app's [`Clock`], so they fire at the start
+    /// of the first frame at or after the deadline and freeze while it is
+    /// paused.
+    pub fn set_timeout<F>(&mut self, delay: Duration, callback: F) -> TimerId
+    where
+        F: FnOnce(&mut [ShapeRenderable]) + 'a,
+    {
+        let now = self.clock.borrow().time();
+        self.timers.add(now, delay.as_secs_f64(), None, TimerTask::Once(Some(Box::new(callback))))
+    }
+
+    /// Run `callback` every `period`, starting one period from now. If a
+    /// frame takes longer than the period the callback runs once for it,
+    /// not once per missed period.
+    pub fn set_interval<F>(&mut self, period: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut [ShapeRenderable]) + 'a,
+    {
+        let now = self.clock.borrow().time();
+        let period = period.as_secs_f64();
+        self.timers.add(now, period, Some(period), TimerTask::Repeat(Box::new(callback)))
+    }
+
+    /// Cancel a timeout or interval. Returns `false` if it already ran (for
+    /// timeouts) or was cancelled before.
+    pub fn clear_timer(&mut self, id: TimerId) -> bool {
+        self.timers.cancel(id)
+    }
+
+    /// Play `animation` against the shapes, advancing it every frame on the
+    /// app's [`Clock`] before `on_update`. Returns its index for
+    /// [`animation_mut`](Self::animation_mut).
+    pub fn add_animation(&mut self, animation: Animation<'a>) -> usize {
+        self.animations.push(animation);
+        self.animations.len() - 1
+    }
+
+    /// An added animation, to play, pause or seek it.
+    pub fn animation_mut(&mut self, index: usize) -> Option<&mut Animation<'a>> {
+        self.animations.get_mut(index)
+    }
+
+    /// Record all cursor, scroll, key and mouse button input, with the frame
+    /// it arrived in, and write it to `path` when [`run`](Self::run)
+    /// returns. See [`InputRecording`] for the format.
+    pub fn record_input(&mut self, path: impl Into<PathBuf>) {
+        self.input_record = Some((path.into(), InputRecording::new()));
+    }
+
+    /// Feed `recording` through the window callbacks, each event on the
+    /// frame it was recorded in. Live input is ignored until the last
+    /// recorded frame has played. For an exact reproduction, use the same
+    /// window size and a [fixed step](Clock::set_fixed_step) on both runs.
+    pub fn replay_input(&mut self, recording: InputRecording) {
+        self.input_replay = Some(recording);
+    }
+
+    /// Capture rendered frames with `recorder` (after `on_render`, so
+    /// overlays drawn there are included) and finish it when
+    /// [`run`](Self::run) returns. Recording stops at the first write error.
+    pub fn record_frames(&mut self, recorder: FrameRecorder) {
+        self.frame_recorder = Some(recorder);
+    }
+
     /// Enable camera-controlled pan and zoom.
     ///
     /// Creates a [`CameraController`] and wires scroll, cursor, mouse button,
@@ -106,6 +831,63 @@ impl<'a> App<'a> {
         blocked
     }
 
+    /// Skip drawing (and picking) shapes whose [`bounds`] lie outside the
+    /// camera's view, so content spread over a large world only costs draw
+    /// calls while on screen. On by default; only applies once a camera is
+    /// enabled. Shapes with instances are always drawn, since their bounds
+    /// don't cover the instances.
+    ///
+    /// [`bounds`]: ShapeRenderable::bounds
+    pub fn set_culling(&mut self, enabled: bool) {
+        self.culling = enabled;
+    }
+
+    /// Fill the window with a gradient before drawing shapes, or go back to
+    /// the plain background color with `None`. Translucent gradient colors
+    /// blend over the background color.
+    ///
+    /// ```ignore
+    /// app.set_background_gradient(Some(BackgroundGradient::Vertical {
+    ///     top: Color::from_rgb8(0x0b, 0x1d, 0x3a),
+    ///     bottom: Color::from_rgb8(0x02, 0x06, 0x10),
+    /// }));
+    /// ```
+    pub fn set_background_gradient(&mut self, gradient: Option<BackgroundGradient>) {
+        match (&mut self.background, gradient) {
+            (Some(background), Some(gradient)) => background.set_gradient(gradient),
+            (background, gradient) => *background = gradient.map(Background::new),
+        }
+    }
+
+    /// Draw `overlay` over the shapes each frame, through the camera if one
+    /// is enabled and in window pixels otherwise. The returned flag shows
+    /// or hides it, e.g. from a key callback:
+    ///
+    /// ```ignore
+    /// let visible = app.enable_debug_overlay(DebugOverlay::new());
+    /// app.window.on_key(move |key, _, action, _| {
+    ///     if key == GLFW_KEY_F3 && action == GLFW_PRESS {
+    ///         visible.set(!visible.get());
+    ///     }
+    /// });
+    /// ```
+    pub fn enable_debug_overlay(&mut self, overlay: DebugOverlay) -> Rc<Cell<bool>> {
+        let visible = Rc::new(Cell::new(overlay.is_visible()));
+        self.debug_overlay = Some((overlay, Rc::clone(&visible)));
+        visible
+    }
+
+    /// Outline the [`bounds`] of every shape drawn each frame, and of each
+    /// of its instances ([`instance_bounds`]), as thin rectangles. Shapes
+    /// culled by the camera get no outline, so culling, hit-testing and
+    /// anchor bugs show up at a glance.
+    ///
+    /// [`bounds`]: ShapeRenderable::bounds
+    /// [`instance_bounds`]: ShapeRenderable::instance_bounds
+    pub fn set_debug_bounds(&mut self, enabled: bool) {
+        self.bounds_overlay = enabled.then(BoundsOverlay::new);
+    }
+
     /// Set camera smoothness for animated interpolation.
     ///
     /// - `0.0` (default): instant camera updates
@@ -118,6 +900,16 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Rotate the camera with right-button drags; see
+    /// [`CameraController::set_drag_rotation`].
+    ///
+    /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
+    pub fn set_camera_drag_rotation(&mut self, enabled: bool) {
+        if let Some(ctrl) = &self.camera_controller {
+            ctrl.borrow_mut().set_drag_rotation(enabled);
+        }
+    }
+
     /// Set camera zoom sensitivity. Default is 1.1 (10% per scroll tick).
     ///
     /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
@@ -129,7 +921,7 @@ impl<'a> App<'a> {
 
     /// Set minimum and maximum zoom scale limits.
     ///
-    /// When set, scroll zoom is clamped to stay within these bounds.
+    /// When set, zooming is clamped to stay within these bounds.
     /// Pass `None` for either limit to leave it unbounded.
     ///
     /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
@@ -139,39 +931,466 @@ impl<'a> App<'a> {
         }
     }
 
-    pub fn run(mut self) {
-        let mut last_time = self.renderer.get_time();
+    /// Let released camera drags keep gliding; see
+    /// [`CameraController::set_inertia`].
+    ///
+    /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
+    pub fn set_camera_inertia(&mut self, friction: f32) {
+        if let Some(ctrl) = &self.camera_controller {
+            ctrl.borrow_mut().set_inertia(friction);
+        }
+    }
+
+    /// Keep the camera view inside a world rectangle; see
+    /// [`CameraController::set_pan_bounds`].
+    ///
+    /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
+    pub fn set_camera_pan_bounds(&mut self, bounds: Option<(f32, f32, f32, f32)>) {
+        if let Some(ctrl) = &self.camera_controller {
+            ctrl.borrow_mut().set_pan_bounds(bounds);
+        }
+    }
+
+    /// Register a callback for left clicks that don't pan the camera,
+    /// receiving the world position under the cursor. Double-clicks also
+    /// zoom in one step; see [`CameraController::on_click`].
+    ///
+    /// No-op if [`enable_camera`](Self::enable_camera) has not been called.
+    pub fn on_camera_click<F>(&mut self, callback: F)
+    where
+        F: FnMut(Vec2) + 'static,
+    {
+        if let Some(ctrl) = &self.camera_controller {
+            ctrl.borrow_mut().on_click(callback);
+        }
+    }
 
-        while !self.window.window_should_close() {
-            let now = self.renderer.get_time();
-            let dt = (now - last_time) as f32;
-            last_time = now;
+    /// The shapes and camera as a [`Scene`], ready to
+    /// [`save`](Scene::save).
+    #[cfg(feature = "serde")]
+    pub fn scene(&self) -> Scene {
+        let camera = self.camera_controller.as_ref().map(|ctrl| *ctrl.borrow().camera());
+        Scene::from_shapes(&self.shapes, camera.as_ref())
+    }
 
-            if let Some(ctrl) = &self.camera_controller {
-                ctrl.borrow_mut().update(dt);
+    /// Add the scene's shapes after the existing ones and, if it has a
+    /// camera, move the camera there (enabling it if needed). The window
+    /// keeps its current size. Returns the index of the first added shape.
+    ///
+    /// If any shape cannot be built, e.g. it names a missing font, nothing
+    /// is added and the error is returned.
+    #[cfg(feature = "serde")]
+    pub fn load_scene(&mut self, scene: &Scene) -> Result<usize, WilhelmError> {
+        let shapes = scene.try_to_shapes()?;
+        let first = self.shapes.len();
+        self.shapes.extend(shapes);
+        if let Some(mut camera) = scene.camera {
+            match &self.camera_controller {
+                Some(ctrl) => {
+                    let mut ctrl = ctrl.borrow_mut();
+                    camera.set_screen_size(ctrl.camera().screen_size());
+                    ctrl.set_camera(camera);
+                }
+                None => {
+                    let size = Vec2::new(self.window.width() as f32, self.window.height() as f32);
+                    camera.set_screen_size(size);
+                    self.enable_camera(camera);
+                }
             }
+        }
+        Ok(first)
+    }
 
-            self.window.clear_color();
+    /// Let test scripts and external tools drive the app over TCP, and
+    /// return the address listened on (port 0 picks a free port). There is
+    /// no authentication, so only loopback addresses such as
+    /// `127.0.0.1:0` are accepted. A connection is closed at its first line
+    /// that is not a valid command, which also turns away HTTP requests a
+    /// web page might send to the port.
+    ///
+    /// Each request is one line of JSON and gets one line back, `{"ok":
+    /// true, ...}` or `{"ok": false, "error": "..."}`. Commands run between
+    /// frames:
+    ///
+    /// ```text
+    /// {"cmd": "add_shape", "shape": {"kind": {"Circle": {"radius": 20}}, "position": [100, 100]}}   -> {"ok": true, "index": 3}
+    /// {"cmd": "remove_shape", "index": 3}
+    /// {"cmd": "set_style", "index": 0, "style": {"fill": {"r": 1, "g": 0, "b": 0, "a": 1}}}
+    /// {"cmd": "set_position", "index": 0, "x": 50, "y": 80}
+    /// {"cmd": "move_camera", "x": 0, "y": 0, "scale": 2}
+    /// {"cmd": "screenshot", "path": "frame.png"}
+    /// ```
+    ///
+    /// `add_shape` takes a [`SceneShape`](crate::core::SceneShape) and
+    /// `remove_shape` works like [`remove_shape`](Self::remove_shape).
+    /// `move_camera` needs [`enable_camera`](Self::enable_camera); omitted
+    /// fields keep their value. `screenshot` replies once the next frame
+    /// is saved; it takes a plain `.png` file name and is refused until
+    /// [`set_remote_screenshot_dir`](Self::set_remote_screenshot_dir)
+    /// names the directory to write into.
+    #[cfg(all(feature = "net", feature = "serde"))]
+    pub fn enable_remote_control(&mut self, address: impl std::net::ToSocketAddrs) -> std::io::Result<std::net::SocketAddr> {
+        let control = RemoteControl::bind(address)?;
+        let address = control.local_addr();
+        self.remote = Some((control, Vec::new()));
+        Ok(address)
+    }
 
-            if let Some(cb) = self.pre_render_callback.as_mut() {
-                cb(&mut self.shapes, &self.renderer);
+    /// Let the remote `screenshot` command write PNG files into `dir`.
+    #[cfg(all(feature = "net", feature = "serde"))]
+    pub fn set_remote_screenshot_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.remote_screenshot_dir = Some(dir.into());
+    }
+
+    /// Run the remote commands received since the last frame; screenshots
+    /// wait for [`save_remote_screenshots`](Self::save_remote_screenshots).
+    #[cfg(all(feature = "net", feature = "serde"))]
+    fn run_remote_commands(&mut self) {
+        let Some((control, _)) = &self.remote else {
+            return;
+        };
+        let requests: Vec<Request> = control.pending().collect();
+        for request in requests {
+            if let Command::Screenshot { .. } = request.command {
+                if let Some((_, screenshots)) = &mut self.remote {
+                    screenshots.push(request);
+                }
+                continue;
             }
+            let result = self.run_remote_command(request.command);
+            let _ = request.reply.send(result);
+        }
+    }
 
-            self.shapes.sort_by_key(|s| s.z_order());
+    #[cfg(all(feature = "net", feature = "serde"))]
+    fn run_remote_command(&mut self, command: Command) -> Result<serde_json::Value, String> {
+        let count = self.shapes.len();
+        let missing = |index: usize| format!("no shape at index {index} (there are {count})");
+        match command {
+            Command::AddShape { shape } => {
+                let index = self.add_shape(shape.try_to_shape().map_err(|e| e.to_string())?);
+                Ok(serde_json::json!({ "index": index }))
+            }
+            Command::RemoveShape { index } => {
+                if index >= count {
+                    return Err(missing(index));
+                }
+                self.remove_shape(index);
+                Ok(serde_json::Value::Null)
+            }
+            Command::SetStyle { index, style } => {
+                self.shapes.get_mut(index).ok_or_else(|| missing(index))?.set_style(style);
+                Ok(serde_json::Value::Null)
+            }
+            Command::SetPosition { index, x, y } => {
+                self.shapes.get_mut(index).ok_or_else(|| missing(index))?.set_position(x, y);
+                Ok(serde_json::Value::Null)
+            }
+            Command::MoveCamera { x, y, scale } => {
+                let ctrl = self.camera_controller.as_ref().ok_or("the camera is not enabled")?;
+                let mut camera = *ctrl.borrow().camera();
+                let center = camera.center();
+                camera.set_center(Vec2::new(x.unwrap_or(center.x), y.unwrap_or(center.y)));
+                if let Some(scale) = scale {
+                    camera.set_scale(scale);
+                }
+                ctrl.borrow_mut().set_camera(camera);
+                Ok(serde_json::Value::Null)
+            }
+            Command::Screenshot { .. } => Err("screenshots are taken after the frame".to_string()),
+        }
+    }
+
+    /// Save the frame just rendered for each waiting screenshot request.
+    #[cfg(all(feature = "net", feature = "serde"))]
+    fn save_remote_screenshots(&mut self) {
+        let Some((_, screenshots)) = &mut self.remote else {
+            return;
+        };
+        if screenshots.is_empty() {
+            return;
+        }
+        let image = self.renderer.read_framebuffer();
+        for request in screenshots.drain(..) {
+            if let Command::Screenshot { path } = request.command {
+                let result = screenshot_path(self.remote_screenshot_dir.as_deref(), &path).and_then(|file| {
+                    image.save(&file).map_err(|e| e.to_string())?;
+                    Ok(serde_json::json!({ "path": file }))
+                });
+                let _ = request.reply.send(result);
+            }
+        }
+    }
 
-            for shape in &mut self.shapes {
+    /// Render a picking pass each frame after the scene is drawn.
+    ///
+    /// Shapes with a [`PickId`](crate::core::PickId) (set via
+    /// `ShapeRenderable::set_pick_id`) are drawn into an offscreen id buffer;
+    /// [`Renderer::pick`] then returns the id under a window position. Call
+    /// it from `on_render` or input callbacks that hold the renderer.
+    pub fn enable_picking(&mut self) {
+        self.picking = true;
+    }
+
+    /// Render the scene once into the window and return the framebuffer
+    /// contents, without swapping buffers or polling events. Simulation
+    /// time advances by the clock's fixed step (or not at all without
+    /// one), so the result is reproducible. Meant for golden-image tests
+    /// with a [`Window::headless`] window; see [`check_golden`](super::check_golden).
+    pub fn render_to_image(&mut self) -> RgbaImage {
+        self.render_frame(0.0);
+        self.renderer.read_framebuffer()
+    }
+
+    /// Everything drawn in one frame, from clearing the window to the
+    /// render callback; `dt` is the real time since the previous frame.
+    fn render_frame(&mut self, dt: f32) {
+        // GL objects dropped since the last frame, possibly on other threads.
+        flush_deleted_resources();
+        self.renderer.reset_state_cache();
+
+        if let Some(ctrl) = &self.camera_controller {
+            ctrl.borrow_mut().update(dt);
+        }
+
+        self.window.clear_color();
+        if let Some(background) = &mut self.background {
+            background.render(&self.renderer);
+        }
+
+        let clock = {
+            let mut clock = self.clock.borrow_mut();
+            clock.tick(dt);
+            clock.clone()
+        };
+
+        if let Some((_, receiver)) = &self.commands {
+            for commands in receiver.try_iter() {
+                commands.execute(&mut self.shapes);
+            }
+        }
+
+        if let Some(assets) = &mut self.assets {
+            assets.poll(&mut self.shapes);
+        }
+
+        if !self.timers.is_empty() {
+            let shapes = &mut self.shapes;
+            self.timers.fire_due(clock.time(), |task| match task {
+                TimerTask::Once(callback) => {
+                    if let Some(callback) = callback.take() {
+                        callback(shapes);
+                    }
+                }
+                TimerTask::Repeat(callback) => callback(shapes),
+            });
+        }
+
+        for animation in &mut self.animations {
+            animation.advance(clock.dt(), &mut self.shapes);
+        }
+
+        if let Some(cb) = self.update_callback.as_mut() {
+            cb(&mut self.shapes, &clock);
+        }
+        #[cfg(feature = "script")]
+        self.call_script("update", (clock.time(), f64::from(clock.dt())));
+
+        if let Some(cb) = self.pre_render_callback.as_mut() {
+            cb(&mut self.shapes, &self.renderer);
+        }
+
+        let shapes = &self.shapes;
+        self.layers.draw_order(shapes.len(), |i| shapes[i].z_order(), &mut self.draw_order);
+
+        if !self.shape_events.is_empty() || self.editing.is_some() || self.rubber_band.is_some() {
+            let events = self.window.take_mouse_button_events();
+            self.dispatch_shape_events(events);
+        }
+
+        let view = self.camera_controller.as_ref().filter(|_| self.culling).map(|ctrl| *ctrl.borrow().camera());
+        let in_view = |shape: &ShapeRenderable| {
+            shape.is_visible()
+                && view.as_ref().is_none_or(|camera| {
+                    !shape.instance_positions().is_empty() || !shape.instance_transforms().is_empty() || camera.is_visible(shape.bounds())
+                })
+        };
+
+        for &i in &self.draw_order {
+            let shape = &mut self.shapes[i];
+            if in_view(shape) {
                 shape.render(&self.renderer);
             }
+        }
 
-            if let Some(cb) = self.render_callback.as_mut() {
-                let camera = self.camera_controller.as_ref().map(|ctrl| {
-                    *ctrl.borrow().camera()
-                });
-                cb(&self.renderer, camera.as_ref());
+        if let Some(overlay) = &mut self.bounds_overlay {
+            let shapes = &self.shapes;
+            overlay.render(&self.renderer, self.draw_order.iter().map(|&i| &shapes[i]).filter(|shape| in_view(shape)));
+        }
+
+        if let Some(editing) = &mut self.editing {
+            let layers = &self.layers;
+            if let Some(shape) = editing.selected.filter(|&i| layers.shows(i)).map(|i| &self.shapes[i]).filter(|s| s.is_visible()) {
+                editing.editor.render_handles(shape, &self.renderer);
             }
+        }
 
-            self.window.swap_buffers();
-            self.window.poll_events();
+        if let Some(band) = &mut self.rubber_band {
+            band.render(&self.renderer);
+        }
+
+        if let Some((overlay, visible)) = &mut self.debug_overlay {
+            let camera = self.camera_controller.as_ref().map(|ctrl| *ctrl.borrow().camera()).unwrap_or_else(|| {
+                // Without a camera, world coordinates are window pixels.
+                let (w, h) = self.renderer.window_handle.size();
+                let size = Vec2::new(w as f32, h as f32);
+                Camera2D::new(Vec2::new(size.x / 2.0, size.y / 2.0), 1.0, size)
+            });
+            overlay.set_visible(visible.get()).render(&self.renderer, &camera);
+        }
+
+        if self.picking {
+            self.renderer.begin_picking();
+            for &i in &self.draw_order {
+                let shape = &mut self.shapes[i];
+                if let (true, Some(id)) = (in_view(shape), shape.pick_id()) {
+                    self.renderer.set_pick_id(id);
+                    shape.render(&self.renderer);
+                }
+            }
+            self.renderer.end_picking();
         }
+
+        if let Some(cb) = self.render_callback.as_mut() {
+            let camera = self.camera_controller.as_ref().map(|ctrl| {
+                *ctrl.borrow().camera()
+            });
+            cb(&self.renderer, camera.as_ref());
+        }
+
+        #[cfg(any(feature = "egui", feature = "imgui"))]
+        let ui_events = self.window.take_ui_input_events();
+        #[cfg(feature = "egui")]
+        if let Some((overlay, ui)) = &mut self.egui {
+            for &event in &ui_events {
+                overlay.handle_input(event);
+            }
+            overlay.run(&self.renderer, ui);
+        }
+        #[cfg(feature = "imgui")]
+        if let Some((overlay, ui)) = &mut self.imgui {
+            for &event in &ui_events {
+                overlay.handle_input(event);
+            }
+            overlay.frame(&self.renderer, ui);
+        }
+    }
+
+    pub fn run(mut self) {
+        while self.run_frame() {}
+
+        if let Some(recorder) = self.frame_recorder.take() {
+            if let Err(err) = recorder.finish() {
+                log::error!("failed to finish frame recording: {err}");
+            }
+        }
+        if let Some((path, recording)) = &self.input_record {
+            if let Err(err) = recording.save(path) {
+                log::error!("failed to write input recording to {}: {err}", path.display());
+            }
+        }
+    }
+
+    /// Run one iteration of [`run`](Self::run)'s loop: render a frame,
+    /// swap buffers and poll events. Returns `false`, without rendering,
+    /// once the window should close. For hosts that own the event loop;
+    /// frame and input recordings are only written out by `run`.
+    pub fn run_frame(&mut self) -> bool {
+        if self.window.window_should_close() {
+            return false;
+        }
+        let now = self.renderer.get_time();
+        let dt = match self.last_frame_time {
+            Some(last) => (now - last) as f32,
+            None => {
+                if self.input_record.is_some() {
+                    self.window.record_input_events();
+                }
+                if self.input_replay.is_some() {
+                    self.window.set_replaying(true);
+                }
+                0.0
+            }
+        };
+        self.last_frame_time = Some(now);
+
+        #[cfg(all(feature = "net", feature = "serde"))]
+        self.run_remote_commands();
+        self.render_frame(dt);
+        #[cfg(all(feature = "net", feature = "serde"))]
+        self.save_remote_screenshots();
+
+        if let Some(recorder) = &mut self.frame_recorder {
+            if let Err(err) = recorder.capture(&self.renderer) {
+                log::error!("frame recording stopped: {err}");
+                self.frame_recorder = None;
+            }
+        }
+
+        self.window.swap_buffers();
+        self.window.poll_events();
+        self.record_and_replay_input(self.frame);
+        self.frame += 1;
+        true
+    }
+
+    /// Capture this frame's live input and deliver its recorded input.
+    fn record_and_replay_input(&mut self, frame: u64) {
+        if let Some((_, recording)) = &mut self.input_record {
+            for event in self.window.take_input_events() {
+                recording.push(frame, event);
+            }
+        }
+        if let Some(replay) = &self.input_replay {
+            for recorded in replay.frame_events(frame) {
+                self.window.inject_input(recorded.event);
+            }
+            if replay.last_frame().is_none_or(|last| frame >= last) {
+                self.input_replay = None;
+                self.window.set_replaying(false);
+            }
+        }
+    }
+}
+
+/// Indices of the `count` shapes in shown layers that `hit` accepts,
+/// topmost first by layer and then `z_order`.
+fn topmost_first<'s>(
+    layers: &Layers,
+    count: usize,
+    z_order: impl Fn(usize) -> i32,
+    hit: impl Fn(usize) -> bool + 's,
+) -> impl Iterator<Item = usize> + 's {
+    let mut order = Vec::new();
+    layers.draw_order(count, z_order, &mut order);
+    order.into_iter().rev().filter(move |&i| hit(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_are_topmost_first_and_skip_hidden_layers() {
+        let z_orders = [0, 5, 0, 0];
+        let mut layers = Layers::new();
+        let labels = layers.add(10);
+        layers.assign(2, Some(labels));
+        let hits = |layers: &Layers, hit: fn(usize) -> bool| topmost_first(layers, 4, |i| z_orders[i], hit).collect::<Vec<_>>();
+
+        assert_eq!(hits(&layers, |_| true), vec![2, 1, 3, 0]);
+        assert_eq!(hits(&layers, |i| i != 1), vec![2, 3, 0]);
+        layers.set_visible(labels, false);
+        assert_eq!(hits(&layers, |_| true), vec![1, 3, 0]);
     }
 }
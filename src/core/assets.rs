@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use image::ImageReader;
+
+use crate::core::font::FontData;
+use crate::core::gpu::GpuTexture;
+use crate::core::image::Image;
+use crate::core::texture::{generate_texture_from_image_with_options, TextureOptions};
+use crate::core::FontAtlas;
+use crate::graphics2d::shapes::{cache_font_atlas, ShapeRenderable};
+
+/// Atlas size used for loaded fonts, matching the text font cache.
+const FONT_ATLAS_SIZE: u32 = 512;
+
+/// Identifies an asset requested from an [`AssetLoader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AssetId(u64);
+
+/// A loaded asset, uploaded to the GPU.
+pub enum Asset {
+    /// An image texture. Draw it with
+    /// [`ShapeRenderable::image_from_texture`]; the loader owns the
+    /// texture until [`AssetLoader::remove`] is called.
+    Texture { texture: GpuTexture, width: u32, height: u32 },
+    /// A font atlas with the ASCII range cached. It is also put in the text
+    /// font cache, so text shapes with the same font path and size use it.
+    Font(Rc<RefCell<FontAtlas>>),
+}
+
+impl Asset {
+    /// The GL texture of an image or font asset.
+    pub fn texture_id(&self) -> u32 {
+        match self {
+            Asset::Texture { texture, .. } => texture.id(),
+            Asset::Font(atlas) => atlas.borrow().texture_id(),
+        }
+    }
+}
+
+enum Job {
+    Image { path: String, options: TextureOptions },
+    Font { path: String, size: u32 },
+}
+
+/// CPU-side data decoded by a worker, waiting for upload.
+enum Decoded {
+    Image { image: Image, options: TextureOptions },
+    Font { path: String, data: Box<FontData> },
+}
+
+type ReadyCallback<'a> = Box<dyn FnOnce(Result<&Asset, &str>, &mut Vec<ShapeRenderable>) + 'a>;
+
+/// Decodes images and fonts on worker threads and uploads them on the
+/// render thread, so startup with many assets doesn't block the first
+/// frame.
+///
+/// Requests return an [`AssetId`] at once. Each [`poll`](Self::poll) on
+/// the GL thread uploads whatever the workers have finished, then runs the
+/// [`on_ready`](Self::on_ready) callbacks of those assets. [`App`] owns
+/// one and polls it every frame; see [`App::assets`](super::App::assets).
+///
+/// ```ignore
+/// let logo = app.assets().load_image("logo.png");
+/// app.assets().on_ready(logo, |asset, shapes| match asset {
+///     Ok(Asset::Texture { texture, width, height }) => {
+///         shapes.push(ShapeRenderable::image_from_texture(texture.id(), *width as f32, *height as f32));
+///     }
+///     Ok(_) => {}
+///     Err(e) => eprintln!("logo: {e}"),
+/// });
+/// ```
+///
+/// [`App`]: super::App
+pub struct AssetLoader<'a> {
+    jobs: Sender<(AssetId, Job)>,
+    decoded: Receiver<(AssetId, Result<Decoded, String>)>,
+    next_id: u64,
+    pending: usize,
+    assets: HashMap<AssetId, Result<Asset, String>>,
+    callbacks: HashMap<AssetId, Vec<ReadyCallback<'a>>>,
+}
+
+impl<'a> AssetLoader<'a> {
+    /// Start `workers` decoding threads (at least one). They exit when the
+    /// loader is dropped.
+    pub fn new(workers: usize) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<(AssetId, Job)>();
+        let (decoded_sender, decoded) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        for _ in 0..workers.max(1) {
+            let (jobs, decoded) = (Arc::clone(&job_receiver), decoded_sender.clone());
+            std::thread::spawn(move || loop {
+                // Hold the lock only while waiting, not while decoding.
+                let next = jobs.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                let Ok((id, job)) = next else { break };
+                if decoded.send((id, decode(job))).is_err() {
+                    break;
+                }
+            });
+        }
+        Self { jobs, decoded, next_id: 0, pending: 0, assets: HashMap::new(), callbacks: HashMap::new() }
+    }
+
+    /// Decode the image at `path` with default [`TextureOptions`].
+    pub fn load_image(&mut self, path: &str) -> AssetId {
+        self.load_image_with_options(path, TextureOptions::default())
+    }
+
+    pub fn load_image_with_options(&mut self, path: &str, options: TextureOptions) -> AssetId {
+        self.request(Job::Image { path: path.to_string(), options })
+    }
+
+    /// Open the font at `path` and rasterize its ASCII glyphs at `size`
+    /// pixels.
+    pub fn load_font(&mut self, path: &str, size: u32) -> AssetId {
+        self.request(Job::Font { path: path.to_string(), size })
+    }
+
+    fn request(&mut self, job: Job) -> AssetId {
+        let id = AssetId(self.next_id);
+        self.next_id += 1;
+        self.pending += 1;
+        // The workers only stop once `self.jobs` is dropped.
+        let _ = self.jobs.send((id, job));
+        id
+    }
+
+    /// Call `callback` on the render thread once `id` is uploaded or has
+    /// failed, with the shapes to add to. Runs during the next
+    /// [`poll`](Self::poll) if the asset is already done.
+    pub fn on_ready<F>(&mut self, id: AssetId, callback: F)
+    where
+        F: FnOnce(Result<&Asset, &str>, &mut Vec<ShapeRenderable>) + 'a,
+    {
+        self.callbacks.entry(id).or_default().push(Box::new(callback));
+    }
+
+    /// Upload the assets decoded since the last call and run their
+    /// callbacks. Must run on the GL thread. Returns how many assets
+    /// became ready.
+    pub fn poll(&mut self, shapes: &mut Vec<ShapeRenderable>) -> usize {
+        let mut ready = 0;
+        while let Ok((id, decoded)) = self.decoded.try_recv() {
+            self.pending -= 1;
+            ready += 1;
+            if let Err(error) = &decoded {
+                log::warn!("asset {id:?} failed to load: {error}");
+            }
+            self.assets.insert(id, decoded.map(upload));
+        }
+        if !self.callbacks.is_empty() {
+            let done: Vec<AssetId> = self.callbacks.keys().filter(|id| self.assets.contains_key(id)).copied().collect();
+            for id in done {
+                let asset = self.assets[&id].as_ref().map_err(String::as_str);
+                for callback in self.callbacks.remove(&id).unwrap_or_default() {
+                    callback(asset, shapes);
+                }
+            }
+        }
+        ready
+    }
+
+    /// The asset, once uploaded, or why it failed to load.
+    pub fn get(&self, id: AssetId) -> Option<Result<&Asset, &str>> {
+        self.assets.get(&id).map(|asset| asset.as_ref().map_err(String::as_str))
+    }
+
+    /// Drop the loader's copy of an asset, releasing its texture unless
+    /// the font cache still holds it.
+    pub fn remove(&mut self, id: AssetId) -> Option<Result<Asset, String>> {
+        self.assets.remove(&id)
+    }
+
+    /// Number of requested assets not yet decoded and uploaded.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+}
+
+/// Decode on a worker thread.
+fn decode(job: Job) -> Result<Decoded, String> {
+    match job {
+        Job::Image { path, options } => {
+            let image = ImageReader::open(&path)
+                .map_err(|e| format!("Failed to open image {path}: {e}"))?
+                .decode()
+                .map_err(|e| format!("Failed to decode image {path}: {e}"))?
+                .to_rgba8();
+            let (width, height) = image.dimensions();
+            Ok(Decoded::Image { image: Image { width, height, pixels: image.into_raw() }, options })
+        }
+        Job::Font { path, size } => {
+            let data = FontData::load(&path, size, FONT_ATLAS_SIZE)?;
+            Ok(Decoded::Font { path, data: Box::new(data) })
+        }
+    }
+}
+
+/// Upload on the GL thread.
+fn upload(decoded: Decoded) -> Asset {
+    match decoded {
+        Decoded::Image { image, options } => Asset::Texture {
+            texture: GpuTexture::from_raw(generate_texture_from_image_with_options(&image, &options)),
+            width: image.width,
+            height: image.height,
+        },
+        Decoded::Font { path, data } => {
+            let size = data.font_size();
+            Asset::Font(cache_font_atlas(&path, size, data.into_atlas()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workers_decode_images_off_the_calling_thread() {
+        let mut loader = AssetLoader::new(2);
+        let image = loader.load_image(concat!(env!("CARGO_MANIFEST_DIR"), "/images/alpha_transparency.png"));
+        let missing = loader.load_image("no/such/image.png");
+        assert_eq!(loader.pending(), 2);
+
+        // Take the decoded data directly: uploading needs a GL context.
+        let mut results: HashMap<_, _> = (0..2).map(|_| loader.decoded.recv().unwrap()).collect();
+        let Ok(Decoded::Image { image: decoded, .. }) = results.remove(&image).unwrap() else {
+            panic!("image failed to decode");
+        };
+        assert_eq!(decoded.pixels.len(), (decoded.width * decoded.height * 4) as usize);
+        let Err(error) = results.remove(&missing).unwrap() else {
+            panic!("missing image decoded");
+        };
+        assert!(error.contains("no/such/image.png"));
+    }
+}
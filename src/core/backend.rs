@@ -0,0 +1,182 @@
+//! The seam between [`Renderer`](crate::core::Renderer) and the graphics
+//! API. The renderer gathers each draw's state into [`DrawParams`] and
+//! hands it to a [`RenderBackend`], which issues the API calls.
+
+use std::ffi::c_void;
+
+use crate::core::engine::opengl::{
+    gl_active_texture, gl_bind_buffer, gl_bind_texture, gl_bind_vertex_array, gl_blend_func, gl_clear_color,
+    gl_disable, gl_draw_arrays, gl_draw_arrays_instanced, gl_enable, gl_get_integerv, gl_get_uniform_location,
+    gl_point_size, gl_read_pixels, gl_uniform_1f, gl_uniform_2f, gl_uniform_4f, gl_uniform_matrix_4f, gl_use_program,
+    gl_vertex_attrib_4f, gl_viewport, GL_ARRAY_BUFFER, GL_BLEND, GL_ONE_MINUS_SRC_ALPHA, GL_RGBA, GL_SRC_ALPHA,
+    GL_TEXTURE0, GL_TEXTURE_2D, GL_UNSIGNED_BYTE, GL_VIEWPORT,
+};
+use crate::core::math::Mat4;
+use crate::core::{Color, Mesh, PickId};
+
+/// Everything a draw needs besides the mesh's geometry and shader, built
+/// by the renderer for each [`draw_mesh`](crate::core::Renderer::draw_mesh)
+/// and [`draw_mesh_instanced`](crate::core::Renderer::draw_mesh_instanced).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DrawParams {
+    /// `u_Transform`, already mirrored when drawing into a render target.
+    pub(crate) transform: Mat4,
+    /// Screen position plus pivot shift; only the pivot shift for
+    /// instanced draws, whose positions are per instance.
+    pub(crate) screen_offset: (f32, f32),
+    pub(crate) point_size: f32,
+    pub(crate) scale: f32,
+    pub(crate) rotation: f32,
+    /// 1 in the picking pass, so ids are written unblended.
+    pub(crate) opacity: f32,
+    pub(crate) color: Option<Color>,
+    /// Dash length, gap length and current offset, in pixels.
+    pub(crate) dash: Option<(f32, f32, f32)>,
+    pub(crate) texture: Option<u32>,
+    /// The id to write during the picking pass, `None` when drawing colors.
+    pub(crate) pick: Option<PickId>,
+    /// Number of instances, or `None` for a plain draw.
+    pub(crate) instances: Option<i32>,
+}
+
+/// Issues the graphics API calls for a [`Renderer`](crate::core::Renderer).
+///
+/// Crate-private and GL-shaped for now: `draw` binds the mesh's VAO and
+/// program, and meshes create their GL buffers and textures themselves.
+/// [`OpenGlBackend`] is the only implementation.
+pub(crate) trait RenderBackend {
+    /// Draw `mesh`'s geometry with its shader and `params`.
+    fn draw(&self, mesh: &Mesh, params: &DrawParams);
+
+    /// Set the area drawn into, in framebuffer pixels.
+    fn set_viewport(&self, width: i32, height: i32);
+
+    /// Size of the area drawn into, in framebuffer pixels.
+    fn viewport_size(&self) -> (i32, i32);
+
+    /// Fill the bound framebuffer with `color`.
+    fn clear(&self, color: Color);
+
+    /// Size of `GL_POINTS` on APIs that set it outside the shader.
+    fn set_point_size(&self, point_size: f32);
+
+    /// RGBA pixels of a `width` x `height` area of the bound framebuffer,
+    /// bottom row first.
+    fn read_pixels(&self, x: i32, y: i32, width: i32, height: i32) -> Vec<u8>;
+
+    /// Unbind what drawing left bound, for hosts that share the context.
+    fn end_frame(&self);
+}
+
+/// Draws through the OpenGL functions loaded by `wilhelm_renderer_sys`.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct OpenGlBackend;
+
+impl RenderBackend for OpenGlBackend {
+    fn draw(&self, mesh: &Mesh, params: &DrawParams) {
+        let program = mesh.shader.program();
+        mesh.shader.use_program();
+        mesh.geometry.bind();
+
+        match params.pick {
+            None => {
+                gl_enable(GL_BLEND);
+                gl_blend_func(GL_SRC_ALPHA, GL_ONE_MINUS_SRC_ALPHA);
+            }
+            Some(_) => gl_disable(GL_BLEND),
+        }
+        let set_1f = |name: &str, value: f32| {
+            let location = gl_get_uniform_location(program, name);
+            if location != -1 {
+                gl_uniform_1f(location, value);
+            }
+        };
+        let set_color = |name: &str, color: Color| {
+            let location = gl_get_uniform_location(program, name);
+            if location != -1 {
+                gl_uniform_4f(location, color.red_value(), color.green_value(), color.blue_value(), color.alpha());
+            }
+        };
+
+        set_1f("u_picking", if params.pick.is_some() { 1.0 } else { 0.0 });
+        if let Some(id) = params.pick {
+            set_1f("u_pick_base", id.0 as f32);
+            let [r, g, b, a] = id.to_rgba();
+            set_color("u_pick_color", Color::from_rgba(r, g, b, a));
+        }
+
+        // Reset instance color attribute to (0,0,0,0) so the shader falls back to
+        // the geometryColor uniform. OpenGL defaults disabled attributes to (0,0,0,1).
+        gl_vertex_attrib_4f(2, 0.0, 0.0, 0.0, 0.0);
+        // Likewise an identity instance transform (scale 1, no rotation).
+        gl_vertex_attrib_4f(4, 1.0, 1.0, 0.0, 0.0);
+
+        let transform_loc = gl_get_uniform_location(program, "u_Transform");
+        if transform_loc != -1 {
+            gl_uniform_matrix_4f(transform_loc, params.transform.as_array());
+        }
+        let offset_loc = gl_get_uniform_location(program, "u_screen_offset");
+        if offset_loc != -1 {
+            gl_uniform_2f(offset_loc, params.screen_offset.0, params.screen_offset.1);
+        }
+        set_1f("u_point_size", params.point_size);
+        set_1f("u_scale", params.scale);
+        set_1f("u_rotation", params.rotation);
+        set_1f("u_opacity", params.opacity);
+        if let Some(color) = params.color {
+            set_color("geometryColor", color);
+            // Also u_color (vec4 with alpha) - used by text shader
+            set_color("u_color", color);
+        }
+        if let Some((dash, gap, offset)) = params.dash {
+            set_1f("u_dash_length", dash);
+            set_1f("u_gap_length", gap);
+            set_1f("u_dash_offset", offset);
+        }
+
+        if let Some(texture_id) = params.texture {
+            gl_active_texture(GL_TEXTURE0);
+            gl_bind_texture(GL_TEXTURE_2D, texture_id);
+        }
+        let (mode, count) = (mesh.geometry.drawing_mode(), mesh.geometry.vertex_count());
+        match params.instances {
+            Some(instances) => gl_draw_arrays_instanced(mode, 0, count, instances.max(0)),
+            None => gl_draw_arrays(mode, 0, count),
+        }
+        if params.texture.is_some() {
+            gl_bind_texture(GL_TEXTURE_2D, 0);
+        }
+    }
+
+    fn set_viewport(&self, width: i32, height: i32) {
+        gl_viewport(0, 0, width, height);
+    }
+
+    fn viewport_size(&self) -> (i32, i32) {
+        let mut viewport = [0, 0, 0, 0];
+        gl_get_integerv(GL_VIEWPORT, viewport.as_mut_ptr() as *mut c_void);
+        (viewport[2], viewport[3]) // width, height
+    }
+
+    fn clear(&self, color: Color) {
+        gl_clear_color(color.red_value(), color.green_value(), color.blue_value(), color.alpha());
+    }
+
+    fn set_point_size(&self, point_size: f32) {
+        gl_point_size(point_size);
+    }
+
+    fn read_pixels(&self, x: i32, y: i32, width: i32, height: i32) -> Vec<u8> {
+        let mut pixels = vec![0u8; (width.max(0) * height.max(0) * 4) as usize];
+        gl_read_pixels(x, y, width, height, GL_RGBA as u32, GL_UNSIGNED_BYTE, &mut pixels);
+        pixels
+    }
+
+    fn end_frame(&self) {
+        gl_use_program(0);
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+        gl_active_texture(GL_TEXTURE0);
+        gl_bind_texture(GL_TEXTURE_2D, 0);
+    }
+}
@@ -4,7 +4,9 @@
 //! [`CameraController`] for handling input-driven pan and zoom,
 //! and the [`Projection`] trait for custom coordinate transformations.
 
-use crate::core::engine::glfw::{GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS};
+use std::f32::consts::{PI, TAU};
+
+use crate::core::engine::glfw::{GLFW_MOUSE_BUTTON_LEFT, GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS};
 use crate::core::engine::opengl::Vec2;
 
 /// Trait for coordinate transformations between world and screen space.
@@ -36,8 +38,9 @@ impl Projection for IdentityProjection {
 
 /// A 2D camera that defines the visible region of the world.
 ///
-/// The camera manages pan and zoom state, converting between world coordinates
-/// and screen coordinates. Screen origin is at top-left, with Y increasing downward.
+/// The camera manages pan, zoom and rotation state, converting between world
+/// coordinates and screen coordinates. Screen origin is at top-left, with Y
+/// increasing downward.
 ///
 /// # Example
 ///
@@ -56,6 +59,7 @@ impl Projection for IdentityProjection {
 /// assert_eq!(screen_pos.y, 300.0);
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera2D {
     /// World coordinates at the center of the screen.
     center: Vec2,
@@ -64,6 +68,10 @@ pub struct Camera2D {
     scale: f32,
     /// Screen dimensions in pixels.
     screen_size: Vec2,
+    /// View rotation in radians around the screen center. The world is
+    /// drawn turned by the opposite angle.
+    #[cfg_attr(feature = "serde", serde(default))]
+    rotation: f32,
 }
 
 impl Camera2D {
@@ -78,6 +86,7 @@ impl Camera2D {
             center,
             scale,
             screen_size,
+            rotation: 0.0,
         }
     }
 
@@ -101,6 +110,33 @@ impl Camera2D {
         self.scale = scale;
     }
 
+    /// Get the view rotation in radians.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Rotate the view to `angle` radians around the screen center.
+    ///
+    /// The world turns by `-angle` on screen, in the shapes' convention
+    /// (positive is clockwise with Y down), so a world direction at `angle`
+    /// points along the screen's +X axis. For a track-up display, set it to
+    /// the vehicle's heading plus a quarter turn, or call
+    /// [`set_heading_up`](Self::set_heading_up).
+    pub fn set_rotation(&mut self, angle: f32) {
+        self.rotation = angle;
+    }
+
+    /// Add `delta` radians to the view rotation.
+    pub fn rotate(&mut self, delta: f32) {
+        self.rotation += delta;
+    }
+
+    /// Rotate the view so the world direction `heading` (radians, same
+    /// convention as shape rotation) points up the screen.
+    pub fn set_heading_up(&mut self, heading: f32) {
+        self.rotation = heading + std::f32::consts::FRAC_PI_2;
+    }
+
     /// Get the screen size.
     pub fn screen_size(&self) -> Vec2 {
         self.screen_size
@@ -119,8 +155,9 @@ impl Camera2D {
 
     /// Pan the camera by a delta in screen coordinates.
     pub fn pan_screen(&mut self, delta_pixels: Vec2) {
-        self.center.x -= delta_pixels.x / self.scale;
-        self.center.y -= delta_pixels.y / self.scale;
+        let delta = rotate(delta_pixels, self.rotation);
+        self.center.x -= delta.x / self.scale;
+        self.center.y -= delta.y / self.scale;
     }
 
     /// Zoom by a factor, keeping the screen center fixed.
@@ -148,10 +185,14 @@ impl Camera2D {
         self.center.y += world_before.y - world_after.y;
     }
 
-    /// Get the visible world bounds as (min_x, min_y, max_x, max_y).
+    /// Get the visible world bounds as (min_x, min_y, max_x, max_y). When
+    /// the view is rotated, this is the axis-aligned box around the visible
+    /// region, so it includes some area off screen.
     pub fn world_bounds(&self) -> (f32, f32, f32, f32) {
-        let half_width = self.screen_size.x / (2.0 * self.scale);
-        let half_height = self.screen_size.y / (2.0 * self.scale);
+        let (sin, cos) = self.rotation.sin_cos();
+        let (w, h) = (self.screen_size.x, self.screen_size.y);
+        let half_width = (w * cos.abs() + h * sin.abs()) / (2.0 * self.scale);
+        let half_height = (w * sin.abs() + h * cos.abs()) / (2.0 * self.scale);
         (
             self.center.x - half_width,
             self.center.y - half_height,
@@ -159,20 +200,39 @@ impl Camera2D {
             self.center.y + half_height,
         )
     }
+
+    /// The screen area the camera shows, as `(min_x, min_y, max_x,
+    /// max_y)`: the region shapes must overlap to be seen.
+    pub fn visible_screen_bounds(&self) -> (f32, f32, f32, f32) {
+        (0.0, 0.0, self.screen_size.x, self.screen_size.y)
+    }
+
+    /// True if the screen-space `bounds`, e.g. from
+    /// [`ShapeRenderable::bounds`](crate::graphics2d::shapes::ShapeRenderable::bounds),
+    /// overlap the visible region. Touching edges count as visible.
+    pub fn is_visible(&self, bounds: (f32, f32, f32, f32)) -> bool {
+        let (vx0, vy0, vx1, vy1) = self.visible_screen_bounds();
+        bounds.0 <= vx1 && bounds.2 >= vx0 && bounds.1 <= vy1 && bounds.3 >= vy0
+    }
 }
 
 impl Projection for Camera2D {
     fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        let offset = rotate(Vec2::new(world.x - self.center.x, world.y - self.center.y), -self.rotation);
         Vec2 {
-            x: (world.x - self.center.x) * self.scale + self.screen_size.x * 0.5,
-            y: (world.y - self.center.y) * self.scale + self.screen_size.y * 0.5,
+            x: offset.x * self.scale + self.screen_size.x * 0.5,
+            y: offset.y * self.scale + self.screen_size.y * 0.5,
         }
     }
 
     fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        let offset = rotate(
+            Vec2::new(screen.x - self.screen_size.x * 0.5, screen.y - self.screen_size.y * 0.5),
+            self.rotation,
+        );
         Vec2 {
-            x: (screen.x - self.screen_size.x * 0.5) / self.scale + self.center.x,
-            y: (screen.y - self.screen_size.y * 0.5) / self.scale + self.center.y,
+            x: offset.x / self.scale + self.center.x,
+            y: offset.y / self.scale + self.center.y,
         }
     }
 }
@@ -183,6 +243,18 @@ impl Projection for Camera2D {
 /// standard camera controls. Connect it to window callbacks to enable:
 /// - **Drag-to-pan**: Hold left mouse button and drag to pan the view
 /// - **Scroll-to-zoom**: Mouse wheel zooms in/out, centered on cursor position
+/// - **Click**: A press and release that moves less than the
+///   [click threshold](Self::set_click_threshold) fires [`on_click`](Self::on_click)
+///   instead of panning
+/// - **Double-click-to-zoom**: Two clicks in quick succession zoom in one step
+///   centered on the cursor
+/// - **Inertial panning** (opt-in, see [`set_inertia`](Self::set_inertia)):
+///   a released drag keeps gliding and slows down exponentially
+/// - **Right-drag-to-rotate** (opt-in, see
+///   [`set_drag_rotation`](Self::set_drag_rotation)): the view turns around
+///   the screen center following the cursor
+///
+/// Double-click timing uses the clock advanced by [`update`](Self::update).
 ///
 /// # Example
 ///
@@ -207,12 +279,34 @@ pub struct CameraController {
     camera: Camera2D,
     target_scale: f32,
     target_center: Vec2,
+    target_rotation: f32,
     smoothness: f32,
     is_dragging: bool,
+    /// Cursor position at left press, until released.
+    press_pos: Option<Vec2>,
     last_cursor_pos: Vec2,
+    /// Drag movement in pixels since the last `update`.
+    drag_delta: Vec2,
+    /// Pan velocity in pixels per second: tracked while dragging, decaying
+    /// while gliding after release.
+    velocity: Vec2,
+    /// Glide decay rate per second; 0 disables inertia.
+    inertia: f32,
+    drag_rotation: bool,
+    is_rotating: bool,
     zoom_sensitivity: f32,
     min_scale: Option<f32>,
     max_scale: Option<f32>,
+    /// World rectangle the view is kept inside, as (min_x, min_y, max_x, max_y).
+    pan_bounds: Option<(f32, f32, f32, f32)>,
+    click_threshold: f32,
+    double_click_interval: f64,
+    double_click_zoom: f32,
+    /// Seconds accumulated by `update`.
+    clock: f64,
+    /// Time and cursor position of the last click not yet part of a double-click.
+    last_click: Option<(f64, Vec2)>,
+    on_click: Option<Box<dyn FnMut(Vec2)>>,
 }
 
 impl CameraController {
@@ -220,16 +314,31 @@ impl CameraController {
     pub fn new(camera: Camera2D) -> Self {
         let scale = camera.scale();
         let center = camera.center();
+        let rotation = camera.rotation();
         Self {
             camera,
             target_scale: scale,
             target_center: center,
+            target_rotation: rotation,
             smoothness: 0.0,
             is_dragging: false,
+            press_pos: None,
             last_cursor_pos: Vec2::new(0.0, 0.0),
+            drag_delta: Vec2::new(0.0, 0.0),
+            velocity: Vec2::new(0.0, 0.0),
+            inertia: 0.0,
+            drag_rotation: false,
+            is_rotating: false,
             zoom_sensitivity: 1.1,
             min_scale: None,
             max_scale: None,
+            pan_bounds: None,
+            click_threshold: 4.0,
+            double_click_interval: 0.3,
+            double_click_zoom: 2.0,
+            clock: 0.0,
+            last_click: None,
+            on_click: None,
         }
     }
 
@@ -246,14 +355,19 @@ impl CameraController {
             // Snap camera to targets when disabling
             self.camera.set_scale(self.target_scale);
             self.camera.set_center(self.target_center);
+            self.camera.set_rotation(self.target_rotation);
         }
     }
 
     /// Advance camera interpolation by `dt` seconds.
     ///
     /// Called automatically by `App::run()` when the controller is registered.
-    /// No-op when smoothness is 0 (disabled).
+    /// Also advances the clock used to detect double-clicks; interpolation
+    /// is a no-op when smoothness is 0 (disabled).
     pub fn update(&mut self, dt: f32) {
+        self.clock += dt as f64;
+        self.glide(dt);
+        self.constrain();
         if self.smoothness == 0.0 {
             return;
         }
@@ -270,6 +384,46 @@ impl CameraController {
             current_center.y + (self.target_center.y - current_center.y) * t,
         );
         self.camera.set_center(new_center);
+
+        let current_rotation = self.camera.rotation();
+        self.camera.set_rotation(current_rotation + (self.target_rotation - current_rotation) * t);
+    }
+
+    /// Keep panning after a drag is released, at the drag's final speed,
+    /// slowing down by the factor `e^-friction` every second. Typical values
+    /// are 3–8: lower glides further. `0.0` (default) disables inertia.
+    ///
+    /// The speed is measured in [`update`](Self::update), so the glide
+    /// needs it to be called every frame, as `App` does.
+    pub fn set_inertia(&mut self, friction: f32) {
+        self.inertia = friction.max(0.0);
+        if self.inertia == 0.0 {
+            self.velocity = Vec2::new(0.0, 0.0);
+        }
+    }
+
+    /// Track the drag speed while dragging; otherwise continue a released
+    /// drag's motion and decay it.
+    fn glide(&mut self, dt: f32) {
+        let delta = std::mem::replace(&mut self.drag_delta, Vec2::new(0.0, 0.0));
+        if self.inertia == 0.0 || dt <= 0.0 {
+            return;
+        }
+        if self.is_dragging {
+            // Smooth over uneven event delivery, so a pause just before
+            // release still brings the speed down.
+            let k = 1.0 - (-dt / VELOCITY_SMOOTHING).exp();
+            self.velocity.x += (delta.x / dt - self.velocity.x) * k;
+            self.velocity.y += (delta.y / dt - self.velocity.y) * k;
+            return;
+        }
+        if self.velocity.x.hypot(self.velocity.y) < MIN_GLIDE_SPEED {
+            self.velocity = Vec2::new(0.0, 0.0);
+            return;
+        }
+        self.pan_by(Vec2::new(self.velocity.x * dt, self.velocity.y * dt));
+        let decay = (-self.inertia * dt).exp();
+        self.velocity = Vec2::new(self.velocity.x * decay, self.velocity.y * decay);
     }
 
     /// Set zoom sensitivity. Default is 1.1 (10% zoom per scroll tick).
@@ -282,11 +436,103 @@ impl CameraController {
 
     /// Set minimum and maximum zoom scale limits.
     ///
-    /// When set, scroll zoom is clamped to stay within these bounds.
-    /// Pass `None` for either limit to leave it unbounded.
+    /// When set, zooming is clamped to stay within these bounds, and a scale
+    /// set directly on the camera is pulled back into them by
+    /// [`update`](Self::update). Pass `None` for either limit to leave it
+    /// unbounded.
     pub fn set_zoom_limits(&mut self, min: Option<f32>, max: Option<f32>) {
         self.min_scale = min;
         self.max_scale = max;
+        self.constrain();
+    }
+
+    /// Keep the view inside the world rectangle `(min_x, min_y, max_x,
+    /// max_y)`, e.g. the extent of the data, so it cannot be panned away.
+    /// When the view is larger than the rectangle along an axis, it is
+    /// centered on it instead. `None` (the default) removes the limit.
+    ///
+    /// Like the zoom limits, the bounds are enforced after each input and in
+    /// [`update`](Self::update), on both the camera and the smoothing target.
+    pub fn set_pan_bounds(&mut self, bounds: Option<(f32, f32, f32, f32)>) {
+        self.pan_bounds = bounds;
+        self.constrain();
+    }
+
+    /// Clamp the scale and center, of the camera when moving instantly or
+    /// of the animation targets when smoothing, to the configured limits.
+    fn constrain(&mut self) {
+        self.target_scale = self.clamp_scale(self.target_scale);
+        self.target_center = self.clamp_center(self.target_center, self.target_scale);
+        if self.smoothness == 0.0 {
+            let scale = self.clamp_scale(self.camera.scale());
+            self.camera.set_scale(scale);
+            let center = self.clamp_center(self.camera.center(), scale);
+            self.camera.set_center(center);
+        }
+    }
+
+    fn clamp_center(&self, center: Vec2, scale: f32) -> Vec2 {
+        let Some((min_x, min_y, max_x, max_y)) = self.pan_bounds else {
+            return center;
+        };
+        let mut view = self.camera;
+        view.set_center(center);
+        view.set_scale(scale);
+        let (x0, y0, x1, y1) = view.world_bounds();
+        Vec2::new(
+            clamp_axis(center.x, (x1 - x0) * 0.5, min_x, max_x),
+            clamp_axis(center.y, (y1 - y0) * 0.5, min_y, max_y),
+        )
+    }
+
+    /// Rotate the view with right-button drags, turning it by the angle the
+    /// cursor sweeps around the screen center. Off by default.
+    pub fn set_drag_rotation(&mut self, enabled: bool) {
+        self.drag_rotation = enabled;
+        self.is_rotating &= enabled;
+    }
+
+    /// Rotate the view by `delta` radians, e.g. from Q/E key bindings.
+    /// Animated when smoothing is enabled.
+    pub fn rotate(&mut self, delta: f32) {
+        self.set_rotation(self.target_rotation + delta);
+    }
+
+    /// Rotate the view to `angle` radians, e.g. a vehicle's heading for a
+    /// track-up display; see [`Camera2D::set_rotation`]. Animated when
+    /// smoothing is enabled.
+    pub fn set_rotation(&mut self, angle: f32) {
+        self.target_rotation = angle;
+        if self.smoothness == 0.0 {
+            self.camera.set_rotation(angle);
+        }
+    }
+
+    /// Set how far, in pixels, the cursor may move between press and release
+    /// for it to count as a click rather than a drag. Default is 4.
+    pub fn set_click_threshold(&mut self, pixels: f32) {
+        self.click_threshold = pixels.max(0.0);
+    }
+
+    /// Set the maximum time between two clicks, in seconds, for them to count
+    /// as a double-click. Default is 0.3.
+    pub fn set_double_click_interval(&mut self, seconds: f64) {
+        self.double_click_interval = seconds;
+    }
+
+    /// Set the zoom factor applied on double-click. Default is 2.0; pass 1.0
+    /// to disable double-click zoom.
+    pub fn set_double_click_zoom(&mut self, factor: f32) {
+        self.double_click_zoom = factor;
+    }
+
+    /// Register a callback for clicks, receiving the world position under the
+    /// cursor. Both clicks of a double-click fire it.
+    pub fn on_click<F>(&mut self, callback: F)
+    where
+        F: FnMut(Vec2) + 'static,
+    {
+        self.on_click = Some(Box::new(callback));
     }
 
     fn clamp_scale(&self, scale: f32) -> f32 {
@@ -309,12 +555,52 @@ impl CameraController {
         if self.smoothness > 0.0 {
             self.target_scale *= scale_factor;
         }
+        self.constrain();
     }
 
     /// Handle mouse button events. Call this from `Window::on_mouse_button`.
+    ///
+    /// Panning starts only once the cursor moves past the click threshold;
+    /// a release before that is a click.
     pub fn on_mouse_button(&mut self, button: i32, action: i32) {
-        if button == GLFW_MOUSE_BUTTON_LEFT {
-            self.is_dragging = action == GLFW_PRESS;
+        if button == GLFW_MOUSE_BUTTON_RIGHT {
+            self.is_rotating = self.drag_rotation && action == GLFW_PRESS;
+            return;
+        }
+        if button != GLFW_MOUSE_BUTTON_LEFT {
+            return;
+        }
+        if action == GLFW_PRESS {
+            self.press_pos = Some(self.last_cursor_pos);
+            self.is_dragging = false;
+            // Grabbing the map stops a glide.
+            self.velocity = Vec2::new(0.0, 0.0);
+            return;
+        }
+        let was_click = self.press_pos.take().is_some() && !self.is_dragging;
+        self.is_dragging = false;
+        if was_click {
+            self.velocity = Vec2::new(0.0, 0.0);
+            self.click();
+        }
+    }
+
+    fn click(&mut self) {
+        let cursor = self.last_cursor_pos;
+        let world = self.camera.screen_to_world(cursor);
+        if let Some(callback) = &mut self.on_click {
+            callback(world);
+        }
+
+        let is_double = self.last_click.is_some_and(|(time, pos)| {
+            self.clock - time <= self.double_click_interval
+                && distance(pos, cursor) <= self.click_threshold
+        });
+        if is_double {
+            self.last_click = None;
+            self.zoom_at_cursor(self.double_click_zoom);
+        } else {
+            self.last_click = Some((self.clock, cursor));
         }
     }
 
@@ -322,20 +608,35 @@ impl CameraController {
     pub fn on_cursor_move(&mut self, x: f64, y: f64) {
         let cursor = Vec2::new(x as f32, y as f32);
 
+        if let Some(press) = self.press_pos.filter(|_| !self.is_dragging) {
+            if distance(press, cursor) > self.click_threshold {
+                // Include the movement made within the threshold in the pan
+                self.is_dragging = true;
+                self.last_cursor_pos = press;
+            }
+        }
+
         if self.is_dragging {
             let delta = Vec2::new(
                 cursor.x - self.last_cursor_pos.x,
                 cursor.y - self.last_cursor_pos.y,
             );
-            if self.smoothness > 0.0 {
-                // Update target; use target_scale for correct world-space delta
-                self.target_center.x -= delta.x / self.target_scale;
-                self.target_center.y -= delta.y / self.target_scale;
-            } else {
-                self.camera.pan_screen(delta);
-            }
+            self.pan_by(delta);
+            self.drag_delta.x += delta.x;
+            self.drag_delta.y += delta.y;
+        }
+
+        if self.is_rotating {
+            let size = self.camera.screen_size();
+            let angle = |p: Vec2| (p.y - size.y * 0.5).atan2(p.x - size.x * 0.5);
+            let swept = (angle(cursor) - angle(self.last_cursor_pos) + PI).rem_euclid(TAU) - PI;
+            // The world turns with the cursor, i.e. the view turns against it.
+            self.rotate(-swept);
         }
 
+        if self.is_dragging || self.is_rotating {
+            self.constrain();
+        }
         self.last_cursor_pos = cursor;
     }
 
@@ -348,7 +649,24 @@ impl CameraController {
         } else {
             1.0 / self.zoom_sensitivity
         };
+        self.zoom_at_cursor(factor);
+    }
+
+    /// Move the view so the world follows a cursor movement of `delta`
+    /// pixels.
+    fn pan_by(&mut self, delta: Vec2) {
+        if self.smoothness > 0.0 {
+            // Update target; use target_scale for correct world-space delta
+            let delta = rotate(delta, self.target_rotation);
+            self.target_center.x -= delta.x / self.target_scale;
+            self.target_center.y -= delta.y / self.target_scale;
+        } else {
+            self.camera.pan_screen(delta);
+        }
+    }
 
+    /// Zoom by `factor` keeping the world point under the cursor fixed.
+    fn zoom_at_cursor(&mut self, factor: f32) {
         if self.smoothness > 0.0 {
             let new_target = self.clamp_scale(self.target_scale * factor);
             if (new_target - self.target_scale).abs() < f32::EPSILON {
@@ -356,22 +674,16 @@ impl CameraController {
             }
 
             // Compute world point under cursor using target state
-            let screen_size = self.camera.screen_size();
-            let world_before = Vec2 {
-                x: (self.last_cursor_pos.x - screen_size.x * 0.5) / self.target_scale
-                    + self.target_center.x,
-                y: (self.last_cursor_pos.y - screen_size.y * 0.5) / self.target_scale
-                    + self.target_center.y,
-            };
+            let mut target = self.camera;
+            target.set_center(self.target_center);
+            target.set_scale(self.target_scale);
+            target.set_rotation(self.target_rotation);
+            let world_before = target.screen_to_world(self.last_cursor_pos);
 
             self.target_scale = new_target;
 
-            let world_after = Vec2 {
-                x: (self.last_cursor_pos.x - screen_size.x * 0.5) / self.target_scale
-                    + self.target_center.x,
-                y: (self.last_cursor_pos.y - screen_size.y * 0.5) / self.target_scale
-                    + self.target_center.y,
-            };
+            target.set_scale(self.target_scale);
+            let world_after = target.screen_to_world(self.last_cursor_pos);
 
             self.target_center.x += world_before.x - world_after.x;
             self.target_center.y += world_before.y - world_after.y;
@@ -382,6 +694,7 @@ impl CameraController {
                 self.camera.zoom_at(actual_factor, self.last_cursor_pos);
             }
         }
+        self.constrain();
     }
 
     /// Get a reference to the underlying camera.
@@ -389,15 +702,50 @@ impl CameraController {
         &self.camera
     }
 
+    /// Replace the camera, settling any smooth animation at it.
+    pub fn set_camera(&mut self, camera: Camera2D) {
+        self.camera = camera;
+        self.target_scale = camera.scale();
+        self.target_center = camera.center();
+        self.target_rotation = camera.rotation();
+    }
+
     /// Get a mutable reference to the underlying camera.
     pub fn camera_mut(&mut self) -> &mut Camera2D {
         &mut self.camera
     }
 }
 
+/// Time constant, in seconds, of the drag speed average used for inertia.
+const VELOCITY_SMOOTHING: f32 = 0.05;
+
+/// Glide speed, in pixels per second, below which inertial panning stops.
+const MIN_GLIDE_SPEED: f32 = 5.0;
+
+/// `v` turned by `angle` radians (clockwise on screen, Y down).
+fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
+
+/// `center` moved so `center ± half` stays within `min..max`, or the middle
+/// of the range if it is narrower than `2 * half`.
+fn clamp_axis(center: f32, half: f32, min: f32, max: f32) -> f32 {
+    if max - min <= 2.0 * half {
+        (min + max) * 0.5
+    } else {
+        center.clamp(min + half, max - half)
+    }
+}
+
+fn distance(a: Vec2, b: Vec2) -> f32 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::engine::glfw::GLFW_RELEASE;
 
     #[test]
     fn test_identity_projection() {
@@ -408,6 +756,16 @@ mod tests {
         assert_eq!(proj.screen_to_world(world), world);
     }
 
+    #[test]
+    fn visibility_follows_the_viewport() {
+        let camera = Camera2D::new(Vec2::new(500.0, 500.0), 2.0, Vec2::new(800.0, 600.0));
+        assert_eq!(camera.visible_screen_bounds(), (0.0, 0.0, 800.0, 600.0));
+        assert!(camera.is_visible((790.0, 590.0, 820.0, 620.0)));
+        assert!(camera.is_visible((-10.0, 100.0, 0.0, 120.0)));
+        assert!(!camera.is_visible((801.0, 0.0, 900.0, 10.0)));
+        assert!(!camera.is_visible((10.0, -50.0, 20.0, -1.0)));
+    }
+
     #[test]
     fn test_camera_center_at_origin() {
         let camera = Camera2D::new(
@@ -517,4 +875,170 @@ mod tests {
         assert!((corner_world_before.x - corner_world_after.x).abs() < 0.001);
         assert!((corner_world_before.y - corner_world_after.y).abs() < 0.001);
     }
+
+    #[test]
+    fn rotation_turns_the_view_around_the_center() {
+        let mut camera = Camera2D::new(Vec2::new(0.0, 0.0), 2.0, Vec2::new(800.0, 600.0));
+        camera.set_heading_up(0.0);
+        // World +X (heading 0) now points up the screen.
+        let screen = camera.world_to_screen(Vec2::new(10.0, 0.0));
+        assert!((screen.x - 400.0).abs() < 1e-3 && (screen.y - 280.0).abs() < 1e-3);
+        let world = camera.screen_to_world(screen);
+        assert!((world.x - 10.0).abs() < 1e-3 && world.y.abs() < 1e-3);
+
+        // Panning follows the screen: dragging right moves the view along world -Y.
+        camera.pan_screen(Vec2::new(20.0, 0.0));
+        assert!(camera.center().x.abs() < 1e-3 && (camera.center().y + 10.0).abs() < 1e-3);
+
+        // A quarter turn swaps the extents of the visible world box.
+        let (x0, y0, x1, y1) = camera.world_bounds();
+        assert!((x1 - x0 - 300.0).abs() < 1e-3 && (y1 - y0 - 400.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn right_drag_rotates_only_when_enabled() {
+        let mut ctrl = controller();
+        ctrl.on_cursor_move(500.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS);
+        ctrl.on_cursor_move(400.0, 400.0);
+        assert_eq!(ctrl.camera().rotation(), 0.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_RIGHT, GLFW_RELEASE);
+
+        ctrl.set_drag_rotation(true);
+        ctrl.on_cursor_move(500.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS);
+        ctrl.on_cursor_move(400.0, 400.0);
+        // The cursor swept a quarter turn clockwise; the world follows it.
+        assert!((ctrl.camera().rotation() + std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+        let under_cursor = ctrl.camera().world_to_screen(Vec2::new(100.0, 0.0));
+        assert!((under_cursor.x - 400.0).abs() < 1e-3 && (under_cursor.y - 400.0).abs() < 1e-3);
+    }
+
+    fn controller() -> CameraController {
+        CameraController::new(Camera2D::new(Vec2::new(0.0, 0.0), 1.0, Vec2::new(800.0, 600.0)))
+    }
+
+    fn clicks(ctrl: &mut CameraController) -> std::rc::Rc<std::cell::RefCell<Vec<Vec2>>> {
+        let clicks = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = clicks.clone();
+        ctrl.on_click(move |world| sink.borrow_mut().push(world));
+        clicks
+    }
+
+    fn press_release(ctrl: &mut CameraController) {
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+    }
+
+    #[test]
+    fn small_movement_is_a_click_not_a_pan() {
+        let mut ctrl = controller();
+        let clicks = clicks(&mut ctrl);
+        ctrl.on_cursor_move(500.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        ctrl.on_cursor_move(502.0, 301.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+
+        assert_eq!(ctrl.camera().center(), Vec2::new(0.0, 0.0));
+        assert_eq!(clicks.borrow().len(), 1);
+        assert_eq!(clicks.borrow()[0], Vec2::new(102.0, 1.0));
+    }
+
+    #[test]
+    fn drag_past_threshold_pans_the_whole_distance() {
+        let mut ctrl = controller();
+        let clicks = clicks(&mut ctrl);
+        ctrl.on_cursor_move(400.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        ctrl.on_cursor_move(403.0, 300.0);
+        ctrl.on_cursor_move(410.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+
+        assert_eq!(ctrl.camera().center(), Vec2::new(-10.0, 0.0));
+        assert!(clicks.borrow().is_empty());
+    }
+
+    #[test]
+    fn double_click_zooms_at_cursor() {
+        let mut ctrl = controller();
+        ctrl.on_cursor_move(600.0, 300.0);
+        press_release(&mut ctrl);
+        ctrl.update(0.1);
+        press_release(&mut ctrl);
+
+        assert_eq!(ctrl.camera().scale(), 2.0);
+        let under_cursor = ctrl.camera().screen_to_world(Vec2::new(600.0, 300.0));
+        assert!((under_cursor.x - 200.0).abs() < 0.001);
+
+        // A third click starts a new pair
+        ctrl.update(0.1);
+        press_release(&mut ctrl);
+        assert_eq!(ctrl.camera().scale(), 2.0);
+    }
+
+    #[test]
+    fn pan_bounds_keep_the_view_on_the_data() {
+        let mut ctrl = controller();
+        ctrl.set_pan_bounds(Some((-1000.0, -500.0, 1000.0, 500.0)));
+        ctrl.on_cursor_move(400.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        ctrl.on_cursor_move(1400.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+        // Panned 1000 px left of the origin, but the left edge stops at -1000.
+        assert_eq!(ctrl.camera().center(), Vec2::new(-600.0, 0.0));
+
+        // Zoomed out past the data, the view centers on it.
+        ctrl.camera_mut().set_scale(0.25);
+        ctrl.update(0.016);
+        assert_eq!(ctrl.camera().center(), Vec2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn update_clamps_smoothing_targets() {
+        let mut ctrl = controller();
+        ctrl.set_smoothness(10.0);
+        ctrl.set_zoom_limits(Some(0.5), Some(4.0));
+        ctrl.set_pan_bounds(Some((-1000.0, -1000.0, 1000.0, 1000.0)));
+        ctrl.set_camera(Camera2D::new(Vec2::new(5000.0, 0.0), 10.0, Vec2::new(800.0, 600.0)));
+        ctrl.update(100.0);
+        assert!((ctrl.camera().scale() - 4.0).abs() < 1e-3);
+        assert!((ctrl.camera().center().x - 900.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn released_drag_glides_and_slows_down() {
+        let mut ctrl = controller();
+        ctrl.set_inertia(4.0);
+        ctrl.on_cursor_move(400.0, 300.0);
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        for step in 1..=10 {
+            ctrl.on_cursor_move(400.0 + 10.0 * step as f64, 300.0);
+            ctrl.update(0.01);
+        }
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+        let released = ctrl.camera().center().x;
+        assert_eq!(released, -100.0);
+
+        ctrl.update(0.1);
+        let first = released - ctrl.camera().center().x;
+        ctrl.update(0.1);
+        let second = released - ctrl.camera().center().x - first;
+        assert!(first > 50.0, "glide continues at roughly the drag speed: {first}");
+        assert!(second < first && second > 0.0);
+
+        // A press stops the glide.
+        ctrl.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        let held = ctrl.camera().center();
+        ctrl.update(0.1);
+        assert_eq!(ctrl.camera().center(), held);
+    }
+
+    #[test]
+    fn slow_clicks_do_not_zoom() {
+        let mut ctrl = controller();
+        press_release(&mut ctrl);
+        ctrl.update(0.5);
+        press_release(&mut ctrl);
+        assert_eq!(ctrl.camera().scale(), 1.0);
+    }
 }
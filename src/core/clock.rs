@@ -0,0 +1,163 @@
+//! Simulation time that can be paused, slowed down and single-stepped.
+
+/// Scaled simulation time owned by [`App`](super::App).
+///
+/// Each frame the app ticks the clock with the real frame duration; the
+/// clock turns it into a simulation delta that is `0` while paused and
+/// multiplied by the speed otherwise. Animations, timers and `on_update`
+/// callbacks run on this time, so pausing freezes them, while camera
+/// navigation keeps using real time.
+///
+/// [`App::clock`](super::App::clock) returns a shared handle, so key
+/// callbacks can control it:
+///
+/// ```ignore
+/// let clock = app.clock();
+/// app.window.on_key(move |key, _scancode, action, _mods| match (key, action) {
+///     (GLFW_KEY_SPACE, GLFW_PRESS) => clock.borrow_mut().toggle_pause(),
+///     (GLFW_KEY_PERIOD, GLFW_PRESS) => clock.borrow_mut().step_frame(),
+///     _ => {}
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clock {
+    time: f64,
+    dt: f32,
+    speed: f32,
+    paused: bool,
+    step_pending: bool,
+    fixed_step: Option<f32>,
+    frame: u64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock {
+    /// Running at normal speed from time 0.
+    pub fn new() -> Self {
+        Self { time: 0.0, dt: 0.0, speed: 1.0, paused: false, step_pending: false, fixed_step: None, frame: 0 }
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.step_pending = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Time multiplier: `0.25` for slow motion, `2.0` for double speed.
+    /// Negative values are treated as `0`.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Advance every frame by exactly `step` seconds (times the speed)
+    /// instead of the measured frame time, or `None` to follow real time.
+    /// Makes simulations reproducible, e.g. when replaying recorded input.
+    pub fn set_fixed_step(&mut self, step: Option<f32>) {
+        self.fixed_step = step.filter(|s| *s > 0.0);
+    }
+
+    pub fn fixed_step(&self) -> Option<f32> {
+        self.fixed_step
+    }
+
+    /// Advance exactly one frame while paused: the next tick moves time by
+    /// that frame's duration (times the speed), then the clock stays
+    /// paused. Pauses the clock if it is running.
+    pub fn step_frame(&mut self) {
+        self.paused = true;
+        self.step_pending = true;
+    }
+
+    /// Simulation time in seconds since the clock started.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Simulation time that passed in the current frame, in seconds.
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Number of frames in which simulation time advanced.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Advance by a frame that took `real_dt` seconds.
+    pub(crate) fn tick(&mut self, real_dt: f32) {
+        let running = !self.paused || std::mem::take(&mut self.step_pending);
+        let frame_dt = self.fixed_step.unwrap_or(real_dt.max(0.0));
+        self.dt = if running { frame_dt * self.speed } else { 0.0 };
+        self.time += self.dt as f64;
+        if running {
+            self.frame += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_scales_and_pause_freezes() {
+        let mut clock = Clock::new();
+        clock.set_speed(0.5);
+        clock.tick(0.2);
+        assert_eq!((clock.dt(), clock.time()), (0.1, 0.1f32 as f64));
+        clock.pause();
+        clock.tick(0.2);
+        assert_eq!(clock.dt(), 0.0);
+        assert_eq!(clock.frame(), 1);
+        clock.resume();
+        clock.set_speed(-1.0);
+        clock.tick(0.2);
+        assert_eq!(clock.dt(), 0.0);
+    }
+
+    #[test]
+    fn step_advances_one_frame_then_stays_paused() {
+        let mut clock = Clock::new();
+        clock.step_frame();
+        assert!(clock.is_paused());
+        clock.tick(0.5);
+        assert_eq!(clock.dt(), 0.5);
+        clock.tick(0.5);
+        assert_eq!((clock.dt(), clock.time(), clock.frame()), (0.0, 0.5, 1));
+    }
+
+    #[test]
+    fn fixed_step_ignores_frame_time() {
+        let mut clock = Clock::new();
+        clock.set_fixed_step(Some(0.25));
+        clock.set_speed(2.0);
+        clock.tick(0.9);
+        assert_eq!(clock.dt(), 0.5);
+        clock.set_fixed_step(Some(0.0));
+        assert_eq!(clock.fixed_step(), None);
+    }
+}
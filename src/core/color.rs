@@ -1,4 +1,5 @@
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Color{
     r:f32,
@@ -16,6 +17,66 @@ impl Color{
         Color { r, g, b, a }
     }
 
+    /// Create an opaque color from 8-bit channels (0-255).
+    pub const fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Color::from_rgba8(r, g, b, 255)
+    }
+
+    /// Create a color from 8-bit channels including alpha (0-255).
+    pub const fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: a as f32 / 255.0,
+        }
+    }
+
+    /// Return a copy of this color with the alpha channel replaced.
+    ///
+    /// Handy for deriving translucent overlays (selection highlights, zones)
+    /// from an opaque palette color: `Color::blue().with_alpha(0.3)`.
+    pub fn with_alpha(self, a: f32) -> Self {
+        Color { a, ..self }
+    }
+
+    /// Linearly interpolate every channel (including alpha) between `a` and
+    /// `b`. `t` is clamped to `0..=1`.
+    pub fn lerp(a: Color, b: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Color {
+            r: a.r + (b.r - a.r) * t,
+            g: a.g + (b.g - a.g) * t,
+            b: a.b + (b.b - a.b) * t,
+            a: a.a + (b.a - a.a) * t,
+        }
+    }
+
+    /// Interpolate in HSV space, taking the shorter way around the hue wheel.
+    /// Keeps saturated ramps (red to blue) from passing through muddy grays.
+    pub fn lerp_hsv(a: Color, b: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let (ha, sa, va) = a.to_hsv();
+        let (hb, sb, vb) = b.to_hsv();
+        let mut dh = hb - ha;
+        if dh > 180.0 {
+            dh -= 360.0;
+        } else if dh < -180.0 {
+            dh += 360.0;
+        }
+        Color::from_hsva(
+            ha + dh * t,
+            sa + (sb - sa) * t,
+            va + (vb - va) * t,
+            a.a + (b.a - a.a) * t,
+        )
+    }
+
+    /// True if the color has any transparency (alpha below 1.0).
+    pub fn is_translucent(&self) -> bool {
+        self.a < 1.0
+    }
+
     /// Create a color from HSL (hue 0-360, saturation 0-1, lightness 0-1).
     pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
         let (r, g, b) = hsl_to_rgb(h, s, l);
@@ -28,6 +89,44 @@ impl Color{
         Color { r, g, b, a }
     }
 
+    /// Create a color from HSV (hue 0-360, saturation 0-1, value 0-1).
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let (r, g, b) = hsv_to_rgb(h, s, v);
+        Color { r, g, b, a: 1.0 }
+    }
+
+    /// Create a color from HSVA (hue 0-360, saturation 0-1, value 0-1, alpha 0-1).
+    pub fn from_hsva(h: f32, s: f32, v: f32, a: f32) -> Self {
+        let (r, g, b) = hsv_to_rgb(h, s, v);
+        Color { r, g, b, a }
+    }
+
+    /// Convert to HSL as `(hue 0-360, saturation 0-1, lightness 0-1)`.
+    ///
+    /// Achromatic colors (grays) report a hue of 0. Alpha is dropped; read it
+    /// with [`alpha`](Self::alpha).
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (max, min, h) = hue_and_range(self.r, self.g, self.b);
+        let l = (max + min) * 0.5;
+        let d = max - min;
+        let s = if d == 0.0 {
+            0.0
+        } else {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (h, s, l)
+    }
+
+    /// Convert to HSV as `(hue 0-360, saturation 0-1, value 0-1)`.
+    ///
+    /// Achromatic colors (grays) report a hue of 0. Alpha is dropped; read it
+    /// with [`alpha`](Self::alpha).
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (max, min, h) = hue_and_range(self.r, self.g, self.b);
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (h, s, max)
+    }
+
     pub fn red_value(&self)->f32{
         self.r
     }
@@ -75,17 +174,124 @@ impl Color{
 
 fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
     let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
-    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
     let m = l - c / 2.0;
+    let (r, g, b) = hue_sector(h, c);
+    (r + m, g + m, b + m)
+}
+
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let c = v * s;
+    let m = v - c;
+    let (r, g, b) = hue_sector(h, c);
+    (r + m, g + m, b + m)
+}
 
-    let (r, g, b) = match h as u32 {
+/// Shared hue-to-RGB step for HSL and HSV: places chroma `c` in the sextant
+/// selected by `h` (degrees, wrapped into 0..360).
+fn hue_sector(h: f32, c: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0);
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+
+    match h as u32 {
         0..=59 => (c, x, 0.0),
         60..=119 => (x, c, 0.0),
         120..=179 => (0.0, c, x),
         180..=239 => (0.0, x, c),
         240..=299 => (x, 0.0, c),
         _ => (c, 0.0, x),
+    }
+}
+
+/// Returns `(max, min, hue_degrees)` for an RGB triple.
+fn hue_and_range(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let h = if d == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
     };
+    (max, min, h)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    (r + m, g + m, b + m)
-}
\ No newline at end of file
+    #[test]
+    fn from_rgb_is_opaque() {
+        let c = Color::from_rgb(0.1, 0.2, 0.3);
+        assert_eq!(c.alpha(), 1.0);
+        assert!(!c.is_translucent());
+    }
+
+    #[test]
+    fn with_alpha_keeps_rgb() {
+        let c = Color::from_rgb(0.1, 0.2, 0.3).with_alpha(0.25);
+        assert_eq!(c.red_value(), 0.1);
+        assert_eq!(c.green_value(), 0.2);
+        assert_eq!(c.blue_value(), 0.3);
+        assert_eq!(c.alpha(), 0.25);
+        assert!(c.is_translucent());
+    }
+
+    fn approx(a: (f32, f32, f32), b: (f32, f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3 && (a.2 - b.2).abs() < 1e-3
+    }
+
+    fn rgb(c: Color) -> (f32, f32, f32) {
+        (c.red_value(), c.green_value(), c.blue_value())
+    }
+
+    #[test]
+    fn hsv_primaries() {
+        assert!(approx(rgb(Color::from_hsv(0.0, 1.0, 1.0)), (1.0, 0.0, 0.0)));
+        assert!(approx(rgb(Color::from_hsv(120.0, 1.0, 1.0)), (0.0, 1.0, 0.0)));
+        assert!(approx(rgb(Color::from_hsv(240.0, 1.0, 1.0)), (0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn hue_wraps_around() {
+        assert!(approx(rgb(Color::from_hsv(360.0, 1.0, 1.0)), (1.0, 0.0, 0.0)));
+        assert!(approx(rgb(Color::from_hsl(-120.0, 1.0, 0.5)), (0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn hsl_roundtrip() {
+        let c = Color::from_hsl(200.0, 0.6, 0.4);
+        assert!(approx(c.to_hsl(), (200.0, 0.6, 0.4)));
+    }
+
+    #[test]
+    fn hsv_roundtrip() {
+        let c = Color::from_hsv(35.0, 0.8, 0.9);
+        assert!(approx(c.to_hsv(), (35.0, 0.8, 0.9)));
+    }
+
+    #[test]
+    fn lerp_midpoint_and_clamp() {
+        let mid = Color::lerp(Color::black(), Color::white().with_alpha(0.0), 0.5);
+        assert!(approx(rgb(mid), (0.5, 0.5, 0.5)));
+        assert_eq!(mid.alpha(), 0.5);
+        assert!(approx(rgb(Color::lerp(Color::black(), Color::white(), 2.0)), (1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn lerp_hsv_takes_short_hue_path() {
+        // 350 -> 10 should pass through red (0), not cyan (180).
+        let a = Color::from_hsv(350.0, 1.0, 1.0);
+        let b = Color::from_hsv(10.0, 1.0, 1.0);
+        assert!(approx(rgb(Color::lerp_hsv(a, b, 0.5)), (1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn gray_has_zero_saturation() {
+        let (_, s, l) = Color::from_rgb(0.5, 0.5, 0.5).to_hsl();
+        assert_eq!(s, 0.0);
+        assert!((l - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::core::color::Color;
+use crate::core::gradient::Gradient;
+
+/// Perceptual colormaps for data visualization (heatmaps, per-instance colors
+/// by value).
+///
+/// Each map is stored as evenly spaced control colors and linearly
+/// interpolated in RGB, which stays within a couple of 8-bit steps of the
+/// reference 256-entry tables.
+///
+/// ```ignore
+/// let color = ColorMap::Viridis.sample_range(elevation, 0.0, 3000.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMap {
+    /// Perceptually uniform dark purple to yellow (matplotlib default).
+    Viridis,
+    /// Perceptually uniform dark blue through magenta to yellow.
+    Plasma,
+    /// Perceptually uniform black through red to pale yellow.
+    Inferno,
+    /// Improved rainbow from dark blue through green to dark red.
+    Turbo,
+    /// Diverging blue to red through light gray (Moreland).
+    Coolwarm,
+}
+
+const VIRIDIS: &[u32] = &[
+    0x440154, 0x482878, 0x3e4989, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6ece58, 0xb5de2b,
+    0xfde725,
+];
+
+const PLASMA: &[u32] = &[
+    0x0d0887, 0x46039f, 0x7201a8, 0x9c179e, 0xbd3786, 0xd8576b, 0xed7953, 0xfb9f3a, 0xfdca26,
+    0xf0f921,
+];
+
+const INFERNO: &[u32] = &[
+    0x000004, 0x1b0c41, 0x4a0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9b06, 0xf7d13d,
+    0xfcffa4,
+];
+
+const TURBO: &[u32] = &[
+    0x30123b, 0x4662d7, 0x36aaf9, 0x1ae4b6, 0x72fe5e, 0xc8ef34, 0xfaba39, 0xf66b19, 0xca2a04,
+    0x7a0403,
+];
+
+const COOLWARM: &[u32] = &[
+    0x3b4cc0, 0x6282ea, 0x8db0fe, 0xb8d0f9, 0xdddddd, 0xf5c4ad, 0xf49a7b, 0xde604d, 0xb40426,
+];
+
+impl ColorMap {
+    /// Every available colormap, for pickers and legends.
+    pub const ALL: [ColorMap; 5] = [
+        ColorMap::Viridis,
+        ColorMap::Plasma,
+        ColorMap::Inferno,
+        ColorMap::Turbo,
+        ColorMap::Coolwarm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMap::Viridis => "viridis",
+            ColorMap::Plasma => "plasma",
+            ColorMap::Inferno => "inferno",
+            ColorMap::Turbo => "turbo",
+            ColorMap::Coolwarm => "coolwarm",
+        }
+    }
+
+    fn table(&self) -> &'static [u32] {
+        match self {
+            ColorMap::Viridis => VIRIDIS,
+            ColorMap::Plasma => PLASMA,
+            ColorMap::Inferno => INFERNO,
+            ColorMap::Turbo => TURBO,
+            ColorMap::Coolwarm => COOLWARM,
+        }
+    }
+
+    /// Sample the map at `t` (clamped to `0..=1`).
+    pub fn sample(&self, t: f32) -> Color {
+        let table = self.table();
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let pos = t * (table.len() - 1) as f32;
+        let i = (pos.floor() as usize).min(table.len() - 2);
+        Color::lerp(hex(table[i]), hex(table[i + 1]), pos - i as f32)
+    }
+
+    /// Sample after normalising `value` from the `min..max` range.
+    pub fn sample_range(&self, value: f32, min: f32, max: f32) -> Color {
+        let t = if max > min { (value - min) / (max - min) } else { 0.0 };
+        self.sample(t)
+    }
+
+    /// The map as a [`Gradient`], for callers that want to add or edit stops.
+    pub fn to_gradient(&self) -> Gradient {
+        let colors: Vec<Color> = self.table().iter().map(|&c| hex(c)).collect();
+        Gradient::evenly_spaced(&colors)
+    }
+}
+
+const fn hex(rgb: u32) -> Color {
+    Color::from_rgb8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Color, b: Color) -> bool {
+        (a.red_value() - b.red_value()).abs() < 1e-4
+            && (a.green_value() - b.green_value()).abs() < 1e-4
+            && (a.blue_value() - b.blue_value()).abs() < 1e-4
+    }
+
+    #[test]
+    fn endpoints_match_reference() {
+        assert!(close(ColorMap::Viridis.sample(0.0), hex(0x440154)));
+        assert!(close(ColorMap::Viridis.sample(1.0), hex(0xfde725)));
+        assert!(close(ColorMap::Coolwarm.sample(0.5), hex(0xdddddd)));
+        assert!(close(ColorMap::Turbo.sample(1.0), hex(0x7a0403)));
+    }
+
+    #[test]
+    fn out_of_range_is_clamped() {
+        for map in ColorMap::ALL {
+            assert!(close(map.sample(-3.0), map.sample(0.0)));
+            assert!(close(map.sample(7.0), map.sample(1.0)));
+            assert!(close(map.sample(f32::NAN), map.sample(0.0)));
+        }
+    }
+
+    #[test]
+    fn gradient_agrees_with_sample() {
+        let map = ColorMap::Plasma;
+        let gradient = map.to_gradient();
+        for i in 0..=20 {
+            let t = i as f32 / 20.0;
+            assert!(close(map.sample(t), gradient.sample(t)));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::ops::Range;
+use std::sync::mpsc::Sender;
+
+use crate::core::{Color, Vec2};
+use crate::graphics2d::shapes::{ShapeRenderable, ShapeStyle};
+
+/// One recorded change to the shape at `shape`. Instance data lives in the
+/// buffer's shared arrays.
+#[derive(Clone)]
+enum Command {
+    Position { shape: usize, x: f32, y: f32 },
+    Scale { shape: usize, scale: f32 },
+    Rotation { shape: usize, angle: f32 },
+    Visible { shape: usize, visible: bool },
+    Style { shape: usize, style: ShapeStyle },
+    FillColor { shape: usize, color: Color },
+    InstancePositions { shape: usize, range: Range<usize> },
+    InstanceColors { shape: usize, range: Range<usize> },
+}
+
+/// Shape changes recorded off the render thread and applied on it.
+///
+/// `ShapeRenderable`s own GL objects and stay on the GL thread, but a
+/// `CommandBuffer` is plain data: worker threads can project points, lay
+/// out labels and pick styles into one while the previous frame renders,
+/// then hand it over through a [`CommandQueue`]. Shapes are addressed by
+/// index, as in [`App::shapes_mut`](super::App::shapes_mut). Commands
+/// apply in recording order; those naming a missing shape are skipped.
+///
+/// ```ignore
+/// let queue = app.command_queue();
+/// std::thread::spawn(move || loop {
+///     let mut commands = CommandBuffer::new();
+///     commands.set_instance_positions(0, &project(&points));
+///     if !queue.submit(commands) {
+///         break;
+///     }
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+    positions: Vec<Vec2>,
+    colors: Vec<Color>,
+}
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_position(&mut self, shape: usize, x: f32, y: f32) -> &mut Self {
+        self.commands.push(Command::Position { shape, x, y });
+        self
+    }
+
+    pub fn set_scale(&mut self, shape: usize, scale: f32) -> &mut Self {
+        self.commands.push(Command::Scale { shape, scale });
+        self
+    }
+
+    pub fn set_rotation(&mut self, shape: usize, angle: f32) -> &mut Self {
+        self.commands.push(Command::Rotation { shape, angle });
+        self
+    }
+
+    pub fn set_visible(&mut self, shape: usize, visible: bool) -> &mut Self {
+        self.commands.push(Command::Visible { shape, visible });
+        self
+    }
+
+    /// Record a [`ShapeRenderable::set_style`].
+    pub fn set_style(&mut self, shape: usize, style: ShapeStyle) -> &mut Self {
+        self.commands.push(Command::Style { shape, style });
+        self
+    }
+
+    pub fn set_fill_color(&mut self, shape: usize, color: Color) -> &mut Self {
+        self.commands.push(Command::FillColor { shape, color });
+        self
+    }
+
+    pub fn set_instance_positions(&mut self, shape: usize, positions: &[Vec2]) -> &mut Self {
+        self.extend_instance_positions(shape, positions.iter().copied())
+    }
+
+    /// Record instance positions straight from an iterator, without an
+    /// intermediate `Vec`.
+    pub fn extend_instance_positions(&mut self, shape: usize, positions: impl IntoIterator<Item = Vec2>) -> &mut Self {
+        let start = self.positions.len();
+        self.positions.extend(positions);
+        self.commands.push(Command::InstancePositions { shape, range: start..self.positions.len() });
+        self
+    }
+
+    pub fn set_instance_colors(&mut self, shape: usize, colors: &[Color]) -> &mut Self {
+        self.extend_instance_colors(shape, colors.iter().copied())
+    }
+
+    pub fn extend_instance_colors(&mut self, shape: usize, colors: impl IntoIterator<Item = Color>) -> &mut Self {
+        let start = self.colors.len();
+        self.colors.extend(colors);
+        self.commands.push(Command::InstanceColors { shape, range: start..self.colors.len() });
+        self
+    }
+
+    /// Move `other`'s commands after this buffer's, e.g. to join buffers
+    /// recorded in parallel in a fixed order.
+    pub fn append(&mut self, other: CommandBuffer) -> &mut Self {
+        let (positions, colors) = (self.positions.len(), self.colors.len());
+        self.positions.extend(other.positions);
+        self.colors.extend(other.colors);
+        self.commands.extend(other.commands.into_iter().map(|command| match command {
+            Command::InstancePositions { shape, range } => {
+                Command::InstancePositions { shape, range: range.start + positions..range.end + positions }
+            }
+            Command::InstanceColors { shape, range } => Command::InstanceColors { shape, range: range.start + colors..range.end + colors },
+            command => command,
+        }));
+        self
+    }
+
+    /// Number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Forget the commands but keep the allocations for the next frame.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.positions.clear();
+        self.colors.clear();
+    }
+
+    /// Apply the commands to `shapes` in order. Must run on the GL thread.
+    pub fn execute(&self, shapes: &mut [ShapeRenderable]) {
+        for command in &self.commands {
+            let index = match command {
+                Command::Position { shape, .. }
+                | Command::Scale { shape, .. }
+                | Command::Rotation { shape, .. }
+                | Command::Visible { shape, .. }
+                | Command::Style { shape, .. }
+                | Command::FillColor { shape, .. }
+                | Command::InstancePositions { shape, .. }
+                | Command::InstanceColors { shape, .. } => *shape,
+            };
+            let Some(shape) = shapes.get_mut(index) else {
+                continue;
+            };
+            match command {
+                Command::Position { x, y, .. } => {
+                    shape.set_position(*x, *y);
+                }
+                Command::Scale { scale, .. } => {
+                    shape.set_scale(*scale);
+                }
+                Command::Rotation { angle, .. } => {
+                    shape.set_rotation(*angle);
+                }
+                Command::Visible { visible, .. } => {
+                    shape.set_visible(*visible);
+                }
+                Command::Style { style, .. } => {
+                    shape.set_style(style.clone());
+                }
+                Command::FillColor { color, .. } => {
+                    shape.set_fill_color(*color);
+                }
+                Command::InstancePositions { range, .. } => {
+                    let positions = &self.positions[range.clone()];
+                    // Instancing needs a buffer first, like `Snapshot::apply_to`.
+                    if shape.instance_positions().is_empty() {
+                        shape.create_multiple_instances(positions.len());
+                    }
+                    shape.set_instance_positions(positions);
+                }
+                Command::InstanceColors { range, .. } => {
+                    shape.set_instance_colors(&self.colors[range.clone()]);
+                }
+            }
+        }
+    }
+}
+
+/// Hands [`CommandBuffer`]s from any thread to an [`App`](super::App),
+/// which executes them at the start of the next frame, before
+/// `on_update`, in submission order. Clone it for each worker; get one
+/// from [`App::command_queue`](super::App::command_queue).
+#[derive(Clone)]
+pub struct CommandQueue {
+    pub(crate) sender: Sender<CommandBuffer>,
+}
+
+impl CommandQueue {
+    /// Queue `commands` for the next frame. Returns `false` once the app
+    /// is gone, so workers know to stop.
+    pub fn submit(&self, commands: CommandBuffer) -> bool {
+        self.sender.send(commands).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance_positions(buffer: &CommandBuffer) -> Vec<(usize, Vec<Vec2>)> {
+        buffer
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::InstancePositions { shape, range } => Some((*shape, buffer.positions[range.clone()].to_vec())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn append_keeps_instance_slices_with_their_commands() {
+        let mut first = CommandBuffer::new();
+        first.set_instance_positions(0, &[Vec2::new(1.0, 1.0)]).set_position(1, 5.0, 5.0);
+        let mut second = CommandBuffer::new();
+        second.extend_instance_positions(2, (0..3).map(|i| Vec2::new(i as f32, 0.0)));
+        second.set_instance_colors(2, &[Color::red(); 3]);
+        first.append(second);
+
+        assert_eq!(first.len(), 4);
+        let positions = instance_positions(&first);
+        assert_eq!(positions[0], (0, vec![Vec2::new(1.0, 1.0)]));
+        assert_eq!(positions[1], (2, vec![Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0)]));
+        assert!(matches!(&first.commands[3], Command::InstanceColors { shape: 2, range } if *range == (0..3)));
+    }
+
+    #[test]
+    fn buffers_cross_threads_through_the_queue() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let queue = CommandQueue { sender };
+        std::thread::spawn(move || {
+            let mut commands = CommandBuffer::new();
+            commands.set_visible(3, false);
+            assert!(queue.submit(commands));
+        })
+        .join()
+        .unwrap();
+        let received = receiver.try_recv().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(!received.is_empty());
+    }
+}
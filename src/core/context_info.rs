@@ -0,0 +1,102 @@
+//! Runtime description of the current OpenGL context, for feature
+//! detection.
+
+use std::collections::HashSet;
+
+use crate::core::engine::opengl::{
+    gl_get_integer, gl_get_string, gl_get_stringi, GL_EXTENSIONS, GL_MAX_SAMPLES, GL_MAX_TEXTURE_SIZE, GL_NUM_EXTENSIONS,
+    GL_RENDERER, GL_SHADING_LANGUAGE_VERSION, GL_VENDOR, GL_VERSION,
+};
+
+/// What the current OpenGL context offers, from
+/// [`Renderer::context_info`](super::Renderer::context_info).
+///
+/// ```ignore
+/// let info = renderer.context_info();
+/// if info.supports(4, 4) || info.has_extension("GL_ARB_buffer_storage") {
+///     // persistent mapping is available
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContextInfo {
+    /// `GL_VERSION` as reported, e.g. `"4.6.0 NVIDIA 550.54"` or
+    /// `"OpenGL ES 3.2 Mesa 24.0"`.
+    pub version: String,
+    /// Major and minor version parsed from `version`.
+    pub major: u32,
+    pub minor: u32,
+    /// Whether this is an OpenGL ES context.
+    pub es: bool,
+    /// `GL_SHADING_LANGUAGE_VERSION`, e.g. `"4.60 NVIDIA"`.
+    pub glsl_version: String,
+    pub vendor: String,
+    pub renderer: String,
+    /// Largest width or height of a 2D texture.
+    pub max_texture_size: i32,
+    /// Most samples a multisampled framebuffer can have.
+    pub max_samples: i32,
+    extensions: HashSet<String>,
+}
+
+impl ContextInfo {
+    /// Query the context current on this thread.
+    pub(crate) fn query() -> Self {
+        let version = gl_get_string(GL_VERSION).unwrap_or_default();
+        let (major, minor, es) = parse_version(&version);
+        let count = gl_get_integer(GL_NUM_EXTENSIONS).max(0) as u32;
+        Self {
+            major,
+            minor,
+            es,
+            version,
+            glsl_version: gl_get_string(GL_SHADING_LANGUAGE_VERSION).unwrap_or_default(),
+            vendor: gl_get_string(GL_VENDOR).unwrap_or_default(),
+            renderer: gl_get_string(GL_RENDERER).unwrap_or_default(),
+            max_texture_size: gl_get_integer(GL_MAX_TEXTURE_SIZE),
+            max_samples: gl_get_integer(GL_MAX_SAMPLES),
+            extensions: (0..count).filter_map(|i| gl_get_stringi(GL_EXTENSIONS, i)).collect(),
+        }
+    }
+
+    /// Whether the context is at least version `major.minor` of its API
+    /// (desktop OpenGL or OpenGL ES, see [`es`](Self::es)).
+    pub fn supports(&self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+
+    /// Whether the extension is supported, e.g. `"GL_ARB_buffer_storage"`.
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.contains(name)
+    }
+
+    /// Supported extensions, in no particular order.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.extensions.iter().map(String::as_str)
+    }
+}
+
+/// Major, minor and ES flag of a `GL_VERSION` string: `"<major>.<minor>..."`
+/// on desktop, `"OpenGL ES <major>.<minor>..."` on ES. Zero if unparsable.
+fn parse_version(version: &str) -> (u32, u32, bool) {
+    let (es, number) = match version.strip_prefix("OpenGL ES") {
+        // Some ES 1.x drivers report a profile, e.g. "OpenGL ES-CM 1.1".
+        Some(rest) => (true, rest.split_whitespace().find(|w| w.starts_with(|c: char| c.is_ascii_digit())).unwrap_or("")),
+        None => (false, version.split_whitespace().next().unwrap_or("")),
+    };
+    let mut parts = number.split('.').map(|part| part.parse::<u32>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0), es)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_parse_on_desktop_and_es() {
+        assert_eq!(parse_version("4.6.0 NVIDIA 550.54.14"), (4, 6, false));
+        assert_eq!(parse_version("3.3 (Core Profile) Mesa 24.0.5"), (3, 3, false));
+        assert_eq!(parse_version("OpenGL ES 3.2 Mesa 24.0.5"), (3, 2, true));
+        assert_eq!(parse_version("OpenGL ES-CM 1.1"), (1, 1, true));
+        assert_eq!(parse_version(""), (0, 0, false));
+    }
+}
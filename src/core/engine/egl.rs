@@ -0,0 +1,110 @@
+//! Surfaceless EGL contexts for rendering without a window or display
+//! server, enabled with the `egl` feature on Linux.
+//!
+//! [`EglContext`] opens an EGL display on a GPU device
+//! (`EGL_EXT_platform_device`, which Mesa and the NVIDIA driver expose for
+//! DRM render nodes and for the llvmpipe software rasterizer) and makes an
+//! OpenGL 3.3 core context, or an OpenGL ES 3.0 one where that is all the
+//! driver offers, current without a surface. There is no default
+//! framebuffer; drawing goes to a framebuffer object the caller binds.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr};
+
+use glutin::api::egl::context::PossiblyCurrentContext;
+use glutin::api::egl::device::Device;
+use glutin::api::egl::display::Display;
+use glutin::config::{Api, ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glutin::context::{ContextApi, ContextAttributesBuilder, GlProfile, Version};
+use glutin::display::GlDisplay;
+
+use crate::core::engine::opengl::GLloadproc;
+
+thread_local! {
+    /// Display of the context being loaded, for [`load_gl_function`].
+    static LOADING_DISPLAY: RefCell<Option<Display>> = const { RefCell::new(None) };
+}
+
+pub(crate) struct EglContext {
+    display: Display,
+    // Kept alive (and current) for as long as the renderer draws.
+    _context: PossiblyCurrentContext,
+}
+
+impl EglContext {
+    /// Create a context on the first device that supports OpenGL 3.3 or
+    /// OpenGL ES 3.0 and make it current on this thread.
+    pub(crate) fn new() -> Result<Self, String> {
+        let devices = Device::query_devices().map_err(|e| format!("failed to query EGL devices: {e}"))?;
+        let mut errors = Vec::new();
+        for device in devices {
+            match Self::on_device(&device) {
+                Ok(context) => return Ok(context),
+                Err(e) => {
+                    let error = format!("{}: {e}", device.name().unwrap_or("unnamed device"));
+                    log::debug!("skipping EGL device {error}");
+                    errors.push(error);
+                }
+            }
+        }
+        if errors.is_empty() {
+            Err("no EGL devices found".to_string())
+        } else {
+            Err(format!("no EGL device could create an OpenGL 3.3 or ES 3.0 context ({})", errors.join("; ")))
+        }
+    }
+
+    fn on_device(device: &Device) -> Result<Self, String> {
+        // SAFETY: no native display is passed.
+        let display = unsafe { Display::with_device(device, None) }.map_err(|e| e.to_string())?;
+        // Desktop OpenGL 3.3 core, else OpenGL ES 3.0 on embedded GPUs.
+        Self::create(&display, Api::OPENGL, ContextApi::OpenGl(Some(Version::new(3, 3))))
+            .or_else(|e| {
+                log::info!("no OpenGL 3.3 context ({e}), trying OpenGL ES 3.0");
+                Self::create(&display, Api::GLES3, ContextApi::Gles(Some(Version::new(3, 0))))
+            })
+    }
+
+    fn create(display: &Display, api: Api, context_api: ContextApi) -> Result<Self, String> {
+        let template = ConfigTemplateBuilder::new()
+            .with_api(api)
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .build();
+        // SAFETY: the template has no native window.
+        let config = unsafe { display.find_configs(template) }
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or("no OpenGL config")?;
+        let mut attributes = ContextAttributesBuilder::new().with_context_api(context_api);
+        if api == Api::OPENGL {
+            attributes = attributes.with_profile(GlProfile::Core);
+        }
+        // SAFETY: the context has no native window to outlive.
+        let context = unsafe { display.create_context(&config, &attributes.build(None)) }.map_err(|e| e.to_string())?;
+        let context = context.make_current_surfaceless().map_err(|e| e.to_string())?;
+        Ok(Self { display: display.clone(), _context: context })
+    }
+
+    /// Call `f` with a loader resolving GL functions through this display.
+    pub(crate) fn with_loader<R>(&self, f: impl FnOnce(GLloadproc) -> R) -> R {
+        LOADING_DISPLAY.with(|d| *d.borrow_mut() = Some(self.display.clone()));
+        let result = f(Some(load_gl_function));
+        LOADING_DISPLAY.with(|d| *d.borrow_mut() = None);
+        result
+    }
+}
+
+impl Drop for EglContext {
+    fn drop(&mut self) {
+        // The renderer and its framebuffer are dropped first; delete what
+        // they queued and report what was never deleted (debug builds only).
+        crate::core::flush_deleted_resources();
+        crate::core::report_leaks();
+    }
+}
+
+extern "C" fn load_gl_function(name: *const c_char) -> *const c_void {
+    // SAFETY: `_glLoad` passes NUL-terminated function names.
+    let name = unsafe { CStr::from_ptr(name) };
+    LOADING_DISPLAY.with(|display| display.borrow().as_ref().map_or(std::ptr::null(), |d| d.get_proc_address(name)))
+}
@@ -78,6 +78,12 @@ pub fn load_char(face: FT_Face, char_code: char) -> Result<(), i32> {
     }
 }
 
+/// Glyph index of a character in the face, or 0 if the face has no glyph
+/// for it
+pub(crate) fn char_index(face: FT_Face, char_code: char) -> u32 {
+    unsafe { sys::_ft_get_char_index(face, char_code as c_ulong) }
+}
+
 /// Get the metrics of the currently loaded glyph
 pub fn get_glyph_metrics(face: FT_Face) -> GlyphMetrics {
     let mut metrics = GlyphMetrics::default();
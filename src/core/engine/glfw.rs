@@ -21,17 +21,27 @@ pub use wilhelm_renderer_sys::glfw::{
     GLFW_MOD_NUM_LOCK, GLFW_MOD_SHIFT, GLFW_MOD_SUPER, GLFW_MOUSE_BUTTON_LEFT,
     GLFW_MOUSE_BUTTON_MIDDLE, GLFW_MOUSE_BUTTON_RIGHT, GLFW_PLATFORM_COCOA, GLFW_PLATFORM_NULL,
     GLFW_PLATFORM_WAYLAND, GLFW_PLATFORM_WIN32, GLFW_PLATFORM_X11, GLFW_PRESS, GLFW_RELEASE,
-    GLFW_REPEAT, GLFW_SAMPLES, GLFW_SCALE_TO_MONITOR, GLFWcursorposfun, GLFWframebuffersizefun,
+    GLFW_REPEAT, GLFW_SAMPLES, GLFW_SCALE_TO_MONITOR, GLFW_VISIBLE, GLFWcursorposfun, GLFWframebuffersizefun,
     GLFWkeyfun, GLFWmousebuttonfun, GLFWscrollfun, GLFWwindow, GLFWwindowsizefun,
 };
 
 // Private alias for the raw FFI. Not re-exported.
 use wilhelm_renderer_sys::glfw as sys;
 
+use super::opengl::gl_load;
+
 pub fn glfw_get_time() -> f64 {
     unsafe { sys::_glfwGetTime() }
 }
 
+/// Initialise GLFW ahead of window creation, so window hints can be set.
+/// Returns `false` if initialisation failed.
+pub fn glfw_init() -> bool {
+    unsafe { sys::_glfwInit() != 0 }
+}
+
+/// Create a window, make its context current and load the GL functions
+/// through GLFW. Returns null if either step failed.
 pub fn glfw_create_window(
     title: &str,
     width: i32,
@@ -43,6 +53,13 @@ pub fn glfw_create_window(
     unsafe {
         window_pointer = sys::_glfwCreateWindow(title_c_string.as_ptr(), width, height, callback);
     }
+    if !window_pointer.is_null() && !gl_load(Some(sys::_glfwGetProcAddress)) {
+        unsafe {
+            sys::_glfwDestroyWindow(window_pointer);
+            sys::_glfwTerminate();
+        }
+        return std::ptr::null();
+    }
     window_pointer
 }
 
@@ -55,6 +72,18 @@ pub fn glfw_get_window_content_scale(window: *const GLFWwindow) -> (f32, f32) {
     }
 }
 
+/// Size of the window's framebuffer in pixels, which is larger than the
+/// window size on high-DPI displays.
+#[cfg(not(feature = "winit"))]
+pub(crate) fn glfw_get_framebuffer_size(window: *const GLFWwindow) -> (i32, i32) {
+    let mut width = 0;
+    let mut height = 0;
+    unsafe {
+        sys::_glfwGetFramebufferSize(window, &mut width, &mut height);
+    }
+    (width, height)
+}
+
 pub fn glfw_window_hint(hint: i32, value: i32) {
     unsafe {
         sys::_glfwWindowHint(hint, value);
@@ -140,3 +169,39 @@ pub fn glfw_terminate() {
 pub fn glfw_get_platform() -> i32 {
     unsafe { sys::_glfwGetPlatform() }
 }
+
+/// Native `HWND` of `window`, or null off Windows.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_win32_window(window: *const GLFWwindow) -> *mut c_void {
+    unsafe { sys::_glfwGetWin32Window(window) }
+}
+
+/// Native `NSView` of `window`, or null off macOS.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_cocoa_view(window: *const GLFWwindow) -> *mut c_void {
+    unsafe { sys::_glfwGetCocoaView(window) }
+}
+
+/// X11 window id of `window`, or 0 off X11.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_x11_window(window: *const GLFWwindow) -> u64 {
+    unsafe { sys::_glfwGetX11Window(window) as u64 }
+}
+
+/// The X11 `Display*` GLFW is connected to, or null off X11.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_x11_display() -> *mut c_void {
+    unsafe { sys::_glfwGetX11Display() }
+}
+
+/// Wayland `wl_surface*` of `window`, or null off Wayland.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_wayland_window(window: *const GLFWwindow) -> *mut c_void {
+    unsafe { sys::_glfwGetWaylandWindow(window) }
+}
+
+/// The Wayland `wl_display*` GLFW is connected to, or null off Wayland.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+pub(crate) fn glfw_get_wayland_display() -> *mut c_void {
+    unsafe { sys::_glfwGetWaylandDisplay() }
+}
@@ -0,0 +1,99 @@
+//! Debug-build registry of live GL objects, to find resources that are
+//! never deleted.
+//!
+//! The [`opengl`](super::opengl) wrappers register every vertex array,
+//! buffer, texture and program they generate, with a backtrace of where it
+//! was created, and unregister it when it is deleted. When the context
+//! shuts down, whatever is left is reported as a `log` warning. Backtraces follow
+//! the standard library's rules: set `RUST_LIB_BACKTRACE=1` (or
+//! `RUST_BACKTRACE=1`) to record them. In release builds every function
+//! here is a no-op.
+
+#[cfg(debug_assertions)]
+use std::backtrace::Backtrace;
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+
+use wilhelm_renderer_sys::opengl::GLuint;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum Resource {
+    VertexArray,
+    Buffer,
+    Texture,
+    Program,
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /// Live objects of the context current on this thread; `None` for
+    /// objects kept for the life of the thread on purpose.
+    static LIVE: RefCell<HashMap<(Resource, GLuint), Option<Backtrace>>> = RefCell::new(HashMap::new());
+}
+
+/// Register a newly generated object.
+pub(crate) fn created(resource: Resource, id: GLuint) {
+    #[cfg(debug_assertions)]
+    if id != 0 {
+        LIVE.with(|live| live.borrow_mut().insert((resource, id), Some(Backtrace::capture())));
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = (resource, id);
+}
+
+pub(crate) fn deleted(resource: Resource, id: GLuint) {
+    #[cfg(debug_assertions)]
+    LIVE.with(|live| live.borrow_mut().remove(&(resource, id)));
+    #[cfg(not(debug_assertions))]
+    let _ = (resource, id);
+}
+
+/// Exclude an object that is deliberately kept until the thread exits,
+/// such as a cached shader, from leak reports.
+pub(crate) fn keep(resource: Resource, id: GLuint) {
+    #[cfg(debug_assertions)]
+    LIVE.with(|live| {
+        if let Some(backtrace) = live.borrow_mut().get_mut(&(resource, id)) {
+            *backtrace = None;
+        }
+    });
+    #[cfg(not(debug_assertions))]
+    let _ = (resource, id);
+}
+
+/// Report the objects still alive as `log` warnings, with their creation
+/// backtraces, and forget them. Returns how many there were; always 0 in
+/// release builds. Called when a window or headless context shuts down.
+pub fn report_leaks() -> usize {
+    #[cfg(debug_assertions)]
+    {
+        let live = LIVE.with(|live| std::mem::take(&mut *live.borrow_mut()));
+        let mut leaks: Vec<_> = live.into_iter().filter_map(|(key, backtrace)| Some((key, backtrace?))).collect();
+        leaks.sort_by_key(|&(key, _)| key);
+        for ((resource, id), backtrace) in &leaks {
+            log::warn!("GL resource leak: {resource:?} {id} was never deleted; created at:\n{backtrace}");
+        }
+        leaks.len()
+    }
+    #[cfg(not(debug_assertions))]
+    0
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_undeleted_objects_are_reported() {
+        created(Resource::Buffer, 1);
+        created(Resource::Texture, 1);
+        created(Resource::Program, 2);
+        created(Resource::VertexArray, 0);
+        deleted(Resource::Buffer, 1);
+        keep(Resource::Program, 2);
+        assert_eq!(report_leaks(), 1);
+        assert_eq!(report_leaks(), 0, "reported objects are forgotten");
+    }
+}
@@ -1,3 +1,9 @@
 pub mod opengl;
+pub(crate) mod state;
+pub(crate) mod leaks;
 pub mod glfw;
 pub mod freetype;
+#[cfg(feature = "winit")]
+pub(crate) mod winit;
+#[cfg(all(feature = "egl", target_os = "linux"))]
+pub(crate) mod egl;
@@ -6,33 +6,85 @@
 //! through this module. Clients that need direct FFI access must depend
 //! on `wilhelm_renderer_sys` explicitly.
 
-use std::ffi::{CString, c_char};
+use std::ffi::{CStr, CString, c_char};
 
 // Re-export the public OpenGL types and constants as part of our API.
 pub use wilhelm_renderer_sys::opengl::{
-    GL_ARRAY_BUFFER, GL_BLEND, GL_CLAMP_TO_EDGE, GL_COMPILE_STATUS, GL_CULL_FACE, GL_DYNAMIC_DRAW,
-    GL_ELEMENT_ARRAY_BUFFER, GL_FLOAT, GL_FRAGMENT_SHADER, GL_GEOMETRY_SHADER, GL_LINEAR,
-    GL_LINEAR_MIPMAP_LINEAR, GL_LINES, GL_LINE_STRIP, GL_MULTISAMPLE, GL_ONE_MINUS_SRC_ALPHA,
-    GL_POINTS, GL_RED, GL_REPEAT, GL_RGB, GL_RGBA, GL_SAMPLES, GL_SRC_ALPHA, GL_STATIC_DRAW,
+    GL_ARRAY_BUFFER, GL_BLEND, GL_CLAMP_TO_EDGE, GL_COLOR_ATTACHMENT0, GL_COMPILE_STATUS, GL_INFO_LOG_LENGTH, GL_LINK_STATUS, GL_CULL_FACE, GL_DEPTH_TEST, GL_DYNAMIC_DRAW,
+    GL_ELEMENT_ARRAY_BUFFER, GL_FLOAT, GL_FRAGMENT_SHADER, GL_FRAMEBUFFER, GL_FRAMEBUFFER_BINDING, GL_FRAMEBUFFER_COMPLETE, GL_GEOMETRY_SHADER, GL_LINEAR,
+    GL_LEQUAL, GL_LESS, GL_LINEAR_MIPMAP_LINEAR, GL_LINEAR_MIPMAP_NEAREST, GL_LINES, GL_LINE_STRIP, GL_MAP_INVALIDATE_BUFFER_BIT,
+    GL_MAP_INVALIDATE_RANGE_BIT, GL_MAP_UNSYNCHRONIZED_BIT, GL_MAP_WRITE_BIT, GL_ALREADY_SIGNALED, GL_CONDITION_SATISFIED,
+    GL_SYNC_FLUSH_COMMANDS_BIT, GL_TIMEOUT_EXPIRED, GL_WAIT_FAILED, GL_MAP_READ_BIT, GL_PIXEL_PACK_BUFFER, GL_PIXEL_UNPACK_BUFFER,
+    GL_STREAM_DRAW, GL_STREAM_READ, GL_VENDOR, GL_RENDERER, GL_VERSION, GL_EXTENSIONS, GL_SHADING_LANGUAGE_VERSION,
+    GL_NUM_EXTENSIONS, GL_MAX_TEXTURE_SIZE, GL_MAX_SAMPLES,
+    GL_MIRRORED_REPEAT, GL_MULTISAMPLE, GL_NEAREST, GL_NEAREST_MIPMAP_LINEAR,
+    GL_NEAREST_MIPMAP_NEAREST, GL_ONE, GL_ONE_MINUS_SRC_ALPHA,
+    GL_POINTS, GL_R8, GL_RED, GL_REPEAT, GL_RGB, GL_RGBA, GL_SAMPLES, GL_SCISSOR_TEST, GL_SRC_ALPHA, GL_STATIC_DRAW,
     GL_TEXTURE0, GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S,
     GL_TEXTURE_WRAP_T, GL_TRIANGLES, GL_TRIANGLE_FAN, GL_TRIANGLE_STRIP, GL_UNPACK_ALIGNMENT,
     GL_UNSIGNED_BYTE, GL_UNSIGNED_INT, GL_VERTEX_SHADER, GL_VIEWPORT, GLboolean, GLchar, GLenum,
-    GLfloat, GLint, GLsizei, GLsizeiptr, GLuint, GLvoid, Vec2,
+    GLbitfield, GLfloat, GLint, GLloadproc, GLsizei, GLsizeiptr, GLsync, GLuint, GLvoid, Vec2,
 };
 
 // Private alias for the raw FFI. Not re-exported.
 use wilhelm_renderer_sys::opengl as sys;
 
+use super::leaks::{self, Resource};
+use super::state::{self, UniformValue};
+
+/// Load the OpenGL function pointers of the context current on this
+/// thread through `loader`, or the platform's GL library when `None`.
+/// Returns `false` if loading failed.
+pub fn gl_load(loader: GLloadproc) -> bool {
+    state::reset();
+    unsafe { sys::_glLoad(loader) != 0 }
+}
+
+/// Whether the loaded context is OpenGL ES rather than desktop OpenGL.
+pub fn gl_is_es() -> bool {
+    unsafe { sys::_glIsES() != 0 }
+}
+
 pub fn gl_clear_color(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
     unsafe { sys::_glClearColor(red, green, blue, alpha) }
 }
 
+/// Clear the depth buffer, for depth-tested drawing.
+pub fn gl_clear_depth_buffer() {
+    unsafe { sys::_glClearDepthBuffer() }
+}
+
 pub fn gl_viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
     unsafe {
         sys::_glViewPort(x, y, width, height);
     }
 }
 
+pub fn gl_scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    unsafe { sys::_glScissor(x, y, width, height) }
+}
+
+/// A GL string such as `GL_VERSION`, or `None` if GL has none for `name`.
+pub fn gl_get_string(name: GLenum) -> Option<String> {
+    let string = unsafe { sys::_glGetString(name) };
+    // SAFETY: GL returns a NUL-terminated string or null.
+    (!string.is_null()).then(|| unsafe { CStr::from_ptr(string) }.to_string_lossy().into_owned())
+}
+
+/// Entry `index` of an indexed GL string such as `GL_EXTENSIONS`.
+pub fn gl_get_stringi(name: GLenum, index: GLuint) -> Option<String> {
+    let string = unsafe { sys::_glGetStringi(name, index) };
+    // SAFETY: as for `gl_get_string`.
+    (!string.is_null()).then(|| unsafe { CStr::from_ptr(string) }.to_string_lossy().into_owned())
+}
+
+/// A single integer state value such as `GL_MAX_TEXTURE_SIZE`.
+pub fn gl_get_integer(pname: GLenum) -> GLint {
+    let mut value: GLint = 0;
+    unsafe { sys::_glGetIntegerv(pname, &mut value as *mut GLint as *mut GLvoid) };
+    value
+}
+
 pub fn gl_get_integerv(pname: GLenum, data: *mut GLvoid) {
     unsafe {
         sys::_glGetIntegerv(pname, data);
@@ -68,8 +120,45 @@ pub fn gl_get_shaderiv(shader: GLuint, pname: GLenum, params: &mut GLint) {
     unsafe { sys::_glGetShaderiv(shader, pname, params as *mut GLint) }
 }
 
+pub fn gl_get_shader_compile_status(shader: GLuint) -> bool {
+    let mut status = 0;
+    gl_get_shaderiv(shader, GL_COMPILE_STATUS, &mut status);
+    status != 0
+}
+
+pub fn gl_get_shader_info_log(shader: GLuint) -> String {
+    let mut length = 0;
+    gl_get_shaderiv(shader, GL_INFO_LOG_LENGTH, &mut length);
+    let mut log = vec![0u8; length.max(1) as usize];
+    unsafe { sys::_glGetShaderInfoLog(shader, log.len() as GLsizei, log.as_mut_ptr() as *mut c_char) };
+    info_log_string(&log)
+}
+
+pub fn gl_get_program_link_status(program: GLuint) -> bool {
+    let mut status = 0;
+    unsafe { sys::_glGetProgramiv(program, GL_LINK_STATUS, &mut status) };
+    status != 0
+}
+
+pub fn gl_get_program_info_log(program: GLuint) -> String {
+    let mut length = 0;
+    unsafe { sys::_glGetProgramiv(program, GL_INFO_LOG_LENGTH, &mut length) };
+    let mut log = vec![0u8; length.max(1) as usize];
+    unsafe { sys::_glGetProgramInfoLog(program, log.len() as GLsizei, log.as_mut_ptr() as *mut c_char) };
+    info_log_string(&log)
+}
+
+/// The text of a nul-terminated info log, without trailing whitespace.
+fn info_log_string(log: &[u8]) -> String {
+    CStr::from_bytes_until_nul(log)
+        .map(|log| log.to_string_lossy().trim_end().to_string())
+        .unwrap_or_default()
+}
+
 pub fn gl_create_program() -> GLuint {
-    unsafe { sys::_glCreateProgram() }
+    let program = unsafe { sys::_glCreateProgram() };
+    leaks::created(Resource::Program, program);
+    program
 }
 
 pub fn gl_attach_shader(program: GLuint, shader: GLuint) {
@@ -77,25 +166,34 @@ pub fn gl_attach_shader(program: GLuint, shader: GLuint) {
 }
 
 pub fn gl_link_program(program: GLuint) {
+    state::with(|state| state.forget_program(program));
     unsafe {
         sys::_glLinkProgram(program);
     }
 }
 
 pub fn gl_delete_program(program: GLuint) {
+    state::with(|state| state.forget_program(program));
+    leaks::deleted(Resource::Program, program);
     unsafe { sys::_glDeleteProgram(program) }
 }
 
 pub fn gl_use_program(program: GLuint) {
+    if !state::with(|state| state.use_program(program)) {
+        return;
+    }
     unsafe {
         sys::_glUseProgram(program);
     }
 }
 
 pub fn gl_gen_buffer() -> GLuint {
-    unsafe { sys::_glGenBuffer() }
+    let buffer = unsafe { sys::_glGenBuffer() };
+    leaks::created(Resource::Buffer, buffer);
+    buffer
 }
 pub fn gl_delete_buffer(buffer: GLuint) {
+    leaks::deleted(Resource::Buffer, buffer);
     unsafe { sys::_glDeleteBuffer(buffer) }
 }
 
@@ -103,6 +201,9 @@ pub fn gl_gen_buffers(buffers: &mut Vec<GLuint>) {
     unsafe {
         sys::_glGenBuffers(buffers.len().try_into().unwrap(), buffers.as_mut_ptr());
     }
+    for &buffer in buffers.iter() {
+        leaks::created(Resource::Buffer, buffer);
+    }
 }
 
 pub fn gl_bind_buffer(target: GLuint, buffer: GLuint) {
@@ -112,18 +213,26 @@ pub fn gl_bind_buffer(target: GLuint, buffer: GLuint) {
 }
 
 pub fn gl_gen_texture() -> GLuint {
-    unsafe { sys::_glGenTexture() }
+    let texture = unsafe { sys::_glGenTexture() };
+    leaks::created(Resource::Texture, texture);
+    texture
 }
 
 pub fn gl_bind_texture(target: GLenum, texture: GLuint) {
+    if target == GL_TEXTURE_2D && !state::with(|state| state.bind_texture(texture)) {
+        return;
+    }
     unsafe { sys::_glBindTexture(target, texture) }
 }
 
 pub fn gl_gen_vertex_array() -> GLuint {
-    unsafe { sys::_glGenVertexArray() }
+    let vao = unsafe { sys::_glGenVertexArray() };
+    leaks::created(Resource::VertexArray, vao);
+    vao
 }
 
 pub fn gl_delete_vertex_array(vao: GLuint) {
+    leaks::deleted(Resource::VertexArray, vao);
     unsafe { sys::_glDeleteVertexArray(vao) }
 }
 
@@ -182,6 +291,43 @@ pub fn gl_buffer_sub_data_vec2(target: GLenum, xy: &[Vec2]) {
     }
 }
 
+/// Map `length` bytes of the buffer bound to `target`, starting at
+/// `offset`. Returns null if the mapping failed.
+///
+/// # Safety
+/// The pointer is only valid until [`gl_unmap_buffer`], and only for the
+/// accesses granted by `access`.
+pub unsafe fn gl_map_buffer_range(target: GLenum, offset: GLsizeiptr, length: GLsizeiptr, access: GLbitfield) -> *mut GLvoid {
+    unsafe { sys::_glMapBufferRange(target, offset, length, access) }
+}
+
+/// Unmap the buffer bound to `target`. Returns `false` if its contents
+/// were lost while mapped and must be written again.
+pub fn gl_unmap_buffer(target: GLenum) -> bool {
+    unsafe { sys::_glUnmapBuffer(target) != 0 }
+}
+
+/// Insert a fence that signals once the commands issued so far complete.
+pub fn gl_fence_sync() -> GLsync {
+    unsafe { sys::_glFenceSync() }
+}
+
+/// Wait up to `timeout` nanoseconds for `sync`. Returns
+/// `GL_ALREADY_SIGNALED`, `GL_CONDITION_SATISFIED`, `GL_TIMEOUT_EXPIRED`
+/// or `GL_WAIT_FAILED`.
+///
+/// # Safety
+/// `sync` must come from [`gl_fence_sync`] and not be deleted yet.
+pub unsafe fn gl_client_wait_sync(sync: GLsync, flags: GLbitfield, timeout: u64) -> GLenum {
+    unsafe { sys::_glClientWaitSync(sync, flags, timeout) }
+}
+
+/// # Safety
+/// `sync` must come from [`gl_fence_sync`] and not be deleted yet.
+pub unsafe fn gl_delete_sync(sync: GLsync) {
+    unsafe { sys::_glDeleteSync(sync) }
+}
+
 pub fn gl_enable_vertex_attrib_array(index: GLuint) {
     unsafe {
         sys::_glEnableVertexAttribArray(index);
@@ -223,6 +369,9 @@ pub fn gl_vertex_attrib_divisor(index: GLuint, divisor: GLuint) {
     }
 }
 
+/// Set the constant value of a disabled vertex attribute. Not cached: an
+/// instanced draw with the attribute's array enabled leaves its current
+/// value undefined.
 pub fn gl_vertex_attrib_4f(index: GLuint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat) {
     unsafe {
         sys::_glVertexAttrib4f(index, v0, v1, v2, v3);
@@ -233,7 +382,17 @@ pub fn gl_draw_elements(mode: GLenum, count: GLsizei, element_type: GLenum, offs
     unsafe { sys::_glDrawElements(mode, count, element_type, offset) }
 }
 
+/// Look up a uniform location, cached per program until it is relinked.
 pub fn gl_get_uniform_location(program: GLuint, name: &str) -> GLint {
+    if let Some(location) = state::with(|state| state.location(program, name)) {
+        return location;
+    }
+    let location = query_uniform_location(program, name);
+    state::with(|state| state.set_location(program, name, location));
+    location
+}
+
+fn query_uniform_location(program: GLuint, name: &str) -> GLint {
     const MAX_STACK_LEN: usize = 63;
 
     debug_assert!(!name.contains('\0'), "Uniform name contains null byte");
@@ -251,24 +410,36 @@ pub fn gl_get_uniform_location(program: GLuint, name: &str) -> GLint {
 }
 
 pub fn gl_uniform_1f(location: GLint, v0: GLfloat) {
+    if !state::with(|state| state.uniform(location, UniformValue::new(&[v0]))) {
+        return;
+    }
     unsafe {
         sys::_glUniform1f(location, v0);
     }
 }
 
 pub fn gl_uniform_2f(location: GLint, v0: GLfloat, v1: GLfloat) {
+    if !state::with(|state| state.uniform(location, UniformValue::new(&[v0, v1]))) {
+        return;
+    }
     unsafe {
         sys::_glUniform2f(location, v0, v1);
     }
 }
 
 pub fn gl_uniform_3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+    if !state::with(|state| state.uniform(location, UniformValue::new(&[v0, v1, v2]))) {
+        return;
+    }
     unsafe {
         sys::_glUniform3f(location, v0, v1, v2);
     }
 }
 
 pub fn gl_uniform_4f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat) {
+    if !state::with(|state| state.uniform(location, UniformValue::new(&[v0, v1, v2, v3]))) {
+        return;
+    }
     unsafe {
         sys::_glUniform4f(location, v0, v1, v2, v3);
     }
@@ -280,26 +451,63 @@ pub fn gl_uniform_matrix_4fv(
     transpose: GLboolean,
     value: *const GLfloat,
 ) {
+    state::with(|state| state.forget_uniform(location));
     unsafe {
         sys::_glUniformMatrix4fv(location, count, transpose, value);
     }
 }
 
+/// Set a single column-major 4x4 matrix uniform, skipped if unchanged.
+pub fn gl_uniform_matrix_4f(location: GLint, matrix: &[GLfloat; 16]) {
+    if !state::with(|state| state.uniform(location, UniformValue::new(matrix))) {
+        return;
+    }
+    unsafe {
+        sys::_glUniformMatrix4fv(location, 1, GLboolean::FALSE, matrix.as_ptr());
+    }
+}
+
 pub fn gl_point_size(size: GLfloat) {
     unsafe { sys::_glPointSize(size) }
 }
 
 pub fn gl_enable(cap: u32) {
+    if cap == GL_BLEND && !state::with(|state| state.set_blend(true)) {
+        return;
+    }
     unsafe {
         sys::_glEnable(cap);
     }
 }
 
+pub fn gl_disable(cap: u32) {
+    if cap == GL_BLEND && !state::with(|state| state.set_blend(false)) {
+        return;
+    }
+    unsafe {
+        sys::_glDisable(cap);
+    }
+}
+
 pub fn gl_blend_func(sfactor: GLenum, dfactor: GLenum) {
+    if !state::with(|state| state.blend_func(sfactor, dfactor)) {
+        return;
+    }
     unsafe { sys::_glBlendFunc(sfactor, dfactor) }
 }
 
+pub fn gl_depth_func(func: GLenum) {
+    unsafe { sys::_glDepthFunc(func) }
+}
+
+pub fn gl_depth_mask(flag: bool) {
+    unsafe { sys::_glDepthMask(if flag { GLboolean::TRUE } else { GLboolean::FALSE }) }
+}
+
 pub fn gl_active_texture(unit: GLenum) {
+    if !state::with(|state| state.active_texture(unit)) {
+        return;
+    }
     unsafe {
         sys::_glActiveTexture(unit);
     }
@@ -376,7 +584,63 @@ pub fn gl_pixel_storei(pname: GLenum, param: GLint) {
 }
 
 pub fn gl_delete_texture(texture: GLuint) {
+    state::with(|state| state.delete_texture(texture));
+    leaks::deleted(Resource::Texture, texture);
     unsafe {
         sys::_glDeleteTexture(texture);
     }
 }
+
+pub fn gl_gen_framebuffer() -> GLuint {
+    unsafe { sys::_glGenFramebuffer() }
+}
+
+pub fn gl_bind_framebuffer(target: GLenum, framebuffer: GLuint) {
+    unsafe { sys::_glBindFramebuffer(target, framebuffer) }
+}
+
+pub fn gl_framebuffer_texture_2d(
+    target: GLenum,
+    attachment: GLenum,
+    textarget: GLenum,
+    texture: GLuint,
+    level: GLint,
+) {
+    unsafe { sys::_glFramebufferTexture2D(target, attachment, textarget, texture, level) }
+}
+
+pub fn gl_check_framebuffer_status(target: GLenum) -> GLenum {
+    unsafe { sys::_glCheckFramebufferStatus(target) }
+}
+
+pub fn gl_delete_framebuffer(framebuffer: GLuint) {
+    unsafe { sys::_glDeleteFramebuffer(framebuffer) }
+}
+
+/// Read a block of pixels from the bound framebuffer into `data`, which must
+/// hold at least `width * height` pixels in the requested format.
+pub fn gl_read_pixels(
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    data_type: GLenum,
+    data: &mut [u8],
+) {
+    unsafe { sys::_glReadPixels(x, y, width, height, format, data_type, data.as_mut_ptr() as *mut GLvoid) }
+}
+
+/// Start reading a block of RGBA8 pixels from the bound framebuffer into
+/// `buffer` at byte `offset`, through `GL_PIXEL_PACK_BUFFER`. Returns
+/// without waiting for the transfer; map the buffer for reading once it
+/// has completed.
+pub fn gl_read_pixels_rgba_to_buffer(buffer: GLuint, offset: usize, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    // With a pack buffer bound the pointer argument is an offset into it,
+    // and GL checks the write against the buffer size.
+    unsafe {
+        sys::_glBindBuffer(GL_PIXEL_PACK_BUFFER, buffer);
+        sys::_glReadPixels(x, y, width, height, GL_RGBA as GLenum, GL_UNSIGNED_BYTE, offset as *mut GLvoid);
+        sys::_glBindBuffer(GL_PIXEL_PACK_BUFFER, 0);
+    }
+}
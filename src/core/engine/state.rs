@@ -0,0 +1,162 @@
+//! Shadow copy of the GL state set through the [`opengl`](super::opengl)
+//! wrappers, so they can skip calls that would not change anything.
+//!
+//! Drawing many shapes of the same style repeats the same program,
+//! texture, blend and uniform values call after call. The wrappers record
+//! what they set here and return early when the value is already current.
+//! Anything that may have changed the context behind the wrappers' back
+//! (a host application, a second context on the thread) must call
+//! [`reset`]; [`Renderer`](crate::core::Renderer) does so each frame.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use wilhelm_renderer_sys::opengl::{GLenum, GLfloat, GLint, GLuint};
+
+/// A uniform value as float bit patterns, so NaNs compare equal to
+/// themselves.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct UniformValue {
+    len: usize,
+    bits: [u32; 16],
+}
+
+impl UniformValue {
+    pub(crate) fn new(values: &[GLfloat]) -> Self {
+        let mut bits = [0; 16];
+        for (bit, value) in bits.iter_mut().zip(values) {
+            *bit = value.to_bits();
+        }
+        Self { len: values.len().min(16), bits }
+    }
+}
+
+/// Known GL state; `None` and missing entries mean unknown.
+#[derive(Default)]
+pub(crate) struct StateCache {
+    program: Option<GLuint>,
+    active_texture: Option<GLenum>,
+    /// `GL_TEXTURE_2D` binding per texture unit.
+    textures: HashMap<GLenum, GLuint>,
+    blend: Option<bool>,
+    blend_func: Option<(GLenum, GLenum)>,
+    uniforms: HashMap<(GLuint, GLint), UniformValue>,
+    locations: HashMap<GLuint, HashMap<String, GLint>>,
+}
+
+impl StateCache {
+    /// Record `program` as current; `false` if it already was.
+    pub(crate) fn use_program(&mut self, program: GLuint) -> bool {
+        self.program.replace(program) != Some(program)
+    }
+
+    /// Forget what is known about `program`, after it is relinked or
+    /// deleted.
+    pub(crate) fn forget_program(&mut self, program: GLuint) {
+        self.uniforms.retain(|&(p, _), _| p != program);
+        self.locations.remove(&program);
+    }
+
+    pub(crate) fn active_texture(&mut self, unit: GLenum) -> bool {
+        self.active_texture.replace(unit) != Some(unit)
+    }
+
+    /// Record a `GL_TEXTURE_2D` binding on the active unit; `false` if it
+    /// was already bound. Always `true` while the active unit is unknown.
+    pub(crate) fn bind_texture(&mut self, texture: GLuint) -> bool {
+        match self.active_texture {
+            Some(unit) => self.textures.insert(unit, texture) != Some(texture),
+            None => true,
+        }
+    }
+
+    /// GL unbinds a texture from every unit when it is deleted.
+    pub(crate) fn delete_texture(&mut self, texture: GLuint) {
+        for bound in self.textures.values_mut().filter(|bound| **bound == texture) {
+            *bound = 0;
+        }
+    }
+
+    pub(crate) fn set_blend(&mut self, enabled: bool) -> bool {
+        self.blend.replace(enabled) != Some(enabled)
+    }
+
+    pub(crate) fn blend_func(&mut self, sfactor: GLenum, dfactor: GLenum) -> bool {
+        self.blend_func.replace((sfactor, dfactor)) != Some((sfactor, dfactor))
+    }
+
+    /// Record a uniform of the current program; `false` if it already had
+    /// `value`. Always `true` while the current program is unknown.
+    pub(crate) fn uniform(&mut self, location: GLint, value: UniformValue) -> bool {
+        match self.program {
+            Some(program) if location != -1 => self.uniforms.insert((program, location), value) != Some(value),
+            _ => true,
+        }
+    }
+
+    /// Forget a uniform of the current program, set in a way not tracked.
+    pub(crate) fn forget_uniform(&mut self, location: GLint) {
+        if let Some(program) = self.program {
+            self.uniforms.remove(&(program, location));
+        }
+    }
+
+    pub(crate) fn location(&self, program: GLuint, name: &str) -> Option<GLint> {
+        self.locations.get(&program)?.get(name).copied()
+    }
+
+    pub(crate) fn set_location(&mut self, program: GLuint, name: &str, location: GLint) {
+        self.locations.entry(program).or_default().insert(name.to_string(), location);
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<StateCache> = RefCell::new(StateCache::default());
+}
+
+/// Run `f` on this thread's state cache.
+pub(crate) fn with<R>(f: impl FnOnce(&mut StateCache) -> R) -> R {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// Forget all recorded state, so the next calls reach GL again.
+pub fn reset() {
+    with(|state| *state = StateCache::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_values_are_skipped_per_program() {
+        let mut state = StateCache::default();
+        let red = UniformValue::new(&[1.0, 0.0, 0.0, 1.0]);
+        assert!(state.uniform(3, red), "unknown program");
+        assert!(state.use_program(7));
+        assert!(!state.use_program(7));
+        assert!(state.uniform(3, red));
+        assert!(!state.uniform(3, red));
+        assert!(state.uniform(3, UniformValue::new(&[f32::NAN])));
+        assert!(!state.uniform(3, UniformValue::new(&[f32::NAN])));
+
+        state.use_program(8);
+        assert!(state.uniform(3, red), "values are kept per program");
+        state.use_program(7);
+        state.forget_program(7);
+        assert!(state.uniform(3, UniformValue::new(&[f32::NAN])), "relinking forgets uniforms");
+    }
+
+    #[test]
+    fn texture_bindings_follow_units_and_deletion() {
+        let mut state = StateCache::default();
+        assert!(state.bind_texture(4), "unknown unit");
+        state.active_texture(0x84C0);
+        assert!(state.bind_texture(4));
+        assert!(!state.bind_texture(4));
+        state.delete_texture(4);
+        assert!(state.bind_texture(4));
+        assert!(state.active_texture(0x84C1));
+        assert!(state.bind_texture(4));
+    }
+}
@@ -0,0 +1,301 @@
+//! Window backend built on winit and glutin, enabled with the `winit`
+//! feature in place of GLFW.
+//!
+//! [`WinitWindow`] owns the event loop, the OS window and its OpenGL 3.3
+//! core context (OpenGL ES 3.0 where the driver has no desktop GL). Its events are translated to the GLFW codes the
+//! [`Window`](crate::core::Window) callbacks already use (key codes, mouse
+//! buttons, actions and modifier bits), so application code does not
+//! change with the backend. Sizes and cursor positions are in logical
+//! pixels, like GLFW's screen coordinates.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr};
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+use glutin::config::{Config, ConfigTemplateBuilder, GlConfig};
+use glutin::context::{ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentGlContext, PossiblyCurrentContext, Version};
+use glutin::display::{Display, GetGlDisplay, GlDisplay};
+use glutin::surface::{GlSurface, Surface, WindowSurface};
+use glutin_winit::{DisplayBuilder, GlWindow};
+use raw_window_handle::HasWindowHandle;
+use winit::application::ApplicationHandler;
+use winit::dpi::LogicalSize;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+use winit::platform::pump_events::EventLoopExtPumpEvents;
+use winit::platform::scancode::PhysicalKeyExtScancode;
+use winit::window::{Window as OsWindow, WindowId};
+
+use crate::core::engine::glfw::{
+    GLFW_MOD_ALT, GLFW_MOD_CONTROL, GLFW_MOD_SHIFT, GLFW_MOD_SUPER, GLFW_MOUSE_BUTTON_LEFT, GLFW_MOUSE_BUTTON_MIDDLE,
+    GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS, GLFW_RELEASE, GLFW_REPEAT,
+};
+use crate::core::engine::opengl::{gl_load, gl_viewport};
+use crate::core::input_record::InputEvent;
+
+/// Pixels of a trackpad scroll counted as one wheel step.
+const PIXELS_PER_SCROLL_LINE: f64 = 20.0;
+
+thread_local! {
+    /// Display of the context being loaded, for [`load_gl_function`].
+    static LOADING_DISPLAY: RefCell<Option<Display>> = const { RefCell::new(None) };
+    /// Start of the clock behind [`get_time`].
+    static START: Instant = Instant::now();
+}
+
+/// Seconds since the first call, standing in for `glfwGetTime`.
+pub(crate) fn get_time() -> f64 {
+    START.with(|start| start.elapsed().as_secs_f64())
+}
+
+/// A window event in the terms of the `Window` callbacks.
+pub(crate) enum WinitEvent {
+    /// New size in logical pixels.
+    Resized(i32, i32),
+    Input(InputEvent),
+}
+
+pub(crate) struct WinitWindow {
+    event_loop: EventLoop<()>,
+    state: WindowState,
+}
+
+/// Everything the event handler needs, apart from the event loop that
+/// drives it.
+struct WindowState {
+    // Dropped in declaration order: context and surface before the window.
+    context: PossiblyCurrentContext,
+    surface: Surface<WindowSurface>,
+    window: OsWindow,
+    modifiers: ModifiersState,
+    should_close: bool,
+    events: Vec<WinitEvent>,
+}
+
+impl WinitWindow {
+    /// Open a window with a current OpenGL 3.3 core context, or OpenGL ES
+    /// 3.0 as a fallback, and load the GL functions. Panics if no such context can be created, as GLFW
+    /// window creation does.
+    pub(crate) fn new(title: &str, width: i32, height: i32, visible: bool) -> Self {
+        let event_loop = EventLoop::new().expect("failed to create winit event loop");
+        let attributes = OsWindow::default_attributes()
+            .with_title(title)
+            .with_inner_size(LogicalSize::new(width, height))
+            .with_visible(visible);
+        let template = ConfigTemplateBuilder::new().with_multisampling(4).with_depth_size(24);
+        let (window, config) = DisplayBuilder::new()
+            .with_window_attributes(Some(attributes))
+            .build(&event_loop, template, pick_config)
+            .expect("failed to create window");
+        let window = window.expect("failed to create window");
+
+        let display = config.display();
+        let raw_handle = window.window_handle().ok().map(|handle| handle.as_raw());
+        let context_attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+            .with_profile(GlProfile::Core)
+            .build(raw_handle);
+        // Embedded GPUs (Raspberry Pi, Mali) often only offer OpenGL ES.
+        let gles_attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::Gles(Some(Version::new(3, 0))))
+            .build(raw_handle);
+        let surface_attributes = window.build_surface_attributes(Default::default()).expect("failed to create window surface");
+        // SAFETY: the window outlives the surface and context, which are
+        // dropped first (see `WindowState`).
+        let (context, surface) = unsafe {
+            let context = display
+                .create_context(&config, &context_attributes)
+                .or_else(|e| {
+                    log::info!("no OpenGL 3.3 context ({e}), trying OpenGL ES 3.0");
+                    display.create_context(&config, &gles_attributes)
+                })
+                .expect("failed to create an OpenGL 3.3 or OpenGL ES 3.0 context");
+            let surface = display.create_window_surface(&config, &surface_attributes).expect("failed to create window surface");
+            (context, surface)
+        };
+        let context = context.make_current(&surface).expect("failed to make OpenGL context current");
+
+        LOADING_DISPLAY.with(|d| *d.borrow_mut() = Some(display));
+        let loaded = gl_load(Some(load_gl_function));
+        LOADING_DISPLAY.with(|d| *d.borrow_mut() = None);
+        assert!(loaded, "failed to load OpenGL functions");
+
+        let size = window.inner_size();
+        gl_viewport(0, 0, size.width as i32, size.height as i32);
+        Self {
+            event_loop,
+            state: WindowState { context, surface, window, modifiers: ModifiersState::empty(), should_close: false, events: Vec::new() },
+        }
+    }
+
+    pub(crate) fn os_window(&self) -> &OsWindow {
+        &self.state.window
+    }
+
+    pub(crate) fn content_scale(&self) -> (f32, f32) {
+        let scale = self.state.window.scale_factor() as f32;
+        (scale, scale)
+    }
+
+    /// Size of the drawable surface in physical pixels.
+    pub(crate) fn framebuffer_size(&self) -> (i32, i32) {
+        let size = self.state.window.inner_size();
+        (size.width as i32, size.height as i32)
+    }
+
+    pub(crate) fn should_close(&self) -> bool {
+        self.state.should_close
+    }
+
+    pub(crate) fn swap_buffers(&self) {
+        if let Err(err) = self.state.surface.swap_buffers(&self.state.context) {
+            log::error!("swap_buffers failed: {err}");
+        }
+    }
+
+    /// Process pending OS events without blocking and return them.
+    pub(crate) fn poll_events(&mut self) -> Vec<WinitEvent> {
+        let _ = self.event_loop.pump_app_events(Some(Duration::ZERO), &mut self.state);
+        std::mem::take(&mut self.state.events)
+    }
+}
+
+impl ApplicationHandler for WindowState {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+        let scale = self.window.scale_factor();
+        match event {
+            WindowEvent::CloseRequested => self.should_close = true,
+            WindowEvent::Resized(size) => {
+                if let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+                    self.surface.resize(&self.context, width, height);
+                }
+                gl_viewport(0, 0, size.width as i32, size.height as i32);
+                let logical = size.to_logical::<f64>(scale);
+                self.events.push(WinitEvent::Resized(logical.width.round() as i32, logical.height.round() as i32));
+            }
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
+            WindowEvent::CursorMoved { position, .. } => {
+                let logical = position.to_logical::<f64>(scale);
+                self.events.push(WinitEvent::Input(InputEvent::CursorPosition { x: logical.x, y: logical.y }));
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x_offset, y_offset) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x as f64, y as f64),
+                    MouseScrollDelta::PixelDelta(p) => (p.x / PIXELS_PER_SCROLL_LINE, p.y / PIXELS_PER_SCROLL_LINE),
+                };
+                self.events.push(WinitEvent::Input(InputEvent::Scroll { x_offset, y_offset }));
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let button = glfw_mouse_button(button);
+                let action = if state == ElementState::Pressed { GLFW_PRESS } else { GLFW_RELEASE };
+                self.events.push(WinitEvent::Input(InputEvent::MouseButton { button, action, mods: glfw_mods(self.modifiers) }));
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                let key = match event.physical_key {
+                    PhysicalKey::Code(code) => glfw_key(code),
+                    PhysicalKey::Unidentified(_) => -1,
+                };
+                let scancode = event.physical_key.to_scancode().map_or(0, |s| s as i32);
+                let action = match (event.state, event.repeat) {
+                    (ElementState::Released, _) => GLFW_RELEASE,
+                    (ElementState::Pressed, true) => GLFW_REPEAT,
+                    (ElementState::Pressed, false) => GLFW_PRESS,
+                };
+                self.events.push(WinitEvent::Input(InputEvent::Key { key, scancode, action, mods: glfw_mods(self.modifiers) }));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The config with the most MSAA samples.
+fn pick_config(configs: Box<dyn Iterator<Item = Config> + '_>) -> Config {
+    configs
+        .reduce(|best, config| if config.num_samples() > best.num_samples() { config } else { best })
+        .expect("no OpenGL config available")
+}
+
+extern "C" fn load_gl_function(name: *const c_char) -> *const c_void {
+    // SAFETY: `_glLoad` passes NUL-terminated function names.
+    let name = unsafe { CStr::from_ptr(name) };
+    LOADING_DISPLAY.with(|display| display.borrow().as_ref().map_or(std::ptr::null(), |d| d.get_proc_address(name)))
+}
+
+fn glfw_mouse_button(button: MouseButton) -> i32 {
+    match button {
+        MouseButton::Left => GLFW_MOUSE_BUTTON_LEFT,
+        MouseButton::Right => GLFW_MOUSE_BUTTON_RIGHT,
+        MouseButton::Middle => GLFW_MOUSE_BUTTON_MIDDLE,
+        MouseButton::Back => 3,
+        MouseButton::Forward => 4,
+        MouseButton::Other(n) => n as i32,
+    }
+}
+
+fn glfw_mods(modifiers: ModifiersState) -> i32 {
+    [
+        (modifiers.shift_key(), GLFW_MOD_SHIFT),
+        (modifiers.control_key(), GLFW_MOD_CONTROL),
+        (modifiers.alt_key(), GLFW_MOD_ALT),
+        (modifiers.super_key(), GLFW_MOD_SUPER),
+    ]
+    .into_iter()
+    .filter(|(held, _)| *held)
+    .fold(0, |mods, (_, bit)| mods | bit)
+}
+
+/// GLFW key codes of the physical keys, by US layout position.
+const KEY_CODES: &[(KeyCode, i32)] = &[
+    (KeyCode::Space, 32), (KeyCode::Quote, 39), (KeyCode::Comma, 44), (KeyCode::Minus, 45),
+    (KeyCode::Period, 46), (KeyCode::Slash, 47), (KeyCode::Semicolon, 59), (KeyCode::Equal, 61),
+    (KeyCode::BracketLeft, 91), (KeyCode::Backslash, 92), (KeyCode::BracketRight, 93), (KeyCode::Backquote, 96),
+    (KeyCode::Digit0, 48), (KeyCode::Digit1, 49), (KeyCode::Digit2, 50), (KeyCode::Digit3, 51), (KeyCode::Digit4, 52),
+    (KeyCode::Digit5, 53), (KeyCode::Digit6, 54), (KeyCode::Digit7, 55), (KeyCode::Digit8, 56), (KeyCode::Digit9, 57),
+    (KeyCode::KeyA, 65), (KeyCode::KeyB, 66), (KeyCode::KeyC, 67), (KeyCode::KeyD, 68), (KeyCode::KeyE, 69),
+    (KeyCode::KeyF, 70), (KeyCode::KeyG, 71), (KeyCode::KeyH, 72), (KeyCode::KeyI, 73), (KeyCode::KeyJ, 74),
+    (KeyCode::KeyK, 75), (KeyCode::KeyL, 76), (KeyCode::KeyM, 77), (KeyCode::KeyN, 78), (KeyCode::KeyO, 79),
+    (KeyCode::KeyP, 80), (KeyCode::KeyQ, 81), (KeyCode::KeyR, 82), (KeyCode::KeyS, 83), (KeyCode::KeyT, 84),
+    (KeyCode::KeyU, 85), (KeyCode::KeyV, 86), (KeyCode::KeyW, 87), (KeyCode::KeyX, 88), (KeyCode::KeyY, 89),
+    (KeyCode::KeyZ, 90),
+    (KeyCode::Escape, 256), (KeyCode::Enter, 257), (KeyCode::Tab, 258), (KeyCode::Backspace, 259),
+    (KeyCode::Insert, 260), (KeyCode::Delete, 261), (KeyCode::ArrowRight, 262), (KeyCode::ArrowLeft, 263),
+    (KeyCode::ArrowDown, 264), (KeyCode::ArrowUp, 265), (KeyCode::PageUp, 266), (KeyCode::PageDown, 267),
+    (KeyCode::Home, 268), (KeyCode::End, 269), (KeyCode::CapsLock, 280), (KeyCode::ScrollLock, 281),
+    (KeyCode::NumLock, 282), (KeyCode::PrintScreen, 283), (KeyCode::Pause, 284),
+    (KeyCode::F1, 290), (KeyCode::F2, 291), (KeyCode::F3, 292), (KeyCode::F4, 293), (KeyCode::F5, 294),
+    (KeyCode::F6, 295), (KeyCode::F7, 296), (KeyCode::F8, 297), (KeyCode::F9, 298), (KeyCode::F10, 299),
+    (KeyCode::F11, 300), (KeyCode::F12, 301),
+    (KeyCode::Numpad0, 320), (KeyCode::Numpad1, 321), (KeyCode::Numpad2, 322), (KeyCode::Numpad3, 323),
+    (KeyCode::Numpad4, 324), (KeyCode::Numpad5, 325), (KeyCode::Numpad6, 326), (KeyCode::Numpad7, 327),
+    (KeyCode::Numpad8, 328), (KeyCode::Numpad9, 329), (KeyCode::NumpadDecimal, 330), (KeyCode::NumpadDivide, 331),
+    (KeyCode::NumpadMultiply, 332), (KeyCode::NumpadSubtract, 333), (KeyCode::NumpadAdd, 334),
+    (KeyCode::NumpadEnter, 335), (KeyCode::NumpadEqual, 336),
+    (KeyCode::ShiftLeft, 340), (KeyCode::ControlLeft, 341), (KeyCode::AltLeft, 342), (KeyCode::SuperLeft, 343),
+    (KeyCode::ShiftRight, 344), (KeyCode::ControlRight, 345), (KeyCode::AltRight, 346), (KeyCode::SuperRight, 347),
+    (KeyCode::ContextMenu, 348),
+];
+
+/// The GLFW key code of `code`, or -1 (`GLFW_KEY_UNKNOWN`).
+fn glfw_key(code: KeyCode) -> i32 {
+    KEY_CODES.iter().find(|(k, _)| *k == code).map_or(-1, |(_, key)| *key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::glfw::{GLFW_KEY_ESCAPE, GLFW_KEY_LEFT_SHIFT, GLFW_KEY_SPACE};
+
+    #[test]
+    fn keys_map_to_glfw_codes() {
+        assert_eq!(glfw_key(KeyCode::Space), GLFW_KEY_SPACE);
+        assert_eq!(glfw_key(KeyCode::Escape), GLFW_KEY_ESCAPE);
+        assert_eq!(glfw_key(KeyCode::ShiftLeft), GLFW_KEY_LEFT_SHIFT);
+        assert_eq!(glfw_key(KeyCode::KeyW), 'W' as i32);
+        assert_eq!(glfw_key(KeyCode::Fn), -1);
+        assert_eq!(glfw_mods(ModifiersState::SHIFT | ModifiersState::CONTROL), GLFW_MOD_SHIFT | GLFW_MOD_CONTROL);
+    }
+}
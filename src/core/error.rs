@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// Why a resource-backed shape or asset could not be created.
+///
+/// Returned by the `try_` constructors such as
+/// [`ShapeRenderable::try_from_shape`](crate::graphics2d::shapes::ShapeRenderable::try_from_shape),
+/// so applications can recover from a missing font or image instead of
+/// panicking. Converts into `String` for functions using `String` errors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WilhelmError {
+    /// A font file could not be read or rasterized.
+    Font { path: String, message: String },
+    /// An image file could not be opened or decoded.
+    Image { path: String, message: String },
+    /// A shader failed to compile or link; the message holds the driver's
+    /// info log.
+    Shader(String),
+    /// The shape's geometry is invalid, e.g. a polyline with one point.
+    InvalidShape(String),
+}
+
+impl fmt::Display for WilhelmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Font { path, message } => write!(f, "failed to load font {path}: {message}"),
+            Self::Image { path, message } => write!(f, "failed to load image {path}: {message}"),
+            Self::Shader(message) => write!(f, "shader error: {message}"),
+            Self::InvalidShape(message) => write!(f, "invalid shape: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for WilhelmError {}
+
+impl From<WilhelmError> for String {
+    fn from(error: WilhelmError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::try_load_image;
+
+    #[test]
+    fn missing_image_is_an_error_naming_the_path() {
+        let error = try_load_image("does/not/exist.png").err().unwrap();
+        assert!(matches!(&error, WilhelmError::Image { path, .. } if path == "does/not/exist.png"));
+        let message: String = error.into();
+        assert!(message.starts_with("failed to load image does/not/exist.png: "));
+    }
+}
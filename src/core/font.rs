@@ -3,15 +3,16 @@
 //! Manages glyph caching in an OpenGL texture atlas.
 
 use crate::core::engine::freetype::{
-    done_face, done_freetype, get_glyph_bitmap, get_glyph_metrics, init_freetype, load_char,
-    new_face, set_pixel_sizes, FT_Face, FT_Library,
+    char_index, done_face, done_freetype, get_glyph_bitmap, get_glyph_metrics, init_freetype,
+    load_char, new_face, set_pixel_sizes, FT_Face, FT_Library,
 };
 use crate::core::engine::opengl::{
-    gl_bind_texture, gl_delete_texture, gl_gen_texture, gl_pixel_storei, gl_tex_image_2d,
-    gl_tex_parameteri, gl_tex_sub_image_2d, GL_CLAMP_TO_EDGE, GL_LINEAR, GL_RED, GL_TEXTURE_2D,
+    gl_bind_texture, gl_pixel_storei, gl_tex_image_2d,
+    gl_tex_parameteri, gl_tex_sub_image_2d, GL_CLAMP_TO_EDGE, GL_LINEAR, GL_R8, GL_RED, GL_TEXTURE_2D,
     GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S, GL_TEXTURE_WRAP_T,
     GL_UNPACK_ALIGNMENT, GL_UNSIGNED_BYTE,
 };
+use crate::core::gpu::GpuTexture;
 use std::collections::HashMap;
 
 /// Information about a cached glyph in the atlas
@@ -33,105 +34,217 @@ pub struct GlyphInfo {
     pub advance: f32,
 }
 
-/// A font atlas that caches glyphs in an OpenGL texture
-pub struct FontAtlas {
-    library: FT_Library,
-    face: FT_Face,
-    texture_id: u32,
-    atlas_width: u32,
-    atlas_height: u32,
+/// Default limit for [`FontAtlas::set_max_size`].
+const DEFAULT_MAX_ATLAS_SIZE: u32 = 4096;
+
+/// Glyph bitmaps packed row by row into a square single-channel image
+/// that can double in size. Kept on the CPU so a grown atlas can be
+/// re-uploaded.
+struct Shelf {
+    size: u32,
+    max_size: u32,
     /// Current packing position
     cursor_x: u32,
     cursor_y: u32,
     row_height: u32,
+    /// Atlas pixels, row by row.
+    pixels: Vec<u8>,
+}
+
+impl Shelf {
+    fn new(size: u32, max_size: u32) -> Self {
+        Self {
+            size,
+            max_size: max_size.max(size),
+            cursor_x: 0,
+            cursor_y: 0,
+            row_height: 0,
+            pixels: vec![0; (size * size) as usize],
+        }
+    }
+
+    /// Reserve a `width` x `height` area, returning its top-left corner, or
+    /// `None` (reserving nothing) if it does not fit at the current size.
+    fn place(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (mut x, mut y, mut row_height) = (self.cursor_x, self.cursor_y, self.row_height);
+        // Check if we need to move to next row
+        if x + width > self.size {
+            x = 0;
+            y += row_height + 1; // +1 for padding
+            row_height = 0;
+        }
+        if width > self.size || y + height > self.size {
+            return None;
+        }
+        self.cursor_x = x + width + 1; // +1 for padding
+        self.cursor_y = y;
+        self.row_height = row_height.max(height);
+        Some((x, y))
+    }
+
+    /// The size the atlas reaches once it cannot double any more.
+    fn largest_size(&self) -> u32 {
+        let mut size = self.size;
+        while size * 2 <= self.max_size {
+            size *= 2;
+        }
+        size
+    }
+
+    /// Double the size, keeping placed bitmaps where they are. Returns
+    /// false if the atlas is already at its maximum size.
+    fn grow(&mut self) -> bool {
+        if self.size * 2 > self.max_size {
+            return false;
+        }
+        let (old, new) = (self.size as usize, self.size as usize * 2);
+        let mut pixels = vec![0; new * new];
+        for (row, source) in self.pixels.chunks_exact(old).enumerate() {
+            pixels[row * new..row * new + old].copy_from_slice(source);
+        }
+        self.pixels = pixels;
+        self.size *= 2;
+        true
+    }
+
+    /// Drop every placed bitmap, keeping the current size.
+    fn clear(&mut self) {
+        self.pixels.fill(0);
+        self.cursor_x = 0;
+        self.cursor_y = 0;
+        self.row_height = 0;
+    }
+
+    /// Copy a `width` x `height` bitmap to `(x, y)`; `row` returns each of
+    /// its rows.
+    fn blit<'a>(&mut self, x: u32, y: u32, width: u32, height: u32, row: impl Fn(usize) -> &'a [u8]) {
+        for r in 0..height as usize {
+            let start = (y as usize + r) * self.size as usize + x as usize;
+            self.pixels[start..start + width as usize].copy_from_slice(&row(r)[..width as usize]);
+        }
+    }
+
+    /// The pixels of a placed area, row by row.
+    fn region(&self, x: u32, y: u32, width: u32, height: u32) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for r in y..y + height {
+            let start = (r * self.size + x) as usize;
+            pixels.extend_from_slice(&self.pixels[start..start + width as usize]);
+        }
+        pixels
+    }
+}
+
+/// What part of the atlas image changed while looking up a glyph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AtlasUpdate {
+    Unchanged,
+    Region { x: u32, y: u32, width: u32, height: u32 },
+    /// The atlas grew or was cleared.
+    All,
+}
+
+/// The FreeType faces and the atlas layout, without any GL state.
+struct GlyphPacker {
+    library: FT_Library,
+    /// The primary face first, then the fallbacks in order.
+    faces: Vec<FT_Face>,
+    fallbacks: Vec<String>,
+    shelf: Shelf,
     /// Cached glyphs
     glyphs: HashMap<char, GlyphInfo>,
     /// Font size in pixels
     font_size: u32,
+    /// How many times live text uses each character; those glyphs survive
+    /// when the full atlas is compacted.
+    pins: HashMap<char, usize>,
+    /// Bumped whenever cached texture coordinates change.
+    generation: u64,
 }
 
-impl FontAtlas {
-    /// Create a new font atlas
-    ///
-    /// # Arguments
-    /// * `font_path` - Path to the TTF/OTF font file
-    /// * `font_size` - Font size in pixels
-    /// * `atlas_size` - Size of the texture atlas (width and height, must be power of 2)
-    pub fn new(font_path: &str, font_size: u32, atlas_size: u32) -> Result<Self, String> {
+// SAFETY: the FreeType library and faces are owned by this packer alone and
+// only used through `&mut self` or in `Drop`, so moving it to another
+// thread cannot race.
+unsafe impl Send for GlyphPacker {}
+
+impl GlyphPacker {
+    fn open(font_path: &str, font_size: u32, atlas_size: u32) -> Result<Self, String> {
         // Initialize FreeType
         let library = init_freetype().map_err(|e| format!("Failed to init FreeType: {}", e))?;
 
         // Load font face
-        let face =
-            new_face(library, font_path, 0).map_err(|e| format!("Failed to load font: {}", e))?;
-
-        // Set font size
-        set_pixel_sizes(face, 0, font_size)
-            .map_err(|e| format!("Failed to set font size: {}", e))?;
-
-        // Create OpenGL texture
-        let texture_id = gl_gen_texture();
-        gl_bind_texture(GL_TEXTURE_2D, texture_id);
-
-        // Set texture parameters
-        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE);
-        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE);
-        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
-        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
-
-        // Allocate empty texture (single channel for grayscale glyphs)
-        gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
-        gl_tex_image_2d(
-            GL_TEXTURE_2D,
-            0,
-            GL_RED as i32,
-            atlas_size as i32,
-            atlas_size as i32,
-            0,
-            GL_RED,
-            GL_UNSIGNED_BYTE,
-            std::ptr::null(),
-        );
-
+        let face = match open_face(library, font_path, font_size) {
+            Ok(face) => face,
+            Err(e) => {
+                done_freetype(library);
+                return Err(e);
+            }
+        };
         Ok(Self {
             library,
-            face,
-            texture_id,
-            atlas_width: atlas_size,
-            atlas_height: atlas_size,
-            cursor_x: 0,
-            cursor_y: 0,
-            row_height: 0,
+            faces: vec![face],
+            fallbacks: Vec::new(),
+            shelf: Shelf::new(atlas_size, DEFAULT_MAX_ATLAS_SIZE),
             glyphs: HashMap::new(),
             font_size,
+            pins: HashMap::new(),
+            generation: 0,
         })
     }
 
-    /// Get the OpenGL texture ID
-    pub fn texture_id(&self) -> u32 {
-        self.texture_id
+    /// Replace the fallback faces with those at `paths`, clearing the
+    /// atlas so characters are looked up again. On error the current
+    /// fallbacks are kept.
+    fn set_fallbacks(&mut self, paths: &[&str]) -> Result<(), String> {
+        if self.fallbacks.iter().map(String::as_str).eq(paths.iter().copied()) {
+            return Ok(());
+        }
+        let mut faces = Vec::with_capacity(paths.len());
+        for path in paths {
+            match open_face(self.library, path, self.font_size) {
+                Ok(face) => faces.push(face),
+                Err(e) => {
+                    faces.into_iter().for_each(done_face);
+                    return Err(format!("fallback {path}: {e}"));
+                }
+            }
+        }
+        self.faces.drain(1..).for_each(done_face);
+        self.faces.extend(faces);
+        self.fallbacks = paths.iter().map(|path| path.to_string()).collect();
+        self.reset();
+        Ok(())
     }
 
-    /// Get glyph info, loading it into the atlas if necessary
-    pub fn get_glyph(&mut self, ch: char) -> Option<GlyphInfo> {
+    /// Evict every cached glyph.
+    fn reset(&mut self) {
+        self.glyphs.clear();
+        self.shelf.clear();
+        self.generation += 1;
+    }
+
+    /// The first face with a glyph for `ch`, or the primary face, which
+    /// draws its missing-glyph box.
+    fn face_for(&self, ch: char) -> FT_Face {
+        self.faces.iter().copied().find(|&face| char_index(face, ch) != 0).unwrap_or(self.faces[0])
+    }
+
+    /// Get glyph info, rasterizing and placing it if necessary, and which
+    /// part of the atlas image that changed.
+    fn get_glyph(&mut self, ch: char) -> (Option<GlyphInfo>, AtlasUpdate) {
         // Return cached glyph if available
         if let Some(&info) = self.glyphs.get(&ch) {
-            return Some(info);
+            return (Some(info), AtlasUpdate::Unchanged);
         }
 
-        // Load and cache the glyph
-        self.cache_glyph(ch)
-    }
-
-    /// Cache a glyph into the atlas
-    fn cache_glyph(&mut self, ch: char) -> Option<GlyphInfo> {
         // Load the glyph
-        if load_char(self.face, ch).is_err() {
-            return None;
+        let face = self.face_for(ch);
+        if load_char(face, ch).is_err() {
+            return (None, AtlasUpdate::Unchanged);
         }
 
-        let metrics = get_glyph_metrics(self.face);
-        let (bitmap_ptr, _pitch) = get_glyph_bitmap(self.face);
+        let metrics = get_glyph_metrics(face);
+        let (bitmap_ptr, pitch) = get_glyph_bitmap(face);
 
         if bitmap_ptr.is_null() || metrics.width == 0 || metrics.height == 0 {
             // Space or empty glyph - still need to track advance
@@ -147,52 +260,61 @@ impl FontAtlas {
                 advance: (metrics.advance >> 6) as f32, // Convert from 1/64th pixels
             };
             self.glyphs.insert(ch, info);
-            return Some(info);
+            return (Some(info), AtlasUpdate::Unchanged);
         }
 
         let glyph_width = metrics.width as u32;
         let glyph_height = metrics.height as u32;
-
-        // Check if we need to move to next row
-        if self.cursor_x + glyph_width > self.atlas_width {
-            self.cursor_x = 0;
-            self.cursor_y += self.row_height + 1; // +1 for padding
-            self.row_height = 0;
-        }
-
-        // Check if atlas is full
-        if self.cursor_y + glyph_height > self.atlas_height {
-            eprintln!("Font atlas is full!");
-            return None;
+        // Anything smaller fits once the atlas has grown or been emptied.
+        let largest = self.shelf.largest_size();
+        if glyph_width > largest || glyph_height > largest {
+            log::warn!("glyph {ch:?} is larger than the font atlas and will not be drawn");
+            return (None, AtlasUpdate::Unchanged);
         }
 
-        // Upload glyph bitmap to texture
-        gl_bind_texture(GL_TEXTURE_2D, self.texture_id);
-        gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
+        // Grow the atlas when it is full, and once it cannot, make room by
+        // evicting the glyphs no live text uses.
+        let mut update = None;
+        let (x, y) = loop {
+            if let Some(corner) = self.shelf.place(glyph_width, glyph_height) {
+                break corner;
+            }
+            let old_size = self.shelf.size as f32;
+            if self.shelf.grow() {
+                let factor = old_size / self.shelf.size as f32;
+                for glyph in self.glyphs.values_mut() {
+                    glyph.uv_x *= factor;
+                    glyph.uv_y *= factor;
+                    glyph.uv_width *= factor;
+                    glyph.uv_height *= factor;
+                }
+                self.generation += 1;
+            } else if compact(&mut self.shelf, &mut self.glyphs, &self.pins) {
+                self.generation += 1;
+            } else {
+                log::warn!(
+                    "font atlas is full at {0}x{0} with glyphs in use; {ch:?} will not be drawn",
+                    self.shelf.size
+                );
+                return (None, update.unwrap_or(AtlasUpdate::Unchanged));
+            }
+            update = Some(AtlasUpdate::All);
+        };
 
-        gl_tex_sub_image_2d(
-            GL_TEXTURE_2D,
-            0,
-            self.cursor_x as i32,
-            self.cursor_y as i32,
-            glyph_width as i32,
-            glyph_height as i32,
-            GL_RED,
-            GL_UNSIGNED_BYTE,
-            bitmap_ptr as *const std::ffi::c_void,
-        );
+        let row_pitch = if pitch > 0 { pitch as usize } else { glyph_width as usize };
+        self.shelf.blit(x, y, glyph_width, glyph_height, |row| {
+            // SAFETY: FreeType's bitmap holds `height` rows `pitch` bytes
+            // apart, valid until the next glyph is loaded.
+            unsafe { std::slice::from_raw_parts(bitmap_ptr.add(row * row_pitch), glyph_width as usize) }
+        });
 
         // Calculate UV coordinates
-        let uv_x = self.cursor_x as f32 / self.atlas_width as f32;
-        let uv_y = self.cursor_y as f32 / self.atlas_height as f32;
-        let uv_width = glyph_width as f32 / self.atlas_width as f32;
-        let uv_height = glyph_height as f32 / self.atlas_height as f32;
-
+        let atlas_size = self.shelf.size as f32;
         let info = GlyphInfo {
-            uv_x,
-            uv_y,
-            uv_width,
-            uv_height,
+            uv_x: x as f32 / atlas_size,
+            uv_y: y as f32 / atlas_size,
+            uv_width: glyph_width as f32 / atlas_size,
+            uv_height: glyph_height as f32 / atlas_size,
             width: metrics.width,
             height: metrics.height,
             bearing_x: metrics.bearing_x,
@@ -200,12 +322,252 @@ impl FontAtlas {
             advance: (metrics.advance >> 6) as f32,
         };
 
-        // Update cursor position
-        self.cursor_x += glyph_width + 1; // +1 for padding
-        self.row_height = self.row_height.max(glyph_height);
-
         self.glyphs.insert(ch, info);
-        Some(info)
+        let update = update.unwrap_or(AtlasUpdate::Region { x, y, width: glyph_width, height: glyph_height });
+        (Some(info), update)
+    }
+}
+
+/// Evict the glyphs with no entry in `pins` from a full `shelf` and pack
+/// the rest again from the top-left corner, in their old order so they
+/// still fit. Returns false, changing nothing, if every placed glyph is
+/// pinned.
+fn compact(shelf: &mut Shelf, glyphs: &mut HashMap<char, GlyphInfo>, pins: &HashMap<char, usize>) -> bool {
+    let placed = |glyph: &GlyphInfo| glyph.width > 0 && glyph.height > 0;
+    if glyphs.iter().all(|(ch, glyph)| !placed(glyph) || pins.contains_key(ch)) {
+        return false;
+    }
+    glyphs.retain(|ch, glyph| !placed(glyph) || pins.contains_key(ch));
+
+    let size = shelf.size as f32;
+    let mut kept: Vec<(char, u32, u32, Vec<u8>)> = glyphs
+        .iter()
+        .filter(|(_, glyph)| placed(glyph))
+        .map(|(&ch, glyph)| {
+            let (x, y) = ((glyph.uv_x * size).round() as u32, (glyph.uv_y * size).round() as u32);
+            (ch, x, y, shelf.region(x, y, glyph.width as u32, glyph.height as u32))
+        })
+        .collect();
+    kept.sort_by_key(|&(_, x, y, _)| (y, x));
+
+    shelf.clear();
+    for (ch, _, _, bitmap) in kept {
+        let glyph = glyphs.get_mut(&ch).expect("kept glyphs stay cached");
+        let (width, height) = (glyph.width as u32, glyph.height as u32);
+        match shelf.place(width, height) {
+            Some((x, y)) => {
+                shelf.blit(x, y, width, height, |row| &bitmap[row * width as usize..]);
+                glyph.uv_x = x as f32 / size;
+                glyph.uv_y = y as f32 / size;
+            }
+            None => {
+                glyphs.remove(&ch);
+            }
+        }
+    }
+    true
+}
+
+impl Drop for GlyphPacker {
+    fn drop(&mut self) {
+        // Clean up FreeType resources
+        self.faces.drain(..).for_each(done_face);
+        done_freetype(self.library);
+    }
+}
+
+/// Open the face at `font_path` and set its pixel size.
+fn open_face(library: FT_Library, font_path: &str, font_size: u32) -> Result<FT_Face, String> {
+    let face = new_face(library, font_path, 0).map_err(|e| format!("Failed to load font: {}", e))?;
+    if let Err(e) = set_pixel_sizes(face, 0, font_size) {
+        done_face(face);
+        return Err(format!("Failed to set font size: {}", e));
+    }
+    Ok(face)
+}
+
+/// Check that `font_path` can be opened, without keeping it.
+pub(crate) fn check_font(font_path: &str) -> Result<(), String> {
+    let library = init_freetype().map_err(|e| format!("Failed to init FreeType: {}", e))?;
+    let face = new_face(library, font_path, 0).map_err(|e| format!("Failed to load font: {}", e));
+    if let Ok(face) = face {
+        done_face(face);
+    }
+    done_freetype(library);
+    face.map(|_| ())
+}
+
+/// A font opened and rasterized into a CPU-side atlas, ready to become a
+/// [`FontAtlas`] on the GL thread.
+///
+/// Loading touches no GL state, so it can run on a worker thread; see
+/// [`AssetLoader::load_font`](super::AssetLoader::load_font).
+pub struct FontData {
+    packer: GlyphPacker,
+}
+
+impl FontData {
+    /// Open `font_path` and rasterize the printable ASCII range, like
+    /// [`FontAtlas::new`] followed by [`FontAtlas::cache_ascii`].
+    pub fn load(font_path: &str, font_size: u32, atlas_size: u32) -> Result<Self, String> {
+        let mut packer = GlyphPacker::open(font_path, font_size, atlas_size)?;
+        for ch in 32u8..127u8 {
+            packer.get_glyph(ch as char);
+        }
+        Ok(Self { packer })
+    }
+
+    pub fn font_size(&self) -> u32 {
+        self.packer.font_size
+    }
+
+    /// Upload the atlas to a texture. Needs a current GL context.
+    pub fn into_atlas(self) -> FontAtlas {
+        let texture = create_atlas_texture(self.packer.shelf.size, self.packer.shelf.pixels.as_ptr());
+        FontAtlas { packer: self.packer, texture }
+    }
+}
+
+/// A font atlas that caches glyphs in an OpenGL texture
+///
+/// Characters missing from the font are taken from the first fallback
+/// font that has them; see [`set_fallbacks`](Self::set_fallbacks). Only
+/// outline and monochrome bitmap fonts are supported, so color emoji fonts
+/// do not work as fallbacks; use a monochrome symbol font instead.
+///
+/// When the texture fills up it doubles in size, up to
+/// [`set_max_size`](Self::set_max_size). After that, glyphs no text has
+/// pinned with [`pin_glyphs`](Self::pin_glyphs) are evicted and the rest
+/// are packed again; a glyph that still does not fit is not drawn. Either
+/// way, texture coordinates from earlier [`get_glyph`](Self::get_glyph)
+/// calls become invalid and [`generation`](Self::generation) changes; text
+/// shapes pin their glyphs and re-read their texture coordinates when
+/// they see it change.
+pub struct FontAtlas {
+    packer: GlyphPacker,
+    texture: GpuTexture,
+}
+
+impl FontAtlas {
+    /// Create a new font atlas
+    ///
+    /// # Arguments
+    /// * `font_path` - Path to the TTF/OTF font file
+    /// * `font_size` - Font size in pixels
+    /// * `atlas_size` - Initial size of the texture atlas (width and height, must be power of 2)
+    pub fn new(font_path: &str, font_size: u32, atlas_size: u32) -> Result<Self, String> {
+        let packer = GlyphPacker::open(font_path, font_size, atlas_size)?;
+        let texture = create_atlas_texture(atlas_size, packer.shelf.pixels.as_ptr());
+        Ok(Self { packer, texture })
+    }
+
+    /// Get the OpenGL texture ID
+    pub fn texture_id(&self) -> u32 {
+        self.texture.id()
+    }
+
+    /// Current width and height of the atlas texture
+    pub fn size(&self) -> u32 {
+        self.packer.shelf.size
+    }
+
+    /// Largest size the atlas grows to before evicting unpinned glyphs
+    /// (default 4096). Never below the current size.
+    pub fn set_max_size(&mut self, max_size: u32) -> &mut Self {
+        self.packer.shelf.max_size = max_size.max(self.packer.shelf.size);
+        self
+    }
+
+    /// Changes whenever cached glyphs move or are evicted
+    pub fn generation(&self) -> u64 {
+        self.packer.generation
+    }
+
+    /// Fonts to take characters missing from this one from, in order
+    pub fn fallbacks(&self) -> &[String] {
+        &self.packer.fallbacks
+    }
+
+    /// Replace the fallback fonts. Cached glyphs are evicted so characters
+    /// the previous chain lacked are looked up again. If a font cannot be
+    /// opened, the previous chain is kept.
+    pub fn set_fallbacks(&mut self, font_paths: &[&str]) -> Result<(), String> {
+        let generation = self.packer.generation;
+        self.packer.set_fallbacks(font_paths)?;
+        if self.packer.generation != generation {
+            self.upload(AtlasUpdate::All);
+        }
+        Ok(())
+    }
+
+    /// Keep the glyphs of `text` cached while it is drawn, so evicting
+    /// glyphs from the full atlas never takes them. Pins are counted per
+    /// character; release them with [`unpin_glyphs`](Self::unpin_glyphs).
+    pub fn pin_glyphs(&mut self, text: &str) {
+        for ch in text.chars() {
+            *self.packer.pins.entry(ch).or_insert(0) += 1;
+        }
+    }
+
+    /// Release pins taken by [`pin_glyphs`](Self::pin_glyphs) with the
+    /// same `text`.
+    pub fn unpin_glyphs(&mut self, text: &str) {
+        for ch in text.chars() {
+            if let Some(count) = self.packer.pins.get_mut(&ch) {
+                *count -= 1;
+                if *count == 0 {
+                    self.packer.pins.remove(&ch);
+                }
+            }
+        }
+    }
+
+    /// Get glyph info, loading it into the atlas if necessary
+    pub fn get_glyph(&mut self, ch: char) -> Option<GlyphInfo> {
+        let (info, update) = self.packer.get_glyph(ch);
+        self.upload(update);
+        info
+    }
+
+    /// Copy the changed part of the atlas image to the texture.
+    fn upload(&self, update: AtlasUpdate) {
+        let shelf = &self.packer.shelf;
+        match update {
+            AtlasUpdate::Unchanged => {}
+            AtlasUpdate::Region { x, y, width, height } => {
+                let pixels = shelf.region(x, y, width, height);
+                gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+                gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
+                gl_tex_sub_image_2d(
+                    GL_TEXTURE_2D,
+                    0,
+                    x as i32,
+                    y as i32,
+                    width as i32,
+                    height as i32,
+                    GL_RED,
+                    GL_UNSIGNED_BYTE,
+                    pixels.as_ptr() as *const std::ffi::c_void,
+                );
+            }
+            AtlasUpdate::All => {
+                // Respecify the storage in place so meshes holding the
+                // texture id keep working.
+                gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+                gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
+                gl_tex_image_2d(
+                    GL_TEXTURE_2D,
+                    0,
+                    GL_R8,
+                    shelf.size as i32,
+                    shelf.size as i32,
+                    0,
+                    GL_RED,
+                    GL_UNSIGNED_BYTE,
+                    shelf.pixels.as_ptr() as *const std::ffi::c_void,
+                );
+            }
+        }
     }
 
     /// Pre-cache ASCII characters (useful for initialization)
@@ -228,17 +590,103 @@ impl FontAtlas {
 
     /// Get font size
     pub fn font_size(&self) -> u32 {
-        self.font_size
+        self.packer.font_size
     }
 }
 
-impl Drop for FontAtlas {
-    fn drop(&mut self) {
-        // Clean up OpenGL texture
-        gl_delete_texture(self.texture_id);
+/// Create the square single-channel atlas texture, filled from `pixels`.
+fn create_atlas_texture(atlas_size: u32, pixels: *const u8) -> GpuTexture {
+    // Create OpenGL texture
+    let texture = GpuTexture::generate();
+    gl_bind_texture(GL_TEXTURE_2D, texture.id());
 
-        // Clean up FreeType resources
-        done_face(self.face);
-        done_freetype(self.library);
+    // Set texture parameters
+    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE);
+    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE);
+    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
+    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+
+    // Allocate the texture (single channel for grayscale glyphs)
+    gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
+    gl_tex_image_2d(
+        GL_TEXTURE_2D,
+        0,
+        // Sized format: GLES 3 rejects an unsized GL_RED.
+        GL_R8,
+        atlas_size as i32,
+        atlas_size as i32,
+        0,
+        GL_RED,
+        GL_UNSIGNED_BYTE,
+        pixels as *const std::ffi::c_void,
+    );
+    texture
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shelf_wraps_rows_and_grows_keeping_pixels() {
+        let mut shelf = Shelf::new(8, 16);
+        assert_eq!(shelf.place(5, 3), Some((0, 0)));
+        shelf.blit(0, 0, 5, 3, |_| &[7; 5]);
+        // Too wide for the rest of the row, so it starts the next one.
+        assert_eq!(shelf.place(4, 2), Some((0, 4)));
+        assert_eq!(shelf.place(4, 4), None);
+
+        assert!(shelf.grow());
+        assert_eq!(shelf.size, 16);
+        assert_eq!(shelf.region(0, 0, 6, 1), vec![7, 7, 7, 7, 7, 0]);
+        assert_eq!(shelf.region(0, 2, 5, 1), vec![7; 5]);
+        assert_eq!(shelf.place(4, 4), Some((5, 4)));
+        assert!(!shelf.grow());
+
+        shelf.clear();
+        assert_eq!(shelf.place(4, 4), Some((0, 0)));
+        assert!(shelf.pixels.iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn largest_size_stops_at_the_last_doubling() {
+        // 1024 would exceed the maximum, so a 600 pixel glyph never fits.
+        let mut shelf = Shelf::new(512, 1000);
+        assert_eq!(shelf.largest_size(), 512);
+        assert_eq!(shelf.place(600, 10), None);
+        assert!(!shelf.grow());
+        assert_eq!(Shelf::new(64, 1000).largest_size(), 512);
+    }
+
+    #[test]
+    fn compacting_keeps_pinned_glyphs_and_their_pixels() {
+        let mut shelf = Shelf::new(8, 8);
+        let mut glyphs = HashMap::new();
+        for (ch, value) in [('a', 1), ('b', 2), ('c', 3)] {
+            let (x, y) = shelf.place(2, 2).unwrap();
+            let row = [value; 2];
+            shelf.blit(x, y, 2, 2, |_| &row);
+            let info = GlyphInfo {
+                uv_x: x as f32 / 8.0,
+                uv_y: y as f32 / 8.0,
+                uv_width: 0.25,
+                uv_height: 0.25,
+                width: 2,
+                height: 2,
+                bearing_x: 0,
+                bearing_y: 0,
+                advance: 2.0,
+            };
+            glyphs.insert(ch, info);
+        }
+        let pins = HashMap::from([('c', 1)]);
+
+        assert!(compact(&mut shelf, &mut glyphs, &pins));
+        assert_eq!(glyphs.len(), 1);
+        let c = glyphs[&'c'];
+        assert_eq!((c.uv_x, c.uv_y), (0.0, 0.0));
+        assert_eq!(shelf.region(0, 0, 2, 2), vec![3; 4]);
+        // Only pinned glyphs left: nothing to evict.
+        assert!(!compact(&mut shelf, &mut glyphs, &pins));
     }
 }
@@ -1,5 +1,6 @@
-use crate::core::engine::opengl::{GL_ARRAY_BUFFER, GLboolean, GLenum, GLfloat, GLint, GLsizei, GLsizeiptr, GLuint, Vec2, gl_bind_buffer, gl_bind_vertex_array, gl_buffer_data, gl_buffer_data_empty, gl_buffer_sub_data, gl_buffer_sub_data_vec2, gl_delete_buffer, gl_delete_vertex_array, gl_enable_vertex_attrib_array, gl_gen_buffer, gl_gen_vertex_array, gl_vertex_attrib_divisor, gl_vertex_attrib_pointer_float};
+use crate::core::engine::opengl::{GL_ARRAY_BUFFER, GLbitfield, GLboolean, GLenum, GLfloat, GLint, GLsizei, GLsizeiptr, GLuint, Vec2, gl_bind_buffer, gl_bind_vertex_array, gl_buffer_data, gl_buffer_data_empty, gl_buffer_sub_data, gl_buffer_sub_data_vec2, gl_enable_vertex_attrib_array, gl_map_buffer_range, gl_unmap_buffer, gl_vertex_attrib_divisor, GL_MAP_INVALIDATE_BUFFER_BIT, GL_MAP_INVALIDATE_RANGE_BIT, GL_MAP_UNSYNCHRONIZED_BIT, GL_MAP_WRITE_BIT, gl_vertex_attrib_pointer_float};
 use crate::core::color::Color;
+use crate::core::gpu::{GpuBuffer, GpuFence, GpuVertexArray};
 
 #[derive(Debug, Clone)]
 pub struct Attribute {
@@ -40,6 +41,18 @@ impl Attribute {
         }
     }
 
+    pub fn instanced_vec3(location: u32) -> Self {
+        // tightly packed vec3 (e.g., InstanceTransform), divisor=1
+        Self {
+            location,
+            size: 3,
+            normalize: GLboolean::FALSE,
+            stride: (3 * std::mem::size_of::<GLfloat>()) as GLsizei,
+            offset: 0,
+            divisor: 1,
+        }
+    }
+
     pub fn instanced_vec4(location: u32) -> Self {
         // tightly packed vec4 (e.g., RGBA color), divisor=1
         Self {
@@ -53,39 +66,70 @@ impl Attribute {
     }
 }
 
+/// Per-instance scale and rotation for instanced drawing, applied in the
+/// mesh's local space before the instance is moved to its position.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstanceTransform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    /// Radians, added to the shape's own rotation.
+    pub rotation: f32,
+}
+
+impl Default for InstanceTransform {
+    fn default() -> Self {
+        Self { scale_x: 1.0, scale_y: 1.0, rotation: 0.0 }
+    }
+}
+
+impl InstanceTransform {
+    pub fn new(scale_x: f32, scale_y: f32, rotation: f32) -> Self {
+        Self { scale_x, scale_y, rotation }
+    }
+
+    /// Uniform scale, no rotation.
+    pub fn scale(scale: f32) -> Self {
+        Self::new(scale, scale, 0.0)
+    }
+}
+
+/// How long a ring write waits for the GPU to release a region before
+/// overwriting it anyway, in nanoseconds.
+const RING_WAIT_TIMEOUT: u64 = 1_000_000_000;
+
+/// Instance positions written round-robin into `fences.len()` regions of
+/// one buffer. A region is fenced when the next one is written, after the
+/// draws that read it were issued, and waited on before it is reused.
+struct InstanceRing {
+    /// Instances per region.
+    capacity: usize,
+    /// The region the attribute currently points at.
+    current: usize,
+    fences: Vec<Option<GpuFence>>,
+}
+
 /// A GPU-backed buffer representing a drawable shape or mesh.
 ///
 /// `Geometry` encapsulates the OpenGL resources (such as VAOs and VBOs)  and metadata required to render
 /// a shape using a specific drawing mode (e.g., triangles, lines).
 ///
 pub struct Geometry {
-    vao: GLuint,
-    vbo: GLuint,
+    vao: GpuVertexArray,
+    vbo: GpuBuffer,
     vertex_count: i32,
+    values_per_vertex: i32,
     drawing_mode: GLenum,
     attributes: Vec<Attribute>,
-    instance_vbo: GLuint,
-    instance_color_vbo: GLuint,
+    uv_vbo: GpuBuffer,
+    instance_vbo: GpuBuffer,
+    instance_color_vbo: GpuBuffer,
+    instance_transform_vbo: GpuBuffer,
+    instance_uv_vbo: GpuBuffer,
+    instance_ring: Option<InstanceRing>,
     instance_count: i32,
 }
 
-impl Drop for Geometry {
-    fn drop(&mut self) {
-        if self.instance_color_vbo != 0 {
-            gl_delete_buffer(self.instance_color_vbo);
-        }
-        if self.instance_vbo != 0 {
-            gl_delete_buffer(self.instance_vbo);
-        }
-        if self.vbo != 0 {
-            gl_delete_buffer(self.vbo);
-        }
-        if self.vao != 0 {
-            gl_delete_vertex_array(self.vao);
-        }
-    }
-}
-
 impl Geometry {
     /// Creates a new empty [`Geometry`] object with the specified OpenGL drawing mode.
     ///
@@ -101,15 +145,19 @@ impl Geometry {
     /// A new, uninitialized [`Geometry`] instance.
     ///
     pub fn new(drawing_mode: GLenum) -> Self {
-        let vao = gl_gen_vertex_array();
         Geometry {
-            vao,
-            vbo: 0,
+            vao: GpuVertexArray::generate(),
+            vbo: GpuBuffer::default(),
             vertex_count: 0,
+            values_per_vertex: 0,
             attributes: Vec::new(),
             drawing_mode,
-            instance_vbo: 0,
-            instance_color_vbo: 0,
+            uv_vbo: GpuBuffer::default(),
+            instance_vbo: GpuBuffer::default(),
+            instance_color_vbo: GpuBuffer::default(),
+            instance_transform_vbo: GpuBuffer::default(),
+            instance_uv_vbo: GpuBuffer::default(),
+            instance_ring: None,
             instance_count: 0,
         }
     }
@@ -133,15 +181,67 @@ impl Geometry {
     /// - The VAO is unbound after the operation to avoid unintended side effects.
     ///
     pub fn add_buffer(&mut self, buffer: &[GLfloat], values_per_vertex: i32) {
-        self.vbo = gl_gen_buffer();
+        self.vbo = GpuBuffer::generate();
         self.vertex_count = buffer.len() as i32 / values_per_vertex;
+        self.values_per_vertex = values_per_vertex;
 
-        gl_bind_vertex_array(self.vao);
-        gl_bind_buffer(GL_ARRAY_BUFFER, self.vbo);
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.vbo.id());
         gl_buffer_data(GL_ARRAY_BUFFER, buffer);
         gl_bind_vertex_array(0);
     }
 
+    /// Replaces the vertex data in place, keeping the VAO, attribute layout
+    /// and instance buffers.
+    ///
+    /// The existing VBO is orphaned and refilled with `GL_DYNAMIC_DRAW`
+    /// usage, so geometry regenerated every frame (morphing, live editing)
+    /// does not allocate new GL objects. The vertex count may change; the
+    /// layout must match the attributes already added. Falls back to
+    /// [Self::add_buffer] if no buffer exists yet.
+    pub fn update_buffer(&mut self, buffer: &[GLfloat], values_per_vertex: i32) {
+        if self.vbo.id() == 0 {
+            self.add_buffer(buffer, values_per_vertex);
+            return;
+        }
+        self.vertex_count = buffer.len() as i32 / values_per_vertex;
+        self.values_per_vertex = values_per_vertex;
+
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.vbo.id());
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, std::mem::size_of_val(buffer) as GLsizeiptr);
+        gl_buffer_sub_data(GL_ARRAY_BUFFER, 0, buffer);
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
+    /// Uploads per-vertex texture coordinates (`u, v` pairs) to a buffer of
+    /// their own at attribute location 1, so they can be replaced without
+    /// the positions; see [Self::update_uv_buffer].
+    pub fn add_uv_buffer(&mut self, uvs: &[GLfloat]) {
+        self.uv_vbo = GpuBuffer::generate();
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.uv_vbo.id());
+        gl_buffer_data(GL_ARRAY_BUFFER, uvs);
+        self.add_vertex_attribute(Attribute::new(1, 2, 2, 0));
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
+    /// Replaces the texture coordinates from [Self::add_uv_buffer] in
+    /// place, leaving the positions and every other buffer alone.
+    pub fn update_uv_buffer(&mut self, uvs: &[GLfloat]) {
+        if self.uv_vbo.id() == 0 {
+            self.add_uv_buffer(uvs);
+            return;
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.uv_vbo.id());
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, std::mem::size_of_val(uvs) as GLsizeiptr);
+        gl_buffer_sub_data(GL_ARRAY_BUFFER, 0, uvs);
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
     /// Defines a vertex attribute layout for this geometry object.
     ///
     /// This sets up how each vertex's data is interpreted in the currently bound Vertex Array Object (VAO).
@@ -162,7 +262,7 @@ impl Geometry {
     /// - The VAO is bound during the call and unbound afterward to preserve OpenGL state.
     /// - You can call this multiple times to add multiple attributes (e.g., position and color).
     pub fn add_vertex_attribute(&mut self, attribute: Attribute) {
-        gl_bind_vertex_array(self.vao);
+        gl_bind_vertex_array(self.vao.id());
 
         gl_enable_vertex_attrib_array(attribute.location);
         gl_vertex_attrib_pointer_float(
@@ -180,13 +280,34 @@ impl Geometry {
     }
 
     pub fn enable_instancing_xy(&mut self, max_instances: usize) {
-        if self.instance_vbo == 0 {
-            self.instance_vbo = gl_gen_buffer();
+        self.instance_ring = None;
+        self.allocate_instance_xy(max_instances);
+    }
+
+    /// Like [`enable_instancing_xy`](Self::enable_instancing_xy), but
+    /// positions go round-robin into `buffers` regions of `max_instances`
+    /// each, guarded by fences, instead of orphaning one buffer per update.
+    /// The CPU then never writes a region the GPU may still be reading;
+    /// it waits for it instead. Three regions suit most frame pacing.
+    /// Updates with more instances than `max_instances` grow the ring.
+    pub fn enable_instancing_xy_buffered(&mut self, max_instances: usize, buffers: usize) {
+        let buffers = buffers.max(1);
+        self.allocate_instance_xy(max_instances * buffers);
+        self.instance_ring = Some(InstanceRing {
+            capacity: max_instances,
+            current: 0,
+            fences: (0..buffers).map(|_| None).collect(),
+        });
+    }
+
+    fn allocate_instance_xy(&mut self, instances: usize) {
+        if self.instance_vbo.id() == 0 {
+            self.instance_vbo = GpuBuffer::generate();
         }
-        gl_bind_vertex_array(self.vao);
-        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo);
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo.id());
 
-        let bytes = (max_instances * 2 * std::mem::size_of::<GLfloat>()) as GLsizei;
+        let bytes = (instances * 2 * std::mem::size_of::<GLfloat>()) as GLsizei;
         gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
 
         // Attribute at location=1, vec2, divisor=1
@@ -206,11 +327,11 @@ impl Geometry {
     }
 
     pub fn enable_instancing_color(&mut self, max_instances: usize) {
-        if self.instance_color_vbo == 0 {
-            self.instance_color_vbo = gl_gen_buffer();
+        if self.instance_color_vbo.id() == 0 {
+            self.instance_color_vbo = GpuBuffer::generate();
         }
-        gl_bind_vertex_array(self.vao);
-        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_color_vbo);
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_color_vbo.id());
 
         let bytes = (max_instances * 4 * std::mem::size_of::<GLfloat>()) as GLsizei;
         gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
@@ -231,10 +352,66 @@ impl Geometry {
         gl_bind_buffer(GL_ARRAY_BUFFER, 0);
     }
 
+    pub fn enable_instancing_transform(&mut self, max_instances: usize) {
+        if self.instance_transform_vbo.id() == 0 {
+            self.instance_transform_vbo = GpuBuffer::generate();
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_transform_vbo.id());
+
+        let bytes = (max_instances * 3 * std::mem::size_of::<GLfloat>()) as GLsizei;
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
+
+        // Attribute at location=4, vec3 (scale x, scale y, rotation), divisor=1
+        let transform_attr = Attribute::instanced_vec3(4);
+        gl_enable_vertex_attrib_array(transform_attr.location);
+        gl_vertex_attrib_pointer_float(
+            transform_attr.location,
+            transform_attr.size,
+            transform_attr.normalize,
+            transform_attr.stride,
+            transform_attr.offset,
+        );
+        gl_vertex_attrib_divisor(transform_attr.location, 1);
+
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
+    pub fn enable_instancing_uv(&mut self, max_instances: usize) {
+        if self.instance_uv_vbo.id() == 0 {
+            self.instance_uv_vbo = GpuBuffer::generate();
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_uv_vbo.id());
+
+        let bytes = (max_instances * 4 * std::mem::size_of::<GLfloat>()) as GLsizei;
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
+
+        // Attribute at location=3, vec4 (u, v, width, height), divisor=1
+        let uv_attr = Attribute::instanced_vec4(3);
+        gl_enable_vertex_attrib_array(uv_attr.location);
+        gl_vertex_attrib_pointer_float(
+            uv_attr.location,
+            uv_attr.size,
+            uv_attr.normalize,
+            uv_attr.stride,
+            uv_attr.offset,
+        );
+        gl_vertex_attrib_divisor(uv_attr.location, 1);
+
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
     pub fn update_instance_xy(&mut self, xy: &[Vec2]) {
-        if self.instance_vbo == 0 { return; }
-        gl_bind_vertex_array(self.vao);
-        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo);
+        if self.instance_vbo.id() == 0 { return; }
+        if self.instance_ring.is_some() {
+            self.write_instance_ring(xy.len(), |dst| dst.copy_from_slice(xy));
+            return;
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo.id());
 
         // orphan + upload
         let bytes = (xy.len() * std::mem::size_of::<Vec2>()) as GLsizei;
@@ -247,12 +424,76 @@ impl Geometry {
         self.instance_count = xy.len() as i32;
     }
 
+    /// Write `count` instance positions straight into the mapped instance
+    /// buffer, skipping the intermediate slice [`update_instance_xy`] copies
+    /// from. The buffer (or, when buffered, the next ring region) is
+    /// invalidated first, so `write` must set every element; the old
+    /// contents are not readable through the slice.
+    ///
+    /// Returns `None` if instancing is not enabled or the buffer cannot be
+    /// mapped (`write` is not called then), or if the driver lost the data
+    /// while it was mapped. The instance count is left unchanged in both
+    /// cases.
+    ///
+    /// [`update_instance_xy`]: Self::update_instance_xy
+    pub fn map_instance_buffer<R>(&mut self, count: usize, write: impl FnOnce(&mut [Vec2]) -> R) -> Option<R> {
+        if self.instance_vbo.id() == 0 {
+            return None;
+        }
+        if self.instance_ring.is_some() {
+            return self.write_instance_ring(count, write);
+        }
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo.id());
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, (count * std::mem::size_of::<Vec2>()) as GLsizeiptr);
+        let result = map_bound_vec2(0, count, GL_MAP_WRITE_BIT | GL_MAP_INVALIDATE_BUFFER_BIT, write);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+        if result.is_some() {
+            self.instance_count = count as i32;
+        }
+        result
+    }
+
+    /// Write `count` positions into the next ring region and point the
+    /// instance attribute at it.
+    fn write_instance_ring<R>(&mut self, count: usize, write: impl FnOnce(&mut [Vec2]) -> R) -> Option<R> {
+        let ring = self.instance_ring.as_ref()?;
+        if count > ring.capacity {
+            // Reallocating orphans the old storage, so pending draws keep
+            // reading it and the fences no longer apply.
+            let buffers = ring.fences.len();
+            self.enable_instancing_xy_buffered(count.next_power_of_two(), buffers);
+        }
+        let ring = self.instance_ring.as_mut()?;
+        // Everything reading the current region has been issued by now.
+        ring.fences[ring.current] = Some(GpuFence::insert());
+        ring.current = (ring.current + 1) % ring.fences.len();
+        if let Some(fence) = ring.fences[ring.current].take() {
+            fence.wait(RING_WAIT_TIMEOUT);
+        }
+        let offset = ring.current * ring.capacity * std::mem::size_of::<Vec2>();
+
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_vbo.id());
+        // The fence already guarantees the GPU is done with the region.
+        let access = GL_MAP_WRITE_BIT | GL_MAP_INVALIDATE_RANGE_BIT | GL_MAP_UNSYNCHRONIZED_BIT;
+        let result = map_bound_vec2(offset, count, access, write);
+        gl_bind_vertex_array(self.vao.id());
+        let inst_attr = Attribute::instanced_vec2(1);
+        gl_vertex_attrib_pointer_float(inst_attr.location, inst_attr.size, inst_attr.normalize, inst_attr.stride, offset as GLsizei);
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+
+        if result.is_some() {
+            self.instance_count = count as i32;
+        }
+        result
+    }
+
     pub fn update_instance_colors(&mut self, colors: &[Color]) {
-        if self.instance_color_vbo == 0 {
+        if self.instance_color_vbo.id() == 0 {
             self.enable_instancing_color(colors.len());
         }
-        gl_bind_vertex_array(self.vao);
-        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_color_vbo);
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_color_vbo.id());
 
         // orphan + upload (Color is #[repr(C)] with 4 f32 fields)
         let bytes = (colors.len() * std::mem::size_of::<Color>()) as GLsizei;
@@ -263,6 +504,40 @@ impl Geometry {
         gl_bind_buffer(GL_ARRAY_BUFFER, 0);
     }
 
+    pub fn update_instance_transforms(&mut self, transforms: &[InstanceTransform]) {
+        if self.instance_transform_vbo.id() == 0 {
+            self.enable_instancing_transform(transforms.len());
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_transform_vbo.id());
+
+        // orphan + upload (InstanceTransform is #[repr(C)] with 3 f32 fields)
+        let bytes = std::mem::size_of_val(transforms) as GLsizei;
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
+        gl_buffer_sub_data(GL_ARRAY_BUFFER, 0, transforms);
+
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
+    /// Per-instance texture sub-rectangles `[u, v, width, height]`, e.g.
+    /// sprites in a [`TextureAtlas`](crate::core::TextureAtlas).
+    pub fn update_instance_uv_rects(&mut self, rects: &[[f32; 4]]) {
+        if self.instance_uv_vbo.id() == 0 {
+            self.enable_instancing_uv(rects.len());
+        }
+        gl_bind_vertex_array(self.vao.id());
+        gl_bind_buffer(GL_ARRAY_BUFFER, self.instance_uv_vbo.id());
+
+        // orphan + upload
+        let bytes = std::mem::size_of_val(rects) as GLsizei;
+        gl_buffer_data_empty(GL_ARRAY_BUFFER, bytes as GLsizeiptr);
+        gl_buffer_sub_data(GL_ARRAY_BUFFER, 0, rects);
+
+        gl_bind_vertex_array(0);
+        gl_bind_buffer(GL_ARRAY_BUFFER, 0);
+    }
+
     pub fn clear_instancing(&mut self) {
         self.instance_count = 0;
         // keep instance_vbo for reuse
@@ -278,11 +553,33 @@ impl Geometry {
         self.vertex_count
     }
 
+    /// Floats per vertex in the vertex buffer, 0 before any is added.
+    pub fn values_per_vertex(&self) -> i32 {
+        self.values_per_vertex
+    }
+
     pub fn bind(&self) {
-        gl_bind_vertex_array(self.vao)
+        gl_bind_vertex_array(self.vao.id())
     }
 
     pub fn unbind(&self) {
         gl_bind_vertex_array(0)
     }
 }
+
+/// Map `count` Vec2s at byte `offset` of the bound `GL_ARRAY_BUFFER`, pass
+/// them to `write` and unmap. `None` if mapping failed or the data was lost.
+fn map_bound_vec2<R>(offset: usize, count: usize, access: GLbitfield, write: impl FnOnce(&mut [Vec2]) -> R) -> Option<R> {
+    if count == 0 {
+        return Some(write(&mut []));
+    }
+    let bytes = (count * std::mem::size_of::<Vec2>()) as GLsizeiptr;
+    // SAFETY: the mapping covers `count` Vec2s (#[repr(C)], two f32
+    // fields) and the slice does not outlive it.
+    let ptr = unsafe { gl_map_buffer_range(GL_ARRAY_BUFFER, offset as GLsizeiptr, bytes, access) };
+    if ptr.is_null() {
+        return None;
+    }
+    let result = write(unsafe { std::slice::from_raw_parts_mut(ptr as *mut Vec2, count) });
+    gl_unmap_buffer(GL_ARRAY_BUFFER).then_some(result)
+}
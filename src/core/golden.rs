@@ -0,0 +1,181 @@
+//! Comparing rendered images against reference ("golden") PNGs for
+//! regression tests.
+//!
+//! Render a scene into a [`Window::headless`](super::Window::headless)
+//! window, grab it with [`App::render_to_image`](super::App::render_to_image)
+//! or [`Renderer::read_framebuffer`](super::Renderer::read_framebuffer), and
+//! check it with [`check_golden`]:
+//!
+//! ```ignore
+//! #[test]
+//! fn shapes_scene() {
+//!     let window = Window::headless(800, 600, Color::from_rgb(0.07, 0.13, 0.17));
+//!     let mut app = App::new(window);
+//!     app.add_shapes(build_scene());
+//!     let image = app.render_to_image();
+//!     check_golden(&image, "tests/golden/shapes.png", &GoldenTolerance::default()).unwrap();
+//! }
+//! ```
+//!
+//! A missing golden file is created from the image, and so is every golden
+//! file when the `WILHELM_UPDATE_GOLDEN` environment variable is set, so
+//! references are refreshed by re-running the tests with it. On a mismatch
+//! the rendered image and a diff are written next to the golden file as
+//! `<name>.actual.png` and `<name>.diff.png`.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+/// Environment variable that makes [`check_golden`] overwrite golden files.
+pub const UPDATE_GOLDEN_ENV: &str = "WILHELM_UPDATE_GOLDEN";
+
+/// How far a rendered image may stray from its golden image. Drivers differ
+/// slightly in antialiasing and blending, so exact matches are rarely
+/// portable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    /// Largest per-channel difference (0-255) for a pixel to still match.
+    pub channel: u8,
+    /// Fraction of pixels (0-1) allowed to exceed `channel`.
+    pub max_mismatched: f32,
+}
+
+impl Default for GoldenTolerance {
+    /// Channels within 8 levels, up to 0.1% of pixels off.
+    fn default() -> Self {
+        Self { channel: 8, max_mismatched: 0.001 }
+    }
+}
+
+/// The outcome of [`compare_images`].
+pub struct ImageDiff {
+    /// Pixels with a channel differing by more than the tolerance.
+    pub mismatched: usize,
+    /// Largest channel difference over all pixels.
+    pub max_delta: u8,
+    /// The expected image faded to grey, with mismatched pixels in red.
+    pub diff: RgbaImage,
+}
+
+impl ImageDiff {
+    /// Share of mismatched pixels, in `0..=1`.
+    pub fn mismatched_fraction(&self) -> f32 {
+        let total = self.diff.width() as usize * self.diff.height() as usize;
+        if total == 0 { 0.0 } else { self.mismatched as f32 / total as f32 }
+    }
+
+    pub fn within(&self, tolerance: &GoldenTolerance) -> bool {
+        self.mismatched_fraction() <= tolerance.max_mismatched
+    }
+}
+
+/// Compare two images pixel by pixel. A pixel mismatches when any channel
+/// (including alpha) differs by more than `channel_tolerance`. Fails if the
+/// sizes differ.
+pub fn compare_images(actual: &RgbaImage, expected: &RgbaImage, channel_tolerance: u8) -> Result<ImageDiff, String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(format!(
+            "image size {:?} does not match expected size {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+    let mut mismatched = 0;
+    let mut max_delta = 0;
+    for ((a, e), d) in actual.pixels().zip(expected.pixels()).zip(diff.pixels_mut()) {
+        let delta = a.0.iter().zip(e.0).map(|(a, e)| a.abs_diff(e)).max().unwrap_or(0);
+        max_delta = max_delta.max(delta);
+        *d = if delta > channel_tolerance {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = e.0;
+            let grey = ((r as u32 * 3 + g as u32 * 6 + b as u32) / 10 / 3 + 170) as u8;
+            Rgba([grey, grey, grey, 255])
+        };
+    }
+    Ok(ImageDiff { mismatched, max_delta, diff })
+}
+
+/// Check `actual` against the golden PNG at `golden`.
+///
+/// Creates the golden file when it is missing or `WILHELM_UPDATE_GOLDEN` is
+/// set. On a mismatch writes `<name>.actual.png` and `<name>.diff.png` next
+/// to it and returns a message describing the difference.
+pub fn check_golden(actual: &RgbaImage, golden: impl AsRef<Path>, tolerance: &GoldenTolerance) -> Result<(), String> {
+    let golden = golden.as_ref();
+    if !golden.exists() || std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(dir) = golden.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        return actual.save(golden).map_err(|e| format!("{}: {e}", golden.display()));
+    }
+
+    let expected = image::open(golden).map_err(|e| format!("{}: {e}", golden.display()))?.to_rgba8();
+    let actual_path = sibling(golden, "actual");
+    let result = match compare_images(actual, &expected, tolerance.channel) {
+        Ok(diff) if diff.within(tolerance) => return Ok(()),
+        Ok(diff) => {
+            let diff_path = sibling(golden, "diff");
+            diff.diff.save(&diff_path).map_err(|e| format!("{}: {e}", diff_path.display()))?;
+            Err(format!(
+                "{}: {} pixels ({:.3}%) differ, max channel delta {}; see {} and {}",
+                golden.display(),
+                diff.mismatched,
+                diff.mismatched_fraction() * 100.0,
+                diff.max_delta,
+                actual_path.display(),
+                diff_path.display()
+            ))
+        }
+        Err(e) => Err(format!("{}: {e}; see {}", golden.display(), actual_path.display())),
+    };
+    actual.save(&actual_path).map_err(|e| format!("{}: {e}", actual_path.display()))?;
+    result
+}
+
+/// `dir/name.png` -> `dir/name.<suffix>.png`
+fn sibling(golden: &Path, suffix: &str) -> PathBuf {
+    let stem = golden.file_stem().unwrap_or_default().to_string_lossy();
+    golden.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn compare_counts_pixels_beyond_tolerance() {
+        let expected = filled(4, 4, 100);
+        let mut actual = filled(4, 4, 104);
+        actual.put_pixel(1, 2, Rgba([100, 160, 100, 255]));
+        let diff = compare_images(&actual, &expected, 4).unwrap();
+        assert_eq!((diff.mismatched, diff.max_delta), (1, 60));
+        assert_eq!(diff.diff.get_pixel(1, 2), &Rgba([255, 0, 0, 255]));
+        assert_eq!(diff.mismatched_fraction(), 1.0 / 16.0);
+        assert!(!diff.within(&GoldenTolerance::default()));
+        assert!(compare_images(&filled(4, 3, 0), &expected, 0).is_err());
+    }
+
+    #[test]
+    fn check_golden_creates_then_reports_mismatch() {
+        let dir = std::env::temp_dir().join(format!("wilhelm_golden_{}", std::process::id()));
+        let golden = dir.join("scene.png");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        check_golden(&filled(8, 8, 50), &golden, &GoldenTolerance::default()).unwrap();
+        assert!(golden.exists());
+        check_golden(&filled(8, 8, 52), &golden, &GoldenTolerance::default()).unwrap();
+
+        let err = check_golden(&filled(8, 8, 90), &golden, &GoldenTolerance::default()).unwrap_err();
+        assert!(err.contains("64 pixels"));
+        assert!(dir.join("scene.actual.png").exists() && dir.join("scene.diff.png").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
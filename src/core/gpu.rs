@@ -0,0 +1,196 @@
+//! Owned GL object names whose deletion is deferred to the GL thread.
+//!
+//! Dropping a handle never calls GL: it queues the name, and
+//! [`flush_deleted_resources`] deletes the queued objects on the thread
+//! that owns the context. [`App`](super::App) flushes at the start of each
+//! frame. Resources can therefore be dropped on any thread, or after the
+//! context is gone (the queued names are then simply never deleted).
+
+use std::sync::Mutex;
+
+use crate::core::engine::opengl::{
+    gl_client_wait_sync, gl_delete_buffer, gl_delete_framebuffer, gl_delete_sync, gl_delete_texture, gl_delete_vertex_array, gl_fence_sync, gl_gen_buffer,
+    gl_gen_framebuffer, gl_gen_texture, gl_gen_vertex_array, GLsync, GLuint, GL_SYNC_FLUSH_COMMANDS_BIT, GL_TIMEOUT_EXPIRED, GL_WAIT_FAILED,
+};
+
+enum Deleted {
+    Buffer(GLuint),
+    VertexArray(GLuint),
+    Texture(GLuint),
+    Framebuffer(GLuint),
+    /// A `GLsync`, kept as an address so the queue stays `Send`.
+    Sync(usize),
+}
+
+static DELETED: Mutex<Vec<Deleted>> = Mutex::new(Vec::new());
+
+fn delete_later(object: Deleted) {
+    // A poisoned queue only means another thread panicked mid-push.
+    DELETED.lock().unwrap_or_else(|e| e.into_inner()).push(object);
+}
+
+/// Delete the GL objects whose handles were dropped since the last call
+/// and return how many there were. Call on the GL thread with the context
+/// current; [`App`](super::App) does this every frame, so only custom
+/// render loops need to.
+pub fn flush_deleted_resources() -> usize {
+    let deleted = std::mem::take(&mut *DELETED.lock().unwrap_or_else(|e| e.into_inner()));
+    for object in &deleted {
+        match *object {
+            Deleted::Buffer(id) => gl_delete_buffer(id),
+            Deleted::VertexArray(id) => gl_delete_vertex_array(id),
+            Deleted::Texture(id) => gl_delete_texture(id),
+            Deleted::Framebuffer(id) => gl_delete_framebuffer(id),
+            // SAFETY: each fence is queued once, when its handle drops.
+            Deleted::Sync(sync) => unsafe { gl_delete_sync(sync as GLsync) },
+        }
+    }
+    deleted.len()
+}
+
+/// An owned GL buffer object. The default handle is the zero name and
+/// owns nothing, for buffers that are created lazily.
+#[derive(Debug, Default)]
+pub struct GpuBuffer(GLuint);
+
+impl GpuBuffer {
+    /// Generate a buffer name. Needs a current GL context.
+    pub fn generate() -> Self {
+        Self(gl_gen_buffer())
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.0
+    }
+}
+
+impl Drop for GpuBuffer {
+    fn drop(&mut self) {
+        if self.0 != 0 {
+            delete_later(Deleted::Buffer(self.0));
+        }
+    }
+}
+
+/// An owned GL vertex array object.
+#[derive(Debug)]
+pub struct GpuVertexArray(GLuint);
+
+impl GpuVertexArray {
+    /// Generate a vertex array name. Needs a current GL context.
+    pub fn generate() -> Self {
+        Self(gl_gen_vertex_array())
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.0
+    }
+}
+
+impl Drop for GpuVertexArray {
+    fn drop(&mut self) {
+        if self.0 != 0 {
+            delete_later(Deleted::VertexArray(self.0));
+        }
+    }
+}
+
+/// An owned GL texture.
+#[derive(Debug)]
+pub struct GpuTexture(GLuint);
+
+impl GpuTexture {
+    /// Generate a texture name. Needs a current GL context.
+    pub fn generate() -> Self {
+        Self(gl_gen_texture())
+    }
+
+    /// Take ownership of an existing texture, e.g. one returned by
+    /// [`generate_texture_from_image`](super::generate_texture_from_image).
+    pub fn from_raw(id: GLuint) -> Self {
+        Self(id)
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.0
+    }
+}
+
+impl Drop for GpuTexture {
+    fn drop(&mut self) {
+        if self.0 != 0 {
+            delete_later(Deleted::Texture(self.0));
+        }
+    }
+}
+
+/// An owned GL framebuffer object.
+#[derive(Debug)]
+pub struct GpuFramebuffer(GLuint);
+
+impl GpuFramebuffer {
+    /// Generate a framebuffer name. Needs a current GL context.
+    pub fn generate() -> Self {
+        Self(gl_gen_framebuffer())
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.0
+    }
+}
+
+impl Drop for GpuFramebuffer {
+    fn drop(&mut self) {
+        if self.0 != 0 {
+            delete_later(Deleted::Framebuffer(self.0));
+        }
+    }
+}
+
+/// An owned GL fence sync object.
+#[derive(Debug)]
+pub struct GpuFence(usize);
+
+impl GpuFence {
+    /// Fence the GL commands issued so far. Needs a current GL context.
+    pub fn insert() -> Self {
+        Self(gl_fence_sync() as usize)
+    }
+
+    /// Block until the fenced commands complete, or `timeout` nanoseconds
+    /// pass. Returns `false` on timeout or failure.
+    pub fn wait(&self, timeout: u64) -> bool {
+        // SAFETY: the fence lives until this handle drops.
+        let status = unsafe { gl_client_wait_sync(self.0 as GLsync, GL_SYNC_FLUSH_COMMANDS_BIT, timeout) };
+        status != GL_TIMEOUT_EXPIRED && status != GL_WAIT_FAILED
+    }
+}
+
+impl Drop for GpuFence {
+    fn drop(&mut self) {
+        if self.0 != 0 {
+            delete_later(Deleted::Sync(self.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn handles_cross_threads_and_queue_their_deletion() {
+        assert_send_sync::<GpuBuffer>();
+        assert_send_sync::<GpuVertexArray>();
+        assert_send_sync::<GpuTexture>();
+        assert_send_sync::<GpuFramebuffer>();
+        assert_send_sync::<GpuFence>();
+        // Named objects dropped off the GL thread only enter the queue;
+        // the zero name is never queued.
+        std::thread::spawn(|| drop((GpuTexture::from_raw(7), GpuTexture::from_raw(0)))).join().unwrap();
+        let queued = DELETED.lock().unwrap().iter().filter(|d| matches!(d, Deleted::Texture(7))).count();
+        assert_eq!(queued, 1);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::core::color::Color;
+
+/// Color space used when blending between two neighbouring stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GradientInterpolation {
+    /// Per-channel linear blend in RGB.
+    #[default]
+    Rgb,
+    /// Blend hue/saturation/value, taking the shorter way around the hue wheel.
+    Hsv,
+}
+
+/// An ordered list of color stops that can be sampled at any `t` in `0..=1`.
+///
+/// Used for gradient fills, heatmaps and data-driven styling:
+///
+/// ```ignore
+/// let heat = Gradient::new()
+///     .stop(0.0, Color::BLUE)
+///     .stop(0.5, Color::YELLOW)
+///     .stop(1.0, Color::RED);
+/// let c = heat.sample(0.75);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+    interpolation: GradientInterpolation,
+}
+
+impl Gradient {
+    /// An empty gradient. Sampling it returns transparent black.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Two-stop gradient from `start` (t = 0) to `end` (t = 1).
+    pub fn linear(start: Color, end: Color) -> Self {
+        Self::new().stop(0.0, start).stop(1.0, end)
+    }
+
+    /// Build a gradient from colors spaced evenly across `0..=1`.
+    pub fn evenly_spaced(colors: &[Color]) -> Self {
+        let n = colors.len();
+        let mut gradient = Self::new();
+        for (i, &color) in colors.iter().enumerate() {
+            let t = if n > 1 { i as f32 / (n - 1) as f32 } else { 0.0 };
+            gradient.add_stop(t, color);
+        }
+        gradient
+    }
+
+    /// Add a stop and return the gradient (builder style).
+    pub fn stop(mut self, t: f32, color: Color) -> Self {
+        self.add_stop(t, color);
+        self
+    }
+
+    /// Insert a stop at `t` (clamped to `0..=1`), keeping stops ordered.
+    /// A stop at the same position as an existing one is placed after it,
+    /// which produces a hard edge. A stop at NaN is ignored.
+    pub fn add_stop(&mut self, t: f32, color: Color) -> &mut Self {
+        if t.is_nan() {
+            return self;
+        }
+        let t = t.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|(s, _)| *s <= t);
+        self.stops.insert(index, (t, color));
+        self
+    }
+
+    /// Select the color space used between stops.
+    pub fn with_interpolation(mut self, interpolation: GradientInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn interpolation(&self) -> GradientInterpolation {
+        self.interpolation
+    }
+
+    /// The stops as `(position, color)` pairs in ascending order.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Sample the gradient at `t`. Values outside the first/last stop take
+    /// that stop's color, and NaN takes the first stop's.
+    pub fn sample(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Color::from_rgba(0.0, 0.0, 0.0, 0.0),
+        };
+        if t.is_nan() || t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let upper = self.stops.partition_point(|(s, _)| *s <= t);
+        let (t0, c0) = self.stops[upper - 1];
+        let (t1, c1) = self.stops[upper];
+        let span = t1 - t0;
+        let local = if span > 0.0 { (t - t0) / span } else { 0.0 };
+
+        match self.interpolation {
+            GradientInterpolation::Rgb => Color::lerp(c0, c1, local),
+            GradientInterpolation::Hsv => Color::lerp_hsv(c0, c1, local),
+        }
+    }
+
+    /// Sample `t` after normalising `value` from the `min..max` range.
+    pub fn sample_range(&self, value: f32, min: f32, max: f32) -> Color {
+        let t = if max > min { (value - min) / (max - min) } else { 0.0 };
+        self.sample(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(c: Color) -> (f32, f32, f32) {
+        (c.red_value(), c.green_value(), c.blue_value())
+    }
+
+    fn approx(a: (f32, f32, f32), b: (f32, f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3 && (a.2 - b.2).abs() < 1e-3
+    }
+
+    #[test]
+    fn stops_are_sorted_on_insert() {
+        let g = Gradient::new()
+            .stop(1.0, Color::white())
+            .stop(0.0, Color::black())
+            .stop(0.5, Color::red());
+        let ts: Vec<f32> = g.stops().iter().map(|(t, _)| *t).collect();
+        assert_eq!(ts, vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn sample_interpolates_between_stops() {
+        let g = Gradient::linear(Color::black(), Color::white());
+        assert!(approx(rgb(g.sample(0.25)), (0.25, 0.25, 0.25)));
+        assert!(approx(rgb(g.sample(-1.0)), (0.0, 0.0, 0.0)));
+        assert!(approx(rgb(g.sample(2.0)), (1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn evenly_spaced_hits_each_color() {
+        let g = Gradient::evenly_spaced(&[Color::red(), Color::green(), Color::blue()]);
+        assert!(approx(rgb(g.sample(0.5)), (0.0, 1.0, 0.0)));
+        assert!(approx(rgb(g.sample(1.0)), (0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn hsv_interpolation_keeps_saturation() {
+        let g = Gradient::linear(Color::red(), Color::green())
+            .with_interpolation(GradientInterpolation::Hsv);
+        // Halfway from red (0) to green (120) is yellow (60), not dark olive.
+        assert!(approx(rgb(g.sample(0.5)), (1.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn sample_range_normalises() {
+        let g = Gradient::linear(Color::black(), Color::white());
+        assert!(approx(rgb(g.sample_range(15.0, 10.0, 20.0)), (0.5, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn empty_gradient_is_transparent() {
+        assert_eq!(Gradient::new().sample(0.5).alpha(), 0.0);
+    }
+
+    #[test]
+    fn nan_samples_the_first_stop() {
+        let g = Gradient::new().stop(f32::NAN, Color::red()).stop(0.2, Color::black()).stop(1.0, Color::white());
+        assert_eq!(g.stops().len(), 2);
+        assert!(approx(rgb(g.sample(f32::NAN)), (0.0, 0.0, 0.0)));
+        assert!(approx(rgb(g.sample_range(f32::NAN, 0.0, 10.0)), (0.0, 0.0, 0.0)));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::core::engine::egl::EglContext;
+use crate::core::picking::PickBuffer;
+use crate::core::{flush_deleted_resources, Color, Renderer};
+use image::RgbaImage;
+
+/// A [`Renderer`] with no window, drawing into an offscreen framebuffer of
+/// a surfaceless EGL context. Needs no X11 or Wayland server, so it runs
+/// on GPU servers and in CI containers (with Mesa's llvmpipe when there is
+/// no GPU). Enabled with the `egl` feature, Linux only.
+///
+/// Draw each frame between [`begin_frame`](Self::begin_frame) and
+/// [`read_image`](Self::read_image). The context is current on the thread
+/// that created it; keep the renderer on that thread.
+///
+/// ```ignore
+/// let headless = HeadlessRenderer::new(800, 600)?;
+/// let renderer = headless.renderer();
+/// headless.begin_frame(Some(Color::from_rgb(0.07, 0.13, 0.17)));
+/// chart.render(renderer, &axes);
+/// headless.read_image().save("chart.png")?;
+/// ```
+pub struct HeadlessRenderer {
+    renderer: Renderer,
+    target: PickBuffer,
+    // Dropped last: the framebuffer is deleted while the context is current.
+    _context: EglContext,
+}
+
+impl HeadlessRenderer {
+    /// Create the context and a `width` x `height` framebuffer.
+    pub fn new(width: i32, height: i32) -> Result<Self, String> {
+        if width <= 0 || height <= 0 {
+            return Err(format!("invalid headless size {width}x{height}"));
+        }
+        let context = EglContext::new()?;
+        let renderer = context.with_loader(|loader| Renderer::from_current_context_with_loader(width, height, loader))?;
+        let target = PickBuffer::new(width, height);
+        target.bind();
+        Ok(Self { renderer, target, _context: context })
+    }
+
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        self.target.size()
+    }
+
+    /// Resize the framebuffer. Its contents are undefined until the next
+    /// frame is drawn.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        self.target.resize(width.max(1), height.max(1));
+        self.renderer.resize(width.max(1), height.max(1));
+    }
+
+    /// Bind the framebuffer (picking leaves the default one bound), set
+    /// the viewport and clear to `clear`, if given. Also deletes the GL
+    /// objects dropped since the last frame.
+    pub fn begin_frame(&self, clear: Option<Color>) {
+        flush_deleted_resources();
+        self.target.bind();
+        self.renderer.begin_frame(clear);
+    }
+
+    /// The frame drawn since [`begin_frame`](Self::begin_frame), as a
+    /// top-down RGBA image.
+    pub fn read_image(&self) -> RgbaImage {
+        self.target.bind();
+        self.renderer.read_framebuffer()
+    }
+
+    /// Save the frame drawn since [`begin_frame`](Self::begin_frame) as a
+    /// PNG; see [`save_png`](crate::core::save_png).
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        crate::core::save_png(&self.read_image(), path)
+    }
+}
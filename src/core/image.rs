@@ -1,5 +1,7 @@
 use image::{ImageReader};
 
+use crate::core::WilhelmError;
+
 
 // core/image.rs
 pub struct Image {
@@ -9,19 +11,27 @@ pub struct Image {
 }
 
 
+/// Load an image file as RGBA8. Panics if it cannot be opened or decoded;
+/// see [`try_load_image`].
 pub fn load_image(path: &str) -> Image {
+    try_load_image(path).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Load an image file as RGBA8.
+pub fn try_load_image(path: &str) -> Result<Image, WilhelmError> {
+    let error = |message: String| WilhelmError::Image { path: path.to_string(), message };
     let img = ImageReader::open(path)
-        .expect("Failed to open image")
+        .map_err(|e| error(e.to_string()))?
         .decode()
-        .expect("Failed to decode image")
+        .map_err(|e| error(e.to_string()))?
         .to_rgba8();
 
     let (width, height) = img.dimensions();
     let pixels = img.into_raw();
 
-    Image {
+    Ok(Image {
         width,
         height,
         pixels,
-    }
-}
\ No newline at end of file
+    })
+}
@@ -0,0 +1,173 @@
+//! Recording window input to a file and replaying it later.
+//!
+//! [`App::record_input`](super::App::record_input) captures every cursor,
+//! scroll, key and mouse button event together with the frame it was
+//! delivered in; [`App::replay_input`](super::App::replay_input) feeds a
+//! recording back through the window callbacks on the same frames. With a
+//! fixed time step on the app's [`Clock`](super::Clock) this reproduces a
+//! session exactly, which is handy for interaction bug reports and
+//! scripted demos.
+//!
+//! The file format is plain text, one event per line:
+//!
+//! ```text
+//! # wilhelm input recording v1
+//! 12 cursor 310.5 244
+//! 12 button 0 1 0
+//! 40 key 32 65 1 0
+//! 41 scroll 0 -1
+//! ```
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+const HEADER: &str = "# wilhelm input recording v1";
+
+/// A window input event, with the arguments of the matching
+/// [`Window`](super::Window) callback.
+///
+/// Window resizes are not recorded: replay cannot resize the OS window, so
+/// replay into a window of the recorded size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    CursorPosition { x: f64, y: f64 },
+    Scroll { x_offset: f64, y_offset: f64 },
+    Key { key: i32, scancode: i32, action: i32, mods: i32 },
+    MouseButton { button: i32, action: i32, mods: i32 },
+}
+
+/// An event and the frame (counted from the start of `App::run`) it was
+/// delivered in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordedInput {
+    pub frame: u64,
+    pub event: InputEvent,
+}
+
+/// A sequence of recorded input events in frame order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    events: Vec<RecordedInput>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event. Frames must not decrease.
+    pub fn push(&mut self, frame: u64, event: InputEvent) {
+        debug_assert!(self.events.last().is_none_or(|e| e.frame <= frame));
+        self.events.push(RecordedInput { frame, event });
+    }
+
+    pub fn events(&self) -> &[RecordedInput] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Frame of the last event, or `None` if empty.
+    pub fn last_frame(&self) -> Option<u64> {
+        self.events.last().map(|e| e.frame)
+    }
+
+    /// The events delivered in `frame`.
+    pub fn frame_events(&self, frame: u64) -> &[RecordedInput] {
+        let start = self.events.partition_point(|e| e.frame < frame);
+        let end = self.events.partition_point(|e| e.frame <= frame);
+        &self.events[start..end]
+    }
+
+    /// Serialise to the text format described in the module docs.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(HEADER);
+        text.push('\n');
+        for RecordedInput { frame, event } in &self.events {
+            let _ = match *event {
+                InputEvent::CursorPosition { x, y } => writeln!(text, "{frame} cursor {x} {y}"),
+                InputEvent::Scroll { x_offset, y_offset } => writeln!(text, "{frame} scroll {x_offset} {y_offset}"),
+                InputEvent::Key { key, scancode, action, mods } => writeln!(text, "{frame} key {key} {scancode} {action} {mods}"),
+                InputEvent::MouseButton { button, action, mods } => writeln!(text, "{frame} button {button} {action} {mods}"),
+            };
+        }
+        text
+    }
+
+    /// Parse the text format. Blank lines and `#` comments are skipped.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut recording = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (frame, event) = parse_line(line).ok_or_else(|| format!("line {}: invalid input event `{line}`", number + 1))?;
+            if recording.last_frame().is_some_and(|last| frame < last) {
+                return Err(format!("line {}: frame {frame} is out of order", number + 1));
+            }
+            recording.push(frame, event);
+        }
+        Ok(recording)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_text(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn parse_line(line: &str) -> Option<(u64, InputEvent)> {
+    let mut fields = line.split_whitespace();
+    let frame = fields.next()?.parse().ok()?;
+    let kind = fields.next()?;
+    let args: Vec<&str> = fields.collect();
+    let f = |i: usize| args.get(i)?.parse::<f64>().ok();
+    let n = |i: usize| args.get(i)?.parse::<i32>().ok();
+    let (event, arity) = match kind {
+        "cursor" => (InputEvent::CursorPosition { x: f(0)?, y: f(1)? }, 2),
+        "scroll" => (InputEvent::Scroll { x_offset: f(0)?, y_offset: f(1)? }, 2),
+        "key" => (InputEvent::Key { key: n(0)?, scancode: n(1)?, action: n(2)?, mods: n(3)? }, 4),
+        "button" => (InputEvent::MouseButton { button: n(0)?, action: n(1)?, mods: n(2)? }, 3),
+        _ => return None,
+    };
+    (args.len() == arity).then_some((frame, event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trips() {
+        let mut recording = InputRecording::new();
+        recording.push(3, InputEvent::CursorPosition { x: 310.5, y: 0.1 });
+        recording.push(3, InputEvent::MouseButton { button: 0, action: 1, mods: 1 });
+        recording.push(9, InputEvent::Key { key: 32, scancode: 65, action: 0, mods: 0 });
+        recording.push(10, InputEvent::Scroll { x_offset: 0.0, y_offset: -1.5 });
+        let parsed = InputRecording::from_text(&recording.to_text()).unwrap();
+        assert_eq!(parsed, recording);
+        assert_eq!(parsed.frame_events(3).len(), 2);
+        assert!(parsed.frame_events(4).is_empty());
+        assert_eq!(parsed.last_frame(), Some(10));
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        assert!(InputRecording::from_text("1 key 32 65 1").is_err());
+        assert!(InputRecording::from_text("1 wheel 0 1").is_err());
+        let err = InputRecording::from_text("# c\n\n5 scroll 0 1\n2 scroll 0 1").unwrap_err();
+        assert!(err.starts_with("line 4"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Shape layers used by [`App::add_layer`](super::App::add_layer) and
+//! [`App::add_shape_to_layer`](super::App::add_shape_to_layer).
+
+use std::collections::HashMap;
+
+/// Handle returned by [`App::add_layer`](super::App::add_layer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(usize);
+
+struct Layer {
+    z_index: i32,
+    visible: bool,
+}
+
+/// Layers and the shape indices assigned to them.
+///
+/// Shapes not assigned to a layer belong to an implicit base layer with
+/// z-index 0 that is always shown. Layers draw in z-index order, shapes
+/// within a layer by their own z-order, and ties in insertion order.
+pub(crate) struct Layers {
+    layers: Vec<Layer>,
+    shapes: HashMap<usize, LayerId>,
+}
+
+impl Layers {
+    pub(crate) fn new() -> Self {
+        Self { layers: Vec::new(), shapes: HashMap::new() }
+    }
+
+    pub(crate) fn add(&mut self, z_index: i32) -> LayerId {
+        self.layers.push(Layer { z_index, visible: true });
+        LayerId(self.layers.len() - 1)
+    }
+
+    pub(crate) fn set_z_index(&mut self, layer: LayerId, z_index: i32) {
+        self.layers[layer.0].z_index = z_index;
+    }
+
+    pub(crate) fn set_visible(&mut self, layer: LayerId, visible: bool) {
+        self.layers[layer.0].visible = visible;
+    }
+
+    pub(crate) fn is_visible(&self, layer: LayerId) -> bool {
+        self.layers[layer.0].visible
+    }
+
+    /// Move the shape at `index` into `layer`, or back to the base layer.
+    pub(crate) fn assign(&mut self, index: usize, layer: Option<LayerId>) {
+        match layer {
+            Some(layer) => self.shapes.insert(index, layer),
+            None => self.shapes.remove(&index),
+        };
+    }
+
+    pub(crate) fn layer_of(&self, index: usize) -> Option<LayerId> {
+        self.shapes.get(&index).copied()
+    }
+
+    /// Indices of the shapes in `layer`, in insertion order.
+    pub(crate) fn shapes_in(&self, layer: LayerId) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.shapes.iter().filter(|(_, &l)| l == layer).map(|(&i, _)| i).collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Forget the removed shape at `index` and shift later ones down.
+    pub(crate) fn remove_shape(&mut self, index: usize) {
+        self.shapes = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .filter(|&(i, _)| i != index)
+            .map(|(i, layer)| (if i > index { i - 1 } else { i }, layer))
+            .collect();
+    }
+
+    /// Whether the layer holding the shape at `index` is shown.
+    pub(crate) fn shows(&self, index: usize) -> bool {
+        self.layer_of(index).is_none_or(|layer| self.is_visible(layer))
+    }
+
+    /// Fill `order` with the indices of the `count` shapes in shown layers,
+    /// sorted by layer z-index and then by `z_order`.
+    pub(crate) fn draw_order(&self, count: usize, z_order: impl Fn(usize) -> i32, order: &mut Vec<usize>) {
+        order.clear();
+        order.extend((0..count).filter(|&i| self.shows(i)));
+        let z_index = |i: usize| self.layer_of(i).map_or(0, |layer| self.layers[layer.0].z_index);
+        order.sort_by_key(|&i| (z_index(i), z_order(i)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(layers: &Layers, z_orders: &[i32]) -> Vec<usize> {
+        let mut order = Vec::new();
+        layers.draw_order(z_orders.len(), |i| z_orders[i], &mut order);
+        order
+    }
+
+    #[test]
+    fn layers_sort_before_shape_z_order() {
+        let mut layers = Layers::new();
+        let overlay = layers.add(10);
+        let base_map = layers.add(-1);
+        layers.assign(0, Some(overlay));
+        layers.assign(2, Some(base_map));
+        layers.assign(3, Some(base_map));
+        // Shape 3 outranks shape 1 by z-order, but its layer is below.
+        assert_eq!(order(&layers, &[0, 0, 0, 5]), vec![2, 3, 1, 0]);
+
+        layers.set_z_index(base_map, 20);
+        assert_eq!(order(&layers, &[0, 0, 0, 5]), vec![1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn hidden_layers_are_skipped_and_removal_shifts_indices() {
+        let mut layers = Layers::new();
+        let labels = layers.add(0);
+        layers.assign(1, Some(labels));
+        layers.assign(3, Some(labels));
+        layers.set_visible(labels, false);
+        assert_eq!(order(&layers, &[0; 4]), vec![0, 2]);
+
+        layers.remove_shape(1);
+        assert_eq!(layers.shapes_in(labels), vec![2]);
+        assert!(layers.shows(0) && layers.shows(1) && !layers.shows(2));
+    }
+}
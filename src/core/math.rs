@@ -2,8 +2,8 @@
 ///
 /// Stored as `[f32; 16]` in column-major order, matching OpenGL's expected layout.
 /// This replaces the `glam::Mat4` dependency with the subset of operations
-/// actually used by this crate: identity, orthographic projection, translation,
-/// rotation around Z, and scaling.
+/// actually used by this crate: identity, orthographic and perspective
+/// projection, look-at views, translation, rotation and scaling.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Mat4 {
     /// Column-major storage: columns[0] = first column, etc.
@@ -35,6 +35,37 @@ impl Mat4 {
         }
     }
 
+    /// Create a right-handed perspective projection with OpenGL depth range
+    /// [-1, 1]. `fov_y` is the vertical field of view in radians.
+    pub fn perspective_rh_gl(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fov_y * 0.5).tan();
+        let nmf = near - far;
+        Self {
+            cols: [
+                f / aspect, 0.0, 0.0,                      0.0,
+                0.0,        f,   0.0,                      0.0,
+                0.0,        0.0, (far + near) / nmf,      -1.0,
+                0.0,        0.0, 2.0 * far * near / nmf,   0.0,
+            ],
+        }
+    }
+
+    /// Create a right-handed view matrix for an eye at `eye` looking at
+    /// `target`, with `up` pointing roughly up.
+    pub fn look_at_rh(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        let f = (target - eye).normalize();
+        let s = f.cross(up).normalize();
+        let u = s.cross(f);
+        Self {
+            cols: [
+                s.x,          u.x,          -f.x,        0.0,
+                s.y,          u.y,          -f.y,        0.0,
+                s.z,          u.z,          -f.z,        0.0,
+                -s.dot(eye), -u.dot(eye),   f.dot(eye),  1.0,
+            ],
+        }
+    }
+
     /// Create a translation matrix.
     pub fn from_translation(x: f32, y: f32, z: f32) -> Self {
         Self {
@@ -60,6 +91,32 @@ impl Mat4 {
         }
     }
 
+    /// Create a rotation matrix around the X axis (angle in radians).
+    pub fn from_rotation_x(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self {
+            cols: [
+                1.0, 0.0, 0.0, 0.0,
+                0.0, c,   s,   0.0,
+                0.0, -s,  c,   0.0,
+                0.0, 0.0, 0.0, 1.0,
+            ],
+        }
+    }
+
+    /// Create a rotation matrix around the Y axis (angle in radians).
+    pub fn from_rotation_y(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self {
+            cols: [
+                c,   0.0, -s,  0.0,
+                0.0, 1.0, 0.0, 0.0,
+                s,   0.0, c,   0.0,
+                0.0, 0.0, 0.0, 1.0,
+            ],
+        }
+    }
+
     /// Create a scale matrix.
     pub fn from_scale(x: f32, y: f32, z: f32) -> Self {
         Self {
@@ -72,11 +129,37 @@ impl Mat4 {
         }
     }
 
+    /// Transform a point (w = 1), dividing by the resulting w.
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let c = &self.cols;
+        let x = c[0] * p.x + c[4] * p.y + c[8] * p.z + c[12];
+        let y = c[1] * p.x + c[5] * p.y + c[9] * p.z + c[13];
+        let z = c[2] * p.x + c[6] * p.y + c[10] * p.z + c[14];
+        let w = c[3] * p.x + c[7] * p.y + c[11] * p.z + c[15];
+        Vec3::new(x / w, y / w, z / w)
+    }
+
+    /// Transform a direction (w = 0), ignoring translation.
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let c = &self.cols;
+        Vec3::new(
+            c[0] * v.x + c[4] * v.y + c[8] * v.z,
+            c[1] * v.x + c[5] * v.y + c[9] * v.z,
+            c[2] * v.x + c[6] * v.y + c[10] * v.z,
+        )
+    }
+
     /// Return a pointer to the column-major data, for passing to OpenGL.
     #[inline]
     pub fn as_ptr(&self) -> *const f32 {
         self.cols.as_ptr()
     }
+
+    /// The column-major data.
+    #[inline]
+    pub fn as_array(&self) -> &[f32; 16] {
+        &self.cols
+    }
 }
 
 impl std::ops::Mul for Mat4 {
@@ -101,6 +184,83 @@ impl std::ops::Mul for Mat4 {
     }
 }
 
+/// A 3D vector, for positions, directions and normals.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    pub const ZERO: Self = Self::new(0.0, 0.0, 0.0);
+    pub const X: Self = Self::new(1.0, 0.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0, 0.0);
+    pub const Z: Self = Self::new(0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, rhs: Self) -> f32 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// The unit vector in the same direction, or zero for a zero vector.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length > 0.0 { self * (1.0 / length) } else { Self::ZERO }
+    }
+
+    pub fn to_array(self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl std::ops::Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl std::ops::Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::ops::Mul<f32> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl std::ops::Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,4 +313,29 @@ mod tests {
         assert!((s.cols[5] - 3.0).abs() < 1e-6);
         assert!((s.cols[10] - 1.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn perspective_maps_near_and_far_planes() {
+        let p = Mat4::perspective_rh_gl(std::f32::consts::FRAC_PI_2, 1.0, 1.0, 10.0);
+        assert!((p.transform_point(Vec3::new(0.0, 0.0, -1.0)).z + 1.0).abs() < 1e-5);
+        assert!((p.transform_point(Vec3::new(0.0, 0.0, -10.0)).z - 1.0).abs() < 1e-5);
+        // 90° fov: a point at 45° up lands on the top edge
+        assert!((p.transform_point(Vec3::new(0.0, 2.0, -2.0)).y - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn look_at_moves_target_onto_negative_z() {
+        let view = Mat4::look_at_rh(Vec3::new(3.0, 0.0, 0.0), Vec3::ZERO, Vec3::Y);
+        let target = view.transform_point(Vec3::ZERO);
+        assert!(target.x.abs() < 1e-5 && target.y.abs() < 1e-5);
+        assert!((target.z + 3.0).abs() < 1e-5);
+        let up = view.transform_vector(Vec3::Y);
+        assert!((up.y - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn vec3_cross_follows_right_hand_rule() {
+        assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(Vec3::new(0.0, 3.0, 4.0).normalize().length(), 1.0);
+    }
 }
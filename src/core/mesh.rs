@@ -13,9 +13,15 @@ pub struct Mesh {
     screen_offset: Option<(f32, f32)>,
     scale: f32,
     rotation: f32,
+    pivot: (f32, f32),
+    opacity: f32,
     pub color: Option<Color>,
     pub texture: Option<GLuint>,
     pub dash_pattern: Option<(f32, f32)>,
+    /// Dash pattern shift in pixels, plus `dash_speed` pixels per second of
+    /// frame-clock time. Only used with `dash_pattern`.
+    pub dash_offset: f32,
+    pub dash_speed: f32,
 }
 
 impl Mesh {
@@ -28,9 +34,13 @@ impl Mesh {
             screen_offset: None,
             scale: 1.0,
             rotation: 0.0,
+            pivot: (0.0, 0.0),
+            opacity: 1.0,
             color: None,
             texture: None,
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
         }
     }
 
@@ -42,9 +52,13 @@ impl Mesh {
             screen_offset: None,
             scale: 1.0,
             rotation: 0.0,
+            pivot: (0.0, 0.0),
+            opacity: 1.0,
             color,
             texture: None,
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
         }
     }
 
@@ -56,9 +70,13 @@ impl Mesh {
             screen_offset: None,
             scale: 1.0,
             rotation: 0.0,
+            pivot: (0.0, 0.0),
+            opacity: 1.0,
             color: None,
             texture,
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
         }
     }
 
@@ -96,4 +114,56 @@ impl Mesh {
     pub fn rotation(&self) -> f32 {
         self.rotation
     }
+
+    /// Point, in mesh coordinates, that rotation and scale keep in place.
+    /// Defaults to the origin.
+    pub fn set_pivot(&mut self, x: f32, y: f32) {
+        self.pivot = (x, y);
+    }
+    pub fn pivot(&self) -> (f32, f32) {
+        self.pivot
+    }
+
+    /// Translation the renderer adds to the screen offset so that rotating
+    /// and scaling around the origin, as the shaders do, leaves the pivot
+    /// where it was.
+    pub fn pivot_translation(&self) -> (f32, f32) {
+        pivot_translation(self.pivot, self.scale, self.rotation)
+    }
+
+    /// Multiplier applied to the final fragment alpha (`u_opacity`).
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+}
+
+/// `pivot - R(rotation) * scale * pivot`: the shift that turns a rotation
+/// and scale around the origin into one around `pivot`.
+pub(crate) fn pivot_translation(pivot: (f32, f32), scale: f32, rotation: f32) -> (f32, f32) {
+    let (px, py) = pivot;
+    let (sin_r, cos_r) = rotation.sin_cos();
+    (
+        px - (px * cos_r - py * sin_r) * scale,
+        py - (px * sin_r + py * cos_r) * scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pivot_stays_in_place() {
+        let pivot = (30.0, 20.0);
+        let (scale, rotation) = (2.0, 1.1);
+        let (tx, ty) = pivot_translation(pivot, scale, rotation);
+        let (sin_r, cos_r) = rotation.sin_cos();
+        let x = (pivot.0 * cos_r - pivot.1 * sin_r) * scale + tx;
+        let y = (pivot.0 * sin_r + pivot.1 * cos_r) * scale + ty;
+        assert!((x - pivot.0).abs() < 1e-4 && (y - pivot.1).abs() < 1e-4);
+        assert_eq!(pivot_translation((0.0, 0.0), scale, rotation), (0.0, 0.0));
+    }
 }
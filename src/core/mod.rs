@@ -2,30 +2,88 @@ mod geometry;
 pub mod math;
 mod mesh;
 mod renderer;
+mod backend;
 mod shader;
 mod window;
 mod app;
 mod color;
+mod named_colors;
+mod gradient;
+mod colormap;
 mod texture;
 mod image;
 pub mod engine;
 mod font;
+mod assets;
+mod gpu;
+mod pixel_buffer;
+mod context_info;
 mod camera;
+mod picking;
+mod render_target;
+mod texture_atlas;
+mod scene_graph;
+mod spatial;
+mod snap;
+mod snapshot;
+mod timer;
+mod layer;
+mod animation;
+mod clock;
+mod commands;
+mod input_record;
+mod recorder;
+mod golden;
+mod error;
+#[cfg(feature = "serde")]
+mod scene;
+#[cfg(all(feature = "egl", target_os = "linux"))]
+mod headless;
 
-pub use self::font::{FontAtlas, GlyphInfo};
+pub use self::font::{FontAtlas, FontData, GlyphInfo};
+pub(crate) use self::font::check_font;
+pub use self::assets::{Asset, AssetId, AssetLoader};
+pub use self::pixel_buffer::{FramebufferReadback, StreamingTexture};
+pub use self::gpu::{flush_deleted_resources, GpuBuffer, GpuFence, GpuFramebuffer, GpuTexture, GpuVertexArray};
 pub use self::geometry::Attribute;
 pub use self::geometry::Geometry;
+pub use self::geometry::InstanceTransform;
 pub use self::engine::opengl::Vec2;
+pub use self::engine::leaks::report_leaks;
 pub use self::mesh::Mesh;
+pub(crate) use self::mesh::pivot_translation;
 pub use self::renderer::Renderer;
+pub use self::context_info::ContextInfo;
 pub use self::renderer::Renderable;
+pub use self::picking::PickId;
+pub use self::render_target::RenderTarget;
+pub use self::texture_atlas::{AtlasRegion, SpriteId, TextureAtlas, TextureAtlasBuilder};
+pub use self::scene_graph::{Node, NodeId, SceneGraph};
+pub use self::spatial::{Bounds, SpatialIndex};
+pub use self::snap::{Snap, Snapper};
+pub use self::snapshot::InstanceSnapshots;
+pub use self::timer::TimerId;
+pub use self::layer::LayerId;
+pub use self::animation::{Animatable, Animation, Easing, Track};
+pub use self::clock::Clock;
+pub use self::commands::{CommandBuffer, CommandQueue};
+pub use self::input_record::{InputEvent, InputRecording, RecordedInput};
+pub use self::recorder::{save_png, FrameRecorder};
+#[cfg(feature = "serde")]
+pub use self::scene::{Scene, SceneShape};
+pub use self::golden::{check_golden, compare_images, GoldenTolerance, ImageDiff, UPDATE_GOLDEN_ENV};
+#[cfg(all(feature = "egl", target_os = "linux"))]
+pub use self::headless::HeadlessRenderer;
 pub use self::shader::Shader;
-pub use self::window::Window;
+pub use self::error::WilhelmError;
+pub use self::window::{PixelUnits, Window};
 pub use self::app::App;
 pub use self::color::Color;
-pub use texture::generate_texture_from_image;
-pub use image::load_image;
-pub use self::math::Mat4;
+pub use self::gradient::{Gradient, GradientInterpolation};
+pub use self::colormap::ColorMap;
+pub use texture::{generate_texture_from_image, generate_texture_from_image_with_options, generate_texture_from_rgba, TextureFilter, TextureOptions, TextureWrap};
+pub use image::{load_image, try_load_image};
+pub use self::math::{Mat4, Vec3};
 pub use self::camera::{Projection, IdentityProjection, Camera2D, CameraController};
 pub use self::engine::glfw::GLFWwindow;
 pub use self::engine::glfw::{GLFW_MOUSE_BUTTON_LEFT, GLFW_MOUSE_BUTTON_RIGHT, GLFW_MOUSE_BUTTON_MIDDLE};
@@ -0,0 +1,369 @@
+//! Standard CSS / X11 named colors.
+//!
+//! Exposes the 148 CSS Color Module Level 4 keywords as associated constants
+//! on [`Color`] (`Color::STEEL_BLUE`) and a case-insensitive lookup via
+//! [`Color::by_name`].
+//!
+//! Note that CSS `GREEN` is `#008000`; the full-intensity green returned by
+//! [`Color::green`] is CSS `LIME`.
+
+use crate::core::color::Color;
+
+impl Color {
+    pub const ALICE_BLUE: Color = Color::from_rgb8(240, 248, 255);
+    pub const ANTIQUE_WHITE: Color = Color::from_rgb8(250, 235, 215);
+    pub const AQUA: Color = Color::from_rgb8(0, 255, 255);
+    pub const AQUAMARINE: Color = Color::from_rgb8(127, 255, 212);
+    pub const AZURE: Color = Color::from_rgb8(240, 255, 255);
+    pub const BEIGE: Color = Color::from_rgb8(245, 245, 220);
+    pub const BISQUE: Color = Color::from_rgb8(255, 228, 196);
+    pub const BLACK: Color = Color::from_rgb8(0, 0, 0);
+    pub const BLANCHED_ALMOND: Color = Color::from_rgb8(255, 235, 205);
+    pub const BLUE: Color = Color::from_rgb8(0, 0, 255);
+    pub const BLUE_VIOLET: Color = Color::from_rgb8(138, 43, 226);
+    pub const BROWN: Color = Color::from_rgb8(165, 42, 42);
+    pub const BURLYWOOD: Color = Color::from_rgb8(222, 184, 135);
+    pub const CADET_BLUE: Color = Color::from_rgb8(95, 158, 160);
+    pub const CHARTREUSE: Color = Color::from_rgb8(127, 255, 0);
+    pub const CHOCOLATE: Color = Color::from_rgb8(210, 105, 30);
+    pub const CORAL: Color = Color::from_rgb8(255, 127, 80);
+    pub const CORNFLOWER_BLUE: Color = Color::from_rgb8(100, 149, 237);
+    pub const CORNSILK: Color = Color::from_rgb8(255, 248, 220);
+    pub const CRIMSON: Color = Color::from_rgb8(220, 20, 60);
+    pub const CYAN: Color = Color::from_rgb8(0, 255, 255);
+    pub const DARK_BLUE: Color = Color::from_rgb8(0, 0, 139);
+    pub const DARK_CYAN: Color = Color::from_rgb8(0, 139, 139);
+    pub const DARK_GOLDENROD: Color = Color::from_rgb8(184, 134, 11);
+    pub const DARK_GRAY: Color = Color::from_rgb8(169, 169, 169);
+    pub const DARK_GREEN: Color = Color::from_rgb8(0, 100, 0);
+    pub const DARK_GREY: Color = Color::from_rgb8(169, 169, 169);
+    pub const DARK_KHAKI: Color = Color::from_rgb8(189, 183, 107);
+    pub const DARK_MAGENTA: Color = Color::from_rgb8(139, 0, 139);
+    pub const DARK_OLIVE_GREEN: Color = Color::from_rgb8(85, 107, 47);
+    pub const DARK_ORANGE: Color = Color::from_rgb8(255, 140, 0);
+    pub const DARK_ORCHID: Color = Color::from_rgb8(153, 50, 204);
+    pub const DARK_RED: Color = Color::from_rgb8(139, 0, 0);
+    pub const DARK_SALMON: Color = Color::from_rgb8(233, 150, 122);
+    pub const DARK_SEA_GREEN: Color = Color::from_rgb8(143, 188, 143);
+    pub const DARK_SLATE_BLUE: Color = Color::from_rgb8(72, 61, 139);
+    pub const DARK_SLATE_GRAY: Color = Color::from_rgb8(47, 79, 79);
+    pub const DARK_SLATE_GREY: Color = Color::from_rgb8(47, 79, 79);
+    pub const DARK_TURQUOISE: Color = Color::from_rgb8(0, 206, 209);
+    pub const DARK_VIOLET: Color = Color::from_rgb8(148, 0, 211);
+    pub const DEEP_PINK: Color = Color::from_rgb8(255, 20, 147);
+    pub const DEEP_SKY_BLUE: Color = Color::from_rgb8(0, 191, 255);
+    pub const DIM_GRAY: Color = Color::from_rgb8(105, 105, 105);
+    pub const DIM_GREY: Color = Color::from_rgb8(105, 105, 105);
+    pub const DODGER_BLUE: Color = Color::from_rgb8(30, 144, 255);
+    pub const FIREBRICK: Color = Color::from_rgb8(178, 34, 34);
+    pub const FLORAL_WHITE: Color = Color::from_rgb8(255, 250, 240);
+    pub const FOREST_GREEN: Color = Color::from_rgb8(34, 139, 34);
+    pub const FUCHSIA: Color = Color::from_rgb8(255, 0, 255);
+    pub const GAINSBORO: Color = Color::from_rgb8(220, 220, 220);
+    pub const GHOST_WHITE: Color = Color::from_rgb8(248, 248, 255);
+    pub const GOLD: Color = Color::from_rgb8(255, 215, 0);
+    pub const GOLDENROD: Color = Color::from_rgb8(218, 165, 32);
+    pub const GRAY: Color = Color::from_rgb8(128, 128, 128);
+    pub const GREEN: Color = Color::from_rgb8(0, 128, 0);
+    pub const GREEN_YELLOW: Color = Color::from_rgb8(173, 255, 47);
+    pub const GREY: Color = Color::from_rgb8(128, 128, 128);
+    pub const HONEYDEW: Color = Color::from_rgb8(240, 255, 240);
+    pub const HOT_PINK: Color = Color::from_rgb8(255, 105, 180);
+    pub const INDIAN_RED: Color = Color::from_rgb8(205, 92, 92);
+    pub const INDIGO: Color = Color::from_rgb8(75, 0, 130);
+    pub const IVORY: Color = Color::from_rgb8(255, 255, 240);
+    pub const KHAKI: Color = Color::from_rgb8(240, 230, 140);
+    pub const LAVENDER: Color = Color::from_rgb8(230, 230, 250);
+    pub const LAVENDER_BLUSH: Color = Color::from_rgb8(255, 240, 245);
+    pub const LAWN_GREEN: Color = Color::from_rgb8(124, 252, 0);
+    pub const LEMON_CHIFFON: Color = Color::from_rgb8(255, 250, 205);
+    pub const LIGHT_BLUE: Color = Color::from_rgb8(173, 216, 230);
+    pub const LIGHT_CORAL: Color = Color::from_rgb8(240, 128, 128);
+    pub const LIGHT_CYAN: Color = Color::from_rgb8(224, 255, 255);
+    pub const LIGHT_GOLDENROD_YELLOW: Color = Color::from_rgb8(250, 250, 210);
+    pub const LIGHT_GRAY: Color = Color::from_rgb8(211, 211, 211);
+    pub const LIGHT_GREEN: Color = Color::from_rgb8(144, 238, 144);
+    pub const LIGHT_GREY: Color = Color::from_rgb8(211, 211, 211);
+    pub const LIGHT_PINK: Color = Color::from_rgb8(255, 182, 193);
+    pub const LIGHT_SALMON: Color = Color::from_rgb8(255, 160, 122);
+    pub const LIGHT_SEA_GREEN: Color = Color::from_rgb8(32, 178, 170);
+    pub const LIGHT_SKY_BLUE: Color = Color::from_rgb8(135, 206, 250);
+    pub const LIGHT_SLATE_GRAY: Color = Color::from_rgb8(119, 136, 153);
+    pub const LIGHT_SLATE_GREY: Color = Color::from_rgb8(119, 136, 153);
+    pub const LIGHT_STEEL_BLUE: Color = Color::from_rgb8(176, 196, 222);
+    pub const LIGHT_YELLOW: Color = Color::from_rgb8(255, 255, 224);
+    pub const LIME: Color = Color::from_rgb8(0, 255, 0);
+    pub const LIME_GREEN: Color = Color::from_rgb8(50, 205, 50);
+    pub const LINEN: Color = Color::from_rgb8(250, 240, 230);
+    pub const MAGENTA: Color = Color::from_rgb8(255, 0, 255);
+    pub const MAROON: Color = Color::from_rgb8(128, 0, 0);
+    pub const MEDIUM_AQUAMARINE: Color = Color::from_rgb8(102, 205, 170);
+    pub const MEDIUM_BLUE: Color = Color::from_rgb8(0, 0, 205);
+    pub const MEDIUM_ORCHID: Color = Color::from_rgb8(186, 85, 211);
+    pub const MEDIUM_PURPLE: Color = Color::from_rgb8(147, 112, 219);
+    pub const MEDIUM_SEA_GREEN: Color = Color::from_rgb8(60, 179, 113);
+    pub const MEDIUM_SLATE_BLUE: Color = Color::from_rgb8(123, 104, 238);
+    pub const MEDIUM_SPRING_GREEN: Color = Color::from_rgb8(0, 250, 154);
+    pub const MEDIUM_TURQUOISE: Color = Color::from_rgb8(72, 209, 204);
+    pub const MEDIUM_VIOLET_RED: Color = Color::from_rgb8(199, 21, 133);
+    pub const MIDNIGHT_BLUE: Color = Color::from_rgb8(25, 25, 112);
+    pub const MINT_CREAM: Color = Color::from_rgb8(245, 255, 250);
+    pub const MISTY_ROSE: Color = Color::from_rgb8(255, 228, 225);
+    pub const MOCCASIN: Color = Color::from_rgb8(255, 228, 181);
+    pub const NAVAJO_WHITE: Color = Color::from_rgb8(255, 222, 173);
+    pub const NAVY: Color = Color::from_rgb8(0, 0, 128);
+    pub const OLD_LACE: Color = Color::from_rgb8(253, 245, 230);
+    pub const OLIVE: Color = Color::from_rgb8(128, 128, 0);
+    pub const OLIVE_DRAB: Color = Color::from_rgb8(107, 142, 35);
+    pub const ORANGE: Color = Color::from_rgb8(255, 165, 0);
+    pub const ORANGE_RED: Color = Color::from_rgb8(255, 69, 0);
+    pub const ORCHID: Color = Color::from_rgb8(218, 112, 214);
+    pub const PALE_GOLDENROD: Color = Color::from_rgb8(238, 232, 170);
+    pub const PALE_GREEN: Color = Color::from_rgb8(152, 251, 152);
+    pub const PALE_TURQUOISE: Color = Color::from_rgb8(175, 238, 238);
+    pub const PALE_VIOLET_RED: Color = Color::from_rgb8(219, 112, 147);
+    pub const PAPAYA_WHIP: Color = Color::from_rgb8(255, 239, 213);
+    pub const PEACH_PUFF: Color = Color::from_rgb8(255, 218, 185);
+    pub const PERU: Color = Color::from_rgb8(205, 133, 63);
+    pub const PINK: Color = Color::from_rgb8(255, 192, 203);
+    pub const PLUM: Color = Color::from_rgb8(221, 160, 221);
+    pub const POWDER_BLUE: Color = Color::from_rgb8(176, 224, 230);
+    pub const PURPLE: Color = Color::from_rgb8(128, 0, 128);
+    pub const REBECCA_PURPLE: Color = Color::from_rgb8(102, 51, 153);
+    pub const RED: Color = Color::from_rgb8(255, 0, 0);
+    pub const ROSY_BROWN: Color = Color::from_rgb8(188, 143, 143);
+    pub const ROYAL_BLUE: Color = Color::from_rgb8(65, 105, 225);
+    pub const SADDLE_BROWN: Color = Color::from_rgb8(139, 69, 19);
+    pub const SALMON: Color = Color::from_rgb8(250, 128, 114);
+    pub const SANDY_BROWN: Color = Color::from_rgb8(244, 164, 96);
+    pub const SEA_GREEN: Color = Color::from_rgb8(46, 139, 87);
+    pub const SEASHELL: Color = Color::from_rgb8(255, 245, 238);
+    pub const SIENNA: Color = Color::from_rgb8(160, 82, 45);
+    pub const SILVER: Color = Color::from_rgb8(192, 192, 192);
+    pub const SKY_BLUE: Color = Color::from_rgb8(135, 206, 235);
+    pub const SLATE_BLUE: Color = Color::from_rgb8(106, 90, 205);
+    pub const SLATE_GRAY: Color = Color::from_rgb8(112, 128, 144);
+    pub const SLATE_GREY: Color = Color::from_rgb8(112, 128, 144);
+    pub const SNOW: Color = Color::from_rgb8(255, 250, 250);
+    pub const SPRING_GREEN: Color = Color::from_rgb8(0, 255, 127);
+    pub const STEEL_BLUE: Color = Color::from_rgb8(70, 130, 180);
+    pub const TAN: Color = Color::from_rgb8(210, 180, 140);
+    pub const TEAL: Color = Color::from_rgb8(0, 128, 128);
+    pub const THISTLE: Color = Color::from_rgb8(216, 191, 216);
+    pub const TOMATO: Color = Color::from_rgb8(255, 99, 71);
+    pub const TURQUOISE: Color = Color::from_rgb8(64, 224, 208);
+    pub const VIOLET: Color = Color::from_rgb8(238, 130, 238);
+    pub const WHEAT: Color = Color::from_rgb8(245, 222, 179);
+    pub const WHITE: Color = Color::from_rgb8(255, 255, 255);
+    pub const WHITE_SMOKE: Color = Color::from_rgb8(245, 245, 245);
+    pub const YELLOW: Color = Color::from_rgb8(255, 255, 0);
+    pub const YELLOW_GREEN: Color = Color::from_rgb8(154, 205, 50);
+    /// Fully transparent black (`transparent` in CSS).
+    pub const TRANSPARENT: Color = Color::from_rgba8(0, 0, 0, 0);
+
+    /// Look up a CSS named color, ignoring case, surrounding whitespace and
+    /// `-`/`_`/space separators (`"tomato"`, `"SteelBlue"`, `"steel_blue"`).
+    ///
+    /// Returns `None` for unknown names.
+    pub fn by_name(name: &str) -> Option<Color> {
+        let key: String = name
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        NAMED_COLORS
+            .binary_search_by(|(n, _)| n.cmp(&key.as_str()))
+            .ok()
+            .map(|i| NAMED_COLORS[i].1)
+    }
+
+    /// All CSS named colors as `(name, color)` pairs, sorted by name.
+    pub fn named_colors() -> &'static [(&'static str, Color)] {
+        NAMED_COLORS
+    }
+}
+
+/// Sorted by name so [`Color::by_name`] can binary search.
+static NAMED_COLORS: &[(&str, Color)] = &[
+    ("aliceblue", Color::ALICE_BLUE),
+    ("antiquewhite", Color::ANTIQUE_WHITE),
+    ("aqua", Color::AQUA),
+    ("aquamarine", Color::AQUAMARINE),
+    ("azure", Color::AZURE),
+    ("beige", Color::BEIGE),
+    ("bisque", Color::BISQUE),
+    ("black", Color::BLACK),
+    ("blanchedalmond", Color::BLANCHED_ALMOND),
+    ("blue", Color::BLUE),
+    ("blueviolet", Color::BLUE_VIOLET),
+    ("brown", Color::BROWN),
+    ("burlywood", Color::BURLYWOOD),
+    ("cadetblue", Color::CADET_BLUE),
+    ("chartreuse", Color::CHARTREUSE),
+    ("chocolate", Color::CHOCOLATE),
+    ("coral", Color::CORAL),
+    ("cornflowerblue", Color::CORNFLOWER_BLUE),
+    ("cornsilk", Color::CORNSILK),
+    ("crimson", Color::CRIMSON),
+    ("cyan", Color::CYAN),
+    ("darkblue", Color::DARK_BLUE),
+    ("darkcyan", Color::DARK_CYAN),
+    ("darkgoldenrod", Color::DARK_GOLDENROD),
+    ("darkgray", Color::DARK_GRAY),
+    ("darkgreen", Color::DARK_GREEN),
+    ("darkgrey", Color::DARK_GREY),
+    ("darkkhaki", Color::DARK_KHAKI),
+    ("darkmagenta", Color::DARK_MAGENTA),
+    ("darkolivegreen", Color::DARK_OLIVE_GREEN),
+    ("darkorange", Color::DARK_ORANGE),
+    ("darkorchid", Color::DARK_ORCHID),
+    ("darkred", Color::DARK_RED),
+    ("darksalmon", Color::DARK_SALMON),
+    ("darkseagreen", Color::DARK_SEA_GREEN),
+    ("darkslateblue", Color::DARK_SLATE_BLUE),
+    ("darkslategray", Color::DARK_SLATE_GRAY),
+    ("darkslategrey", Color::DARK_SLATE_GREY),
+    ("darkturquoise", Color::DARK_TURQUOISE),
+    ("darkviolet", Color::DARK_VIOLET),
+    ("deeppink", Color::DEEP_PINK),
+    ("deepskyblue", Color::DEEP_SKY_BLUE),
+    ("dimgray", Color::DIM_GRAY),
+    ("dimgrey", Color::DIM_GREY),
+    ("dodgerblue", Color::DODGER_BLUE),
+    ("firebrick", Color::FIREBRICK),
+    ("floralwhite", Color::FLORAL_WHITE),
+    ("forestgreen", Color::FOREST_GREEN),
+    ("fuchsia", Color::FUCHSIA),
+    ("gainsboro", Color::GAINSBORO),
+    ("ghostwhite", Color::GHOST_WHITE),
+    ("gold", Color::GOLD),
+    ("goldenrod", Color::GOLDENROD),
+    ("gray", Color::GRAY),
+    ("green", Color::GREEN),
+    ("greenyellow", Color::GREEN_YELLOW),
+    ("grey", Color::GREY),
+    ("honeydew", Color::HONEYDEW),
+    ("hotpink", Color::HOT_PINK),
+    ("indianred", Color::INDIAN_RED),
+    ("indigo", Color::INDIGO),
+    ("ivory", Color::IVORY),
+    ("khaki", Color::KHAKI),
+    ("lavender", Color::LAVENDER),
+    ("lavenderblush", Color::LAVENDER_BLUSH),
+    ("lawngreen", Color::LAWN_GREEN),
+    ("lemonchiffon", Color::LEMON_CHIFFON),
+    ("lightblue", Color::LIGHT_BLUE),
+    ("lightcoral", Color::LIGHT_CORAL),
+    ("lightcyan", Color::LIGHT_CYAN),
+    ("lightgoldenrodyellow", Color::LIGHT_GOLDENROD_YELLOW),
+    ("lightgray", Color::LIGHT_GRAY),
+    ("lightgreen", Color::LIGHT_GREEN),
+    ("lightgrey", Color::LIGHT_GREY),
+    ("lightpink", Color::LIGHT_PINK),
+    ("lightsalmon", Color::LIGHT_SALMON),
+    ("lightseagreen", Color::LIGHT_SEA_GREEN),
+    ("lightskyblue", Color::LIGHT_SKY_BLUE),
+    ("lightslategray", Color::LIGHT_SLATE_GRAY),
+    ("lightslategrey", Color::LIGHT_SLATE_GREY),
+    ("lightsteelblue", Color::LIGHT_STEEL_BLUE),
+    ("lightyellow", Color::LIGHT_YELLOW),
+    ("lime", Color::LIME),
+    ("limegreen", Color::LIME_GREEN),
+    ("linen", Color::LINEN),
+    ("magenta", Color::MAGENTA),
+    ("maroon", Color::MAROON),
+    ("mediumaquamarine", Color::MEDIUM_AQUAMARINE),
+    ("mediumblue", Color::MEDIUM_BLUE),
+    ("mediumorchid", Color::MEDIUM_ORCHID),
+    ("mediumpurple", Color::MEDIUM_PURPLE),
+    ("mediumseagreen", Color::MEDIUM_SEA_GREEN),
+    ("mediumslateblue", Color::MEDIUM_SLATE_BLUE),
+    ("mediumspringgreen", Color::MEDIUM_SPRING_GREEN),
+    ("mediumturquoise", Color::MEDIUM_TURQUOISE),
+    ("mediumvioletred", Color::MEDIUM_VIOLET_RED),
+    ("midnightblue", Color::MIDNIGHT_BLUE),
+    ("mintcream", Color::MINT_CREAM),
+    ("mistyrose", Color::MISTY_ROSE),
+    ("moccasin", Color::MOCCASIN),
+    ("navajowhite", Color::NAVAJO_WHITE),
+    ("navy", Color::NAVY),
+    ("oldlace", Color::OLD_LACE),
+    ("olive", Color::OLIVE),
+    ("olivedrab", Color::OLIVE_DRAB),
+    ("orange", Color::ORANGE),
+    ("orangered", Color::ORANGE_RED),
+    ("orchid", Color::ORCHID),
+    ("palegoldenrod", Color::PALE_GOLDENROD),
+    ("palegreen", Color::PALE_GREEN),
+    ("paleturquoise", Color::PALE_TURQUOISE),
+    ("palevioletred", Color::PALE_VIOLET_RED),
+    ("papayawhip", Color::PAPAYA_WHIP),
+    ("peachpuff", Color::PEACH_PUFF),
+    ("peru", Color::PERU),
+    ("pink", Color::PINK),
+    ("plum", Color::PLUM),
+    ("powderblue", Color::POWDER_BLUE),
+    ("purple", Color::PURPLE),
+    ("rebeccapurple", Color::REBECCA_PURPLE),
+    ("red", Color::RED),
+    ("rosybrown", Color::ROSY_BROWN),
+    ("royalblue", Color::ROYAL_BLUE),
+    ("saddlebrown", Color::SADDLE_BROWN),
+    ("salmon", Color::SALMON),
+    ("sandybrown", Color::SANDY_BROWN),
+    ("seagreen", Color::SEA_GREEN),
+    ("seashell", Color::SEASHELL),
+    ("sienna", Color::SIENNA),
+    ("silver", Color::SILVER),
+    ("skyblue", Color::SKY_BLUE),
+    ("slateblue", Color::SLATE_BLUE),
+    ("slategray", Color::SLATE_GRAY),
+    ("slategrey", Color::SLATE_GREY),
+    ("snow", Color::SNOW),
+    ("springgreen", Color::SPRING_GREEN),
+    ("steelblue", Color::STEEL_BLUE),
+    ("tan", Color::TAN),
+    ("teal", Color::TEAL),
+    ("thistle", Color::THISTLE),
+    ("tomato", Color::TOMATO),
+    ("transparent", Color::TRANSPARENT),
+    ("turquoise", Color::TURQUOISE),
+    ("violet", Color::VIOLET),
+    ("wheat", Color::WHEAT),
+    ("white", Color::WHITE),
+    ("whitesmoke", Color::WHITE_SMOKE),
+    ("yellow", Color::YELLOW),
+    ("yellowgreen", Color::YELLOW_GREEN),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn lookup_is_case_and_separator_insensitive() {
+        let expected = Color::STEEL_BLUE.to_hex();
+        assert_eq!(Color::by_name("steelblue").unwrap().to_hex(), expected);
+        assert_eq!(Color::by_name("SteelBlue").unwrap().to_hex(), expected);
+        assert_eq!(Color::by_name(" steel-blue ").unwrap().to_hex(), expected);
+        assert_eq!(Color::by_name("STEEL_BLUE").unwrap().to_hex(), expected);
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(Color::TOMATO.to_hex(), "#FF6347");
+        assert_eq!(Color::REBECCA_PURPLE.to_hex(), "#663399");
+        assert_eq!(Color::by_name("transparent").unwrap().alpha(), 0.0);
+    }
+
+    #[test]
+    fn unknown_name() {
+        assert!(Color::by_name("not-a-color").is_none());
+    }
+}
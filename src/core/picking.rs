@@ -0,0 +1,150 @@
+use crate::core::engine::opengl::{
+    gl_bind_framebuffer, gl_bind_texture, gl_check_framebuffer_status, gl_framebuffer_texture_2d,
+    gl_read_pixels, gl_tex_image_2d, gl_tex_parameteri, GL_CLAMP_TO_EDGE, GL_COLOR_ATTACHMENT0,
+    GL_FRAMEBUFFER, GL_FRAMEBUFFER_COMPLETE, GL_NEAREST, GL_RGBA, GL_TEXTURE_2D,
+    GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S, GL_TEXTURE_WRAP_T,
+    GL_UNSIGNED_BYTE,
+};
+use crate::core::{GpuFramebuffer, GpuTexture};
+
+/// Identifier written into the picking buffer for a shape (or the first
+/// instance of an instanced shape; instance `i` gets `id + i`).
+///
+/// Ids are encoded as 24-bit RGB, so valid values are `1..=PickId::MAX`.
+/// `0` is reserved for "nothing under the cursor".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PickId(pub u32);
+
+impl PickId {
+    pub const MAX: u32 = 0x00FF_FFFF;
+
+    /// The id as normalized RGBA, matching the encoding in the shape shaders.
+    pub(crate) fn to_rgba(self) -> [f32; 4] {
+        let [r, g, b] = self.to_rgb8();
+        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
+    }
+
+    fn to_rgb8(self) -> [u8; 3] {
+        let id = self.0 & Self::MAX;
+        [id as u8, (id >> 8) as u8, (id >> 16) as u8]
+    }
+
+    /// Decode a pixel read back from the picking buffer.
+    fn from_rgb8(rgb: [u8; 3]) -> Option<PickId> {
+        let id = rgb[0] as u32 | (rgb[1] as u32) << 8 | (rgb[2] as u32) << 16;
+        (id != 0).then_some(PickId(id))
+    }
+
+    /// The id `offset` places after this one, e.g. the id of instance
+    /// `offset` when this is the instanced shape's base id.
+    pub fn offset(self, offset: u32) -> PickId {
+        PickId(self.0 + offset)
+    }
+}
+
+/// Offscreen RGBA framebuffer the picking pass renders ids into. Headless
+/// renderers also draw their frames into one. Its framebuffer and texture
+/// are deleted by `flush_deleted_resources` after it is dropped.
+pub(crate) struct PickBuffer {
+    framebuffer: GpuFramebuffer,
+    texture: GpuTexture,
+    width: i32,
+    height: i32,
+}
+
+impl PickBuffer {
+    pub(crate) fn new(width: i32, height: i32) -> Self {
+        let texture = GpuTexture::generate();
+        gl_bind_texture(GL_TEXTURE_2D, texture.id());
+        // Ids must never be blended or filtered.
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_NEAREST);
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_NEAREST);
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE);
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE);
+        Self::allocate(width, height);
+        gl_bind_texture(GL_TEXTURE_2D, 0);
+
+        let framebuffer = GpuFramebuffer::generate();
+        gl_bind_framebuffer(GL_FRAMEBUFFER, framebuffer.id());
+        gl_framebuffer_texture_2d(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, texture.id(), 0);
+        if gl_check_framebuffer_status(GL_FRAMEBUFFER) != GL_FRAMEBUFFER_COMPLETE {
+            log::warn!("picking framebuffer is incomplete; picks will return None");
+        }
+        gl_bind_framebuffer(GL_FRAMEBUFFER, 0);
+
+        Self { framebuffer, texture, width, height }
+    }
+
+    fn allocate(width: i32, height: i32) {
+        gl_tex_image_2d(
+            GL_TEXTURE_2D,
+            0,
+            GL_RGBA,
+            width,
+            height,
+            0,
+            GL_RGBA as u32,
+            GL_UNSIGNED_BYTE,
+            std::ptr::null(),
+        );
+    }
+
+    /// Reallocate the color texture if the framebuffer size changed.
+    pub(crate) fn resize(&mut self, width: i32, height: i32) {
+        if (width, height) != (self.width, self.height) {
+            gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+            Self::allocate(width, height);
+            gl_bind_texture(GL_TEXTURE_2D, 0);
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    pub(crate) fn bind(&self) {
+        gl_bind_framebuffer(GL_FRAMEBUFFER, self.framebuffer.id());
+    }
+
+    pub(crate) fn unbind(&self) {
+        gl_bind_framebuffer(GL_FRAMEBUFFER, 0);
+    }
+
+    pub(crate) fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Read the id at framebuffer pixel `(x, y)` (origin bottom-left).
+    pub(crate) fn read(&self, x: i32, y: i32) -> Option<PickId> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        let mut pixel = [0u8; 4];
+        self.bind();
+        gl_read_pixels(x, y, 1, 1, GL_RGBA as u32, GL_UNSIGNED_BYTE, &mut pixel);
+        self.unbind();
+        PickId::from_rgb8([pixel[0], pixel[1], pixel[2]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_roundtrip() {
+        for id in [1, 255, 256, 0x12_3456, PickId::MAX] {
+            assert_eq!(PickId::from_rgb8(PickId(id).to_rgb8()), Some(PickId(id)));
+        }
+    }
+
+    #[test]
+    fn zero_is_background() {
+        assert_eq!(PickId::from_rgb8([0, 0, 0]), None);
+    }
+
+    #[test]
+    fn rgba_matches_shader_encoding() {
+        // shape.vert: r = id % 256, g = (id / 256) % 256, b = id / 65536
+        let [r, g, b, a] = PickId(0x03_0201).to_rgba();
+        assert_eq!((r * 255.0, g * 255.0, b * 255.0, a), (1.0, 2.0, 3.0, 1.0));
+    }
+}
@@ -0,0 +1,199 @@
+//! Pixel buffer objects for texture uploads and framebuffer readbacks that
+//! don't stall the pipeline.
+
+use std::collections::VecDeque;
+
+use image::RgbaImage;
+
+use crate::core::engine::opengl::{
+    gl_bind_buffer, gl_bind_texture, gl_buffer_data_empty_with_usage, gl_generate_mipmap, gl_map_buffer_range,
+    gl_read_pixels_rgba_to_buffer, gl_tex_sub_image_2d, gl_unmap_buffer, GLsizeiptr, GL_MAP_INVALIDATE_BUFFER_BIT,
+    GL_MAP_READ_BIT, GL_MAP_WRITE_BIT, GL_PIXEL_PACK_BUFFER, GL_PIXEL_UNPACK_BUFFER, GL_RGBA, GL_STREAM_DRAW, GL_STREAM_READ,
+    GL_TEXTURE_2D, GL_UNSIGNED_BYTE,
+};
+use crate::core::gpu::{GpuBuffer, GpuFence, GpuTexture};
+use crate::core::texture::{generate_texture_from_rgba, TextureOptions};
+use crate::core::Renderer;
+
+/// A texture whose contents are replaced often, such as live raster or
+/// video frames.
+///
+/// Frames are written into one of two pixel unpack buffers and copied to
+/// the texture by the GPU, so [`upload`](Self::upload) returns as soon as
+/// the pixels are in driver memory instead of waiting for the texture to
+/// be free. Draw it with
+/// [`ShapeRenderable::image_from_texture`](crate::graphics2d::shapes::ShapeRenderable::image_from_texture).
+pub struct StreamingTexture {
+    texture: GpuTexture,
+    width: u32,
+    height: u32,
+    options: TextureOptions,
+    buffers: [GpuBuffer; 2],
+    next: usize,
+}
+
+impl StreamingTexture {
+    /// Create a `width` x `height` RGBA8 texture, initially transparent.
+    pub fn new(width: u32, height: u32, options: TextureOptions) -> Self {
+        let pixels = vec![0; (width * height * 4) as usize];
+        Self {
+            texture: GpuTexture::from_raw(generate_texture_from_rgba(width, height, &pixels, &options)),
+            width,
+            height,
+            options,
+            buffers: [GpuBuffer::generate(), GpuBuffer::generate()],
+            next: 0,
+        }
+    }
+
+    pub fn texture_id(&self) -> u32 {
+        self.texture.id()
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Replace the texture with `pixels`: RGBA8, rows top to bottom, like
+    /// [`generate_texture_from_rgba`].
+    pub fn upload(&mut self, pixels: &[u8]) {
+        assert_eq!(pixels.len(), (self.width * self.height * 4) as usize, "pixel buffer does not match the size");
+        self.write(|dst| dst.copy_from_slice(pixels));
+    }
+
+    /// Replace the texture with pixels written by `write` straight into
+    /// the mapped buffer. Every byte must be set; the old contents are not
+    /// readable through the slice. Returns `false` if the buffer could not
+    /// be mapped or its data was lost, leaving the texture unchanged.
+    pub fn write(&mut self, write: impl FnOnce(&mut [u8])) -> bool {
+        let len = (self.width * self.height * 4) as usize;
+        let buffer = &self.buffers[self.next];
+        self.next = (self.next + 1) % self.buffers.len();
+
+        gl_bind_buffer(GL_PIXEL_UNPACK_BUFFER, buffer.id());
+        // Orphan, so a transfer still reading the old storage doesn't block.
+        gl_buffer_data_empty_with_usage(GL_PIXEL_UNPACK_BUFFER, len as GLsizeiptr, GL_STREAM_DRAW);
+        // SAFETY: the mapping covers `len` bytes and the slice does not
+        // outlive it.
+        let ptr = unsafe { gl_map_buffer_range(GL_PIXEL_UNPACK_BUFFER, 0, len as GLsizeiptr, GL_MAP_WRITE_BIT | GL_MAP_INVALIDATE_BUFFER_BIT) };
+        let written = !ptr.is_null() && {
+            write(unsafe { std::slice::from_raw_parts_mut(ptr as *mut u8, len) });
+            gl_unmap_buffer(GL_PIXEL_UNPACK_BUFFER)
+        };
+        if written {
+            gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+            // With an unpack buffer bound the data pointer is an offset into it.
+            gl_tex_sub_image_2d(
+                GL_TEXTURE_2D,
+                0,
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                GL_RGBA as u32,
+                GL_UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            if self.options.mipmaps {
+                gl_generate_mipmap(GL_TEXTURE_2D);
+            }
+            gl_bind_texture(GL_TEXTURE_2D, 0);
+        }
+        gl_bind_buffer(GL_PIXEL_UNPACK_BUFFER, 0);
+        written
+    }
+}
+
+/// One readback in flight.
+struct Pending {
+    buffer: GpuBuffer,
+    width: u32,
+    height: u32,
+    fence: GpuFence,
+}
+
+/// Reads the framebuffer back without waiting for the GPU.
+///
+/// [`Renderer::read_framebuffer`] blocks until every queued draw has
+/// finished. Here [`request`](Self::request) only queues a copy into a
+/// pixel pack buffer, and [`poll`](Self::poll) returns the image a frame
+/// or two later, once the copy is done. Use it for periodic screenshots
+/// or streaming frames out of a running app.
+///
+/// ```ignore
+/// let mut readback = FramebufferReadback::new();
+/// app.on_render(move |renderer, _| {
+///     readback.request(renderer);
+///     while let Some(frame) = readback.poll() {
+///         encoder.send(frame);
+///     }
+/// });
+/// ```
+#[derive(Default)]
+pub struct FramebufferReadback {
+    pending: VecDeque<Pending>,
+    /// Buffers of completed readbacks, reused by later requests.
+    free: Vec<GpuBuffer>,
+}
+
+impl FramebufferReadback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a copy of the viewport as currently drawn. Call after drawing
+    /// and before the buffers are swapped, like `read_framebuffer`.
+    pub fn request(&mut self, renderer: &Renderer) {
+        let (width, height) = renderer.viewport_size();
+        let (width, height) = (width.max(0) as u32, height.max(0) as u32);
+        let buffer = self.free.pop().unwrap_or_else(GpuBuffer::generate);
+        gl_bind_buffer(GL_PIXEL_PACK_BUFFER, buffer.id());
+        gl_buffer_data_empty_with_usage(GL_PIXEL_PACK_BUFFER, (width * height * 4) as GLsizeiptr, GL_STREAM_READ);
+        gl_bind_buffer(GL_PIXEL_PACK_BUFFER, 0);
+        gl_read_pixels_rgba_to_buffer(buffer.id(), 0, 0, 0, width as i32, height as i32);
+        self.pending.push_back(Pending { buffer, width, height, fence: GpuFence::insert() });
+    }
+
+    /// The oldest requested frame, top-down, if its copy has completed.
+    /// Never blocks.
+    pub fn poll(&mut self) -> Option<RgbaImage> {
+        if !self.pending.front()?.fence.wait(0) {
+            return None;
+        }
+        let pending = self.pending.pop_front()?;
+        let image = read_buffer(&pending);
+        self.free.push(pending.buffer);
+        image
+    }
+
+    /// Block until every requested frame is read, and return them oldest
+    /// first.
+    pub fn finish(&mut self) -> Vec<RgbaImage> {
+        let mut images = Vec::new();
+        while let Some(pending) = self.pending.pop_front() {
+            pending.fence.wait(u64::MAX);
+            images.extend(read_buffer(&pending));
+            self.free.push(pending.buffer);
+        }
+        images
+    }
+
+    /// Number of requested frames not yet returned.
+    pub fn in_flight(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Map a completed readback and copy it out, flipped to top-down rows.
+fn read_buffer(pending: &Pending) -> Option<RgbaImage> {
+    let len = (pending.width * pending.height * 4) as usize;
+    gl_bind_buffer(GL_PIXEL_PACK_BUFFER, pending.buffer.id());
+    // SAFETY: the mapping covers `len` bytes and is only read before unmapping.
+    let ptr = unsafe { gl_map_buffer_range(GL_PIXEL_PACK_BUFFER, 0, len as GLsizeiptr, GL_MAP_READ_BIT) };
+    let pixels = (!ptr.is_null()).then(|| unsafe { std::slice::from_raw_parts(ptr as *const u8, len) }.to_vec());
+    let intact = ptr.is_null() || gl_unmap_buffer(GL_PIXEL_PACK_BUFFER);
+    gl_bind_buffer(GL_PIXEL_PACK_BUFFER, 0);
+    let mut image = RgbaImage::from_raw(pending.width, pending.height, pixels.filter(|_| intact)?)?;
+    image::imageops::flip_vertical_in_place(&mut image);
+    Some(image)
+}
@@ -0,0 +1,224 @@
+//! Capturing rendered frames to files.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageFormat, RgbaImage};
+
+use crate::core::renderer::Renderer;
+
+/// Records every Nth rendered frame to a PNG sequence, an animated GIF, or
+/// a video encoded by an `ffmpeg` process.
+///
+/// Pass one to [`App::record_frames`](super::App::record_frames), or call
+/// [`capture`](Self::capture) yourself after drawing each frame (before
+/// the buffers are swapped) and [`finish`](Self::finish) at the end.
+///
+/// ```ignore
+/// let mut recorder = FrameRecorder::gif("orbit.gif", 30)?;
+/// recorder.set_every(2); // 60 fps window, 30 fps GIF
+/// app.record_frames(recorder);
+/// ```
+pub struct FrameRecorder {
+    sink: Sink,
+    every: u32,
+    frames_seen: u64,
+    frames_written: u64,
+}
+
+enum Sink {
+    Png { dir: PathBuf },
+    Gif(Box<GifEncoder<BufWriter<File>>>, Delay),
+    Ffmpeg { path: PathBuf, fps: u32, process: Option<(Child, (u32, u32))> },
+}
+
+impl FrameRecorder {
+    /// Write `frame_00000.png`, `frame_00001.png`, ... into `dir`, creating
+    /// it if needed.
+    pub fn png_sequence(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self::with_sink(Sink::Png { dir }))
+    }
+
+    /// Write an endlessly looping animated GIF played back at `fps`.
+    pub fn gif(path: impl Into<PathBuf>, fps: u32) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path.into())?);
+        let mut encoder = GifEncoder::new(file);
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+        let delay = Delay::from_numer_denom_ms(1000, fps.max(1));
+        Ok(Self::with_sink(Sink::Gif(Box::new(encoder), delay)))
+    }
+
+    /// Pipe raw frames to `ffmpeg` (which must be on `PATH`) to encode a
+    /// video at `path` at `fps`; the container and codec follow ffmpeg's
+    /// defaults for the file extension (e.g. H.264 for `.mp4`). The process
+    /// starts with the first frame, whose size fixes the video size.
+    pub fn ffmpeg(path: impl Into<PathBuf>, fps: u32) -> Self {
+        Self::with_sink(Sink::Ffmpeg { path: path.into(), fps: fps.max(1), process: None })
+    }
+
+    fn with_sink(sink: Sink) -> Self {
+        Self { sink, every: 1, frames_seen: 0, frames_written: 0 }
+    }
+
+    /// Keep one frame in `n` (default 1: every frame).
+    pub fn set_every(&mut self, n: u32) -> &mut Self {
+        self.every = n.max(1);
+        self
+    }
+
+    pub fn every(&self) -> u32 {
+        self.every
+    }
+
+    /// Number of frames written so far.
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    /// Count a rendered frame and, if it is one to keep, read it back from
+    /// the framebuffer and write it.
+    pub fn capture(&mut self, renderer: &Renderer) -> io::Result<()> {
+        if self.next_frame_kept() {
+            let frame = renderer.read_framebuffer();
+            self.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Write `frame` unconditionally, bypassing the every-Nth filter.
+    pub fn write_frame(&mut self, frame: &RgbaImage) -> io::Result<()> {
+        match &mut self.sink {
+            Sink::Png { dir } => {
+                let path = dir.join(format!("frame_{:05}.png", self.frames_written));
+                frame.save(path).map_err(io::Error::other)?;
+            }
+            Sink::Gif(encoder, delay) => {
+                let frame = Frame::from_parts(frame.clone(), 0, 0, *delay);
+                encoder.encode_frame(frame).map_err(io::Error::other)?;
+            }
+            Sink::Ffmpeg { path, fps, process } => {
+                let size = frame.dimensions();
+                if process.is_none() {
+                    *process = Some((spawn_ffmpeg(path, *fps, size)?, size));
+                }
+                let (child, video_size) = process.as_mut().unwrap();
+                if *video_size != size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("frame size {size:?} differs from the video size {video_size:?}"),
+                    ));
+                }
+                let stdin = child.stdin.as_mut().ok_or_else(|| io::Error::other("ffmpeg stdin closed"))?;
+                stdin.write_all(frame.as_raw())?;
+            }
+        }
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    /// Flush and close the output. For ffmpeg, waits for encoding to end.
+    pub fn finish(self) -> io::Result<()> {
+        match self.sink {
+            Sink::Png { .. } => Ok(()),
+            Sink::Gif(encoder, _) => {
+                // The GIF trailer is written and the file flushed on drop.
+                drop(encoder);
+                Ok(())
+            }
+            Sink::Ffmpeg { process: Some((mut child, _)), .. } => {
+                drop(child.stdin.take());
+                let status = child.wait()?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(io::Error::other(format!("ffmpeg exited with {status}")))
+                }
+            }
+            Sink::Ffmpeg { process: None, .. } => Ok(()),
+        }
+    }
+
+    /// Advance the frame count; true for the frames to keep.
+    fn next_frame_kept(&mut self) -> bool {
+        let keep = self.frames_seen.is_multiple_of(self.every as u64);
+        self.frames_seen += 1;
+        keep
+    }
+}
+
+/// Write `image` to `path` as a PNG, whatever the file extension, creating
+/// missing parent directories. For screenshots of a frame read back with
+/// [`Renderer::read_framebuffer`], or use [`Renderer::save_png`].
+pub fn save_png(image: &RgbaImage, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    image.save_with_format(path, ImageFormat::Png).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn spawn_ffmpeg(path: &Path, fps: u32, (width, height): (u32, u32)) -> io::Result<Child> {
+    Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgba"])
+        .args(["-s", &format!("{width}x{height}"), "-r", &fps.to_string(), "-i", "-"])
+        // yuv420p needs even dimensions.
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wilhelm_recorder_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn keeps_every_nth_frame() {
+        let mut recorder = FrameRecorder::ffmpeg("unused.mp4", 30);
+        recorder.set_every(3);
+        let kept: Vec<bool> = (0..7).map(|_| recorder.next_frame_kept()).collect();
+        assert_eq!(kept, vec![true, false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn writes_png_sequence_and_gif() {
+        let frame = RgbaImage::from_pixel(4, 3, image::Rgba([255, 0, 0, 255]));
+
+        let dir = temp_path("png");
+        let mut pngs = FrameRecorder::png_sequence(&dir).unwrap();
+        pngs.write_frame(&frame).unwrap();
+        pngs.write_frame(&frame).unwrap();
+        assert_eq!(pngs.frames_written(), 2);
+        pngs.finish().unwrap();
+        let decoded = image::open(dir.join("frame_00001.png")).unwrap().to_rgba8();
+        assert_eq!(decoded, frame);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let gif = temp_path("anim.gif");
+        let mut recorder = FrameRecorder::gif(&gif, 10).unwrap();
+        recorder.write_frame(&frame).unwrap();
+        recorder.finish().unwrap();
+        assert!(std::fs::metadata(&gif).unwrap().len() > 0);
+        std::fs::remove_file(&gif).unwrap();
+    }
+
+    #[test]
+    fn save_png_creates_directories_and_ignores_the_extension() {
+        let frame = RgbaImage::from_fn(5, 2, |x, y| image::Rgba([x as u8 * 50, y as u8 * 100, 7, 255]));
+        let dir = temp_path("shots");
+        let path = dir.join("nested").join("frame.capture");
+        save_png(&frame, &path).unwrap();
+        let decoded = image::load_from_memory_with_format(&std::fs::read(&path).unwrap(), ImageFormat::Png).unwrap();
+        assert_eq!(decoded.to_rgba8(), frame);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+//! Offscreen rendering into a texture.
+
+use std::ffi::c_void;
+
+use image::RgbaImage;
+
+use crate::core::engine::opengl::{
+    gl_bind_framebuffer, gl_bind_texture, gl_check_framebuffer_status, gl_clear_color, gl_framebuffer_texture_2d,
+    gl_generate_mipmap, gl_get_integerv, gl_read_pixels, gl_tex_image_2d, gl_viewport, GLuint,
+    GL_COLOR_ATTACHMENT0, GL_FRAMEBUFFER, GL_FRAMEBUFFER_BINDING, GL_FRAMEBUFFER_COMPLETE, GL_RGBA,
+    GL_TEXTURE_2D, GL_UNSIGNED_BYTE, GL_VIEWPORT,
+};
+use crate::core::{Color, GpuFramebuffer, GpuTexture, Renderer, TextureOptions, TextureWrap};
+
+/// A texture that scenes can be drawn into instead of the window, e.g. for
+/// minimaps, caching an expensive static layer, or post-processing.
+///
+/// Draws inside [`render`](Self::render) use the target's size as the
+/// surface, in pixels, so shapes are positioned as if the target were a
+/// window of that size. The texture's rows run top to bottom like those of
+/// uploaded images, so it can be shown directly with
+/// [`ShapeRenderable::image_from_texture`](crate::graphics2d::shapes::ShapeRenderable::image_from_texture):
+///
+/// ```ignore
+/// let minimap = RenderTarget::new(256, 256)?;
+/// minimap.render(&renderer, Some(Color::black()), |renderer| {
+///     for shape in &mut overview {
+///         shape.render(renderer);
+///     }
+/// });
+/// let mut preview = ShapeRenderable::image_from_texture(minimap.texture_id(), 256.0, 256.0);
+/// ```
+///
+/// Targets have no depth buffer, so [`graphics3d`](crate::graphics3d)
+/// scenes cannot be drawn into them. The GL objects are deleted by
+/// [`flush_deleted_resources`](crate::core::flush_deleted_resources) after
+/// the target is dropped.
+pub struct RenderTarget {
+    framebuffer: GpuFramebuffer,
+    texture: GpuTexture,
+    width: i32,
+    height: i32,
+    options: TextureOptions,
+}
+
+impl RenderTarget {
+    /// A `width` x `height` target sampled with linear filtering, clamped
+    /// at the edges and without mipmaps.
+    pub fn new(width: i32, height: i32) -> Result<Self, String> {
+        let options = TextureOptions::default().wrap(TextureWrap::ClampToEdge).mipmaps(false);
+        Self::with_options(width, height, options)
+    }
+
+    /// A target whose texture is sampled with `options`. With mipmaps
+    /// enabled, they are regenerated after every [`render`](Self::render).
+    pub fn with_options(width: i32, height: i32, options: TextureOptions) -> Result<Self, String> {
+        if width <= 0 || height <= 0 {
+            return Err(format!("invalid render target size {width}x{height}"));
+        }
+        let texture = GpuTexture::generate();
+        gl_bind_texture(GL_TEXTURE_2D, texture.id());
+        options.apply();
+        allocate(width, height);
+        gl_bind_texture(GL_TEXTURE_2D, 0);
+
+        let framebuffer = GpuFramebuffer::generate();
+        let previous = bound_framebuffer();
+        gl_bind_framebuffer(GL_FRAMEBUFFER, framebuffer.id());
+        gl_framebuffer_texture_2d(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, texture.id(), 0);
+        let status = gl_check_framebuffer_status(GL_FRAMEBUFFER);
+        gl_bind_framebuffer(GL_FRAMEBUFFER, previous);
+        if status != GL_FRAMEBUFFER_COMPLETE {
+            return Err(format!("render target framebuffer is incomplete (status {status:#x})"));
+        }
+        Ok(Self { framebuffer, texture, width, height, options })
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// The color texture, for drawing the target's contents with an image
+    /// shape or a custom shader. It stays owned by the target.
+    pub fn texture_id(&self) -> GLuint {
+        self.texture.id()
+    }
+
+    /// Reallocate the texture at a new size. Its contents are undefined
+    /// until the next [`render`](Self::render).
+    pub fn resize(&mut self, width: i32, height: i32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) != (self.width, self.height) {
+            gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+            allocate(width, height);
+            gl_bind_texture(GL_TEXTURE_2D, 0);
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    /// Clear the target to `clear`, if given, and run `draw` with drawing
+    /// redirected into it. The previously bound framebuffer and viewport,
+    /// and the renderer's surface size, are restored afterwards, so this
+    /// can be called in the middle of a frame.
+    pub fn render<R>(&self, renderer: &Renderer, clear: Option<Color>, draw: impl FnOnce(&Renderer) -> R) -> R {
+        let previous = bound_framebuffer();
+        let mut viewport = [0i32; 4];
+        gl_get_integerv(GL_VIEWPORT, viewport.as_mut_ptr() as *mut c_void);
+
+        gl_bind_framebuffer(GL_FRAMEBUFFER, self.framebuffer.id());
+        gl_viewport(0, 0, self.width, self.height);
+        if let Some(color) = clear {
+            gl_clear_color(color.red_value(), color.green_value(), color.blue_value(), color.alpha());
+        }
+        let result = renderer
+            .window_handle
+            .with_surface_size(self.width, self.height, || renderer.with_flipped_y(|| draw(renderer)));
+
+        gl_bind_framebuffer(GL_FRAMEBUFFER, previous);
+        gl_viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        if self.options.mipmaps {
+            gl_bind_texture(GL_TEXTURE_2D, self.texture.id());
+            gl_generate_mipmap(GL_TEXTURE_2D);
+            gl_bind_texture(GL_TEXTURE_2D, 0);
+        }
+        result
+    }
+
+    /// Read the target's contents back as a top-down RGBA image, e.g. to
+    /// save it. Stalls until drawing into the target has finished.
+    pub fn read_image(&self) -> RgbaImage {
+        let previous = bound_framebuffer();
+        gl_bind_framebuffer(GL_FRAMEBUFFER, self.framebuffer.id());
+        let (width, height) = (self.width as u32, self.height as u32);
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        gl_read_pixels(0, 0, self.width, self.height, GL_RGBA as u32, GL_UNSIGNED_BYTE, &mut pixels);
+        gl_bind_framebuffer(GL_FRAMEBUFFER, previous);
+        // Rows were drawn mirrored, so the first one read is the top row.
+        RgbaImage::from_raw(width, height, pixels).expect("pixel buffer matches the target size")
+    }
+
+    /// Save the target's contents as a PNG; see
+    /// [`save_png`](crate::core::save_png).
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        crate::core::save_png(&self.read_image(), path)
+    }
+}
+
+/// Allocate uninitialized RGBA8 storage for the texture bound to
+/// `GL_TEXTURE_2D`.
+fn allocate(width: i32, height: i32) {
+    gl_tex_image_2d(GL_TEXTURE_2D, 0, GL_RGBA, width, height, 0, GL_RGBA as u32, GL_UNSIGNED_BYTE, std::ptr::null());
+}
+
+/// The framebuffer currently bound, e.g. a headless renderer's, so it can
+/// be restored.
+fn bound_framebuffer() -> GLuint {
+    let mut framebuffer = 0i32;
+    gl_get_integerv(GL_FRAMEBUFFER_BINDING, &mut framebuffer as *mut i32 as *mut c_void);
+    framebuffer as GLuint
+}
@@ -1,15 +1,45 @@
+#[cfg(not(feature = "winit"))]
 use crate::core::engine::glfw::glfw_get_time;
-use crate::core::engine::opengl::{gl_active_texture, gl_bind_texture, gl_blend_func, gl_draw_arrays_instanced, gl_enable, gl_get_integerv, gl_uniform_1f, gl_uniform_4f, gl_vertex_attrib_4f, GL_BLEND, GL_ONE_MINUS_SRC_ALPHA, GL_SRC_ALPHA, GL_TEXTURE0, GL_TEXTURE_2D, GL_VIEWPORT};
+#[cfg(feature = "winit")]
+use crate::core::engine::winit::get_time as glfw_get_time;
+use crate::core::engine::state;
+use crate::core::engine::opengl::{gl_disable, gl_load, GLloadproc, GLfloat, GL_BLEND};
+use crate::core::backend::{DrawParams, OpenGlBackend, RenderBackend};
+use crate::core::Color;
+use crate::core::math::Mat4;
 use crate::core::mesh::Mesh;
-use std::ffi::c_void;
-use crate::core::engine::opengl::{
-    gl_draw_arrays, gl_get_uniform_location, gl_point_size, gl_uniform_matrix_4fv, GLboolean,
-    GLfloat,
-};
+use image::RgbaImage;
+use crate::core::context_info::ContextInfo;
+use crate::core::picking::{PickBuffer, PickId};
 use crate::core::window::WindowHandle;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::time::Instant;
 
 pub struct Renderer {
-    pub window_handle: WindowHandle
+    pub window_handle: WindowHandle,
+    backend: Box<dyn RenderBackend>,
+    picking: RefCell<PickState>,
+    /// Start of [`get_time`](Self::get_time) for renderers embedded in a
+    /// host's context, where there is no GLFW clock.
+    embedded_epoch: Option<Instant>,
+    /// Size of `GL_POINTS`, also passed to shaders as `u_point_size` for
+    /// OpenGL ES, which has no `glPointSize`.
+    point_size: Cell<f32>,
+    /// Queried on first use; the context doesn't change afterwards.
+    context_info: OnceCell<ContextInfo>,
+    /// Set while drawing into a [`RenderTarget`](crate::core::RenderTarget):
+    /// `u_Transform` is mirrored vertically so the target's texture rows
+    /// run top to bottom, like uploaded images.
+    flip_y: Cell<bool>,
+}
+
+/// Picking pass state. `active` is set between `begin_picking` and
+/// `end_picking`; draws then write `current` instead of colors.
+#[derive(Default)]
+struct PickState {
+    buffer: Option<PickBuffer>,
+    active: bool,
+    current: Option<PickId>,
 }
 
 pub trait Renderable {
@@ -18,176 +48,229 @@ pub trait Renderable {
 
 impl Renderer {
     pub fn new(window_handle: WindowHandle) -> Self {
-        Renderer { window_handle }
+        Renderer {
+            window_handle,
+            backend: Box::new(OpenGlBackend),
+            picking: RefCell::new(PickState::default()),
+            embedded_epoch: None,
+            point_size: Cell::new(1.0),
+            context_info: OnceCell::new(),
+            flip_y: Cell::new(false),
+        }
     }
 
-    pub fn set_point_size(&self, point_size: GLfloat) {
-        gl_point_size(point_size);
+    /// Attach to the OpenGL 3.3+ context a host application (Qt, GTK, a
+    /// game engine) has made current on this thread, without creating a
+    /// window. GL functions are loaded from the platform's GL library;
+    /// hosts on EGL or other loaders use
+    /// [`from_current_context_with_loader`](Self::from_current_context_with_loader).
+    ///
+    /// The host owns the window, its events and buffer swaps. Bracket each
+    /// frame's drawing with [`begin_frame`](Self::begin_frame) and
+    /// [`end_frame`](Self::end_frame), and report size changes with
+    /// [`resize`](Self::resize).
+    ///
+    /// ```ignore
+    /// // in the host's GL widget, with its context current
+    /// let renderer = Renderer::from_current_context(width, height)?;
+    /// // on paint
+    /// renderer.begin_frame(Some(Color::from_rgb(0.1, 0.1, 0.1)));
+    /// shape.render(&renderer);
+    /// renderer.end_frame();
+    /// ```
+    pub fn from_current_context(width: i32, height: i32) -> Result<Self, String> {
+        Self::from_current_context_with_loader(width, height, None)
     }
 
-    pub fn viewport_size(&self) -> (i32, i32) {
-        let mut viewport = [0, 0, 0, 0];
-        gl_get_integerv(GL_VIEWPORT, viewport.as_mut_ptr() as *mut c_void);
-        (viewport[2], viewport[3]) // width, height
+    /// Like [`from_current_context`](Self::from_current_context), resolving
+    /// GL functions through `loader` (e.g. a trampoline to
+    /// `eglGetProcAddress`).
+    pub fn from_current_context_with_loader(width: i32, height: i32, loader: GLloadproc) -> Result<Self, String> {
+        if !gl_load(loader) {
+            return Err("failed to load OpenGL functions; is a context current on this thread?".to_string());
+        }
+        let mut renderer = Self::new(WindowHandle::detached(width, height, Color::black()));
+        renderer.embedded_epoch = Some(Instant::now());
+        Ok(renderer)
     }
 
-    pub fn get_time(&self) -> f64 {
-        glfw_get_time()
+    /// Update the drawing surface size, in physical pixels, after the host
+    /// resized it.
+    pub fn resize(&self, width: i32, height: i32) {
+        self.window_handle.set_size(width, height);
     }
 
-    pub fn draw_mesh(&self, mesh: &Mesh) {
-        mesh.shader.use_program();
-        mesh.geometry.bind();
-
-        gl_enable(GL_BLEND);
-        gl_blend_func(GL_SRC_ALPHA, GL_ONE_MINUS_SRC_ALPHA);
-
-        // Reset instance color attribute to (0,0,0,0) so the shader falls back to
-        // the geometryColor uniform. OpenGL defaults disabled attributes to (0,0,0,1).
-        gl_vertex_attrib_4f(2, 0.0, 0.0, 0.0, 0.0);
-
-        let transform_loc = gl_get_uniform_location(mesh.shader.program(), "u_Transform");
-        if transform_loc != -1 {
-            gl_uniform_matrix_4fv(
-                transform_loc,
-                1,
-                GLboolean::FALSE,
-                mesh.transform().as_ptr(),
-            );
-        }
+    /// Tell an embedded renderer how many physical pixels make a logical
+    /// one on the host's display, so shapes drawn in
+    /// [`PixelUnits::Logical`](crate::core::PixelUnits::Logical) keep their size on high-DPI screens.
+    /// Defaults to 1.
+    pub fn set_content_scale(&self, scale: f32) {
+        self.window_handle.set_content_scale(scale);
+    }
 
-        let offset_loc = gl_get_uniform_location(mesh.shader.program(), "u_screen_offset");
-        if offset_loc != -1 {
-            let (ox, oy) = mesh.screen_offset();
-            crate::core::engine::opengl::gl_uniform_2f(offset_loc, ox, oy);
+    /// Prepare the host's context for drawing: set the viewport to the
+    /// surface size and clear it to `clear`, if given. The host may have
+    /// changed GL state since the last frame, so the state cache is reset.
+    pub fn begin_frame(&self, clear: Option<Color>) {
+        self.reset_state_cache();
+        let (width, height) = self.window_handle.framebuffer_size();
+        self.backend.set_viewport(width, height);
+        if let Some(color) = clear {
+            self.backend.clear(color);
         }
+    }
 
-        let scale_loc = gl_get_uniform_location(mesh.shader.program(), "u_scale");
-        if scale_loc != -1 {
-            gl_uniform_1f(scale_loc, mesh.scale());
-        }
+    /// Forget the GL state recorded to skip redundant calls. Call after
+    /// changing program, texture, blend or uniform state with raw GL calls
+    /// that bypass this crate's wrappers.
+    pub fn reset_state_cache(&self) {
+        state::reset();
+    }
 
-        let rotation_loc = gl_get_uniform_location(mesh.shader.program(), "u_rotation");
-        if rotation_loc != -1 {
-            gl_uniform_1f(rotation_loc, mesh.rotation());
-        }
+    /// Unbind the program, vertex array, buffer and texture left bound by
+    /// drawing, so the host's own GL code starts from a clean state.
+    pub fn end_frame(&self) {
+        self.backend.end_frame();
+    }
 
-        let color_loc = gl_get_uniform_location(mesh.shader.program(), "geometryColor");
-        if color_loc != -1 {
-            if let Some(color) = mesh.color.as_ref() {
-                gl_uniform_4f(color_loc, color.red_value(), color.green_value(), color.blue_value(), color.alpha());
-            }
-        }
+    pub fn set_point_size(&self, point_size: GLfloat) {
+        self.point_size.set(point_size);
+        self.backend.set_point_size(point_size);
+    }
 
-        // Also check for u_color (vec4 with alpha) - used by text shader
-        let color4_loc = gl_get_uniform_location(mesh.shader.program(), "u_color");
-        if color4_loc != -1 {
-            if let Some(color) = mesh.color.as_ref() {
-                gl_uniform_4f(color4_loc, color.red_value(), color.green_value(), color.blue_value(), color.alpha());
-            }
-        }
+    /// Version, limits and extensions of the GL context, for choosing
+    /// between code paths at runtime.
+    pub fn context_info(&self) -> &ContextInfo {
+        self.context_info.get_or_init(ContextInfo::query)
+    }
 
-        if let Some((dash, gap)) = mesh.dash_pattern {
-            let dash_loc = gl_get_uniform_location(mesh.shader.program(), "u_dash_length");
-            if dash_loc != -1 {
-                gl_uniform_1f(dash_loc, dash);
-            }
-            let gap_loc = gl_get_uniform_location(mesh.shader.program(), "u_gap_length");
-            if gap_loc != -1 {
-                gl_uniform_1f(gap_loc, gap);
-            }
-        }
+    pub fn viewport_size(&self) -> (i32, i32) {
+        self.backend.viewport_size()
+    }
 
-        if let Some(texture_id) = mesh.texture {
-            gl_active_texture(GL_TEXTURE0);
-            gl_bind_texture(GL_TEXTURE_2D, texture_id);
-        }
+    /// Read the current contents of the window's framebuffer as a top-down
+    /// RGBA image (GL rows start at the bottom and are flipped). Call after
+    /// drawing and before the buffers are swapped.
+    pub fn read_framebuffer(&self) -> RgbaImage {
+        let (width, height) = self.viewport_size();
+        let (width, height) = (width.max(0) as u32, height.max(0) as u32);
+        let pixels = self.backend.read_pixels(0, 0, width as i32, height as i32);
+        let mut image = RgbaImage::from_raw(width, height, pixels).expect("pixel buffer matches the viewport");
+        image::imageops::flip_vertical_in_place(&mut image);
+        image
+    }
 
-        gl_draw_arrays(
-            mesh.geometry.drawing_mode(),
-            0,
-            mesh.geometry.vertex_count(),
-        );
+    /// Run `f` with draws mirrored vertically, restoring the previous
+    /// setting afterwards.
+    pub(crate) fn with_flipped_y<R>(&self, f: impl FnOnce() -> R) -> R {
+        let flipped = self.flip_y.replace(true);
+        let result = f();
+        self.flip_y.set(flipped);
+        result
+    }
 
-        if mesh.texture.is_some() {
-            gl_bind_texture(GL_TEXTURE_2D, 0);
+    fn surface_transform(&self, mesh: &Mesh) -> Mat4 {
+        if self.flip_y.get() {
+            Mat4::from_scale(1.0, -1.0, 1.0) * mesh.transform()
+        } else {
+            mesh.transform()
         }
     }
 
-    pub fn draw_mesh_instanced(&self, mesh: &Mesh) {
-        mesh.shader.use_program();
-        mesh.geometry.bind();
-
-        gl_enable(GL_BLEND);
-        gl_blend_func(GL_SRC_ALPHA, GL_ONE_MINUS_SRC_ALPHA);
-
-        // Reset instance color attribute to (0,0,0,0) so the shader falls back to
-        // the geometryColor uniform. OpenGL defaults disabled attributes to (0,0,0,1).
-        gl_vertex_attrib_4f(2, 0.0, 0.0, 0.0, 0.0);
-
-        let transform_loc = gl_get_uniform_location(mesh.shader.program(), "u_Transform");
-        if transform_loc != -1 {
-            gl_uniform_matrix_4fv(
-                transform_loc, 1, GLboolean::FALSE, mesh.transform().as_ptr(),
-            );
-        }
+    /// Save the current contents of the window's framebuffer as a PNG
+    /// screenshot, e.g. from a render callback after drawing. See
+    /// [`save_png`](crate::core::save_png).
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        crate::core::save_png(&self.read_framebuffer(), path)
+    }
 
-        // instanced path uses attribute aInstanceXY → force u_offset = (0,0)
-        let off_loc = gl_get_uniform_location(mesh.shader.program(), "u_screen_offset");
-        if off_loc != -1 {
-            crate::core::engine::opengl::gl_uniform_2f(off_loc, 0.0, 0.0);
+    pub fn get_time(&self) -> f64 {
+        match self.embedded_epoch {
+            Some(epoch) => epoch.elapsed().as_secs_f64(),
+            None => glfw_get_time(),
         }
+    }
 
-        let scale_loc = gl_get_uniform_location(mesh.shader.program(), "u_scale");
-        if scale_loc != -1 {
-            gl_uniform_1f(scale_loc, mesh.scale());
-        }
+    /// Start the picking pass: subsequent draws render pick ids (set with
+    /// [`set_pick_id`](Self::set_pick_id)) into an offscreen buffer the size
+    /// of the viewport instead of drawing to the window.
+    pub fn begin_picking(&self) {
+        let (width, height) = self.viewport_size();
+        let mut state = self.picking.borrow_mut();
+        let buffer = state.buffer.get_or_insert_with(|| PickBuffer::new(width, height));
+        buffer.resize(width, height);
+        buffer.bind();
+        self.backend.clear(Color::from_rgba(0.0, 0.0, 0.0, 0.0));
+        gl_disable(GL_BLEND);
+        state.active = true;
+        state.current = None;
+    }
 
-        let rotation_loc = gl_get_uniform_location(mesh.shader.program(), "u_rotation");
-        if rotation_loc != -1 {
-            gl_uniform_1f(rotation_loc, mesh.rotation());
-        }
+    /// Id written by the following draws in the picking pass. Instanced
+    /// draws write `id + instance_index`.
+    pub fn set_pick_id(&self, id: PickId) {
+        self.picking.borrow_mut().current = Some(id);
+    }
 
-        let color_loc = gl_get_uniform_location(mesh.shader.program(), "geometryColor");
-        if color_loc != -1 {
-            if let Some(color) = mesh.color.as_ref() {
-                gl_uniform_4f(color_loc, color.red_value(), color.green_value(), color.blue_value(), color.alpha());
-            }
+    /// End the picking pass and restore drawing to the window.
+    pub fn end_picking(&self) {
+        let mut state = self.picking.borrow_mut();
+        if let Some(buffer) = &state.buffer {
+            buffer.unbind();
         }
+        state.active = false;
+        state.current = None;
+    }
 
-        // Also check for u_color (vec4 with alpha) - used by text shader
-        let color4_loc = gl_get_uniform_location(mesh.shader.program(), "u_color");
-        if color4_loc != -1 {
-            if let Some(color) = mesh.color.as_ref() {
-                gl_uniform_4f(color4_loc, color.red_value(), color.green_value(), color.blue_value(), color.alpha());
-            }
-        }
+    /// Id under `(x, y)`, in the window's pixel units, from the last picking pass, or
+    /// `None` for background, outside the window, or no pass yet.
+    pub fn pick(&self, x: f32, y: f32) -> Option<PickId> {
+        let state = self.picking.borrow();
+        let buffer = state.buffer.as_ref()?;
+        // Pixel units (Y down) to framebuffer pixels (Y up).
+        let (_, fb_h) = buffer.size();
+        let (px, py) = self.window_handle.to_framebuffer_pixels(x as f64, y as f64);
+        buffer.read(px.floor() as i32, fb_h - 1 - py.floor() as i32)
+    }
 
-        if let Some((dash, gap)) = mesh.dash_pattern {
-            let dash_loc = gl_get_uniform_location(mesh.shader.program(), "u_dash_length");
-            if dash_loc != -1 {
-                gl_uniform_1f(dash_loc, dash);
-            }
-            let gap_loc = gl_get_uniform_location(mesh.shader.program(), "u_gap_length");
-            if gap_loc != -1 {
-                gl_uniform_1f(gap_loc, gap);
-            }
+    /// The state of a draw of `mesh`, with `instances` for instanced draws.
+    fn draw_params(&self, mesh: &Mesh, instances: Option<i32>) -> DrawParams {
+        let state = self.picking.borrow();
+        let (px, py) = mesh.pivot_translation();
+        // Instanced draws take their positions from the aInstanceXY
+        // attribute, so the offset is only the pivot shift.
+        let (ox, oy) = if instances.is_some() { (0.0, 0.0) } else { mesh.screen_offset() };
+        DrawParams {
+            transform: self.surface_transform(mesh),
+            screen_offset: (ox + px, oy + py),
+            point_size: self.point_size.get(),
+            scale: mesh.scale(),
+            rotation: mesh.rotation(),
+            opacity: if state.active { 1.0 } else { mesh.opacity() },
+            color: mesh.color,
+            dash: mesh.dash_pattern.map(|(dash, gap)| (dash, gap, dash_offset(mesh, dash + gap, self.get_time()))),
+            texture: mesh.texture,
+            pick: state.active.then(|| state.current.unwrap_or(PickId(0))),
+            instances,
         }
+    }
 
-        if let Some(texture_id) = mesh.texture {
-            gl_active_texture(GL_TEXTURE0);
-            gl_bind_texture(GL_TEXTURE_2D, texture_id);
-        }
+    pub fn draw_mesh(&self, mesh: &Mesh) {
+        self.backend.draw(mesh, &self.draw_params(mesh, None));
+    }
 
-        gl_draw_arrays_instanced(
-            mesh.geometry.drawing_mode(),
-            0,
-            mesh.geometry.vertex_count(),
-            mesh.geometry.instance_count().max(0),
-        );
+    pub fn draw_mesh_instanced(&self, mesh: &Mesh) {
+        self.backend.draw(mesh, &self.draw_params(mesh, Some(mesh.geometry.instance_count())));
+    }
+}
 
-        if mesh.texture.is_some() {
-            gl_bind_texture(GL_TEXTURE_2D, 0);
-        }
+/// Current dash offset of `mesh`, wrapped into one dash cycle. Computed in
+/// `f64` so marching dashes stay smooth after the clock has run for hours.
+fn dash_offset(mesh: &Mesh, cycle: f32, time: f64) -> f32 {
+    let offset = mesh.dash_offset as f64 + mesh.dash_speed as f64 * time;
+    if cycle > 0.0 {
+        offset.rem_euclid(cycle as f64) as f32
+    } else {
+        offset as f32
     }
 }
@@ -0,0 +1,209 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Camera2D, WilhelmError};
+use crate::graphics2d::shapes::{Anchor, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// A saved layout: shapes with their styles and placement, and the camera.
+///
+/// Save with [`App::scene`](crate::core::App::scene) and [`save`](Self::save);
+/// restore with [`load`](Self::load) and
+/// [`App::load_scene`](crate::core::App::load_scene). Files are JSON, so a
+/// layout can also be written by hand or by another tool. Instance data,
+/// event handlers and editing state are not part of a scene. Image shapes
+/// are left out too, since they do not keep the path they were loaded
+/// from.
+///
+/// ```ignore
+/// app.scene().save("layout.json")?;
+/// // later
+/// app.load_scene(&Scene::load("layout.json")?)?;
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Scene {
+    #[serde(default)]
+    pub shapes: Vec<SceneShape>,
+    #[serde(default)]
+    pub camera: Option<Camera2D>,
+}
+
+/// One shape of a [`Scene`]. Fields missing from a file take the defaults
+/// of a freshly built shape.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SceneShape {
+    pub kind: ShapeKind,
+    #[serde(default)]
+    pub style: ShapeStyle,
+    #[serde(default = "default_anchor")]
+    pub anchor: Anchor,
+    /// `None` keeps the position the shape is built with (the first
+    /// vertex for lines and polylines, the origin otherwise).
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    #[serde(default)]
+    pub z_order: i32,
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default = "one")]
+    pub scale: f32,
+    #[serde(default = "visible")]
+    pub visible: bool,
+}
+
+fn default_anchor() -> Anchor {
+    Anchor::Default
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+fn visible() -> bool {
+    true
+}
+
+impl SceneShape {
+    /// Capture `shape`, with the colors and opacity it currently draws with.
+    pub fn from_shape(shape: &ShapeRenderable) -> Self {
+        let mut style = shape.style().clone();
+        style.fill = shape.fill_color().or(style.fill);
+        style.stroke_color = shape.stroke_color().or(style.stroke_color);
+        style.opacity = shape.opacity();
+        Self {
+            kind: shape.shape().clone(),
+            style,
+            anchor: shape.anchor(),
+            position: Some(shape.position()),
+            z_order: shape.z_order(),
+            rotation: shape.rotation(),
+            scale: shape.scale(),
+            visible: shape.is_visible(),
+        }
+    }
+
+    /// Build the renderable. Needs a current GL context. Panics if the
+    /// shape is invalid; see [`try_to_shape`](Self::try_to_shape).
+    pub fn to_shape(&self) -> ShapeRenderable {
+        self.try_to_shape().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Build the renderable, returning an error for invalid geometry, a
+    /// missing font or an `Image` kind, which has no path to load. Needs a
+    /// current GL context.
+    pub fn try_to_shape(&self) -> Result<ShapeRenderable, WilhelmError> {
+        let mut shape = ShapeRenderable::builder(self.kind.clone(), self.style.clone())
+            .anchor(self.anchor)
+            .z_order(self.z_order)
+            .try_build()?;
+        if let Some((x, y)) = self.position {
+            shape.set_position(x, y);
+        }
+        shape.set_rotation(self.rotation).set_scale(self.scale).set_visible(self.visible);
+        Ok(shape)
+    }
+}
+
+impl Scene {
+    /// Capture `shapes`, skipping image shapes, and the camera.
+    pub fn from_shapes(shapes: &[ShapeRenderable], camera: Option<&Camera2D>) -> Self {
+        let shapes = shapes.iter().filter(|shape| !matches!(shape.shape(), ShapeKind::Image(_)));
+        Self { shapes: shapes.map(SceneShape::from_shape).collect(), camera: camera.copied() }
+    }
+
+    /// Build every shape, in order. Needs a current GL context. Panics if
+    /// a shape is invalid; see [`try_to_shapes`](Self::try_to_shapes).
+    pub fn to_shapes(&self) -> Vec<ShapeRenderable> {
+        self.try_to_shapes().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Build every shape, in order, stopping at the first invalid one.
+    /// Needs a current GL context.
+    pub fn try_to_shapes(&self) -> Result<Vec<ShapeRenderable>, WilhelmError> {
+        self.shapes.iter().map(SceneShape::try_to_shape).collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scenes always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("invalid scene: {e}"))
+    }
+
+    /// Write the scene to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// Read a scene written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_json(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Color, Vec2};
+    use crate::graphics2d::shapes::{Circle, Rectangle};
+
+    #[test]
+    fn scenes_round_trip_through_json() {
+        let scene = Scene {
+            shapes: vec![
+                SceneShape {
+                    kind: ShapeKind::Rectangle(Rectangle::new(40.0, 20.0)),
+                    style: ShapeStyle::fill(Color::from_rgba8(255, 0, 0, 255)),
+                    anchor: Anchor::Center,
+                    position: Some((100.0, 50.0)),
+                    z_order: 2,
+                    rotation: 0.5,
+                    scale: 2.0,
+                    visible: false,
+                },
+                SceneShape {
+                    kind: ShapeKind::Circle(Circle::new(8.0)),
+                    style: ShapeStyle::default(),
+                    anchor: Anchor::Default,
+                    position: None,
+                    z_order: 0,
+                    rotation: 0.0,
+                    scale: 1.0,
+                    visible: true,
+                },
+            ],
+            camera: Some(Camera2D::new(Vec2::new(5.0, 6.0), 3.0, Vec2::new(800.0, 600.0))),
+        };
+        let loaded = Scene::from_json(&scene.to_json()).unwrap();
+        assert_eq!(loaded.shapes.len(), 2);
+        let rect = &loaded.shapes[0];
+        assert!(matches!(&rect.kind, ShapeKind::Rectangle(r) if r.width == 40.0 && r.height == 20.0));
+        assert_eq!(rect.style.fill.unwrap().red_value(), 1.0);
+        assert_eq!((rect.anchor, rect.position, rect.z_order), (Anchor::Center, Some((100.0, 50.0)), 2));
+        assert_eq!((rect.rotation, rect.scale, rect.visible), (0.5, 2.0, false));
+        assert_eq!(loaded.shapes[1].position, None);
+        assert_eq!(loaded.camera.unwrap().center(), Vec2::new(5.0, 6.0));
+    }
+
+    #[test]
+    fn missing_fields_take_defaults() {
+        let scene = Scene::from_json(r#"{"shapes": [{"kind": {"Circle": {"radius": 3.0}}}]}"#).unwrap();
+        let circle = &scene.shapes[0];
+        assert_eq!((circle.scale, circle.visible, circle.z_order), (1.0, true, 0));
+        assert_eq!(circle.style.opacity, 1.0);
+        assert!(scene.camera.is_none());
+        assert!(Scene::from_json("{\"shapes\": 3}").is_err());
+    }
+
+    #[test]
+    fn invalid_shapes_are_errors_not_panics() {
+        let image = Scene::from_json(r#"{"shapes": [{"kind": {"Image": {"width": 4.0, "height": 4.0}}}]}"#).unwrap();
+        assert!(matches!(image.try_to_shapes(), Err(WilhelmError::InvalidShape(_))));
+        let polyline = Scene::from_json(r#"{"shapes": [{"kind": {"Polyline": {"points": [[0.0, 0.0]]}}}]}"#).unwrap();
+        assert!(matches!(polyline.shapes[0].try_to_shape(), Err(WilhelmError::InvalidShape(_))));
+    }
+}
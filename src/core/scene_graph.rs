@@ -0,0 +1,330 @@
+//! Parent/child hierarchy of shapes addressed by [`NodeId`].
+
+use crate::core::{Renderable, Renderer};
+use crate::graphics2d::shapes::{GroupTransform, ShapeRenderable};
+
+/// Handle to a node in a [`SceneGraph`]. Ids are not reused, so the id of
+/// a removed node stays invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A node's transform relative to its parent, its visibility, and the
+/// shape drawn there, if any. Nodes without a shape only group and move
+/// their children.
+pub struct Node {
+    x: f32,
+    y: f32,
+    scale: f32,
+    rotation: f32,
+    visible: bool,
+    shape: Option<ShapeRenderable>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+impl Node {
+    fn new(shape: Option<ShapeRenderable>, parent: Option<NodeId>) -> Self {
+        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, visible: true, shape, parent, children: Vec::new() }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    pub fn set_scale(&mut self, scale: f32) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn set_rotation(&mut self, angle: f32) -> &mut Self {
+        self.rotation = angle;
+        self
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Hide or show the node and all its descendants.
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// The node's shape. Its own position, scale and rotation are relative
+    /// to the node.
+    pub fn shape(&self) -> Option<&ShapeRenderable> {
+        self.shape.as_ref()
+    }
+
+    pub fn shape_mut(&mut self) -> Option<&mut ShapeRenderable> {
+        self.shape.as_mut()
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Child nodes, in drawing order.
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    fn transform(&self) -> GroupTransform {
+        GroupTransform { x: self.x, y: self.y, scale: self.scale, rotation: self.rotation }
+    }
+}
+
+/// Shapes arranged in a tree, each inheriting position, scale, rotation
+/// and visibility from its parent node.
+///
+/// Unlike [`Group`](crate::graphics2d::shapes::Group), which owns its
+/// children, nodes live in the graph and are addressed by id, so they can
+/// be looked up, re-parented and removed after the tree is built. Moving a
+/// node moves everything below it:
+///
+/// ```ignore
+/// let mut graph = SceneGraph::new();
+/// let vehicle = graph.add_shape(None, body);
+/// let sensor = graph.add_shape(Some(vehicle), cone);
+/// graph.node_mut(sensor).unwrap().set_position(20.0, 0.0);
+///
+/// // each frame
+/// graph.node_mut(vehicle).unwrap().set_position(x, y).set_rotation(heading);
+/// graph.render(renderer);
+/// ```
+///
+/// Roots draw in insertion order, each followed by its subtree depth
+/// first, so children draw over their parent. As with `Group`,
+/// per-instance positions of instanced shapes are not transformed.
+#[derive(Default)]
+pub struct SceneGraph {
+    nodes: Vec<Option<Node>>,
+    roots: Vec<NodeId>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an empty node under `parent`, or as a root with `None`.
+    ///
+    /// # Panics
+    /// If `parent` is not in the graph.
+    pub fn add_node(&mut self, parent: Option<NodeId>) -> NodeId {
+        self.insert(Node::new(None, parent))
+    }
+
+    /// Add a node drawing `shape` under `parent`, or as a root with `None`.
+    ///
+    /// # Panics
+    /// If `parent` is not in the graph.
+    pub fn add_shape(&mut self, parent: Option<NodeId>, shape: ShapeRenderable) -> NodeId {
+        self.insert(Node::new(Some(shape), parent))
+    }
+
+    fn insert(&mut self, node: Node) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        match node.parent {
+            Some(parent) => self.node_mut(parent).expect("parent node is in the graph").children.push(id),
+            None => self.roots.push(id),
+        }
+        self.nodes.push(Some(node));
+        id
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.0).and_then(Option::as_ref)
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id.0).and_then(Option::as_mut)
+    }
+
+    /// Root nodes, in drawing order.
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// Number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Move `id` (with its subtree) under `parent`, or make it a root with
+    /// `None`. It is drawn after its new siblings and keeps its local
+    /// transform, so it jumps to the same offset from the new parent.
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> Result<(), String> {
+        if self.node(id).is_none() {
+            return Err(format!("node {} is not in the graph", id.0));
+        }
+        if let Some(parent) = parent {
+            if self.node(parent).is_none() {
+                return Err(format!("parent node {} is not in the graph", parent.0));
+            }
+            if self.ancestors(parent).any(|a| a == id) {
+                return Err(format!("node {} cannot be parented to its own descendant {}", id.0, parent.0));
+            }
+        }
+        self.detach(id);
+        match parent {
+            Some(parent) => self.nodes[parent.0].as_mut().unwrap().children.push(id),
+            None => self.roots.push(id),
+        }
+        self.nodes[id.0].as_mut().unwrap().parent = parent;
+        Ok(())
+    }
+
+    /// Remove `id` and its subtree, returning the removed shapes in
+    /// drawing order. Returns an empty `Vec` if the node is not in the graph.
+    pub fn remove(&mut self, id: NodeId) -> Vec<ShapeRenderable> {
+        if self.node(id).is_none() {
+            return Vec::new();
+        }
+        self.detach(id);
+        let mut shapes = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes[id.0].take() {
+                shapes.extend(node.shape);
+                stack.extend(node.children.into_iter().rev());
+            }
+        }
+        shapes
+    }
+
+    /// `id` and its ancestors, nearest first.
+    fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(Some(id), |&id| self.node(id).and_then(Node::parent))
+    }
+
+    /// Unlink `id` from its parent's children or from the roots.
+    fn detach(&mut self, id: NodeId) {
+        let siblings = match self.node(id).and_then(Node::parent) {
+            Some(parent) => &mut self.nodes[parent.0].as_mut().unwrap().children,
+            None => &mut self.roots,
+        };
+        siblings.retain(|&c| c != id);
+    }
+
+    fn world_transform(&self, id: NodeId) -> Option<GroupTransform> {
+        self.node(id)?;
+        let mut chain: Vec<NodeId> = self.ancestors(id).collect();
+        chain.reverse();
+        Some(chain.iter().fold(GroupTransform::IDENTITY, |world, &id| world.then(&self.nodes[id.0].as_ref().unwrap().transform())))
+    }
+
+    /// The node's origin in screen (or world) coordinates, after applying
+    /// every ancestor's transform.
+    pub fn world_position(&self, id: NodeId) -> Option<(f32, f32)> {
+        self.world_transform(id).map(|t| (t.x, t.y))
+    }
+
+    /// The node's scale multiplied by its ancestors'.
+    pub fn world_scale(&self, id: NodeId) -> Option<f32> {
+        self.world_transform(id).map(|t| t.scale)
+    }
+
+    /// The node's rotation plus its ancestors', in radians.
+    pub fn world_rotation(&self, id: NodeId) -> Option<f32> {
+        self.world_transform(id).map(|t| t.rotation)
+    }
+
+    /// Whether the node and all its ancestors are visible.
+    pub fn is_visible_in_tree(&self, id: NodeId) -> bool {
+        self.node(id).is_some() && self.ancestors(id).all(|id| self.nodes[id.0].as_ref().unwrap().visible)
+    }
+
+    fn render_node(&mut self, id: NodeId, parent: &GroupTransform, renderer: &Renderer) {
+        let Some(node) = self.nodes[id.0].as_mut().filter(|node| node.visible) else {
+            return;
+        };
+        let world = parent.then(&node.transform());
+        if let Some(shape) = &mut node.shape {
+            // Shapes store transforms relative to the node; swap in the
+            // world transform for this draw and restore it afterwards.
+            let (x, y) = shape.position();
+            let (scale, rotation) = (shape.scale(), shape.rotation());
+            let shape_world = world.then(&GroupTransform { x, y, scale, rotation });
+            shape.set_position(shape_world.x, shape_world.y).set_scale(shape_world.scale).set_rotation(shape_world.rotation);
+            shape.render(renderer);
+            shape.set_position(x, y).set_scale(scale).set_rotation(rotation);
+        }
+        for child in node.children.clone() {
+            self.render_node(child, &world, renderer);
+        }
+    }
+}
+
+impl Renderable for SceneGraph {
+    fn render(&mut self, renderer: &Renderer) {
+        for root in self.roots.clone() {
+            self.render_node(root, &GroupTransform::IDENTITY, renderer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4
+    }
+
+    #[test]
+    fn children_follow_their_parent() {
+        let mut graph = SceneGraph::new();
+        let vehicle = graph.add_node(None);
+        let sensor = graph.add_node(Some(vehicle));
+        graph.node_mut(sensor).unwrap().set_position(20.0, 0.0).set_rotation(0.5);
+        graph.node_mut(vehicle).unwrap().set_position(100.0, 50.0).set_scale(2.0).set_rotation(FRAC_PI_2);
+
+        assert!(close(graph.world_position(sensor).unwrap(), (100.0, 90.0)));
+        assert_eq!(graph.world_scale(sensor), Some(2.0));
+        assert!((graph.world_rotation(sensor).unwrap() - (FRAC_PI_2 + 0.5)).abs() < 1e-6);
+
+        graph.node_mut(vehicle).unwrap().set_visible(false);
+        assert!(!graph.is_visible_in_tree(sensor));
+    }
+
+    #[test]
+    fn reparenting_rejects_cycles_and_removal_takes_the_subtree() {
+        let mut graph = SceneGraph::new();
+        let a = graph.add_node(None);
+        let b = graph.add_node(Some(a));
+        let c = graph.add_node(Some(b));
+        let d = graph.add_node(None);
+
+        assert!(graph.set_parent(a, Some(c)).is_err());
+        graph.set_parent(b, Some(d)).unwrap();
+        assert!(graph.node(a).unwrap().children().is_empty());
+        assert_eq!(graph.node(d).unwrap().children(), &[b]);
+
+        graph.remove(d);
+        assert_eq!(graph.roots(), &[a]);
+        assert!(graph.node(b).is_none() && graph.node(c).is_none());
+        assert_eq!(graph.len(), 1);
+        assert!(graph.set_parent(a, Some(c)).is_err());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::core::engine::opengl::{
     GLuint, gl_attach_shader, gl_compile_shader, gl_create_fragment_shader,
     gl_create_geometry_shader, gl_create_program, gl_create_vertex_shader, gl_delete_program,
-    gl_delete_shader, gl_link_program, gl_shader_source, gl_use_program,
+    gl_delete_shader, gl_get_program_info_log, gl_get_program_link_status, gl_get_shader_compile_status,
+    gl_get_shader_info_log, gl_is_es, gl_link_program, gl_shader_source, gl_use_program,
 };
+use std::borrow::Cow;
 
 pub struct Shader {
     program: GLuint,
@@ -17,55 +19,58 @@ impl Drop for Shader {
 }
 
 impl Shader {
+    /// Compile and link a program. On an OpenGL ES context, GLSL 3.x
+    /// desktop sources are compiled as their GLSL ES 3.00 variant (see
+    /// [`glsl_es_variant`]); geometry shaders are not available there.
+    ///
+    /// Returns the driver's info log if a stage fails to compile or the
+    /// program fails to link.
     pub fn compile(
         vertex_src: &str,
         fragment_src: &str,
         geometry_src: Option<&str>,
     ) -> Result<Self, String> {
-        let program = gl_create_program();
-
-        let vertex_shader = gl_create_vertex_shader();
-        gl_shader_source(vertex_shader, vertex_src);
-        gl_compile_shader(vertex_shader);
-        gl_attach_shader(program, vertex_shader);
-
-        /*
-        if !gl_get_shader_compile_status(vertex_shader) {
-            return Err(gl_get_shader_info_log(vertex_shader));
-        }*/
-
-        let fragment_shader = gl_create_fragment_shader();
-        gl_shader_source(fragment_shader, fragment_src);
-        gl_compile_shader(fragment_shader);
-        gl_attach_shader(program, fragment_shader);
-
-        /*
-        if !gl_get_shader_compile_status(fragment_shader) {
-            return Err(gl_get_shader_info_log(fragment_shader));
-        }*/
-
-        let geometry_shader = if let Some(geometry_code) = geometry_src {
-            let shader = gl_create_geometry_shader();
-            gl_shader_source(shader, geometry_code);
-            gl_compile_shader(shader);
-            gl_attach_shader(program, shader);
-            Some(shader)
+        let (vertex_src, fragment_src) = if gl_is_es() {
+            if geometry_src.is_some() {
+                return Err("geometry shaders are not supported on OpenGL ES".to_string());
+            }
+            (glsl_es_variant(vertex_src), glsl_es_variant(fragment_src))
         } else {
-            None
+            (Cow::Borrowed(vertex_src), Cow::Borrowed(fragment_src))
         };
+        let mut stages = vec![
+            ("vertex", gl_create_vertex_shader(), vertex_src),
+            ("fragment", gl_create_fragment_shader(), fragment_src),
+        ];
+        if let Some(geometry_src) = geometry_src {
+            stages.push(("geometry", gl_create_geometry_shader(), Cow::Borrowed(geometry_src)));
+        }
+        let program = gl_create_program();
 
-        gl_link_program(program);
-
-        /*
-        if !gl_get_program_link_status(program) {
-            return Err(gl_get_program_info_log(program));
-        }*/
+        let mut error = None;
+        for (stage, shader, src) in &stages {
+            gl_shader_source(*shader, src);
+            gl_compile_shader(*shader);
+            if !gl_get_shader_compile_status(*shader) {
+                error = Some(format!("{stage} shader failed to compile: {}", gl_get_shader_info_log(*shader)));
+                break;
+            }
+            gl_attach_shader(program, *shader);
+        }
+        if error.is_none() {
+            gl_link_program(program);
+            if !gl_get_program_link_status(program) {
+                error = Some(format!("shader program failed to link: {}", gl_get_program_info_log(program)));
+            }
+        }
 
         // Delete shader objects after linking - they're no longer needed
-        gl_delete_shader(vertex_shader);
-        gl_delete_shader(fragment_shader);
-        if let Some(shader) = geometry_shader {
-            gl_delete_shader(shader);
+        for (_, shader, _) in &stages {
+            gl_delete_shader(*shader);
+        }
+        if let Some(error) = error {
+            gl_delete_program(program);
+            return Err(error);
         }
 
         Ok(Self { program })
@@ -79,3 +84,42 @@ impl Shader {
         self.program
     }
 }
+
+/// The GLSL ES 3.00 variant of a desktop GLSL 3.x shader: the `#version`
+/// line becomes `#version 300 es` followed by default precisions. The
+/// built-in shaders stick to the common subset, so nothing else changes.
+/// Sources without a desktop 3.x version line are returned unchanged.
+pub(crate) fn glsl_es_variant(src: &str) -> Cow<'_, str> {
+    let start = src.len() - src.trim_start().len();
+    let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+    let version = src[start..line_end].trim_end();
+    let desktop_3x = version
+        .strip_prefix("#version 3")
+        .is_some_and(|rest| !rest.trim_end().ends_with(" es"));
+    if !desktop_3x {
+        return Cow::Borrowed(src);
+    }
+    Cow::Owned(format!(
+        "#version 300 es\nprecision highp float;\nprecision highp int;{}",
+        &src[line_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_the_desktop_version_line() {
+        let es = glsl_es_variant("#version 330 core\nout vec4 FragColor;\n");
+        assert_eq!(es, "#version 300 es\nprecision highp float;\nprecision highp int;\nout vec4 FragColor;\n");
+        assert_eq!(glsl_es_variant("\n#version 330\nvoid main() {}").lines().next(), Some("#version 300 es"));
+    }
+
+    #[test]
+    fn leaves_es_and_unversioned_sources_alone() {
+        for src in ["#version 300 es\nvoid main() {}", "void main() {}", "#version 120\nvoid main() {}"] {
+            assert!(matches!(glsl_es_variant(src), Cow::Borrowed(_)));
+        }
+    }
+}
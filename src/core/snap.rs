@@ -0,0 +1,224 @@
+//! Snapping to a grid and to the edges of other shapes.
+//!
+//! A [`Snapper`] is consulted by drag interactions (see
+//! `ShapeEditor::set_snapper`) and can be queried directly for world
+//! positions under a [`Camera2D`]. `GridOverlay::from_snapper` draws a grid
+//! that matches its settings.
+
+use crate::core::camera::{Camera2D, Projection};
+use crate::core::engine::opengl::Vec2;
+use crate::core::spatial::Bounds;
+
+/// Result of a snap query.
+///
+/// `guide_x` / `guide_y` are set when the axis snapped to a shape edge
+/// (the vertical / horizontal line to draw as a guide); grid snaps leave
+/// them `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Snap {
+    pub x: f32,
+    pub y: f32,
+    pub guide_x: Option<f32>,
+    pub guide_y: Option<f32>,
+}
+
+/// Grid and edge snapping settings.
+///
+/// Edge snapping wins over the grid: an axis snaps to the nearest edge
+/// within `tolerance`, and otherwise to the grid (if one is set). With no
+/// grid and no edge in range the value passes through unchanged.
+///
+/// ```ignore
+/// let mut snapper = Snapper::grid(20.0);
+/// snapper.set_edges(others.iter().map(|s| s.bounds()));
+/// let snap = snapper.snap_point(cursor_x, cursor_y);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapper {
+    grid: Option<f32>,
+    origin: (f32, f32),
+    tolerance: f32,
+    edges_x: Vec<f32>,
+    edges_y: Vec<f32>,
+}
+
+impl Default for Snapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Snapper {
+    /// Edge snapping only, with a 6 unit tolerance.
+    pub fn new() -> Self {
+        Self { grid: None, origin: (0.0, 0.0), tolerance: 6.0, edges_x: Vec::new(), edges_y: Vec::new() }
+    }
+
+    /// Snap to a square grid of `size` units, plus edges.
+    pub fn grid(size: f32) -> Self {
+        let mut snapper = Self::new();
+        snapper.set_grid(Some(size));
+        snapper
+    }
+
+    /// Grid spacing, or `None` to disable grid snapping. Non-positive sizes
+    /// disable it too.
+    pub fn set_grid(&mut self, size: Option<f32>) -> &mut Self {
+        self.grid = size.filter(|s| *s > 0.0);
+        self
+    }
+
+    pub fn grid_size(&self) -> Option<f32> {
+        self.grid
+    }
+
+    /// Point the grid passes through (default `(0, 0)`).
+    pub fn set_grid_origin(&mut self, x: f32, y: f32) -> &mut Self {
+        self.origin = (x, y);
+        self
+    }
+
+    pub fn grid_origin(&self) -> (f32, f32) {
+        self.origin
+    }
+
+    /// Maximum distance at which edges attract, in the units of the
+    /// queried coordinates (pixels for screen-space shapes).
+    pub fn set_tolerance(&mut self, tolerance: f32) -> &mut Self {
+        self.tolerance = tolerance.max(0.0);
+        self
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// Replace the snap edges with the left/centre/right and
+    /// top/middle/bottom lines of each bounds.
+    pub fn set_edges(&mut self, bounds: impl IntoIterator<Item = Bounds>) {
+        self.clear_edges();
+        for (x0, y0, x1, y1) in bounds {
+            self.edges_x.extend([x0, (x0 + x1) * 0.5, x1]);
+            self.edges_y.extend([y0, (y0 + y1) * 0.5, y1]);
+        }
+    }
+
+    pub fn clear_edges(&mut self) {
+        self.edges_x.clear();
+        self.edges_y.clear();
+    }
+
+    /// Snap a point.
+    pub fn snap_point(&self, x: f32, y: f32) -> Snap {
+        self.snap_point_with_tolerance(x, y, self.tolerance)
+    }
+
+    /// Snap a world-space point, treating the tolerance as screen pixels
+    /// at the camera's current zoom.
+    pub fn snap_world(&self, world: Vec2, camera: &Camera2D) -> Snap {
+        self.snap_point_with_tolerance(world.x, world.y, self.tolerance / camera.scale())
+    }
+
+    /// Snap a screen-space point under `camera`, returning the snapped
+    /// position in world coordinates.
+    pub fn snap_screen(&self, screen: Vec2, camera: &Camera2D) -> Snap {
+        self.snap_world(camera.screen_to_world(screen), camera)
+    }
+
+    /// Snap a box being dragged: its left, centre or right (and top,
+    /// middle or bottom) is pulled to the nearest edge, otherwise its
+    /// top-left corner to the grid. `x` / `y` are the snapped top-left.
+    pub fn snap_bounds(&self, (x0, y0, x1, y1): Bounds) -> Snap {
+        let (sx, gx) = self.snap_span(x0, x1, &self.edges_x, self.origin.0);
+        let (sy, gy) = self.snap_span(y0, y1, &self.edges_y, self.origin.1);
+        Snap { x: sx, y: sy, guide_x: gx, guide_y: gy }
+    }
+
+    fn snap_point_with_tolerance(&self, x: f32, y: f32, tolerance: f32) -> Snap {
+        let (sx, gx) = match nearest_edge(&[x], &self.edges_x, tolerance) {
+            Some((shift, edge)) => (x + shift, Some(edge)),
+            None => (self.snap_to_grid(x, self.origin.0), None),
+        };
+        let (sy, gy) = match nearest_edge(&[y], &self.edges_y, tolerance) {
+            Some((shift, edge)) => (y + shift, Some(edge)),
+            None => (self.snap_to_grid(y, self.origin.1), None),
+        };
+        Snap { x: sx, y: sy, guide_x: gx, guide_y: gy }
+    }
+
+    fn snap_span(&self, min: f32, max: f32, edges: &[f32], origin: f32) -> (f32, Option<f32>) {
+        match nearest_edge(&[min, (min + max) * 0.5, max], edges, self.tolerance) {
+            Some((shift, edge)) => (min + shift, Some(edge)),
+            None => (self.snap_to_grid(min, origin), None),
+        }
+    }
+
+    fn snap_to_grid(&self, v: f32, origin: f32) -> f32 {
+        match self.grid {
+            Some(g) => ((v - origin) / g).round() * g + origin,
+            None => v,
+        }
+    }
+}
+
+/// The smallest `(edge - candidate, edge)` within `tolerance`.
+fn nearest_edge(candidates: &[f32], edges: &[f32], tolerance: f32) -> Option<(f32, f32)> {
+    candidates
+        .iter()
+        .flat_map(|&c| edges.iter().map(move |&e| (e - c, e)))
+        .filter(|(shift, _)| shift.abs() <= tolerance)
+        .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_rounds_to_nearest_line() {
+        let snapper = Snapper::grid(10.0);
+        assert_eq!(snapper.snap_point(14.0, 26.0), Snap { x: 10.0, y: 30.0, guide_x: None, guide_y: None });
+
+        let mut shifted = Snapper::grid(10.0);
+        shifted.set_grid_origin(3.0, 0.0);
+        assert_eq!(shifted.snap_point(14.0, 0.0).x, 13.0);
+    }
+
+    #[test]
+    fn edges_win_over_grid_within_tolerance() {
+        let mut snapper = Snapper::grid(10.0);
+        snapper.set_tolerance(5.0);
+        snapper.set_edges([(17.0, 100.0, 57.0, 140.0)]);
+        let snap = snapper.snap_point(19.0, 3.0);
+        assert_eq!((snap.x, snap.guide_x), (17.0, Some(17.0)));
+        // No horizontal edge near y = 3: grid.
+        assert_eq!((snap.y, snap.guide_y), (0.0, None));
+        // Out of tolerance: grid.
+        assert_eq!(snapper.snap_point(24.0, 0.0).x, 20.0);
+    }
+
+    #[test]
+    fn bounds_snap_any_side_or_centre() {
+        let mut snapper = Snapper::new();
+        snapper.set_edges([(100.0, 0.0, 200.0, 50.0)]);
+        // Right side 98 is 2 from the other box's left edge 100.
+        let snap = snapper.snap_bounds((78.0, 300.0, 98.0, 320.0));
+        assert_eq!((snap.x, snap.guide_x), (80.0, Some(100.0)));
+        // Centre 148 is 2 from the centre line 150.
+        let snap = snapper.snap_bounds((138.0, 300.0, 158.0, 320.0));
+        assert_eq!((snap.x, snap.guide_x), (140.0, Some(150.0)));
+        // Nothing nearby and no grid: unchanged.
+        assert_eq!(snapper.snap_bounds((500.0, 500.0, 510.0, 510.0)).x, 500.0);
+    }
+
+    #[test]
+    fn world_tolerance_scales_with_zoom() {
+        let mut snapper = Snapper::new();
+        snapper.set_tolerance(6.0);
+        snapper.set_edges([(10.0, 10.0, 10.0, 10.0)]);
+        let zoomed = Camera2D::new(Vec2::new(0.0, 0.0), 4.0, Vec2::new(800.0, 600.0));
+        // 6px at 4x zoom is 1.5 world units.
+        assert_eq!(snapper.snap_world(Vec2::new(11.0, 0.0), &zoomed).x, 10.0);
+        assert_eq!(snapper.snap_world(Vec2::new(12.0, 0.0), &zoomed).x, 12.0);
+    }
+}
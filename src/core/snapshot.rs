@@ -0,0 +1,185 @@
+//! Smoothing of instance positions that arrive in irregular batches, such
+//! as updates streamed over a network.
+
+use crate::core::engine::opengl::Vec2;
+use crate::graphics2d::shapes::ShapeRenderable;
+
+/// Default limit on how far past the latest snapshot positions are
+/// extrapolated, in seconds.
+const DEFAULT_MAX_EXTRAPOLATION: f64 = 0.25;
+
+/// Keeps the last two snapshots of instance positions and produces smooth
+/// per-frame positions between them.
+///
+/// Push each batch as it arrives with [`push`](Self::push), then call
+/// [`apply`](Self::apply) (or [`positions_at`](Self::positions_at)) every
+/// frame. Positions move along the line through the two snapshots at the
+/// speed implied by their timestamps:
+///
+/// - **Interpolation.** Between the two snapshots, positions are blended.
+///   Set [`set_delay`](Self::set_delay) to about one update interval to
+///   render slightly in the past, so frames almost always interpolate.
+/// - **Extrapolation.** Past the latest snapshot (a late or lost update),
+///   positions keep moving for up to
+///   [`set_max_extrapolation`](Self::set_max_extrapolation) seconds and
+///   then hold still until the next update.
+///
+/// Timestamps can be arrival times from
+/// [`Renderer::get_time`](crate::core::Renderer::get_time) or sender-side
+/// times, as long as the frame time uses the same clock. When the instance
+/// count changes between snapshots, instances present in both are
+/// interpolated and the rest take their latest position.
+///
+/// ```ignore
+/// // when a message arrives
+/// snapshots.push(renderer.get_time(), positions);
+/// // in on_render
+/// snapshots.apply(renderer.get_time(), &mut balls);
+/// balls.render(renderer);
+/// ```
+pub struct InstanceSnapshots {
+    previous: Option<(f64, Vec<Vec2>)>,
+    latest: Option<(f64, Vec<Vec2>)>,
+    delay: f64,
+    max_extrapolation: f64,
+    output: Vec<Vec2>,
+}
+
+impl Default for InstanceSnapshots {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InstanceSnapshots {
+    /// No delay, extrapolating at most 0.25 seconds.
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            latest: None,
+            delay: 0.0,
+            max_extrapolation: DEFAULT_MAX_EXTRAPOLATION,
+            output: Vec::new(),
+        }
+    }
+
+    /// Render this many seconds behind the frame time (default 0).
+    pub fn set_delay(&mut self, seconds: f64) -> &mut Self {
+        self.delay = seconds.max(0.0);
+        self
+    }
+
+    pub fn delay(&self) -> f64 {
+        self.delay
+    }
+
+    /// Longest time past the latest snapshot to keep extrapolating.
+    /// `0.0` disables extrapolation.
+    pub fn set_max_extrapolation(&mut self, seconds: f64) -> &mut Self {
+        self.max_extrapolation = seconds.max(0.0);
+        self
+    }
+
+    pub fn max_extrapolation(&self) -> f64 {
+        self.max_extrapolation
+    }
+
+    /// Record the positions received at `time`. A snapshot that is not
+    /// newer than the latest one replaces it, so out-of-order or duplicate
+    /// updates never run time backwards.
+    pub fn push(&mut self, time: f64, positions: Vec<Vec2>) {
+        match self.latest.take() {
+            Some((latest, _)) if time <= latest => self.latest = Some((latest, positions)),
+            Some(latest) => {
+                self.previous = Some(latest);
+                self.latest = Some((time, positions));
+            }
+            None => self.latest = Some((time, positions)),
+        }
+    }
+
+    /// Drop both snapshots.
+    pub fn clear(&mut self) {
+        self.previous = None;
+        self.latest = None;
+    }
+
+    /// Timestamp of the latest snapshot.
+    pub fn latest_time(&self) -> Option<f64> {
+        self.latest.as_ref().map(|(time, _)| *time)
+    }
+
+    /// Positions to draw at frame time `now`. Empty until the first push.
+    pub fn positions_at(&mut self, now: f64) -> &[Vec2] {
+        self.output.clear();
+        let Some((t1, latest)) = &self.latest else {
+            return &self.output;
+        };
+        match &self.previous {
+            Some((t0, previous)) if t1 > t0 => {
+                let now = (now - self.delay).min(t1 + self.max_extrapolation);
+                let f = ((now - t0) / (t1 - t0)).max(0.0) as f32;
+                self.output.extend(latest.iter().enumerate().map(|(i, b)| match previous.get(i) {
+                    Some(a) => Vec2::new(a.x + (b.x - a.x) * f, a.y + (b.y - a.y) * f),
+                    None => *b,
+                }));
+            }
+            _ => self.output.extend_from_slice(latest),
+        }
+        &self.output
+    }
+
+    /// Upload the positions for frame time `now` to `shape`'s instances.
+    /// Does nothing before the first snapshot.
+    pub fn apply(&mut self, now: f64, shape: &mut ShapeRenderable) {
+        if self.latest.is_none() {
+            return;
+        }
+        let positions = self.positions_at(now);
+        shape.set_instance_positions(positions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xs(snapshots: &mut InstanceSnapshots, now: f64) -> Vec<f32> {
+        snapshots.positions_at(now).iter().map(|p| p.x).collect()
+    }
+
+    #[test]
+    fn interpolates_and_extrapolates_with_limit() {
+        let mut snapshots = InstanceSnapshots::new();
+        assert!(xs(&mut snapshots, 0.0).is_empty());
+        snapshots.push(1.0, vec![Vec2::new(0.0, 0.0)]);
+        assert_eq!(xs(&mut snapshots, 5.0), vec![0.0]);
+        snapshots.push(2.0, vec![Vec2::new(10.0, 0.0)]);
+        assert_eq!(xs(&mut snapshots, 1.5), vec![5.0]);
+        assert_eq!(xs(&mut snapshots, 2.1), vec![11.0]);
+        // Capped at 0.25s past the latest snapshot.
+        assert_eq!(xs(&mut snapshots, 9.0), vec![12.5]);
+        // Before the previous snapshot: hold at it.
+        assert_eq!(xs(&mut snapshots, 0.0), vec![0.0]);
+    }
+
+    #[test]
+    fn delay_renders_in_the_past() {
+        let mut snapshots = InstanceSnapshots::new();
+        snapshots.set_delay(0.5);
+        snapshots.push(1.0, vec![Vec2::new(0.0, 0.0)]);
+        snapshots.push(2.0, vec![Vec2::new(10.0, 0.0)]);
+        assert_eq!(xs(&mut snapshots, 2.0), vec![5.0]);
+    }
+
+    #[test]
+    fn stale_snapshots_replace_latest_and_counts_may_differ() {
+        let mut snapshots = InstanceSnapshots::new();
+        snapshots.push(1.0, vec![Vec2::new(0.0, 0.0)]);
+        snapshots.push(2.0, vec![Vec2::new(10.0, 0.0)]);
+        snapshots.push(1.5, vec![Vec2::new(20.0, 0.0), Vec2::new(7.0, 7.0)]);
+        assert_eq!(snapshots.latest_time(), Some(2.0));
+        // The new instance has no history and takes its latest position.
+        assert_eq!(xs(&mut snapshots, 1.5), vec![10.0, 7.0]);
+    }
+}
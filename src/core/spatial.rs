@@ -0,0 +1,308 @@
+//! Quadtree spatial index for rectangle and point queries.
+//!
+//! Rectangles use the same `(min_x, min_y, max_x, max_y)` tuple as
+//! [`Camera2D::world_bounds`](super::Camera2D::world_bounds), so the visible
+//! area can be passed straight to [`SpatialIndex::query_rect`] for culling.
+
+/// Axis-aligned bounds as `(min_x, min_y, max_x, max_y)`.
+pub type Bounds = (f32, f32, f32, f32);
+
+const MAX_ITEMS: usize = 8;
+const MAX_DEPTH: usize = 8;
+
+/// A quadtree over axis-aligned bounds, for picking, viewport culling and
+/// label collision.
+///
+/// Each inserted item gets a handle that stays valid until it is removed;
+/// use it to [`update`](Self::update) the bounds of moving objects. Items
+/// that straddle a quadrant boundary stay in the parent node, and items
+/// outside the root bounds are kept at the root, so every item is always
+/// found by queries.
+///
+/// ```ignore
+/// let mut index = SpatialIndex::new((0.0, 0.0, 1920.0, 1080.0));
+/// for (i, shape) in shapes.iter().enumerate() {
+///     index.insert(shape.bounds(), i);
+/// }
+/// let visible = index.query_rect(camera.world_bounds());
+/// let under_cursor = index.query_point(mouse_x, mouse_y);
+/// ```
+pub struct SpatialIndex<T> {
+    root: Node,
+    entries: Vec<Option<Entry<T>>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+struct Entry<T> {
+    bounds: Bounds,
+    item: T,
+}
+
+struct Node {
+    bounds: Bounds,
+    depth: usize,
+    items: Vec<usize>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl<T> SpatialIndex<T> {
+    /// Create an empty index covering `bounds`. Items outside it are still
+    /// accepted but are not subdivided.
+    pub fn new(bounds: Bounds) -> Self {
+        Self { root: Node::new(bounds, 0), entries: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    /// Insert `item` with the given bounds and return its handle.
+    pub fn insert(&mut self, bounds: Bounds, item: T) -> usize {
+        let bounds = normalize(bounds);
+        let handle = match self.free.pop() {
+            Some(handle) => {
+                self.entries[handle] = Some(Entry { bounds, item });
+                handle
+            }
+            None => {
+                self.entries.push(Some(Entry { bounds, item }));
+                self.entries.len() - 1
+            }
+        };
+        self.root.insert(handle, bounds, &self.entries);
+        self.len += 1;
+        handle
+    }
+
+    /// Insert a point item (zero-size bounds).
+    pub fn insert_point(&mut self, x: f32, y: f32, item: T) -> usize {
+        self.insert((x, y, x, y), item)
+    }
+
+    /// Remove the item with `handle`, returning it. The handle may be reused
+    /// by a later insert.
+    pub fn remove(&mut self, handle: usize) -> Option<T> {
+        let entry = self.entries.get_mut(handle)?.take()?;
+        self.root.remove(handle, entry.bounds);
+        self.free.push(handle);
+        self.len -= 1;
+        Some(entry.item)
+    }
+
+    /// Move the item with `handle` to new bounds. Returns `false` if the
+    /// handle is not in the index.
+    pub fn update(&mut self, handle: usize, bounds: Bounds) -> bool {
+        let bounds = normalize(bounds);
+        let old = match self.entries.get_mut(handle) {
+            Some(Some(entry)) => std::mem::replace(&mut entry.bounds, bounds),
+            _ => return false,
+        };
+        self.root.remove(handle, old);
+        self.root.insert(handle, bounds, &self.entries);
+        true
+    }
+
+    pub fn get(&self, handle: usize) -> Option<&T> {
+        self.entries.get(handle)?.as_ref().map(|e| &e.item)
+    }
+
+    pub fn get_mut(&mut self, handle: usize) -> Option<&mut T> {
+        self.entries.get_mut(handle)?.as_mut().map(|e| &mut e.item)
+    }
+
+    /// Bounds the item with `handle` was inserted or last updated with.
+    pub fn bounds(&self, handle: usize) -> Option<Bounds> {
+        self.entries.get(handle)?.as_ref().map(|e| e.bounds)
+    }
+
+    /// Handles of all items whose bounds intersect `rect` (edges touching
+    /// counts), in ascending handle order.
+    pub fn query_rect_handles(&self, rect: Bounds) -> Vec<usize> {
+        let rect = normalize(rect);
+        let mut out = Vec::new();
+        self.root.query(rect, &self.entries, &mut out);
+        out.sort_unstable();
+        out
+    }
+
+    /// Items whose bounds intersect `rect`, in ascending handle order
+    /// (insertion order while nothing has been removed).
+    pub fn query_rect(&self, rect: Bounds) -> Vec<&T> {
+        self.query_rect_handles(rect)
+            .into_iter()
+            .filter_map(|h| self.get(h))
+            .collect()
+    }
+
+    /// Items whose bounds contain the point `(x, y)`.
+    ///
+    /// Bounds are conservative, so follow up with an exact test such as
+    /// `ShapeRenderable::contains` when shapes are not rectangular.
+    pub fn query_point(&self, x: f32, y: f32) -> Vec<&T> {
+        self.query_rect((x, y, x, y))
+    }
+
+    /// Whether any item intersects `rect`. Cheaper than `query_rect` for
+    /// collision checks such as label placement.
+    pub fn intersects_any(&self, rect: Bounds) -> bool {
+        self.root.any(normalize(rect), &self.entries)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all items, keeping the root bounds.
+    pub fn clear(&mut self) {
+        self.root = Node::new(self.root.bounds, 0);
+        self.entries.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+}
+
+impl Node {
+    fn new(bounds: Bounds, depth: usize) -> Self {
+        Self { bounds, depth, items: Vec::new(), children: None }
+    }
+
+    /// Index of the child quadrant fully containing `bounds`, if any.
+    fn quadrant(&self, bounds: Bounds) -> Option<usize> {
+        let children = self.children.as_ref()?;
+        children.iter().position(|child| contains(child.bounds, bounds))
+    }
+
+    fn insert<T>(&mut self, handle: usize, bounds: Bounds, entries: &[Option<Entry<T>>]) {
+        if let Some(q) = self.quadrant(bounds) {
+            self.children.as_mut().unwrap()[q].insert(handle, bounds, entries);
+            return;
+        }
+        self.items.push(handle);
+        if self.children.is_none() && self.items.len() > MAX_ITEMS && self.depth < MAX_DEPTH {
+            self.split(entries);
+        }
+    }
+
+    fn split<T>(&mut self, entries: &[Option<Entry<T>>]) {
+        let (x0, y0, x1, y1) = self.bounds;
+        let (mx, my) = ((x0 + x1) * 0.5, (y0 + y1) * 0.5);
+        let d = self.depth + 1;
+        self.children = Some(Box::new([
+            Node::new((x0, y0, mx, my), d),
+            Node::new((mx, y0, x1, my), d),
+            Node::new((x0, my, mx, y1), d),
+            Node::new((mx, my, x1, y1), d),
+        ]));
+        for handle in std::mem::take(&mut self.items) {
+            if let Some(entry) = &entries[handle] {
+                self.insert(handle, entry.bounds, entries);
+            }
+        }
+    }
+
+    fn remove(&mut self, handle: usize, bounds: Bounds) -> bool {
+        if let Some(pos) = self.items.iter().position(|&h| h == handle) {
+            self.items.swap_remove(pos);
+            return true;
+        }
+        match self.quadrant(bounds) {
+            Some(q) => self.children.as_mut().unwrap()[q].remove(handle, bounds),
+            None => false,
+        }
+    }
+
+    fn query<T>(&self, rect: Bounds, entries: &[Option<Entry<T>>], out: &mut Vec<usize>) {
+        for &handle in &self.items {
+            if let Some(entry) = &entries[handle] {
+                if intersects(entry.bounds, rect) {
+                    out.push(handle);
+                }
+            }
+        }
+        if let Some(children) = &self.children {
+            for child in children.iter().filter(|c| intersects(c.bounds, rect)) {
+                child.query(rect, entries, out);
+            }
+        }
+    }
+
+    fn any<T>(&self, rect: Bounds, entries: &[Option<Entry<T>>]) -> bool {
+        let here = self.items.iter().any(|&handle| {
+            entries[handle].as_ref().is_some_and(|e| intersects(e.bounds, rect))
+        });
+        here || self.children.as_ref().is_some_and(|children| {
+            children.iter().any(|c| intersects(c.bounds, rect) && c.any(rect, entries))
+        })
+    }
+}
+
+fn normalize((x0, y0, x1, y1): Bounds) -> Bounds {
+    (x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1))
+}
+
+fn intersects(a: Bounds, b: Bounds) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+fn contains(outer: Bounds, inner: Bounds) -> bool {
+    outer.0 <= inner.0 && outer.1 <= inner.1 && inner.2 <= outer.2 && inner.3 <= outer.3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(n: usize) -> SpatialIndex<usize> {
+        let mut index = SpatialIndex::new((0.0, 0.0, 100.0, 100.0));
+        for i in 0..n * n {
+            let (x, y) = ((i % n) as f32 * 10.0, (i / n) as f32 * 10.0);
+            index.insert((x, y, x + 5.0, y + 5.0), i);
+        }
+        index
+    }
+
+    #[test]
+    fn query_rect_matches_brute_force() {
+        let index = grid(10);
+        let rect = (12.0, 31.0, 47.0, 58.0);
+        let expected: Vec<usize> = (0..100)
+            .filter(|&i| {
+                let (x, y) = ((i % 10) as f32 * 10.0, (i / 10) as f32 * 10.0);
+                intersects((x, y, x + 5.0, y + 5.0), rect)
+            })
+            .collect();
+        let found: Vec<usize> = index.query_rect(rect).into_iter().copied().collect();
+        assert_eq!(found, expected);
+        assert!(index.root.children.is_some());
+    }
+
+    #[test]
+    fn query_point_hits_containing_items() {
+        let index = grid(10);
+        assert_eq!(index.query_point(42.0, 73.0), vec![&74]);
+        assert!(index.query_point(47.0, 73.0).is_empty());
+    }
+
+    #[test]
+    fn remove_and_update() {
+        let mut index = grid(4);
+        assert_eq!(index.remove(5), Some(5));
+        assert_eq!(index.remove(5), None);
+        assert!(index.query_point(12.0, 12.0).is_empty());
+        assert_eq!(index.len(), 15);
+
+        assert!(index.update(0, (90.0, 90.0, 95.0, 95.0)));
+        assert!(index.query_point(2.0, 2.0).is_empty());
+        assert_eq!(index.query_point(92.0, 92.0), vec![&0]);
+    }
+
+    #[test]
+    fn items_outside_root_are_found() {
+        let mut index = SpatialIndex::new((0.0, 0.0, 10.0, 10.0));
+        index.insert_point(-50.0, 500.0, "far");
+        assert_eq!(index.query_point(-50.0, 500.0), vec![&"far"]);
+        assert!(index.intersects_any((-60.0, 490.0, -40.0, 510.0)));
+        assert!(!index.intersects_any((0.0, 0.0, 10.0, 10.0)));
+    }
+}
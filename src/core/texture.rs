@@ -1,33 +1,193 @@
 use std::ffi::c_void;
 use crate::core::image::{Image};
-use crate::core::engine::opengl::{gl_bind_texture, gl_gen_texture, gl_generate_mipmap, gl_tex_image_2d, gl_tex_parameteri, GL_LINEAR, GL_LINEAR_MIPMAP_LINEAR, GL_REPEAT, GL_RGBA, GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S, GL_TEXTURE_WRAP_T, GL_UNSIGNED_BYTE};
+use crate::core::engine::opengl::{gl_bind_texture, gl_gen_texture, gl_generate_mipmap, gl_tex_image_2d, gl_tex_parameteri, GLint, GL_CLAMP_TO_EDGE, GL_LINEAR, GL_LINEAR_MIPMAP_LINEAR, GL_MIRRORED_REPEAT, GL_NEAREST, GL_NEAREST_MIPMAP_NEAREST, GL_REPEAT, GL_RGBA, GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S, GL_TEXTURE_WRAP_T, GL_UNSIGNED_BYTE};
+
+/// Texel filtering used when a texture is minified or magnified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFilter {
+    /// Pick the closest texel. Keeps pixel-art crisp.
+    Nearest,
+    /// Bilinear interpolation between neighbouring texels.
+    Linear,
+}
+
+/// Behaviour when texture coordinates fall outside `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureWrap {
+    /// Tile the texture (`GL_REPEAT`).
+    Repeat,
+    /// Tile the texture, mirroring every other repetition (`GL_MIRRORED_REPEAT`).
+    MirroredRepeat,
+    /// Stretch the edge texels (`GL_CLAMP_TO_EDGE`).
+    ClampToEdge,
+}
+
+/// Sampling configuration applied when a texture is uploaded.
+///
+/// The default matches the historical behaviour of
+/// [`generate_texture_from_image`]: linear filtering, repeat wrapping and
+/// mipmap generation.
+///
+/// ```ignore
+/// let sprite = TextureOptions::default()
+///     .filter(TextureFilter::Nearest)
+///     .mipmaps(false);
+/// let texture = generate_texture_from_image_with_options(&image, &sprite);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureOptions {
+    pub min_filter: TextureFilter,
+    pub mag_filter: TextureFilter,
+    pub wrap_s: TextureWrap,
+    pub wrap_t: TextureWrap,
+    pub mipmaps: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            mipmaps: true,
+        }
+    }
+}
+
+impl TextureOptions {
+    /// Nearest filtering without mipmaps, clamped at the edges. Suited to
+    /// pixel-art sprites and icons drawn at integer scales.
+    pub fn pixel_art() -> Self {
+        Self {
+            min_filter: TextureFilter::Nearest,
+            mag_filter: TextureFilter::Nearest,
+            wrap_s: TextureWrap::ClampToEdge,
+            wrap_t: TextureWrap::ClampToEdge,
+            mipmaps: false,
+        }
+    }
+
+    /// Set both the minification and magnification filter.
+    pub fn filter(mut self, filter: TextureFilter) -> Self {
+        self.min_filter = filter;
+        self.mag_filter = filter;
+        self
+    }
+
+    /// Set the wrap mode on both axes.
+    pub fn wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap_s = wrap;
+        self.wrap_t = wrap;
+        self
+    }
+
+    /// Enable or disable mipmap generation.
+    pub fn mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Set the wrap and filter parameters of the texture bound to
+    /// `GL_TEXTURE_2D`.
+    pub(crate) fn apply(&self) {
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, self.wrap_s.param());
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, self.wrap_t.param());
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, self.min_filter_param());
+        gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, self.mag_filter_param());
+    }
+
+    /// `GL_TEXTURE_MIN_FILTER` value. Mipmapped textures sample the nearest
+    /// or interpolated mip level to match the chosen filter.
+    fn min_filter_param(&self) -> GLint {
+        match (self.min_filter, self.mipmaps) {
+            (TextureFilter::Nearest, false) => GL_NEAREST,
+            (TextureFilter::Linear, false) => GL_LINEAR,
+            (TextureFilter::Nearest, true) => GL_NEAREST_MIPMAP_NEAREST,
+            (TextureFilter::Linear, true) => GL_LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    /// `GL_TEXTURE_MAG_FILTER` value. Magnification never uses mipmaps.
+    fn mag_filter_param(&self) -> GLint {
+        match self.mag_filter {
+            TextureFilter::Nearest => GL_NEAREST,
+            TextureFilter::Linear => GL_LINEAR,
+        }
+    }
+}
+
+impl TextureWrap {
+    fn param(self) -> GLint {
+        match self {
+            TextureWrap::Repeat => GL_REPEAT,
+            TextureWrap::MirroredRepeat => GL_MIRRORED_REPEAT,
+            TextureWrap::ClampToEdge => GL_CLAMP_TO_EDGE,
+        }
+    }
+}
 
 pub fn generate_texture_from_image(image: &Image) -> u32 {
+    generate_texture_from_image_with_options(image, &TextureOptions::default())
+}
+
+/// Upload an RGBA image to a new texture using the given sampling options.
+pub fn generate_texture_from_image_with_options(image: &Image, options: &TextureOptions) -> u32 {
+    generate_texture_from_rgba(image.width, image.height, &image.pixels, options)
+}
+
+/// Upload `width * height` RGBA8 pixels, rows top to bottom, to a new
+/// texture using the given sampling options.
+pub fn generate_texture_from_rgba(width: u32, height: u32, pixels: &[u8], options: &TextureOptions) -> u32 {
+    assert_eq!(pixels.len(), (width * height * 4) as usize, "pixel buffer does not match the size");
     let texture = gl_gen_texture();
     gl_bind_texture(GL_TEXTURE_2D, texture);
 
-    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_REPEAT);
-    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_REPEAT);
-    // set texture filtering parameters
-    gl_tex_parameteri(
-        GL_TEXTURE_2D,
-        GL_TEXTURE_MIN_FILTER,
-        GL_LINEAR_MIPMAP_LINEAR,
-    );
-    gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+    options.apply();
 
     gl_tex_image_2d(
         GL_TEXTURE_2D,
         0,
-        GL_RGBA as i32,
-        image.width as i32,
-        image.height as i32,
+        GL_RGBA,
+        width as i32,
+        height as i32,
         0,
         GL_RGBA as u32,
         GL_UNSIGNED_BYTE,
-        image.pixels.as_ptr() as *const c_void,
+        pixels.as_ptr() as *const c_void,
     );
-    gl_generate_mipmap(GL_TEXTURE_2D);
+    if options.mipmaps {
+        gl_generate_mipmap(GL_TEXTURE_2D);
+    }
 
     texture
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_legacy_parameters() {
+        let o = TextureOptions::default();
+        assert_eq!(o.min_filter_param(), GL_LINEAR_MIPMAP_LINEAR);
+        assert_eq!(o.mag_filter_param(), GL_LINEAR);
+        assert_eq!(o.wrap_s.param(), GL_REPEAT);
+        assert_eq!(o.wrap_t.param(), GL_REPEAT);
+    }
+
+    #[test]
+    fn pixel_art_uses_nearest_without_mipmaps() {
+        let o = TextureOptions::pixel_art();
+        assert_eq!(o.min_filter_param(), GL_NEAREST);
+        assert_eq!(o.mag_filter_param(), GL_NEAREST);
+        assert_eq!(o.wrap_s.param(), GL_CLAMP_TO_EDGE);
+    }
+
+    #[test]
+    fn nearest_with_mipmaps_selects_nearest_level() {
+        let o = TextureOptions::default().filter(TextureFilter::Nearest);
+        assert_eq!(o.min_filter_param(), GL_NEAREST_MIPMAP_NEAREST);
+        assert_eq!(o.mag_filter_param(), GL_NEAREST);
+    }
+}
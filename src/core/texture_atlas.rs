@@ -0,0 +1,260 @@
+//! Many images packed into one texture, for drawing icons and sprites in
+//! a single draw call.
+
+use std::collections::HashMap;
+
+use image::RgbaImage;
+
+use crate::core::{generate_texture_from_rgba, try_load_image, GpuTexture, TextureOptions, TextureWrap, WilhelmError};
+
+/// Handle to an image in a [`TextureAtlas`], returned when it is added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpriteId(usize);
+
+/// Where an image sits in the atlas texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasRegion {
+    /// UV coordinates (top-left)
+    pub uv_x: f32,
+    pub uv_y: f32,
+    /// UV size
+    pub uv_width: f32,
+    pub uv_height: f32,
+    /// Image size in pixels
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// `[u, v, width, height]`, as taken by
+    /// [`Geometry::update_instance_uv_rects`](crate::core::Geometry::update_instance_uv_rects).
+    pub fn uv_rect(&self) -> [f32; 4] {
+        [self.uv_x, self.uv_y, self.uv_width, self.uv_height]
+    }
+}
+
+/// Collects images for a [`TextureAtlas`]; see [`TextureAtlas::builder`].
+pub struct TextureAtlasBuilder {
+    images: Vec<(String, RgbaImage)>,
+    padding: u32,
+    max_size: u32,
+    options: TextureOptions,
+}
+
+impl TextureAtlasBuilder {
+    /// Transparent pixels between images, so filtering does not bleed
+    /// neighbours into each other (default 1).
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Largest width and height of the texture (default 4096).
+    pub fn max_size(mut self, max_size: u32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sampling options for the texture. The default filters linearly and
+    /// clamps at the edges, without mipmaps, which would blend neighbouring
+    /// images at small sizes.
+    pub fn options(mut self, options: TextureOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Add an image under `name`. A later image with the same name
+    /// replaces it in [`TextureAtlas::find`], but both are packed.
+    pub fn add(&mut self, name: &str, image: RgbaImage) -> SpriteId {
+        self.images.push((name.to_string(), image));
+        SpriteId(self.images.len() - 1)
+    }
+
+    /// Load an image file and add it, named by its path.
+    pub fn add_file(&mut self, path: &str) -> Result<SpriteId, WilhelmError> {
+        let image = try_load_image(path)?;
+        let image = RgbaImage::from_raw(image.width, image.height, image.pixels).expect("decoded image matches its size");
+        Ok(self.add(path, image))
+    }
+
+    /// Pack the images and upload them to a texture. Needs a current GL
+    /// context. Fails if they do not fit at the maximum size.
+    pub fn build(self) -> Result<TextureAtlas, String> {
+        let (size, pixels, regions) = self.compose()?;
+        let texture = generate_texture_from_rgba(size, size, &pixels, &self.options);
+        let names = self.images.iter().enumerate().map(|(i, (name, _))| (name.clone(), SpriteId(i))).collect();
+        Ok(TextureAtlas { texture: GpuTexture::from_raw(texture), size, regions, names })
+    }
+
+    /// The packed RGBA pixels of a `size` x `size` atlas and each image's
+    /// region.
+    fn compose(&self) -> Result<(u32, Vec<u8>, Vec<AtlasRegion>), String> {
+        let sizes: Vec<(u32, u32)> = self.images.iter().map(|(_, image)| image.dimensions()).collect();
+        let (size, corners) = pack(&sizes, self.padding, self.max_size).ok_or_else(|| {
+            format!("{} images do not fit in a {1}x{1} texture atlas", self.images.len(), self.max_size)
+        })?;
+        let mut pixels = vec![0; (size * size * 4) as usize];
+        let mut regions = Vec::with_capacity(self.images.len());
+        for ((_, image), &(x, y)) in self.images.iter().zip(&corners) {
+            let (width, height) = image.dimensions();
+            let row_bytes = width as usize * 4;
+            for (row, source) in image.as_raw().chunks_exact(row_bytes.max(1)).enumerate() {
+                let start = ((y as usize + row) * size as usize + x as usize) * 4;
+                pixels[start..start + row_bytes].copy_from_slice(source);
+            }
+            let size = size as f32;
+            regions.push(AtlasRegion {
+                uv_x: x as f32 / size,
+                uv_y: y as f32 / size,
+                uv_width: width as f32 / size,
+                uv_height: height as f32 / size,
+                width,
+                height,
+            });
+        }
+        Ok((size, pixels, regions))
+    }
+}
+
+/// Images packed into one square texture, each addressed by a
+/// [`SpriteId`] and drawn from its [`AtlasRegion`].
+///
+/// Drawing many icons from one atlas with a
+/// [`SpriteBatch`](crate::graphics2d::sprites::SpriteBatch) takes a single
+/// draw call, where an image shape per icon takes one each:
+///
+/// ```ignore
+/// let mut builder = TextureAtlas::builder();
+/// let airport = builder.add_file("icons/airport.png")?;
+/// let heliport = builder.add_file("icons/heliport.png")?;
+/// let atlas = Rc::new(builder.build()?);
+/// ```
+///
+/// The texture is deleted by
+/// [`flush_deleted_resources`](crate::core::flush_deleted_resources) after
+/// the atlas is dropped.
+pub struct TextureAtlas {
+    texture: GpuTexture,
+    size: u32,
+    regions: Vec<AtlasRegion>,
+    names: HashMap<String, SpriteId>,
+}
+
+impl TextureAtlas {
+    pub fn builder() -> TextureAtlasBuilder {
+        TextureAtlasBuilder {
+            images: Vec::new(),
+            padding: 1,
+            max_size: 4096,
+            options: TextureOptions::default().wrap(TextureWrap::ClampToEdge).mipmaps(false),
+        }
+    }
+
+    /// Build an atlas from image files with the default options, each named
+    /// by its path.
+    pub fn from_files(paths: &[&str]) -> Result<Self, String> {
+        let mut builder = Self::builder();
+        for path in paths {
+            builder.add_file(path)?;
+        }
+        builder.build()
+    }
+
+    pub fn texture_id(&self) -> u32 {
+        self.texture.id()
+    }
+
+    /// Width and height of the texture.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Number of images in the atlas.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// The image added under `name`.
+    pub fn find(&self, name: &str) -> Option<SpriteId> {
+        self.names.get(name).copied()
+    }
+
+    /// Where `sprite` sits in the texture, or `None` if it belongs to
+    /// another atlas with more images.
+    pub fn region(&self, sprite: SpriteId) -> Option<AtlasRegion> {
+        self.regions.get(sprite.0).copied()
+    }
+}
+
+/// Place rectangles of `sizes` on shelves, tallest first and `padding`
+/// pixels apart, in the smallest power-of-two square up to `max_size`
+/// that holds them. Returns the square's size and each top-left corner.
+fn pack(sizes: &[(u32, u32)], padding: u32, max_size: u32) -> Option<(u32, Vec<(u32, u32)>)> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].1));
+    let area: u64 = sizes.iter().map(|&(w, h)| (w + padding) as u64 * (h + padding) as u64).sum();
+    let largest = sizes.iter().map(|&(w, h)| w.max(h)).max().unwrap_or(1);
+    let mut size = ((area as f64).sqrt().ceil() as u32).max(largest).max(1).next_power_of_two();
+    while size <= max_size {
+        if let Some(corners) = shelve(sizes, &order, padding, size) {
+            return Some((size, corners));
+        }
+        size *= 2;
+    }
+    None
+}
+
+/// Corners of `sizes`, placed in `order` on rows of a `size` square, or
+/// `None` if they overflow it.
+fn shelve(sizes: &[(u32, u32)], order: &[usize], padding: u32, size: u32) -> Option<Vec<(u32, u32)>> {
+    let mut corners = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for &i in order {
+        let (width, height) = sizes[i];
+        if x + width > size {
+            x = 0;
+            y += row_height + padding;
+            row_height = 0;
+        }
+        if width > size || y + height > size {
+            return None;
+        }
+        corners[i] = (x, y);
+        x += width + padding;
+        row_height = row_height.max(height);
+    }
+    Some(corners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_tallest_first_into_the_smallest_square() {
+        let (size, corners) = pack(&[(10, 4), (6, 8), (6, 8)], 1, 64).unwrap();
+        assert_eq!(size, 16);
+        assert_eq!(corners, vec![(0, 9), (0, 0), (7, 0)]);
+        assert!(pack(&[(40, 40), (40, 40)], 0, 64).is_none());
+        assert_eq!(pack(&[], 1, 64), Some((1, vec![])));
+    }
+
+    #[test]
+    fn compose_copies_pixels_and_computes_regions() {
+        let mut builder = TextureAtlas::builder();
+        let red = builder.add("red", RgbaImage::from_pixel(2, 1, image::Rgba([255, 0, 0, 255])));
+        let blue = builder.add("blue", RgbaImage::from_pixel(1, 2, image::Rgba([0, 0, 255, 255])));
+        let (size, pixels, regions) = builder.compose().unwrap();
+        assert_eq!(size, 4);
+        // Blue is taller, so it goes first; red follows after a pixel of padding.
+        assert_eq!(&pixels[0..4], &[0, 0, 255, 255]);
+        assert_eq!(&pixels[16..20], &[0, 0, 255, 255]);
+        assert_eq!(&pixels[8..16], &[255, 0, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(regions[red.0].uv_rect(), [0.5, 0.0, 0.5, 0.25]);
+        assert_eq!((regions[blue.0].width, regions[blue.0].height), (1, 2));
+    }
+}
@@ -0,0 +1,114 @@
+//! Frame-clock timers used by [`App::set_timeout`](super::App::set_timeout)
+//! and [`App::set_interval`](super::App::set_interval).
+
+/// Handle returned when scheduling a timer, used to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+struct Timer<T> {
+    id: TimerId,
+    due: f64,
+    interval: Option<f64>,
+    task: T,
+}
+
+/// Timers keyed on a monotonically increasing clock in seconds.
+///
+/// Timers fire from [`fire_due`](Self::fire_due), which the app calls once
+/// per frame, so resolution is one frame. An interval that falls behind
+/// (a long frame) fires once and then resumes from the current time rather
+/// than firing a burst to catch up.
+pub(crate) struct Timers<T> {
+    timers: Vec<Timer<T>>,
+    next_id: u64,
+}
+
+impl<T> Timers<T> {
+    pub(crate) fn new() -> Self {
+        Self { timers: Vec::new(), next_id: 0 }
+    }
+
+    /// Schedule `task` at `now + delay`, repeating every `interval` seconds
+    /// if given.
+    pub(crate) fn add(&mut self, now: f64, delay: f64, interval: Option<f64>, task: T) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer { id, due: now + delay.max(0.0), interval, task });
+        id
+    }
+
+    /// Remove a timer. Returns `false` if it already fired (for one-shots)
+    /// or was cancelled.
+    pub(crate) fn cancel(&mut self, id: TimerId) -> bool {
+        let before = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.timers.len() != before
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Run every timer due at `now`, earliest first. One-shot timers are
+    /// removed; intervals are rescheduled.
+    pub(crate) fn fire_due(&mut self, now: f64, mut run: impl FnMut(&mut T)) {
+        let mut due: Vec<usize> = (0..self.timers.len()).filter(|&i| self.timers[i].due <= now).collect();
+        due.sort_by(|&a, &b| self.timers[a].due.total_cmp(&self.timers[b].due));
+        for &i in &due {
+            let timer = &mut self.timers[i];
+            run(&mut timer.task);
+            if let Some(interval) = timer.interval {
+                timer.due += interval.max(f64::EPSILON);
+                if timer.due <= now {
+                    timer.due = now + interval;
+                }
+            }
+        }
+        self.timers.retain(|t| t.interval.is_some() || t.due > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fired(timers: &mut Timers<&'static str>, now: f64) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        timers.fire_due(now, |name| out.push(*name));
+        out
+    }
+
+    #[test]
+    fn timeout_fires_once() {
+        let mut timers = Timers::new();
+        timers.add(0.0, 1.0, None, "t");
+        assert!(fired(&mut timers, 0.5).is_empty());
+        assert_eq!(fired(&mut timers, 1.0), vec!["t"]);
+        assert!(fired(&mut timers, 2.0).is_empty());
+        assert!(timers.is_empty());
+    }
+
+    #[test]
+    fn interval_repeats_without_bursting() {
+        let mut timers = Timers::new();
+        timers.add(0.0, 1.0, Some(1.0), "i");
+        assert_eq!(fired(&mut timers, 1.0), vec!["i"]);
+        assert!(fired(&mut timers, 1.5).is_empty());
+        assert_eq!(fired(&mut timers, 2.0), vec!["i"]);
+        // A 5 second stall fires once, then resumes a second later.
+        assert_eq!(fired(&mut timers, 7.0), vec!["i"]);
+        assert!(fired(&mut timers, 7.5).is_empty());
+        assert_eq!(fired(&mut timers, 8.0), vec!["i"]);
+    }
+
+    #[test]
+    fn due_timers_fire_in_order_and_cancel() {
+        let mut timers = Timers::new();
+        timers.add(0.0, 0.3, None, "late");
+        let dropped = timers.add(0.0, 0.2, None, "cancelled");
+        timers.add(0.0, 0.1, None, "early");
+        assert!(timers.cancel(dropped));
+        assert!(!timers.cancel(dropped));
+        assert_eq!(fired(&mut timers, 1.0), vec!["early", "late"]);
+    }
+}
@@ -1,26 +1,138 @@
 use std::cell::Cell;
+#[cfg(not(feature = "winit"))]
 use std::ffi::c_void;
 use std::rc::Rc;
-use crate::core::Color;
-use crate::core::engine::opengl::{gl_clear_color, gl_viewport};
-use crate::core::engine::glfw::{GLFWwindow, glfw_create_window, glfw_destroy_window, glfw_get_window_content_scale, glfw_get_window_user_pointer, glfw_poll_events, glfw_set_cursor_pos_callback, glfw_set_key_callback, glfw_set_mouse_button_callback, glfw_set_scroll_callback, glfw_set_window_size_callback, glfw_set_window_user_pointer, glfw_swap_buffers, glfw_window_should_close};
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle as rwh;
+use crate::core::{flush_deleted_resources, report_leaks, Color};
+use crate::core::input_record::InputEvent;
+use crate::core::engine::opengl::gl_clear_color;
+#[cfg(not(feature = "winit"))]
+use crate::core::engine::opengl::gl_viewport;
+#[cfg(not(feature = "winit"))]
+use crate::core::engine::glfw::{GLFW_VISIBLE, GLFWwindow, glfw_create_window, glfw_init, glfw_window_hint, glfw_destroy_window, glfw_get_framebuffer_size, glfw_get_window_content_scale, glfw_get_window_size, glfw_get_window_user_pointer, glfw_poll_events, glfw_set_cursor_pos_callback, glfw_set_key_callback, glfw_set_mouse_button_callback, glfw_set_scroll_callback, glfw_set_window_size_callback, glfw_set_window_user_pointer, glfw_swap_buffers, glfw_window_should_close};
+#[cfg(feature = "winit")]
+use crate::core::engine::winit::{WinitEvent, WinitWindow};
 
 
 /// Shared inner state that both Window and WindowHandle can access.
 struct InnerWindow {
+    /// Window size in the window system's coordinates: logical pixels on
+    /// macOS, Wayland and winit, physical pixels on Windows and X11.
     width: Cell<i32>,
     height: Cell<i32>,
+    /// Drawable size in physical pixels.
+    framebuffer_size: Cell<(i32, i32)>,
+    /// Physical pixels per logical pixel.
+    content_scale: Cell<f32>,
+    pixel_units: Cell<PixelUnits>,
     background_color: Cell<Color>,
+    /// In [`pixel_units`](Self::pixel_units).
+    cursor_position: Cell<(f64, f64)>,
 }
 
+/// The units of window sizes, cursor positions and the 2D coordinate
+/// system shapes are drawn in.
+///
+/// On high-DPI displays one logical pixel covers several physical ones
+/// (2 on a Retina Mac, 1.5 on Windows at 150% scaling). Logical units keep
+/// shapes the same apparent size on every display; physical units address
+/// each framebuffer pixel, e.g. for pixel-exact images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PixelUnits {
+    #[default]
+    Logical,
+    Physical,
+}
+
+/// An OS window with an OpenGL context, and its input callbacks.
+///
+/// Backed by GLFW, or by winit and glutin with the `winit` feature. Both
+/// report the same GLFW key codes, mouse buttons and modifier bits to the
+/// callbacks.
 pub struct Window {
     inner: Rc<InnerWindow>,
+    #[cfg(not(feature = "winit"))]
     glfw_window: *const GLFWwindow,
+    #[cfg(feature = "winit")]
+    backend: WinitWindow,
     on_resize: Option<Box<dyn FnMut(i32, i32)>>,
+    on_framebuffer_resize: Option<Box<dyn FnMut(i32, i32)>>,
     on_scroll: Option<Box<dyn FnMut(f64, f64)>>,
     on_cursor_position: Option<Box<dyn FnMut(f64, f64)>>,
     on_key: Option<Box<dyn FnMut(i32, i32, i32, i32)>>,
     on_mouse_button: Option<Box<dyn FnMut(i32, i32, i32)>>,
+    /// Button events queued for `App`'s shape event routing; only recorded
+    /// once routing is enabled so plain windows don't accumulate them.
+    mouse_button_events: Option<Vec<MouseButtonEvent>>,
+    /// Live input captured for `App::record_input`.
+    input_log: Option<Vec<InputEvent>>,
+    /// Live and replayed input queued for a UI overlay.
+    ui_input: Option<Vec<InputEvent>>,
+    /// While replaying, live input is dropped so only recorded events reach
+    /// the callbacks.
+    replaying: bool,
+}
+
+/// A mouse button press or release and the cursor position it happened at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MouseButtonEvent {
+    pub button: i32,
+    pub action: i32,
+    pub mods: i32,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl InnerWindow {
+    fn new(width: i32, height: i32, background_color: Color) -> Rc<Self> {
+        Rc::new(InnerWindow {
+            width: Cell::new(width),
+            height: Cell::new(height),
+            framebuffer_size: Cell::new((width, height)),
+            content_scale: Cell::new(1.0),
+            pixel_units: Cell::new(PixelUnits::default()),
+            background_color: Cell::new(background_color),
+            cursor_position: Cell::new((0.0, 0.0)),
+        })
+    }
+
+    /// Physical pixels per unit of [`pixel_units`](Self::pixel_units).
+    fn pixels_per_unit(&self) -> f32 {
+        match self.pixel_units.get() {
+            PixelUnits::Logical if self.content_scale.get() > 0.0 => self.content_scale.get(),
+            _ => 1.0,
+        }
+    }
+
+    /// Framebuffer size in [`pixel_units`](Self::pixel_units).
+    fn size(&self) -> (i32, i32) {
+        let (width, height) = self.framebuffer_size.get();
+        let scale = self.pixels_per_unit();
+        ((width as f32 / scale).round() as i32, (height as f32 / scale).round() as i32)
+    }
+
+    /// Convert a position in window system coordinates to
+    /// [`pixel_units`](Self::pixel_units).
+    fn to_pixel_units(&self, x: f64, y: f64) -> (f64, f64) {
+        let (fb_width, fb_height) = self.framebuffer_size.get();
+        let (width, height) = (self.width.get(), self.height.get());
+        if width <= 0 || height <= 0 {
+            return (x, y);
+        }
+        let scale = self.pixels_per_unit() as f64;
+        (x * fb_width as f64 / width as f64 / scale, y * fb_height as f64 / height as f64 / scale)
+    }
+
+    fn to_framebuffer_pixels(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = self.pixels_per_unit() as f64;
+        (x * scale, y * scale)
+    }
+
+    fn framebuffer_to_pixel_units(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = self.pixels_per_unit() as f64;
+        (x / scale, y / scale)
+    }
 }
 
 /// Cheap, cloneable handle to query window state without owning the window.
@@ -29,10 +141,25 @@ pub struct WindowHandle {
     inner: Rc<InnerWindow>,
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_viewport_resized(_window: *const GLFWwindow, width: i32, height: i32) {
     gl_viewport(0, 0, width, height);
+    // Fires on resizes and on moves to a monitor with another scale, so the
+    // resize callback is reported from here, in pixel units.
+    let user_ptr = glfw_get_window_user_pointer(_window);
+    if !user_ptr.is_null() {
+        unsafe {
+            let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
+            window_ref.inner.framebuffer_size.set((width, height));
+            window_ref.inner.content_scale.set(glfw_get_window_content_scale(_window).0);
+            window_ref._on_framebuffer_resize(width, height);
+            let (width, height) = window_ref.inner.size();
+            window_ref._on_resize(width, height);
+        }
+    }
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_window_resized_callback(_window: *const GLFWwindow, width: i32, height: i32){
     let user_ptr = glfw_get_window_user_pointer(_window);
     if !user_ptr.is_null() {
@@ -40,31 +167,33 @@ extern "C" fn _on_window_resized_callback(_window: *const GLFWwindow, width: i32
             let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
             window_ref.inner.width.set(width);
             window_ref.inner.height.set(height);
-            window_ref._on_resize(width, height);
         }
     }
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_scroll_callback(_window: *const GLFWwindow, x_offset: f64, y_offset: f64) {
     let user_ptr = glfw_get_window_user_pointer(_window);
     if !user_ptr.is_null() {
         unsafe {
             let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
-            window_ref._on_scroll(x_offset, y_offset);
+            window_ref.live_input(InputEvent::Scroll { x_offset, y_offset });
         }
     }
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_cursor_position_callback(_window: *const GLFWwindow, x_pos: f64, y_pos: f64) {
     let user_ptr = glfw_get_window_user_pointer(_window);
     if !user_ptr.is_null() {
         unsafe {
             let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
-            window_ref._on_cursor_position(x_pos, y_pos);
+            window_ref.live_input(InputEvent::CursorPosition { x: x_pos, y: y_pos });
         }
     }
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_key_callback(
     _window: *const GLFWwindow,
     key: i32,
@@ -76,11 +205,12 @@ extern "C" fn _on_key_callback(
     if !user_ptr.is_null() {
         unsafe {
             let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
-            window_ref._on_key(key, scancode, action, mods);
+            window_ref.live_input(InputEvent::Key { key, scancode, action, mods });
         }
     }
 }
 
+#[cfg(not(feature = "winit"))]
 extern "C" fn _on_mouse_button_callback(
     _window: *const GLFWwindow,
     button: i32,
@@ -91,12 +221,13 @@ extern "C" fn _on_mouse_button_callback(
     if !user_ptr.is_null() {
         unsafe {
             let window_ref: &mut Window = &mut *(user_ptr as *mut Window);
-            window_ref._on_mouse_button(button, action, mods);
+            window_ref.live_input(InputEvent::MouseButton { button, action, mods });
         }
     }
 }
 
 impl Window {
+    #[cfg(not(feature = "winit"))]
     pub fn new(title: &str, width: i32, height: i32, background_color: Color) -> Box<Self> {
         let glfw_window = glfw_create_window(title, width, height, Some(_on_viewport_resized));
         // hook callbacks
@@ -106,22 +237,78 @@ impl Window {
         glfw_set_key_callback(glfw_window, Some(_on_key_callback));
         glfw_set_mouse_button_callback(glfw_window, Some(_on_mouse_button_callback));
 
-        let inner = Rc::new(InnerWindow {
-            width: Cell::new(width),
-            height: Cell::new(height),
-            background_color: Cell::new(background_color),
-        });
+        let mut window = Self::with_backend(glfw_window, width, height, background_color);
+        // With GLFW_SCALE_TO_MONITOR the window may be larger than asked.
+        let (mut width, mut height) = (width, height);
+        glfw_get_window_size(glfw_window, &mut width, &mut height);
+        window.inner.width.set(width);
+        window.inner.height.set(height);
+        window.inner.framebuffer_size.set(glfw_get_framebuffer_size(glfw_window));
+        window.inner.content_scale.set(window.content_scale().0);
+        glfw_set_window_user_pointer(glfw_window, &mut *window as *mut _ as *mut c_void);
+        window
+    }
 
-        let mut window = Box::new(Window {
+    #[cfg(feature = "winit")]
+    pub fn new(title: &str, width: i32, height: i32, background_color: Color) -> Box<Self> {
+        Self::with_backend(WinitWindow::new(title, width, height, true), width, height, background_color)
+    }
+
+    /// Create a hidden window for rendering without showing anything on
+    /// screen, e.g. in golden-image tests. It still needs a display
+    /// connection: on a headless Linux machine run under `xvfb-run`.
+    #[cfg(not(feature = "winit"))]
+    pub fn headless(width: i32, height: i32, background_color: Color) -> Box<Self> {
+        glfw_init();
+        glfw_window_hint(GLFW_VISIBLE, 0);
+        let window = Self::new("wilhelm headless", width, height, background_color);
+        glfw_window_hint(GLFW_VISIBLE, 1);
+        window
+    }
+
+    /// Create a hidden window for rendering without showing anything on
+    /// screen, e.g. in golden-image tests. It still needs a display
+    /// connection: on a headless Linux machine run under `xvfb-run`.
+    #[cfg(feature = "winit")]
+    pub fn headless(width: i32, height: i32, background_color: Color) -> Box<Self> {
+        Self::with_backend(WinitWindow::new("wilhelm headless", width, height, false), width, height, background_color)
+    }
+
+    /// Read the framebuffer size and scale after creation and resizes.
+    #[cfg(feature = "winit")]
+    fn update_framebuffer(&self) {
+        self.inner.framebuffer_size.set(self.backend.framebuffer_size());
+        self.inner.content_scale.set(self.backend.content_scale().0);
+    }
+
+    fn with_backend(
+        #[cfg(not(feature = "winit"))] glfw_window: *const GLFWwindow,
+        #[cfg(feature = "winit")] backend: WinitWindow,
+        width: i32,
+        height: i32,
+        background_color: Color,
+    ) -> Box<Self> {
+        let inner = InnerWindow::new(width, height, background_color);
+
+        let window = Box::new(Window {
+            #[cfg(not(feature = "winit"))]
             glfw_window,
+            #[cfg(feature = "winit")]
+            backend,
             inner,
             on_resize: None,
+            on_framebuffer_resize: None,
             on_scroll: None,
             on_cursor_position: None,
             on_key: None,
             on_mouse_button: None,
+            mouse_button_events: None,
+            input_log: None,
+            ui_input: None,
+            replaying: false,
         });
-        glfw_set_window_user_pointer(glfw_window, &mut *window as *mut _ as *mut c_void);
+        #[cfg(feature = "winit")]
+        window.update_framebuffer();
         gl_clear_color(background_color.red_value(), background_color.green_value(), background_color.blue_value(), 1.0);
         window
     }
@@ -133,36 +320,121 @@ impl Window {
         }
     }
 
+    /// Width in [`pixel_units`](Self::pixel_units).
     pub fn width(&self)->i32{
-        self.inner.width.get()
+        self.inner.size().0
     }
 
+    /// Height in [`pixel_units`](Self::pixel_units).
     pub fn height(&self)->i32{
-        self.inner.height.get()
+        self.inner.size().1
     }
 
+    /// Size of the drawable surface in physical pixels.
+    pub fn framebuffer_size(&self) -> (i32, i32) {
+        self.inner.framebuffer_size.get()
+    }
+
+    /// Units of sizes, cursor positions and 2D drawing coordinates.
+    pub fn pixel_units(&self) -> PixelUnits {
+        self.inner.pixel_units.get()
+    }
+
+    /// Choose the units of sizes, cursor positions and 2D drawing
+    /// coordinates; logical by default. The resize callback is not run.
+    pub fn set_pixel_units(&self, units: PixelUnits) {
+        self.handle().set_pixel_units(units);
+    }
+
+    #[cfg(not(feature = "winit"))]
     pub fn content_scale(&self)->(f32, f32){
         glfw_get_window_content_scale(self.glfw_window)
     }
 
-    /// Returns the raw GLFW window pointer for FFI integration (e.g., ImGui).
+    #[cfg(feature = "winit")]
+    pub fn content_scale(&self) -> (f32, f32) {
+        self.backend.content_scale()
+    }
+
+    /// Convert a point in [`pixel_units`](Self::pixel_units), such as a
+    /// cursor position, to framebuffer pixels (same origin, Y down).
+    pub fn to_framebuffer_pixels(&self, x: f64, y: f64) -> (f64, f64) {
+        self.inner.to_framebuffer_pixels(x, y)
+    }
+
+    /// Convert a point in framebuffer pixels to
+    /// [`pixel_units`](Self::pixel_units).
+    pub fn framebuffer_to_pixel_units(&self, x: f64, y: f64) -> (f64, f64) {
+        self.inner.framebuffer_to_pixel_units(x, y)
+    }
+
+    /// Last cursor position reported by the window, in
+    /// [`pixel_units`](Self::pixel_units).
+    pub fn cursor_position(&self) -> (f64, f64) {
+        self.inner.cursor_position.get()
+    }
+
+    /// Returns the raw GLFW window pointer for FFI integration. For Dear
+    /// ImGui, the `imgui` feature's `ui::imgui::ImguiOverlay`
+    /// needs no pointer.
+    #[cfg(not(feature = "winit"))]
     pub fn glfw_window_ptr(&self) -> *const GLFWwindow {
         self.glfw_window
     }
 
+    /// Change the color the window is cleared to, from the next frame on.
+    pub fn set_background_color(&self, color: Color) {
+        self.inner.background_color.set(color);
+    }
+
+    pub fn background_color(&self) -> Color {
+        self.inner.background_color.get()
+    }
+
     pub fn clear_color(&self) {
         gl_clear_color(self.inner.background_color.get().red_value(), self.inner.background_color.get().green_value(), self.inner.background_color.get().blue_value(), 1.0);
     }
+    #[cfg(not(feature = "winit"))]
     pub fn window_should_close(&self) -> bool {
         glfw_window_should_close(self.glfw_window)
     }
+    #[cfg(not(feature = "winit"))]
     pub fn swap_buffers(&self) {
         glfw_swap_buffers(self.glfw_window);
     }
-    pub fn poll_events(&self) {
+    /// Process pending window events, running the callbacks.
+    #[cfg(not(feature = "winit"))]
+    pub fn poll_events(&mut self) {
         glfw_poll_events();
     }
 
+    #[cfg(feature = "winit")]
+    pub fn window_should_close(&self) -> bool {
+        self.backend.should_close()
+    }
+    #[cfg(feature = "winit")]
+    pub fn swap_buffers(&self) {
+        self.backend.swap_buffers();
+    }
+    /// Process pending window events, running the callbacks.
+    #[cfg(feature = "winit")]
+    pub fn poll_events(&mut self) {
+        for event in self.backend.poll_events() {
+            match event {
+                WinitEvent::Resized(width, height) => {
+                    self.inner.width.set(width);
+                    self.inner.height.set(height);
+                    self.update_framebuffer();
+                    let (fb_width, fb_height) = self.inner.framebuffer_size.get();
+                    self._on_framebuffer_resize(fb_width, fb_height);
+                    let (width, height) = self.inner.size();
+                    self._on_resize(width, height);
+                }
+                WinitEvent::Input(event) => self.live_input(event),
+            }
+        }
+    }
+
     pub fn on_resize<F>(&mut self, f: F)
     where
         F: FnMut(i32, i32) + 'static,
@@ -170,6 +442,16 @@ impl Window {
         self.on_resize = Some(Box::new(f));
     }
 
+    /// Run `f` with the framebuffer size in physical pixels whenever it
+    /// changes, including when the window moves to a monitor with another
+    /// scale. Runs before the [`on_resize`](Self::on_resize) callback.
+    pub fn on_framebuffer_resize<F>(&mut self, f: F)
+    where
+        F: FnMut(i32, i32) + 'static,
+    {
+        self.on_framebuffer_resize = Some(Box::new(f));
+    }
+
     pub fn on_scroll<F>(&mut self, f: F)
     where
         F: FnMut(f64, f64) + 'static,
@@ -204,12 +486,19 @@ impl Window {
         }
     }
 
+    fn _on_framebuffer_resize(&mut self, width: i32, height: i32) {
+        if let Some(callback) = &mut self.on_framebuffer_resize {
+            callback(width, height);
+        }
+    }
+
     fn _on_scroll(&mut self, x_offset: f64, y_offset: f64) {
         if let Some(callback) = &mut self.on_scroll {
             callback(x_offset, y_offset);
         }
     }
     fn _on_cursor_position(&mut self, x_pos: f64, y_pos: f64) {
+        self.inner.cursor_position.set((x_pos, y_pos));
         if let Some(callback) = &mut self.on_cursor_position {
             callback(x_pos, y_pos);
         }
@@ -221,7 +510,81 @@ impl Window {
         }
     }
 
+    /// Start queueing mouse button events for [`take_mouse_button_events`](Self::take_mouse_button_events).
+    pub(crate) fn record_mouse_button_events(&mut self) {
+        self.mouse_button_events.get_or_insert_with(Vec::new);
+    }
+
+    /// Drain the button events queued since the last call.
+    pub(crate) fn take_mouse_button_events(&mut self) -> Vec<MouseButtonEvent> {
+        self.mouse_button_events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Start capturing live input for [`take_input_events`](Self::take_input_events).
+    pub(crate) fn record_input_events(&mut self) {
+        self.input_log.get_or_insert_with(Vec::new);
+    }
+
+    /// Drain the input captured since the last call.
+    pub(crate) fn take_input_events(&mut self) -> Vec<InputEvent> {
+        self.input_log.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Start queueing all delivered input, live or replayed, for
+    /// [`take_ui_input_events`](Self::take_ui_input_events).
+    #[cfg(any(feature = "egui", feature = "imgui"))]
+    pub(crate) fn record_ui_input_events(&mut self) {
+        self.ui_input.get_or_insert_with(Vec::new);
+    }
+
+    /// Drain the input queued for the UI overlay since the last call.
+    #[cfg(any(feature = "egui", feature = "imgui"))]
+    pub(crate) fn take_ui_input_events(&mut self) -> Vec<InputEvent> {
+        self.ui_input.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Ignore live input (except resizes) while replaying a recording.
+    pub(crate) fn set_replaying(&mut self, replaying: bool) {
+        self.replaying = replaying;
+    }
+
+    /// Deliver a recorded event as if GLFW had reported it.
+    pub(crate) fn inject_input(&mut self, event: InputEvent) {
+        self.dispatch_input(event);
+    }
+
+    fn live_input(&mut self, event: InputEvent) {
+        if self.replaying {
+            return;
+        }
+        if let Some(log) = &mut self.input_log {
+            log.push(event);
+        }
+        self.dispatch_input(event);
+    }
+
+    fn dispatch_input(&mut self, mut event: InputEvent) {
+        // Recordings keep window system coordinates; everything past here
+        // sees pixel units.
+        if let InputEvent::CursorPosition { x, y } = &mut event {
+            (*x, *y) = self.inner.to_pixel_units(*x, *y);
+        }
+        if let Some(queue) = &mut self.ui_input {
+            queue.push(event);
+        }
+        match event {
+            InputEvent::CursorPosition { x, y } => self._on_cursor_position(x, y),
+            InputEvent::Scroll { x_offset, y_offset } => self._on_scroll(x_offset, y_offset),
+            InputEvent::Key { key, scancode, action, mods } => self._on_key(key, scancode, action, mods),
+            InputEvent::MouseButton { button, action, mods } => self._on_mouse_button(button, action, mods),
+        }
+    }
+
     fn _on_mouse_button(&mut self, button: i32, action: i32, mods: i32) {
+        if let Some(events) = &mut self.mouse_button_events {
+            let (x, y) = self.inner.cursor_position.get();
+            events.push(MouseButtonEvent { button, action, mods, x, y });
+        }
         if let Some(callback) = &mut self.on_mouse_button {
             callback(button, action, mods);
         }
@@ -230,6 +593,11 @@ impl Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
+        // Delete what was dropped while the context is still current, then
+        // report what never was (debug builds only).
+        flush_deleted_resources();
+        report_leaks();
+        #[cfg(not(feature = "winit"))]
         if !self.glfw_window.is_null() {
             glfw_destroy_window(self.glfw_window);
         }
@@ -237,18 +605,210 @@ impl Drop for Window {
 }
 
 impl WindowHandle {
+    /// State for a drawing surface owned by someone else, such as a host
+    /// application's GL widget.
+    pub(crate) fn detached(width: i32, height: i32, background_color: Color) -> Self {
+        WindowHandle { inner: InnerWindow::new(width, height, background_color) }
+    }
+
+    /// Set the drawing surface size, in physical pixels.
+    pub(crate) fn set_size(&self, width: i32, height: i32) {
+        self.inner.width.set(width);
+        self.inner.height.set(height);
+        self.inner.framebuffer_size.set((width, height));
+    }
+
+    /// Report the drawing surface as `width` x `height` pixels, in physical
+    /// units, while `f` runs, e.g. while drawing into a render target; the
+    /// window's own size and units are restored afterwards.
+    pub(crate) fn with_surface_size<R>(&self, width: i32, height: i32, f: impl FnOnce() -> R) -> R {
+        let size = self.inner.framebuffer_size.replace((width, height));
+        let units = self.inner.pixel_units.replace(PixelUnits::Physical);
+        let result = f();
+        self.inner.framebuffer_size.set(size);
+        self.inner.pixel_units.set(units);
+        result
+    }
+
+    pub(crate) fn set_content_scale(&self, scale: f32) {
+        self.inner.content_scale.set(scale);
+    }
+
+    /// Size in [`pixel_units`](Self::pixel_units): the extent of the 2D
+    /// coordinate system shapes are drawn in.
     #[inline]
     pub fn size(&self) -> (i32, i32) {
-        (self.inner.width.get(), self.inner.height.get())
+        self.inner.size()
     }
     #[inline]
     pub fn width(&self) -> i32 {
-        self.inner.width.get()
+        self.inner.size().0
     }
     #[inline]
     pub fn height(&self) -> i32 {
-        self.inner.height.get()
+        self.inner.size().1
+    }
+    /// Size of the drawable surface in physical pixels.
+    #[inline]
+    pub fn framebuffer_size(&self) -> (i32, i32) {
+        self.inner.framebuffer_size.get()
+    }
+    /// Physical pixels per logical pixel.
+    #[inline]
+    pub fn content_scale(&self) -> f32 {
+        self.inner.content_scale.get()
+    }
+    #[inline]
+    pub fn pixel_units(&self) -> PixelUnits {
+        self.inner.pixel_units.get()
+    }
+    /// Choose the units of sizes, cursor positions and 2D drawing
+    /// coordinates; logical by default.
+    pub fn set_pixel_units(&self, units: PixelUnits) {
+        // Keep the last cursor position, converted to the new units.
+        let (x, y) = self.inner.cursor_position.get();
+        let old_scale = self.inner.pixels_per_unit() as f64;
+        self.inner.pixel_units.set(units);
+        let scale = old_scale / self.inner.pixels_per_unit() as f64;
+        self.inner.cursor_position.set((x * scale, y * scale));
+    }
+    /// Convert a point in [`pixel_units`](Self::pixel_units), such as a
+    /// cursor position, to framebuffer pixels (same origin, Y down). Exact
+    /// under fractional scales, unlike ratios of the rounded sizes.
+    #[inline]
+    pub fn to_framebuffer_pixels(&self, x: f64, y: f64) -> (f64, f64) {
+        self.inner.to_framebuffer_pixels(x, y)
+    }
+    /// Convert a point in framebuffer pixels to
+    /// [`pixel_units`](Self::pixel_units).
+    #[inline]
+    pub fn framebuffer_to_pixel_units(&self, x: f64, y: f64) -> (f64, f64) {
+        self.inner.framebuffer_to_pixel_units(x, y)
     }
     #[inline]
     pub fn background_color(&self) -> Color {self.inner.background_color.get()}
+    /// Last cursor position reported by the window, in
+    /// [`pixel_units`](Self::pixel_units).
+    #[inline]
+    pub fn cursor_position(&self) -> (f64, f64) {
+        self.inner.cursor_position.get()
+    }
+}
+
+/// The native window, for handing to other graphics and UI crates (wgpu
+/// surfaces, native dialogs, video players).
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+impl rwh::HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<rwh::WindowHandle<'_>, rwh::HandleError> {
+        use std::num::NonZeroIsize;
+        use std::ptr::NonNull;
+        use crate::core::engine::glfw::{GLFW_PLATFORM_COCOA, GLFW_PLATFORM_WAYLAND, GLFW_PLATFORM_WIN32, GLFW_PLATFORM_X11, glfw_get_cocoa_view, glfw_get_platform, glfw_get_wayland_window, glfw_get_win32_window, glfw_get_x11_window};
+
+        let unavailable = rwh::HandleError::Unavailable;
+        let raw: rwh::RawWindowHandle = match glfw_get_platform() {
+            GLFW_PLATFORM_WIN32 => {
+                let hwnd = NonZeroIsize::new(glfw_get_win32_window(self.glfw_window) as isize).ok_or(unavailable)?;
+                rwh::Win32WindowHandle::new(hwnd).into()
+            }
+            GLFW_PLATFORM_COCOA => {
+                let view = NonNull::new(glfw_get_cocoa_view(self.glfw_window)).ok_or(unavailable)?;
+                rwh::AppKitWindowHandle::new(view).into()
+            }
+            GLFW_PLATFORM_X11 => match glfw_get_x11_window(self.glfw_window) {
+                0 => return Err(unavailable),
+                window => rwh::XlibWindowHandle::new(window as _).into(),
+            },
+            GLFW_PLATFORM_WAYLAND => {
+                let surface = NonNull::new(glfw_get_wayland_window(self.glfw_window)).ok_or(unavailable)?;
+                rwh::WaylandWindowHandle::new(surface).into()
+            }
+            _ => return Err(rwh::HandleError::NotSupported),
+        };
+        // SAFETY: the native window lives as long as this `Window`.
+        Ok(unsafe { rwh::WindowHandle::borrow_raw(raw) })
+    }
+}
+
+/// The display connection the window belongs to.
+#[cfg(all(feature = "raw-window-handle", not(feature = "winit")))]
+impl rwh::HasDisplayHandle for Window {
+    fn display_handle(&self) -> Result<rwh::DisplayHandle<'_>, rwh::HandleError> {
+        use std::ptr::NonNull;
+        use crate::core::engine::glfw::{GLFW_PLATFORM_COCOA, GLFW_PLATFORM_WAYLAND, GLFW_PLATFORM_WIN32, GLFW_PLATFORM_X11, glfw_get_platform, glfw_get_wayland_display, glfw_get_x11_display};
+
+        let raw: rwh::RawDisplayHandle = match glfw_get_platform() {
+            GLFW_PLATFORM_WIN32 => rwh::WindowsDisplayHandle::new().into(),
+            GLFW_PLATFORM_COCOA => rwh::AppKitDisplayHandle::new().into(),
+            GLFW_PLATFORM_X11 => rwh::XlibDisplayHandle::new(NonNull::new(glfw_get_x11_display()), 0).into(),
+            GLFW_PLATFORM_WAYLAND => {
+                let display = NonNull::new(glfw_get_wayland_display()).ok_or(rwh::HandleError::Unavailable)?;
+                rwh::WaylandDisplayHandle::new(display).into()
+            }
+            _ => return Err(rwh::HandleError::NotSupported),
+        };
+        // SAFETY: GLFW keeps the display connection open until it terminates.
+        Ok(unsafe { rwh::DisplayHandle::borrow_raw(raw) })
+    }
+}
+
+/// The native window, for handing to other graphics and UI crates (wgpu
+/// surfaces, native dialogs, video players).
+#[cfg(feature = "winit")]
+impl rwh::HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<rwh::WindowHandle<'_>, rwh::HandleError> {
+        self.backend.os_window().window_handle()
+    }
+}
+
+/// The display connection the window belongs to.
+#[cfg(feature = "winit")]
+impl rwh::HasDisplayHandle for Window {
+    fn display_handle(&self) -> Result<rwh::DisplayHandle<'_>, rwh::HandleError> {
+        self.backend.os_window().display_handle()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(width: i32, height: i32, framebuffer_size: (i32, i32), content_scale: f32) -> Rc<InnerWindow> {
+        let inner = InnerWindow::new(width, height, Color::black());
+        inner.framebuffer_size.set(framebuffer_size);
+        inner.content_scale.set(content_scale);
+        inner
+    }
+
+    #[test]
+    fn retina_sizes_and_cursor_follow_pixel_units() {
+        // macOS reports the window and cursor in logical pixels.
+        let inner = window(800, 600, (1600, 1200), 2.0);
+        assert_eq!(inner.size(), (800, 600));
+        assert_eq!(inner.to_pixel_units(100.0, 50.0), (100.0, 50.0));
+        inner.pixel_units.set(PixelUnits::Physical);
+        assert_eq!(inner.size(), (1600, 1200));
+        assert_eq!(inner.to_pixel_units(100.0, 50.0), (200.0, 100.0));
+    }
+
+    #[test]
+    fn scaled_windows_display_draws_in_logical_pixels() {
+        // Windows at 150% reports the window and cursor in physical pixels.
+        let inner = window(1200, 900, (1200, 900), 1.5);
+        assert_eq!(inner.size(), (800, 600));
+        assert_eq!(inner.to_pixel_units(300.0, 150.0), (200.0, 100.0));
+        inner.pixel_units.set(PixelUnits::Physical);
+        assert_eq!(inner.size(), (1200, 900));
+        assert_eq!(inner.to_pixel_units(300.0, 150.0), (300.0, 150.0));
+    }
+
+    #[test]
+    fn framebuffer_conversion_is_exact_at_fractional_scales() {
+        // 1001 physical pixels at 125% round to 801 logical ones.
+        let inner = window(1001, 751, (1001, 751), 1.25);
+        assert_eq!(inner.size(), (801, 601));
+        assert_eq!(inner.to_framebuffer_pixels(800.0, 400.0), (1000.0, 500.0));
+        assert_eq!(inner.framebuffer_to_pixel_units(1000.0, 500.0), (800.0, 400.0));
+        inner.pixel_units.set(PixelUnits::Physical);
+        assert_eq!(inner.to_framebuffer_pixels(800.0, 400.0), (800.0, 400.0));
+    }
 }
@@ -0,0 +1,167 @@
+//! Draw [`bevy_ecs`] entities through instanced batches, enabled with the
+//! `ecs` feature.
+//!
+//! Entities with a [`Position`], a [`Shape`] and a [`Style`] are gathered
+//! each frame by [`EcsRenderer::sync`]: all entities with equal shapes and
+//! styles (fill aside) become one instanced [`ShapeRenderable`], with each
+//! entity's fill as its instance color. Positions are window coordinates,
+//! like the instance positions of any shape.
+//!
+//! ```ignore
+//! use wilhelm_renderer::ecs::{bevy_ecs::world::World, EcsRenderer, Position, Shape, Style};
+//!
+//! let mut world = World::new();
+//! world.spawn((Position(Vec2::new(10.0, 20.0)), Shape(ShapeKind::Circle(Circle::new(4.0))), Style(ShapeStyle::fill(Color::red()))));
+//! let mut ecs = EcsRenderer::new();
+//! app.on_render(move |renderer, _| {
+//!     // step the simulation systems, then
+//!     ecs.sync(&mut world);
+//!     ecs.render(renderer);
+//! });
+//! ```
+
+pub use bevy_ecs;
+
+use bevy_ecs::component::Component;
+use bevy_ecs::world::World;
+
+use crate::core::{Color, Renderable, Renderer, Vec2};
+use crate::graphics2d::shapes::{ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Where an entity is drawn, in window coordinates.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Position(pub Vec2);
+
+/// What an entity is drawn as.
+#[derive(Component, Clone, PartialEq)]
+pub struct Shape(pub ShapeKind);
+
+/// How an entity is drawn. The fill may differ between entities of one
+/// batch; every other field splits batches.
+#[derive(Component, Clone, PartialEq)]
+pub struct Style(pub ShapeStyle);
+
+/// Entities sharing a shape and batch style, and their renderable once
+/// built.
+struct Batch {
+    shape: ShapeKind,
+    style: ShapeStyle,
+    positions: Vec<Vec2>,
+    colors: Vec<Color>,
+    /// The instanced renderable and its instance capacity.
+    renderable: Option<(ShapeRenderable, usize)>,
+}
+
+impl Batch {
+    fn matches(&self, shape: &ShapeKind, style: &ShapeStyle) -> bool {
+        self.style == *style && self.shape == *shape
+    }
+}
+
+/// Keeps one instanced renderable per distinct shape and style among the
+/// entities of a [`World`].
+#[derive(Default)]
+pub struct EcsRenderer {
+    batches: Vec<Batch>,
+}
+
+impl EcsRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gather the drawable entities of `world` and upload their positions
+    /// and colors. Batches are built when a new shape or style appears,
+    /// rebuilt when they outgrow their capacity and dropped once empty.
+    pub fn sync(&mut self, world: &mut World) {
+        gather(world, &mut self.batches);
+        for batch in &mut self.batches {
+            let count = batch.positions.len();
+            if batch.renderable.as_ref().is_none_or(|(_, capacity)| *capacity < count) {
+                let capacity = count.next_power_of_two();
+                let mut shape = ShapeRenderable::from_shape(batch.shape.clone(), batch.style.clone());
+                shape.create_multiple_instances(capacity);
+                batch.renderable = Some((shape, capacity));
+            }
+            let (shape, _) = batch.renderable.as_mut().unwrap();
+            shape.set_instance_positions(&batch.positions);
+            if !batch.colors.is_empty() {
+                shape.set_instance_colors(&batch.colors);
+            }
+        }
+    }
+
+    /// Number of instanced renderables, i.e. draw calls per frame.
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+}
+
+impl Renderable for EcsRenderer {
+    /// Draw the batches as of the last [`sync`](Self::sync).
+    fn render(&mut self, renderer: &Renderer) {
+        for (shape, _) in self.batches.iter_mut().filter_map(|b| b.renderable.as_mut()) {
+            shape.render(renderer);
+        }
+    }
+}
+
+/// Refill `batches` from the entities of `world`, adding batches for new
+/// shape and style combinations and removing those left empty.
+fn gather(world: &mut World, batches: &mut Vec<Batch>) {
+    for batch in batches.iter_mut() {
+        batch.positions.clear();
+        batch.colors.clear();
+    }
+    let mut query = world.query::<(&Position, &Shape, &Style)>();
+    // Entities of one batch tend to be stored together; try the last
+    // match before searching.
+    let mut last = 0;
+    for (position, shape, style) in query.iter(world) {
+        let batch_style = ShapeStyle { fill: style.0.fill.map(|_| Color::white()), ..style.0.clone() };
+        let index = if batches.get(last).is_some_and(|b| b.matches(&shape.0, &batch_style)) {
+            last
+        } else if let Some(index) = batches.iter().position(|b| b.matches(&shape.0, &batch_style)) {
+            index
+        } else {
+            batches.push(Batch { shape: shape.0.clone(), style: batch_style, positions: Vec::new(), colors: Vec::new(), renderable: None });
+            batches.len() - 1
+        };
+        last = index;
+        let batch = &mut batches[index];
+        batch.positions.push(position.0);
+        if let Some(fill) = style.0.fill {
+            batch.colors.push(fill);
+        }
+    }
+    batches.retain(|b| !b.positions.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics2d::shapes::{Circle, Rectangle};
+
+    #[test]
+    fn entities_batch_by_shape_and_style_but_not_fill() {
+        let mut world = World::new();
+        let circle = Shape(ShapeKind::Circle(Circle::new(4.0)));
+        let at = |x: f32| Position(Vec2::new(x, 0.0));
+        world.spawn((at(1.0), circle.clone(), Style(ShapeStyle::fill(Color::red()))));
+        world.spawn((at(2.0), circle.clone(), Style(ShapeStyle::fill(Color::blue()))));
+        world.spawn((at(3.0), Shape(ShapeKind::Rectangle(Rectangle::new(2.0, 2.0))), Style(ShapeStyle::fill(Color::red()))));
+        let outline = ShapeStyle { stroke_color: Some(Color::black()), stroke_width: Some(1.0), ..ShapeStyle::fill(Color::red()) };
+        let outlined = world.spawn((at(4.0), circle.clone(), Style(outline))).id();
+        world.spawn((at(5.0), circle));
+
+        let mut batches = Vec::new();
+        gather(&mut world, &mut batches);
+        assert_eq!(batches.len(), 3);
+        let circles = batches.iter().find(|b| b.positions.len() == 2).unwrap();
+        assert_eq!(circles.colors, vec![Color::red(), Color::blue()]);
+
+        world.despawn(outlined);
+        gather(&mut world, &mut batches);
+        assert_eq!(batches.len(), 2, "empty batches are dropped");
+    }
+}
@@ -0,0 +1,70 @@
+//! Window backgrounds drawn before any shape.
+
+use std::rc::Rc;
+
+use crate::core::engine::opengl::{gl_get_uniform_location, gl_uniform_1f, gl_uniform_4f, GL_TRIANGLES};
+use crate::core::{Attribute, Color, Geometry, Mesh, Renderable, Renderer, Shader};
+
+/// A gradient filling the window behind everything else, e.g. for day and
+/// night dashboard themes. Set it with
+/// [`App::set_background_gradient`](crate::core::App::set_background_gradient).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundGradient {
+    /// From `top` at the top edge to `bottom` at the bottom edge.
+    Vertical { top: Color, bottom: Color },
+    /// From `center` at the window center to `edge` at the corners.
+    Radial { center: Color, edge: Color },
+}
+
+/// Draws a [`BackgroundGradient`] over the whole window. The shader and
+/// quad are created on the first render.
+pub struct Background {
+    gradient: BackgroundGradient,
+    mesh: Option<Mesh>,
+}
+
+impl Background {
+    pub fn new(gradient: BackgroundGradient) -> Self {
+        Self { gradient, mesh: None }
+    }
+
+    pub fn gradient(&self) -> BackgroundGradient {
+        self.gradient
+    }
+
+    pub fn set_gradient(&mut self, gradient: BackgroundGradient) {
+        self.gradient = gradient;
+    }
+}
+
+impl Renderable for Background {
+    fn render(&mut self, renderer: &Renderer) {
+        let (width, height) = renderer.window_handle.size();
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let mesh = self.mesh.get_or_insert_with(|| {
+            let shader = Shader::compile(
+                include_str!("shaders/background.vert"),
+                include_str!("shaders/background.frag"),
+                None,
+            )
+            .expect("Failed to compile background shader");
+            let mut geometry = Geometry::new(GL_TRIANGLES);
+            geometry.add_buffer(&[-1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, 1.0], 2);
+            geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
+            Mesh::new(Rc::new(shader), geometry)
+        });
+        let (a, b, radial) = match self.gradient {
+            BackgroundGradient::Vertical { top, bottom } => (top, bottom, 0.0),
+            BackgroundGradient::Radial { center, edge } => (center, edge, 1.0),
+        };
+        let program = mesh.shader.program();
+        mesh.shader.use_program();
+        gl_uniform_4f(gl_get_uniform_location(program, "u_color_a"), a.red_value(), a.green_value(), a.blue_value(), a.alpha());
+        gl_uniform_4f(gl_get_uniform_location(program, "u_color_b"), b.red_value(), b.green_value(), b.blue_value(), b.alpha());
+        gl_uniform_1f(gl_get_uniform_location(program, "u_radial"), radial);
+        gl_uniform_1f(gl_get_uniform_location(program, "u_aspect"), width as f32 / height as f32);
+        renderer.draw_mesh(mesh);
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+
+use crate::core::{Color, Renderable, Renderer, Vec2};
+use crate::graphics2d::shapes::{Anchor, Line, Polyline, ShapeKind, ShapeRenderable, ShapeStyle, Text};
+
+/// Length of the tick marks outside the plot rectangle, in pixels.
+const TICK_LENGTH: f32 = 5.0;
+/// Gap between tick marks and their labels, in pixels.
+const LABEL_GAP: f32 = 3.0;
+
+type TickFormat = Box<dyn Fn(f64) -> String>;
+
+/// A screen-space rectangle, in pixels, that a chart is drawn into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PlotRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.x && point.x <= self.x + self.width && point.y >= self.y && point.y <= self.y + self.height
+    }
+}
+
+/// One axis of a chart: the data range it spans, its ticks and an optional
+/// title.
+///
+/// Ticks fall on round values (1, 2, 2.5 or 5 times a power of ten) spaced so
+/// there are about [`set_target_ticks`](Self::set_target_ticks) of them.
+pub struct Axis {
+    min: f64,
+    max: f64,
+    target_ticks: usize,
+    step: Option<f64>,
+    title: Option<String>,
+    format: Option<TickFormat>,
+}
+
+impl Axis {
+    pub fn new(min: f64, max: f64) -> Self {
+        Self { min, max, target_ticks: 5, step: None, title: None, format: None }
+    }
+
+    /// A categorical axis: one tick per label at `0, 1, 2, ...`, with half
+    /// a category of margin on both ends.
+    pub fn categories(labels: &[&str]) -> Self {
+        let labels: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        let mut axis = Self::new(-0.5, labels.len() as f64 - 0.5);
+        axis.set_tick_step(Some(1.0));
+        axis.set_format(move |v| {
+            let i = v.round();
+            if i >= 0.0 { labels.get(i as usize).cloned().unwrap_or_default() } else { String::new() }
+        });
+        axis
+    }
+
+    /// An axis spanning `values`, widened to round tick values. Empty or
+    /// constant input gets a unit range.
+    pub fn fit(values: impl IntoIterator<Item = f64>) -> Self {
+        let mut axis = Self::new(0.0, 1.0);
+        axis.fit_to(values);
+        axis
+    }
+
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    pub fn set_range(&mut self, min: f64, max: f64) -> &mut Self {
+        (self.min, self.max) = (min, max);
+        self
+    }
+
+    /// Set the range to span `values`, widened to round tick values.
+    pub fn fit_to(&mut self, values: impl IntoIterator<Item = f64>) -> &mut Self {
+        let values: Vec<f64> = values.into_iter().collect();
+        let (min, max) = match value_range(&values) {
+            None => (0.0, 1.0),
+            Some((min, max)) if min == max => (min - 0.5, max + 0.5),
+            Some(range) => range,
+        };
+        let step = self.step.unwrap_or_else(|| tick_step(min, max, self.target_ticks));
+        self.set_range((min / step).floor() * step, (max / step).ceil() * step)
+    }
+
+    /// Fixed distance between ticks, instead of a round value picked for
+    /// the target count (`None`, the default).
+    pub fn set_tick_step(&mut self, step: Option<f64>) -> &mut Self {
+        self.step = step.filter(|s| *s > 0.0);
+        self
+    }
+
+    /// About how many ticks to show (default 5).
+    pub fn set_target_ticks(&mut self, count: usize) -> &mut Self {
+        self.target_ticks = count.max(1);
+        self
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Format tick labels with `format` instead of as plain numbers.
+    pub fn set_format(&mut self, format: impl Fn(f64) -> String + 'static) -> &mut Self {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    /// Tick values within the range.
+    pub fn ticks(&self) -> Vec<f64> {
+        let (lo, hi) = (self.min.min(self.max), self.min.max(self.max));
+        let step = self.step();
+        let first = (lo / step - 1e-9).ceil() as i64;
+        let last = (hi / step + 1e-9).floor() as i64;
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+
+    /// The label shown for the tick at `value`.
+    pub fn format_tick(&self, value: f64) -> String {
+        match &self.format {
+            Some(format) => format(value),
+            None => format_number(value, self.step()),
+        }
+    }
+
+    /// `value` formatted like the tick labels, or for plain numbers with
+    /// decimals down to `resolution` (such as one pixel's worth of data).
+    pub(crate) fn format_value(&self, value: f64, resolution: f64) -> String {
+        match &self.format {
+            Some(format) => format(value),
+            None => format_number(value, round_resolution(resolution)),
+        }
+    }
+
+    fn step(&self) -> f64 {
+        self.step.unwrap_or_else(|| tick_step(self.min, self.max, self.target_ticks))
+    }
+
+    /// Position of `value` along the axis, 0 at `min` and 1 at `max`.
+    pub fn normalize(&self, value: f64) -> f64 {
+        if self.max == self.min { 0.5 } else { (value - self.min) / (self.max - self.min) }
+    }
+
+    /// The value at position `t` along the axis.
+    pub fn denormalize(&self, t: f64) -> f64 {
+        self.min + t * (self.max - self.min)
+    }
+}
+
+/// A chart frame: an x and a y [`Axis`] laid over a [`PlotRect`].
+///
+/// Converts between data and screen coordinates for the chart layers, and
+/// draws the frame, tick marks, optional grid lines and, once
+/// [`set_labels`](Self::set_labels) gives it a font, tick labels and axis
+/// titles.
+///
+/// ```ignore
+/// let mut axes = Axes::new(PlotRect::new(60.0, 20.0, 700.0, 500.0), Axis::fit(xs), Axis::fit(ys));
+/// axes.set_labels("fonts/DejaVuSans.ttf", 12);
+/// axes.y_axis_mut().set_title("altitude (m)");
+/// // in on_render
+/// axes.render(renderer);
+/// scatter.render(renderer, &axes);
+/// ```
+pub struct Axes {
+    rect: PlotRect,
+    x: Axis,
+    y: Axis,
+    color: Color,
+    grid_color: Option<Color>,
+    label_font: Option<(String, u32)>,
+    /// Frame, tick and grid shapes, rebuilt after any change.
+    shapes: Option<Vec<ShapeRenderable>>,
+    labels: HashMap<String, ShapeRenderable>,
+}
+
+impl Axes {
+    pub fn new(rect: PlotRect, x: Axis, y: Axis) -> Self {
+        Self {
+            rect,
+            x,
+            y,
+            color: Color::from_rgb(0.6, 0.6, 0.6),
+            grid_color: None,
+            label_font: None,
+            shapes: None,
+            labels: HashMap::new(),
+        }
+    }
+
+    pub fn rect(&self) -> PlotRect {
+        self.rect
+    }
+
+    pub fn set_rect(&mut self, rect: PlotRect) -> &mut Self {
+        self.rect = rect;
+        self.shapes = None;
+        self
+    }
+
+    pub fn x_axis(&self) -> &Axis {
+        &self.x
+    }
+
+    pub fn x_axis_mut(&mut self) -> &mut Axis {
+        self.shapes = None;
+        &mut self.x
+    }
+
+    pub fn y_axis(&self) -> &Axis {
+        &self.y
+    }
+
+    pub fn y_axis_mut(&mut self) -> &mut Axis {
+        self.shapes = None;
+        &mut self.y
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.shapes = None;
+        self.labels.clear();
+        self
+    }
+
+    /// Draw grid lines across the plot at every tick (`None` to hide).
+    pub fn set_grid(&mut self, color: Option<Color>) -> &mut Self {
+        self.grid_color = color;
+        self.shapes = None;
+        self
+    }
+
+    /// Label ticks and show axis titles in this font.
+    pub fn set_labels(&mut self, font_path: impl Into<String>, font_size: u32) -> &mut Self {
+        self.label_font = Some((font_path.into(), font_size));
+        self.labels.clear();
+        self
+    }
+
+    /// Screen position of the data point `(x, y)`. The y axis points up.
+    pub fn data_to_screen(&self, x: f64, y: f64) -> Vec2 {
+        let r = self.rect;
+        Vec2::new(
+            r.x + self.x.normalize(x) as f32 * r.width,
+            r.y + r.height - self.y.normalize(y) as f32 * r.height,
+        )
+    }
+
+    /// Data coordinates of the screen position `point`.
+    pub fn screen_to_data(&self, point: Vec2) -> (f64, f64) {
+        let r = self.rect;
+        let tx = if r.width == 0.0 { 0.0 } else { (point.x - r.x) / r.width };
+        let ty = if r.height == 0.0 { 0.0 } else { (r.y + r.height - point.y) / r.height };
+        (self.x.denormalize(tx as f64), self.y.denormalize(ty as f64))
+    }
+
+    pub fn render(&mut self, renderer: &Renderer) {
+        let shapes = self.shapes.get_or_insert_with(|| build_frame(self.rect, &self.x, &self.y, self.color, self.grid_color));
+        for shape in shapes.iter_mut() {
+            shape.render(renderer);
+        }
+        if self.label_font.is_some() {
+            self.render_labels(renderer);
+        }
+    }
+
+    fn render_labels(&mut self, renderer: &Renderer) {
+        let Some((font_path, font_size)) = self.label_font.clone() else { return };
+        let r = self.rect;
+        let bottom = r.y + r.height + TICK_LENGTH + LABEL_GAP;
+        let left = r.x - TICK_LENGTH - LABEL_GAP;
+        let mut wanted: Vec<(String, Anchor, Vec2)> = Vec::new();
+        for value in self.x.ticks() {
+            let x = self.data_to_screen(value, self.y.min).x;
+            wanted.push((self.x.format_tick(value), Anchor::North, Vec2::new(x, bottom)));
+        }
+        for value in self.y.ticks() {
+            let y = self.data_to_screen(self.x.min, value).y;
+            wanted.push((self.y.format_tick(value), Anchor::East, Vec2::new(left, y)));
+        }
+        let title_offset = font_size as f32 * 1.5;
+        if let Some(title) = self.x.title() {
+            wanted.push((title.to_string(), Anchor::North, Vec2::new(r.x + r.width / 2.0, bottom + title_offset)));
+        }
+        if let Some(title) = self.y.title() {
+            wanted.push((title.to_string(), Anchor::SouthWest, Vec2::new(r.x, r.y - LABEL_GAP)));
+        }
+
+        let mut used = HashMap::with_capacity(self.labels.len());
+        for (text, anchor, at) in wanted {
+            let key = format!("{anchor:?}{text}");
+            let mut label = self.labels.remove(&key).unwrap_or_else(|| {
+                ShapeRenderable::builder(ShapeKind::Text(Text::new(text, font_path.as_str(), font_size)), ShapeStyle::fill(self.color))
+                    .anchor(anchor)
+                    .build()
+            });
+            label.set_position(at.x, at.y).render(renderer);
+            used.insert(key, label);
+        }
+        self.labels = used;
+    }
+}
+
+fn build_frame(rect: PlotRect, x: &Axis, y: &Axis, color: Color, grid: Option<Color>) -> Vec<ShapeRenderable> {
+    let r = rect;
+    let (left, right, top, bottom) = (r.x, r.x + r.width, r.y, r.y + r.height);
+    let xs: Vec<f32> = x.ticks().into_iter().map(|v| left + x.normalize(v) as f32 * r.width).collect();
+    let ys: Vec<f32> = y.ticks().into_iter().map(|v| bottom - y.normalize(v) as f32 * r.height).collect();
+    let style = ShapeStyle::stroke(color, 1.0);
+
+    let mut shapes = Vec::new();
+    if let Some(grid) = grid {
+        let grid_style = ShapeStyle::stroke(grid, 1.0);
+        shapes.extend(instanced_line((0.0, top), (0.0, bottom), &grid_style, xs.iter().map(|&x| Vec2::new(x, 0.0))));
+        shapes.extend(instanced_line((left, 0.0), (right, 0.0), &grid_style, ys.iter().map(|&y| Vec2::new(0.0, y))));
+    }
+    let frame = Polyline::new(vec![(left, top), (left, bottom), (right, bottom)]);
+    shapes.push(ShapeRenderable::from_shape(ShapeKind::Polyline(frame), style.clone()));
+    shapes.extend(instanced_line((0.0, bottom), (0.0, bottom + TICK_LENGTH), &style, xs.iter().map(|&x| Vec2::new(x, 0.0))));
+    shapes.extend(instanced_line((left - TICK_LENGTH, 0.0), (left, 0.0), &style, ys.iter().map(|&y| Vec2::new(0.0, y))));
+    shapes
+}
+
+/// One line drawn at every offset in `offsets`, or `None` without offsets.
+fn instanced_line(start: (f32, f32), end: (f32, f32), style: &ShapeStyle, offsets: impl Iterator<Item = Vec2>) -> Option<ShapeRenderable> {
+    let offsets: Vec<Vec2> = offsets.collect();
+    if offsets.is_empty() {
+        return None;
+    }
+    let mut line = ShapeRenderable::builder(ShapeKind::Line(Line::new(start, end)), style.clone())
+        .anchor(Anchor::Custom(0.0, 0.0))
+        .build();
+    line.create_multiple_instances(offsets.len());
+    line.set_instance_positions(&offsets);
+    Some(line)
+}
+
+/// Smallest and largest finite value, or `None` if there are none.
+pub(crate) fn value_range(values: &[f64]) -> Option<(f64, f64)> {
+    values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(None, |range, &v| Some(range.map_or((v, v), |(lo, hi): (f64, f64)| (lo.min(v), hi.max(v)))))
+}
+
+/// A round step (1, 2, 2.5 or 5 times a power of ten) giving about
+/// `target` ticks across `min..max`.
+pub(crate) fn tick_step(min: f64, max: f64, target: usize) -> f64 {
+    let span = (max - min).abs();
+    if span == 0.0 || !span.is_finite() {
+        return 1.0;
+    }
+    let target = target.max(1) as f64;
+    let magnitude = 10f64.powf((span / target).log10().floor());
+    [1.0, 2.0, 2.5, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .min_by(|a, b| (span / a - target).abs().total_cmp(&(span / b - target).abs()))
+        .unwrap()
+}
+
+/// The power of ten at or below `resolution`, for [`format_number`].
+pub(crate) fn round_resolution(resolution: f64) -> f64 {
+    let resolution = resolution.abs();
+    if resolution > 0.0 && resolution.is_finite() { 10f64.powf(resolution.log10().floor()) } else { 1.0 }
+}
+
+/// `value` with as many decimals as `step` needs.
+pub(crate) fn format_number(value: f64, step: f64) -> String {
+    let decimals = (0..10)
+        .find(|&d| {
+            let scaled = step * 10f64.powi(d);
+            (scaled - scaled.round()).abs() < 1e-6 * scaled.max(1.0)
+        })
+        .unwrap_or(10) as usize;
+    let text = format!("{value:.decimals$}");
+    // Avoid "-0".
+    if text.trim_start_matches('-').chars().all(|c| c == '0' || c == '.') { text.trim_start_matches('-').to_string() } else { text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_fall_on_round_values() {
+        assert_eq!(Axis::new(0.0, 100.0).ticks(), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        assert_eq!(Axis::new(-0.3, 0.7).ticks().len(), 5);
+        assert_eq!(Axis::new(3.0, 3.0).ticks(), vec![3.0]);
+        let fitted = Axis::fit([0.3, 9.2, 4.0]);
+        assert_eq!(fitted.range(), (0.0, 10.0));
+        assert_eq!(Axis::fit(std::iter::empty()).range(), (0.0, 1.0));
+
+        let categories = Axis::categories(&["a", "b", "c", "d", "e", "f", "g"]);
+        assert_eq!(categories.ticks(), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(categories.format_tick(2.0), "c");
+    }
+
+    #[test]
+    fn value_range_skips_non_finite() {
+        assert_eq!(value_range(&[3.0, f64::NAN, -1.0, 7.5]), Some((-1.0, 7.5)));
+        assert_eq!(value_range(&[f64::INFINITY]), None);
+    }
+
+    #[test]
+    fn tick_labels_use_step_precision() {
+        assert_eq!(format_number(20.0, 20.0), "20");
+        assert_eq!(format_number(0.25, 0.05), "0.25");
+        assert_eq!(format_number(0.75, 0.25), "0.75");
+        assert_eq!(format_number(-0.0000001, 0.1), "0.0");
+        let mut axis = Axis::new(0.0, 1.0);
+        axis.set_format(|v| format!("{:.0}%", v * 100.0));
+        assert_eq!(axis.format_tick(0.5), "50%");
+    }
+
+    #[test]
+    fn data_and_screen_round_trip_with_y_up() {
+        let axes = Axes::new(PlotRect::new(10.0, 20.0, 200.0, 100.0), Axis::new(0.0, 10.0), Axis::new(-1.0, 1.0));
+        let p = axes.data_to_screen(5.0, 1.0);
+        assert_eq!((p.x, p.y), (110.0, 20.0));
+        assert_eq!(axes.data_to_screen(0.0, -1.0).y, 120.0);
+        let (x, y) = axes.screen_to_data(Vec2::new(60.0, 70.0));
+        assert!((x - 2.5).abs() < 1e-6 && y.abs() < 1e-6);
+    }
+}
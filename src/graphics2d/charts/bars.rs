@@ -0,0 +1,231 @@
+use crate::core::{Color, InstanceTransform, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::Axes;
+use crate::graphics2d::shapes::{Anchor, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// A bar (column) series: one instanced rectangle per bar, rising from a
+/// baseline to its value.
+///
+/// Bars are centered on their x value and [`set_width`](Self::set_width)
+/// data units wide. For categories, [`set_values`](Self::set_values)
+/// places bar `i` at `x = i`, matching [`Axis::categories`](super::Axis::categories);
+/// for time bins, [`set_bars`](Self::set_bars) takes the bin centers.
+/// Values update in place ([`set_value`](Self::set_value),
+/// [`push`](Self::push)), and every frame is one instanced draw, so live
+/// dashboards can update each frame.
+///
+/// ```ignore
+/// let mut axes = Axes::new(rect, Axis::categories(&["N", "E", "S", "W"]), Axis::new(0.0, 100.0));
+/// let mut bars = BarSeries::new();
+/// bars.set_values(&[42.0, 17.0, 88.0, 63.0]);
+/// // on new data
+/// bars.set_value(2, 91.0);
+/// // in on_render
+/// bars.render(renderer, &axes);
+/// ```
+pub struct BarSeries {
+    xs: Vec<f64>,
+    values: Vec<f64>,
+    width: f64,
+    baseline: f64,
+    color: Color,
+    colors: Option<Vec<Color>>,
+    /// Unit rectangle batch and its instance capacity.
+    batch: Option<(ShapeRenderable, usize)>,
+}
+
+impl Default for BarSeries {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BarSeries {
+    /// An empty series of bars 0.8 data units wide, rising from 0.
+    pub fn new() -> Self {
+        Self {
+            xs: Vec::new(),
+            values: Vec::new(),
+            width: 0.8,
+            baseline: 0.0,
+            color: Color::from_rgb(0.12, 0.47, 0.71),
+            colors: None,
+            batch: None,
+        }
+    }
+
+    /// One bar per value, at `x = 0, 1, 2, ...`.
+    pub fn set_values(&mut self, values: &[f64]) -> &mut Self {
+        self.xs = (0..values.len()).map(|i| i as f64).collect();
+        self.values = values.to_vec();
+        self
+    }
+
+    /// Bars centered on `xs`. Extra entries of the longer slice are
+    /// ignored.
+    pub fn set_bars(&mut self, xs: &[f64], values: &[f64]) -> &mut Self {
+        let n = xs.len().min(values.len());
+        self.xs = xs[..n].to_vec();
+        self.values = values[..n].to_vec();
+        self
+    }
+
+    /// Change the value of bar `index`. No-op when out of range.
+    pub fn set_value(&mut self, index: usize, value: f64) -> &mut Self {
+        if let Some(v) = self.values.get_mut(index) {
+            *v = value;
+        }
+        self
+    }
+
+    /// Append a bar centered on `x`.
+    pub fn push(&mut self, x: f64, value: f64) -> &mut Self {
+        self.xs.push(x);
+        self.values.push(value);
+        self
+    }
+
+    /// Drop the oldest bars so at most `count` remain, for rolling
+    /// time-binned series.
+    pub fn keep_last(&mut self, count: usize) -> &mut Self {
+        let excess = self.len().saturating_sub(count);
+        self.xs.drain(..excess);
+        self.values.drain(..excess);
+        if let Some(colors) = &mut self.colors {
+            colors.drain(..excess.min(colors.len()));
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    /// Center and value of bar `index`.
+    pub fn bar(&self, index: usize) -> Option<(f64, f64)> {
+        Some((*self.xs.get(index)?, *self.values.get(index)?))
+    }
+
+    /// Bar width in data units (default 0.8).
+    pub fn set_width(&mut self, width: f64) -> &mut Self {
+        self.width = width.max(0.0);
+        self
+    }
+
+    /// Value the bars rise from (default 0). Bars below it hang down.
+    pub fn set_baseline(&mut self, baseline: f64) -> &mut Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Color of every bar without an entry in [`set_colors`](Self::set_colors).
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    /// Per-bar colors.
+    pub fn set_colors(&mut self, colors: &[Color]) -> &mut Self {
+        self.colors = Some(colors.to_vec());
+        self
+    }
+
+    pub fn clear_colors(&mut self) -> &mut Self {
+        self.colors = None;
+        self
+    }
+
+    /// The bar under the screen position `at`.
+    pub fn bar_at(&self, at: Vec2, axes: &Axes) -> Option<usize> {
+        self.screen_rects(axes)
+            .into_iter()
+            .find(|&(_, (x, y, w, h))| at.x >= x && at.x <= x + w && at.y >= y && at.y <= y + h)
+            .map(|(i, _)| i)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, axes: &Axes) {
+        let rects = self.screen_rects(axes);
+        if rects.is_empty() {
+            return;
+        }
+        let positions: Vec<Vec2> = rects.iter().map(|&(_, (x, y, _, h))| Vec2::new(x, y + h)).collect();
+        let transforms: Vec<InstanceTransform> = rects.iter().map(|&(_, (_, _, w, h))| InstanceTransform::new(w, h, 0.0)).collect();
+        let colors: Vec<Color> = rects
+            .iter()
+            .map(|&(i, _)| self.colors.as_ref().and_then(|c| c.get(i)).copied().unwrap_or(self.color))
+            .collect();
+
+        if self.batch.as_ref().is_none_or(|(_, capacity)| *capacity < rects.len()) {
+            let capacity = rects.len().next_power_of_two();
+            // A unit square with its origin at the bottom-left corner, so
+            // the instance scale is the bar's width and height.
+            let mut shape = ShapeRenderable::builder(ShapeKind::Rectangle(Rectangle::new(1.0, 1.0)), ShapeStyle::fill(self.color))
+                .anchor(Anchor::SouthWest)
+                .build();
+            shape.create_multiple_instances(capacity);
+            self.batch = Some((shape, capacity));
+        }
+        let (shape, _) = self.batch.as_mut().unwrap();
+        shape.set_instance_positions(&positions).set_instance_transforms(&transforms).set_instance_colors(&colors);
+        shape.render(renderer);
+    }
+
+    /// Screen rectangles `(x, y, width, height)` of the visible bars with
+    /// their indices, clipped to the plot rectangle.
+    fn screen_rects(&self, axes: &Axes) -> Vec<(usize, (f32, f32, f32, f32))> {
+        let r = axes.rect();
+        let half = self.width / 2.0;
+        (0..self.len())
+            .filter_map(|i| {
+                let (x, value) = (self.xs[i], self.values[i]);
+                if !value.is_finite() {
+                    return None;
+                }
+                let a = axes.data_to_screen(x - half, value);
+                let b = axes.data_to_screen(x + half, self.baseline);
+                let left = a.x.min(b.x).max(r.x);
+                let right = a.x.max(b.x).min(r.x + r.width);
+                let top = a.y.min(b.y).max(r.y);
+                let bottom = a.y.max(b.y).min(r.y + r.height);
+                (right > left && bottom > top).then_some((i, (left, top, right - left, bottom - top)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics2d::charts::{Axis, PlotRect};
+
+    #[test]
+    fn bars_rise_from_baseline_and_are_clipped() {
+        let axes = Axes::new(PlotRect::new(0.0, 0.0, 400.0, 100.0), Axis::categories(&["a", "b", "c", "d"]), Axis::new(-50.0, 50.0));
+        let mut bars = BarSeries::new();
+        bars.set_values(&[25.0, -10.0, 80.0, f64::NAN]);
+        let rects = bars.screen_rects(&axes);
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[0], (0, (10.0, 25.0, 80.0, 25.0)));
+        assert_eq!(rects[1], (1, (110.0, 50.0, 80.0, 10.0)));
+        // Clipped at the top of the plot.
+        let (x, y, w, h) = rects[2].1;
+        assert!((x - 210.0).abs() < 1e-3 && y == 0.0 && (w - 80.0).abs() < 1e-3 && h == 50.0);
+        assert_eq!(bars.bar_at(Vec2::new(150.0, 55.0), &axes), Some(1));
+        assert_eq!(bars.bar_at(Vec2::new(150.0, 45.0), &axes), None);
+    }
+
+    #[test]
+    fn rolling_series_keeps_latest_bars() {
+        let mut bars = BarSeries::new();
+        for i in 0..10 {
+            bars.push(i as f64, i as f64 * 2.0);
+        }
+        bars.keep_last(3).set_value(0, -1.0);
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars.bar(0), Some((7.0, -1.0)));
+        assert_eq!(bars.bar(2), Some((9.0, 18.0)));
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use crate::core::{Camera2D, Color, ColorMap, Mesh, Projection, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::Axes;
+use crate::graphics2d::shapes::{Anchor, Polyline, ShapeKind, ShapeRenderable, ShapeStyle, render_triangle_mesh, triangle_mesh};
+
+/// A polyline through data or world coordinates.
+type Path = Vec<(f64, f64)>;
+
+/// One triangle of a filled band.
+type Triangle = [(f64, f64); 3];
+
+/// Scalar samples on a regular grid, such as a heightmap or an
+/// interpolated sensor field.
+///
+/// Values are stored row by row; sample `(column, row)` sits at
+/// `origin + (column * spacing.0, row * spacing.1)`. NaN marks missing
+/// samples: cells touching one are left out of contours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarGrid {
+    columns: usize,
+    rows: usize,
+    values: Vec<f64>,
+    origin: (f64, f64),
+    spacing: (f64, f64),
+}
+
+impl ScalarGrid {
+    /// A grid of `columns` by `rows` samples at unit spacing from the
+    /// origin. Fails if `values` does not hold exactly `columns * rows`
+    /// samples.
+    pub fn new(columns: usize, rows: usize, values: Vec<f64>) -> Result<Self, String> {
+        if values.len() != columns * rows {
+            return Err(format!("grid of {columns}x{rows} needs {} values, got {}", columns * rows, values.len()));
+        }
+        Ok(Self { columns, rows, values, origin: (0.0, 0.0), spacing: (1.0, 1.0) })
+    }
+
+    /// Sample `(0, 0)` position and distance between samples.
+    pub fn with_placement(mut self, origin: (f64, f64), spacing: (f64, f64)) -> Self {
+        self.origin = origin;
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> f64 {
+        self.values[row * self.columns + column]
+    }
+
+    pub fn position(&self, column: f64, row: f64) -> (f64, f64) {
+        (self.origin.0 + column * self.spacing.0, self.origin.1 + row * self.spacing.1)
+    }
+
+    /// Smallest and largest finite sample.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        super::axis::value_range(&self.values)
+    }
+}
+
+/// Isolines of `grid` at `level` by marching squares, joined into
+/// polylines. Closed lines end on their first point.
+pub fn contour_lines(grid: &ScalarGrid, level: f64) -> Vec<Vec<(f64, f64)>> {
+    // Crossings are identified by the grid edge they lie on:
+    // (column, row, vertical) for the edge leaving sample (column, row).
+    type Edge = (usize, usize, bool);
+    let mut segments: Vec<(Edge, Edge)> = Vec::new();
+    for row in 0..grid.rows.saturating_sub(1) {
+        for column in 0..grid.columns.saturating_sub(1) {
+            let corners = [grid.get(column, row), grid.get(column + 1, row), grid.get(column + 1, row + 1), grid.get(column, row + 1)];
+            if corners.iter().any(|v| v.is_nan()) {
+                continue;
+            }
+            // Cell edges counter-clockwise in index order: top, right,
+            // bottom, left.
+            let edges: [Edge; 4] = [(column, row, false), (column + 1, row, true), (column, row + 1, false), (column, row, true)];
+            let case = corners.iter().enumerate().fold(0, |case, (i, v)| case | ((*v >= level) as usize) << i);
+            let center_above = corners.iter().sum::<f64>() / 4.0 >= level;
+            for (a, b) in cell_segments(case, center_above) {
+                segments.push((edges[a], edges[b]));
+            }
+        }
+    }
+
+    let crossing = |(column, row, vertical): Edge| {
+        let (c2, r2) = if vertical { (column, row + 1) } else { (column + 1, row) };
+        let (v1, v2) = (grid.get(column, row), grid.get(c2, r2));
+        let t = if v2 == v1 { 0.5 } else { (level - v1) / (v2 - v1) };
+        grid.position(column as f64 + t * (c2 - column) as f64, row as f64 + t * (r2 - row) as f64)
+    };
+
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        by_edge.entry(*a).or_default().push(i);
+        by_edge.entry(*b).or_default().push(i);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    // Open lines first, starting from their ends, then closed loops.
+    let ends: Vec<usize> = by_edge.values().filter(|s| s.len() == 1).map(|s| s[0]).collect();
+    for start in ends.into_iter().chain(0..segments.len()) {
+        if used[start] {
+            continue;
+        }
+        let (a, b) = segments[start];
+        // Walk away from the end shared with no other segment.
+        let mut edge = if by_edge[&a].len() == 1 { b } else { a };
+        let mut path: Path = vec![crossing(if edge == a { b } else { a }), crossing(edge)];
+        used[start] = true;
+        while let Some(&next) = by_edge[&edge].iter().find(|&&s| !used[s]) {
+            used[next] = true;
+            let (a, b) = segments[next];
+            edge = if a == edge { b } else { a };
+            path.push(crossing(edge));
+        }
+        lines.push(path);
+    }
+    lines
+}
+
+/// Segments of one marching-squares cell, as pairs of cell edge indices
+/// (0 top, 1 right, 2 bottom, 3 left). `case` has bit `i` set when corner
+/// `i` (top-left, top-right, bottom-right, bottom-left) is at or above the
+/// level. Saddles are resolved by the cell's average.
+fn cell_segments(case: usize, center_above: bool) -> Vec<(usize, usize)> {
+    match case {
+        0 | 15 => vec![],
+        1 | 14 => vec![(3, 0)],
+        2 | 13 => vec![(0, 1)],
+        3 | 12 => vec![(3, 1)],
+        4 | 11 => vec![(1, 2)],
+        6 | 9 => vec![(0, 2)],
+        7 | 8 => vec![(2, 3)],
+        5 if center_above => vec![(3, 2), (1, 0)],
+        5 => vec![(3, 0), (1, 2)],
+        10 if center_above => vec![(0, 3), (2, 1)],
+        10 => vec![(0, 1), (2, 3)],
+        _ => unreachable!("marching squares case {case}"),
+    }
+}
+
+/// Triangles covering the part of `grid` with values in `min..=max`. Each
+/// cell is split into two triangles whose values are interpolated
+/// linearly, then clipped to the band.
+pub fn contour_band(grid: &ScalarGrid, min: f64, max: f64) -> Vec<Triangle> {
+    let mut triangles = Vec::new();
+    for row in 0..grid.rows.saturating_sub(1) {
+        for column in 0..grid.columns.saturating_sub(1) {
+            let corner = |c: usize, r: usize| {
+                let (x, y) = grid.position(c as f64, r as f64);
+                (x, y, grid.get(c, r))
+            };
+            let (a, b, c, d) = (corner(column, row), corner(column + 1, row), corner(column + 1, row + 1), corner(column, row + 1));
+            if [a, b, c, d].iter().any(|p| p.2.is_nan()) {
+                continue;
+            }
+            for triangle in [[a, b, c], [a, c, d]] {
+                let clipped = clip_above(&clip_above(&triangle, min, 1.0), max, -1.0);
+                for i in 1..clipped.len().saturating_sub(1) {
+                    let p = |v: (f64, f64, f64)| (v.0, v.1);
+                    triangles.push([p(clipped[0]), p(clipped[i]), p(clipped[i + 1])]);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+/// Clip a convex polygon of `(x, y, value)` vertices to where
+/// `sign * (value - level) >= 0`, interpolating new vertices.
+fn clip_above(polygon: &[(f64, f64, f64)], level: f64, sign: f64) -> Vec<(f64, f64, f64)> {
+    let inside = |v: &(f64, f64, f64)| sign * (v.2 - level) >= 0.0;
+    let mut out = Vec::with_capacity(polygon.len() + 2);
+    for (i, current) in polygon.iter().enumerate() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        if inside(current) {
+            out.push(*current);
+        }
+        if inside(current) != inside(next) {
+            let t = (level - current.2) / (next.2 - current.2);
+            out.push((current.0 + t * (next.0 - current.0), current.1 + t * (next.1 - current.1), level));
+        }
+    }
+    out
+}
+
+/// Contour lines, and optionally filled bands between them, of a
+/// [`ScalarGrid`].
+///
+/// Contours are extracted once when the grid or levels change; drawing
+/// only reprojects them when the axes or the camera scale change.
+///
+/// ```ignore
+/// let grid = ScalarGrid::new(columns, rows, heights)?.with_placement(origin, (30.0, 30.0));
+/// let mut contours = ContourLayer::new(grid, &[100.0, 200.0, 300.0, 400.0]);
+/// contours.set_fill(Some(ColorMap::Viridis));
+/// // in on_render
+/// contours.render_with_camera(renderer, camera);
+/// ```
+pub struct ContourLayer {
+    grid: ScalarGrid,
+    levels: Vec<f64>,
+    line_color: Color,
+    line_width: f32,
+    fill: Option<ColorMap>,
+    fill_opacity: f32,
+    lines: Vec<Path>,
+    bands: Vec<(Color, Vec<Triangle>)>,
+    extracted: bool,
+    /// Shapes built for the current placement.
+    built: Option<Built>,
+}
+
+struct Built {
+    /// Axes rect and ranges, or camera scale and grid origin.
+    key: [f64; 8],
+    lines: Vec<ShapeRenderable>,
+    bands: Vec<Mesh>,
+}
+
+impl ContourLayer {
+    /// Contours of `grid` at `levels`.
+    pub fn new(grid: ScalarGrid, levels: &[f64]) -> Self {
+        let mut layer = Self {
+            grid,
+            levels: Vec::new(),
+            line_color: Color::from_rgba(0.1, 0.1, 0.1, 0.8),
+            line_width: 1.0,
+            fill: None,
+            fill_opacity: 0.6,
+            lines: Vec::new(),
+            bands: Vec::new(),
+            extracted: false,
+            built: None,
+        };
+        layer.set_levels(levels);
+        layer
+    }
+
+    /// `count` evenly spaced levels over round values spanning the grid.
+    pub fn auto_levels(grid: ScalarGrid, count: usize) -> Self {
+        let (min, max) = grid.range().unwrap_or((0.0, 1.0));
+        let mut axis = super::Axis::new(min, max);
+        axis.set_target_ticks(count);
+        let levels: Vec<f64> = axis.ticks().into_iter().filter(|l| *l > min && *l < max).collect();
+        Self::new(grid, &levels)
+    }
+
+    pub fn grid(&self) -> &ScalarGrid {
+        &self.grid
+    }
+
+    pub fn set_grid(&mut self, grid: ScalarGrid) -> &mut Self {
+        self.grid = grid;
+        self.invalidate()
+    }
+
+    pub fn levels(&self) -> &[f64] {
+        &self.levels
+    }
+
+    pub fn set_levels(&mut self, levels: &[f64]) -> &mut Self {
+        self.levels = levels.to_vec();
+        self.levels.sort_by(f64::total_cmp);
+        self.levels.dedup();
+        self.invalidate()
+    }
+
+    pub fn set_line_color(&mut self, color: Color) -> &mut Self {
+        self.line_color = color;
+        self.built = None;
+        self
+    }
+
+    /// Width of the contour lines in pixels (0 hides them).
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width.max(0.0);
+        self.built = None;
+        self
+    }
+
+    /// Fill the bands between levels, below the lowest and above the
+    /// highest, with colors from `colormap` (`None`, the default, for lines
+    /// only).
+    pub fn set_fill(&mut self, colormap: Option<ColorMap>) -> &mut Self {
+        self.fill = colormap;
+        self.invalidate()
+    }
+
+    pub fn set_fill_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.fill_opacity = opacity.clamp(0.0, 1.0);
+        self.invalidate()
+    }
+
+    /// Draw in chart space.
+    pub fn render(&mut self, renderer: &Renderer, axes: &Axes) {
+        let r = axes.rect();
+        let ((x0, x1), (y0, y1)) = (axes.x_axis().range(), axes.y_axis().range());
+        let key = [r.x as f64, r.y as f64, r.width as f64, r.height as f64, x0, x1, y0, y1];
+        self.draw(renderer, key, |x, y| {
+            let p = axes.data_to_screen(x, y);
+            (p.x, p.y)
+        }, (0.0, 0.0));
+    }
+
+    /// Draw in world space under `camera`.
+    pub fn render_with_camera(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        // Built relative to the grid origin at the camera's scale, so
+        // panning only moves the shapes.
+        let (ox, oy) = self.grid.origin;
+        let scale = camera.scale() as f64;
+        let key = [scale, ox, oy, 0.0, 0.0, 0.0, 0.0, f64::NAN];
+        let offset = camera.world_to_screen(Vec2::new(ox as f32, oy as f32));
+        self.draw(renderer, key, |x, y| (((x - ox) * scale) as f32, ((y - oy) * scale) as f32), (offset.x, offset.y));
+    }
+
+    fn invalidate(&mut self) -> &mut Self {
+        self.extracted = false;
+        self.built = None;
+        self
+    }
+
+    fn extract(&mut self) {
+        self.lines = self.levels.iter().flat_map(|&level| contour_lines(&self.grid, level)).collect();
+        self.bands.clear();
+        if let Some(colormap) = self.fill {
+            let bounds: Vec<f64> = std::iter::once(f64::NEG_INFINITY).chain(self.levels.iter().copied()).chain(std::iter::once(f64::INFINITY)).collect();
+            let count = bounds.len() - 1;
+            for (i, pair) in bounds.windows(2).enumerate() {
+                let t = if count > 1 { i as f32 / (count - 1) as f32 } else { 0.5 };
+                let color = colormap.sample(t).with_alpha(self.fill_opacity);
+                self.bands.push((color, contour_band(&self.grid, pair[0], pair[1])));
+            }
+        }
+        self.extracted = true;
+    }
+
+    fn draw(&mut self, renderer: &Renderer, key: [f64; 8], to_local: impl Fn(f64, f64) -> (f32, f32), offset: (f32, f32)) {
+        if !self.extracted {
+            self.extract();
+        }
+        // NaN never equals itself, so compare bit patterns.
+        let same = |a: &[f64; 8]| a.iter().zip(&key).all(|(a, b)| a.to_bits() == b.to_bits());
+        if self.built.as_ref().is_none_or(|b| !same(&b.key)) {
+            let bands = self
+                .bands
+                .iter()
+                .filter(|(_, triangles)| !triangles.is_empty())
+                .map(|(color, triangles)| {
+                    let vertices: Vec<f32> = triangles
+                        .iter()
+                        .flatten()
+                        .flat_map(|&(x, y)| {
+                            let (x, y) = to_local(x, y);
+                            [x, y]
+                        })
+                        .collect();
+                    triangle_mesh(&vertices, *color)
+                })
+                .collect();
+            let lines = if self.line_width > 0.0 {
+                let style = ShapeStyle::stroke(self.line_color, self.line_width);
+                self.lines
+                    .iter()
+                    .filter(|path| path.len() >= 2)
+                    .map(|path| {
+                        let points = path.iter().map(|&(x, y)| to_local(x, y)).collect();
+                        ShapeRenderable::builder(ShapeKind::Polyline(Polyline::new(points)), style.clone())
+                            .anchor(Anchor::Custom(0.0, 0.0))
+                            .build()
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            self.built = Some(Built { key, lines, bands });
+        }
+
+        let built = self.built.as_mut().unwrap();
+        for mesh in &mut built.bands {
+            render_triangle_mesh(renderer, mesh, offset);
+        }
+        for line in &mut built.lines {
+            line.set_position(offset.0, offset.1).render(renderer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Distance from the grid center, on a 5x5 grid.
+    fn cone() -> ScalarGrid {
+        let values = (0..25).map(|i| (((i % 5) as f64 - 2.0).powi(2) + ((i / 5) as f64 - 2.0).powi(2)).sqrt()).collect();
+        ScalarGrid::new(5, 5, values).unwrap()
+    }
+
+    #[test]
+    fn closed_isoline_around_a_peak() {
+        let lines = contour_lines(&cone(), 1.5);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.first(), line.last());
+        assert!(line.len() > 8);
+        // Linear interpolation keeps every crossing close to the circle.
+        assert!(line.iter().all(|&(x, y)| ((x - 2.0).hypot(y - 2.0) - 1.5).abs() < 0.2));
+    }
+
+    #[test]
+    fn open_isolines_end_at_the_border() {
+        let values = (0..12).map(|i| (i % 4) as f64).collect();
+        let ramp = ScalarGrid::new(4, 3, values).unwrap().with_placement((10.0, 0.0), (2.0, 1.0));
+        let lines = contour_lines(&ramp, 1.5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 3);
+        assert!(lines[0].iter().all(|&(x, _)| x == 13.0));
+        assert!(ScalarGrid::new(4, 3, vec![0.0; 11]).is_err());
+    }
+
+    #[test]
+    fn bands_partition_the_grid() {
+        let area = |triangles: &[Triangle]| {
+            triangles
+                .iter()
+                .map(|[a, b, c]| ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0)
+                .sum::<f64>()
+        };
+        let grid = cone();
+        let total: f64 = [(f64::NEG_INFINITY, 1.0), (1.0, 2.0), (2.0, f64::INFINITY)]
+            .iter()
+            .map(|&(lo, hi)| area(&contour_band(&grid, lo, hi)))
+            .sum();
+        assert!((total - 16.0).abs() < 1e-9);
+        assert!(area(&contour_band(&grid, 5.0, 6.0)) == 0.0);
+    }
+}
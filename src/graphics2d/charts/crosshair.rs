@@ -0,0 +1,335 @@
+use crate::core::{Camera2D, Color, Projection, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::{Axes, PlotRect};
+use crate::graphics2d::charts::axis::{format_number, round_resolution};
+use crate::graphics2d::shapes::{Anchor, Circle, Polyline, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle, Text};
+
+/// Gap between the crosshair point and its tooltip, in pixels.
+const TOOLTIP_GAP: f32 = 10.0;
+/// Padding around the tooltip text, in pixels.
+const TOOLTIP_PADDING: f32 = 4.0;
+
+type ValueFormat = Box<dyn Fn(f64, f64) -> String>;
+
+/// Where the crosshair is: the snapped data point, or the free cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrosshairTarget {
+    /// Screen position of the crosshair.
+    pub screen: Vec2,
+    /// Data (or world) coordinates of the crosshair.
+    pub data: (f64, f64),
+    /// Index of the point snapped to, `None` when following the cursor.
+    pub index: Option<usize>,
+}
+
+/// A hover crosshair: guide lines from the point under the cursor to the
+/// axes and a tooltip with its coordinates.
+///
+/// Call [`update`](Self::update) with the cursor position and the points
+/// to snap to (for instance a [`ScatterLayer`](super::ScatterLayer)'s
+/// [`points`](super::ScatterLayer::points)) whenever the cursor moves. The
+/// crosshair snaps to the nearest point within
+/// [`set_snap_radius`](Self::set_snap_radius) pixels, marking it with a
+/// ring, and otherwise follows the cursor; outside the plot rectangle it
+/// is hidden. [`update_with_camera`](Self::update_with_camera) does the
+/// same for world-space points, with guides to the window's edges.
+///
+/// The tooltip needs a font from [`set_labels`](Self::set_labels). Values
+/// are formatted like the axis tick labels (category names on categorical
+/// axes), with decimals down to one pixel, or by
+/// [`set_format`](Self::set_format).
+///
+/// ```ignore
+/// let mut crosshair = Crosshair::new();
+/// crosshair.set_labels("fonts/DejaVuSans.ttf", 12);
+/// // on cursor move
+/// if let Some(hit) = crosshair.update(cursor, &axes, scatter.points()) {
+///     hovered = hit.index;
+/// }
+/// // in on_render, after the chart layers
+/// crosshair.render(renderer);
+/// ```
+pub struct Crosshair {
+    snap_radius: f32,
+    color: Color,
+    line_width: f32,
+    dash: Option<(f32, f32)>,
+    label_font: Option<(String, u32)>,
+    label_color: Color,
+    background: Option<Color>,
+    format: Option<ValueFormat>,
+    target: Option<CrosshairTarget>,
+    text: String,
+    /// Guide corners: the axis foot of the vertical guide, the point and
+    /// the axis foot of the horizontal guide.
+    guide_points: Vec<(f32, f32)>,
+    rect: PlotRect,
+    guides: Option<ShapeRenderable>,
+    ring: Option<ShapeRenderable>,
+    label: Option<(String, ShapeRenderable)>,
+    backdrop: Option<ShapeRenderable>,
+}
+
+impl Default for Crosshair {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crosshair {
+    pub fn new() -> Self {
+        Self {
+            snap_radius: 12.0,
+            color: Color::from_rgba(0.5, 0.5, 0.5, 0.9),
+            line_width: 1.0,
+            dash: Some((4.0, 3.0)),
+            label_font: None,
+            label_color: Color::from_rgb(0.95, 0.95, 0.95),
+            background: Some(Color::from_rgba(0.1, 0.1, 0.1, 0.85)),
+            format: None,
+            target: None,
+            text: String::new(),
+            guide_points: Vec::new(),
+            rect: PlotRect::new(0.0, 0.0, 0.0, 0.0),
+            guides: None,
+            ring: None,
+            label: None,
+            backdrop: None,
+        }
+    }
+
+    /// Distance in pixels within which the crosshair snaps to a point
+    /// (default 12, 0 to never snap).
+    pub fn set_snap_radius(&mut self, radius: f32) -> &mut Self {
+        self.snap_radius = radius.max(0.0);
+        self
+    }
+
+    /// Color of the guide lines and the snap ring.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.guides = None;
+        self.ring = None;
+        self
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width;
+        self.guides = None;
+        self.ring = None;
+        self
+    }
+
+    /// Dash and gap lengths of the guide lines (`None` for solid lines).
+    pub fn set_dash(&mut self, dash: Option<(f32, f32)>) -> &mut Self {
+        self.dash = dash;
+        self.guides = None;
+        self
+    }
+
+    /// Show a tooltip using this font.
+    pub fn set_labels(&mut self, font_path: impl Into<String>, font_size: u32) -> &mut Self {
+        self.label_font = Some((font_path.into(), font_size));
+        self.label = None;
+        self
+    }
+
+    pub fn clear_labels(&mut self) -> &mut Self {
+        self.label_font = None;
+        self.label = None;
+        self
+    }
+
+    pub fn set_label_color(&mut self, color: Color) -> &mut Self {
+        self.label_color = color;
+        self.label = None;
+        self
+    }
+
+    /// Fill behind the tooltip text (`None` for none).
+    pub fn set_background(&mut self, color: Option<Color>) -> &mut Self {
+        self.background = color;
+        self.backdrop = None;
+        self
+    }
+
+    /// Format the tooltip from the crosshair's `(x, y)` instead.
+    pub fn set_format(&mut self, format: impl Fn(f64, f64) -> String + 'static) -> &mut Self {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    /// The current crosshair position, if shown.
+    pub fn target(&self) -> Option<CrosshairTarget> {
+        self.target
+    }
+
+    /// The tooltip text of the current position.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Hide the crosshair, for instance when the cursor leaves the window.
+    pub fn hide(&mut self) {
+        self.target = None;
+    }
+
+    /// Move the crosshair to the screen position `cursor` over `axes`,
+    /// snapping to the nearest of `points` in data coordinates.
+    pub fn update(&mut self, cursor: Vec2, axes: &Axes, points: impl IntoIterator<Item = (f64, f64)>) -> Option<CrosshairTarget> {
+        let rect = axes.rect();
+        let target = rect.contains(cursor).then(|| {
+            let snapped = nearest(points.into_iter().map(|(x, y)| ((x, y), axes.data_to_screen(x, y))), cursor, self.snap_radius);
+            match snapped {
+                Some((index, data, screen)) => CrosshairTarget { screen, data, index: Some(index) },
+                None => CrosshairTarget { screen: cursor, data: axes.screen_to_data(cursor), index: None },
+            }
+        });
+        let (x_axis, y_axis) = (axes.x_axis(), axes.y_axis());
+        let resolution = |(min, max): (f64, f64), pixels: f32| (max - min) / pixels.max(1.0) as f64;
+        let rx = resolution(x_axis.range(), rect.width);
+        let ry = resolution(y_axis.range(), rect.height);
+        self.set_target(target, rect, |(x, y)| format!("{}, {}", x_axis.format_value(x, rx), y_axis.format_value(y, ry)))
+    }
+
+    /// Move the crosshair to the screen position `cursor` under `camera`,
+    /// snapping to the nearest of `points` in world coordinates.
+    pub fn update_with_camera(&mut self, cursor: Vec2, camera: &Camera2D, points: impl IntoIterator<Item = (f64, f64)>) -> Option<CrosshairTarget> {
+        let size = camera.screen_size();
+        let rect = PlotRect::new(0.0, 0.0, size.x, size.y);
+        let target = rect.contains(cursor).then(|| {
+            let placed = points.into_iter().map(|(x, y)| ((x, y), camera.world_to_screen(Vec2::new(x as f32, y as f32))));
+            match nearest(placed, cursor, self.snap_radius) {
+                Some((index, data, screen)) => CrosshairTarget { screen, data, index: Some(index) },
+                None => {
+                    let world = camera.screen_to_world(cursor);
+                    CrosshairTarget { screen: cursor, data: (world.x as f64, world.y as f64), index: None }
+                }
+            }
+        });
+        let step = round_resolution(1.0 / camera.scale() as f64);
+        self.set_target(target, rect, |(x, y)| format!("{}, {}", format_number(x, step), format_number(y, step)))
+    }
+
+    fn set_target(&mut self, target: Option<CrosshairTarget>, rect: PlotRect, default_format: impl Fn((f64, f64)) -> String) -> Option<CrosshairTarget> {
+        self.target = target;
+        self.rect = rect;
+        let target = target?;
+        let p = target.screen;
+        self.guide_points = vec![(p.x, rect.y + rect.height), (p.x, p.y), (rect.x, p.y)];
+        self.text = match &self.format {
+            Some(format) => format(target.data.0, target.data.1),
+            None => default_format(target.data),
+        };
+        if let Some(guides) = &mut self.guides {
+            guides.set_points(&self.guide_points);
+        }
+        Some(target)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer) {
+        let Some(target) = self.target else { return };
+        let p = target.screen;
+
+        let guides = self.guides.get_or_insert_with(|| {
+            let style = match self.dash {
+                Some((dash, gap)) => ShapeStyle::dashed_stroke(self.color, self.line_width, dash, gap),
+                None => ShapeStyle::stroke(self.color, self.line_width),
+            };
+            ShapeRenderable::builder(ShapeKind::Polyline(Polyline::new(self.guide_points.clone())), style)
+                .anchor(Anchor::Custom(0.0, 0.0))
+                .build()
+        });
+        guides.render(renderer);
+
+        if target.index.is_some() {
+            let ring = self.ring.get_or_insert_with(|| {
+                ShapeRenderable::from_shape(ShapeKind::Circle(Circle::new(5.0)), ShapeStyle::stroke(self.color, self.line_width.max(1.5)))
+            });
+            ring.set_position(p.x, p.y).render(renderer);
+        }
+
+        let Some((font_path, font_size)) = &self.label_font else { return };
+        if self.label.as_ref().is_none_or(|(text, _)| *text != self.text) {
+            let label = ShapeRenderable::builder(ShapeKind::Text(Text::new(self.text.clone(), font_path.as_str(), *font_size)), ShapeStyle::fill(self.label_color))
+                .anchor(Anchor::SouthWest)
+                .build();
+            self.label = Some((self.text.clone(), label));
+        }
+        let (_, label) = self.label.as_mut().unwrap();
+        let (x0, y0, x1, y1) = label.bounds();
+        let (left, bottom) = tooltip_position(p, (x1 - x0, y1 - y0), self.rect);
+        label.set_position(left, bottom);
+
+        if let Some(background) = self.background {
+            let (x0, y0, x1, y1) = label.bounds();
+            let (width, height) = (x1 - x0 + 2.0 * TOOLTIP_PADDING, y1 - y0 + 2.0 * TOOLTIP_PADDING);
+            let backdrop = self.backdrop.get_or_insert_with(|| {
+                ShapeRenderable::builder(ShapeKind::Rectangle(Rectangle::new(width, height)), ShapeStyle::fill(background))
+                    .anchor(Anchor::NorthWest)
+                    .build()
+            });
+            backdrop.set_size(width, height).set_position(x0 - TOOLTIP_PADDING, y0 - TOOLTIP_PADDING).render(renderer);
+        }
+        label.render(renderer);
+    }
+}
+
+/// The point within `radius` pixels of `cursor` nearest to it, as its
+/// index, data position and screen position.
+fn nearest(points: impl Iterator<Item = ((f64, f64), Vec2)>, cursor: Vec2, radius: f32) -> Option<(usize, (f64, f64), Vec2)> {
+    points
+        .enumerate()
+        .filter_map(|(i, (data, screen))| {
+            let d = (screen.x - cursor.x).hypot(screen.y - cursor.y);
+            (d <= radius).then_some((i, data, screen, d))
+        })
+        .min_by(|a, b| a.3.total_cmp(&b.3))
+        .map(|(i, data, screen, _)| (i, data, screen))
+}
+
+/// Bottom-left corner of a `size` tooltip next to `point`: above and to
+/// the right, flipped to stay inside `rect` where it would overflow.
+fn tooltip_position(point: Vec2, size: (f32, f32), rect: PlotRect) -> (f32, f32) {
+    let margin = TOOLTIP_GAP + TOOLTIP_PADDING;
+    let mut left = point.x + margin;
+    if left + size.0 + TOOLTIP_PADDING > rect.x + rect.width {
+        left = point.x - margin - size.0;
+    }
+    let mut bottom = point.y - margin;
+    if bottom - size.1 - TOOLTIP_PADDING < rect.y {
+        bottom = point.y + margin + size.1;
+    }
+    (left, bottom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics2d::charts::Axis;
+
+    #[test]
+    fn snaps_to_nearest_point_within_radius() {
+        let axes = Axes::new(PlotRect::new(0.0, 0.0, 100.0, 100.0), Axis::new(0.0, 10.0), Axis::new(0.0, 10.0));
+        let points = [(2.0, 5.0), (5.0, 5.0), (5.5, 5.0)];
+        let mut crosshair = Crosshair::new();
+
+        let hit = crosshair.update(Vec2::new(56.0, 51.0), &axes, points).unwrap();
+        assert_eq!(hit.index, Some(2));
+        assert_eq!(hit.data, (5.5, 5.0));
+        assert_eq!(crosshair.guide_points, vec![(55.0, 100.0), (55.0, 50.0), (0.0, 50.0)]);
+
+        let free = crosshair.update(Vec2::new(30.0, 80.0), &axes, points).unwrap();
+        assert_eq!(free.index, None);
+        assert_eq!(crosshair.text(), "3.0, 2.0");
+
+        assert_eq!(crosshair.update(Vec2::new(120.0, 50.0), &axes, points), None);
+        assert_eq!(crosshair.target(), None);
+    }
+
+    #[test]
+    fn tooltip_flips_at_plot_edges() {
+        let rect = PlotRect::new(0.0, 0.0, 200.0, 100.0);
+        assert_eq!(tooltip_position(Vec2::new(50.0, 50.0), (40.0, 10.0), rect), (64.0, 36.0));
+        assert_eq!(tooltip_position(Vec2::new(180.0, 5.0), (40.0, 10.0), rect), (126.0, 29.0));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::core::{Color, Renderer, Vec2};
+use crate::graphics2d::charts::axis::value_range;
+use crate::graphics2d::charts::{Axes, BarSeries};
+
+/// How a [`Histogram`] splits its range into bins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binning {
+    /// This many bins of equal width across the range.
+    Count(usize),
+    /// Bins of this width, aligned to multiples of it.
+    Width(f64),
+}
+
+/// A histogram: counts of values per bin, drawn as touching bars.
+///
+/// Bins cover a fixed range from [`set_range`](Self::set_range), or by
+/// default the range of the values seen so far. [`push`](Self::push) only
+/// increments one bin unless a value falls outside an automatic range, in
+/// which case the next render rebins everything. Values outside a fixed
+/// range are not counted. Bars are a [`BarSeries`], so the histogram draws
+/// over an [`Axes`] like the other chart layers and
+/// [`fit_axes`](Self::fit_axes) sizes those to the bins.
+///
+/// ```ignore
+/// let mut latency = Histogram::new(Binning::Width(5.0));
+/// latency.set_values(&samples);
+/// latency.fit_axes(&mut axes);
+/// // on every sample
+/// latency.push(ms);
+/// // in on_render
+/// latency.render(renderer, &axes);
+/// ```
+pub struct Histogram {
+    binning: Binning,
+    range: Option<(f64, f64)>,
+    values: Vec<f64>,
+    /// Left edge of the first bin and width of every bin.
+    start: f64,
+    width: f64,
+    counts: Vec<u64>,
+    /// Counts are stale and the bins need rebuilding from `values`.
+    dirty: bool,
+    bars: BarSeries,
+    bars_dirty: bool,
+}
+
+impl Histogram {
+    pub fn new(binning: Binning) -> Self {
+        let mut bars = BarSeries::new();
+        bars.set_width(1.0);
+        Self {
+            binning,
+            range: None,
+            values: Vec::new(),
+            start: 0.0,
+            width: 1.0,
+            counts: Vec::new(),
+            dirty: true,
+            bars,
+            bars_dirty: true,
+        }
+    }
+
+    pub fn set_binning(&mut self, binning: Binning) -> &mut Self {
+        self.binning = binning;
+        self.dirty = true;
+        self
+    }
+
+    /// Range the bins cover, or `None` (the default) for the range of the
+    /// values.
+    pub fn set_range(&mut self, range: Option<(f64, f64)>) -> &mut Self {
+        self.range = range.filter(|(min, max)| max > min);
+        self.dirty = true;
+        self
+    }
+
+    /// Replace the values.
+    pub fn set_values(&mut self, values: &[f64]) -> &mut Self {
+        self.values = values.to_vec();
+        self.dirty = true;
+        self
+    }
+
+    /// Add one value. Non-finite values are ignored.
+    pub fn push(&mut self, value: f64) -> &mut Self {
+        if !value.is_finite() {
+            return self;
+        }
+        self.values.push(value);
+        if !self.dirty {
+            match self.bin_of(value) {
+                Some(bin) => {
+                    self.counts[bin] += 1;
+                    self.bars.set_value(bin, self.counts[bin] as f64);
+                }
+                // An automatic range has to grow to fit the value.
+                None if self.range.is_none() => self.dirty = true,
+                None => {}
+            }
+        }
+        self
+    }
+
+    pub fn extend(&mut self, values: impl IntoIterator<Item = f64>) -> &mut Self {
+        for value in values {
+            self.push(value);
+        }
+        self
+    }
+
+    pub fn clear(&mut self) -> &mut Self {
+        self.values.clear();
+        self.dirty = true;
+        self
+    }
+
+    /// Number of values pushed, counted or not.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Count of values in each bin.
+    pub fn counts(&mut self) -> &[u64] {
+        self.rebin();
+        &self.counts
+    }
+
+    /// Lower and upper edge of bin `index`.
+    pub fn bin_edges(&mut self, index: usize) -> Option<(f64, f64)> {
+        self.rebin();
+        (index < self.counts.len()).then(|| {
+            let lo = self.start + index as f64 * self.width;
+            (lo, lo + self.width)
+        })
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.bars.set_color(color);
+        self
+    }
+
+    /// Fit the x axis to the bins and the y axis to the counts.
+    pub fn fit_axes(&mut self, axes: &mut Axes) {
+        self.rebin();
+        let end = self.start + self.counts.len() as f64 * self.width;
+        axes.x_axis_mut().set_range(self.start, end);
+        let highest = self.counts.iter().copied().max().unwrap_or(0);
+        axes.y_axis_mut().fit_to([0.0, highest.max(1) as f64]);
+    }
+
+    /// The bin whose bar is under the screen position `at`.
+    pub fn bin_at(&mut self, at: Vec2, axes: &Axes) -> Option<usize> {
+        self.sync_bars();
+        self.bars.bar_at(at, axes)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, axes: &Axes) {
+        self.sync_bars();
+        self.bars.render(renderer, axes);
+    }
+
+    fn sync_bars(&mut self) {
+        self.rebin();
+        if self.bars_dirty {
+            let centers: Vec<f64> = (0..self.counts.len()).map(|i| self.start + (i as f64 + 0.5) * self.width).collect();
+            let counts: Vec<f64> = self.counts.iter().map(|&c| c as f64).collect();
+            self.bars.set_bars(&centers, &counts).set_width(self.width);
+            self.bars_dirty = false;
+        }
+    }
+
+    /// Recompute the bins and counts from the values, if stale.
+    fn rebin(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let (min, max) = self.range.or_else(|| value_range(&self.values)).unwrap_or((0.0, 1.0));
+        let (start, width, bins) = match self.binning {
+            Binning::Count(count) => {
+                let count = count.max(1);
+                let width = if max > min { (max - min) / count as f64 } else { 1.0 };
+                (min, width, count)
+            }
+            Binning::Width(width) => {
+                let width = if width > 0.0 { width } else { 1.0 };
+                let start = (min / width).floor() * width;
+                // The last bin is closed, so a maximum on an edge stays in it.
+                let bins = (((max - start) / width).ceil() as usize).max(1);
+                (start, width, bins)
+            }
+        };
+        (self.start, self.width) = (start, width);
+        self.counts = vec![0; bins];
+        self.dirty = false;
+        for i in 0..self.values.len() {
+            if let Some(bin) = self.bin_of(self.values[i]) {
+                self.counts[bin] += 1;
+            }
+        }
+        self.bars_dirty = true;
+    }
+
+    /// The bin holding `value`; the last bin includes its upper edge.
+    fn bin_of(&self, value: f64) -> Option<usize> {
+        let t = (value - self.start) / self.width;
+        let bins = self.counts.len();
+        if !(0.0..=bins as f64).contains(&t) {
+            return None;
+        }
+        Some((t as usize).min(bins - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bins_by_count_and_by_width() {
+        let mut hist = Histogram::new(Binning::Count(4));
+        hist.set_values(&[0.0, 1.0, 2.5, 3.9, 4.0, f64::NAN]);
+        assert_eq!(hist.counts(), &[1, 1, 1, 2]);
+        assert_eq!(hist.bin_edges(3), Some((3.0, 4.0)));
+
+        hist.set_binning(Binning::Width(2.5));
+        assert_eq!(hist.counts(), &[2, 3]);
+        assert_eq!(hist.bin_edges(1), Some((2.5, 5.0)));
+    }
+
+    #[test]
+    fn push_grows_an_automatic_range_but_not_a_fixed_one() {
+        let mut hist = Histogram::new(Binning::Width(1.0));
+        hist.extend([0.5, 1.5]);
+        assert_eq!(hist.counts(), &[1, 1]);
+        hist.push(1.2).push(3.5);
+        assert_eq!(hist.counts(), &[1, 2, 0, 1]);
+
+        hist.set_range(Some((0.0, 2.0))).push(10.0);
+        assert_eq!(hist.counts(), &[1, 2]);
+        assert_eq!(hist.len(), 5);
+    }
+}
@@ -0,0 +1,23 @@
+//! Charts drawn into a screen-space plot rectangle.
+//!
+//! An [`Axes`] maps data coordinates to pixels and draws the frame, ticks
+//! and labels; the chart layers take it as an argument when rendering, so
+//! several layers can share one set of axes.
+
+pub(crate) mod axis;
+mod bars;
+mod contour;
+mod crosshair;
+mod histogram;
+mod quiver;
+mod scatter;
+mod sparkline;
+
+pub use axis::{Axes, Axis, PlotRect};
+pub use bars::BarSeries;
+pub use contour::{ContourLayer, ScalarGrid, contour_band, contour_lines};
+pub use crosshair::{Crosshair, CrosshairTarget};
+pub use histogram::{Binning, Histogram};
+pub use quiver::QuiverLayer;
+pub use scatter::ScatterLayer;
+pub use sparkline::Sparkline;
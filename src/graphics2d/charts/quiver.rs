@@ -0,0 +1,221 @@
+use crate::core::{Camera2D, Color, ColorMap, InstanceTransform, Projection, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::Axes;
+use crate::graphics2d::charts::axis::value_range;
+use crate::graphics2d::shapes::{Polygon, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Length of the arrow mesh before instance scaling, in pixels.
+const BASE_LENGTH: f32 = 10.0;
+
+/// A vector field: one instanced arrow per `(position, vector)` sample,
+/// rotated to the vector's direction and scaled and colored by its
+/// magnitude. For wind, currents and flow fields.
+///
+/// Arrows start at their sample position. By default the longest arrow is
+/// [`set_max_length`](Self::set_max_length) pixels and the others scale
+/// linearly; [`set_scale`](Self::set_scale) fixes the pixels per unit of
+/// magnitude instead, so lengths stay comparable between frames.
+///
+/// Draw in chart space with [`render`](Self::render), where vectors point
+/// up for positive y, or in world space with
+/// [`render_with_camera`](Self::render_with_camera), where they follow the
+/// world's axes (on the Web Mercator maps of [`geo`](crate::graphics2d::geo)
+/// y points south, so a northward wind has a negative y component).
+///
+/// ```ignore
+/// let mut wind = QuiverLayer::new();
+/// wind.set_samples(&positions, &vectors).set_colormap(Some(ColorMap::Turbo));
+/// // in on_render
+/// wind.render_with_camera(renderer, camera);
+/// ```
+pub struct QuiverLayer {
+    positions: Vec<(f64, f64)>,
+    vectors: Vec<(f64, f64)>,
+    max_length: f32,
+    scale: Option<f32>,
+    color: Color,
+    colormap: Option<ColorMap>,
+    magnitude_range: Option<(f64, f64)>,
+    /// Arrow batch and its instance capacity.
+    batch: Option<(ShapeRenderable, usize)>,
+}
+
+impl Default for QuiverLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuiverLayer {
+    pub fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            vectors: Vec::new(),
+            max_length: 30.0,
+            scale: None,
+            color: Color::from_rgb(0.9, 0.9, 0.9),
+            colormap: None,
+            magnitude_range: None,
+            batch: None,
+        }
+    }
+
+    /// Samples as positions and vectors. Extra entries of the longer slice
+    /// are ignored.
+    pub fn set_samples(&mut self, positions: &[(f64, f64)], vectors: &[(f64, f64)]) -> &mut Self {
+        let n = positions.len().min(vectors.len());
+        self.positions = positions[..n].to_vec();
+        self.vectors = vectors[..n].to_vec();
+        self
+    }
+
+    /// Samples on a regular grid: `vectors` row by row, `columns` per row,
+    /// starting at `origin` and `spacing` apart.
+    pub fn set_grid(&mut self, origin: (f64, f64), spacing: (f64, f64), columns: usize, vectors: &[(f64, f64)]) -> &mut Self {
+        let columns = columns.max(1);
+        self.positions = (0..vectors.len())
+            .map(|i| (origin.0 + (i % columns) as f64 * spacing.0, origin.1 + (i / columns) as f64 * spacing.1))
+            .collect();
+        self.vectors = vectors.to_vec();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Length of the longest arrow in pixels when no fixed scale is set
+    /// (default 30).
+    pub fn set_max_length(&mut self, pixels: f32) -> &mut Self {
+        self.max_length = pixels.max(1.0);
+        self
+    }
+
+    /// Pixels per unit of magnitude, or `None` (the default) to fit the
+    /// longest arrow to the maximum length.
+    pub fn set_scale(&mut self, scale: Option<f32>) -> &mut Self {
+        self.scale = scale.filter(|s| *s > 0.0);
+        self
+    }
+
+    /// Color of every arrow when no colormap is set.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.batch = None;
+        self
+    }
+
+    /// Color arrows by magnitude (`None` for a single color).
+    pub fn set_colormap(&mut self, colormap: Option<ColorMap>) -> &mut Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Magnitudes mapped to the ends of the colormap (default: the samples'
+    /// own range).
+    pub fn set_magnitude_range(&mut self, min: f64, max: f64) -> &mut Self {
+        self.magnitude_range = Some((min, max));
+        self
+    }
+
+    /// Draw in chart space, skipping samples outside the plot rectangle.
+    pub fn render(&mut self, renderer: &Renderer, axes: &Axes) {
+        let rect = axes.rect();
+        self.draw(renderer, |(x, y), (vx, vy)| {
+            let p = axes.data_to_screen(x, y);
+            // Screen y points down, chart y up.
+            rect.contains(p).then_some((p, (vx, -vy)))
+        });
+    }
+
+    /// Draw in world space under `camera`, skipping samples off screen.
+    pub fn render_with_camera(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        let size = camera.screen_size();
+        self.draw(renderer, |(x, y), vector| {
+            let p = camera.world_to_screen(Vec2::new(x as f32, y as f32));
+            (p.x >= 0.0 && p.y >= 0.0 && p.x <= size.x && p.y <= size.y).then_some((p, vector))
+        });
+    }
+
+    fn draw(&mut self, renderer: &Renderer, place: impl Fn((f64, f64), (f64, f64)) -> Option<(Vec2, (f64, f64))>) {
+        let magnitudes: Vec<f64> = self.vectors.iter().map(|(x, y)| x.hypot(*y)).collect();
+        let scale = self.scale.unwrap_or_else(|| {
+            let longest = magnitudes.iter().copied().filter(|m| m.is_finite()).fold(0.0, f64::max);
+            if longest > 0.0 { self.max_length / longest as f32 } else { 0.0 }
+        });
+        let range = self.magnitude_range.or_else(|| value_range(&magnitudes));
+
+        let mut positions = Vec::new();
+        let mut transforms = Vec::new();
+        let mut colors = Vec::new();
+        for (i, &magnitude) in magnitudes.iter().enumerate() {
+            if !(magnitude > 0.0 && magnitude.is_finite()) {
+                continue;
+            }
+            let Some((p, screen_vector)) = place(self.positions[i], self.vectors[i]) else { continue };
+            positions.push(p);
+            transforms.push(arrow_transform(screen_vector, magnitude as f32 * scale));
+            colors.push(match (self.colormap, range) {
+                (Some(map), Some((min, max))) => map.sample_range(magnitude as f32, min as f32, max as f32),
+                _ => self.color,
+            });
+        }
+        if positions.is_empty() {
+            return;
+        }
+
+        if self.batch.as_ref().is_none_or(|(_, capacity)| *capacity < positions.len()) {
+            let capacity = positions.len().next_power_of_two();
+            let arrow = Polygon::new(arrow_vertices(BASE_LENGTH));
+            let mut shape = ShapeRenderable::from_shape(ShapeKind::Polygon(arrow), ShapeStyle::fill(self.color));
+            shape.create_multiple_instances(capacity);
+            self.batch = Some((shape, capacity));
+        }
+        let (shape, _) = self.batch.as_mut().unwrap();
+        shape.set_instance_positions(&positions).set_instance_transforms(&transforms).set_instance_colors(&colors);
+        shape.render(renderer);
+    }
+}
+
+/// Instance transform turning the base arrow, pointing along +x, into one
+/// `length` pixels long pointing along the screen-space `vector`.
+fn arrow_transform(vector: (f64, f64), length: f32) -> InstanceTransform {
+    let angle = vector.1.atan2(vector.0) as f32;
+    InstanceTransform::new(length / BASE_LENGTH, length / BASE_LENGTH, angle)
+}
+
+/// An arrow `length` long pointing along +x, its tail at the origin.
+fn arrow_vertices(length: f32) -> Vec<(f32, f32)> {
+    let (shaft, head) = (length * 0.07, length * 0.25);
+    let neck = length * 0.7;
+    vec![(0.0, -shaft), (neck, -shaft), (neck, -head), (length, 0.0), (neck, head), (neck, shaft), (0.0, shaft)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrows_point_along_vector_and_scale_with_length() {
+        let t = arrow_transform((0.0, 5.0), 20.0);
+        assert_eq!((t.scale_x, t.scale_y), (2.0, 2.0));
+        assert!((t.rotation - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        let t = arrow_transform((-1.0, 0.0), 10.0);
+        assert!((t.rotation.abs() - std::f32::consts::PI).abs() < 1e-6);
+
+        let tip = arrow_vertices(BASE_LENGTH).into_iter().fold(f32::MIN, |m, (x, _)| m.max(x));
+        assert_eq!(tip, BASE_LENGTH);
+    }
+
+    #[test]
+    fn grid_samples_are_laid_out_row_by_row() {
+        let mut quiver = QuiverLayer::new();
+        quiver.set_grid((10.0, 20.0), (5.0, 2.0), 3, &[(1.0, 0.0); 7]);
+        assert_eq!(quiver.len(), 7);
+        assert_eq!(quiver.positions[4], (15.0, 22.0));
+        assert_eq!(quiver.positions[6], (10.0, 24.0));
+    }
+}
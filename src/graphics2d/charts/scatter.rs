@@ -0,0 +1,202 @@
+use crate::core::{Color, ColorMap, InstanceTransform, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::Axes;
+use crate::graphics2d::charts::axis::value_range;
+use crate::graphics2d::markers::MarkerType;
+use crate::graphics2d::shapes::{Polygon, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// A scatter plot: one instanced marker per `(x, y)` row, optionally sized
+/// and colored by further columns.
+///
+/// Sizes are marker radii in pixels. Values are mapped through a
+/// [`ColorMap`] over [`set_value_range`](Self::set_value_range), or over
+/// the values' own range when none is set. Points outside the plot
+/// rectangle are not drawn. All points go out in one instanced draw call.
+///
+/// ```ignore
+/// let mut scatter = ScatterLayer::new(MarkerType::Circle);
+/// scatter.set_data(&speeds, &altitudes).set_values(&temperatures).set_colormap(ColorMap::Turbo);
+/// // in on_render
+/// scatter.render(renderer, &axes);
+/// ```
+pub struct ScatterLayer {
+    marker: MarkerType,
+    radius: f32,
+    color: Color,
+    colormap: ColorMap,
+    value_range: Option<(f64, f64)>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    sizes: Option<Vec<f32>>,
+    values: Option<Vec<f64>>,
+    /// Marker batch and its instance capacity.
+    batch: Option<(ShapeRenderable, usize)>,
+}
+
+impl ScatterLayer {
+    /// An empty layer drawing `marker`s 4 pixels in radius.
+    pub fn new(marker: MarkerType) -> Self {
+        Self {
+            marker,
+            radius: 4.0,
+            color: Color::from_rgb(0.12, 0.47, 0.71),
+            colormap: ColorMap::Viridis,
+            value_range: None,
+            xs: Vec::new(),
+            ys: Vec::new(),
+            sizes: None,
+            values: None,
+            batch: None,
+        }
+    }
+
+    pub fn set_marker(&mut self, marker: MarkerType) -> &mut Self {
+        self.marker = marker;
+        self.batch = None;
+        self
+    }
+
+    /// Radius of every marker without a size column, in pixels.
+    pub fn set_radius(&mut self, radius: f32) -> &mut Self {
+        self.radius = radius.max(0.5);
+        self.batch = None;
+        self
+    }
+
+    /// Color of every marker without a value column.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.batch = None;
+        self
+    }
+
+    pub fn set_colormap(&mut self, colormap: ColorMap) -> &mut Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Values mapped to the ends of the colormap (default: the values' own
+    /// minimum and maximum).
+    pub fn set_value_range(&mut self, min: f64, max: f64) -> &mut Self {
+        self.value_range = Some((min, max));
+        self
+    }
+
+    /// Point coordinates in data space. Extra entries of the longer slice
+    /// are ignored.
+    pub fn set_data(&mut self, xs: &[f64], ys: &[f64]) -> &mut Self {
+        let n = xs.len().min(ys.len());
+        self.xs = xs[..n].to_vec();
+        self.ys = ys[..n].to_vec();
+        self
+    }
+
+    /// Marker radii in pixels, one per point.
+    pub fn set_sizes(&mut self, sizes: &[f32]) -> &mut Self {
+        self.sizes = Some(sizes.to_vec());
+        self
+    }
+
+    pub fn clear_sizes(&mut self) -> &mut Self {
+        self.sizes = None;
+        self
+    }
+
+    /// Values colored through the colormap, one per point.
+    pub fn set_values(&mut self, values: &[f64]) -> &mut Self {
+        self.values = Some(values.to_vec());
+        self
+    }
+
+    pub fn clear_values(&mut self) -> &mut Self {
+        self.values = None;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    /// Data coordinates of point `index`.
+    pub fn point(&self, index: usize) -> Option<(f64, f64)> {
+        Some((*self.xs.get(index)?, *self.ys.get(index)?))
+    }
+
+    /// Data coordinates of every point, for
+    /// [`Crosshair::update`](super::Crosshair::update).
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.xs.iter().copied().zip(self.ys.iter().copied())
+    }
+
+    /// The point whose marker covers the screen position `at`, nearest
+    /// first.
+    pub fn pick(&self, at: Vec2, axes: &Axes) -> Option<usize> {
+        (0..self.len())
+            .filter_map(|i| {
+                let p = axes.data_to_screen(self.xs[i], self.ys[i]);
+                let d = ((p.x - at.x).powi(2) + (p.y - at.y).powi(2)).sqrt();
+                (d <= self.radius_of(i)).then_some((i, d))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, axes: &Axes) {
+        let rect = axes.rect();
+        let mut positions = Vec::with_capacity(self.len());
+        let mut transforms = Vec::with_capacity(self.len());
+        let mut colors = Vec::with_capacity(self.len());
+        let range = self.value_range.or_else(|| value_range(self.values.as_deref()?));
+        for i in 0..self.len() {
+            let p = axes.data_to_screen(self.xs[i], self.ys[i]);
+            if !rect.contains(p) {
+                continue;
+            }
+            positions.push(p);
+            transforms.push(InstanceTransform::scale(self.radius_of(i) / self.radius));
+            let value = self.values.as_ref().and_then(|v| v.get(i));
+            colors.push(match (value, range) {
+                (Some(&v), Some((min, max))) => self.colormap.sample_range(v as f32, min as f32, max as f32),
+                _ => self.color,
+            });
+        }
+        if positions.is_empty() {
+            return;
+        }
+
+        if self.batch.as_ref().is_none_or(|(_, capacity)| *capacity < positions.len()) {
+            let capacity = positions.len().next_power_of_two();
+            let polygon = Polygon::new(self.marker.vertices(self.radius));
+            let mut shape = ShapeRenderable::from_shape(ShapeKind::Polygon(polygon), ShapeStyle::fill(self.color));
+            shape.create_multiple_instances(capacity);
+            self.batch = Some((shape, capacity));
+        }
+        let (shape, _) = self.batch.as_mut().unwrap();
+        shape.set_instance_positions(&positions).set_instance_transforms(&transforms).set_instance_colors(&colors);
+        shape.render(renderer);
+    }
+
+    fn radius_of(&self, index: usize) -> f32 {
+        self.sizes.as_ref().and_then(|s| s.get(index)).copied().unwrap_or(self.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics2d::charts::{Axis, PlotRect};
+
+    #[test]
+    fn pick_uses_marker_sizes() {
+        let axes = Axes::new(PlotRect::new(0.0, 0.0, 100.0, 100.0), Axis::new(0.0, 10.0), Axis::new(0.0, 10.0));
+        let mut scatter = ScatterLayer::new(MarkerType::Circle);
+        scatter.set_data(&[2.0, 5.0, 8.0], &[5.0, 5.0, 5.0]);
+        assert_eq!(scatter.pick(Vec2::new(52.0, 50.0), &axes), Some(1));
+        assert_eq!(scatter.pick(Vec2::new(30.0, 50.0), &axes), None);
+        scatter.set_sizes(&[12.0, 4.0, 4.0]);
+        assert_eq!(scatter.pick(Vec2::new(30.0, 50.0), &axes), Some(0));
+    }
+}
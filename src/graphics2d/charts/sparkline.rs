@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+
+use crate::core::{Color, InstanceTransform, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::PlotRect;
+use crate::graphics2d::shapes::{Anchor, Polyline, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// A small fixed-size line chart of the latest samples of one channel.
+///
+/// Keeps a rolling window of the last `capacity` values; [`push`](Self::push)
+/// is O(1) and drops the oldest sample once the window is full. The
+/// newest sample is at the right edge. When the window holds more samples
+/// than the sparkline is wide, each pixel column shows the last value and,
+/// as a band behind the line, the minimum and maximum of the samples it
+/// covers, so spikes are not lost.
+///
+/// Geometry is rebuilt at most once per frame and only after new samples;
+/// moving the sparkline with [`set_rect`](Self::set_rect) to a rect of the
+/// same size is free.
+///
+/// ```ignore
+/// let mut sparks: Vec<Sparkline> = (0..32)
+///     .map(|i| Sparkline::new(PlotRect::new(10.0, 10.0 + i as f32 * 24.0, 120.0, 20.0), 600))
+///     .collect();
+/// // on every sample
+/// sparks[channel].push(value);
+/// // in on_render
+/// for spark in &mut sparks { spark.render(renderer); }
+/// ```
+pub struct Sparkline {
+    rect: PlotRect,
+    capacity: usize,
+    values: VecDeque<f32>,
+    range: Option<(f32, f32)>,
+    color: Color,
+    line_width: f32,
+    band_color: Option<Color>,
+    dirty: bool,
+    line: Option<ShapeRenderable>,
+    /// One instanced rectangle per column, and its instance capacity.
+    band: Option<(ShapeRenderable, usize)>,
+    /// Column positions relative to the rect, and the rect corner they were
+    /// last uploaded for (instanced shapes ignore their own position).
+    band_positions: Vec<Vec2>,
+    band_origin: Option<(f32, f32)>,
+}
+
+/// Summary of the samples in one pixel column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Column {
+    index: usize,
+    min: f32,
+    max: f32,
+    last: f32,
+}
+
+impl Sparkline {
+    /// A sparkline drawn in `rect` showing the last `capacity` samples.
+    pub fn new(rect: PlotRect, capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            rect,
+            capacity,
+            values: VecDeque::with_capacity(capacity),
+            range: None,
+            color: Color::from_rgb(0.3, 0.7, 1.0),
+            line_width: 1.0,
+            band_color: Some(Color::from_rgba(0.3, 0.7, 1.0, 0.25)),
+            dirty: true,
+            line: None,
+            band: None,
+            band_positions: Vec::new(),
+            band_origin: None,
+        }
+    }
+
+    /// Append a sample, dropping the oldest if the window is full.
+    pub fn push(&mut self, value: f32) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+        self.dirty = true;
+    }
+
+    pub fn extend(&mut self, values: impl IntoIterator<Item = f32>) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.dirty = true;
+    }
+
+    /// Samples in the window, oldest first.
+    pub fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.values.iter().copied()
+    }
+
+    pub fn last(&self) -> Option<f32> {
+        self.values.back().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn rect(&self) -> PlotRect {
+        self.rect
+    }
+
+    pub fn set_rect(&mut self, rect: PlotRect) -> &mut Self {
+        if (rect.width, rect.height) != (self.rect.width, self.rect.height) {
+            // The band's column width depends on the rect width.
+            self.band = None;
+            self.dirty = true;
+        }
+        self.rect = rect;
+        self
+    }
+
+    /// Values at the bottom and top edge, or `None` (the default) to fit
+    /// the samples in the window.
+    pub fn set_range(&mut self, range: Option<(f32, f32)>) -> &mut Self {
+        self.range = range;
+        self.dirty = true;
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.line = None;
+        self.dirty = true;
+        self
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width;
+        self.line = None;
+        self.dirty = true;
+        self
+    }
+
+    /// Color of the min/max band (`None` to hide it).
+    pub fn set_band_color(&mut self, color: Option<Color>) -> &mut Self {
+        self.band_color = color;
+        self.band = None;
+        self.dirty = true;
+        self
+    }
+
+    pub fn render(&mut self, renderer: &Renderer) {
+        if self.dirty {
+            self.rebuild();
+            self.dirty = false;
+        }
+        let (x, y) = (self.rect.x, self.rect.y);
+        if let Some((band, _)) = self.band.as_mut().filter(|_| !self.band_positions.is_empty()) {
+            if self.band_origin != Some((x, y)) {
+                let positions: Vec<Vec2> = self.band_positions.iter().map(|p| Vec2::new(p.x + x, p.y + y)).collect();
+                band.set_instance_positions(&positions);
+                self.band_origin = Some((x, y));
+            }
+            band.render(renderer);
+        }
+        if let Some(line) = &mut self.line {
+            line.set_position(x, y).render(renderer);
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let (width, height) = (self.rect.width, self.rect.height);
+        let count = (width.floor() as usize).clamp(1, self.capacity);
+        let columns = columns(&self.values, self.capacity, count);
+        let (lo, hi) = self.range.unwrap_or_else(|| {
+            self.values.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)))
+        });
+        let column_width = width / count as f32;
+        let to_y = |v: f32| {
+            let t = if hi > lo { ((v - lo) / (hi - lo)).clamp(0.0, 1.0) } else { 0.5 };
+            height * (1.0 - t)
+        };
+
+        let points: Vec<(f32, f32)> = columns.iter().map(|c| ((c.index as f32 + 0.5) * column_width, to_y(c.last))).collect();
+        match (&mut self.line, points.len()) {
+            (_, 0 | 1) => self.line = None,
+            (Some(line), _) => {
+                line.set_points(&points);
+            }
+            (None, _) => {
+                let style = ShapeStyle::stroke(self.color, self.line_width);
+                // Anchored at the local origin, so the rect corner is the position.
+                let line = ShapeRenderable::builder(ShapeKind::Polyline(Polyline::new(points)), style)
+                    .anchor(Anchor::Custom(0.0, 0.0))
+                    .build();
+                self.line = Some(line);
+            }
+        }
+
+        let Some(band_color) = self.band_color else { return };
+        if self.band.as_ref().is_none_or(|(_, capacity)| *capacity < count) {
+            let mut band = ShapeRenderable::builder(ShapeKind::Rectangle(Rectangle::new(column_width.max(1.0), 1.0)), ShapeStyle::fill(band_color))
+                .anchor(Anchor::Custom(0.0, 0.0))
+                .build();
+            band.create_multiple_instances(count);
+            self.band = Some((band, count));
+        }
+        let (band, _) = self.band.as_mut().unwrap();
+        self.band_positions = columns.iter().map(|c| Vec2::new(c.index as f32 * column_width, to_y(c.max))).collect();
+        self.band_origin = None;
+        let transforms: Vec<InstanceTransform> = columns
+            .iter()
+            .map(|c| InstanceTransform::new(1.0, (to_y(c.min) - to_y(c.max)).max(1.0), 0.0))
+            .collect();
+        band.set_instance_transforms(&transforms);
+    }
+}
+
+/// Group the samples of a `capacity`-long window into `count` columns, the
+/// newest sample in the last column. Columns without samples are skipped.
+fn columns(values: &VecDeque<f32>, capacity: usize, count: usize) -> Vec<Column> {
+    let offset = capacity - values.len().min(capacity);
+    let mut out: Vec<Column> = Vec::with_capacity(count.min(values.len()));
+    for (i, &v) in values.iter().enumerate() {
+        let index = (offset + i) * count / capacity;
+        match out.last_mut() {
+            Some(c) if c.index == index => {
+                c.min = c.min.min(v);
+                c.max = c.max.max(v);
+                c.last = v;
+            }
+            _ => out.push(Column { index, min: v, max: v, last: v }),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_summarise_samples_newest_right() {
+        let values: VecDeque<f32> = [1.0, 5.0, 2.0, 3.0].into_iter().collect();
+        let cols = columns(&values, 4, 2);
+        assert_eq!(cols, vec![Column { index: 0, min: 1.0, max: 5.0, last: 5.0 }, Column { index: 1, min: 2.0, max: 3.0, last: 3.0 }]);
+
+        // A half-full window fills from the right.
+        let values: VecDeque<f32> = [7.0, 8.0].into_iter().collect();
+        let cols = columns(&values, 8, 4);
+        assert_eq!(cols.iter().map(|c| c.index).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(cols[0].last, 8.0);
+    }
+
+    #[test]
+    fn push_keeps_a_rolling_window() {
+        let mut spark = Sparkline::new(PlotRect::new(0.0, 0.0, 100.0, 20.0), 3);
+        spark.extend([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(spark.values().collect::<Vec<_>>(), vec![2.0, 3.0, 4.0]);
+        assert_eq!(spark.last(), Some(4.0));
+    }
+}
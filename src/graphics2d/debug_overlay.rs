@@ -0,0 +1,301 @@
+//! A world-space grid with origin axes and edge rulers, for authoring
+//! scenes and checking projections.
+//!
+//! ```ignore
+//! let mut overlay = DebugOverlay::new();
+//! overlay.set_labels("fonts/DejaVuSans.ttf", 11);
+//! // in on_render
+//! overlay.render(renderer, camera);
+//! ```
+//!
+//! [`App::enable_debug_overlay`](crate::core::App::enable_debug_overlay)
+//! draws one over the app's shapes through its camera.
+
+use std::collections::HashMap;
+
+use crate::core::{Camera2D, Color, Projection, Renderable, Renderer, Vec2};
+use crate::graphics2d::charts::axis::{format_number, tick_step};
+use crate::graphics2d::shapes::{Anchor, GridOverlay, Line, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle, Text};
+
+/// Width of the ruler strips along the top and left edges, in pixels.
+const RULER_SIZE: f32 = 18.0;
+/// Length of a ruler tick, in pixels.
+const TICK_LENGTH: f32 = 6.0;
+/// Distance of the labels from their tick, in pixels.
+const LABEL_MARGIN: f32 = 3.0;
+
+/// Grid lines at a round world spacing chosen for the zoom level, the X and
+/// Y axes through the world origin, and rulers with world coordinates
+/// along the top and left edges of the window.
+pub struct DebugOverlay {
+    visible: bool,
+    min_spacing: f32,
+    grid: GridOverlay,
+    x_axis_color: Color,
+    y_axis_color: Color,
+    ruler_color: Color,
+    label_font: Option<(String, u32)>,
+    label_color: Color,
+    /// Axis lines, ruler strips and ticks, and the window size they were
+    /// built for.
+    shapes: Option<((i32, i32), OverlayShapes)>,
+    labels: HashMap<String, ShapeRenderable>,
+}
+
+struct OverlayShapes {
+    x_axis: ShapeRenderable,
+    y_axis: ShapeRenderable,
+    top_ruler: ShapeRenderable,
+    left_ruler: ShapeRenderable,
+    top_ticks: ShapeRenderable,
+    left_ticks: ShapeRenderable,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    /// A visible overlay without labels, grid lines at least 80 pixels
+    /// apart.
+    pub fn new() -> Self {
+        let mut grid = GridOverlay::new(1.0);
+        grid.set_color(Color::from_rgba(0.5, 0.5, 0.5, 0.35));
+        Self {
+            visible: true,
+            min_spacing: 80.0,
+            grid,
+            x_axis_color: Color::from_rgba(0.9, 0.25, 0.25, 0.8),
+            y_axis_color: Color::from_rgba(0.25, 0.8, 0.3, 0.8),
+            ruler_color: Color::from_rgba(0.1, 0.1, 0.1, 0.6),
+            label_font: None,
+            label_color: Color::from_rgba(0.9, 0.9, 0.9, 0.95),
+            shapes: None,
+            labels: HashMap::new(),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn toggle(&mut self) -> &mut Self {
+        self.visible = !self.visible;
+        self
+    }
+
+    /// Smallest distance between two grid lines, in pixels. The spacing is
+    /// the round world value (1, 2, 2.5 or 5 times a power of ten) closest
+    /// to this.
+    pub fn set_min_spacing(&mut self, pixels: f32) -> &mut Self {
+        self.min_spacing = pixels.max(4.0);
+        self
+    }
+
+    pub fn set_grid_color(&mut self, color: Color) -> &mut Self {
+        self.grid.set_color(color);
+        self
+    }
+
+    /// Colors of the X axis (the line `y = 0`) and the Y axis (`x = 0`).
+    pub fn set_axis_colors(&mut self, x_axis: Color, y_axis: Color) -> &mut Self {
+        self.x_axis_color = x_axis;
+        self.y_axis_color = y_axis;
+        self.shapes = None;
+        self
+    }
+
+    pub fn set_ruler_color(&mut self, color: Color) -> &mut Self {
+        self.ruler_color = color;
+        self.shapes = None;
+        self
+    }
+
+    /// Label the ruler ticks with world coordinates in this font.
+    pub fn set_labels(&mut self, font_path: impl Into<String>, font_size: u32) -> &mut Self {
+        self.label_font = Some((font_path.into(), font_size));
+        self.labels.clear();
+        self
+    }
+
+    pub fn clear_labels(&mut self) -> &mut Self {
+        self.label_font = None;
+        self.labels.clear();
+        self
+    }
+
+    pub fn set_label_color(&mut self, color: Color) -> &mut Self {
+        self.label_color = color;
+        self.labels.clear();
+        self
+    }
+
+    /// The world distance between grid lines at the camera's scale.
+    pub fn spacing(&self, camera: &Camera2D) -> f32 {
+        let (x0, _, x1, _) = camera.world_bounds();
+        let width = camera.screen_size().x;
+        overlay_spacing(x0, x1, width, self.min_spacing)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        if !self.visible {
+            return;
+        }
+        let spacing = self.spacing(camera);
+        self.grid.set_spacing(spacing).render_with_camera(renderer, camera);
+
+        let size = renderer.window_handle.size();
+        if self.shapes.as_ref().is_none_or(|(built, _)| *built != size) {
+            self.shapes = Some((size, self.build_shapes(size)));
+        }
+        let (_, shapes) = self.shapes.as_mut().unwrap();
+        let (w, h) = (size.0 as f32, size.1 as f32);
+
+        let origin = camera.world_to_screen(Vec2::new(0.0, 0.0));
+        if (0.0..=h).contains(&origin.y) {
+            shapes.x_axis.set_position(0.0, origin.y).render(renderer);
+        }
+        if (0.0..=w).contains(&origin.x) {
+            shapes.y_axis.set_position(origin.x, 0.0).render(renderer);
+        }
+
+        let (x0, y0, x1, y1) = camera.world_bounds();
+        let columns: Vec<(f32, f64)> = multiples(x0, x1, spacing)
+            .map(|x| (camera.world_to_screen(Vec2::new(x as f32, 0.0)).x, x))
+            .filter(|&(sx, _)| sx >= RULER_SIZE)
+            .collect();
+        let rows: Vec<(f32, f64)> = multiples(y0, y1, spacing)
+            .map(|y| (camera.world_to_screen(Vec2::new(0.0, y as f32)).y, y))
+            .filter(|&(sy, _)| sy >= RULER_SIZE)
+            .collect();
+
+        shapes.top_ruler.render(renderer);
+        shapes.left_ruler.render(renderer);
+        // An empty instance list would fall back to a single draw at (0, 0).
+        if !columns.is_empty() {
+            let positions: Vec<Vec2> = columns.iter().map(|&(x, _)| Vec2::new(x, RULER_SIZE - TICK_LENGTH)).collect();
+            shapes.top_ticks.set_instance_positions(&positions).render(renderer);
+        }
+        if !rows.is_empty() {
+            let positions: Vec<Vec2> = rows.iter().map(|&(y, _)| Vec2::new(RULER_SIZE - TICK_LENGTH, y)).collect();
+            shapes.left_ticks.set_instance_positions(&positions).render(renderer);
+        }
+
+        if self.label_font.is_some() {
+            self.draw_labels(renderer, spacing as f64, &columns, &rows);
+        }
+    }
+
+    fn build_shapes(&self, (w, h): (i32, i32)) -> OverlayShapes {
+        let (w, h) = (w as f32, h as f32);
+        let line = |start, end, color| {
+            ShapeRenderable::builder(ShapeKind::Line(Line::new(start, end)), ShapeStyle::stroke(color, 1.0))
+                .anchor(Anchor::Custom(0.0, 0.0))
+                .build()
+        };
+        let strip = |width, height| {
+            ShapeRenderable::builder(ShapeKind::Rectangle(Rectangle::new(width, height)), ShapeStyle::fill(self.ruler_color))
+                .anchor(Anchor::NorthWest)
+                .build()
+        };
+        let mut top_ticks = line((0.0, 0.0), (0.0, TICK_LENGTH), self.label_color);
+        let mut left_ticks = line((0.0, 0.0), (TICK_LENGTH, 0.0), self.label_color);
+        top_ticks.create_multiple_instances(w as usize / 4 + 1);
+        left_ticks.create_multiple_instances(h as usize / 4 + 1);
+        let mut top_ruler = strip(w, RULER_SIZE);
+        top_ruler.set_position(0.0, 0.0);
+        let mut left_ruler = strip(RULER_SIZE, (h - RULER_SIZE).max(0.0));
+        left_ruler.set_position(0.0, RULER_SIZE);
+        OverlayShapes {
+            x_axis: line((0.0, 0.0), (w, 0.0), self.x_axis_color),
+            y_axis: line((0.0, 0.0), (0.0, h), self.y_axis_color),
+            top_ruler,
+            left_ruler,
+            top_ticks,
+            left_ticks,
+        }
+    }
+
+    fn draw_labels(&mut self, renderer: &Renderer, spacing: f64, columns: &[(f32, f64)], rows: &[(f32, f64)]) {
+        let Some((font_path, font_size)) = self.label_font.clone() else { return };
+        let wanted = columns
+            .iter()
+            .map(|&(x, value)| (format_number(value, spacing), Anchor::SouthWest, (x + LABEL_MARGIN, RULER_SIZE - LABEL_MARGIN)))
+            .chain(
+                rows.iter()
+                    .map(|&(y, value)| (format_number(value, spacing), Anchor::NorthWest, (LABEL_MARGIN, y + LABEL_MARGIN))),
+            );
+
+        let mut used = HashMap::with_capacity(self.labels.len());
+        for (text, anchor, (x, y)) in wanted {
+            // Column and row labels can share text, but not an anchor.
+            let key = format!("{anchor:?}{text}");
+            let mut label = self.labels.remove(&key).unwrap_or_else(|| {
+                ShapeRenderable::builder(ShapeKind::Text(Text::new(text, font_path.as_str(), font_size)), ShapeStyle::fill(self.label_color))
+                    .anchor(anchor)
+                    .build()
+            });
+            label.set_position(x, y).render(renderer);
+            used.insert(key, label);
+        }
+        self.labels = used;
+    }
+}
+
+/// The round world spacing (see [`tick_step`]) giving lines about
+/// `min_spacing` pixels apart across `x0..x1` shown over `width` pixels,
+/// never closer than `min_spacing`.
+fn overlay_spacing(x0: f32, x1: f32, width: f32, min_spacing: f32) -> f32 {
+    let span = (x1 - x0) as f64;
+    if span <= 0.0 || width <= 0.0 {
+        return 1.0;
+    }
+    let target = (width / min_spacing).floor().max(1.0) as usize;
+    let mut step = tick_step(x0 as f64, x1 as f64, target);
+    // `tick_step` rounds to the nearest count; widen until lines are far
+    // enough apart.
+    while step / span * (width as f64) < min_spacing as f64 {
+        step = tick_step(0.0, step * 2.0, 1);
+    }
+    step as f32
+}
+
+/// Multiples of `step` within `min..=max`.
+fn multiples(min: f32, max: f32, step: f32) -> impl Iterator<Item = f64> {
+    let (min, max, step) = (min as f64, max as f64, step as f64);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(move |i| i as f64 * step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spacing_is_round_and_at_least_min_pixels() {
+        // 800 px showing 0..800: 80 px apart is 100 units (8 lines).
+        assert_eq!(overlay_spacing(0.0, 800.0, 800.0, 80.0), 100.0);
+        // Zoomed out 10x.
+        assert_eq!(overlay_spacing(0.0, 8000.0, 800.0, 80.0), 1000.0);
+        // Zoomed in: 0..8 over 800 px.
+        assert_eq!(overlay_spacing(0.0, 8.0, 800.0, 80.0), 1.0);
+        for (x1, width) in [(123.0, 640.0), (7.7, 1920.0), (55555.0, 300.0)] {
+            let spacing = overlay_spacing(0.0, x1, width, 80.0);
+            assert!(spacing / x1 * width >= 80.0, "{spacing} for {x1} over {width}");
+        }
+    }
+
+    #[test]
+    fn multiples_cover_range() {
+        assert_eq!(multiples(-25.0, 31.0, 10.0).collect::<Vec<_>>(), vec![-20.0, -10.0, 0.0, 10.0, 20.0, 30.0]);
+        assert_eq!(multiples(1.0, 9.0, 10.0).count(), 0);
+    }
+}
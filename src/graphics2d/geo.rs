@@ -0,0 +1,229 @@
+//! Geographic helpers: WGS84 longitude/latitude to world coordinates and
+//! routes that follow the curvature of the Earth.
+//!
+//! World coordinates are Web Mercator meters with `y` pointing south, the
+//! convention used by the [tile layers](super::tiles) and the waypoints
+//! example. On a Mercator map the shortest path between two places is not
+//! a straight line: [`route_polyline`] densifies a great-circle (or rhumb
+//! line) route so it is drawn with the right curve.
+//!
+//! ```ignore
+//! let route = route_polyline((-73.78, 40.64), (103.99, 1.36), RouteKind::GreatCircle, 50_000.0);
+//! // project to the screen each frame
+//! let screen: Vec<(f32, f32)> = route.points.iter()
+//!     .map(|&(x, y)| { let p = camera.world_to_screen(Vec2::new(x, y)); (p.x, p.y) })
+//!     .collect();
+//! flight.set_shape(ShapeKind::Polyline(Polyline::new(screen)));
+//! ```
+
+use std::f64::consts::{FRAC_PI_4, PI};
+
+use crate::core::Vec2;
+use crate::graphics2d::shapes::Polyline;
+
+/// WGS84 semi-major axis, the sphere radius of Web Mercator, in meters.
+pub const EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// Latitude where Web Mercator is cut off, making the world square.
+pub const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// World coordinates of a longitude/latitude in degrees. Latitudes are
+/// clamped to [`MAX_MERCATOR_LATITUDE`].
+pub fn lon_lat_to_world(lon: f64, lat: f64) -> Vec2 {
+    let lat = lat.clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE).to_radians();
+    let x = lon.to_radians() * EARTH_RADIUS;
+    let y = (FRAC_PI_4 + lat / 2.0).tan().ln() * EARTH_RADIUS;
+    Vec2::new(x as f32, -y as f32)
+}
+
+/// Longitude/latitude in degrees of world coordinates.
+pub fn world_to_lon_lat(world: Vec2) -> (f64, f64) {
+    let lon = (world.x as f64 / EARTH_RADIUS).to_degrees();
+    let lat = (2.0 * (-world.y as f64 / EARTH_RADIUS).exp().atan() - PI / 2.0).to_degrees();
+    (lon, lat)
+}
+
+/// Distance in meters along the great circle between two
+/// `(longitude, latitude)` points in degrees (haversine formula).
+pub fn great_circle_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.1.to_radians(), to.1.to_radians());
+    let (dlat, dlon) = (lat2 - lat1, (to.0 - from.0).to_radians());
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// The point `distance` meters from `from` along the great circle leaving
+/// it at `bearing` degrees clockwise from north, as `(longitude, latitude)`
+/// in degrees.
+pub fn destination_point(from: (f64, f64), bearing: f64, distance: f64) -> (f64, f64) {
+    let (lon, lat) = (from.0.to_radians(), from.1.to_radians());
+    let (bearing, angle) = (bearing.to_radians(), distance / EARTH_RADIUS);
+    let lat2 = (lat.sin() * angle.cos() + lat.cos() * angle.sin() * bearing.cos()).clamp(-1.0, 1.0).asin();
+    let lon2 = lon + (bearing.sin() * angle.sin() * lat.cos()).atan2(angle.cos() - lat.sin() * lat2.sin());
+    (lon2.to_degrees(), lat2.to_degrees())
+}
+
+/// `segments` points on the circle of all places `radius` meters from
+/// `center`, starting north and going clockwise. Longitudes are unwrapped
+/// around the center's. Drawn on a Mercator map the circle grows and
+/// stretches towards the pole.
+pub fn geodesic_circle(center: (f64, f64), radius: f64, segments: usize) -> Vec<(f64, f64)> {
+    let segments = segments.max(3);
+    (0..segments)
+        .map(|i| {
+            let (lon, lat) = destination_point(center, i as f64 * 360.0 / segments as f64, radius);
+            (lon - ((lon - center.0) / 360.0).round() * 360.0, lat)
+        })
+        .collect()
+}
+
+/// How a route between two places runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RouteKind {
+    /// The shortest path over the globe. Curves towards the pole on a
+    /// Mercator map.
+    #[default]
+    GreatCircle,
+    /// A loxodrome: constant compass bearing, a straight line on a
+    /// Mercator map, but longer than the great circle.
+    Rhumb,
+}
+
+/// Points along the route from `from` to `to` (`(longitude, latitude)` in
+/// degrees), at most `max_segment` meters apart.
+///
+/// Longitudes are unwrapped rather than kept in `-180..=180`, so a route
+/// across the antimeridian continues past ±180 instead of jumping to the
+/// other side of the map. Antipodal points have no unique great circle;
+/// one of them is picked.
+pub fn route_points(from: (f64, f64), to: (f64, f64), kind: RouteKind, max_segment: f64) -> Vec<(f64, f64)> {
+    let distance = great_circle_distance(from, to);
+    let segments = if max_segment > 0.0 { (distance / max_segment).ceil().max(1.0) as usize } else { 1 };
+    let mut points: Vec<(f64, f64)> = match kind {
+        RouteKind::GreatCircle => great_circle(from, to, segments),
+        RouteKind::Rhumb => rhumb_line(from, to, segments),
+    };
+    for i in 1..points.len() {
+        let previous = points[i - 1].0;
+        points[i].0 -= ((points[i].0 - previous) / 360.0).round() * 360.0;
+    }
+    points
+}
+
+/// A route as a polyline in world coordinates. See [`route_points`].
+pub fn route_polyline(from: (f64, f64), to: (f64, f64), kind: RouteKind, max_segment: f64) -> Polyline {
+    let points = route_points(from, to, kind, max_segment)
+        .into_iter()
+        .map(|(lon, lat)| {
+            let p = lon_lat_to_world(lon, lat);
+            (p.x, p.y)
+        })
+        .collect();
+    Polyline::new(points)
+}
+
+fn unit_vector((lon, lat): (f64, f64)) -> [f64; 3] {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn great_circle(from: (f64, f64), to: (f64, f64), segments: usize) -> Vec<(f64, f64)> {
+    let (a, b) = (unit_vector(from), unit_vector(to));
+    let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cross = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+    let angle = (cross[0].powi(2) + cross[1].powi(2) + cross[2].powi(2)).sqrt().atan2(dot);
+    if angle < 1e-12 {
+        return vec![from, to];
+    }
+    // Antipodal endpoints: pick a point a quarter turn away and go through
+    // it, half a turn in two halves.
+    if (PI - angle) < 1e-9 {
+        let via = if from.1.abs() < 45.0 { (from.0, 90.0) } else { (from.0 + 90.0, 0.0) };
+        let mut points = great_circle(from, via, segments.div_ceil(2));
+        points.extend(great_circle(via, to, segments.div_ceil(2)).into_iter().skip(1));
+        return points;
+    }
+    (0..=segments)
+        .map(|i| {
+            let f = i as f64 / segments as f64;
+            let (wa, wb) = (((1.0 - f) * angle).sin() / angle.sin(), (f * angle).sin() / angle.sin());
+            let p = [wa * a[0] + wb * b[0], wa * a[1] + wb * b[1], wa * a[2] + wb * b[2]];
+            let lat = p[2].clamp(-1.0, 1.0).asin().to_degrees();
+            let lon = p[1].atan2(p[0]).to_degrees();
+            (lon, lat)
+        })
+        .collect()
+}
+
+fn rhumb_line(from: (f64, f64), to: (f64, f64), segments: usize) -> Vec<(f64, f64)> {
+    // Straight in Mercator: longitude and Mercator y change linearly. Take
+    // the shorter way around in longitude.
+    let mercator_y = |lat: f64| (FRAC_PI_4 + lat.clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE).to_radians() / 2.0).tan().ln();
+    let dlon = to.0 - from.0 - ((to.0 - from.0) / 360.0).round() * 360.0;
+    let (y0, y1) = (mercator_y(from.1), mercator_y(to.1));
+    (0..=segments)
+        .map(|i| {
+            let f = i as f64 / segments as f64;
+            let y = y0 + (y1 - y0) * f;
+            (from.0 + dlon * f, (2.0 * y.exp().atan() - PI / 2.0).to_degrees())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_coordinates_round_trip_with_y_south() {
+        let zurich = lon_lat_to_world(8.5417, 47.3769);
+        assert!(zurich.y < 0.0);
+        let (lon, lat) = world_to_lon_lat(zurich);
+        assert!((lon - 8.5417).abs() < 1e-4 && (lat - 47.3769).abs() < 1e-4);
+        assert!((lon_lat_to_world(180.0, 0.0).x as f64 - PI * EARTH_RADIUS).abs() < 1.0);
+    }
+
+    #[test]
+    fn distance_of_one_degree_on_the_equator() {
+        let d = great_circle_distance((0.0, 0.0), (1.0, 0.0));
+        assert!((d - EARTH_RADIUS * PI / 180.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn destination_and_circle_keep_their_distance() {
+        let zurich = (8.5417, 47.3769);
+        let north = destination_point(zurich, 0.0, 111_319.5);
+        assert!((north.0 - zurich.0).abs() < 1e-9 && (north.1 - zurich.1 - 1.0).abs() < 1e-3);
+        let circle = geodesic_circle(zurich, 50_000.0, 36);
+        assert_eq!(circle.len(), 36);
+        assert!(circle.iter().all(|&p| (great_circle_distance(zurich, p) - 50_000.0).abs() < 0.01));
+        let dateline = geodesic_circle((179.9, 0.0), 100_000.0, 8);
+        assert!(dateline.iter().all(|p| (p.0 - 179.9).abs() < 2.0));
+    }
+
+    #[test]
+    fn great_circle_bends_poleward_and_is_densified() {
+        let (jfk, cdg) = ((-73.78, 40.64), (2.55, 49.01));
+        let points = route_points(jfk, cdg, RouteKind::GreatCircle, 100_000.0);
+        assert!(points.len() > 55);
+        let close = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+        assert!(close(points[0], jfk) && close(points[points.len() - 1], cdg));
+        assert!(points.iter().map(|p| p.1).fold(f64::MIN, f64::max) > 51.0);
+
+        let equator = route_points((0.0, 0.0), (90.0, 0.0), RouteKind::GreatCircle, 0.0);
+        assert_eq!(equator.len(), 2);
+        let antipodal = route_points((10.0, 0.0), (-170.0, 0.0), RouteKind::GreatCircle, 1_000_000.0);
+        assert!(antipodal.iter().any(|p| p.1 > 89.0));
+    }
+
+    #[test]
+    fn rhumb_keeps_latitude_and_routes_cross_the_antimeridian() {
+        let points = route_points((170.0, 30.0), (-170.0, 30.0), RouteKind::Rhumb, 100_000.0);
+        assert!(points.iter().all(|p| (p.1 - 30.0).abs() < 1e-9));
+        assert!(points.windows(2).all(|w| w[1].0 > w[0].0));
+        assert!((points[points.len() - 1].0 - 190.0).abs() < 1e-9);
+
+        let gc = route_points((170.0, 30.0), (-170.0, 30.0), RouteKind::GreatCircle, 100_000.0);
+        assert!(gc.windows(2).all(|w| (w[1].0 - w[0].0).abs() < 5.0));
+    }
+}
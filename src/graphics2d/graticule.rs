@@ -0,0 +1,275 @@
+//! A latitude/longitude grid over a Web Mercator map.
+//!
+//! Meridians and parallels are straight lines in Web Mercator, so the
+//! graticule is drawn like a [`GridOverlay`](super::shapes::GridOverlay)
+//! whose horizontal lines are unevenly spaced. The interval is picked from
+//! a list of round values (90°, 45°, ... 1°, 30', ... 1") so lines stay a
+//! readable distance apart at every zoom level.
+//!
+//! ```ignore
+//! let mut graticule = Graticule::new();
+//! graticule.set_labels("fonts/DejaVuSans.ttf", 12);
+//! // in on_render, after the map layers
+//! graticule.render(renderer, camera);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::core::{Camera2D, Color, Projection, Renderable, Renderer, Vec2};
+use crate::graphics2d::geo::{EARTH_RADIUS, MAX_MERCATOR_LATITUDE, lon_lat_to_world, world_to_lon_lat};
+use crate::graphics2d::shapes::{Anchor, Line, ShapeKind, ShapeRenderable, ShapeStyle, Text};
+
+/// Candidate intervals in degrees, from coarsest to finest.
+const INTERVALS: [f64; 22] = [
+    90.0,
+    45.0,
+    30.0,
+    20.0,
+    10.0,
+    5.0,
+    2.0,
+    1.0,
+    30.0 / 60.0,
+    20.0 / 60.0,
+    15.0 / 60.0,
+    10.0 / 60.0,
+    5.0 / 60.0,
+    2.0 / 60.0,
+    1.0 / 60.0,
+    30.0 / 3600.0,
+    20.0 / 3600.0,
+    15.0 / 3600.0,
+    10.0 / 3600.0,
+    5.0 / 3600.0,
+    2.0 / 3600.0,
+    1.0 / 3600.0,
+];
+
+/// Distance of the labels from the window edge, in pixels.
+const LABEL_MARGIN: f32 = 4.0;
+
+/// Meridians and parallels for a map in Web Mercator world coordinates
+/// (see [`geo`](super::geo)), with degree labels along the top and left
+/// edges of the window.
+pub struct Graticule {
+    min_spacing: f32,
+    color: Color,
+    line_width: f32,
+    label_font: Option<(String, u32)>,
+    label_color: Color,
+    /// Meridian and parallel renderables, and the window size they were
+    /// built for.
+    lines: Option<((i32, i32), ShapeRenderable, ShapeRenderable)>,
+    labels: HashMap<String, ShapeRenderable>,
+}
+
+impl Default for Graticule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Graticule {
+    /// A graticule without labels, lines at least 100 pixels apart.
+    pub fn new() -> Self {
+        Self {
+            min_spacing: 100.0,
+            color: Color::from_rgba(0.5, 0.5, 0.5, 0.5),
+            line_width: 1.0,
+            label_font: None,
+            label_color: Color::from_rgba(0.2, 0.2, 0.2, 0.9),
+            lines: None,
+            labels: HashMap::new(),
+        }
+    }
+
+    /// Smallest distance between two meridians, in pixels. The interval is
+    /// the finest round value keeping lines at least this far apart.
+    pub fn set_min_spacing(&mut self, pixels: f32) -> &mut Self {
+        self.min_spacing = pixels.max(1.0);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.lines = None;
+        self
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width;
+        self.lines = None;
+        self
+    }
+
+    /// Label meridians along the top edge and parallels along the left edge.
+    pub fn set_labels(&mut self, font_path: impl Into<String>, font_size: u32) -> &mut Self {
+        self.label_font = Some((font_path.into(), font_size));
+        self.labels.clear();
+        self
+    }
+
+    pub fn clear_labels(&mut self) -> &mut Self {
+        self.label_font = None;
+        self.labels.clear();
+        self
+    }
+
+    pub fn set_label_color(&mut self, color: Color) -> &mut Self {
+        self.label_color = color;
+        self.labels.clear();
+        self
+    }
+
+    /// The interval in degrees used at the camera's scale.
+    pub fn interval(&self, camera: &Camera2D) -> f64 {
+        let degree_px = EARTH_RADIUS.to_radians() * camera.scale() as f64;
+        graticule_interval(degree_px, self.min_spacing as f64)
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        let interval = self.interval(camera);
+        let (x0, y0, x1, y1) = camera.world_bounds();
+        let (lon0, lat0) = world_to_lon_lat(Vec2::new(x0, y1));
+        let (lon1, lat1) = world_to_lon_lat(Vec2::new(x1, y0));
+        let lat0 = lat0.max(-MAX_MERCATOR_LATITUDE);
+        let lat1 = lat1.min(MAX_MERCATOR_LATITUDE);
+
+        let meridians: Vec<(f32, f64)> = graticule_values(lon0, lon1, interval)
+            .into_iter()
+            .map(|lon| (camera.world_to_screen(lon_lat_to_world(lon, 0.0)).x, lon))
+            .collect();
+        let parallels: Vec<(f32, f64)> = graticule_values(lat0, lat1, interval)
+            .into_iter()
+            .map(|lat| (camera.world_to_screen(lon_lat_to_world(0.0, lat)).y, lat))
+            .collect();
+
+        self.draw_lines(renderer, &meridians, &parallels);
+        if self.label_font.is_some() {
+            self.draw_labels(renderer, interval, &meridians, &parallels);
+        }
+    }
+
+    fn draw_lines(&mut self, renderer: &Renderer, meridians: &[(f32, f64)], parallels: &[(f32, f64)]) {
+        let size = renderer.window_handle.size();
+        if self.lines.as_ref().is_none_or(|(built, _, _)| *built != size) {
+            let (w, h) = (size.0 as f32, size.1 as f32);
+            let style = ShapeStyle::stroke(self.color, self.line_width);
+            let mut vertical = ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (0.0, h))), style.clone());
+            let mut horizontal = ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (w, 0.0))), style);
+            vertical.create_multiple_instances(meridians.len().max(1));
+            horizontal.create_multiple_instances(parallels.len().max(1));
+            self.lines = Some((size, vertical, horizontal));
+        }
+        let (_, vertical, horizontal) = self.lines.as_mut().unwrap();
+        // An empty instance list would fall back to a single draw at (0, 0).
+        if !meridians.is_empty() {
+            let positions: Vec<Vec2> = meridians.iter().map(|&(x, _)| Vec2::new(x, 0.0)).collect();
+            vertical.set_instance_positions(&positions).render(renderer);
+        }
+        if !parallels.is_empty() {
+            let positions: Vec<Vec2> = parallels.iter().map(|&(y, _)| Vec2::new(0.0, y)).collect();
+            horizontal.set_instance_positions(&positions).render(renderer);
+        }
+    }
+
+    fn draw_labels(&mut self, renderer: &Renderer, interval: f64, meridians: &[(f32, f64)], parallels: &[(f32, f64)]) {
+        let Some((font_path, font_size)) = self.label_font.clone() else { return };
+        let wanted = meridians
+            .iter()
+            .map(|&(x, lon)| (format_degrees(wrap_longitude(lon), interval, 'E', 'W'), Anchor::North, (x, LABEL_MARGIN)))
+            .chain(
+                parallels
+                    .iter()
+                    .map(|&(y, lat)| (format_degrees(lat, interval, 'N', 'S'), Anchor::West, (LABEL_MARGIN, y))),
+            );
+
+        let mut used = HashMap::with_capacity(self.labels.len());
+        for (text, anchor, (x, y)) in wanted {
+            // Longitude and latitude labels can share text ("0°"), but not
+            // an anchor.
+            let key = format!("{anchor:?}{text}");
+            let mut label = self.labels.remove(&key).unwrap_or_else(|| {
+                ShapeRenderable::builder(ShapeKind::Text(Text::new(text, font_path.as_str(), font_size)), ShapeStyle::fill(self.label_color))
+                    .anchor(anchor)
+                    .build()
+            });
+            label.set_position(x, y).render(renderer);
+            used.insert(key, label);
+        }
+        self.labels = used;
+    }
+}
+
+/// The finest interval in degrees with lines at least `min_spacing` pixels
+/// apart, when a degree spans `degree_px` pixels.
+fn graticule_interval(degree_px: f64, min_spacing: f64) -> f64 {
+    INTERVALS
+        .iter()
+        .rev()
+        .copied()
+        .find(|interval| interval * degree_px >= min_spacing)
+        .unwrap_or(INTERVALS[0])
+}
+
+/// Multiples of `interval` within `min..=max`.
+fn graticule_values(min: f64, max: f64, interval: f64) -> Vec<f64> {
+    let first = (min / interval).ceil() as i64;
+    let last = (max / interval).floor() as i64;
+    (first..=last).map(|i| i as f64 * interval).collect()
+}
+
+/// A longitude in `-180..=180`, for maps panned past the antimeridian.
+fn wrap_longitude(lon: f64) -> f64 {
+    let wrapped = (lon + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 && lon > 0.0 { 180.0 } else { wrapped }
+}
+
+/// `value` as degrees, minutes and seconds down to the precision of
+/// `interval`, with the hemisphere letter: `12°30'N`, `0°`.
+fn format_degrees(value: f64, interval: f64, positive: char, negative: char) -> String {
+    let total_seconds = (value.abs() * 3600.0).round() as u64;
+    let (degrees, minutes, seconds) = (total_seconds / 3600, total_seconds / 60 % 60, total_seconds % 60);
+    let mut text = format!("{degrees}°");
+    if interval < 1.0 {
+        text.push_str(&format!("{minutes:02}'"));
+    }
+    if interval < 1.0 / 60.0 {
+        text.push_str(&format!("{seconds:02}\""));
+    }
+    if total_seconds != 0 && total_seconds != 180 * 3600 {
+        text.push(if value > 0.0 { positive } else { negative });
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_gets_finer_when_zoomed_in() {
+        assert_eq!(graticule_interval(1.0, 100.0), 90.0);
+        assert_eq!(graticule_interval(10.0, 100.0), 10.0);
+        assert_eq!(graticule_interval(100.0, 100.0), 1.0);
+        assert_eq!(graticule_interval(150.0, 100.0), 1.0);
+        assert_eq!(graticule_interval(6000.0, 100.0), 1.0 / 60.0);
+        assert_eq!(graticule_interval(1e9, 100.0), 1.0 / 3600.0);
+    }
+
+    #[test]
+    fn values_are_multiples_within_range() {
+        assert_eq!(graticule_values(-25.0, 31.0, 10.0), vec![-20.0, -10.0, 0.0, 10.0, 20.0, 30.0]);
+        assert!(graticule_values(1.0, 9.0, 10.0).is_empty());
+    }
+
+    #[test]
+    fn labels_show_hemisphere_and_precision() {
+        assert_eq!(format_degrees(0.0, 10.0, 'N', 'S'), "0°");
+        assert_eq!(format_degrees(45.0, 5.0, 'N', 'S'), "45°N");
+        assert_eq!(format_degrees(-12.5, 0.5, 'N', 'S'), "12°30'S");
+        assert_eq!(format_degrees(8.0 + 15.0 / 3600.0, 5.0 / 3600.0, 'E', 'W'), "8°00'15\"E");
+        assert_eq!(format_degrees(wrap_longitude(190.0), 10.0, 'E', 'W'), "170°W");
+        assert_eq!(format_degrees(wrap_longitude(180.0), 10.0, 'E', 'W'), "180°");
+    }
+}
@@ -1,2 +1,12 @@
+pub mod background;
+pub mod charts;
+pub mod debug_overlay;
+pub mod geo;
+pub mod graticule;
 pub mod markers;
+pub mod range_rings;
 pub mod shapes;
+pub mod sprites;
+pub mod tessellation;
+pub mod tiles;
+pub mod vector_tiles;
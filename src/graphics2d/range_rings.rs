@@ -0,0 +1,227 @@
+//! Concentric distance rings around a place on a Web Mercator map, as on
+//! radar, drone and ATC displays.
+//!
+//! ```ignore
+//! let mut rings = RangeRings::new((8.5492, 47.4582), &[10_000.0, 20_000.0, 50_000.0]);
+//! rings.set_labels("fonts/DejaVuSans.ttf", 12);
+//! // in on_render
+//! rings.render(renderer, camera);
+//! ```
+
+use crate::core::{Camera2D, Color, Projection, Renderable, Renderer};
+use crate::graphics2d::geo::{destination_point, geodesic_circle, lon_lat_to_world};
+use crate::graphics2d::shapes::{Anchor, Polygon, ShapeKind, ShapeRenderable, ShapeStyle, Text};
+
+/// Meters in a nautical mile.
+const NAUTICAL_MILE: f64 = 1852.0;
+/// Meters in a statute mile.
+const STATUTE_MILE: f64 = 1609.344;
+/// Gap between a ring and its label, in pixels.
+const LABEL_MARGIN: f32 = 2.0;
+
+/// Unit of the ring labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceUnit {
+    /// Meters below one kilometer, kilometers above.
+    #[default]
+    Metric,
+    NauticalMiles,
+    StatuteMiles,
+}
+
+/// Rings at fixed distances in meters around a `(longitude, latitude)`
+/// center, optionally labeled with their distance.
+///
+/// Each ring is the geodesic circle of all places at that distance, so it
+/// is drawn larger, and slightly egg-shaped, towards the poles, like
+/// everything else on a Mercator map. The outlines are rebuilt only when
+/// the camera's scale changes; panning just moves them.
+pub struct RangeRings {
+    center: (f64, f64),
+    radii: Vec<f64>,
+    segments: usize,
+    color: Color,
+    line_width: f32,
+    dash: Option<(f32, f32)>,
+    label_font: Option<(String, u32)>,
+    label_color: Color,
+    label_bearing: f64,
+    unit: DistanceUnit,
+    /// Ring outlines and the camera scale they were built for.
+    rings: Option<(f32, Vec<ShapeRenderable>)>,
+    labels: Option<Vec<ShapeRenderable>>,
+}
+
+impl RangeRings {
+    /// Rings `radii` meters around `center` (`(longitude, latitude)` in
+    /// degrees).
+    pub fn new(center: (f64, f64), radii: &[f64]) -> Self {
+        Self {
+            center,
+            radii: radii.to_vec(),
+            segments: 128,
+            color: Color::from_rgba(0.2, 0.8, 0.3, 0.8),
+            line_width: 1.0,
+            dash: None,
+            label_font: None,
+            label_color: Color::from_rgba(0.2, 0.8, 0.3, 1.0),
+            label_bearing: 0.0,
+            unit: DistanceUnit::Metric,
+            rings: None,
+            labels: None,
+        }
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        self.center
+    }
+
+    pub fn set_center(&mut self, center: (f64, f64)) -> &mut Self {
+        self.center = center;
+        self.rings = None;
+        self
+    }
+
+    pub fn radii(&self) -> &[f64] {
+        &self.radii
+    }
+
+    pub fn set_radii(&mut self, radii: &[f64]) -> &mut Self {
+        self.radii = radii.to_vec();
+        self.rings = None;
+        self.labels = None;
+        self
+    }
+
+    /// Number of points on each ring (default 128, at least 3).
+    pub fn set_segments(&mut self, segments: usize) -> &mut Self {
+        self.segments = segments.max(3);
+        self.rings = None;
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.rings = None;
+        self
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width;
+        self.rings = None;
+        self
+    }
+
+    /// Draw the rings dashed, `dash` and `gap` in pixels.
+    pub fn set_dashed(&mut self, dash: f32, gap: f32) -> &mut Self {
+        self.dash = Some((dash, gap));
+        self.rings = None;
+        self
+    }
+
+    /// Label each ring with its distance.
+    pub fn set_labels(&mut self, font_path: impl Into<String>, font_size: u32) -> &mut Self {
+        self.label_font = Some((font_path.into(), font_size));
+        self.labels = None;
+        self
+    }
+
+    pub fn clear_labels(&mut self) -> &mut Self {
+        self.label_font = None;
+        self.labels = None;
+        self
+    }
+
+    pub fn set_label_color(&mut self, color: Color) -> &mut Self {
+        self.label_color = color;
+        self.labels = None;
+        self
+    }
+
+    /// Direction from the center, in degrees clockwise from north, where
+    /// labels sit on their rings (default 0, north).
+    pub fn set_label_bearing(&mut self, bearing: f64) -> &mut Self {
+        self.label_bearing = bearing;
+        self
+    }
+
+    pub fn set_unit(&mut self, unit: DistanceUnit) -> &mut Self {
+        self.unit = unit;
+        self.labels = None;
+        self
+    }
+
+    pub fn render(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        let center_world = lon_lat_to_world(self.center.0, self.center.1);
+        let center = camera.world_to_screen(center_world);
+        let scale = camera.scale();
+        let to_local = |(lon, lat): (f64, f64)| {
+            let p = lon_lat_to_world(lon, lat);
+            ((p.x - center_world.x) * scale, (p.y - center_world.y) * scale)
+        };
+
+        if self.rings.as_ref().is_none_or(|(built, _)| *built != scale) {
+            let style = match self.dash {
+                Some((dash, gap)) => ShapeStyle::dashed_stroke(self.color, self.line_width, dash, gap),
+                None => ShapeStyle::stroke(self.color, self.line_width),
+            };
+            let rings = self
+                .radii
+                .iter()
+                .map(|&radius| {
+                    let points = geodesic_circle(self.center, radius, self.segments).into_iter().map(to_local).collect();
+                    ShapeRenderable::builder(ShapeKind::Polygon(Polygon::new(points)), style.clone())
+                        .anchor(Anchor::Custom(0.0, 0.0))
+                        .build()
+                })
+                .collect();
+            self.rings = Some((scale, rings));
+        }
+        for ring in &mut self.rings.as_mut().unwrap().1 {
+            ring.set_position(center.x, center.y).render(renderer);
+        }
+
+        let Some((font_path, font_size)) = &self.label_font else { return };
+        let labels = self.labels.get_or_insert_with(|| {
+            self.radii
+                .iter()
+                .map(|&radius| {
+                    let text = Text::new(format_distance(radius, self.unit), font_path.as_str(), *font_size);
+                    ShapeRenderable::builder(ShapeKind::Text(text), ShapeStyle::fill(self.label_color))
+                        .anchor(Anchor::South)
+                        .build()
+                })
+                .collect()
+        });
+        for (label, &radius) in labels.iter_mut().zip(&self.radii) {
+            let (x, y) = to_local(destination_point(self.center, self.label_bearing, radius));
+            label.set_position(center.x + x, center.y + y - LABEL_MARGIN).render(renderer);
+        }
+    }
+}
+
+/// `meters` in `unit`, with up to one decimal: `500 m`, `2.5 km`, `10 NM`.
+fn format_distance(meters: f64, unit: DistanceUnit) -> String {
+    let (value, suffix) = match unit {
+        DistanceUnit::Metric if meters < 1000.0 => (meters, "m"),
+        DistanceUnit::Metric => (meters / 1000.0, "km"),
+        DistanceUnit::NauticalMiles => (meters / NAUTICAL_MILE, "NM"),
+        DistanceUnit::StatuteMiles => (meters / STATUTE_MILE, "mi"),
+    };
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 { format!("{rounded:.0} {suffix}") } else { format!("{rounded:.1} {suffix}") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_are_formatted_in_unit() {
+        assert_eq!(format_distance(500.0, DistanceUnit::Metric), "500 m");
+        assert_eq!(format_distance(2500.0, DistanceUnit::Metric), "2.5 km");
+        assert_eq!(format_distance(50_000.0, DistanceUnit::Metric), "50 km");
+        assert_eq!(format_distance(18_520.0, DistanceUnit::NauticalMiles), "10 NM");
+        assert_eq!(format_distance(4023.36, DistanceUnit::StatuteMiles), "2.5 mi");
+    }
+}
@@ -0,0 +1,24 @@
+#version 330 core
+
+in vec2 v_uv;
+out vec4 FragColor;
+
+// Top and bottom (vertical) or center and edge (radial) colors
+uniform vec4 u_color_a;
+uniform vec4 u_color_b;
+// 1.0 for a radial gradient
+uniform float u_radial;
+// Window width / height, so radial gradients stay circular
+uniform float u_aspect;
+
+void main() {
+    float t;
+    if (u_radial > 0.5) {
+        vec2 d = (v_uv - 0.5) * vec2(u_aspect, 1.0);
+        // 0 at the center, 1 at the corners
+        t = clamp(length(d) / length(vec2(u_aspect, 1.0) * 0.5), 0.0, 1.0);
+    } else {
+        t = 1.0 - v_uv.y;
+    }
+    FragColor = mix(u_color_a, u_color_b, t);
+}
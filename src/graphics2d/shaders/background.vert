@@ -0,0 +1,12 @@
+#version 330 core
+
+// Full-window quad in clip space, -1..1
+layout(location = 0) in vec2 aPos;
+
+// 0..1 across the window, Y up
+out vec2 v_uv;
+
+void main() {
+    gl_Position = vec4(aPos, 0.0, 1.0);
+    v_uv = aPos * 0.5 + 0.5;
+}
@@ -1,14 +1,16 @@
 #version 330 core
 uniform vec4 geometryColor;
+uniform float u_opacity;
 uniform float u_dash_length;
 uniform float u_gap_length;
+uniform float u_dash_offset;
 in vec4 vInstanceColor;
 in float vLineDist;
 out vec4 FragColor;
 void main()
 {
     float cycle = u_dash_length + u_gap_length;
-    float t = mod(vLineDist, cycle);
+    float t = mod(vLineDist - u_dash_offset, cycle);
     if (t > u_dash_length)
         discard;
 
@@ -17,4 +19,5 @@ void main()
         FragColor = vInstanceColor;
     else
         FragColor = geometryColor;
+    FragColor.a *= u_opacity;
 }
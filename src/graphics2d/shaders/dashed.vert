@@ -4,26 +4,37 @@ uniform mat4 u_Transform;                     // projection matrix
 uniform vec2 u_screen_offset;                 // single-shape translation (uniform). Forced to 0 when instancing
 uniform float u_scale;                        // per-shape scale factor (default 1.0)
 uniform float u_rotation;                     // per-shape rotation in radians (default 0.0)
+uniform float u_picking;                      // 1.0 during the picking pass, else 0.0
+uniform float u_pick_base;                    // pick id of instance 0 (picking pass only)
 
 layout (location = 0) in vec2 aPos;           // mesh-local vertex
 layout (location = 1) in vec2 aInstanceXY;    // optional; if disabled => (0,0)
 layout (location = 2) in vec4 aInstanceColor; // optional; if disabled => (0,0,0,0)
+layout (location = 4) in vec3 aInstanceTransform; // optional (scale x, scale y, rotation); if disabled => (1,1,0)
 layout (location = 3) in float aLineDist;     // cumulative distance along line centerline
 
 out vec4 vInstanceColor;
 out float vLineDist;
 
 void main() {
-    // Rotate around origin (local coordinates)
-    float cos_r = cos(u_rotation);
-    float sin_r = sin(u_rotation);
+    // Per-instance scale, then rotate around origin (local coordinates)
+    vec2 local = aPos * aInstanceTransform.xy;
+    float angle = u_rotation + aInstanceTransform.z;
+    float cos_r = cos(angle);
+    float sin_r = sin(angle);
     vec2 rotated = vec2(
-        aPos.x * cos_r - aPos.y * sin_r,
-        aPos.x * sin_r + aPos.y * cos_r
+        local.x * cos_r - local.y * sin_r,
+        local.x * sin_r + local.y * cos_r
     );
     // Scale, then translate
     vec2 p = rotated * u_scale + u_screen_offset + aInstanceXY;
     gl_Position = u_Transform * vec4(p, 0.0, 1.0);
-    vInstanceColor = aInstanceColor;
+    if (u_picking > 0.5) {
+        // Encode (u_pick_base + instance) as a 24-bit RGB id; alpha 1 selects it in the fragment shader
+        float id = u_pick_base + float(gl_InstanceID);
+        vInstanceColor = vec4(mod(id, 256.0), mod(floor(id / 256.0), 256.0), floor(id / 65536.0), 255.0) / 255.0;
+    } else {
+        vInstanceColor = aInstanceColor;
+    }
     vLineDist = aLineDist * u_scale;
 }
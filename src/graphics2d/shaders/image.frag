@@ -5,7 +5,18 @@ out vec4 FragColor;
 
 // texture samples
 uniform sampler2D texture1;
+uniform float u_opacity;
+uniform float u_picking;
+uniform vec4 u_pick_color;
 
 void main() {
     FragColor = texture(texture1, TexCoord);
+    if (u_picking > 0.5) {
+        // Picking pass: solid id color over the opaque part of the image
+        if (FragColor.a < 0.5)
+            discard;
+        FragColor = u_pick_color;
+        return;
+    }
+    FragColor.a *= u_opacity;
 }
\ No newline at end of file
@@ -2,6 +2,7 @@
 out vec4 FragColor;
 
 uniform vec4 geometryColor;
+uniform float u_opacity;
 in vec4 vInstanceColor;
 
 void main() {
@@ -19,4 +20,5 @@ void main() {
         FragColor = vInstanceColor;
     else
         FragColor = geometryColor;
+    FragColor.a *= u_opacity;
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 #version 330 core
 uniform vec4 geometryColor;
+uniform float u_opacity;
 in vec4 vInstanceColor;
 out vec4 FragColor;
 void main()
@@ -9,4 +10,5 @@ void main()
         FragColor = vInstanceColor;
     else
         FragColor = geometryColor;
+    FragColor.a *= u_opacity;
 }
\ No newline at end of file
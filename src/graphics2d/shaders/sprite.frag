@@ -0,0 +1,22 @@
+#version 330 core
+
+in vec2 TexCoord;
+in vec4 vTint;
+out vec4 FragColor;
+
+uniform sampler2D texture1;
+uniform float u_opacity;
+uniform float u_picking;
+
+void main() {
+    vec4 color = texture(texture1, TexCoord);
+    if (u_picking > 0.5) {
+        // Picking pass: the sprite's id over its opaque part
+        if (color.a < 0.5)
+            discard;
+        FragColor = vTint;
+        return;
+    }
+    FragColor = color * vTint;
+    FragColor.a *= u_opacity;
+}
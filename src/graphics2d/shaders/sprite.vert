@@ -0,0 +1,40 @@
+#version 330 core
+
+uniform mat4 u_Transform;                     // projection matrix
+uniform vec2 u_screen_offset;                 // pivot shift; instances are placed by aInstanceXY
+uniform float u_scale;                        // per-batch scale factor (default 1.0)
+uniform float u_rotation;                     // per-batch rotation in radians (default 0.0)
+uniform float u_picking;                      // 1.0 during the picking pass, else 0.0
+uniform float u_pick_base;                    // pick id of instance 0 (picking pass only)
+
+layout (location = 0) in vec2 aPos;           // unit quad corner, -0.5 to 0.5
+layout (location = 1) in vec2 aInstanceXY;    // sprite center
+layout (location = 2) in vec4 aInstanceColor; // tint
+layout (location = 3) in vec4 aInstanceUV;    // atlas region (u, v, width, height)
+layout (location = 4) in vec3 aInstanceTransform; // size x, size y (pixels), rotation
+
+out vec2 TexCoord;
+out vec4 vTint;
+
+void main() {
+    // Size the quad, then rotate around its center
+    vec2 local = aPos * aInstanceTransform.xy;
+    float angle = u_rotation + aInstanceTransform.z;
+    float cos_r = cos(angle);
+    float sin_r = sin(angle);
+    vec2 rotated = vec2(
+        local.x * cos_r - local.y * sin_r,
+        local.x * sin_r + local.y * cos_r
+    );
+    vec2 p = rotated * u_scale + u_screen_offset + aInstanceXY;
+    gl_Position = u_Transform * vec4(p, 0.0, 1.0);
+    // The quad's top-left corner (-0.5, -0.5) maps to the region's top-left
+    TexCoord = aInstanceUV.xy + (aPos + 0.5) * aInstanceUV.zw;
+    if (u_picking > 0.5) {
+        // Encode (u_pick_base + instance) as a 24-bit RGB id
+        float id = u_pick_base + float(gl_InstanceID);
+        vTint = vec4(mod(id, 256.0), mod(floor(id / 256.0), 256.0), floor(id / 65536.0), 255.0) / 255.0;
+    } else {
+        vTint = aInstanceColor;
+    }
+}
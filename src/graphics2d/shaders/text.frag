@@ -5,9 +5,19 @@ out vec4 FragColor;
 
 uniform sampler2D u_fontAtlas;
 uniform vec4 u_color;
+uniform float u_opacity;
+uniform float u_picking;
+uniform vec4 u_pick_color;
 
 void main() {
     // Sample the red channel from the font atlas (grayscale glyph)
     float alpha = texture(u_fontAtlas, TexCoord).r;
-    FragColor = vec4(u_color.rgb, u_color.a * alpha);
+    if (u_picking > 0.5) {
+        // Picking pass: solid id color over the glyph coverage
+        if (alpha < 0.5)
+            discard;
+        FragColor = u_pick_color;
+        return;
+    }
+    FragColor = vec4(u_color.rgb, u_color.a * alpha * u_opacity);
 }
@@ -0,0 +1,134 @@
+//! Adaptive flattening of Bézier curves into polylines.
+
+use crate::graphics2d::shapes::Bezier;
+
+/// Maximum distance, in local units, between a flattened curve and the
+/// true curve when building a [`ShapeRenderable`](super::ShapeRenderable).
+pub(crate) const FLATTEN_TOLERANCE: f32 = 0.25;
+
+/// Subdivision depth limit: at most 2^12 segments per curve.
+const MAX_DEPTH: u32 = 12;
+
+type Cubic = [(f32, f32); 4];
+
+impl Bezier {
+    /// The point at parameter `t` in 0..=1.
+    pub fn point_at(&self, t: f32) -> (f32, f32) {
+        let [p0, p1, p2, p3] = self.to_cubic();
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        (
+            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+        )
+    }
+
+    /// Points along the curve, from start to end, such that no point of
+    /// the curve is further than `tolerance` from the resulting polyline.
+    /// Straight stretches get few points and tight bends many, by
+    /// splitting the curve in half until each piece is flat enough.
+    pub fn flatten(&self, tolerance: f32) -> Vec<(f32, f32)> {
+        let cubic = self.to_cubic();
+        let mut points = vec![cubic[0]];
+        subdivide(cubic, tolerance.max(1e-4), MAX_DEPTH, &mut points);
+        points
+    }
+
+    /// The same curve as a cubic; quadratics are degree-elevated exactly.
+    fn to_cubic(self) -> Cubic {
+        match self {
+            Self::Quadratic { start, control, end } => {
+                let toward = |p: (f32, f32)| (p.0 + (control.0 - p.0) * 2.0 / 3.0, p.1 + (control.1 - p.1) * 2.0 / 3.0);
+                [start, toward(start), toward(end), end]
+            }
+            Self::Cubic { start, control1, control2, end } => [start, control1, control2, end],
+        }
+    }
+}
+
+/// Append the end points of `cubic`'s flat pieces to `out`.
+fn subdivide(cubic: Cubic, tolerance: f32, depth: u32, out: &mut Vec<(f32, f32)>) {
+    if depth == 0 || is_flat(&cubic, tolerance) {
+        out.push(cubic[3]);
+        return;
+    }
+    let (left, right) = split(&cubic);
+    subdivide(left, tolerance, depth - 1, out);
+    subdivide(right, tolerance, depth - 1, out);
+}
+
+/// Whether both control points lie within `tolerance` of the chord; the
+/// curve stays inside their convex hull, so it does too.
+fn is_flat(&[p0, p1, p2, p3]: &Cubic, tolerance: f32) -> bool {
+    let (dx, dy) = (p3.0 - p0.0, p3.1 - p0.1);
+    let length = dx.hypot(dy);
+    let distance = |p: (f32, f32)| {
+        if length < f32::EPSILON {
+            (p.0 - p0.0).hypot(p.1 - p0.1)
+        } else {
+            ((p.0 - p0.0) * dy - (p.1 - p0.1) * dx).abs() / length
+        }
+    };
+    distance(p1) <= tolerance && distance(p2) <= tolerance
+}
+
+/// de Casteljau split at t = 0.5.
+fn split(&[p0, p1, p2, p3]: &Cubic) -> (Cubic, Cubic) {
+    let mid = |a: (f32, f32), b: (f32, f32)| ((a.0 + b.0) * 0.5, (a.1 + b.1) * 0.5);
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let center = mid(p012, p123);
+    ([p0, p01, p012, center], [center, p123, p23, p3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_deviation(curve: &Bezier, points: &[(f32, f32)]) -> f32 {
+        (0..=200)
+            .map(|i| {
+                let p = curve.point_at(i as f32 / 200.0);
+                points
+                    .windows(2)
+                    .map(|w| crate::graphics2d::shapes::hit::segment_distance(p, w[0], w[1]))
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn flattened_curve_stays_within_tolerance() {
+        let curve = Bezier::cubic((0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0));
+        for tolerance in [2.0, 0.25] {
+            let points = curve.flatten(tolerance);
+            assert_eq!(points.first(), Some(&(0.0, 0.0)));
+            assert_eq!(points.last(), Some(&(100.0, 0.0)));
+            assert!(max_deviation(&curve, &points) <= tolerance);
+        }
+        // Finer tolerance, more points
+        assert!(curve.flatten(0.25).len() > curve.flatten(2.0).len());
+    }
+
+    #[test]
+    fn straight_curve_is_a_single_segment() {
+        let curve = Bezier::quadratic((0.0, 0.0), (5.0, 5.0), (10.0, 10.0));
+        assert_eq!(curve.flatten(0.25), vec![(0.0, 0.0), (10.0, 10.0)]);
+    }
+
+    #[test]
+    fn quadratic_matches_its_definition() {
+        let curve = Bezier::quadratic((0.0, 0.0), (10.0, 20.0), (20.0, 0.0));
+        // B(0.5) = 0.25 p0 + 0.5 c + 0.25 p2
+        let (x, y) = curve.point_at(0.5);
+        assert!((x - 10.0).abs() < 1e-5 && (y - 10.0).abs() < 1e-5);
+        assert!(max_deviation(&curve, &curve.flatten(0.1)) <= 0.1);
+    }
+
+    #[test]
+    fn from_points_picks_the_degree() {
+        assert!(matches!(Bezier::from_points(&[(0.0, 0.0); 3]), Some(Bezier::Quadratic { .. })));
+        assert!(matches!(Bezier::from_points(&[(0.0, 0.0); 4]), Some(Bezier::Cubic { .. })));
+        assert_eq!(Bezier::from_points(&[(0.0, 0.0); 2]), None);
+    }
+}
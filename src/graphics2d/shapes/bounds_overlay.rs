@@ -0,0 +1,102 @@
+use crate::core::{Color, InstanceTransform, Renderer, Renderable, Vec2};
+use crate::graphics2d::shapes::{Anchor, Line, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Outlines of shapes' computed [`bounds`](ShapeRenderable::bounds) and
+/// [`instance_bounds`](ShapeRenderable::instance_bounds), for spotting
+/// culling, hit-testing and anchor bugs.
+///
+/// All boxes of one color are drawn with two instanced lines: a unit edge
+/// per box side, stretched along its length so the line width stays 1px.
+///
+/// ```ignore
+/// let mut overlay = BoundsOverlay::new();
+/// // in on_render
+/// overlay.render(renderer, shapes.iter());
+/// ```
+pub struct BoundsOverlay {
+    shape_boxes: BoxBatch,
+    instance_boxes: BoxBatch,
+}
+
+impl Default for BoundsOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BoundsOverlay {
+    pub fn new() -> Self {
+        Self {
+            shape_boxes: BoxBatch::new(Color::from_rgba(1.0, 0.0, 1.0, 0.8)),
+            instance_boxes: BoxBatch::new(Color::from_rgba(0.0, 0.9, 1.0, 0.6)),
+        }
+    }
+
+    /// Colors of shape bounds and of instance bounds.
+    pub fn set_colors(&mut self, shapes: Color, instances: Color) -> &mut Self {
+        self.shape_boxes = BoxBatch::new(shapes);
+        self.instance_boxes = BoxBatch::new(instances);
+        self
+    }
+
+    /// Outline the bounds of each visible shape, and of each of its
+    /// instances.
+    pub fn render<'s>(&mut self, renderer: &Renderer, shapes: impl IntoIterator<Item = &'s ShapeRenderable>) {
+        let mut shape_boxes = Vec::new();
+        let mut instance_boxes = Vec::new();
+        for shape in shapes.into_iter().filter(|shape| shape.is_visible()) {
+            shape_boxes.push(shape.bounds());
+            instance_boxes.extend(shape.instance_bounds());
+        }
+        self.instance_boxes.render(renderer, &instance_boxes);
+        self.shape_boxes.render(renderer, &shape_boxes);
+    }
+
+    /// Outline arbitrary screen-space boxes `(min_x, min_y, max_x, max_y)` in
+    /// the shape bounds color.
+    pub fn render_boxes(&mut self, renderer: &Renderer, boxes: &[(f32, f32, f32, f32)]) {
+        self.shape_boxes.render(renderer, boxes);
+    }
+}
+
+/// Horizontal and vertical unit edges instanced once per box side.
+struct BoxBatch {
+    color: Color,
+    /// Lines and their instance capacity.
+    edges: Option<(usize, ShapeRenderable, ShapeRenderable)>,
+}
+
+impl BoxBatch {
+    fn new(color: Color) -> Self {
+        Self { color, edges: None }
+    }
+
+    fn render(&mut self, renderer: &Renderer, boxes: &[(f32, f32, f32, f32)]) {
+        // An empty instance list would fall back to a single draw at (0, 0).
+        if boxes.is_empty() {
+            return;
+        }
+        let count = boxes.len() * 2;
+        if self.edges.as_ref().is_none_or(|(capacity, _, _)| *capacity < count) {
+            let capacity = count.next_power_of_two();
+            let edge = |end| {
+                let mut line = ShapeRenderable::builder(ShapeKind::Line(Line::new((0.0, 0.0), end)), ShapeStyle::stroke(self.color, 1.0))
+                    .anchor(Anchor::Custom(0.0, 0.0))
+                    .build();
+                line.create_multiple_instances(capacity);
+                line
+            };
+            self.edges = Some((capacity, edge((1.0, 0.0)), edge((0.0, 1.0))));
+        }
+        let (_, horizontal, vertical) = self.edges.as_mut().unwrap();
+
+        let stretch = |x: f32, y: f32| InstanceTransform { scale_x: x, scale_y: y, rotation: 0.0 };
+        let top_bottom: Vec<Vec2> = boxes.iter().flat_map(|&(x0, y0, _, y1)| [Vec2::new(x0, y0), Vec2::new(x0, y1)]).collect();
+        let widths: Vec<InstanceTransform> = boxes.iter().flat_map(|&(x0, _, x1, _)| [stretch(x1 - x0, 1.0); 2]).collect();
+        horizontal.set_instance_positions(&top_bottom).set_instance_transforms(&widths).render(renderer);
+
+        let left_right: Vec<Vec2> = boxes.iter().flat_map(|&(x0, y0, x1, _)| [Vec2::new(x0, y0), Vec2::new(x1, y0)]).collect();
+        let heights: Vec<InstanceTransform> = boxes.iter().flat_map(|&(_, y0, _, y1)| [stretch(1.0, y1 - y0); 2]).collect();
+        vertical.set_instance_positions(&left_right).set_instance_transforms(&heights).render(renderer);
+    }
+}
@@ -0,0 +1,502 @@
+use crate::core::{Color, Renderable, Renderer, Snapper, Vec2};
+use crate::graphics2d::shapes::{Anchor, Line, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Smallest width, height or radius a handle drag can produce.
+const MIN_EXTENT: f32 = 1.0;
+/// Extra pixels around a handle that still grab it.
+const HANDLE_SLOP: f32 = 2.0;
+
+/// Which shape parameter a grab handle controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    /// Corner of a rectangle, rounded rectangle or image, clockwise from
+    /// the top-left: 0 = top-left, 1 = top-right, 2 = bottom-right,
+    /// 3 = bottom-left. The opposite corner stays in place.
+    Corner(usize),
+    /// Corner radius of a rounded rectangle.
+    CornerRadius,
+    /// Radius of a circle or arc.
+    Radius,
+    /// Horizontal radius of an ellipse.
+    RadiusX,
+    /// Vertical radius of an ellipse.
+    RadiusY,
+    /// Vertex `i` of a line, polyline, triangle, polygon or multipoint.
+    Vertex(usize),
+}
+
+/// A grab handle at a screen position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Handle {
+    pub kind: HandleKind,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Change reported while editing a shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditEvent {
+    /// The shape became the editing selection.
+    Selected,
+    /// The shape stopped being the editing selection.
+    Deselected,
+    /// The shape was dragged by its body; its position changed.
+    Moved,
+    /// A handle was dragged; the shape parameters (and possibly position,
+    /// to keep the opposite side fixed) changed.
+    Reshaped(HandleKind),
+    /// A drag that changed the shape ended.
+    Finished,
+}
+
+enum DragTarget {
+    /// Body drag: offset from the cursor to the shape position.
+    Body { offset: (f32, f32) },
+    /// Handle drag, with the local cursor and corner radius at grab time
+    /// for handles that apply deltas.
+    Handle { kind: HandleKind, start: (f32, f32), start_radius: f32 },
+}
+
+struct Drag {
+    target: DragTarget,
+    changed: bool,
+}
+
+/// Grab handles and drag editing for a single [`ShapeRenderable`].
+///
+/// The editor holds only the drag state and the handle visuals; the shape
+/// is passed in on each call, so it works with shapes owned by `App`, a
+/// `Group` or the application. `App::enable_editing` wires it to the mouse
+/// and to shapes marked [`set_editable`](ShapeRenderable::set_editable).
+///
+/// ```ignore
+/// // mouse press
+/// editor.begin_drag(&shape, x, y);
+/// // cursor move
+/// if let Some(event) = editor.drag_to(&mut shape, x, y) { save(&shape); }
+/// // mouse release
+/// editor.end_drag();
+/// // each frame, after drawing the shape
+/// editor.render_handles(&shape, renderer);
+/// ```
+///
+/// Handles are tested against the renderable's own transform; instanced
+/// shapes are edited at their base position only.
+pub struct ShapeEditor {
+    handle_size: f32,
+    drag: Option<Drag>,
+    handle_shape: Option<(f32, ShapeRenderable)>,
+    snapper: Option<Snapper>,
+    /// Edge guides `(x, y)` from the last snapped drag step.
+    guides: (Option<f32>, Option<f32>),
+    guide_shapes: Option<(ShapeRenderable, ShapeRenderable)>,
+}
+
+impl Default for ShapeEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShapeEditor {
+    pub fn new() -> Self {
+        Self {
+            handle_size: 8.0,
+            drag: None,
+            handle_shape: None,
+            snapper: None,
+            guides: (None, None),
+            guide_shapes: None,
+        }
+    }
+
+    /// Snap drags with `snapper`: body drags snap the shape's bounds, and
+    /// handle drags snap the cursor. Edge snaps are drawn as guide lines
+    /// by [`render_handles`](Self::render_handles). `None` disables it.
+    pub fn set_snapper(&mut self, snapper: Option<Snapper>) -> &mut Self {
+        self.snapper = snapper;
+        self
+    }
+
+    pub fn snapper(&self) -> Option<&Snapper> {
+        self.snapper.as_ref()
+    }
+
+    /// Mutable access, e.g. to refresh the edges of other shapes before a
+    /// drag (`App` does this when editing starts a drag).
+    pub fn snapper_mut(&mut self) -> Option<&mut Snapper> {
+        self.snapper.as_mut()
+    }
+
+    /// Side length of the square handles in pixels (default 8).
+    pub fn set_handle_size(&mut self, size: f32) -> &mut Self {
+        self.handle_size = size.max(1.0);
+        self
+    }
+
+    pub fn handle_size(&self) -> f32 {
+        self.handle_size
+    }
+
+    /// The grab handles for `shape` in screen coordinates. Points and text
+    /// have none and can only be moved.
+    pub fn handles(&self, shape: &ShapeRenderable) -> Vec<Handle> {
+        // Keep the corner-radius handle clear of the top-left corner handle.
+        let inset = self.handle_size * 1.5 / shape.scale().abs().max(f32::EPSILON);
+        local_handles(shape.shape(), inset)
+            .into_iter()
+            .map(|(kind, (lx, ly))| {
+                let (x, y) = shape.to_screen(lx, ly);
+                Handle { kind, x, y }
+            })
+            .collect()
+    }
+
+    /// The handle of `shape` under the screen point `(x, y)`, if any.
+    /// Later handles win where they overlap.
+    pub fn handle_at(&self, shape: &ShapeRenderable, x: f32, y: f32) -> Option<HandleKind> {
+        let reach = self.handle_size * 0.5 + HANDLE_SLOP;
+        self.handles(shape)
+            .into_iter()
+            .rev()
+            .find(|h| (h.x - x).abs() <= reach && (h.y - y).abs() <= reach)
+            .map(|h| h.kind)
+    }
+
+    /// Start dragging a handle of `shape` under `(x, y)`, or the shape body
+    /// if the point is [inside](ShapeRenderable::contains) it. Returns
+    /// `false` (and starts nothing) if neither is hit.
+    pub fn begin_drag(&mut self, shape: &ShapeRenderable, x: f32, y: f32) -> bool {
+        let target = if let Some(kind) = self.handle_at(shape, x, y) {
+            let start = shape.to_local(x, y).unwrap_or((0.0, 0.0));
+            let start_radius = match shape.shape() {
+                ShapeKind::RoundedRectangle(r) => r.radius,
+                _ => 0.0,
+            };
+            DragTarget::Handle { kind, start, start_radius }
+        } else if shape.contains(x, y) {
+            let (sx, sy) = shape.position();
+            DragTarget::Body { offset: (sx - x, sy - y) }
+        } else {
+            return false;
+        };
+        self.drag = Some(Drag { target, changed: false });
+        true
+    }
+
+    /// Continue the current drag with the cursor at `(x, y)`, updating the
+    /// shape. Returns the change, or `None` when not dragging or nothing
+    /// changed.
+    pub fn drag_to(&mut self, shape: &mut ShapeRenderable, x: f32, y: f32) -> Option<EditEvent> {
+        let drag = self.drag.as_mut()?;
+        self.guides = (None, None);
+        let event = match drag.target {
+            DragTarget::Body { offset } => {
+                let mut to = (x + offset.0, y + offset.1);
+                if let Some(snapper) = &self.snapper {
+                    let (px, py) = shape.position();
+                    let (x0, y0, x1, y1) = shape.bounds();
+                    let (dx, dy) = (to.0 - px, to.1 - py);
+                    let snap = snapper.snap_bounds((x0 + dx, y0 + dy, x1 + dx, y1 + dy));
+                    to = (to.0 + snap.x - (x0 + dx), to.1 + snap.y - (y0 + dy));
+                    self.guides = (snap.guide_x, snap.guide_y);
+                }
+                if to == shape.position() {
+                    return None;
+                }
+                shape.set_position(to.0, to.1);
+                EditEvent::Moved
+            }
+            DragTarget::Handle { kind, start, start_radius } => {
+                let (x, y) = match &self.snapper {
+                    Some(snapper) => {
+                        let snap = snapper.snap_point(x, y);
+                        self.guides = (snap.guide_x, snap.guide_y);
+                        (snap.x, snap.y)
+                    }
+                    None => (x, y),
+                };
+                let p = shape.to_local(x, y)?;
+                if !reshape(shape, kind, p, (x, y), start, start_radius) {
+                    return None;
+                }
+                EditEvent::Reshaped(kind)
+            }
+        };
+        drag.changed = true;
+        Some(event)
+    }
+
+    /// End the current drag. Returns [`EditEvent::Finished`] if the drag
+    /// changed the shape.
+    pub fn end_drag(&mut self) -> Option<EditEvent> {
+        self.guides = (None, None);
+        let drag = self.drag.take()?;
+        drag.changed.then_some(EditEvent::Finished)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Draw the handles of `shape`, and the snap guides while a drag is
+    /// snapped to an edge. Call after the shape itself is drawn.
+    pub fn render_handles(&mut self, shape: &ShapeRenderable, renderer: &Renderer) {
+        self.render_guides(renderer);
+        let positions: Vec<Vec2> = self.handles(shape).iter().map(|h| Vec2::new(h.x, h.y)).collect();
+        if positions.is_empty() {
+            return;
+        }
+        let size = self.handle_size;
+        if self.handle_shape.as_ref().is_none_or(|(built, _)| *built != size) {
+            let mut marker = ShapeRenderable::builder(
+                ShapeKind::Rectangle(Rectangle::new(size, size)),
+                ShapeStyle::fill_and_stroke(Color::WHITE, Color::DODGER_BLUE, 1.0),
+            )
+            .anchor(Anchor::Center)
+            .build();
+            marker.create_multiple_instances(positions.len());
+            self.handle_shape = Some((size, marker));
+        }
+        let (_, marker) = self.handle_shape.as_mut().unwrap();
+        marker.set_instance_positions(&positions);
+        marker.render(renderer);
+    }
+
+    fn render_guides(&mut self, renderer: &Renderer) {
+        if self.guides == (None, None) {
+            return;
+        }
+        let (w, h) = renderer.window_handle.size();
+        let (w, h) = (w as f32, h as f32);
+        let (vertical, horizontal) = self.guide_shapes.get_or_insert_with(|| {
+            let style = ShapeStyle::stroke(Color::DEEP_PINK, 1.0);
+            (
+                ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (0.0, h))), style.clone()),
+                ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (w, 0.0))), style),
+            )
+        });
+        if let Some(x) = self.guides.0 {
+            if let ShapeKind::Line(line) = vertical.shape() {
+                if line.end.1 != h {
+                    vertical.set_points(&[(0.0, 0.0), (0.0, h)]);
+                }
+            }
+            vertical.set_position(x, 0.0).render(renderer);
+        }
+        if let Some(y) = self.guides.1 {
+            if let ShapeKind::Line(line) = horizontal.shape() {
+                if line.end.0 != w {
+                    horizontal.set_points(&[(0.0, 0.0), (w, 0.0)]);
+                }
+            }
+            horizontal.set_position(0.0, y).render(renderer);
+        }
+    }
+}
+
+/// Handles in shape coordinates.
+fn local_handles(shape: &ShapeKind, inset: f32) -> Vec<(HandleKind, (f32, f32))> {
+    let corners = |min: (f32, f32), max: (f32, f32)| {
+        (0..4).map(move |i| (HandleKind::Corner(i), rect_corner(min, max, i)))
+    };
+    let vertices = |points: &[(f32, f32)]| {
+        points.iter().enumerate().map(|(i, &p)| (HandleKind::Vertex(i), p)).collect()
+    };
+    match shape {
+        ShapeKind::Point | ShapeKind::Text(_) => Vec::new(),
+        ShapeKind::Rectangle(r) => corners((0.0, 0.0), (r.width, r.height)).collect(),
+        ShapeKind::RoundedRectangle(r) => {
+            let mut handles: Vec<_> = corners((0.0, 0.0), (r.width, r.height)).collect();
+            handles.push((HandleKind::CornerRadius, (r.radius.max(inset), inset)));
+            handles
+        }
+        ShapeKind::Image(img) => {
+            let (hw, hh) = (img.width * 0.5, img.height * 0.5);
+            corners((-hw, -hh), (hw, hh)).collect()
+        }
+        ShapeKind::Circle(c) => vec![(HandleKind::Radius, (c.radius, 0.0))],
+        ShapeKind::Arc(a) => {
+            let mut sweep = a.end_angle - a.start_angle;
+            if sweep < 0.0 {
+                sweep += std::f32::consts::TAU;
+            }
+            let mid = a.start_angle + sweep * 0.5;
+            vec![(HandleKind::Radius, (a.radius * mid.cos(), -a.radius * mid.sin()))]
+        }
+        ShapeKind::Ellipse(e) => vec![
+            (HandleKind::RadiusX, (e.radius_x, 0.0)),
+            (HandleKind::RadiusY, (0.0, e.radius_y)),
+        ],
+        ShapeKind::Line(l) => vertices(&[l.start, l.end]),
+        ShapeKind::Triangle(t) => vertices(&t.vertices),
+        ShapeKind::Polyline(p) => vertices(&p.points),
+        ShapeKind::Polygon(p) => vertices(&p.points),
+        ShapeKind::MultiPoint(p) => vertices(&p.points),
+        ShapeKind::Bezier(b) => vertices(&b.points()),
+    }
+}
+
+/// Corner `i` of the box `min..max`, clockwise from the top-left.
+fn rect_corner(min: (f32, f32), max: (f32, f32), i: usize) -> (f32, f32) {
+    match i % 4 {
+        0 => min,
+        1 => (max.0, min.1),
+        2 => max,
+        _ => (min.0, max.1),
+    }
+}
+
+/// New `(width, height)` when corner `i` of `min..max` is dragged to `p`
+/// with the opposite corner fixed. Dragging past the opposite corner
+/// clamps to `MIN_EXTENT` rather than flipping the box.
+fn resize_from_corner(min: (f32, f32), max: (f32, f32), i: usize, p: (f32, f32)) -> (f32, f32) {
+    let fixed = rect_corner(min, max, i + 2);
+    let sx = if matches!(i % 4, 1 | 2) { 1.0 } else { -1.0 };
+    let sy = if i % 4 >= 2 { 1.0 } else { -1.0 };
+    (
+        (sx * (p.0 - fixed.0)).max(MIN_EXTENT),
+        (sy * (p.1 - fixed.1)).max(MIN_EXTENT),
+    )
+}
+
+fn points_of(shape: &ShapeKind) -> Option<Vec<(f32, f32)>> {
+    match shape {
+        ShapeKind::Line(l) => Some(vec![l.start, l.end]),
+        ShapeKind::Triangle(t) => Some(t.vertices.to_vec()),
+        ShapeKind::Polyline(p) => Some(p.points.clone()),
+        ShapeKind::Polygon(p) => Some(p.points.clone()),
+        ShapeKind::MultiPoint(p) => Some(p.points.clone()),
+        ShapeKind::Bezier(b) => Some(b.points()),
+        _ => None,
+    }
+}
+
+/// Apply a handle drag to local point `p` (screen point `cursor`). The
+/// shape is then shifted so a reference point (the opposite corner, the
+/// centre, or an undragged vertex) stays where it was on screen, since a
+/// rebuild can move the anchor. Returns `false` if nothing changed.
+fn reshape(
+    shape: &mut ShapeRenderable,
+    kind: HandleKind,
+    p: (f32, f32),
+    cursor: (f32, f32),
+    start: (f32, f32),
+    start_radius: f32,
+) -> bool {
+    let centre = (0.0, 0.0);
+    // (reference point before the change in local coordinates, its local
+    // coordinates after the change); the screen target is computed first.
+    let (target, fixed_new) = match (shape.shape().clone(), kind) {
+        (ShapeKind::Rectangle(r), HandleKind::Corner(i)) => {
+            let (w, h) = resize_from_corner(centre, (r.width, r.height), i, p);
+            let target = screen(shape, rect_corner(centre, (r.width, r.height), i + 2));
+            shape.set_size(w, h);
+            (target, rect_corner(centre, (w, h), i + 2))
+        }
+        (ShapeKind::RoundedRectangle(r), HandleKind::Corner(i)) => {
+            let (w, h) = resize_from_corner(centre, (r.width, r.height), i, p);
+            let target = screen(shape, rect_corner(centre, (r.width, r.height), i + 2));
+            shape.set_size(w, h);
+            (target, rect_corner(centre, (w, h), i + 2))
+        }
+        (ShapeKind::RoundedRectangle(r), HandleKind::CornerRadius) => {
+            let radius = (start_radius + p.0 - start.0).clamp(0.0, r.width.min(r.height) * 0.5);
+            if radius == r.radius {
+                return false;
+            }
+            let target = screen(shape, centre);
+            shape.set_radius(radius);
+            (target, centre)
+        }
+        (ShapeKind::Image(img), HandleKind::Corner(i)) => {
+            let (hw, hh) = (img.width * 0.5, img.height * 0.5);
+            let (w, h) = resize_from_corner((-hw, -hh), (hw, hh), i, p);
+            let target = screen(shape, rect_corner((-hw, -hh), (hw, hh), i + 2));
+            shape.set_size(w, h);
+            (target, rect_corner((-w * 0.5, -h * 0.5), (w * 0.5, h * 0.5), i + 2))
+        }
+        (ShapeKind::Circle(_) | ShapeKind::Arc(_), HandleKind::Radius) => {
+            let target = screen(shape, centre);
+            shape.set_radius(p.0.hypot(p.1).max(MIN_EXTENT));
+            (target, centre)
+        }
+        (ShapeKind::Ellipse(e), HandleKind::RadiusX | HandleKind::RadiusY) => {
+            let (rx, ry) = if kind == HandleKind::RadiusX {
+                (p.0.abs().max(MIN_EXTENT), e.radius_y)
+            } else {
+                (e.radius_x, p.1.abs().max(MIN_EXTENT))
+            };
+            let target = screen(shape, centre);
+            shape.set_radii(rx, ry);
+            (target, centre)
+        }
+        (kind_shape, HandleKind::Vertex(i)) => {
+            let Some(mut points) = points_of(&kind_shape) else {
+                return false;
+            };
+            if i >= points.len() || points[i] == p {
+                return false;
+            }
+            points[i] = p;
+            // Pin an undragged vertex; a lone vertex follows the cursor.
+            let (target, fixed) = match (0..points.len()).find(|&j| j != i) {
+                Some(j) => (screen(shape, points[j]), points[j]),
+                None => (cursor, p),
+            };
+            shape.set_points(&points);
+            (target, fixed)
+        }
+        _ => return false,
+    };
+
+    let (ax, ay) = screen(shape, fixed_new);
+    let (x, y) = shape.position();
+    shape.set_position(x + target.0 - ax, y + target.1 - ay);
+    true
+}
+
+fn screen(shape: &ShapeRenderable, (x, y): (f32, f32)) -> (f32, f32) {
+    shape.to_screen(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_run_clockwise_from_top_left() {
+        let (min, max) = ((0.0, 0.0), (10.0, 20.0));
+        let corners: Vec<_> = (0..4).map(|i| rect_corner(min, max, i)).collect();
+        assert_eq!(corners, vec![(0.0, 0.0), (10.0, 0.0), (10.0, 20.0), (0.0, 20.0)]);
+    }
+
+    #[test]
+    fn corner_drag_keeps_opposite_corner() {
+        let (min, max) = ((0.0, 0.0), (100.0, 50.0));
+        // Bottom-right dragged outwards grows from the top-left.
+        assert_eq!(resize_from_corner(min, max, 2, (120.0, 70.0)), (120.0, 70.0));
+        // Top-left dragged inwards shrinks towards the bottom-right.
+        assert_eq!(resize_from_corner(min, max, 0, (30.0, 10.0)), (70.0, 40.0));
+        // Top-right measures width from the left and height from the bottom.
+        assert_eq!(resize_from_corner(min, max, 1, (80.0, -10.0)), (80.0, 60.0));
+    }
+
+    #[test]
+    fn corner_drag_does_not_flip() {
+        let (min, max) = ((-5.0, -5.0), (5.0, 5.0));
+        assert_eq!(resize_from_corner(min, max, 2, (-20.0, -20.0)), (MIN_EXTENT, MIN_EXTENT));
+    }
+
+    #[test]
+    fn handles_follow_shape_parameters() {
+        use crate::graphics2d::shapes::{Ellipse, RoundedRectangle};
+        let rr = local_handles(&ShapeKind::RoundedRectangle(RoundedRectangle::new(40.0, 20.0, 6.0)), 3.0);
+        assert_eq!(rr.len(), 5);
+        assert_eq!(rr[4], (HandleKind::CornerRadius, (6.0, 3.0)));
+
+        let e = local_handles(&ShapeKind::Ellipse(Ellipse::new(30.0, 10.0)), 0.0);
+        assert_eq!(e, vec![(HandleKind::RadiusX, (30.0, 0.0)), (HandleKind::RadiusY, (0.0, 10.0))]);
+        assert!(local_handles(&ShapeKind::Point, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::core::{Camera2D, Color, Projection, Renderable, Renderer, Snapper, Vec2};
+use crate::graphics2d::shapes::{Line, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Closest two grid lines may get on screen before the spacing is doubled.
+const MIN_LINE_SPACING_PX: f32 = 4.0;
+
+/// A renderable grid of evenly spaced lines across the window.
+///
+/// [`render`](Renderable::render) draws the grid in screen pixels;
+/// [`render_with_camera`](Self::render_with_camera) draws a world-space
+/// grid that pans and zooms with a [`Camera2D`]. When zoomed out far
+/// enough that lines would be closer than a few pixels, every other line
+/// is dropped so the grid stays readable.
+///
+/// ```ignore
+/// let snapper = Snapper::grid(25.0);
+/// let mut grid = GridOverlay::from_snapper(&snapper).unwrap();
+/// // in on_render
+/// grid.render_with_camera(renderer, camera);
+/// ```
+pub struct GridOverlay {
+    spacing: f32,
+    origin: (f32, f32),
+    color: Color,
+    line_width: f32,
+    /// Vertical and horizontal line renderables, and the window size they
+    /// were built for.
+    lines: Option<((i32, i32), ShapeRenderable, ShapeRenderable)>,
+}
+
+impl GridOverlay {
+    /// A grid with lines every `spacing` units (clamped to at least 1).
+    pub fn new(spacing: f32) -> Self {
+        Self {
+            spacing: spacing.max(1.0),
+            origin: (0.0, 0.0),
+            color: Color::from_rgba(0.5, 0.5, 0.5, 0.3),
+            line_width: 1.0,
+            lines: None,
+        }
+    }
+
+    /// A grid matching the snapper's grid size and origin, or `None` if the
+    /// snapper has no grid.
+    pub fn from_snapper(snapper: &Snapper) -> Option<Self> {
+        let mut grid = Self::new(snapper.grid_size()?);
+        let (x, y) = snapper.grid_origin();
+        grid.set_origin(x, y);
+        Some(grid)
+    }
+
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.spacing = spacing.max(1.0);
+        self
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// Point a grid line crossing passes through (default `(0, 0)`).
+    pub fn set_origin(&mut self, x: f32, y: f32) -> &mut Self {
+        self.origin = (x, y);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self.lines = None;
+        self
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> &mut Self {
+        self.line_width = width;
+        self.lines = None;
+        self
+    }
+
+    /// Draw the grid in world space under `camera`.
+    pub fn render_with_camera(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        let (x0, y0, x1, y1) = camera.world_bounds();
+        let spacing = readable_spacing(self.spacing, camera.scale());
+        let xs = grid_lines(x0, x1, spacing, self.origin.0)
+            .into_iter()
+            .map(|x| camera.world_to_screen(Vec2::new(x, 0.0)).x);
+        let ys = grid_lines(y0, y1, spacing, self.origin.1)
+            .into_iter()
+            .map(|y| camera.world_to_screen(Vec2::new(0.0, y)).y);
+        self.draw(renderer, xs.collect(), ys.collect());
+    }
+
+    fn draw(&mut self, renderer: &Renderer, xs: Vec<f32>, ys: Vec<f32>) {
+        let size = renderer.window_handle.size();
+        if self.lines.as_ref().is_none_or(|(built, _, _)| *built != size) {
+            let (w, h) = (size.0 as f32, size.1 as f32);
+            let style = ShapeStyle::stroke(self.color, self.line_width);
+            let mut vertical = ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (0.0, h))), style.clone());
+            let mut horizontal = ShapeRenderable::from_shape(ShapeKind::Line(Line::new((0.0, 0.0), (w, 0.0))), style);
+            vertical.create_multiple_instances(xs.len().max(1));
+            horizontal.create_multiple_instances(ys.len().max(1));
+            self.lines = Some((size, vertical, horizontal));
+        }
+        let (_, vertical, horizontal) = self.lines.as_mut().unwrap();
+        // An empty instance list would fall back to a single draw at (0, 0).
+        if !xs.is_empty() {
+            let positions: Vec<Vec2> = xs.into_iter().map(|x| Vec2::new(x, 0.0)).collect();
+            vertical.set_instance_positions(&positions).render(renderer);
+        }
+        if !ys.is_empty() {
+            let positions: Vec<Vec2> = ys.into_iter().map(|y| Vec2::new(0.0, y)).collect();
+            horizontal.set_instance_positions(&positions).render(renderer);
+        }
+    }
+}
+
+impl Renderable for GridOverlay {
+    /// Draw the grid in screen pixels.
+    fn render(&mut self, renderer: &Renderer) {
+        let (w, h) = renderer.window_handle.size();
+        let spacing = readable_spacing(self.spacing, 1.0);
+        let xs = grid_lines(0.0, w as f32, spacing, self.origin.0);
+        let ys = grid_lines(0.0, h as f32, spacing, self.origin.1);
+        self.draw(renderer, xs, ys);
+    }
+}
+
+/// Double `spacing` until lines are at least `MIN_LINE_SPACING_PX` apart at
+/// `scale` pixels per unit.
+fn readable_spacing(spacing: f32, scale: f32) -> f32 {
+    let mut spacing = spacing;
+    while spacing * scale < MIN_LINE_SPACING_PX {
+        spacing *= 2.0;
+    }
+    spacing
+}
+
+/// Positions of the grid lines through `origin` every `spacing` within
+/// `min..=max`.
+fn grid_lines(min: f32, max: f32, spacing: f32, origin: f32) -> Vec<f32> {
+    let first = ((min - origin) / spacing).ceil() as i64;
+    let last = ((max - origin) / spacing).floor() as i64;
+    (first..=last).map(|i| origin + i as f32 * spacing).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_cover_range_through_origin() {
+        assert_eq!(grid_lines(0.0, 50.0, 20.0, 0.0), vec![0.0, 20.0, 40.0]);
+        assert_eq!(grid_lines(-15.0, 30.0, 10.0, 5.0), vec![-15.0, -5.0, 5.0, 15.0, 25.0]);
+        assert!(grid_lines(1.0, 9.0, 10.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn spacing_doubles_when_zoomed_out() {
+        assert_eq!(readable_spacing(10.0, 1.0), 10.0);
+        // 10 units at 0.1 px/unit is 1px; 40 units is 4px.
+        assert_eq!(readable_spacing(10.0, 0.1), 40.0);
+    }
+}
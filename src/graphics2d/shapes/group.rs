@@ -0,0 +1,240 @@
+use crate::core::{Renderable, Renderer};
+use crate::graphics2d::shapes::ShapeRenderable;
+
+/// A child of a [`Group`]: either a shape or a nested group.
+///
+/// Shapes are boxed to keep the enum small (a `ShapeRenderable` carries two
+/// meshes and its style).
+pub enum GroupChild {
+    Shape(Box<ShapeRenderable>),
+    Group(Group),
+}
+
+/// A renderable that owns child shapes and groups and propagates its
+/// position, scale, rotation and visibility to them.
+///
+/// Children keep their own transform, interpreted relative to the group:
+/// a child at `(8, -5)` in a group positioned at `(100, 100)` and scaled by
+/// 2 is drawn at `(116, 90)` with twice its own scale. Groups nest, so
+/// multi-part objects (marker + label, a vehicle with wheels) move as one.
+///
+/// ```ignore
+/// let mut waypoint = Group::new();
+/// waypoint.add_shape(marker);
+/// let label = waypoint.add_shape(label);
+/// waypoint.shape_mut(label).unwrap().set_position(8.0, -5.0);
+///
+/// waypoint.set_position(screen.x, screen.y);
+/// waypoint.render(renderer);
+/// ```
+///
+/// Per-instance positions of instanced children are not transformed by the
+/// group; only the group offset, scale and rotation uniforms are applied.
+pub struct Group {
+    x: f32,
+    y: f32,
+    scale: f32,
+    rotation: f32,
+    visible: bool,
+    children: Vec<GroupChild>,
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, visible: true, children: Vec::new() }
+    }
+
+    /// Add a shape and return its child index.
+    pub fn add_shape(&mut self, shape: ShapeRenderable) -> usize {
+        self.children.push(GroupChild::Shape(Box::new(shape)));
+        self.children.len() - 1
+    }
+
+    /// Add a nested group and return its child index.
+    pub fn add_group(&mut self, group: Group) -> usize {
+        self.children.push(GroupChild::Group(group));
+        self.children.len() - 1
+    }
+
+    pub fn children(&self) -> &[GroupChild] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut [GroupChild] {
+        &mut self.children
+    }
+
+    /// The shape at `index`, or `None` if out of range or a nested group.
+    pub fn shape_mut(&mut self, index: usize) -> Option<&mut ShapeRenderable> {
+        match self.children.get_mut(index) {
+            Some(GroupChild::Shape(shape)) => Some(shape.as_mut()),
+            _ => None,
+        }
+    }
+
+    /// The nested group at `index`, or `None` if out of range or a shape.
+    pub fn group_mut(&mut self, index: usize) -> Option<&mut Group> {
+        match self.children.get_mut(index) {
+            Some(GroupChild::Group(group)) => Some(group),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    pub fn set_scale(&mut self, scale: f32) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn set_rotation(&mut self, angle: f32) -> &mut Self {
+        self.rotation = angle;
+        self
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Hide or show the group and everything in it.
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn render_with(&mut self, parent: &GroupTransform, renderer: &Renderer) {
+        if !self.visible {
+            return;
+        }
+        let world = parent.then(&GroupTransform {
+            x: self.x,
+            y: self.y,
+            scale: self.scale,
+            rotation: self.rotation,
+        });
+
+        for child in &mut self.children {
+            match child {
+                GroupChild::Shape(shape) => {
+                    // Children store local transforms; swap in the world
+                    // transform for this draw and restore it afterwards.
+                    let (x, y) = shape.position();
+                    let (scale, rotation) = (shape.scale(), shape.rotation());
+                    let child_world = world.then(&GroupTransform { x, y, scale, rotation });
+                    shape
+                        .set_position(child_world.x, child_world.y)
+                        .set_scale(child_world.scale)
+                        .set_rotation(child_world.rotation);
+                    shape.render(renderer);
+                    shape.set_position(x, y).set_scale(scale).set_rotation(rotation);
+                }
+                GroupChild::Group(group) => group.render_with(&world, renderer),
+            }
+        }
+    }
+}
+
+impl Renderable for Group {
+    fn render(&mut self, renderer: &Renderer) {
+        self.render_with(&GroupTransform::IDENTITY, renderer);
+    }
+}
+
+/// Position/scale/rotation in the same order the shape shaders apply them:
+/// rotate, scale, then translate.
+/// Also used by [`SceneGraph`](crate::core::SceneGraph) nodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct GroupTransform {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) scale: f32,
+    pub(crate) rotation: f32,
+}
+
+impl GroupTransform {
+    pub(crate) const IDENTITY: GroupTransform = GroupTransform { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0 };
+
+    /// Compose a child transform expressed in this transform's local space.
+    pub(crate) fn then(&self, child: &GroupTransform) -> GroupTransform {
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let lx = child.x * self.scale;
+        let ly = child.y * self.scale;
+        GroupTransform {
+            x: self.x + lx * cos_r - ly * sin_r,
+            y: self.y + lx * sin_r + ly * cos_r,
+            scale: self.scale * child.scale,
+            rotation: self.rotation + child.rotation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn t(x: f32, y: f32, scale: f32, rotation: f32) -> GroupTransform {
+        GroupTransform { x, y, scale, rotation }
+    }
+
+    #[test]
+    fn identity_is_neutral() {
+        let child = t(3.0, 4.0, 2.0, 0.5);
+        assert_eq!(GroupTransform::IDENTITY.then(&child), child);
+    }
+
+    #[test]
+    fn offset_is_scaled_and_translated() {
+        let world = t(100.0, 100.0, 2.0, 0.0).then(&t(8.0, -5.0, 1.5, 0.0));
+        assert_eq!(world, t(116.0, 90.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn offset_follows_parent_rotation() {
+        // Quarter turn maps +x to +y (same convention as the shape shaders).
+        let world = t(0.0, 0.0, 1.0, FRAC_PI_2).then(&t(10.0, 0.0, 1.0, 0.25));
+        assert!(world.x.abs() < 1e-4);
+        assert!((world.y - 10.0).abs() < 1e-4);
+        assert!((world.rotation - (FRAC_PI_2 + 0.25)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn nesting_composes() {
+        let outer = t(10.0, 0.0, 2.0, 0.0);
+        let inner = t(5.0, 0.0, 3.0, 0.0);
+        let leaf = t(1.0, 0.0, 1.0, 0.0);
+        let world = outer.then(&inner).then(&leaf);
+        // 10 + 2*5 + 2*3*1 = 26
+        assert_eq!(world, t(26.0, 0.0, 6.0, 0.0));
+    }
+}
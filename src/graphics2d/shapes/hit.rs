@@ -0,0 +1,162 @@
+//! Point-in-shape and distance-to-outline math used by
+//! [`ShapeRenderable::contains`](super::ShapeRenderable::contains).
+//!
+//! All functions work in the shape's own coordinate space (the space the
+//! `ShapeKind` parameters are expressed in), before anchor, scale, rotation
+//! and position are applied.
+
+use std::f32::consts::TAU;
+
+/// Distance from `p` to the segment `a`-`b`.
+pub(crate) fn segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let (apx, apy) = (p.0 - a.0, p.1 - a.1);
+    let len_sq = abx * abx + aby * aby;
+    let t = if len_sq > 0.0 {
+        ((apx * abx + apy * aby) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + abx * t, a.1 + aby * t);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
+
+/// Distance from `p` to a polyline; `closed` adds the last-to-first edge.
+/// Returns `f32::INFINITY` for an empty point list.
+pub(crate) fn polyline_distance(p: (f32, f32), points: &[(f32, f32)], closed: bool) -> f32 {
+    match points {
+        [] => f32::INFINITY,
+        [only] => segment_distance(p, *only, *only),
+        _ => {
+            let open = points
+                .windows(2)
+                .map(|w| segment_distance(p, w[0], w[1]))
+                .fold(f32::INFINITY, f32::min);
+            if closed {
+                open.min(segment_distance(p, points[points.len() - 1], points[0]))
+            } else {
+                open
+            }
+        }
+    }
+}
+
+/// Even-odd point-in-polygon test. Works for concave and self-intersecting
+/// polygons in either winding order.
+pub(crate) fn point_in_polygon(p: (f32, f32), points: &[(f32, f32)]) -> bool {
+    let mut inside = false;
+    let n = points.len();
+    let mut j = n.wrapping_sub(1);
+    for i in 0..n {
+        let (xi, yi) = points[i];
+        let (xj, yj) = points[j];
+        if (yi > p.1) != (yj > p.1) && p.0 < (xj - xi) * (p.1 - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Signed distance to a rounded box spanning `min`..`max` with corner
+/// `radius` (negative inside). A zero radius gives a plain rectangle.
+pub(crate) fn rounded_box_sdf(p: (f32, f32), min: (f32, f32), max: (f32, f32), radius: f32) -> f32 {
+    let hw = (max.0 - min.0) * 0.5;
+    let hh = (max.1 - min.1) * 0.5;
+    let r = radius.clamp(0.0, hw.min(hh));
+    let qx = (p.0 - (min.0 + hw)).abs() - (hw - r);
+    let qy = (p.1 - (min.1 + hh)).abs() - (hh - r);
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    outside + qx.max(qy).min(0.0) - r
+}
+
+/// Approximate signed distance to an origin-centred ellipse (negative
+/// inside), using the first-order `F / |∇F|` estimate. Exact on the
+/// outline, which is all stroke hit testing needs.
+pub(crate) fn ellipse_sdf(p: (f32, f32), rx: f32, ry: f32) -> f32 {
+    if rx <= 0.0 || ry <= 0.0 {
+        return f32::INFINITY;
+    }
+    let f = (p.0 / rx).powi(2) + (p.1 / ry).powi(2) - 1.0;
+    let gx = 2.0 * p.0 / (rx * rx);
+    let gy = 2.0 * p.1 / (ry * ry);
+    let g = (gx * gx + gy * gy).sqrt();
+    if g == 0.0 {
+        -rx.min(ry)
+    } else {
+        f / g
+    }
+}
+
+/// Distance to an origin-centred circular arc, using the same angle
+/// convention as arc rendering (counter-clockwise on screen, Y down).
+pub(crate) fn arc_distance(p: (f32, f32), radius: f32, start_angle: f32, end_angle: f32) -> f32 {
+    let mut sweep = end_angle - start_angle;
+    if sweep < 0.0 {
+        sweep += TAU;
+    }
+    let theta = (-p.1).atan2(p.0);
+    if (theta - start_angle).rem_euclid(TAU) <= sweep {
+        ((p.0 * p.0 + p.1 * p.1).sqrt() - radius).abs()
+    } else {
+        let end = |a: f32| (radius * a.cos(), -radius * a.sin());
+        let (s, e) = (end(start_angle), end(start_angle + sweep));
+        let ds = ((p.0 - s.0).powi(2) + (p.1 - s.1).powi(2)).sqrt();
+        let de = ((p.0 - e.0).powi(2) + (p.1 - e.1).powi(2)).sqrt();
+        ds.min(de)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn segment_distance_clamps_to_endpoints() {
+        assert_eq!(segment_distance((5.0, 3.0), (0.0, 0.0), (10.0, 0.0)), 3.0);
+        assert_eq!(segment_distance((13.0, 4.0), (0.0, 0.0), (10.0, 0.0)), 5.0);
+    }
+
+    #[test]
+    fn polygon_containment_handles_concave() {
+        // L-shape: the notch at (15, 15) is outside.
+        let l = [(0.0, 0.0), (20.0, 0.0), (20.0, 10.0), (10.0, 10.0), (10.0, 20.0), (0.0, 20.0)];
+        assert!(point_in_polygon((5.0, 15.0), &l));
+        assert!(point_in_polygon((15.0, 5.0), &l));
+        assert!(!point_in_polygon((15.0, 15.0), &l));
+    }
+
+    #[test]
+    fn closed_polyline_includes_closing_edge() {
+        let tri = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)];
+        let p = (0.0, 5.0);
+        assert!(polyline_distance(p, &tri, true) < 1e-6);
+        assert!(polyline_distance(p, &tri, false) > 1.0);
+    }
+
+    #[test]
+    fn rounded_box_corners_are_cut() {
+        let (min, max) = ((0.0, 0.0), (100.0, 50.0));
+        assert!(rounded_box_sdf((50.0, 25.0), min, max, 0.0) < 0.0);
+        assert!(rounded_box_sdf((0.5, 0.5), min, max, 0.0) < 0.0);
+        assert!(rounded_box_sdf((0.5, 0.5), min, max, 10.0) > 0.0);
+        assert!((rounded_box_sdf((110.0, 25.0), min, max, 0.0) - 10.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ellipse_sdf_is_zero_on_outline() {
+        assert!(ellipse_sdf((20.0, 0.0), 20.0, 10.0).abs() < 1e-5);
+        assert!(ellipse_sdf((0.0, 10.0), 20.0, 10.0).abs() < 1e-5);
+        assert!(ellipse_sdf((0.0, 0.0), 20.0, 10.0) < 0.0);
+        assert!(ellipse_sdf((0.0, 12.0), 20.0, 10.0) > 0.0);
+    }
+
+    #[test]
+    fn arc_distance_respects_sweep() {
+        // Upper half (screen Y down): 0..PI passes through (0, -r).
+        assert!(arc_distance((0.0, -10.0), 10.0, 0.0, PI) < 1e-4);
+        // Bottom point is outside the sweep; nearest is an endpoint.
+        assert!((arc_distance((0.0, 10.0), 10.0, 0.0, PI) - 200f32.sqrt()).abs() < 1e-3);
+    }
+}
@@ -1,12 +1,39 @@
+mod bezier;
+mod bounds_overlay;
+mod editor;
+mod grid;
+mod group;
+mod hit;
+mod morph;
+mod selection;
 mod shaperenderable;
-
+mod simplify;
+mod svg_path;
+mod text_layout;
+
+pub use bounds_overlay::BoundsOverlay;
+pub use editor::{EditEvent, Handle, HandleKind, ShapeEditor};
+pub use grid::GridOverlay;
+pub use group::{Group, GroupChild};
+pub(crate) use group::GroupTransform;
+pub use morph::ShapeMorph;
+pub use selection::RubberBand;
+pub(crate) use selection::merge_selection;
+pub(crate) use shaperenderable::{cache_font_atlas, render_triangle_mesh, triangle_mesh};
 pub use shaperenderable::Anchor;
 pub use shaperenderable::ShapeRenderable;
 pub use shaperenderable::ShapeRenderableBuilder;
 pub use shaperenderable::ShapeStyle;
 pub use shaperenderable::clear_font_cache;
+pub use shaperenderable::set_font_fallbacks;
+pub use simplify::{simplify_douglas_peucker, simplify_visvalingam, PolylineLod};
+pub use svg_path::{Subpath, SvgPath};
+pub use text_layout::{TextAlign, TextLayout, VerticalAlign};
+
+use crate::graphics2d::tessellation;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShapeKind {
     Point,
     MultiPoint(MultiPoint),
@@ -19,11 +46,13 @@ pub enum ShapeKind {
     Circle(Circle),
     Ellipse(Ellipse),
     Arc(Arc),
+    Bezier(Bezier),
     Image(Image),
     Text(Text),
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point;
 impl Point{
     pub fn new() -> Self{
@@ -31,7 +60,8 @@ impl Point{
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiPoint {
     pub points: Vec<(f32, f32)>,
 }
@@ -53,7 +83,8 @@ impl MultiPoint {
 }
 
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
     pub start: (f32, f32),
     pub end: (f32, f32),
@@ -73,7 +104,8 @@ impl Line {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polyline {
     pub points: Vec<(f32, f32)>,
 }
@@ -93,7 +125,8 @@ impl Polyline {
         (sx / n, sy / n)
     }
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     pub vertices: [(f32, f32); 3],
 }
@@ -111,7 +144,8 @@ impl Triangle {
 }
 
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     pub width: f32,
     pub height: f32,
@@ -123,7 +157,8 @@ impl Rectangle {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoundedRectangle {
     pub width: f32,
     pub height: f32,
@@ -136,7 +171,8 @@ impl RoundedRectangle {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polygon {
     pub points: Vec<(f32, f32)>,
 }
@@ -190,131 +226,29 @@ impl Polygon {
     /// Positive when vertices are ordered counter-clockwise (in a Y-up coordinate
     /// system), negative when clockwise. Magnitude equals the polygon area.
     pub fn signed_area(&self) -> f32 {
-        let n = self.points.len();
-        if n < 3 {
-            return 0.0;
-        }
-        let mut sum = 0.0;
-        for i in 0..n {
-            let (x0, y0) = self.points[i];
-            let (x1, y1) = self.points[(i + 1) % n];
-            sum += x0 * y1 - x1 * y0;
-        }
-        sum * 0.5
+        tessellation::signed_area(&self.points)
     }
 
-    /// Triangulate the (possibly concave) simple polygon using ear clipping.
-    ///
-    /// Returns a list of triangles as triples of indices into `self.points`.
-    /// The polygon is assumed to be simple (non-self-intersecting). The
-    /// algorithm normalizes the working order to counter-clockwise internally,
-    /// so input may be in either winding.
-    ///
-    /// Complexity: O(n²). Suitable for typical polygon sizes; for very large
-    /// polygons consider a more advanced algorithm.
+    /// A polygon with holes, merged into a single ring that can be filled
+    /// with [`triangulate`](Self::triangulate); see
+    /// [`tessellation::bridge_holes`].
     ///
-    /// Returns an empty Vec for degenerate input (fewer than 3 vertices).
-    pub fn triangulate(&self) -> Vec<[usize; 3]> {
-        let n = self.points.len();
-        if n < 3 {
-            return Vec::new();
-        }
-        if n == 3 {
-            return vec![[0, 1, 2]];
-        }
-
-        // Work in CCW order so the convexity test has a consistent sign.
-        let ccw = self.signed_area() > 0.0;
-        let mut indices: Vec<usize> = if ccw {
-            (0..n).collect()
-        } else {
-            (0..n).rev().collect()
-        };
-
-        let mut triangles: Vec<[usize; 3]> = Vec::with_capacity(n - 2);
-        // Safety bound: a simple polygon needs at most n-2 ears, but we may
-        // scan multiple vertices per removal. Cap iterations to avoid infinite
-        // loops on malformed (e.g., self-intersecting) input.
-        let max_iterations = n * n;
-        let mut iterations = 0;
-
-        while indices.len() > 3 {
-            iterations += 1;
-            if iterations > max_iterations {
-                break;
-            }
-
-            let m = indices.len();
-            let mut ear_index: Option<usize> = None;
-            for i in 0..m {
-                let a = indices[(i + m - 1) % m];
-                let b = indices[i];
-                let c = indices[(i + 1) % m];
-                if self.is_ear(a, b, c, &indices) {
-                    ear_index = Some(i);
-                    triangles.push([a, b, c]);
-                    break;
-                }
-            }
-            match ear_index {
-                Some(i) => {
-                    indices.remove(i);
-                }
-                None => break, // degenerate polygon; bail out
-            }
-        }
-
-        if indices.len() == 3 {
-            triangles.push([indices[0], indices[1], indices[2]]);
-        }
-
-        triangles
+    /// Fill the result, but stroke the original rings: an outline of the
+    /// merged ring would draw the bridges between them.
+    pub fn with_holes(outer: Vec<(f32, f32)>, holes: Vec<Vec<(f32, f32)>>) -> Self {
+        Polygon::new(tessellation::bridge_holes(outer, holes))
     }
 
-    /// True if the triangle (a, b, c) is an ear: convex at b (in CCW order)
-    /// and contains no other polygon vertex.
-    fn is_ear(&self, a: usize, b: usize, c: usize, indices: &[usize]) -> bool {
-        let (ax, ay) = self.points[a];
-        let (bx, by) = self.points[b];
-        let (cx, cy) = self.points[c];
-
-        // Convex at b iff cross product of (b-a) x (c-b) is positive in CCW.
-        let cross = (bx - ax) * (cy - by) - (by - ay) * (cx - bx);
-        if cross <= 0.0 {
-            return false;
-        }
-
-        // Reject if any other polygon vertex lies strictly inside the triangle.
-        for &idx in indices {
-            if idx == a || idx == b || idx == c {
-                continue;
-            }
-            let (px, py) = self.points[idx];
-            if point_in_triangle(px, py, ax, ay, bx, by, cx, cy) {
-                return false;
-            }
-        }
-        true
+    /// Triangulate the (possibly concave) simple polygon using ear clipping;
+    /// see [`tessellation::triangulate`].
+    ///
+    /// Returns a list of triangles as triples of indices into `self.points`,
+    /// or an empty Vec for degenerate input (fewer than 3 vertices).
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        tessellation::triangulate(&self.points)
     }
 }
 
-/// Returns true if point p lies inside the triangle (a, b, c), using the
-/// half-plane sign test. Points exactly on edges are treated as inside.
-fn point_in_triangle(
-    px: f32, py: f32,
-    ax: f32, ay: f32,
-    bx: f32, by: f32,
-    cx: f32, cy: f32,
-) -> bool {
-    let d1 = (px - bx) * (ay - by) - (ax - bx) * (py - by);
-    let d2 = (px - cx) * (by - cy) - (bx - cx) * (py - cy);
-    let d3 = (px - ax) * (cy - ay) - (cx - ax) * (py - ay);
-
-    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
-    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
-    !(has_neg && has_pos)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -415,6 +349,23 @@ mod tests {
         assert_eq!(tris.len(), 4);
     }
 
+    #[test]
+    fn with_holes_triangulates_to_the_area_between_rings() {
+        let outer = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let holes = vec![
+            vec![(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)],
+            vec![(6.0, 6.0), (6.0, 8.0), (8.0, 8.0), (8.0, 6.0)],
+        ];
+        let polygon = Polygon::with_holes(outer, holes);
+        assert_eq!(polygon.points.len(), 4 + 2 * (4 + 2));
+        let area: f32 = polygon
+            .triangulate()
+            .iter()
+            .map(|t| Polygon::new(t.iter().map(|&i| polygon.points[i]).collect()).signed_area().abs())
+            .sum();
+        assert!((area - (100.0 - 8.0)).abs() < 1e-3, "area {area}");
+    }
+
     #[test]
     fn triangulate_degenerate_returns_empty() {
         let p = Polygon::new(vec![(0.0, 0.0), (1.0, 0.0)]);
@@ -422,7 +373,8 @@ mod tests {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle {
     pub radius: f32,
 }
@@ -433,7 +385,8 @@ impl Circle {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ellipse {
     pub radius_x: f32,
     pub radius_y: f32,
@@ -445,7 +398,8 @@ impl Ellipse {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Image {
     pub width: f32,
     pub height: f32,
@@ -457,7 +411,8 @@ impl Image {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arc {
     pub radius: f32,
     pub start_angle: f32,
@@ -470,11 +425,72 @@ impl Arc {
     }
 }
 
-#[derive(Clone)]
+/// A quadratic or cubic Bézier curve, stroked like a [`Polyline`] after
+/// [`flatten`](Self::flatten)ing it into line segments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Bezier {
+    Quadratic {
+        start: (f32, f32),
+        control: (f32, f32),
+        end: (f32, f32),
+    },
+    Cubic {
+        start: (f32, f32),
+        control1: (f32, f32),
+        control2: (f32, f32),
+        end: (f32, f32),
+    },
+}
+
+impl Bezier {
+    pub fn quadratic(start: (f32, f32), control: (f32, f32), end: (f32, f32)) -> Self {
+        Self::Quadratic { start, control, end }
+    }
+
+    pub fn cubic(start: (f32, f32), control1: (f32, f32), control2: (f32, f32), end: (f32, f32)) -> Self {
+        Self::Cubic { start, control1, control2, end }
+    }
+
+    /// Quadratic from 3 points or cubic from 4, in curve order; `None`
+    /// for other lengths.
+    pub fn from_points(points: &[(f32, f32)]) -> Option<Self> {
+        match *points {
+            [start, control, end] => Some(Self::quadratic(start, control, end)),
+            [start, control1, control2, end] => Some(Self::cubic(start, control1, control2, end)),
+            _ => None,
+        }
+    }
+
+    /// Start, control and end points in curve order.
+    pub fn points(&self) -> Vec<(f32, f32)> {
+        match *self {
+            Self::Quadratic { start, control, end } => vec![start, control, end],
+            Self::Cubic { start, control1, control2, end } => vec![start, control1, control2, end],
+        }
+    }
+
+    pub fn start(&self) -> (f32, f32) {
+        match *self {
+            Self::Quadratic { start, .. } | Self::Cubic { start, .. } => start,
+        }
+    }
+
+    pub fn end(&self) -> (f32, f32) {
+        match *self {
+            Self::Quadratic { end, .. } | Self::Cubic { end, .. } => end,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     pub content: String,
     pub font_path: String,
     pub font_size: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub layout: TextLayout,
 }
 
 impl Text {
@@ -483,6 +499,13 @@ impl Text {
             content: content.into(),
             font_path: font_path.into(),
             font_size,
+            layout: TextLayout::default(),
         }
     }
+
+    /// Set the alignment, wrapping and line spacing; see [`TextLayout`].
+    pub fn with_layout(mut self, layout: TextLayout) -> Self {
+        self.layout = layout;
+        self
+    }
 }
\ No newline at end of file
@@ -0,0 +1,291 @@
+use crate::graphics2d::shapes::{Circle, Ellipse, Line, Polygon, Polyline, Rectangle, ShapeKind, Triangle};
+
+/// Segments used when a circle or ellipse is morphed into a polygon.
+const CURVE_SEGMENTS: usize = 64;
+
+type Outline = Vec<(f32, f32)>;
+
+/// Interpolation between two compatible shapes.
+///
+/// Supported pairs:
+/// - circle ↔ circle / ellipse: the radii are interpolated.
+/// - rectangle ↔ rectangle: the size is interpolated.
+/// - line ↔ line and triangle ↔ triangle: the vertices are interpolated.
+/// - polyline ↔ polyline: both are resampled to the same vertex count.
+/// - polygon ↔ polygon, triangle, rectangle, circle or ellipse: both
+///   outlines are resampled to the same vertex count and interpolated as
+///   polygons.
+///
+/// Resampling places a vertex at every original corner of both shapes
+/// (by fraction of perimeter), so corners stay sharp through the morph.
+/// Closed outlines are matched in winding and rotated to the start vertex
+/// that minimises travel. Outlines are taken in each shape's local
+/// coordinates: circles and ellipses are centred on the origin, rectangles
+/// extend from it.
+///
+/// Feed [`sample`](Self::sample) to
+/// [`ShapeRenderable::apply_morph`](super::ShapeRenderable::apply_morph)
+/// each frame:
+///
+/// ```ignore
+/// let morph = ShapeMorph::new(&ShapeKind::Circle(Circle::new(40.0)), &star).unwrap();
+/// shape.apply_morph(&morph, (elapsed / duration).min(1.0));
+/// ```
+pub struct ShapeMorph {
+    from: ShapeKind,
+    to: ShapeKind,
+    path: MorphPath,
+}
+
+enum MorphPath {
+    Radii((f32, f32), (f32, f32)),
+    Size((f32, f32), (f32, f32)),
+    Line(Line, Line),
+    Triangle(Triangle, Triangle),
+    Open(Outline, Outline),
+    Closed(Outline, Outline),
+}
+
+impl ShapeMorph {
+    /// A morph from `from` to `to`, or `None` if the pair is not supported
+    /// (see the type docs) or a polyline/polygon has too few points.
+    pub fn new(from: &ShapeKind, to: &ShapeKind) -> Option<Self> {
+        use ShapeKind::*;
+        let path = match (from, to) {
+            (Circle(_) | Ellipse(_), Circle(_) | Ellipse(_)) => MorphPath::Radii(radii(from)?, radii(to)?),
+            (Rectangle(a), Rectangle(b)) => MorphPath::Size((a.width, a.height), (b.width, b.height)),
+            (Line(a), Line(b)) => MorphPath::Line(*a, *b),
+            (Triangle(a), Triangle(b)) => MorphPath::Triangle(*a, *b),
+            (Polyline(a), Polyline(b)) => {
+                if a.points.len() < 2 || b.points.len() < 2 {
+                    return None;
+                }
+                let (a, b) = resample_pair(&a.points, &b.points, false);
+                MorphPath::Open(a, b)
+            }
+            _ => {
+                let a = closed_outline(from)?;
+                let mut b = closed_outline(to)?;
+                if !matches!(from, Polygon(_)) && !matches!(to, Polygon(_)) {
+                    return None;
+                }
+                if signed_area(&a).signum() != signed_area(&b).signum() {
+                    b.reverse();
+                }
+                let (a, mut b) = resample_pair(&a, &b, true);
+                let shift = best_rotation(&a, &b);
+                b.rotate_left(shift);
+                MorphPath::Closed(a, b)
+            }
+        };
+        Some(Self { from: from.clone(), to: to.clone(), path })
+    }
+
+    /// The shape at `t` (clamped to `0..=1`). The endpoints return the
+    /// original shapes unchanged; in between, mixed circle/ellipse pairs
+    /// yield an ellipse and outline pairs a polygon.
+    pub fn sample(&self, t: f32) -> ShapeKind {
+        if t <= 0.0 {
+            return self.from.clone();
+        }
+        if t >= 1.0 {
+            return self.to.clone();
+        }
+        match &self.path {
+            MorphPath::Radii(a, b) => {
+                let (rx, ry) = lerp2(*a, *b, t);
+                match (&self.from, &self.to) {
+                    (ShapeKind::Circle(_), ShapeKind::Circle(_)) => ShapeKind::Circle(Circle::new(rx)),
+                    _ => ShapeKind::Ellipse(Ellipse::new(rx, ry)),
+                }
+            }
+            MorphPath::Size(a, b) => {
+                let (w, h) = lerp2(*a, *b, t);
+                ShapeKind::Rectangle(Rectangle::new(w, h))
+            }
+            MorphPath::Line(a, b) => ShapeKind::Line(Line::new(lerp2(a.start, b.start, t), lerp2(a.end, b.end, t))),
+            MorphPath::Triangle(a, b) => {
+                let [a0, a1, a2] = a.vertices;
+                let [b0, b1, b2] = b.vertices;
+                ShapeKind::Triangle(Triangle::new([lerp2(a0, b0, t), lerp2(a1, b1, t), lerp2(a2, b2, t)]))
+            }
+            MorphPath::Open(a, b) => ShapeKind::Polyline(Polyline::new(lerp_points(a, b, t))),
+            MorphPath::Closed(a, b) => ShapeKind::Polygon(Polygon::new(lerp_points(a, b, t))),
+        }
+    }
+}
+
+fn radii(shape: &ShapeKind) -> Option<(f32, f32)> {
+    match shape {
+        ShapeKind::Circle(c) => Some((c.radius, c.radius)),
+        ShapeKind::Ellipse(e) => Some((e.radius_x, e.radius_y)),
+        _ => None,
+    }
+}
+
+/// The closed outline of a filled shape, without a repeated first point.
+fn closed_outline(shape: &ShapeKind) -> Option<Outline> {
+    let points = match shape {
+        ShapeKind::Polygon(p) => p.points.clone(),
+        ShapeKind::Triangle(t) => t.vertices.to_vec(),
+        ShapeKind::Rectangle(r) => vec![(0.0, 0.0), (r.width, 0.0), (r.width, r.height), (0.0, r.height)],
+        ShapeKind::Circle(_) | ShapeKind::Ellipse(_) => {
+            let (rx, ry) = radii(shape)?;
+            (0..CURVE_SEGMENTS)
+                .map(|i| {
+                    let angle = std::f32::consts::TAU * i as f32 / CURVE_SEGMENTS as f32;
+                    (rx * angle.cos(), ry * angle.sin())
+                })
+                .collect()
+        }
+        _ => return None,
+    };
+    (points.len() >= 3).then_some(points)
+}
+
+fn signed_area(points: &[(f32, f32)]) -> f32 {
+    Polygon::new(points.to_vec()).signed_area()
+}
+
+/// Fraction of the total length at which each vertex sits. For a closed
+/// outline the closing edge counts towards the length.
+fn vertex_params(points: &[(f32, f32)], closed: bool) -> Vec<f32> {
+    let edges = if closed { points.len() } else { points.len() - 1 };
+    let mut lengths = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    lengths.push(0.0);
+    for i in 0..edges {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        total += ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        lengths.push(total);
+    }
+    lengths.truncate(points.len());
+    if total <= f32::EPSILON {
+        // Degenerate: spread vertices evenly.
+        let n = edges.max(1) as f32;
+        return (0..points.len()).map(|i| i as f32 / n).collect();
+    }
+    lengths.iter().map(|l| l / total).collect()
+}
+
+/// The point at fraction `u` along the outline through `points` whose
+/// vertices sit at `params`.
+fn point_at(points: &[(f32, f32)], params: &[f32], u: f32, closed: bool) -> (f32, f32) {
+    let n = points.len();
+    let i = params.partition_point(|&p| p <= u).saturating_sub(1);
+    let (start, end) = if i + 1 < n {
+        (params[i], params[i + 1])
+    } else if closed {
+        (params[i], 1.0)
+    } else {
+        return points[n - 1];
+    };
+    let next = points[(i + 1) % n];
+    let span = end - start;
+    let f = if span > f32::EPSILON { (u - start) / span } else { 0.0 };
+    lerp2(points[i], next, f.clamp(0.0, 1.0))
+}
+
+/// Resample both outlines at the union of their vertex parameters so they
+/// share a vertex count and keep every original corner.
+fn resample_pair(a: &[(f32, f32)], b: &[(f32, f32)], closed: bool) -> (Outline, Outline) {
+    let pa = vertex_params(a, closed);
+    let pb = vertex_params(b, closed);
+    let mut params: Vec<f32> = pa.iter().chain(&pb).copied().collect();
+    if !closed {
+        params.push(1.0);
+    }
+    params.sort_by(f32::total_cmp);
+    params.dedup_by(|x, y| (*x - *y).abs() < 1e-5);
+    let sample = |points: &[(f32, f32)], own: &[f32]| params.iter().map(|&u| point_at(points, own, u, closed)).collect();
+    (sample(a, &pa), sample(b, &pb))
+}
+
+/// The left rotation of `b` that minimises the summed squared distance to
+/// the matching points of `a`.
+fn best_rotation(a: &[(f32, f32)], b: &[(f32, f32)]) -> usize {
+    let n = a.len();
+    (0..n)
+        .map(|shift| {
+            let cost: f32 = (0..n)
+                .map(|i| {
+                    let (p, q) = (a[i], b[(i + shift) % n]);
+                    (p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)
+                })
+                .sum();
+            (shift, cost)
+        })
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .map_or(0, |(shift, _)| shift)
+}
+
+fn lerp2(a: (f32, f32), b: (f32, f32), t: f32) -> (f32, f32) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+fn lerp_points(a: &[(f32, f32)], b: &[(f32, f32)], t: f32) -> Vec<(f32, f32)> {
+    a.iter().zip(b).map(|(&p, &q)| lerp2(p, q, t)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon_points(shape: ShapeKind) -> Vec<(f32, f32)> {
+        match shape {
+            ShapeKind::Polygon(p) => p.points,
+            _ => panic!("expected a polygon"),
+        }
+    }
+
+    #[test]
+    fn circle_to_ellipse_interpolates_radii() {
+        let morph = ShapeMorph::new(&ShapeKind::Circle(Circle::new(10.0)), &ShapeKind::Ellipse(Ellipse::new(30.0, 20.0))).unwrap();
+        match morph.sample(0.5) {
+            ShapeKind::Ellipse(e) => assert_eq!((e.radius_x, e.radius_y), (20.0, 15.0)),
+            _ => panic!("expected an ellipse"),
+        }
+        assert!(matches!(morph.sample(0.0), ShapeKind::Circle(c) if c.radius == 10.0));
+        assert!(matches!(morph.sample(2.0), ShapeKind::Ellipse(e) if e.radius_x == 30.0));
+    }
+
+    #[test]
+    fn polygons_resample_keeping_corners() {
+        let square = ShapeKind::Polygon(Polygon::new(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]));
+        let triangle = ShapeKind::Polygon(Polygon::new(vec![(0.0, 0.0), (10.0, 0.0), (5.0, 10.0)]));
+        let morph = ShapeMorph::new(&square, &triangle).unwrap();
+        let MorphPath::Closed(a, b) = &morph.path else { panic!("expected closed outlines") };
+        assert_eq!(a.len(), b.len());
+        for corner in [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)] {
+            assert!(a.contains(&corner));
+        }
+        for corner in [(0.0, 0.0), (10.0, 0.0), (5.0, 10.0)] {
+            assert!(b.iter().any(|p| (p.0 - corner.0).abs() < 1e-4 && (p.1 - corner.1).abs() < 1e-4));
+        }
+        let halfway = polygon_points(morph.sample(0.5));
+        assert_eq!(halfway.len(), a.len());
+        assert!(halfway.contains(&(0.0, 0.0)));
+    }
+
+    #[test]
+    fn opposite_winding_is_matched() {
+        let ccw = ShapeKind::Polygon(Polygon::new(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]));
+        let cw = ShapeKind::Polygon(Polygon::new(vec![(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]));
+        let morph = ShapeMorph::new(&ccw, &cw).unwrap();
+        // Same square either way round: nothing should move mid-morph.
+        let MorphPath::Closed(a, b) = &morph.path else { panic!("expected closed outlines") };
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn unsupported_pairs_are_rejected() {
+        let circle = ShapeKind::Circle(Circle::new(5.0));
+        let line = ShapeKind::Line(Line::new((0.0, 0.0), (1.0, 1.0)));
+        let rect = ShapeKind::Rectangle(Rectangle::new(2.0, 3.0));
+        assert!(ShapeMorph::new(&circle, &line).is_none());
+        // Rectangle and circle only morph through a polygon.
+        assert!(ShapeMorph::new(&rect, &circle).is_none());
+        let star = ShapeKind::Polygon(Polygon::new(vec![(0.0, -5.0), (2.0, 0.0), (0.0, 5.0), (-2.0, 0.0)]));
+        assert!(ShapeMorph::new(&circle, &star).is_some());
+    }
+}
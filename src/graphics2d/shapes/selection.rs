@@ -0,0 +1,168 @@
+use crate::core::{Bounds, Color, Renderable, Renderer};
+use crate::graphics2d::shapes::{Rectangle, ShapeKind, ShapeRenderable, ShapeStyle};
+
+/// Rubber-band (marquee) selection: a rectangle dragged out with the mouse
+/// and drawn as a translucent overlay.
+///
+/// `App::enable_rubber_band_selection` wires it to the mouse and keeps the
+/// selected shape indices. Used standalone, feed it press, move and release
+/// positions and test the finished rectangle against shape bounds with
+/// [`shapes_in`](Self::shapes_in), or against instances stored in a
+/// [`SpatialIndex`](crate::core::SpatialIndex) via `query_rect`.
+///
+/// ```ignore
+/// band.begin(x, y, shift_held);
+/// band.update(x, y);
+/// if let Some((rect, additive)) = band.finish() {
+///     let hits = index.query_rect(rect);
+/// }
+/// ```
+pub struct RubberBand {
+    start: Option<(f32, f32)>,
+    end: (f32, f32),
+    additive: bool,
+    fill: Color,
+    stroke: Color,
+    overlay: Option<ShapeRenderable>,
+}
+
+impl Default for RubberBand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RubberBand {
+    pub fn new() -> Self {
+        Self {
+            start: None,
+            end: (0.0, 0.0),
+            additive: false,
+            fill: Color::DODGER_BLUE.with_alpha(0.15),
+            stroke: Color::DODGER_BLUE,
+            overlay: None,
+        }
+    }
+
+    /// Overlay colors (default translucent and solid dodger blue).
+    pub fn set_colors(&mut self, fill: Color, stroke: Color) -> &mut Self {
+        self.fill = fill;
+        self.stroke = stroke;
+        self.overlay = None;
+        self
+    }
+
+    /// Start a band at `(x, y)`. `additive` (typically Shift held) asks for
+    /// the result to be added to the existing selection.
+    pub fn begin(&mut self, x: f32, y: f32, additive: bool) {
+        self.start = Some((x, y));
+        self.end = (x, y);
+        self.additive = additive;
+    }
+
+    /// Move the free corner to `(x, y)`.
+    pub fn update(&mut self, x: f32, y: f32) {
+        if self.start.is_some() {
+            self.end = (x, y);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// The current band as `(min_x, min_y, max_x, max_y)`.
+    pub fn rect(&self) -> Option<Bounds> {
+        self.start.map(|start| band_rect(start, self.end))
+    }
+
+    /// End the band, returning its rectangle and whether it is additive.
+    pub fn finish(&mut self) -> Option<(Bounds, bool)> {
+        let rect = self.rect()?;
+        self.start = None;
+        Some((rect, self.additive))
+    }
+
+    /// Drop the band without producing a selection.
+    pub fn cancel(&mut self) {
+        self.start = None;
+    }
+
+    /// Indices of the visible shapes whose [bounds](ShapeRenderable::bounds)
+    /// intersect `rect`.
+    pub fn shapes_in<'s>(rect: Bounds, shapes: impl IntoIterator<Item = &'s ShapeRenderable>) -> Vec<usize> {
+        shapes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, shape)| shape.is_visible() && intersects(shape.bounds(), rect))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Draw the band overlay while a drag is active.
+    pub fn render(&mut self, renderer: &Renderer) {
+        let Some((x0, y0, x1, y1)) = self.rect() else {
+            return;
+        };
+        let (w, h) = ((x1 - x0).max(1.0), (y1 - y0).max(1.0));
+        let overlay = self.overlay.get_or_insert_with(|| {
+            ShapeRenderable::from_shape(
+                ShapeKind::Rectangle(Rectangle::new(w, h)),
+                ShapeStyle::fill_and_stroke(self.fill, self.stroke, 1.0),
+            )
+        });
+        if let ShapeKind::Rectangle(r) = overlay.shape() {
+            if (r.width, r.height) != (w, h) {
+                overlay.set_size(w, h);
+            }
+        }
+        overlay.set_position(x0, y0);
+        overlay.render(renderer);
+    }
+}
+
+/// Combine a band result with the current selection: replace it, or add
+/// the new hits when `additive`. The result is sorted and deduplicated.
+pub(crate) fn merge_selection(current: &mut Vec<usize>, hits: Vec<usize>, additive: bool) {
+    if !additive {
+        current.clear();
+    }
+    current.extend(hits);
+    current.sort_unstable();
+    current.dedup();
+}
+
+fn band_rect(a: (f32, f32), b: (f32, f32)) -> Bounds {
+    (a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1))
+}
+
+fn intersects(a: Bounds, b: Bounds) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn band_normalizes_drag_direction() {
+        let mut band = RubberBand::new();
+        band.begin(50.0, 40.0, false);
+        band.update(10.0, 90.0);
+        assert_eq!(band.rect(), Some((10.0, 40.0, 50.0, 90.0)));
+        assert_eq!(band.finish(), Some(((10.0, 40.0, 50.0, 90.0), false)));
+        assert!(!band.is_active());
+        assert_eq!(band.finish(), None);
+    }
+
+    #[test]
+    fn merge_replaces_or_adds() {
+        let mut selection = vec![1, 4];
+        merge_selection(&mut selection, vec![4, 2], true);
+        assert_eq!(selection, vec![1, 2, 4]);
+        merge_selection(&mut selection, vec![7], false);
+        assert_eq!(selection, vec![7]);
+        merge_selection(&mut selection, Vec::new(), false);
+        assert!(selection.is_empty());
+    }
+}
@@ -1,19 +1,21 @@
 use crate::core::engine::opengl::{
-    GL_POINTS, GL_TRIANGLE_FAN, GL_TRIANGLE_STRIP, GL_TRIANGLES, GLfloat, Vec2,
+    GL_POINTS, GL_TRIANGLE_FAN, GL_TRIANGLE_STRIP, GL_TRIANGLES, GLenum, GLfloat, Vec2,
 };
 use crate::core::{
-    Attribute, Color, FontAtlas, Geometry, Mesh, Renderable, Renderer, Shader,
-    generate_texture_from_image, load_image,
-};
-use crate::graphics2d::shapes::{
-    Arc as ArcShape, Circle, Ellipse, Image, Line, MultiPoint, Polygon, Polyline, Rectangle,
-    RoundedRectangle, ShapeKind, Text, Triangle,
+    Attribute, Color, FontAtlas, Geometry, InstanceTransform, Mesh, PickId, Renderable, Renderer, Shader, TextureOptions,
+    WilhelmError, check_font, generate_texture_from_image_with_options, try_load_image,
 };
+use crate::graphics2d::shapes::hit;
+use crate::graphics2d::shapes::bezier::FLATTEN_TOLERANCE;
+use crate::graphics2d::shapes::{Arc as ArcShape, Bezier, Image, ShapeKind, ShapeMorph, Text, TextLayout};
+use crate::core::engine::leaks::{self, Resource};
 use crate::core::math::Mat4;
+use crate::core::pivot_translation;
 use std::cell::{OnceCell, RefCell};
 use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::rc::Rc;
+use std::thread::LocalKey;
 
 const MIN_STROKE_WIDTH: f32 = 1.0;
 
@@ -36,9 +38,11 @@ const MIN_STROKE_WIDTH: f32 = 1.0;
 /// - `Point`, `Circle`, `Ellipse`, `Image`, `Arc`: center
 /// - `Rectangle`, `RoundedRectangle`: north-west corner (top-left)
 /// - `Line`, `Polyline`, `Polygon`, `MultiPoint`: first vertex
+/// - `Bezier`: start point
 /// - `Triangle`: centroid
 /// - `Text`: north-west corner of the text cell
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Anchor {
     Default,
     Center,
@@ -106,12 +110,139 @@ fn resolve_anchor(
     }
 }
 
-#[derive(Clone, Debug)]
+/// Vertices of one mesh, built on the CPU so they can become a new
+/// [`Geometry`] or be written into an existing one.
+struct VertexData {
+    mode: GLenum,
+    vertices: Vec<GLfloat>,
+    /// 2 for positions, 3 with a dash distance, 4 with texture coordinates.
+    values_per_vertex: i32,
+}
+
+impl VertexData {
+    fn new(mode: GLenum, vertices: Vec<GLfloat>, values_per_vertex: i32) -> Self {
+        Self { mode, vertices, values_per_vertex }
+    }
+
+    fn into_geometry(self) -> Geometry {
+        let stride = self.values_per_vertex as usize;
+        let mut geometry = Geometry::new(self.mode);
+        geometry.add_buffer(&self.vertices, self.values_per_vertex);
+        geometry.add_vertex_attribute(Attribute::new(0, 2, stride, 0)); // vec2 position
+        match self.values_per_vertex {
+            3 => geometry.add_vertex_attribute(Attribute::new(3, 1, stride, 2)), // float distance
+            4 => geometry.add_vertex_attribute(Attribute::new(1, 2, stride, 2)), // vec2 uv
+            _ => {}
+        }
+        geometry
+    }
+
+    /// Whether these vertices can replace `geometry`'s: same drawing mode
+    /// and vertex layout.
+    fn fits(&self, geometry: &Geometry) -> bool {
+        geometry.drawing_mode() == self.mode && geometry.values_per_vertex() == self.values_per_vertex
+    }
+
+    fn write_into(self, geometry: &mut Geometry) {
+        geometry.update_buffer(&self.vertices, self.values_per_vertex);
+    }
+}
+
+/// Vertices of a shape's meshes and the resolved anchor they were shifted by.
+struct ShapeVertices {
+    /// The fill, or the stroke of shapes drawn only as a stroke.
+    main: VertexData,
+    /// The outline drawn over a filled shape.
+    stroke: Option<VertexData>,
+    origin: (f32, f32),
+}
+
+/// Glyph quads of a laid out text, with positions and texture coordinates
+/// apart so the coordinates can be replaced on their own.
+struct GlyphQuads {
+    positions: Vec<GLfloat>,
+    uvs: Vec<GLfloat>,
+    /// Bbox over the quads before the anchor shift.
+    bounds: ((f32, f32), (f32, f32)),
+    origin: (f32, f32),
+}
+
+/// The atlas a text shape's glyphs come from. The text's characters stay
+/// pinned there while it lives, so a full atlas never evicts them.
+struct TextGlyphs {
+    atlas: Rc<RefCell<FontAtlas>>,
+    /// The pinned characters.
+    content: String,
+    /// Atlas generation the texture coordinates were read at.
+    generation: u64,
+}
+
+impl TextGlyphs {
+    fn pin(atlas: Rc<RefCell<FontAtlas>>, content: &str) -> Self {
+        atlas.borrow_mut().pin_glyphs(content);
+        Self { atlas, content: content.to_string(), generation: 0 }
+    }
+
+    /// Move the pins over to `content`.
+    fn repin(&mut self, content: &str) {
+        if self.content != content {
+            let mut atlas = self.atlas.borrow_mut();
+            atlas.pin_glyphs(content);
+            atlas.unpin_glyphs(&self.content);
+            self.content = content.to_string();
+        }
+    }
+
+    /// Whether the atlas moved glyphs since the last [`layout`](Self::layout).
+    fn is_stale(&self) -> bool {
+        self.atlas.borrow().generation() != self.generation
+    }
+
+    /// Lay `text` out around `anchor`. Every glyph is placed before any
+    /// texture coordinate is read, so placing a later glyph cannot move
+    /// an earlier one's.
+    fn layout(&mut self, text: &Text, anchor: Anchor) -> GlyphQuads {
+        let mut atlas = self.atlas.borrow_mut();
+        for ch in text.content.chars() {
+            atlas.get_glyph(ch);
+        }
+        self.generation = atlas.generation();
+        let mut quads = ShapeRenderable::text_raw_vertices(&text.content, &text.layout, &mut atlas);
+
+        // Default anchor for Text is the top-left of the text cell (raw origin).
+        let (bbox_min, bbox_max) = quads.bounds;
+        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, (0.0, 0.0));
+        for position in quads.positions.chunks_exact_mut(2) {
+            position[0] -= ax;
+            position[1] -= ay;
+        }
+        quads.origin = (ax, ay);
+        quads
+    }
+}
+
+impl Drop for TextGlyphs {
+    fn drop(&mut self) {
+        if let Ok(mut atlas) = self.atlas.try_borrow_mut() {
+            atlas.unpin_glyphs(&self.content);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct ShapeStyle {
     pub fill: Option<Color>,
     pub stroke_color: Option<Color>,
     pub stroke_width: Option<f32>,
     pub dash_pattern: Option<(f32, f32)>,
+    /// Shift of the dash pattern along the stroke, in pixels.
+    pub dash_offset: f32,
+    /// Pixels per second the dashes travel along the stroke direction;
+    /// negative values march backwards. `0.0` keeps them still.
+    pub dash_speed: f32,
+    /// Whole-shape opacity (0-1), multiplied into fill and stroke alpha.
+    pub opacity: f32,
 }
 
 impl Default for ShapeStyle {
@@ -121,6 +252,9 @@ impl Default for ShapeStyle {
             stroke_color: None,
             stroke_width: None,
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
+            opacity: 1.0,
         }
     }
 }
@@ -132,6 +266,9 @@ impl ShapeStyle {
             stroke_color: None,
             stroke_width: None,
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
+            opacity: 1.0,
         }
     }
 
@@ -141,6 +278,9 @@ impl ShapeStyle {
             stroke_color: Some(color),
             stroke_width: Some(width),
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
+            opacity: 1.0,
         }
     }
 
@@ -150,6 +290,9 @@ impl ShapeStyle {
             stroke_color: Some(stroke),
             stroke_width: Some(width),
             dash_pattern: None,
+            dash_offset: 0.0,
+            dash_speed: 0.0,
+            opacity: 1.0,
         }
     }
 
@@ -159,6 +302,9 @@ impl ShapeStyle {
             stroke_color: Some(color),
             stroke_width: Some(width),
             dash_pattern: Some((dash, gap)),
+            dash_offset: 0.0,
+            dash_speed: 0.0,
+            opacity: 1.0,
         }
     }
 
@@ -166,91 +312,108 @@ impl ShapeStyle {
         self.dash_pattern = Some((dash, gap));
         self
     }
+
+    /// Start the dash pattern `offset` pixels along the stroke.
+    pub fn with_dash_offset(mut self, offset: f32) -> Self {
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Animate the dashes along the stroke at `speed` pixels per second
+    /// ("marching ants"). Positive speeds follow the stroke's point order.
+    pub fn with_dash_speed(mut self, speed: f32) -> Self {
+        self.dash_speed = speed;
+        self
+    }
+
+    /// Whole-shape opacity, clamped to 0-1 like
+    /// [`ShapeRenderable::set_opacity`].
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Wrap a shader cached for the life of the thread, which is not a leak.
+fn keep_cached(shader: Shader) -> Rc<Shader> {
+    leaks::keep(Resource::Program, shader.program());
+    Rc::new(shader)
+}
+
+/// The shader in `cell`, compiled from `vert_src` and `frag_src` on first
+/// use. A failed compile leaves the cell empty, so the next call retries.
+fn try_cached_shader(
+    cell: &'static LocalKey<OnceCell<Rc<Shader>>>,
+    vert_src: &str,
+    frag_src: &str,
+) -> Result<Rc<Shader>, WilhelmError> {
+    cell.with(|cell| {
+        if let Some(shader) = cell.get() {
+            return Ok(shader.clone());
+        }
+        let shader = Shader::compile(vert_src, frag_src, None).map_err(WilhelmError::Shader)?;
+        Ok(cell.get_or_init(|| keep_cached(shader)).clone())
+    })
 }
 
 thread_local! {
     static DEFAULT_SHADER: OnceCell<Rc<Shader>> = OnceCell::new();
 }
 
+fn try_default_shader() -> Result<Rc<Shader>, WilhelmError> {
+    try_cached_shader(&DEFAULT_SHADER, include_str!("../shaders/shape.vert"), include_str!("../shaders/shape.frag"))
+}
+
 fn default_shader() -> Rc<Shader> {
-    DEFAULT_SHADER.with(|cell| {
-        cell.get_or_init(|| {
-            let vert_src = include_str!("../shaders/shape.vert");
-            let frag_src = include_str!("../shaders/shape.frag");
-            Rc::new(
-                Shader::compile(vert_src, frag_src, None)
-                    .expect("Failed to compile default shader"),
-            )
-        })
-        .clone()
-    })
+    try_default_shader().expect("Failed to compile default shader")
 }
 
 thread_local! {
     static DASHED_SHADER: OnceCell<Rc<Shader>> = OnceCell::new();
 }
 
+fn try_dashed_shader() -> Result<Rc<Shader>, WilhelmError> {
+    try_cached_shader(&DASHED_SHADER, include_str!("../shaders/dashed.vert"), include_str!("../shaders/dashed.frag"))
+}
+
 fn dashed_shader() -> Rc<Shader> {
-    DASHED_SHADER.with(|cell| {
-        cell.get_or_init(|| {
-            let vert_src = include_str!("../shaders/dashed.vert");
-            let frag_src = include_str!("../shaders/dashed.frag");
-            Rc::new(
-                Shader::compile(vert_src, frag_src, None)
-                    .expect("Failed to compile dashed shader"),
-            )
-        })
-        .clone()
-    })
+    try_dashed_shader().expect("Failed to compile dashed shader")
 }
 
 thread_local! {
     static POINT_SHADER: OnceCell<Rc<Shader>> = OnceCell::new();
 }
 
+fn try_point_shader() -> Result<Rc<Shader>, WilhelmError> {
+    try_cached_shader(&POINT_SHADER, include_str!("../shaders/shape.vert"), include_str!("../shaders/point.frag"))
+}
+
 fn point_shader() -> Rc<Shader> {
-    POINT_SHADER.with(|cell| {
-        cell.get_or_init(|| {
-            let vert_src = include_str!("../shaders/shape.vert");
-            let frag_src = include_str!("../shaders/point.frag");
-            Rc::new(
-                Shader::compile(vert_src, frag_src, None).expect("Failed to compile point shader"),
-            )
-        })
-        .clone()
-    })
+    try_point_shader().expect("Failed to compile point shader")
 }
 
 thread_local! {
     static IMAGE_SHADER: OnceCell<Rc<Shader>> = OnceCell::new();
 }
+
+fn try_image_shader() -> Result<Rc<Shader>, WilhelmError> {
+    try_cached_shader(&IMAGE_SHADER, include_str!("../shaders/image.vert"), include_str!("../shaders/image.frag"))
+}
+
 fn image_shader() -> Rc<Shader> {
-    IMAGE_SHADER.with(|cell| {
-        cell.get_or_init(|| {
-            let vert_src = include_str!("../shaders/image.vert");
-            let frag_src = include_str!("../shaders/image.frag");
-            Rc::new(
-                Shader::compile(vert_src, frag_src, None).expect("Failed to compile image shader"),
-            )
-        })
-        .clone()
-    })
+    try_image_shader().expect("Failed to compile image shader")
 }
 
 thread_local! {
     static TEXT_SHADER: OnceCell<Rc<Shader>> = OnceCell::new();
 }
+
+fn try_text_shader() -> Result<Rc<Shader>, WilhelmError> {
+    try_cached_shader(&TEXT_SHADER, include_str!("../shaders/text.vert"), include_str!("../shaders/text.frag"))
+}
+
 fn text_shader() -> Rc<Shader> {
-    TEXT_SHADER.with(|cell| {
-        cell.get_or_init(|| {
-            let vert_src = include_str!("../shaders/text.vert");
-            let frag_src = include_str!("../shaders/text.frag");
-            Rc::new(
-                Shader::compile(vert_src, frag_src, None).expect("Failed to compile text shader"),
-            )
-        })
-        .clone()
-    })
+    try_text_shader().expect("Failed to compile text shader")
 }
 
 /// Font cache key: (font_path, font_size)
@@ -260,24 +423,71 @@ thread_local! {
     /// Global font cache - shares FontAtlas instances across text renderables.
     /// Properly dropped when thread exits, no memory leaks.
     static FONT_CACHE: RefCell<HashMap<FontCacheKey, Rc<RefCell<FontAtlas>>>> = RefCell::new(HashMap::new());
+    /// Fallback fonts applied to every cached atlas.
+    static FONT_FALLBACKS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Set the fonts that text takes characters missing from its own font
+/// from, in order, e.g. a CJK font followed by a symbol font. Applies to
+/// all text created afterwards, for every font; existing text picks up the
+/// new glyphs on its next draw. Pass an empty slice to remove the chain.
+///
+/// Color emoji fonts are not supported; use a monochrome symbol font.
+pub fn set_font_fallbacks(font_paths: &[&str]) -> Result<(), WilhelmError> {
+    for path in font_paths {
+        check_font(path).map_err(|message| WilhelmError::Font { path: path.to_string(), message })?;
+    }
+    FONT_FALLBACKS.with(|fallbacks| *fallbacks.borrow_mut() = font_paths.iter().map(|path| path.to_string()).collect());
+    FONT_CACHE.with(|cache| {
+        cache.borrow().iter().try_for_each(|((font_path, _), atlas)| apply_font_fallbacks(font_path, &mut atlas.borrow_mut()))
+    })
+}
+
+fn apply_font_fallbacks(font_path: &str, atlas: &mut FontAtlas) -> Result<(), WilhelmError> {
+    FONT_FALLBACKS.with(|fallbacks| {
+        let fallbacks = fallbacks.borrow();
+        let paths: Vec<&str> = fallbacks.iter().map(String::as_str).collect();
+        atlas.set_fallbacks(&paths)
+    })
+    .map_err(|message| WilhelmError::Font { path: font_path.to_string(), message })
 }
 
 /// Get or create a FontAtlas from the cache
-fn get_or_create_font_atlas(font_path: &str, font_size: u32) -> Rc<RefCell<FontAtlas>> {
+fn try_get_or_create_font_atlas(font_path: &str, font_size: u32) -> Result<Rc<RefCell<FontAtlas>>, WilhelmError> {
     FONT_CACHE.with(|cache| {
         let mut cache = cache.borrow_mut();
         let key = (font_path.to_string(), font_size);
 
         if let Some(atlas) = cache.get(&key) {
-            return atlas.clone();
+            return Ok(atlas.clone());
         }
 
         // Create new FontAtlas and cache it
-        let atlas = FontAtlas::new(font_path, font_size, 512)
-            .expect("Failed to create font atlas");
+        let mut atlas = FontAtlas::new(font_path, font_size, 512)
+            .map_err(|message| WilhelmError::Font { path: font_path.to_string(), message })?;
+        apply_font_fallbacks(font_path, &mut atlas)?;
+        leaks::keep(Resource::Texture, atlas.texture_id());
         let atlas_rc = Rc::new(RefCell::new(atlas));
         cache.insert(key, atlas_rc.clone());
-        atlas_rc
+        Ok(atlas_rc)
+    })
+}
+
+fn get_or_create_font_atlas(font_path: &str, font_size: u32) -> Rc<RefCell<FontAtlas>> {
+    try_get_or_create_font_atlas(font_path, font_size).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Put an atlas built elsewhere (e.g. by an `AssetLoader`) in the cache,
+/// so text using `font_path` at `font_size` picks it up without loading.
+pub(crate) fn cache_font_atlas(font_path: &str, font_size: u32, mut atlas: FontAtlas) -> Rc<RefCell<FontAtlas>> {
+    leaks::keep(Resource::Texture, atlas.texture_id());
+    if let Err(e) = apply_font_fallbacks(font_path, &mut atlas) {
+        log::warn!("{e}");
+    }
+    FONT_CACHE.with(|cache| {
+        let atlas = Rc::new(RefCell::new(atlas));
+        cache.borrow_mut().insert((font_path.to_string(), font_size), atlas.clone());
+        atlas
     })
 }
 
@@ -293,23 +503,82 @@ pub fn clear_font_cache() {
 fn ortho_2d(width: f32, height: f32) -> Mat4 {
     Mat4::orthographic_rh_gl(0.0, width, height, 0.0, -1.0, 1.0)
 }
+
+/// A fill mesh from raw `GL_TRIANGLES` vertices (`x, y` pairs in pixels),
+/// for layers that generate triangle soups no [`ShapeKind`] describes,
+/// such as contour bands. Draw it with [`render_triangle_mesh`].
+pub(crate) fn triangle_mesh(vertices: &[GLfloat], color: Color) -> Mesh {
+    let mut geometry = Geometry::new(GL_TRIANGLES);
+    geometry.add_buffer(vertices, 2);
+    geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
+    Mesh::with_color(default_shader(), geometry, Some(color))
+}
+
+/// Draw a [`triangle_mesh`] shifted by `offset` pixels.
+pub(crate) fn render_triangle_mesh(renderer: &Renderer, mesh: &mut Mesh, offset: (f32, f32)) {
+    let (width, height) = renderer.window_handle.size();
+    mesh.set_transform(ortho_2d(width as f32, height as f32));
+    mesh.set_screen_offset(offset.0, offset.1);
+    renderer.draw_mesh(mesh);
+}
+
+/// Axis-aligned `(min_x, min_y, max_x, max_y)` of transformed box corners.
+fn corner_extent(corners: [(f32, f32); 4]) -> (f32, f32, f32, f32) {
+    corners.iter().fold(
+        (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+    )
+}
+
 pub struct ShapeRenderable {
     x: f32,
     y: f32,
     scale: f32,
     rotation: f32,
     z_order: i32,
+    visible: bool,
+    editable: bool,
+    pick_id: Option<PickId>,
     mesh: Mesh,
     stroke_mesh: Option<Mesh>,
+    /// True when `mesh` itself is the outline (lines, arcs, outline-only
+    /// shapes), so restyling routes the stroke color to it.
+    stroke_only: bool,
     shape: ShapeKind,
+    style: ShapeStyle,
+    anchor: Anchor,
+    /// Point rotation and scaling keep fixed; `Default` is the anchor.
+    pivot: Anchor,
+    /// Resolved anchor point in shape coordinates (what the geometry was
+    /// shifted by), used to map screen points back for hit testing.
+    origin: (f32, f32),
+    /// Local bbox measured at build time for shapes whose extent can't be
+    /// derived from `shape` alone (text).
+    measured_bounds: Option<((f32, f32), (f32, f32))>,
+    /// Atlas a text shape's glyphs are pinned in, to re-read their texture
+    /// coordinates once they move.
+    text_glyphs: Option<TextGlyphs>,
+    /// CPU-side copies of the last uploaded instance data, which otherwise
+    /// only lives in GPU buffers.
+    instance_positions: Vec<Vec2>,
+    instance_colors: Vec<Color>,
+    instance_transforms: Vec<InstanceTransform>,
 }
 impl Renderable for ShapeRenderable {
     fn render(&mut self, renderer: &Renderer) {
+        if !self.visible {
+            return;
+        }
+        if self.text_glyphs.as_ref().is_some_and(TextGlyphs::is_stale) {
+            self.refresh_text_uvs();
+        }
         let (window_width, window_height) = renderer.window_handle.size();
         let transform = ortho_2d(window_width as f32, window_height as f32);
+        let (pivot_x, pivot_y) = self.pivot_offset();
         self.mesh.set_transform(transform);
         self.mesh.set_scale(self.scale);
         self.mesh.set_rotation(self.rotation);
+        self.mesh.set_pivot(pivot_x, pivot_y);
 
         if self.mesh.geometry.instance_count() > 0 {
             // instanced: u_offset = (0,0), positions come from attrib 1
@@ -325,6 +594,7 @@ impl Renderable for ShapeRenderable {
             stroke.set_transform(transform);
             stroke.set_scale(self.scale);
             stroke.set_rotation(self.rotation);
+            stroke.set_pivot(pivot_x, pivot_y);
 
             if stroke.geometry.instance_count() > 0 {
                 renderer.draw_mesh_instanced(stroke);
@@ -338,11 +608,11 @@ impl Renderable for ShapeRenderable {
 
 impl ShapeRenderable {
     fn new(mesh: Mesh, shape: ShapeKind) -> Self {
-        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, z_order: 0, mesh, stroke_mesh: None, shape }
+        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, z_order: 0, visible: true, editable: false, pick_id: None, mesh, stroke_mesh: None, stroke_only: false, shape, style: ShapeStyle::default(), anchor: Anchor::Default, pivot: Anchor::Default, origin: (0.0, 0.0), measured_bounds: None, text_glyphs: None, instance_positions: Vec::new(), instance_colors: Vec::new(), instance_transforms: Vec::new() }
     }
 
     fn new_with_stroke(mesh: Mesh, stroke_mesh: Mesh, shape: ShapeKind) -> Self {
-        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, z_order: 0, mesh, stroke_mesh: Some(stroke_mesh), shape }
+        Self { x: 0.0, y: 0.0, scale: 1.0, rotation: 0.0, z_order: 0, visible: true, editable: false, pick_id: None, mesh, stroke_mesh: Some(stroke_mesh), stroke_only: false, shape, style: ShapeStyle::default(), anchor: Anchor::Default, pivot: Anchor::Default, origin: (0.0, 0.0), measured_bounds: None, text_glyphs: None, instance_positions: Vec::new(), instance_colors: Vec::new(), instance_transforms: Vec::new() }
     }
 
     pub fn set_position(&mut self, x: f32, y: f32) -> &mut Self {
@@ -381,6 +651,8 @@ impl ShapeRenderable {
         self.rotation
     }
 
+    /// Set the stacking order used by `App`: higher values draw on top, and
+    /// shapes with equal z-order draw in insertion order.
     pub fn set_z_order(&mut self, z_order: i32) -> &mut Self {
         self.z_order = z_order;
         self
@@ -390,724 +662,1083 @@ impl ShapeRenderable {
         self.z_order
     }
 
+    /// Let `App`'s editing mode select this shape and drag it or its
+    /// handles (see [`App::enable_editing`](crate::core::App::enable_editing)).
+    pub fn set_editable(&mut self, editable: bool) -> &mut Self {
+        self.editable = editable;
+        self
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    /// Id this shape writes in `App`'s picking pass (see
+    /// [`App::enable_picking`](crate::core::App::enable_picking)). Shapes
+    /// without an id are not pickable. Instance `i` of an instanced shape
+    /// reports `id.offset(i)`.
+    pub fn set_pick_id(&mut self, id: PickId) -> &mut Self {
+        self.pick_id = Some(id);
+        self
+    }
+
+    pub fn pick_id(&self) -> Option<PickId> {
+        self.pick_id
+    }
+
+    /// Show or hide the shape. Hidden shapes keep their GPU buffers and are
+    /// skipped by [`render`](Renderable::render) and `App`'s draw loop.
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
     pub fn set_fill_color(&mut self, color: Color) -> &mut Self {
         self.mesh.color = Some(color);
         self
     }
 
+    /// Change the stroke color in place. A filled shape built without a
+    /// stroke gains an outline drawn over its fill, `stroke_width` wide
+    /// from its style (1 px if unset); that first call rebuilds the
+    /// geometry, like [`set_anchor`](Self::set_anchor).
     pub fn set_stroke_color(&mut self, color: Color) -> &mut Self {
-        if let Some(stroke) = &mut self.stroke_mesh {
+        if self.stroke_only {
+            self.mesh.color = Some(color);
+        } else if let Some(stroke) = &mut self.stroke_mesh {
             stroke.color = Some(color);
+        } else if self.is_fillable() {
+            self.style.fill = self.style.fill.or(self.mesh.color);
+            self.style.stroke_color = Some(color);
+            self.rebuild();
         }
         self
     }
 
-    pub fn fill_color(&self) -> Option<Color> {
-        self.mesh.color
+    /// Change the stroke width, rebuilding the stroke geometry. On a filled
+    /// shape without a stroke the width is kept for when
+    /// [`set_stroke_color`](Self::set_stroke_color) adds one.
+    pub fn set_stroke_width(&mut self, width: f32) -> &mut Self {
+        self.style.stroke_width = Some(width);
+        if self.stroke_only || self.stroke_mesh.is_some() {
+            self.rebuild();
+        }
+        self
     }
 
-    pub fn stroke_color(&self) -> Option<Color> {
-        self.stroke_mesh.as_ref().and_then(|s| s.color)
-    }
-    /// Construct a `ShapeRenderable` with the shape's default anchor.
-    ///
-    /// Equivalent to `ShapeRenderable::builder(shape, style).build()`.
-    pub fn from_shape(shape: ShapeKind, style: ShapeStyle) -> Self {
-        Self::builder(shape, style).build()
+    /// Whether the shape has an area that can be filled and outlined.
+    fn is_fillable(&self) -> bool {
+        matches!(
+            self.shape,
+            ShapeKind::Triangle(_)
+                | ShapeKind::Rectangle(_)
+                | ShapeKind::RoundedRectangle(_)
+                | ShapeKind::Polygon(_)
+                | ShapeKind::Circle(_)
+                | ShapeKind::Ellipse(_)
+        )
     }
 
-    /// Start a builder that lets you override the anchor (and, later, other
-    /// per-shape parameters) before constructing the `ShapeRenderable`.
-    pub fn builder(shape: ShapeKind, style: ShapeStyle) -> ShapeRenderableBuilder {
-        ShapeRenderableBuilder {
-            shape,
-            style,
-            anchor: Anchor::Default,
-        }
+    /// Change the fill color in place. Shorthand for [`set_fill_color`](Self::set_fill_color).
+    pub fn set_fill(&mut self, color: Color) -> &mut Self {
+        self.set_fill_color(color)
     }
 
-    fn from_shape_with_anchor(shape: ShapeKind, style: ShapeStyle, anchor: Anchor) -> Self {
-        match shape {
-            ShapeKind::Point => {
-                ShapeRenderable::point(style.fill.unwrap_or(Color::white()), anchor)
-            }
-            ShapeKind::MultiPoint(mp) => {
-                ShapeRenderable::multi_points(mp, style.fill.unwrap_or(Color::white()), anchor)
-            }
-            ShapeKind::Line(line) => ShapeRenderable::line(
-                line,
-                style.stroke_color.unwrap_or_else(Color::white),
-                style.stroke_width.unwrap_or(1.0),
-                anchor,
-                style.dash_pattern,
-            ),
-            ShapeKind::Polyline(poly_line) => ShapeRenderable::polyline(
-                poly_line,
-                style.stroke_color.unwrap_or(Color::white()),
-                style.stroke_width.unwrap_or(1.0),
-                anchor,
-                style.dash_pattern,
-            ),
-            ShapeKind::Triangle(triangle) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::triangle_fill_and_stroke(
-                    triangle, fill, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::triangle_outline(
-                    triangle, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::triangle(
-                    triangle, fill.unwrap_or(Color::white()), anchor,
-                ),
-            },
-            ShapeKind::Rectangle(rect) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::rectangle_fill_and_stroke(
-                    rect,
-                    fill,
-                    stroke,
-                    style.stroke_width.unwrap_or(1.0),
-                    anchor,
-                    style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::rectangle_outline(
-                    rect,
-                    stroke,
-                    style.stroke_width.unwrap_or(1.0),
-                    anchor,
-                    style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::rectangle(
-                    rect,
-                    fill.unwrap_or(Color::white()),
-                    anchor,
-                ),
-            },
-            ShapeKind::RoundedRectangle(rr) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::rounded_rectangle_fill_and_stroke(
-                    rr, fill, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::rounded_rectangle_outline(
-                    rr, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::rounded_rectangle(
-                    rr, fill.unwrap_or(Color::white()), anchor,
-                ),
-            },
-            ShapeKind::Polygon(polygon) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::polygon_fill_and_stroke(
-                    polygon, fill, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::polygon_outline(
-                    polygon, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::polygon(
-                    polygon, fill.unwrap_or(Color::white()), anchor,
-                ),
-            },
-            ShapeKind::Circle(circle) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::circle_fill_and_stroke(
-                    circle, fill, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::circle_outline(
-                    circle, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::circle(
-                    circle, fill.unwrap_or(Color::white()), anchor,
-                ),
-            },
-            ShapeKind::Ellipse(ellipse) => match (style.fill, style.stroke_color) {
-                (Some(fill), Some(stroke)) => ShapeRenderable::ellipse_fill_and_stroke(
-                    ellipse, fill, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (None, Some(stroke)) => ShapeRenderable::ellipse_outline(
-                    ellipse, stroke, style.stroke_width.unwrap_or(1.0), anchor, style.dash_pattern,
-                ),
-                (fill, None) => ShapeRenderable::ellipse(
-                    ellipse, fill.unwrap_or(Color::white()), anchor,
-                ),
-            },
-            ShapeKind::Arc(arc) => ShapeRenderable::arc(
-                arc,
-                style.stroke_color.unwrap_or(Color::white()),
-                style.stroke_width.unwrap_or(1.0),
-                anchor,
-                style.dash_pattern,
-            ),
-            ShapeKind::Image(_) => {
-                unimplemented!("ShapeRenderable::from_shape cannot create Image without path")
+    /// Apply a new style without regenerating geometry or GPU buffers.
+    ///
+    /// Fill color, stroke color, dash lengths and motion, and opacity are updated in
+    /// place. A `None` fill or stroke hides that part (fully transparent).
+    /// Changes that need new geometry are ignored: stroke width, adding a
+    /// stroke to a shape built without one, or switching between solid and
+    /// dashed strokes. Use [`set_stroke_width`](Self::set_stroke_width) and
+    /// [`set_stroke_color`](Self::set_stroke_color) for the first two, and
+    /// rebuild the renderable for the last.
+    pub fn set_style(&mut self, style: ShapeStyle) -> &mut Self {
+        self.style = style.clone();
+        let hidden = Color::from_rgba(0.0, 0.0, 0.0, 0.0);
+        if self.stroke_only {
+            self.mesh.color = Some(style.stroke_color.unwrap_or(hidden));
+            Self::restyle_dash(&mut self.mesh, &style);
+        } else {
+            self.mesh.color = Some(style.fill.unwrap_or(hidden));
+            if let Some(stroke) = &mut self.stroke_mesh {
+                stroke.color = Some(style.stroke_color.unwrap_or(hidden));
+                Self::restyle_dash(stroke, &style);
             }
-            ShapeKind::Text(text) => ShapeRenderable::text(
-                text,
-                style.fill.unwrap_or(Color::white()),
-                anchor,
-            ),
         }
+        self.set_opacity(style.opacity)
     }
 
-    pub fn create_multiple_instances(&mut self, capacity: usize) {
-        self.mesh.geometry.enable_instancing_xy(capacity);
-        if let Some(stroke) = &mut self.stroke_mesh {
-            stroke.geometry.enable_instancing_xy(capacity);
+    /// Dash lengths and motion are uniforms, so they can change freely, but
+    /// only on a mesh that was built with the dashed shader.
+    fn restyle_dash(mesh: &mut Mesh, style: &ShapeStyle) {
+        if mesh.dash_pattern.is_none() {
+            return;
+        }
+        if let Some(pattern) = style.dash_pattern {
+            mesh.dash_pattern = Some(pattern);
         }
+        mesh.dash_offset = style.dash_offset;
+        mesh.dash_speed = style.dash_speed;
     }
 
-    pub fn set_instance_positions(&mut self, positions: &[Vec2]) -> &mut Self {
-        self.mesh.geometry.update_instance_xy(positions);
-        if let Some(stroke) = &mut self.stroke_mesh {
-            stroke.geometry.update_instance_xy(positions);
-        }
-        self
+    /// The mesh drawn with the dashed shader, if the stroke is dashed.
+    fn dashed_mesh(&mut self) -> Option<&mut Mesh> {
+        let mesh = if self.stroke_only { Some(&mut self.mesh) } else { self.stroke_mesh.as_mut() };
+        mesh.filter(|m| m.dash_pattern.is_some())
     }
 
-    pub fn set_instance_colors(&mut self, colors: &[Color]) -> &mut Self {
-        self.mesh.geometry.update_instance_colors(colors);
+    /// Shift the dash pattern `offset` pixels along the stroke. No-op for
+    /// solid strokes.
+    pub fn set_dash_offset(&mut self, offset: f32) -> &mut Self {
+        self.style.dash_offset = offset;
+        if let Some(mesh) = self.dashed_mesh() {
+            mesh.dash_offset = offset;
+        }
         self
     }
 
-    pub fn set_instance_stroke_colors(&mut self, colors: &[Color]) -> &mut Self {
-        if let Some(stroke) = &mut self.stroke_mesh {
-            stroke.geometry.update_instance_colors(colors);
+    /// Animate the dashes at `speed` pixels per second along the stroke
+    /// (`0.0` stops them). The offset is advanced on the GPU from the frame
+    /// clock, so no per-frame call is needed. No-op for solid strokes.
+    pub fn set_dash_speed(&mut self, speed: f32) -> &mut Self {
+        self.style.dash_speed = speed;
+        if let Some(mesh) = self.dashed_mesh() {
+            mesh.dash_speed = speed;
         }
         self
     }
 
-    pub fn clear_instances(&mut self) {
-        self.mesh.geometry.clear_instancing();
+    /// Fade the whole shape (fill and stroke) without touching its colors.
+    /// Clamped to `0..=1`; multiplies any per-instance color alpha as well.
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.mesh.set_opacity(opacity);
         if let Some(stroke) = &mut self.stroke_mesh {
-            stroke.geometry.clear_instancing();
+            stroke.set_opacity(opacity);
         }
+        self
     }
 
-    fn point(color: Color, _anchor: Anchor) -> Self {
-        // Point has only one vertex at (0, 0); anchor is trivially that point
-        // for every variant (bbox is degenerate). Ignore the anchor.
-        let geometry = ShapeRenderable::point_geometry();
-        let mesh = Mesh::with_color(point_shader(), geometry, Some(color));
-        ShapeRenderable::new(mesh, ShapeKind::Point)
+    pub fn opacity(&self) -> f32 {
+        self.mesh.opacity()
     }
 
-    fn multi_points(multi_point: MultiPoint, color: Color, anchor: Anchor) -> Self {
-        assert!(!multi_point.points.is_empty(), "MultiPoint requires at least one point");
-
-        let (bbox_min, bbox_max) = bbox_of_points(&multi_point.points);
-        let default = multi_point.points[0];
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let rel_points: Vec<(GLfloat, GLfloat)> = multi_point
-            .points
-            .iter()
-            .map(|(px, py)| (px - ax, py - ay))
-            .collect();
+    /// The style the shape was built with, as updated by
+    /// [`set_style`](Self::set_style). Color setters change only what is
+    /// drawn; see [`fill_color`](Self::fill_color) and
+    /// [`stroke_color`](Self::stroke_color).
+    pub fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
 
-        let geometry = ShapeRenderable::point_list_geometry(&rel_points);
-        let mesh = Mesh::with_color(point_shader(), geometry, Some(color));
+    pub fn fill_color(&self) -> Option<Color> {
+        self.mesh.color
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::MultiPoint(multi_point));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn stroke_color(&self) -> Option<Color> {
+        self.stroke_mesh.as_ref().and_then(|s| s.color)
+    }
+    /// Construct a `ShapeRenderable` with the shape's default anchor.
+    ///
+    /// Equivalent to `ShapeRenderable::builder(shape, style).build()`.
+    pub fn from_shape(shape: ShapeKind, style: ShapeStyle) -> Self {
+        Self::builder(shape, style).build()
     }
 
-    fn line(shape: Line, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let (x1, y1) = shape.start;
-        let (x2, y2) = shape.end;
-        let bbox_min = (x1.min(x2), y1.min(y2));
-        let bbox_max = (x1.max(x2), y1.max(y2));
-        let default = (x1, y1); // start point
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
+    /// Like [`from_shape`](Self::from_shape), but returns an error instead
+    /// of panicking on invalid geometry, a missing font or a shader that
+    /// fails to compile.
+    pub fn try_from_shape(shape: ShapeKind, style: ShapeStyle) -> Result<Self, WilhelmError> {
+        Self::builder(shape, style).try_build()
+    }
 
-        let (geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::line_geometry_dashed(x1 - ax, y1 - ay, x2 - ax, y2 - ay, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::line_geometry(x1 - ax, y1 - ay, x2 - ax, y2 - ay, stroke_width), default_shader())
-        };
-        let mut mesh = Mesh::with_color(shader, geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            mesh.dash_pattern = Some((dash, gap));
+    /// Check that `shape` can be built and load the shaders and font it
+    /// needs, so that building it afterwards cannot fail.
+    fn prepare(shape: &ShapeKind, style: &ShapeStyle) -> Result<(), WilhelmError> {
+        let invalid = |message: &str| Err(WilhelmError::InvalidShape(message.to_string()));
+        match shape {
+            ShapeKind::Image(_) => return invalid("an Image needs a path; use ShapeRenderable::try_image"),
+            ShapeKind::MultiPoint(mp) if mp.points.is_empty() => {
+                return invalid("MultiPoint requires at least one point");
+            }
+            ShapeKind::Polyline(p) if p.points.len() < 2 => return invalid("Polyline requires at least two points"),
+            ShapeKind::Polygon(p) if p.points.len() < 3 => return invalid("Polygon requires at least 3 points"),
+            ShapeKind::RoundedRectangle(rr) if rr.radius * 2.0 > rr.width.min(rr.height) => {
+                return invalid("RoundedRectangle radius exceeds half its width or height");
+            }
+            _ => {}
         }
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Line(shape));
-        s.x = ax;
-        s.y = ay;
-        s
+        match shape {
+            ShapeKind::Point | ShapeKind::MultiPoint(_) => {
+                try_point_shader()?;
+            }
+            ShapeKind::Text(text) => {
+                try_text_shader()?;
+                try_get_or_create_font_atlas(&text.font_path, text.font_size)?;
+            }
+            _ => {
+                try_default_shader()?;
+                if style.dash_pattern.is_some() {
+                    try_dashed_shader()?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn polyline(polyline: Polyline, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        assert!(polyline.points.len() >= 2, "Polyline requires at least two points");
-
-        let (bbox_min, bbox_max) = bbox_of_points(&polyline.points);
-        let default = polyline.points[0];
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let rel_points: Vec<(f32, f32)> = polyline
-            .points
-            .iter()
-            .map(|(px, py)| (px - ax, py - ay))
-            .collect();
-
-        let (geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::polyline_geometry_dashed(&rel_points, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::polyline_geometry(&rel_points, stroke_width), default_shader())
-        };
-        let mut mesh = Mesh::with_color(shader, geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            mesh.dash_pattern = Some((dash, gap));
+    /// Start a builder that lets you override the anchor (and, later, other
+    /// per-shape parameters) before constructing the `ShapeRenderable`.
+    pub fn builder(shape: ShapeKind, style: ShapeStyle) -> ShapeRenderableBuilder {
+        ShapeRenderableBuilder {
+            shape,
+            style,
+            anchor: Anchor::Default,
+            pivot: Anchor::Default,
+            z_order: 0,
         }
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Polyline(polyline));
-        s.x = ax;
-        s.y = ay;
-        s
     }
 
-    fn arc(arc: ArcShape, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        use std::f32::consts::TAU;
-
-        let segments = 64;
-
-        // Normalize sweep to [0, TAU)
-        let mut sweep = arc.end_angle - arc.start_angle;
-        if sweep < 0.0 {
-            sweep += TAU;
+    fn from_shape_with_anchor(shape: ShapeKind, style: ShapeStyle, anchor: Anchor) -> Self {
+        let opacity = style.opacity;
+        let mut renderable = match shape {
+            ShapeKind::Image(_) => {
+                unimplemented!("ShapeRenderable::from_shape cannot create Image without path")
+            }
+            ShapeKind::Text(text) => ShapeRenderable::text(
+                text,
+                style.fill.unwrap_or(Color::white()),
+                anchor,
+            ),
+            shape => {
+                let vertices = Self::shape_vertices(&shape, &style, anchor)
+                    .expect("every shape kind but Image and Text has plain vertices");
+                let stroke_only = Self::is_stroke_only(&shape, &style);
+                let fill = style.fill.unwrap_or(Color::white());
+                let stroke = style.stroke_color.unwrap_or(Color::white());
+                let mesh = match shape {
+                    ShapeKind::Point | ShapeKind::MultiPoint(_) => {
+                        Mesh::with_color(point_shader(), vertices.main.into_geometry(), Some(fill))
+                    }
+                    _ if stroke_only => Self::stroke_mesh(vertices.main, stroke, style.dash_pattern),
+                    _ => Mesh::with_color(default_shader(), vertices.main.into_geometry(), Some(fill)),
+                };
+                let mut s = match vertices.stroke {
+                    Some(outline) => {
+                        let stroke_mesh = Self::stroke_mesh(outline, stroke, style.dash_pattern);
+                        ShapeRenderable::new_with_stroke(mesh, stroke_mesh, shape)
+                    }
+                    None => ShapeRenderable::new(mesh, shape),
+                };
+                s.stroke_only = stroke_only;
+                (s.x, s.y) = vertices.origin;
+                s.origin = vertices.origin;
+                s
+            }
+        };
+        renderable.set_opacity(opacity);
+        if let Some(mesh) = renderable.dashed_mesh() {
+            mesh.dash_offset = style.dash_offset;
+            mesh.dash_speed = style.dash_speed;
         }
+        renderable.style = style;
+        renderable.anchor = anchor;
+        renderable
+    }
 
-        // Generate points around the arc's center (local origin).
-        let mut points = Vec::with_capacity(segments + 1);
-        for i in 0..=segments {
-            let t = i as f32 / segments as f32;
-            let theta = arc.start_angle + t * sweep;
-            let px = arc.radius * theta.cos();
-            let py = -arc.radius * theta.sin();
-            points.push((px, py));
+    /// Whether `shape` drawn with `style` is only a stroke: lines and
+    /// curves always, closed shapes when they have a stroke but no fill.
+    fn is_stroke_only(shape: &ShapeKind, style: &ShapeStyle) -> bool {
+        match shape {
+            ShapeKind::Line(_) | ShapeKind::Polyline(_) | ShapeKind::Arc(_) | ShapeKind::Bezier(_) => true,
+            ShapeKind::Point | ShapeKind::MultiPoint(_) | ShapeKind::Image(_) | ShapeKind::Text(_) => false,
+            _ => style.fill.is_none() && style.stroke_color.is_some(),
         }
+    }
 
-        // Bbox over the curve points; default anchor is the arc's circle center (0, 0).
-        let (bbox_min, bbox_max) = bbox_of_points(&points);
-        let default = (0.0, 0.0);
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
+    /// A stroke mesh, drawn with the dashed shader when `dash_pattern` is set.
+    fn stroke_mesh(vertices: VertexData, color: Color, dash_pattern: Option<(f32, f32)>) -> Mesh {
+        let shader = if dash_pattern.is_some() { dashed_shader() } else { default_shader() };
+        let mut mesh = Mesh::with_color(shader, vertices.into_geometry(), Some(color));
+        mesh.dash_pattern = dash_pattern;
+        mesh
+    }
 
-        let shifted: Vec<(f32, f32)> =
-            points.iter().map(|(x, y)| (x - ax, y - ay)).collect();
-        let (geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::polyline_geometry_dashed(&shifted, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::polyline_geometry(&shifted, stroke_width), default_shader())
+    /// Vertices of `shape` drawn with `style`, shifted so `anchor` sits at
+    /// the local origin. `None` for `Image` and `Text`, whose vertices
+    /// depend on a texture.
+    fn shape_vertices(shape: &ShapeKind, style: &ShapeStyle, anchor: Anchor) -> Option<ShapeVertices> {
+        let stroke_width = style.stroke_width.unwrap_or(1.0);
+        let dashed = style.dash_pattern.is_some();
+        let stroke = |points: &[(f32, f32)]| {
+            if dashed {
+                ShapeRenderable::polyline_vertices_dashed(points, stroke_width)
+            } else {
+                ShapeRenderable::polyline_vertices(points, stroke_width)
+            }
         };
-        let mut mesh = Mesh::with_color(shader, geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            mesh.dash_pattern = Some((dash, gap));
-        }
+        let open = |main: VertexData, origin: (f32, f32)| Some(ShapeVertices { main, stroke: None, origin });
+        // Only filled shapes need their (possibly triangulated) fill.
+        let filled = style.fill.is_some() || style.stroke_color.is_none();
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Arc(arc));
-        s.x = ax;
-        s.y = ay;
-        s
-    }
+        // Closed shapes: resolved anchor, fill and closed outline.
+        let (origin, fill, mut outline) = match shape {
+            ShapeKind::Point => {
+                // Point has only one vertex at (0, 0); anchor is trivially that point
+                // for every variant (bbox is degenerate). Ignore the anchor.
+                return open(VertexData::new(GL_POINTS, vec![0.0, 0.0], 2), (0.0, 0.0));
+            }
+            ShapeKind::MultiPoint(multi_point) => {
+                assert!(!multi_point.points.is_empty(), "MultiPoint requires at least one point");
+                let (bbox_min, bbox_max) = bbox_of_points(&multi_point.points);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, multi_point.points[0]);
+                let rel_points: Vec<(GLfloat, GLfloat)> =
+                    multi_point.points.iter().map(|(px, py)| (px - ax, py - ay)).collect();
+                return open(ShapeRenderable::point_list_vertices(&rel_points), (ax, ay));
+            }
+            ShapeKind::Line(line) => {
+                let (x1, y1) = line.start;
+                let (x2, y2) = line.end;
+                let bbox_min = (x1.min(x2), y1.min(y2));
+                let bbox_max = (x1.max(x2), y1.max(y2));
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, line.start);
+                let main = if dashed {
+                    ShapeRenderable::line_vertices_dashed(x1 - ax, y1 - ay, x2 - ax, y2 - ay, stroke_width)
+                } else {
+                    ShapeRenderable::line_vertices(x1 - ax, y1 - ay, x2 - ax, y2 - ay, stroke_width)
+                };
+                return open(main, (ax, ay));
+            }
+            ShapeKind::Polyline(polyline) => {
+                assert!(polyline.points.len() >= 2, "Polyline requires at least two points");
+                let (bbox_min, bbox_max) = bbox_of_points(&polyline.points);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, polyline.points[0]);
+                let rel_points: Vec<(f32, f32)> =
+                    polyline.points.iter().map(|(px, py)| (px - ax, py - ay)).collect();
+                return open(stroke(&rel_points), (ax, ay));
+            }
+            ShapeKind::Bezier(bezier) => {
+                let points = bezier.flatten(FLATTEN_TOLERANCE);
+                // Bbox over the flattened curve, which hugs it tighter than the
+                // control points do.
+                let (bbox_min, bbox_max) = bbox_of_points(&points);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, bezier.start());
+                let shifted: Vec<(f32, f32)> = points.iter().map(|(x, y)| (x - ax, y - ay)).collect();
+                return open(stroke(&shifted), (ax, ay));
+            }
+            ShapeKind::Arc(arc) => {
+                let points = ShapeRenderable::arc_points(arc);
+                // Bbox over the curve points; default anchor is the arc's circle center (0, 0).
+                let (bbox_min, bbox_max) = bbox_of_points(&points);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, (0.0, 0.0));
+                let shifted: Vec<(f32, f32)> = points.iter().map(|(x, y)| (x - ax, y - ay)).collect();
+                return open(stroke(&shifted), (ax, ay));
+            }
+            ShapeKind::Triangle(triangle) => {
+                let (bbox_min, bbox_max) = bbox_of_points(&triangle.vertices);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, triangle.centroid());
+                let shifted = triangle.vertices.map(|(x, y)| (x - ax, y - ay));
+                let fill = filled.then(|| ShapeRenderable::triangle_vertices(&shifted));
+                ((ax, ay), fill, vec![shifted[0], shifted[1], shifted[2], shifted[0]])
+            }
+            ShapeKind::Rectangle(rect) => {
+                let (ax, ay) = rectangle_anchor(rect.width, rect.height, anchor);
+                let fill = filled.then(|| ShapeRenderable::rectangle_vertices(rect.width, rect.height, ax, ay));
+                let outline = vec![
+                    (0.0 - ax, 0.0 - ay),
+                    (rect.width - ax, 0.0 - ay),
+                    (rect.width - ax, rect.height - ay),
+                    (0.0 - ax, rect.height - ay),
+                    (0.0 - ax, 0.0 - ay), // close the loop
+                ];
+                ((ax, ay), fill, outline)
+            }
+            ShapeKind::RoundedRectangle(rr) => {
+                let (ax, ay) = rectangle_anchor(rr.width, rr.height, anchor);
+                let fill = filled.then(|| {
+                    ShapeRenderable::rounded_rectangle_vertices(rr.width, rr.height, rr.radius, 8, ax, ay)
+                });
+                let outline = ShapeRenderable::rounded_rectangle_outline_points(rr.width, rr.height, rr.radius, 8, ax, ay);
+                ((ax, ay), fill, outline)
+            }
+            ShapeKind::Polygon(polygon) => {
+                assert!(polygon.points.len() >= 3, "Polygon requires at least 3 points");
+                let (bbox_min, bbox_max) = bbox_of_points(&polygon.points);
+                let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, polygon.points[0]);
+                let mut rel_points: Vec<(f32, f32)> =
+                    polygon.points.iter().map(|(px, py)| (px - ax, py - ay)).collect();
+                let fill = filled.then(|| ShapeRenderable::polygon_vertices(&rel_points, &polygon.triangulate()));
+                rel_points.push(rel_points[0]); // close
+                ((ax, ay), fill, rel_points)
+            }
+            ShapeKind::Circle(circle) => {
+                let r = circle.radius;
+                let (ax, ay) = resolve_anchor(anchor, (-r, -r), (r, r), (0.0, 0.0));
+                let fill = filled.then(|| ShapeRenderable::circle_vertices(r, 100, ax, ay));
+                ((ax, ay), fill, ShapeRenderable::circle_outline_points(r, 100, ax, ay))
+            }
+            ShapeKind::Ellipse(ellipse) => {
+                let (rx, ry) = (ellipse.radius_x, ellipse.radius_y);
+                let (ax, ay) = resolve_anchor(anchor, (-rx, -ry), (rx, ry), (0.0, 0.0));
+                let fill = filled.then(|| ShapeRenderable::ellipse_vertices(rx, ry, 64, ax, ay));
+                ((ax, ay), fill, ShapeRenderable::ellipse_outline_points(rx, ry, 64, ax, ay))
+            }
+            ShapeKind::Image(_) | ShapeKind::Text(_) => return None,
+        };
 
-    /// Build a stroke mesh from a closed polyline, choosing solid or dashed rendering.
-    fn stroke_mesh_from_outline(
-        points: &mut Vec<(f32, f32)>,
-        stroke: Color,
-        stroke_width: f32,
-        dash_pattern: Option<(f32, f32)>,
-    ) -> Mesh {
         // For solid strokes, add a direction hint (second point) for proper miter at closing corner.
         // For dashed strokes, skip it — the overlap would fill in gaps.
-        if dash_pattern.is_none() {
-            if let Some(&second) = points.get(1) {
-                points.push(second);
+        if !dashed {
+            if let Some(&second) = outline.get(1) {
+                outline.push(second);
             }
         }
-
-        let (geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::polyline_geometry_dashed(points, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::polyline_geometry(points, stroke_width), default_shader())
+        let outline = style.stroke_color.map(|_| stroke(&outline));
+        let (main, stroke) = match (fill, outline) {
+            (Some(fill), outline) => (fill, outline),
+            (None, Some(outline)) => (outline, None),
+            (None, None) => unreachable!("shapes without a fill are drawn as their stroke"),
         };
-        let mut mesh = Mesh::with_color(shader, geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            mesh.dash_pattern = Some((dash, gap));
-        }
-        mesh
+        Some(ShapeVertices { main, stroke, origin })
     }
 
-    fn triangle(triangle: Triangle, color: Color, anchor: Anchor) -> Self {
-        let [v0, v1, v2] = triangle.vertices;
-        let bbox_min = (v0.0.min(v1.0).min(v2.0), v0.1.min(v1.1).min(v2.1));
-        let bbox_max = (v0.0.max(v1.0).max(v2.0), v0.1.max(v1.1).max(v2.1));
-        let default = triangle.centroid();
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let shifted = [
-            (v0.0 - ax, v0.1 - ay),
-            (v1.0 - ax, v1.1 - ay),
-            (v2.0 - ax, v2.1 - ay),
-        ];
-        let geometry = ShapeRenderable::triangle_geometry(&shifted);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Triangle(triangle));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Move the anchor (pivot for rotation and scaling) after creation.
+    ///
+    /// The vertices are shifted around the new anchor in their existing
+    /// buffers; position, scale, rotation, z-order, visibility, colors,
+    /// opacity and instances are kept. Since the anchor is the point placed
+    /// by [`set_position`](Self::set_position), the shape shifts on screen
+    /// unless it is repositioned.
+    pub fn set_anchor(&mut self, anchor: Anchor) -> &mut Self {
+        self.anchor = anchor;
+        self.rebuild();
+        self
     }
 
-    fn triangle_outline(triangle: Triangle, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let [v0, v1, v2] = triangle.vertices;
-        let bbox_min = (v0.0.min(v1.0).min(v2.0), v0.1.min(v1.1).min(v2.1));
-        let bbox_max = (v0.0.max(v1.0).max(v2.0), v0.1.max(v1.1).max(v2.1));
-        let default = triangle.centroid();
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let mut points = vec![
-            (v0.0 - ax, v0.1 - ay),
-            (v1.0 - ax, v1.1 - ay),
-            (v2.0 - ax, v2.1 - ay),
-            (v0.0 - ax, v0.1 - ay), // close
-        ];
-        let mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Triangle(triangle));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
     }
 
-    fn triangle_fill_and_stroke(triangle: Triangle, fill: Color, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let [v0, v1, v2] = triangle.vertices;
-        let bbox_min = (v0.0.min(v1.0).min(v2.0), v0.1.min(v1.1).min(v2.1));
-        let bbox_max = (v0.0.max(v1.0).max(v2.0), v0.1.max(v1.1).max(v2.1));
-        let default = triangle.centroid();
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let shifted = [
-            (v0.0 - ax, v0.1 - ay),
-            (v1.0 - ax, v1.1 - ay),
-            (v2.0 - ax, v2.1 - ay),
-        ];
-        let fill_geometry = ShapeRenderable::triangle_geometry(&shifted);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
-
-        let mut points = vec![shifted[0], shifted[1], shifted[2], shifted[0]];
-        let stroke_mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
-
-        let mut s = ShapeRenderable::new_with_stroke(fill_mesh, stroke_mesh, ShapeKind::Triangle(triangle));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Rotate and scale around `pivot` instead of the anchor, without
+    /// moving the shape: unlike [`set_anchor`](Self::set_anchor), the shape
+    /// stays where it is until rotated or scaled. Compass variants resolve
+    /// against the shape's bounding box, `Custom` is in shape coordinates,
+    /// and `Default` pivots on the anchor again. Per-instance transforms
+    /// still rotate and scale each instance around its anchor.
+    pub fn set_pivot(&mut self, pivot: Anchor) -> &mut Self {
+        self.pivot = pivot;
+        self
     }
 
-    fn rectangle(rect: Rectangle, color: Color, anchor: Anchor) -> Self {
-        let (ax, ay) = rectangle_anchor(rect.width, rect.height, anchor);
-        let geometry = ShapeRenderable::rectangle_geometry(rect.width, rect.height, ax, ay);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Rectangle(rect));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn pivot(&self) -> Anchor {
+        self.pivot
     }
 
-    fn rectangle_outline(rect: Rectangle, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let (ax, ay) = rectangle_anchor(rect.width, rect.height, anchor);
-        // Closed polyline in the same local frame as the fill, shifted by anchor.
-        // The direction hint (6th point) retraces the top edge for a proper miter join.
-        // For dashed lines, skip it — the overlap would fill in the gaps on the top edge.
-        let mut points = vec![
-            (0.0 - ax, 0.0 - ay),
-            (rect.width - ax, 0.0 - ay),
-            (rect.width - ax, rect.height - ay),
-            (0.0 - ax, rect.height - ay),
-            (0.0 - ax, 0.0 - ay),                // close the loop
-        ];
-        if dash_pattern.is_none() {
-            points.push((rect.width - ax, 0.0 - ay)); // direction hint for join at closing corner
-        }
-
-        let (geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::polyline_geometry_dashed(&points, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::polyline_geometry(&points, stroke_width), default_shader())
+    /// The pivot relative to the anchor, in the coordinates the geometry
+    /// was built in.
+    fn pivot_offset(&self) -> (f32, f32) {
+        let point = match self.pivot {
+            Anchor::Default => return (0.0, 0.0),
+            Anchor::Custom(x, y) => (x, y),
+            pivot => match self.local_extent() {
+                Some((min, max)) => resolve_anchor(pivot, min, max, self.origin),
+                None => return (0.0, 0.0),
+            },
         };
-        let mut mesh = Mesh::with_color(shader, geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            mesh.dash_pattern = Some((dash, gap));
-        }
+        (point.0 - self.origin.0, point.1 - self.origin.1)
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Rectangle(rect));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// What the renderer adds to the position so that rotating and scaling
+    /// around the anchor leaves the pivot in place; see
+    /// [`Mesh::pivot_translation`].
+    fn pivot_translation(&self) -> (f32, f32) {
+        pivot_translation(self.pivot_offset(), self.scale, self.rotation)
     }
 
-    fn rectangle_fill_and_stroke(
-        rect: Rectangle,
-        fill: Color,
-        stroke: Color,
-        stroke_width: f32,
-        anchor: Anchor,
-        dash_pattern: Option<(f32, f32)>,
-    ) -> Self {
-        let (ax, ay) = rectangle_anchor(rect.width, rect.height, anchor);
-
-        let fill_geometry = ShapeRenderable::rectangle_geometry(rect.width, rect.height, ax, ay);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
-
-        let mut points = vec![
-            (0.0 - ax, 0.0 - ay),
-            (rect.width - ax, 0.0 - ay),
-            (rect.width - ax, rect.height - ay),
-            (0.0 - ax, rect.height - ay),
-            (0.0 - ax, 0.0 - ay),
-        ];
-        if dash_pattern.is_none() {
-            points.push((rect.width - ax, 0.0 - ay));
+    /// Map a screen point into shape coordinates (the space the `ShapeKind`
+    /// parameters are expressed in), undoing position, rotation, scale and
+    /// anchor. `None` when the scale is zero.
+    pub fn to_local(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        if self.scale == 0.0 {
+            return None;
         }
-        let (stroke_geometry, shader) = if let Some(_) = dash_pattern {
-            (ShapeRenderable::polyline_geometry_dashed(&points, stroke_width), dashed_shader())
-        } else {
-            (ShapeRenderable::polyline_geometry(&points, stroke_width), default_shader())
-        };
-        let mut stroke_mesh = Mesh::with_color(shader, stroke_geometry, Some(stroke));
-        if let Some((dash, gap)) = dash_pattern {
-            stroke_mesh.dash_pattern = Some((dash, gap));
-        }
-
-        let mut s = ShapeRenderable::new_with_stroke(
-            fill_mesh,
-            stroke_mesh,
-            ShapeKind::Rectangle(rect),
-        );
-        s.x = ax;
-        s.y = ay;
-        s
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let (px, py) = self.pivot_offset();
+        let (dx, dy) = (x - self.x - px, y - self.y - py);
+        Some((
+            (dx * cos_r + dy * sin_r) / self.scale + px + self.origin.0,
+            (-dx * sin_r + dy * cos_r) / self.scale + py + self.origin.1,
+        ))
     }
 
-    fn rounded_rectangle(rr: RoundedRectangle, color: Color, anchor: Anchor) -> Self {
-        let (ax, ay) = rectangle_anchor(rr.width, rr.height, anchor);
-        let geometry =
-            ShapeRenderable::rounded_rectangle_geometry(rr.width, rr.height, rr.radius, 8, ax, ay);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::RoundedRectangle(rr));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Map a point in shape coordinates to the screen; the inverse of
+    /// [`to_local`](Self::to_local).
+    pub fn to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let (px, py) = self.pivot_offset();
+        let (lx, ly) = ((x - self.origin.0 - px) * self.scale, (y - self.origin.1 - py) * self.scale);
+        (self.x + px + lx * cos_r - ly * sin_r, self.y + py + lx * sin_r + ly * cos_r)
     }
 
-    fn rounded_rectangle_outline(rr: RoundedRectangle, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let (ax, ay) = rectangle_anchor(rr.width, rr.height, anchor);
-        let mut points = ShapeRenderable::rounded_rectangle_outline_points(rr.width, rr.height, rr.radius, 8, ax, ay);
-        let mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
-
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::RoundedRectangle(rr));
-        s.x = ax;
-        s.y = ay;
-        s
-    }
+    /// True if the screen point `(x, y)` hits the shape.
+    ///
+    /// The point is mapped back through position, rotation, scale and anchor,
+    /// then tested exactly against the shape: filled interiors (even-odd for
+    /// polygons), and strokes within half the stroke width of the outline.
+    /// Points and multipoints are hit within half the stroke width (default
+    /// 1px) of a vertex; text and images use their bounding box.
+    ///
+    /// Visibility and opacity are ignored, and instanced shapes are tested
+    /// only at the renderable's own position.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let Some(p) = self.to_local(x, y) else {
+            return false;
+        };
+        let half_width = self.style.stroke_width.unwrap_or(1.0).max(MIN_STROKE_WIDTH) * 0.5;
 
-    fn rounded_rectangle_fill_and_stroke(rr: RoundedRectangle, fill: Color, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let (ax, ay) = rectangle_anchor(rr.width, rr.height, anchor);
+        // (inside the fill, distance to the outline)
+        let (inside, edge) = match &self.shape {
+            ShapeKind::Point => return p.0.hypot(p.1) <= half_width,
+            ShapeKind::MultiPoint(mp) => {
+                return mp.points.iter().any(|&(vx, vy)| (p.0 - vx).hypot(p.1 - vy) <= half_width)
+            }
+            ShapeKind::Line(line) => (false, hit::segment_distance(p, line.start, line.end)),
+            ShapeKind::Polyline(poly) => (false, hit::polyline_distance(p, &poly.points, false)),
+            ShapeKind::Arc(arc) => (false, hit::arc_distance(p, arc.radius, arc.start_angle, arc.end_angle)),
+            ShapeKind::Bezier(bezier) => (false, hit::polyline_distance(p, &bezier.flatten(FLATTEN_TOLERANCE), false)),
+            ShapeKind::Triangle(t) => (
+                hit::point_in_polygon(p, &t.vertices),
+                hit::polyline_distance(p, &t.vertices, true),
+            ),
+            ShapeKind::Polygon(poly) => (
+                hit::point_in_polygon(p, &poly.points),
+                hit::polyline_distance(p, &poly.points, true),
+            ),
+            ShapeKind::Rectangle(r) => {
+                let d = hit::rounded_box_sdf(p, (0.0, 0.0), (r.width, r.height), 0.0);
+                (d <= 0.0, d.abs())
+            }
+            ShapeKind::RoundedRectangle(r) => {
+                let d = hit::rounded_box_sdf(p, (0.0, 0.0), (r.width, r.height), r.radius);
+                (d <= 0.0, d.abs())
+            }
+            ShapeKind::Circle(c) => {
+                let d = (p.0 * p.0 + p.1 * p.1).sqrt() - c.radius;
+                (d <= 0.0, d.abs())
+            }
+            ShapeKind::Ellipse(e) => {
+                let d = hit::ellipse_sdf(p, e.radius_x, e.radius_y);
+                (d <= 0.0, d.abs())
+            }
+            ShapeKind::Image(img) => {
+                let (hw, hh) = (img.width * 0.5, img.height * 0.5);
+                return hit::rounded_box_sdf(p, (-hw, -hh), (hw, hh), 0.0) <= 0.0;
+            }
+            ShapeKind::Text(_) => {
+                return match self.measured_bounds {
+                    Some((min, max)) => hit::rounded_box_sdf(p, min, max, 0.0) <= 0.0,
+                    None => false,
+                };
+            }
+        };
 
-        let fill_geometry = ShapeRenderable::rounded_rectangle_geometry(rr.width, rr.height, rr.radius, 8, ax, ay);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
+        let has_fill = !self.stroke_only;
+        let has_stroke = self.stroke_only || self.stroke_mesh.is_some();
+        (has_fill && inside) || (has_stroke && edge <= half_width)
+    }
 
-        let mut points = ShapeRenderable::rounded_rectangle_outline_points(rr.width, rr.height, rr.radius, 8, ax, ay);
-        let stroke_mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+    /// Screen-space axis-aligned bounds as `(min_x, min_y, max_x, max_y)`,
+    /// including half the stroke width, for use with
+    /// [`SpatialIndex`](crate::core::SpatialIndex).
+    ///
+    /// Like [`contains`](Self::contains), this covers only the renderable's
+    /// own position, not its instances (see
+    /// [`instance_bounds`](Self::instance_bounds)). Arcs use their full
+    /// circle.
+    pub fn bounds(&self) -> (f32, f32, f32, f32) {
+        match self.local_corners() {
+            Some(corners) => corner_extent(corners.map(|(lx, ly)| self.to_screen(lx, ly))),
+            // Empty point list
+            None => (self.x, self.y, self.x, self.y),
+        }
+    }
 
-        let mut s = ShapeRenderable::new_with_stroke(fill_mesh, stroke_mesh, ShapeKind::RoundedRectangle(rr));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Screen-space bounds of each instance from
+    /// [`set_instance_positions`](Self::set_instance_positions), like
+    /// [`bounds`](Self::bounds), with the instance transforms applied.
+    /// Empty for shapes without instances.
+    pub fn instance_bounds(&self) -> Vec<(f32, f32, f32, f32)> {
+        let Some(corners) = self.local_corners() else {
+            return self.instance_positions.iter().map(|p| (p.x, p.y, p.x, p.y)).collect();
+        };
+        let shift = self.pivot_translation();
+        self.instance_positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                // As in shape.vert: instance scale, shape and instance
+                // rotation, shape scale, then the instance position and
+                // the pivot shift.
+                let t = self.instance_transforms.get(i).copied().unwrap_or_default();
+                let (sin_r, cos_r) = (self.rotation + t.rotation).sin_cos();
+                let (px, py) = (p.x + shift.0, p.y + shift.1);
+                corner_extent(corners.map(|(lx, ly)| {
+                    let (lx, ly) = ((lx - self.origin.0) * t.scale_x, (ly - self.origin.1) * t.scale_y);
+                    (px + (lx * cos_r - ly * sin_r) * self.scale, py + (lx * sin_r + ly * cos_r) * self.scale)
+                }))
+            })
+            .collect()
+    }
+
+    /// Corners of the shape-coordinate bounding box, padded by half the
+    /// stroke width where a stroke is drawn, or `None` for an empty shape.
+    fn local_corners(&self) -> Option<[(f32, f32); 4]> {
+        let ((x0, y0), (x1, y1)) = self.local_extent()?;
+        let half_width = self.style.stroke_width.unwrap_or(1.0).max(MIN_STROKE_WIDTH) * 0.5;
+        let pad = match &self.shape {
+            ShapeKind::Image(_) | ShapeKind::Text(_) => 0.0,
+            _ if self.stroke_only || self.stroke_mesh.is_some() => half_width,
+            ShapeKind::Point | ShapeKind::MultiPoint(_) => half_width,
+            _ => 0.0,
+        };
+        Some([(x0 - pad, y0 - pad), (x1 + pad, y0 - pad), (x0 - pad, y1 + pad), (x1 + pad, y1 + pad)])
     }
 
-    fn polygon(polygon: Polygon, color: Color, anchor: Anchor) -> Self {
-        assert!(polygon.points.len() >= 3, "Polygon requires at least 3 points");
-
-        let (bbox_min, bbox_max) = bbox_of_points(&polygon.points);
-        let default = polygon.points[0];
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
-
-        let rel_points: Vec<(f32, f32)> = polygon
-            .points
-            .iter()
-            .map(|(px, py)| (px - ax, py - ay))
-            .collect();
-        let triangles = polygon.triangulate();
+    /// Shape-coordinate bounding box `(min, max)` of the geometry, without
+    /// stroke padding, or `None` for an empty shape.
+    fn local_extent(&self) -> Option<((f32, f32), (f32, f32))> {
+        fn extent(points: &[(f32, f32)]) -> ((f32, f32), (f32, f32)) {
+            points.iter().fold(
+                ((f32::INFINITY, f32::INFINITY), (f32::NEG_INFINITY, f32::NEG_INFINITY)),
+                |(min, max), &(x, y)| ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y))),
+            )
+        }
 
-        let geometry = ShapeRenderable::polygon_geometry(&rel_points, &triangles);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
+        let ((x0, y0), (x1, y1)) = match &self.shape {
+            ShapeKind::Point => ((0.0, 0.0), (0.0, 0.0)),
+            ShapeKind::MultiPoint(mp) => extent(&mp.points),
+            ShapeKind::Line(line) => extent(&[line.start, line.end]),
+            ShapeKind::Polyline(poly) => extent(&poly.points),
+            ShapeKind::Polygon(poly) => extent(&poly.points),
+            ShapeKind::Triangle(t) => extent(&t.vertices),
+            ShapeKind::Rectangle(r) => ((0.0, 0.0), (r.width, r.height)),
+            ShapeKind::RoundedRectangle(r) => ((0.0, 0.0), (r.width, r.height)),
+            ShapeKind::Circle(c) => ((-c.radius, -c.radius), (c.radius, c.radius)),
+            ShapeKind::Arc(a) => ((-a.radius, -a.radius), (a.radius, a.radius)),
+            ShapeKind::Bezier(b) => extent(&b.flatten(FLATTEN_TOLERANCE)),
+            ShapeKind::Ellipse(e) => ((-e.radius_x, -e.radius_y), (e.radius_x, e.radius_y)),
+            ShapeKind::Image(img) => {
+                let (hw, hh) = (img.width * 0.5, img.height * 0.5);
+                ((-hw, -hh), (hw, hh))
+            }
+            ShapeKind::Text(_) => self.measured_bounds.unwrap_or(((0.0, 0.0), (0.0, 0.0))),
+        };
+        if x0 > x1 {
+            return None;
+        }
+        Some(((x0, y0), (x1, y1)))
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Polygon(polygon));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// The shape parameters this renderable was built from.
+    pub fn shape(&self) -> &ShapeKind {
+        &self.shape
     }
 
-    fn polygon_outline(polygon: Polygon, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        assert!(polygon.points.len() >= 3, "Polygon requires at least 3 points");
+    /// Resize a `Rectangle`, `RoundedRectangle` or `Image` in place.
+    ///
+    /// Like all geometry setters, this writes the new vertices into the
+    /// existing vertex buffers (images keep their texture), so transform,
+    /// colors, opacity and instances are preserved. No-op for other shape
+    /// kinds.
+    pub fn set_size(&mut self, width: f32, height: f32) -> &mut Self {
+        let changed = match &mut self.shape {
+            ShapeKind::Rectangle(r) => { r.width = width; r.height = height; true }
+            ShapeKind::RoundedRectangle(r) => { r.width = width; r.height = height; true }
+            ShapeKind::Image(i) => { i.width = width; i.height = height; true }
+            _ => false,
+        };
+        if changed {
+            self.rebuild();
+        }
+        self
+    }
 
-        let (bbox_min, bbox_max) = bbox_of_points(&polygon.points);
-        let default = polygon.points[0];
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
+    /// Change the radius of a `Circle` or `Arc`, or the corner radius of a
+    /// `RoundedRectangle`. No-op for other shape kinds.
+    pub fn set_radius(&mut self, radius: f32) -> &mut Self {
+        let changed = match &mut self.shape {
+            ShapeKind::Circle(c) => { c.radius = radius; true }
+            ShapeKind::Arc(a) => { a.radius = radius; true }
+            ShapeKind::RoundedRectangle(r) => { r.radius = radius; true }
+            _ => false,
+        };
+        if changed {
+            self.rebuild();
+        }
+        self
+    }
 
-        let mut points: Vec<(f32, f32)> = polygon.points.iter().map(|(px, py)| (px - ax, py - ay)).collect();
-        points.push(points[0]); // close
-        let mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+    /// Change both radii of an `Ellipse`. No-op for other shape kinds.
+    pub fn set_radii(&mut self, radius_x: f32, radius_y: f32) -> &mut Self {
+        if let ShapeKind::Ellipse(e) = &mut self.shape {
+            e.radius_x = radius_x;
+            e.radius_y = radius_y;
+            self.rebuild();
+        }
+        self
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Polygon(polygon));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Replace the vertices of a `Polyline`, `Polygon` or `MultiPoint`, the
+    /// two endpoints of a `Line`, the three corners of a `Triangle`, or the
+    /// start, control and end points of a `Bezier`.
+    ///
+    /// No-op for other shape kinds or when `points` has the wrong length
+    /// for a `Line` (2), `Triangle` (3) or `Bezier` (3 for quadratic, 4 for
+    /// cubic; the degree can change).
+    pub fn set_points(&mut self, points: &[(f32, f32)]) -> &mut Self {
+        let changed = match (&mut self.shape, points) {
+            (ShapeKind::Polyline(p), _) => { p.points = points.to_vec(); true }
+            (ShapeKind::Polygon(p), _) => { p.points = points.to_vec(); true }
+            (ShapeKind::MultiPoint(p), _) => { p.points = points.to_vec(); true }
+            (ShapeKind::Line(l), &[start, end]) => { l.start = start; l.end = end; true }
+            (ShapeKind::Triangle(t), &[a, b, c]) => { t.vertices = [a, b, c]; true }
+            (ShapeKind::Bezier(b), _) => match Bezier::from_points(points) {
+                Some(bezier) => { *b = bezier; true }
+                None => false,
+            },
+            _ => false,
+        };
+        if changed {
+            self.rebuild();
+        }
+        self
     }
 
-    fn polygon_fill_and_stroke(polygon: Polygon, fill: Color, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        assert!(polygon.points.len() >= 3, "Polygon requires at least 3 points");
+    /// Replace the shape, regenerating its vertices with the current style
+    /// and anchor. Transform, colors, opacity and instances are kept; the
+    /// buffers are refilled in place unless the drawing mode changes (e.g. a
+    /// circle becoming a polygon). No-op when either the current or the new
+    /// shape is an `Image`, whose texture cannot be swapped here.
+    pub fn set_shape(&mut self, shape: ShapeKind) -> &mut Self {
+        if matches!(self.shape, ShapeKind::Image(_)) || matches!(shape, ShapeKind::Image(_)) {
+            return self;
+        }
+        self.shape = shape;
+        self.rebuild();
+        self
+    }
 
-        let (bbox_min, bbox_max) = bbox_of_points(&polygon.points);
-        let default = polygon.points[0];
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, default);
+    /// Show `morph` at `t` (0 to 1) through [`set_shape`](Self::set_shape),
+    /// so steps that keep the drawing mode refill the vertex buffers in
+    /// place. A circle turning into a polygon on the first step gets new
+    /// meshes once.
+    pub fn apply_morph(&mut self, morph: &ShapeMorph, t: f32) -> &mut Self {
+        self.set_shape(morph.sample(t))
+    }
 
-        let rel_points: Vec<(f32, f32)> = polygon.points.iter().map(|(px, py)| (px - ax, py - ay)).collect();
-        let triangles = polygon.triangulate();
-        let fill_geometry = ShapeRenderable::polygon_geometry(&rel_points, &triangles);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
+    /// Regenerate the vertices from the stored shape, style and anchor.
+    ///
+    /// They are written into the existing vertex buffers when the drawing
+    /// mode and vertex layout stay the same, keeping the VAOs and instance
+    /// buffers, and text is laid out again the same way. Otherwise (a stroke
+    /// is added, a circle becomes a polygon, text changes font) the meshes
+    /// are recreated and given the instance data kept on the CPU. Runtime
+    /// colors and opacity are carried over either way.
+    fn rebuild(&mut self) {
+        if self.relayout_text() {
+            return;
+        }
+        let fill = self.mesh.color;
+        let stroke = self.stroke_color();
+        let opacity = self.opacity();
+
+        let vertices = match &self.shape {
+            ShapeKind::Image(image) => {
+                // Reuse the uploaded texture; only the quad depends on the anchor.
+                let hw = image.width * 0.5;
+                let hh = image.height * 0.5;
+                let (ax, ay) = resolve_anchor(self.anchor, (-hw, -hh), (hw, hh), (0.0, 0.0));
+                let main = ShapeRenderable::image_vertices(image.width, image.height, ax, ay);
+                Some(ShapeVertices { main, stroke: None, origin: (ax, ay) })
+            }
+            shape => Self::shape_vertices(shape, &self.style, self.anchor),
+        };
 
-        let mut outline = rel_points.clone();
-        outline.push(outline[0]); // close
-        let stroke_mesh = ShapeRenderable::stroke_mesh_from_outline(&mut outline, stroke, stroke_width, dash_pattern);
+        match vertices {
+            Some(vertices)
+                if vertices.main.fits(&self.mesh.geometry)
+                    && self.stroke_only == Self::is_stroke_only(&self.shape, &self.style) =>
+            {
+                vertices.main.write_into(&mut self.mesh.geometry);
+                match (vertices.stroke, &mut self.stroke_mesh) {
+                    (Some(outline), Some(mesh)) if outline.fits(&mesh.geometry) => {
+                        outline.write_into(&mut mesh.geometry);
+                    }
+                    (outline, _) => {
+                        self.stroke_mesh = outline.map(|outline| {
+                            let color = self.style.stroke_color.unwrap_or(Color::white());
+                            let mut mesh = Self::stroke_mesh(outline, color, self.style.dash_pattern);
+                            mesh.dash_offset = self.style.dash_offset;
+                            mesh.dash_speed = self.style.dash_speed;
+                            mesh
+                        });
+                        if let Some(mesh) = &mut self.stroke_mesh {
+                            Self::upload_instances(&mut mesh.geometry, &self.instance_positions, &self.instance_transforms);
+                        }
+                    }
+                }
+                self.origin = vertices.origin;
+            }
+            _ => {
+                let fresh = Self::from_shape_with_anchor(self.shape.clone(), self.style.clone(), self.anchor);
+                self.mesh = fresh.mesh;
+                self.stroke_mesh = fresh.stroke_mesh;
+                self.stroke_only = fresh.stroke_only;
+                self.origin = fresh.origin;
+                self.measured_bounds = fresh.measured_bounds;
+                self.text_glyphs = fresh.text_glyphs;
+
+                Self::upload_instances(&mut self.mesh.geometry, &self.instance_positions, &self.instance_transforms);
+                if !self.instance_colors.is_empty() {
+                    self.mesh.geometry.update_instance_colors(&self.instance_colors);
+                }
+                if let Some(mesh) = &mut self.stroke_mesh {
+                    Self::upload_instances(&mut mesh.geometry, &self.instance_positions, &self.instance_transforms);
+                }
+            }
+        }
 
-        let mut s = ShapeRenderable::new_with_stroke(fill_mesh, stroke_mesh, ShapeKind::Polygon(polygon));
-        s.x = ax;
-        s.y = ay;
-        s
+        self.mesh.color = fill;
+        if let (Some(mesh), Some(color)) = (&mut self.stroke_mesh, stroke) {
+            mesh.color = Some(color);
+        }
+        self.set_opacity(opacity);
     }
 
-    fn circle(circle: Circle, color: Color, anchor: Anchor) -> Self {
-        let r = circle.radius;
-        let (ax, ay) = resolve_anchor(anchor, (-r, -r), (r, r), (0.0, 0.0));
-        let geometry = ShapeRenderable::circle_geometry(r, 100, ax, ay);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
+    /// Give a freshly created `geometry` the shape's instance positions and
+    /// transforms, if it is instanced.
+    fn upload_instances(geometry: &mut Geometry, positions: &[Vec2], transforms: &[InstanceTransform]) {
+        if positions.is_empty() {
+            return;
+        }
+        geometry.enable_instancing_xy(positions.len());
+        geometry.update_instance_xy(positions);
+        if !transforms.is_empty() {
+            geometry.update_instance_transforms(transforms);
+        }
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Circle(circle));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn create_multiple_instances(&mut self, capacity: usize) {
+        self.mesh.geometry.enable_instancing_xy(capacity);
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.enable_instancing_xy(capacity);
+        }
     }
 
-    fn circle_outline(circle: Circle, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let r = circle.radius;
-        let (ax, ay) = resolve_anchor(anchor, (-r, -r), (r, r), (0.0, 0.0));
-        let mut points = ShapeRenderable::circle_outline_points(r, 100, ax, ay);
-        let mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+    /// Like [`create_multiple_instances`](Self::create_multiple_instances),
+    /// but position updates rotate through `buffers` fenced regions instead
+    /// of orphaning one buffer each time (see
+    /// [`Geometry::enable_instancing_xy_buffered`]). Use it for shapes with
+    /// very many instances updated every frame; 3 buffers is typical.
+    pub fn create_multiple_instances_buffered(&mut self, capacity: usize, buffers: usize) {
+        self.mesh.geometry.enable_instancing_xy_buffered(capacity, buffers);
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.enable_instancing_xy_buffered(capacity, buffers);
+        }
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Circle(circle));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn set_instance_positions(&mut self, positions: &[Vec2]) -> &mut Self {
+        self.instance_positions.clear();
+        self.instance_positions.extend_from_slice(positions);
+        self.mesh.geometry.update_instance_xy(positions);
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.update_instance_xy(positions);
+        }
+        self
     }
 
-    fn circle_fill_and_stroke(circle: Circle, fill: Color, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let r = circle.radius;
-        let (ax, ay) = resolve_anchor(anchor, (-r, -r), (r, r), (0.0, 0.0));
+    /// Set `count` instance positions in place: `write` fills the shape's
+    /// own position buffer, which is then written straight into the mapped
+    /// GL buffers. Per-frame updates need no `Vec` of their own this way.
+    /// Falls back to [`set_instance_positions`](Self::set_instance_positions)
+    /// if a buffer cannot be mapped.
+    pub fn write_instance_positions(&mut self, count: usize, write: impl FnOnce(&mut [Vec2])) -> &mut Self {
+        self.instance_positions.resize(count, Vec2::new(0.0, 0.0));
+        write(&mut self.instance_positions);
+        let positions = &self.instance_positions;
+        let mut mapped = self.mesh.geometry.map_instance_buffer(count, |dst| dst.copy_from_slice(positions)).is_some();
+        if let Some(stroke) = &mut self.stroke_mesh {
+            mapped &= stroke.geometry.map_instance_buffer(count, |dst| dst.copy_from_slice(positions)).is_some();
+        }
+        if !mapped {
+            self.mesh.geometry.update_instance_xy(positions);
+            if let Some(stroke) = &mut self.stroke_mesh {
+                stroke.geometry.update_instance_xy(positions);
+            }
+        }
+        self
+    }
 
-        let fill_geometry = ShapeRenderable::circle_geometry(r, 100, ax, ay);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
+    pub fn set_instance_colors(&mut self, colors: &[Color]) -> &mut Self {
+        self.instance_colors.clear();
+        self.instance_colors.extend_from_slice(colors);
+        self.mesh.geometry.update_instance_colors(colors);
+        self
+    }
 
-        let mut points = ShapeRenderable::circle_outline_points(r, 100, ax, ay);
-        let stroke_mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+    pub fn set_instance_stroke_colors(&mut self, colors: &[Color]) -> &mut Self {
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.update_instance_colors(colors);
+        }
+        self
+    }
 
-        let mut s = ShapeRenderable::new_with_stroke(fill_mesh, stroke_mesh, ShapeKind::Circle(circle));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Per-instance scale and rotation, e.g. marker sizes or arrow
+    /// directions, one per instance position.
+    pub fn set_instance_transforms(&mut self, transforms: &[InstanceTransform]) -> &mut Self {
+        self.instance_transforms.clear();
+        self.instance_transforms.extend_from_slice(transforms);
+        self.mesh.geometry.update_instance_transforms(transforms);
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.update_instance_transforms(transforms);
+        }
+        self
     }
 
-    fn ellipse(ellipse: Ellipse, color: Color, anchor: Anchor) -> Self {
-        let rx = ellipse.radius_x;
-        let ry = ellipse.radius_y;
-        let (ax, ay) = resolve_anchor(anchor, (-rx, -ry), (rx, ry), (0.0, 0.0));
-        let geometry = ShapeRenderable::ellipse_geometry(rx, ry, 64, ax, ay);
-        let mesh = Mesh::with_color(default_shader(), geometry, Some(color));
+    /// Instance positions from the last
+    /// [`set_instance_positions`](Self::set_instance_positions), kept on the
+    /// CPU so exporters and hit tests can see every instance.
+    pub fn instance_positions(&self) -> &[Vec2] {
+        &self.instance_positions
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Ellipse(ellipse));
-        s.x = ax;
-        s.y = ay;
-        s
+    /// Fill colors from the last
+    /// [`set_instance_colors`](Self::set_instance_colors).
+    pub fn instance_colors(&self) -> &[Color] {
+        &self.instance_colors
     }
 
-    fn ellipse_outline(ellipse: Ellipse, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let rx = ellipse.radius_x;
-        let ry = ellipse.radius_y;
-        let (ax, ay) = resolve_anchor(anchor, (-rx, -ry), (rx, ry), (0.0, 0.0));
-        let mut points = ShapeRenderable::ellipse_outline_points(rx, ry, 64, ax, ay);
-        let mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+    /// Transforms from the last
+    /// [`set_instance_transforms`](Self::set_instance_transforms).
+    pub fn instance_transforms(&self) -> &[InstanceTransform] {
+        &self.instance_transforms
+    }
 
-        let mut s = ShapeRenderable::new(mesh, ShapeKind::Ellipse(ellipse));
-        s.x = ax;
-        s.y = ay;
-        s
+    pub fn clear_instances(&mut self) {
+        self.instance_positions.clear();
+        self.instance_colors.clear();
+        self.instance_transforms.clear();
+        self.mesh.geometry.clear_instancing();
+        if let Some(stroke) = &mut self.stroke_mesh {
+            stroke.geometry.clear_instancing();
+        }
     }
 
-    fn ellipse_fill_and_stroke(ellipse: Ellipse, fill: Color, stroke: Color, stroke_width: f32, anchor: Anchor, dash_pattern: Option<(f32, f32)>) -> Self {
-        let rx = ellipse.radius_x;
-        let ry = ellipse.radius_y;
-        let (ax, ay) = resolve_anchor(anchor, (-rx, -ry), (rx, ry), (0.0, 0.0));
+    /// Points along `arc` around its circle's center, the local origin.
+    fn arc_points(arc: &ArcShape) -> Vec<(f32, f32)> {
+        use std::f32::consts::TAU;
 
-        let fill_geometry = ShapeRenderable::ellipse_geometry(rx, ry, 64, ax, ay);
-        let fill_mesh = Mesh::with_color(default_shader(), fill_geometry, Some(fill));
+        let segments = 64;
 
-        let mut points = ShapeRenderable::ellipse_outline_points(rx, ry, 64, ax, ay);
-        let stroke_mesh = ShapeRenderable::stroke_mesh_from_outline(&mut points, stroke, stroke_width, dash_pattern);
+        // Normalize sweep to [0, TAU)
+        let mut sweep = arc.end_angle - arc.start_angle;
+        if sweep < 0.0 {
+            sweep += TAU;
+        }
 
-        let mut s = ShapeRenderable::new_with_stroke(fill_mesh, stroke_mesh, ShapeKind::Ellipse(ellipse));
-        s.x = ax;
-        s.y = ay;
-        s
+        let mut points = Vec::with_capacity(segments + 1);
+        for i in 0..=segments {
+            let t = i as f32 / segments as f32;
+            let theta = arc.start_angle + t * sweep;
+            let px = arc.radius * theta.cos();
+            let py = -arc.radius * theta.sin();
+            points.push((px, py));
+        }
+        points
     }
 
     fn text(text: Text, color: Color, anchor: Anchor) -> Self {
         let font_atlas = get_or_create_font_atlas(&text.font_path, text.font_size);
+        let texture_id = font_atlas.borrow().texture_id();
+        let mut glyphs = TextGlyphs::pin(font_atlas, &text.content);
+        let quads = glyphs.layout(&text, anchor);
 
-        // Generate raw glyph vertices and compute the bbox in one pass.
-        let (mut vertices, bbox_min, bbox_max, texture_id) = {
-            let mut atlas = font_atlas.borrow_mut();
-            let (vs, bmin, bmax) = ShapeRenderable::text_raw_vertices(&text.content, &mut atlas);
-            let tex = atlas.texture_id();
-            (vs, bmin, bmax, tex)
-        };
-
-        // Default anchor for Text is the top-left of the text cell (raw origin).
-        let (ax, ay) = resolve_anchor(anchor, bbox_min, bbox_max, (0.0, 0.0));
-
-        // Apply the anchor shift to the position components (x, y at stride 4).
-        if ax != 0.0 || ay != 0.0 {
-            let stride = 4usize;
-            let mut i = 0;
-            while i + 1 < vertices.len() {
-                vertices[i] -= ax;
-                vertices[i + 1] -= ay;
-                i += stride;
-            }
-        }
-
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, 4);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 4, 0));
-        geometry.add_vertex_attribute(Attribute::new(1, 2, 4, 2));
+        let mut geometry = VertexData::new(GL_TRIANGLES, quads.positions, 2).into_geometry();
+        geometry.add_uv_buffer(&quads.uvs);
 
         let shader = text_shader();
         let mut mesh = Mesh::with_texture(shader, geometry, Some(texture_id));
         mesh.color = Some(color);
 
         let mut s = ShapeRenderable::new(mesh, ShapeKind::Text(text));
-        s.x = ax;
-        s.y = ay;
+        s.measured_bounds = Some(quads.bounds);
+        s.text_glyphs = Some(glyphs);
+        (s.x, s.y) = quads.origin;
+        s.origin = quads.origin;
         s
     }
 
+    /// Re-read a text shape's texture coordinates after its atlas moved
+    /// the glyphs, rewriting only the UV buffer.
+    fn refresh_text_uvs(&mut self) {
+        let (ShapeKind::Text(text), Some(glyphs)) = (&self.shape, &mut self.text_glyphs) else {
+            return;
+        };
+        let quads = glyphs.layout(text, self.anchor);
+        if quads.uvs.len() / 2 == self.mesh.geometry.vertex_count() as usize {
+            self.mesh.geometry.update_uv_buffer(&quads.uvs);
+        } else {
+            // A glyph that did not fit before does now.
+            self.write_glyph_quads(quads);
+        }
+    }
+
+    /// Lay a text shape out again into its existing buffers, keeping its
+    /// pins up to date. False if the shape is not text or its font changed.
+    fn relayout_text(&mut self) -> bool {
+        let (ShapeKind::Text(text), Some(glyphs)) = (&self.shape, &mut self.text_glyphs) else {
+            return false;
+        };
+        let atlas = get_or_create_font_atlas(&text.font_path, text.font_size);
+        if !Rc::ptr_eq(&atlas, &glyphs.atlas) {
+            return false;
+        }
+        glyphs.repin(&text.content);
+        let quads = glyphs.layout(text, self.anchor);
+        self.write_glyph_quads(quads);
+        true
+    }
+
+    fn write_glyph_quads(&mut self, quads: GlyphQuads) {
+        self.mesh.geometry.update_buffer(&quads.positions, 2);
+        self.mesh.geometry.update_uv_buffer(&quads.uvs);
+        self.measured_bounds = Some(quads.bounds);
+        self.origin = quads.origin;
+    }
+
     pub fn image_with_size(path: &str, width: f32, height: f32) -> ShapeRenderable {
-        Self::image_with_size_and_anchor(path, width, height, Anchor::Default)
+        Self::image_with_size_and_anchor(path, width, height, Anchor::Default, &TextureOptions::default())
+    }
+
+    /// Load an image with explicit texture sampling options (filtering, wrap
+    /// modes, mipmaps). Use [`TextureOptions::pixel_art`] for crisp sprites.
+    pub fn image_with_options(path: &str, width: f32, height: f32, options: TextureOptions) -> ShapeRenderable {
+        Self::image_with_size_and_anchor(path, width, height, Anchor::Default, &options)
+    }
+
+    /// Like [`image_with_options`](Self::image_with_options), but returns an
+    /// error if the file cannot be loaded or the image shader fails to
+    /// compile.
+    pub fn try_image_with_options(
+        path: &str,
+        width: f32,
+        height: f32,
+        options: TextureOptions,
+    ) -> Result<ShapeRenderable, WilhelmError> {
+        Self::try_image_with_size_and_anchor(path, width, height, Anchor::Default, &options)
     }
 
     fn image_with_size_and_anchor(
@@ -1115,9 +1746,21 @@ impl ShapeRenderable {
         width: f32,
         height: f32,
         anchor: Anchor,
+        options: &TextureOptions,
     ) -> ShapeRenderable {
-        let image = load_image(path);
-        let texture_id = generate_texture_from_image(&image);
+        Self::try_image_with_size_and_anchor(path, width, height, anchor, options).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_image_with_size_and_anchor(
+        path: &str,
+        width: f32,
+        height: f32,
+        anchor: Anchor,
+        options: &TextureOptions,
+    ) -> Result<ShapeRenderable, WilhelmError> {
+        let shader = try_image_shader()?;
+        let image = try_load_image(path)?;
+        let texture_id = generate_texture_from_image_with_options(&image, options);
 
         // Image geometry is built centered on origin, so bbox = (-w/2..w/2, -h/2..h/2)
         let hw = width * 0.5;
@@ -1125,32 +1768,45 @@ impl ShapeRenderable {
         let (ax, ay) = resolve_anchor(anchor, (-hw, -hh), (hw, hh), (0.0, 0.0));
 
         let geometry = ShapeRenderable::image_geometry(width, height, ax, ay);
-        let shader = image_shader();
         let mesh = Mesh::with_texture(shader, geometry, Some(texture_id));
 
         let mut s =
             ShapeRenderable::new(mesh, ShapeKind::Image(Image::new(width, height)));
         s.x = ax;
         s.y = ay;
-        s
+        s.origin = (ax, ay);
+        s.anchor = anchor;
+        Ok(s)
     }
 
-    pub fn image(path: &str) -> Self {
-        let image = load_image(path);
-        Self::image_with_size(path, image.width as f32, image.height as f32)
+    /// A `width` x `height` quad showing an existing texture, e.g. one made
+    /// with [`generate_texture_from_rgba`](crate::core::generate_texture_from_rgba).
+    /// The texture stays owned by the caller, who deletes it once the shape
+    /// is no longer drawn.
+    pub fn image_from_texture(texture_id: u32, width: f32, height: f32) -> ShapeRenderable {
+        let geometry = ShapeRenderable::image_geometry(width, height, 0.0, 0.0);
+        let mesh = Mesh::with_texture(image_shader(), geometry, Some(texture_id));
+        ShapeRenderable::new(mesh, ShapeKind::Image(Image::new(width, height)))
     }
 
-    fn point_geometry() -> Geometry {
-        let vertex = vec![0.0, 0.0];
-        let mut geometry = Geometry::new(GL_POINTS);
-        geometry.add_buffer(&vertex, 2);
-
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
+    pub fn image(path: &str) -> Self {
+        Self::try_image(path).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        geometry
+    /// Like [`image`](Self::image), but returns an error if the file cannot
+    /// be loaded or the image shader fails to compile.
+    pub fn try_image(path: &str) -> Result<Self, WilhelmError> {
+        let image = try_load_image(path)?;
+        Self::try_image_with_size_and_anchor(
+            path,
+            image.width as f32,
+            image.height as f32,
+            Anchor::Default,
+            &TextureOptions::default(),
+        )
     }
 
-    fn point_list_geometry(points: &[(GLfloat, GLfloat)]) -> Geometry {
+    fn point_list_vertices(points: &[(GLfloat, GLfloat)]) -> VertexData {
         let mut vertices = Vec::with_capacity(points.len() * 2);
 
         for &(x, y) in points {
@@ -1158,35 +1814,23 @@ impl ShapeRenderable {
             vertices.push(y);
         }
 
-        let values_per_vertex = 2;
-
-        let mut geometry = Geometry::new(GL_POINTS);
-        geometry.add_buffer(&vertices, values_per_vertex);
-
-        geometry.add_vertex_attribute(Attribute::new(
-            0, // position
-            values_per_vertex,
-            values_per_vertex as usize,
-            0,
-        ));
-
-        geometry
+        VertexData::new(GL_POINTS, vertices, 2)
     }
 
-    fn line_geometry(
+    fn line_vertices(
         x1: GLfloat,
         y1: GLfloat,
         x2: GLfloat,
         y2: GLfloat,
         stroke_width: f32,
-    ) -> Geometry {
+    ) -> VertexData {
         let stroke_width = stroke_width.max(MIN_STROKE_WIDTH);
         let dx = x2 - x1;
         let dy = y2 - y1;
         let length = (dx * dx + dy * dy).sqrt();
 
         if length == 0.0 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 2);
         }
 
         // Unit perpendicular vector
@@ -1208,18 +1852,7 @@ impl ShapeRenderable {
             v0[0], v0[1], v1[0], v1[1], v2[0], v2[1], v2[0], v2[1], v3[0], v3[1], v0[0], v0[1],
         ];
 
-        let position_values_per_vertex = 2;
-
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, position_values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(
-            0,
-            position_values_per_vertex,
-            position_values_per_vertex as usize,
-            0,
-        ));
-
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 2)
     }
 
     /// Polyline triangulation adapted from JVPolyline by Julien Vernay (2025)
@@ -1230,11 +1863,11 @@ impl ShapeRenderable {
     ///
     /// This implementation is based on the original algorithm,
     /// restructured and translated to idiomatic Rust for use in wilhelm_renderer.
-    fn polyline_geometry(points: &[(GLfloat, GLfloat)], stroke_width: f32) -> Geometry {
+    fn polyline_vertices(points: &[(GLfloat, GLfloat)], stroke_width: f32) -> VertexData {
         const MITER_LIMIT: f32 = 4.0; // Equivalent to JV default
 
         if points.len() < 2 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 2);
         }
 
         let half_thickness = stroke_width.max(1.0) / 2.0;
@@ -1252,7 +1885,7 @@ impl ShapeRenderable {
             }
         }
         if (b.0 - a.0).hypot(b.1 - a.1) == 0.0 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 2);
         }
 
         for i in idx + 1..=points.len() {
@@ -1319,26 +1952,23 @@ impl ShapeRenderable {
             b = c;
         }
 
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, 2);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 2)
     }
 
-    fn line_geometry_dashed(
+    fn line_vertices_dashed(
         x1: GLfloat,
         y1: GLfloat,
         x2: GLfloat,
         y2: GLfloat,
         stroke_width: f32,
-    ) -> Geometry {
+    ) -> VertexData {
         let stroke_width = stroke_width.max(MIN_STROKE_WIDTH);
         let dx = x2 - x1;
         let dy = y2 - y1;
         let length = (dx * dx + dy * dy).sqrt();
 
         if length == 0.0 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 3);
         }
 
         let nx = -dy / length;
@@ -1359,18 +1989,14 @@ impl ShapeRenderable {
             x1 - ox, y1 - oy, 0.0,
         ];
 
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, 3);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 3, 0)); // vec2 position
-        geometry.add_vertex_attribute(Attribute::new(3, 1, 3, 2)); // float distance
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 3)
     }
 
-    fn polyline_geometry_dashed(points: &[(GLfloat, GLfloat)], stroke_width: f32) -> Geometry {
+    fn polyline_vertices_dashed(points: &[(GLfloat, GLfloat)], stroke_width: f32) -> VertexData {
         const MITER_LIMIT: f32 = 4.0;
 
         if points.len() < 2 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 3);
         }
 
         let half_thickness = stroke_width.max(1.0) / 2.0;
@@ -1399,7 +2025,7 @@ impl ShapeRenderable {
             }
         }
         if (b.0 - a.0).hypot(b.1 - a.1) == 0.0 {
-            return Geometry::new(GL_TRIANGLES);
+            return VertexData::new(GL_TRIANGLES, Vec::new(), 3);
         }
 
         for i in idx + 1..=points.len() {
@@ -1473,11 +2099,7 @@ impl ShapeRenderable {
             }
         }
 
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, 3);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 3, 0)); // vec2 position
-        geometry.add_vertex_attribute(Attribute::new(3, 1, 3, 2)); // float distance
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 3)
     }
 
     /// Generate outline points for a circle as a closed polyline.
@@ -1534,17 +2156,12 @@ impl ShapeRenderable {
         points
     }
 
-    fn triangle_geometry(vertices: &[(f32, f32); 3]) -> Geometry {
-        let mut geometry = Geometry::new(GL_TRIANGLES);
+    fn triangle_vertices(vertices: &[(f32, f32); 3]) -> VertexData {
         let flattened: Vec<f32> = vertices.iter().flat_map(|(x, y)| [*x, *y]).collect();
-
-        geometry.add_buffer(&flattened, 2);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
-
-        geometry
+        VertexData::new(GL_TRIANGLES, flattened, 2)
     }
 
-    fn rectangle_geometry(width: GLfloat, height: GLfloat, ox: GLfloat, oy: GLfloat) -> Geometry {
+    fn rectangle_vertices(width: GLfloat, height: GLfloat, ox: GLfloat, oy: GLfloat) -> VertexData {
         let vertices: Vec<GLfloat> = vec![
             0.0 - ox, 0.0 - oy,
             width - ox, 0.0 - oy,
@@ -1552,14 +2169,10 @@ impl ShapeRenderable {
             width - ox, height - oy,
         ];
 
-        let values_per_vertex = 2;
-        let mut geometry = Geometry::new(GL_TRIANGLE_STRIP);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry
+        VertexData::new(GL_TRIANGLE_STRIP, vertices, 2)
     }
 
-    fn circle_geometry(radius: GLfloat, segments: usize, ox: GLfloat, oy: GLfloat) -> Geometry {
+    fn circle_vertices(radius: GLfloat, segments: usize, ox: GLfloat, oy: GLfloat) -> VertexData {
         let mut vertices: Vec<GLfloat> = Vec::with_capacity((segments + 2) * 2);
 
         // Center of the circle
@@ -1572,32 +2185,7 @@ impl ShapeRenderable {
             vertices.extend_from_slice(&[x, y]);
         }
 
-        let values_per_vertex = 2;
-        let mut geometry = Geometry::new(GL_TRIANGLE_FAN);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry
-    }
-
-    fn ellipse_geometry(rx: f32, ry: f32, segments: usize, ox: f32, oy: f32) -> Geometry {
-        use std::f32::consts::PI;
-
-        let mut vertices: Vec<GLfloat> = Vec::with_capacity((segments + 2) * 2);
-
-        vertices.extend_from_slice(&[0.0 - ox, 0.0 - oy]);
-
-        for i in 0..=segments {
-            let angle = 2.0 * PI * (i as f32) / (segments as f32);
-            let x = rx * angle.cos() - ox;
-            let y = ry * angle.sin() - oy;
-            vertices.extend_from_slice(&[x, y]);
-        }
-
-        let values_per_vertex = 2;
-        let mut geometry = Geometry::new(GL_TRIANGLE_FAN);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry
+        VertexData::new(GL_TRIANGLE_FAN, vertices, 2)
     }
 
     pub fn rounded_rectangle_geometry(
@@ -1608,6 +2196,17 @@ impl ShapeRenderable {
         ox: f32,
         oy: f32,
     ) -> Geometry {
+        ShapeRenderable::rounded_rectangle_vertices(width, height, radius, segments, ox, oy).into_geometry()
+    }
+
+    fn rounded_rectangle_vertices(
+        width: f32,
+        height: f32,
+        radius: f32,
+        segments: usize,
+        ox: f32,
+        oy: f32,
+    ) -> VertexData {
         assert!(radius * 2.0 <= width && radius * 2.0 <= height);
 
         let mut vertices: Vec<GLfloat> = Vec::new();
@@ -1651,17 +2250,28 @@ impl ShapeRenderable {
         vertices.push(first_arc_x);
         vertices.push(first_arc_y);
 
-        let values_per_vertex = 2;
-        let mut geometry = Geometry::new(GL_TRIANGLE_FAN);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry
+        VertexData::new(GL_TRIANGLE_FAN, vertices, 2)
     }
 
-    fn polygon_geometry(
-        points: &[(GLfloat, GLfloat)],
-        triangles: &[[usize; 3]],
-    ) -> Geometry {
+    /// Triangle-fan vertices for an ellipse centred at `(-ox, -oy)`.
+    fn ellipse_vertices(rx: f32, ry: f32, segments: usize, ox: f32, oy: f32) -> VertexData {
+        use std::f32::consts::PI;
+
+        let mut vertices: Vec<GLfloat> = Vec::with_capacity((segments + 2) * 2);
+
+        vertices.extend_from_slice(&[0.0 - ox, 0.0 - oy]);
+
+        for i in 0..=segments {
+            let angle = 2.0 * PI * (i as f32) / (segments as f32);
+            let x = rx * angle.cos() - ox;
+            let y = ry * angle.sin() - oy;
+            vertices.extend_from_slice(&[x, y]);
+        }
+        VertexData::new(GL_TRIANGLE_FAN, vertices, 2)
+    }
+
+    /// Flat `GL_TRIANGLES` vertex list for a triangulated polygon.
+    fn polygon_vertices(points: &[(GLfloat, GLfloat)], triangles: &[[usize; 3]]) -> VertexData {
         assert!(points.len() >= 3, "Polygon requires at least 3 points");
         assert!(
             !triangles.is_empty(),
@@ -1678,15 +2288,14 @@ impl ShapeRenderable {
             let (cx, cy) = points[c];
             vertices.extend_from_slice(&[ax, ay, bx, by, cx, cy]);
         }
-
-        let values_per_vertex = 2;
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 2)
     }
 
     pub fn image_geometry(width: f32, height: f32, ox: f32, oy: f32) -> Geometry {
+        ShapeRenderable::image_vertices(width, height, ox, oy).into_geometry()
+    }
+
+    fn image_vertices(width: f32, height: f32, ox: f32, oy: f32) -> VertexData {
         // Vertex format: [x, y, u, v]. Geometry built centered at origin,
         // then shifted by (-ox, -oy) so the resolved anchor sits at local (0, 0).
         let hw = width / 2.0;
@@ -1702,68 +2311,59 @@ impl ShapeRenderable {
             -hw - ox,  hh - oy, 0.0, 1.0, // top-left
         ];
 
-        let values_per_vertex = 4;
-
-        let mut geometry = Geometry::new(GL_TRIANGLES);
-        geometry.add_buffer(&vertices, values_per_vertex);
-        geometry.add_vertex_attribute(Attribute::new(0, 2, values_per_vertex as usize, 0));
-        geometry.add_vertex_attribute(Attribute::new(1, 2, values_per_vertex as usize, 2));
-
-        geometry
+        VertexData::new(GL_TRIANGLES, vertices, 4)
     }
 
-    /// Build raw textured-quad vertices for a string of text and compute the
-    /// bounding box over all glyph quads. Returns `(vertices, bbox_min, bbox_max)`.
-    /// Local origin is the top-left of the text cell (cursor_x = 0, y = 0).
-    fn text_raw_vertices(
-        text: &str,
-        font_atlas: &mut FontAtlas,
-    ) -> (Vec<f32>, (f32, f32), (f32, f32)) {
-        let mut vertices: Vec<f32> = Vec::new();
-        let mut cursor_x: f32 = 0.0;
-        let baseline_y: f32 = font_atlas.font_size() as f32;
+    /// Build raw textured-quad positions and texture coordinates for a
+    /// string of text, broken into lines and aligned by `layout`, and
+    /// compute the bounding box over all glyph quads, not yet shifted by an
+    /// anchor. With the default layout the local origin is the top-left of
+    /// the first line's text cell (cursor_x = 0, y = 0).
+    fn text_raw_vertices(text: &str, layout: &TextLayout, font_atlas: &mut FontAtlas) -> GlyphQuads {
+        let mut positions: Vec<f32> = Vec::new();
+        let mut uvs: Vec<f32> = Vec::new();
+        let font_size = font_atlas.font_size() as f32;
+        let lines = layout.break_lines(text, |ch| font_atlas.get_glyph(ch).map_or(0.0, |g| g.advance));
+        let first_baseline = layout.first_baseline(lines.len(), font_size);
 
         let mut min_x = f32::INFINITY;
         let mut min_y = f32::INFINITY;
         let mut max_x = f32::NEG_INFINITY;
         let mut max_y = f32::NEG_INFINITY;
 
-        for ch in text.chars() {
-            if let Some(glyph) = font_atlas.get_glyph(ch) {
-                if glyph.width == 0 || glyph.height == 0 {
+        for (line, (range, width)) in lines.into_iter().enumerate() {
+            let mut cursor_x = layout.line_x(width);
+            let baseline_y = first_baseline + line as f32 * layout.line_pitch(font_size);
+
+            for ch in text[range].chars() {
+                if let Some(glyph) = font_atlas.get_glyph(ch) {
+                    if glyph.width == 0 || glyph.height == 0 {
+                        cursor_x += glyph.advance;
+                        continue;
+                    }
+
+                    let x0 = cursor_x + glyph.bearing_x as f32;
+                    let y0 = baseline_y - glyph.bearing_y as f32;
+                    let x1 = x0 + glyph.width as f32;
+                    let y1 = y0 + glyph.height as f32;
+
+                    if x0 < min_x { min_x = x0; }
+                    if y0 < min_y { min_y = y0; }
+                    if x1 > max_x { max_x = x1; }
+                    if y1 > max_y { max_y = y1; }
+
+                    let u0 = glyph.uv_x;
+                    let v0 = glyph.uv_y;
+                    let u1 = glyph.uv_x + glyph.uv_width;
+                    let v1 = glyph.uv_y + glyph.uv_height;
+
+                    // Triangle 1: bottom-left, bottom-right, top-right
+                    // Triangle 2: bottom-left, top-right, top-left
+                    positions.extend_from_slice(&[x0, y1, x1, y1, x1, y0, x0, y1, x1, y0, x0, y0]);
+                    uvs.extend_from_slice(&[u0, v1, u1, v1, u1, v0, u0, v1, u1, v0, u0, v0]);
+
                     cursor_x += glyph.advance;
-                    continue;
                 }
-
-                let x0 = cursor_x + glyph.bearing_x as f32;
-                let y0 = baseline_y - glyph.bearing_y as f32;
-                let x1 = x0 + glyph.width as f32;
-                let y1 = y0 + glyph.height as f32;
-
-                if x0 < min_x { min_x = x0; }
-                if y0 < min_y { min_y = y0; }
-                if x1 > max_x { max_x = x1; }
-                if y1 > max_y { max_y = y1; }
-
-                let u0 = glyph.uv_x;
-                let v0 = glyph.uv_y;
-                let u1 = glyph.uv_x + glyph.uv_width;
-                let v1 = glyph.uv_y + glyph.uv_height;
-
-                // Triangle 1: bottom-left, bottom-right, top-right
-                vertices.extend_from_slice(&[
-                    x0, y1, u0, v1,
-                    x1, y1, u1, v1,
-                    x1, y0, u1, v0,
-                ]);
-                // Triangle 2: bottom-left, top-right, top-left
-                vertices.extend_from_slice(&[
-                    x0, y1, u0, v1,
-                    x1, y0, u1, v0,
-                    x0, y0, u0, v0,
-                ]);
-
-                cursor_x += glyph.advance;
             }
         }
 
@@ -1773,7 +2373,7 @@ impl ShapeRenderable {
             ((0.0, 0.0), (0.0, 0.0))
         };
 
-        (vertices, bbox_min, bbox_max)
+        GlyphQuads { positions, uvs, bounds: (bbox_min, bbox_max), origin: (0.0, 0.0) }
     }
 
 }
@@ -1793,6 +2393,8 @@ pub struct ShapeRenderableBuilder {
     shape: ShapeKind,
     style: ShapeStyle,
     anchor: Anchor,
+    pivot: Anchor,
+    z_order: i32,
 }
 
 impl ShapeRenderableBuilder {
@@ -1802,8 +2404,84 @@ impl ShapeRenderableBuilder {
         self
     }
 
+    /// Set the rotation and scaling pivot; see
+    /// [`ShapeRenderable::set_pivot`]. Defaults to the anchor.
+    pub fn pivot(mut self, pivot: Anchor) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Set the z-order (higher draws on top). Defaults to 0.
+    pub fn z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
     /// Build the `ShapeRenderable`, consuming the builder.
     pub fn build(self) -> ShapeRenderable {
-        ShapeRenderable::from_shape_with_anchor(self.shape, self.style, self.anchor)
+        let mut renderable = ShapeRenderable::from_shape_with_anchor(self.shape, self.style, self.anchor);
+        renderable.z_order = self.z_order;
+        renderable.pivot = self.pivot;
+        renderable
+    }
+
+    /// Build the `ShapeRenderable`, returning an error instead of panicking
+    /// on invalid geometry, a missing font or a shader compile failure.
+    pub fn try_build(self) -> Result<ShapeRenderable, WilhelmError> {
+        ShapeRenderable::prepare(&self.shape, &self.style)?;
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics2d::shapes::{Circle, Rectangle};
+
+    #[test]
+    fn resized_shape_keeps_its_vertex_layout() {
+        let style = ShapeStyle::fill_and_stroke(Color::white(), Color::black(), 2.0);
+        let small = ShapeRenderable::shape_vertices(&ShapeKind::Rectangle(Rectangle::new(10.0, 5.0)), &style, Anchor::Center)
+            .unwrap();
+        let large = ShapeRenderable::shape_vertices(&ShapeKind::Rectangle(Rectangle::new(40.0, 20.0)), &style, Anchor::Center)
+            .unwrap();
+
+        assert_eq!((small.main.mode, small.main.values_per_vertex), (large.main.mode, large.main.values_per_vertex));
+        assert_eq!(small.main.vertices.len(), large.main.vertices.len());
+        assert_eq!(large.origin, (20.0, 10.0));
+        let (small_stroke, large_stroke) = (small.stroke.unwrap(), large.stroke.unwrap());
+        assert_eq!(small_stroke.values_per_vertex, large_stroke.values_per_vertex);
+    }
+
+    #[test]
+    fn outline_only_shape_draws_its_stroke_as_the_main_mesh() {
+        let circle = ShapeKind::Circle(Circle::new(10.0));
+        let solid = ShapeRenderable::shape_vertices(&circle, &ShapeStyle::stroke(Color::white(), 2.0), Anchor::Default)
+            .unwrap();
+        let dashed = ShapeRenderable::shape_vertices(
+            &circle,
+            &ShapeStyle::dashed_stroke(Color::white(), 2.0, 4.0, 2.0),
+            Anchor::Default,
+        )
+        .unwrap();
+
+        assert!(solid.stroke.is_none() && dashed.stroke.is_none());
+        assert_eq!((solid.main.mode, solid.main.values_per_vertex), (GL_TRIANGLES, 2));
+        // Dashed strokes carry the distance along the outline.
+        assert_eq!(dashed.main.values_per_vertex, 3);
+    }
+
+    #[test]
+    fn new_anchor_only_shifts_the_vertices() {
+        let shape = ShapeKind::Rectangle(Rectangle::new(10.0, 4.0));
+        let style = ShapeStyle::fill(Color::white());
+        let corner = ShapeRenderable::shape_vertices(&shape, &style, Anchor::Default).unwrap();
+        let center = ShapeRenderable::shape_vertices(&shape, &style, Anchor::Center).unwrap();
+
+        assert_eq!(center.origin, (5.0, 2.0));
+        assert_eq!(corner.main.vertices.len(), center.main.vertices.len());
+        for (a, b) in corner.main.vertices.chunks(2).zip(center.main.vertices.chunks(2)) {
+            assert_eq!((a[0] - 5.0, a[1] - 2.0), (b[0], b[1]));
+        }
     }
 }
@@ -0,0 +1,228 @@
+//! Polyline simplification and scale-dependent level of detail.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::graphics2d::shapes::Polyline;
+
+/// Default largest on-screen deviation, in pixels, of the level picked by
+/// [`PolylineLod`].
+const DEFAULT_PIXEL_TOLERANCE: f32 = 0.5;
+/// Cap on the number of levels [`PolylineLod::new`] builds.
+const MAX_LEVELS: usize = 32;
+
+/// Douglas-Peucker simplification: drop points until every removed point
+/// lies within `tolerance` of the simplified line. Keeps the endpoints and
+/// returns a subset of `points` in order.
+pub fn simplify_douglas_peucker(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    // Explicit stack: tracks with hundreds of thousands of points would
+    // overflow a recursive implementation.
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let (mut farthest, mut max_distance) = (first, 0.0);
+        for i in first + 1..last {
+            let d = segment_distance(points[i], points[first], points[last]);
+            if d > max_distance {
+                (farthest, max_distance) = (i, d);
+            }
+        }
+        if max_distance > tolerance {
+            keep[farthest] = true;
+            stack.push((first, farthest));
+            stack.push((farthest, last));
+        }
+    }
+    points.iter().zip(keep).filter_map(|(p, k)| k.then_some(*p)).collect()
+}
+
+/// Visvalingam-Whyatt simplification: repeatedly drop the point forming
+/// the smallest triangle with its neighbours until every remaining triangle
+/// has an area of at least `min_area`. Smooths more gracefully than
+/// Douglas-Peucker at strong simplification. Keeps the endpoints.
+pub fn simplify_visvalingam(points: &[(f32, f32)], min_area: f32) -> Vec<(f32, f32)> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    let mut prev: Vec<usize> = (0..n).map(|i| i.wrapping_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=n).collect();
+    let mut removed = vec![false; n];
+    let area = |prev: &[usize], next: &[usize], i: usize| triangle_area(points[prev[i]], points[i], points[next[i]]);
+
+    let mut current: Vec<f32> = (0..n).map(|i| if i == 0 || i == n - 1 { f32::INFINITY } else { area(&prev, &next, i) }).collect();
+    let mut heap: BinaryHeap<Candidate> = (1..n - 1).map(|i| Candidate { area: current[i], index: i }).collect();
+    // A neighbour's area is raised to at least the area just removed, so
+    // points are removed in order of their effective area.
+    while let Some(Candidate { area: smallest, index }) = heap.pop() {
+        if removed[index] || smallest != current[index] {
+            continue; // stale entry
+        }
+        if smallest >= min_area {
+            break;
+        }
+        removed[index] = true;
+        let (p, q) = (prev[index], next[index]);
+        next[p] = q;
+        prev[q] = p;
+        for neighbour in [p, q] {
+            if neighbour != 0 && neighbour != n - 1 {
+                current[neighbour] = area(&prev, &next, neighbour).max(smallest);
+                heap.push(Candidate { area: current[neighbour], index: neighbour });
+            }
+        }
+    }
+    points.iter().zip(removed).filter_map(|(p, r)| (!r).then_some(*p)).collect()
+}
+
+impl Polyline {
+    /// A copy simplified with [`simplify_douglas_peucker`].
+    pub fn simplified(&self, tolerance: f32) -> Polyline {
+        Polyline::new(simplify_douglas_peucker(&self.points, tolerance))
+    }
+}
+
+/// Pre-simplified versions of a long polyline, so drawing it zoomed out
+/// does not tessellate points that would all land on the same pixel.
+///
+/// Level 0 is the original; each further level is simplified with twice
+/// the tolerance of the one before. [`points_for_scale`](Self::points_for_scale)
+/// picks the coarsest level whose deviation stays below half a pixel (see
+/// [`set_pixel_tolerance`](Self::set_pixel_tolerance)) at the camera's
+/// scale.
+///
+/// ```ignore
+/// let track = PolylineLod::new(world_points, 0.5); // finest level: 0.5 m
+/// // each frame
+/// let points = track.points_for_scale(camera.scale());
+/// ```
+pub struct PolylineLod {
+    /// `(tolerance, points)` from finest to coarsest.
+    levels: Vec<(f32, Vec<(f32, f32)>)>,
+    pixel_tolerance: f32,
+}
+
+impl PolylineLod {
+    /// Build levels starting at `min_tolerance` (in the points' units)
+    /// and doubling until simplification reaches the two endpoints.
+    pub fn new(points: Vec<(f32, f32)>, min_tolerance: f32) -> Self {
+        let mut levels = vec![(0.0, points)];
+        let mut tolerance = min_tolerance.max(f32::EPSILON);
+        while levels.len() < MAX_LEVELS && levels[levels.len() - 1].1.len() > 2 {
+            let simplified = simplify_douglas_peucker(&levels[0].1, tolerance);
+            if simplified.len() < levels[levels.len() - 1].1.len() {
+                levels.push((tolerance, simplified));
+            }
+            tolerance *= 2.0;
+        }
+        Self { levels, pixel_tolerance: DEFAULT_PIXEL_TOLERANCE }
+    }
+
+    /// Largest deviation from the original allowed on screen, in pixels
+    /// (default 0.5).
+    pub fn set_pixel_tolerance(&mut self, pixels: f32) -> &mut Self {
+        self.pixel_tolerance = pixels.max(0.0);
+        self
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Points of level `index` (0 is the original).
+    pub fn level(&self, index: usize) -> &[(f32, f32)] {
+        &self.levels[index.min(self.levels.len() - 1)].1
+    }
+
+    /// The level to draw at `scale` pixels per unit.
+    pub fn level_for_scale(&self, scale: f32) -> usize {
+        let allowed = self.pixel_tolerance / scale.max(f32::MIN_POSITIVE);
+        self.levels.partition_point(|(tolerance, _)| *tolerance <= allowed).saturating_sub(1)
+    }
+
+    /// The points to draw at `scale` pixels per unit.
+    pub fn points_for_scale(&self, scale: f32) -> &[(f32, f32)] {
+        self.level(self.level_for_scale(scale))
+    }
+}
+
+struct Candidate {
+    area: f32,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    /// Reversed, so the max-heap pops the smallest area first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.area.total_cmp(&self.area).then(other.index.cmp(&self.index))
+    }
+}
+
+fn triangle_area(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() * 0.5
+}
+
+/// Distance from `p` to the segment `a`-`b`.
+fn segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zigzag(n: usize, amplitude: f32) -> Vec<(f32, f32)> {
+        (0..n).map(|i| (i as f32, if i % 2 == 0 { 0.0 } else { amplitude })).collect()
+    }
+
+    #[test]
+    fn douglas_peucker_keeps_corners_and_drops_noise() {
+        let mut points = zigzag(11, 0.1);
+        points.push((10.0, 10.0));
+        let simplified = simplify_douglas_peucker(&points, 0.5);
+        assert_eq!(simplified, vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        assert_eq!(simplify_douglas_peucker(&points, 0.01), points);
+    }
+
+    #[test]
+    fn visvalingam_removes_smallest_triangles_first() {
+        let points = vec![(0.0, 0.0), (1.0, 0.05), (2.0, 0.0), (3.0, 2.0), (4.0, 0.0)];
+        assert_eq!(simplify_visvalingam(&points, 0.5), vec![(0.0, 0.0), (2.0, 0.0), (3.0, 2.0), (4.0, 0.0)]);
+        assert_eq!(simplify_visvalingam(&points, 100.0), vec![(0.0, 0.0), (4.0, 0.0)]);
+        assert_eq!(simplify_visvalingam(&points[..2], 100.0).len(), 2);
+    }
+
+    #[test]
+    fn lod_picks_coarser_levels_when_zoomed_out() {
+        let points: Vec<(f32, f32)> = (0..1000).map(|i| (i as f32, (i as f32 * 0.05).sin() * 50.0)).collect();
+        let lod = PolylineLod::new(points.clone(), 0.01);
+        assert!(lod.level_count() > 5);
+        assert_eq!(lod.points_for_scale(1000.0), &points[..]);
+        let zoomed_out = lod.points_for_scale(0.001);
+        assert_eq!(zoomed_out.len(), 2);
+        let sizes: Vec<usize> = [100.0, 1.0, 0.1, 0.01].iter().map(|&s| lod.points_for_scale(s).len()).collect();
+        assert!(sizes.windows(2).all(|w| w[0] >= w[1]) && sizes[0] > sizes[3]);
+    }
+}
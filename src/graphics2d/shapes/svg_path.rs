@@ -0,0 +1,459 @@
+use crate::graphics2d::shapes::{Polygon, Polyline, ShapeKind};
+
+/// Length of the straight segments curves are flattened into, in path
+/// units.
+const DEFAULT_STEP: f32 = 2.0;
+/// Upper bound on segments per curve command.
+const MAX_CURVE_SEGMENTS: usize = 256;
+
+/// One subpath of a parsed path: the flattened points from a moveto to the
+/// next moveto or the end, and whether it was closed with `Z`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subpath {
+    pub points: Vec<(f32, f32)>,
+    pub closed: bool,
+}
+
+/// Geometry parsed from SVG path data (the `d` attribute of `<path>`).
+///
+/// All commands are supported in absolute and relative form: `M L H V Z`,
+/// cubic (`C S`) and quadratic (`Q T`) Béziers and elliptical arcs (`A`).
+/// Curves are flattened into straight segments about 2 units long (see
+/// [`parse_with_step`](Self::parse_with_step)). Coordinates are kept as
+/// written, with y down as in SVG and in screen space.
+///
+/// ```ignore
+/// let icon = SvgPath::parse("M12 2 L22 20 H2 Z M12 8 v6")?;
+/// for shape in icon.to_shapes() {
+///     app.add_shape(ShapeRenderable::from_shape(shape, ShapeStyle::stroke(Color::WHITE, 1.5)));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SvgPath {
+    subpaths: Vec<Subpath>,
+}
+
+impl SvgPath {
+    /// Parse path data, flattening curves into ~2 unit segments.
+    pub fn parse(d: &str) -> Result<Self, String> {
+        Self::parse_with_step(d, DEFAULT_STEP)
+    }
+
+    /// Parse path data, flattening curves into segments about `step` units
+    /// long. Smaller steps give smoother curves and more vertices.
+    pub fn parse_with_step(d: &str, step: f32) -> Result<Self, String> {
+        Parser { tokens: Tokens::new(d), path: Builder::new(step.max(0.01)) }.run()
+    }
+
+    pub fn subpaths(&self) -> &[Subpath] {
+        &self.subpaths
+    }
+
+    /// One shape per subpath: closed subpaths with at least 3 points become
+    /// a `Polygon`, the others a `Polyline`. Subpaths with fewer than 2
+    /// points are skipped.
+    pub fn to_shapes(&self) -> Vec<ShapeKind> {
+        self.subpaths
+            .iter()
+            .filter(|s| s.points.len() >= 2)
+            .map(|s| {
+                if s.closed && s.points.len() >= 3 {
+                    ShapeKind::Polygon(Polygon::new(s.points.clone()))
+                } else {
+                    let mut points = s.points.clone();
+                    if s.closed {
+                        points.push(points[0]);
+                    }
+                    ShapeKind::Polyline(Polyline::new(points))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Splits path data into command letters, numbers and arc flags.
+struct Tokens<'d> {
+    src: &'d [u8],
+    pos: usize,
+}
+
+impl<'d> Tokens<'d> {
+    fn new(d: &'d str) -> Self {
+        Self { src: d.as_bytes(), pos: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.src.len() && (self.src[self.pos].is_ascii_whitespace() || self.src[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.src.len()
+    }
+
+    /// The next command letter, if the next token is one.
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let c = *self.src.get(self.pos)?;
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            self.pos += 1;
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a number follows (so the previous command repeats).
+    fn number_follows(&mut self) -> bool {
+        self.skip_separators();
+        matches!(self.src.get(self.pos), Some(c) if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.'))
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_separators();
+        let start = self.pos;
+        let bytes = self.src;
+        let mut i = self.pos;
+        if matches!(bytes.get(i), Some(b'-' | b'+')) {
+            i += 1;
+        }
+        let mut seen_dot = false;
+        let mut digits = 0;
+        while let Some(&c) = bytes.get(i) {
+            if c.is_ascii_digit() {
+                digits += 1;
+            } else if c == b'.' && !seen_dot {
+                seen_dot = true;
+            } else {
+                break;
+            }
+            i += 1;
+        }
+        if digits > 0 && matches!(bytes.get(i), Some(b'e' | b'E')) {
+            let mut j = i + 1;
+            if matches!(bytes.get(j), Some(b'-' | b'+')) {
+                j += 1;
+            }
+            if bytes.get(j).is_some_and(u8::is_ascii_digit) {
+                while bytes.get(j).is_some_and(u8::is_ascii_digit) {
+                    j += 1;
+                }
+                i = j;
+            }
+        }
+        let text = std::str::from_utf8(&bytes[start..i]).unwrap_or("");
+        let value = text.parse::<f32>().map_err(|_| format!("expected a number at offset {start}"))?;
+        self.pos = i;
+        Ok(value)
+    }
+
+    /// An arc flag: a single `0` or `1`, which may run into the next token.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+        match self.src.get(self.pos) {
+            Some(b'0') => { self.pos += 1; Ok(false) }
+            Some(b'1') => { self.pos += 1; Ok(true) }
+            _ => Err(format!("expected an arc flag at offset {}", self.pos)),
+        }
+    }
+}
+
+/// Accumulates flattened subpaths.
+struct Builder {
+    step: f32,
+    subpaths: Vec<Subpath>,
+    current: (f32, f32),
+    start: (f32, f32),
+}
+
+impl Builder {
+    fn new(step: f32) -> Self {
+        Self { step, subpaths: Vec::new(), current: (0.0, 0.0), start: (0.0, 0.0) }
+    }
+
+    fn move_to(&mut self, p: (f32, f32)) {
+        self.subpaths.push(Subpath { points: vec![p], closed: false });
+        self.current = p;
+        self.start = p;
+    }
+
+    fn line_to(&mut self, p: (f32, f32)) {
+        if self.subpaths.last().is_none_or(|s| s.closed) {
+            // Drawing after Z (or with no moveto) starts at the last start.
+            let start = self.start;
+            self.move_to(start);
+        }
+        let points = &mut self.subpaths.last_mut().unwrap().points;
+        if points.last() != Some(&p) {
+            points.push(p);
+        }
+        self.current = p;
+    }
+
+    fn close(&mut self) {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            if subpath.points.len() > 1 && subpath.points.last() == subpath.points.first() {
+                subpath.points.pop();
+            }
+            subpath.closed = true;
+        }
+        self.current = self.start;
+    }
+
+    fn segments(&self, length: f32) -> usize {
+        ((length / self.step).ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+    }
+
+    fn cubic_to(&mut self, c1: (f32, f32), c2: (f32, f32), p: (f32, f32)) {
+        let p0 = self.current;
+        let n = self.segments(dist(p0, c1) + dist(c1, c2) + dist(c2, p));
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.line_to((
+                a * p0.0 + b * c1.0 + c * c2.0 + d * p.0,
+                a * p0.1 + b * c1.1 + c * c2.1 + d * p.1,
+            ));
+        }
+    }
+
+    fn quad_to(&mut self, c: (f32, f32), p: (f32, f32)) {
+        let p0 = self.current;
+        let n = self.segments(dist(p0, c) + dist(c, p));
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let (a, b, d) = (u * u, 2.0 * u * t, t * t);
+            self.line_to((a * p0.0 + b * c.0 + d * p.0, a * p0.1 + b * c.1 + d * p.1));
+        }
+    }
+
+    /// Elliptical arc, converted from SVG's endpoint form to centre form
+    /// (SVG 1.1, appendix F.6.5).
+    fn arc_to(&mut self, radii: (f32, f32), rotation_deg: f32, large_arc: bool, sweep: bool, p: (f32, f32)) {
+        let p0 = self.current;
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if p0 == p {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+        let phi = rotation_deg.to_radians();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let dx = (p0.0 - p.0) * 0.5;
+        let dy = (p0.1 - p.1) * 0.5;
+        let x1 = cos_phi * dx + sin_phi * dy;
+        let y1 = -sin_phi * dx + cos_phi * dy;
+
+        // Scale up radii that are too small to reach the endpoint.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            let s = lambda.sqrt();
+            rx *= s;
+            ry *= s;
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let cx = cos_phi * cx1 - sin_phi * cy1 + (p0.0 + p.0) * 0.5;
+        let cy = sin_phi * cx1 + cos_phi * cy1 + (p0.1 + p.1) * 0.5;
+
+        let angle = |ux: f32, uy: f32| uy.atan2(ux);
+        let theta1 = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - theta1;
+        let tau = std::f32::consts::TAU;
+        if sweep && delta < 0.0 {
+            delta += tau;
+        } else if !sweep && delta > 0.0 {
+            delta -= tau;
+        }
+
+        let n = self.segments(delta.abs() * rx.max(ry));
+        for i in 1..=n {
+            if i == n {
+                self.line_to(p);
+                break;
+            }
+            let theta = theta1 + delta * i as f32 / n as f32;
+            let (sin_t, cos_t) = theta.sin_cos();
+            self.line_to((
+                cx + rx * cos_t * cos_phi - ry * sin_t * sin_phi,
+                cy + rx * cos_t * sin_phi + ry * sin_t * cos_phi,
+            ));
+        }
+    }
+}
+
+struct Parser<'d> {
+    tokens: Tokens<'d>,
+    path: Builder,
+}
+
+impl Parser<'_> {
+    fn run(mut self) -> Result<SvgPath, String> {
+        // Reflected control points for S and T.
+        let mut last_cubic: Option<(f32, f32)> = None;
+        let mut last_quad: Option<(f32, f32)> = None;
+        let mut command = None;
+
+        while !self.tokens.at_end() {
+            let cmd = match self.tokens.command() {
+                Some(c) => c,
+                // Implicit repeat; a repeated moveto is a lineto.
+                None => match command {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(c) if c != b'Z' && c != b'z' && self.tokens.number_follows() => c,
+                    _ => return Err(format!("expected a command at offset {}", self.tokens.pos)),
+                },
+            };
+            if command.is_none() && !matches!(cmd, b'M' | b'm') {
+                return Err("path data must start with a moveto".to_string());
+            }
+            command = Some(cmd);
+
+            let relative = cmd.is_ascii_lowercase();
+            let origin = if relative { self.path.current } else { (0.0, 0.0) };
+            let (cubic, quad) = (last_cubic.take(), last_quad.take());
+            match cmd.to_ascii_uppercase() {
+                b'M' => {
+                    let p = self.point(origin)?;
+                    self.path.move_to(p);
+                }
+                b'L' => {
+                    let p = self.point(origin)?;
+                    self.path.line_to(p);
+                }
+                b'H' => {
+                    let x = self.tokens.number()? + origin.0;
+                    self.path.line_to((x, self.path.current.1));
+                }
+                b'V' => {
+                    let y = self.tokens.number()? + origin.1;
+                    self.path.line_to((self.path.current.0, y));
+                }
+                b'Z' => self.path.close(),
+                b'C' => {
+                    let (c1, c2, p) = (self.point(origin)?, self.point(origin)?, self.point(origin)?);
+                    self.path.cubic_to(c1, c2, p);
+                    last_cubic = Some(c2);
+                }
+                b'S' => {
+                    let c1 = reflect(cubic, self.path.current);
+                    let (c2, p) = (self.point(origin)?, self.point(origin)?);
+                    self.path.cubic_to(c1, c2, p);
+                    last_cubic = Some(c2);
+                }
+                b'Q' => {
+                    let (c, p) = (self.point(origin)?, self.point(origin)?);
+                    self.path.quad_to(c, p);
+                    last_quad = Some(c);
+                }
+                b'T' => {
+                    let c = reflect(quad, self.path.current);
+                    let p = self.point(origin)?;
+                    self.path.quad_to(c, p);
+                    last_quad = Some(c);
+                }
+                b'A' => {
+                    let rx = self.tokens.number()?;
+                    let ry = self.tokens.number()?;
+                    let rotation = self.tokens.number()?;
+                    let large_arc = self.tokens.flag()?;
+                    let sweep = self.tokens.flag()?;
+                    let p = self.point(origin)?;
+                    self.path.arc_to((rx, ry), rotation, large_arc, sweep, p);
+                }
+                other => return Err(format!("unknown path command `{}`", other as char)),
+            }
+        }
+        Ok(SvgPath { subpaths: self.path.subpaths })
+    }
+
+    fn point(&mut self, origin: (f32, f32)) -> Result<(f32, f32), String> {
+        let x = self.tokens.number()?;
+        let y = self.tokens.number()?;
+        Ok((origin.0 + x, origin.1 + y))
+    }
+}
+
+/// Reflection of the previous control point about the current point, or the
+/// current point itself when the previous command was not the same kind of
+/// curve.
+fn reflect(control: Option<(f32, f32)>, current: (f32, f32)) -> (f32, f32) {
+    match control {
+        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+        None => current,
+    }
+}
+
+fn dist(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close_to(a: (f32, f32), b: (f32, f32)) -> bool {
+        dist(a, b) < 1e-3
+    }
+
+    #[test]
+    fn lines_relative_and_implicit_commands() {
+        let path = SvgPath::parse("M10,10 20 10 v10 h-10 z m5-5 l1.5.5").unwrap();
+        let [square, tail] = path.subpaths() else { panic!("expected two subpaths") };
+        assert_eq!(square.points, vec![(10.0, 10.0), (20.0, 10.0), (20.0, 20.0), (10.0, 20.0)]);
+        assert!(square.closed);
+        // Relative moveto after Z starts from the closed subpath's start.
+        assert_eq!(tail.points, vec![(15.0, 5.0), (16.5, 5.5)]);
+        assert!(!tail.closed);
+        assert!(matches!(&path.to_shapes()[..], [ShapeKind::Polygon(_), ShapeKind::Polyline(_)]));
+    }
+
+    #[test]
+    fn curves_end_on_their_endpoints() {
+        let path = SvgPath::parse("M0 0 C0 10 10 10 10 0 S20 -10 20 0 Q25 10 30 0 T40 0").unwrap();
+        let points = &path.subpaths()[0].points;
+        for p in [(10.0, 0.0), (20.0, 0.0), (30.0, 0.0), (40.0, 0.0)] {
+            assert!(points.iter().any(|&q| close_to(p, q)), "missing {p:?}");
+        }
+        // Flattened, not just the endpoints.
+        assert!(points.len() > 10);
+    }
+
+    #[test]
+    fn arc_follows_flags_and_packed_flags_parse() {
+        // Half circle of radius 10 from (0,0) to (20,0). With y down, sweep=1
+        // runs clockwise on screen, over the top through (10, -10).
+        let path = SvgPath::parse("M0 0 A10 10 0 0 1 20 0").unwrap();
+        let points = &path.subpaths()[0].points;
+        assert!(close_to(*points.last().unwrap(), (20.0, 0.0)));
+        let top = points.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        assert!((top + 10.0).abs() < 0.1);
+
+        let packed = SvgPath::parse("M0 0a10 10 0 0020 0").unwrap();
+        let bottom = packed.subpaths()[0].points.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+        assert!((bottom - 10.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn malformed_data_is_rejected() {
+        assert!(SvgPath::parse("L10 10").is_err());
+        assert!(SvgPath::parse("M0 0 L10").is_err());
+        assert!(SvgPath::parse("M0 0 X5 5").is_err());
+        assert!(SvgPath::parse("M0 0 A5 5 0 2 0 10 0").is_err());
+        assert_eq!(SvgPath::parse("").unwrap().subpaths().len(), 0);
+    }
+}
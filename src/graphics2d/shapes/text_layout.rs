@@ -0,0 +1,187 @@
+//! Line breaking and alignment for [`Text`](super::Text) shapes.
+
+use std::ops::Range;
+
+/// Horizontal placement of each line relative to the text's origin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAlign {
+    /// Lines start at the origin.
+    #[default]
+    Left,
+    /// Lines are centered on the origin.
+    Center,
+    /// Lines end at the origin.
+    Right,
+}
+
+/// Vertical placement of the block of lines relative to the text's origin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlign {
+    /// The top of the first line is at the origin.
+    #[default]
+    Top,
+    /// The block is centered on the origin.
+    Middle,
+    /// The bottom of the last line is at the origin.
+    Bottom,
+    /// The first line's baseline is at the origin.
+    Baseline,
+}
+
+/// How a [`Text`](super::Text) is broken into lines and placed.
+///
+/// `\n` always starts a new line. With a `max_width`, lines are also
+/// wrapped between words so none is wider, except for single words that
+/// do not fit on a line of their own. The default lays text out as one
+/// left-aligned line per `\n`, its top-left at the origin:
+///
+/// ```ignore
+/// let label = Text::new("Runway 27L\nclosed until 14:00", FONT, 14).with_layout(
+///     TextLayout::new().align(TextAlign::Center).vertical_align(VerticalAlign::Middle).max_width(160.0),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextLayout {
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+    /// Wrap lines longer than this many pixels at word boundaries.
+    pub max_width: Option<f32>,
+    /// Distance between baselines, as a multiple of the font size.
+    pub line_spacing: f32,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self { align: TextAlign::Left, vertical_align: VerticalAlign::Top, max_width: None, line_spacing: 1.2 }
+    }
+}
+
+impl TextLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical_align = align;
+        self
+    }
+
+    pub fn max_width(mut self, width: f32) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    pub fn line_spacing(mut self, spacing: f32) -> Self {
+        self.line_spacing = spacing;
+        self
+    }
+
+    /// Byte ranges of `text`'s lines and their widths, measuring each
+    /// character with `advance`. Spaces at wrap points are dropped.
+    pub(crate) fn break_lines(&self, text: &str, mut advance: impl FnMut(char) -> f32) -> Vec<(Range<usize>, f32)> {
+        let max_width = self.max_width.unwrap_or(f32::INFINITY);
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for raw in text.split('\n') {
+            let paragraph = raw.strip_suffix('\r').unwrap_or(raw);
+            // Current line start, end (after its last word) and width.
+            let mut line: Option<(usize, usize, f32)> = None;
+            let mut word_start = 0;
+            for (i, word) in paragraph.split(' ').enumerate() {
+                if i > 0 {
+                    word_start += 1;
+                }
+                let (start, end) = (offset + word_start, offset + word_start + word.len());
+                word_start += word.len();
+                if word.is_empty() && line.is_some() {
+                    // Runs of spaces count once the next word follows them.
+                    continue;
+                }
+                let width: f32 = word.chars().map(&mut advance).sum();
+                line = match line {
+                    Some((line_start, line_end, line_width)) => {
+                        let gap: f32 = text[line_end..start].chars().map(&mut advance).sum();
+                        if line_width + gap + width <= max_width {
+                            Some((line_start, end, line_width + gap + width))
+                        } else {
+                            lines.push((line_start..line_end, line_width));
+                            Some((start, end, width))
+                        }
+                    }
+                    None => Some((start, end, width)),
+                };
+            }
+            let (start, end, width) = line.unwrap_or((offset, offset, 0.0));
+            lines.push((start..end, width));
+            offset += raw.len() + 1;
+        }
+        lines
+    }
+
+    /// Horizontal start of a line `width` pixels wide.
+    pub(crate) fn line_x(&self, width: f32) -> f32 {
+        match self.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -width * 0.5,
+            TextAlign::Right => -width,
+        }
+    }
+
+    /// Baseline of the first of `lines` lines set at `font_size`.
+    pub(crate) fn first_baseline(&self, lines: usize, font_size: f32) -> f32 {
+        let height = font_size + self.line_pitch(font_size) * lines.saturating_sub(1) as f32;
+        font_size
+            - match self.vertical_align {
+                VerticalAlign::Top => 0.0,
+                VerticalAlign::Middle => height * 0.5,
+                VerticalAlign::Bottom => height,
+                VerticalAlign::Baseline => font_size,
+            }
+    }
+
+    pub(crate) fn line_pitch(&self, font_size: f32) -> f32 {
+        font_size * self.line_spacing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(layout: TextLayout, text: &str) -> Vec<(&str, f32)> {
+        layout.break_lines(text, |_| 10.0).into_iter().map(|(range, width)| (&text[range], width)).collect()
+    }
+
+    #[test]
+    fn newlines_split_and_wrapping_breaks_between_words() {
+        let text = "one two three\nfour";
+        assert_eq!(lines(TextLayout::new(), text), vec![("one two three", 130.0), ("four", 40.0)]);
+        assert_eq!(
+            lines(TextLayout::new().max_width(75.0), text),
+            vec![("one two", 70.0), ("three", 50.0), ("four", 40.0)]
+        );
+        // A word wider than the limit gets a line of its own.
+        assert_eq!(lines(TextLayout::new().max_width(30.0), "a verylong b"), vec![("a", 10.0), ("verylong", 80.0), ("b", 10.0)]);
+        assert_eq!(lines(TextLayout::new(), "a\n\nb"), vec![("a", 10.0), ("", 0.0), ("b", 10.0)]);
+        assert_eq!(lines(TextLayout::new(), " a  b \r\nc"), vec![(" a  b", 50.0), ("c", 10.0)]);
+    }
+
+    #[test]
+    fn alignment_places_lines_around_the_origin() {
+        let layout = TextLayout::new().align(TextAlign::Right).vertical_align(VerticalAlign::Middle).line_spacing(1.5);
+        assert_eq!(layout.line_x(40.0), -40.0);
+        // Three 10px lines 15px apart span 40px, so the first baseline is
+        // 20px above the middle plus one font size.
+        assert_eq!(layout.first_baseline(3, 10.0), -10.0);
+        assert_eq!(TextLayout::new().first_baseline(3, 10.0), 10.0);
+        assert_eq!(TextLayout::new().vertical_align(VerticalAlign::Baseline).first_baseline(1, 10.0), 0.0);
+    }
+}
@@ -0,0 +1,221 @@
+//! Instanced drawing of images from a [`TextureAtlas`].
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::core::engine::leaks::{self, Resource};
+use crate::core::engine::opengl::{Vec2, GL_TRIANGLES};
+use crate::core::math::Mat4;
+use crate::core::{
+    AtlasRegion, Attribute, Color, Geometry, InstanceTransform, Mesh, Renderable, Renderer, Shader, SpriteId,
+    TextureAtlas,
+};
+
+thread_local! {
+    static SPRITE_SHADER: OnceCell<Rc<Shader>> = const { OnceCell::new() };
+}
+
+fn sprite_shader() -> Rc<Shader> {
+    SPRITE_SHADER.with(|cell| {
+        cell.get_or_init(|| {
+            let shader = Shader::compile(include_str!("shaders/sprite.vert"), include_str!("shaders/sprite.frag"), None)
+                .expect("Failed to compile sprite shader");
+            // Cached for the life of the thread, which is not a leak.
+            leaks::keep(Resource::Program, shader.program());
+            Rc::new(shader)
+        })
+        .clone()
+    })
+}
+
+/// One image from the atlas, centered on `(x, y)` in screen pixels and
+/// drawn at its pixel size times `scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub sprite: SpriteId,
+    pub x: f32,
+    pub y: f32,
+    pub scale: f32,
+    /// Radians, clockwise on screen.
+    pub rotation: f32,
+    /// Multiplied with the image's colors; white leaves them unchanged.
+    pub tint: Color,
+}
+
+impl Sprite {
+    pub fn new(sprite: SpriteId, x: f32, y: f32) -> Self {
+        Self { sprite, x, y, scale: 1.0, rotation: 0.0, tint: Color::white() }
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+/// Many sprites from one [`TextureAtlas`], drawn in a single instanced
+/// draw call, e.g. hundreds of points of interest on a map:
+///
+/// ```ignore
+/// let atlas = Rc::new(TextureAtlas::from_files(&["icons/airport.png", "icons/heliport.png"])?);
+/// let airport = atlas.find("icons/airport.png").unwrap();
+/// let mut pois = SpriteBatch::new(atlas);
+/// for (x, y) in airports {
+///     pois.push(Sprite::new(airport, x, y).with_scale(0.5));
+/// }
+///
+/// // each frame
+/// pois.render(renderer);
+/// ```
+///
+/// Sprites draw in order, so later ones cover earlier ones. Changes made
+/// through [`push`](Self::push) or [`sprites_mut`](Self::sprites_mut) are
+/// uploaded on the next render. In the picking pass, sprite `i` writes
+/// the current pick id plus `i` over its opaque pixels.
+pub struct SpriteBatch {
+    atlas: Rc<TextureAtlas>,
+    sprites: Vec<Sprite>,
+    opacity: f32,
+    dirty: bool,
+    /// Created on the first render.
+    mesh: Option<Mesh>,
+}
+
+impl SpriteBatch {
+    pub fn new(atlas: Rc<TextureAtlas>) -> Self {
+        Self { atlas, sprites: Vec::new(), opacity: 1.0, dirty: true, mesh: None }
+    }
+
+    pub fn atlas(&self) -> &Rc<TextureAtlas> {
+        &self.atlas
+    }
+
+    /// Add a sprite on top of the others, returning its index.
+    pub fn push(&mut self, sprite: Sprite) -> usize {
+        self.sprites.push(sprite);
+        self.dirty = true;
+        self.sprites.len() - 1
+    }
+
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    /// The sprites, to move, restyle, add or remove them in place.
+    pub fn sprites_mut(&mut self) -> &mut Vec<Sprite> {
+        self.dirty = true;
+        &mut self.sprites
+    }
+
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+        self.dirty = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Opacity of the whole batch, multiplied with each sprite's tint.
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+}
+
+impl Renderable for SpriteBatch {
+    fn render(&mut self, renderer: &Renderer) {
+        if self.sprites.is_empty() {
+            return;
+        }
+        let atlas = &self.atlas;
+        let mesh = self.mesh.get_or_insert_with(|| {
+            let mut geometry = Geometry::new(GL_TRIANGLES);
+            geometry.add_buffer(&[-0.5, -0.5, 0.5, -0.5, 0.5, 0.5, -0.5, -0.5, 0.5, 0.5, -0.5, 0.5], 2);
+            geometry.add_vertex_attribute(Attribute::new(0, 2, 2, 0));
+            geometry.enable_instancing_xy(0);
+            Mesh::with_texture(sprite_shader(), geometry, Some(atlas.texture_id()))
+        });
+        if self.dirty {
+            let instances = instances(&self.sprites, |sprite| atlas.region(sprite));
+            mesh.geometry.update_instance_xy(&instances.positions);
+            mesh.geometry.update_instance_colors(&instances.tints);
+            mesh.geometry.update_instance_transforms(&instances.transforms);
+            mesh.geometry.update_instance_uv_rects(&instances.uv_rects);
+            self.dirty = false;
+        }
+        let (width, height) = renderer.window_handle.size();
+        mesh.set_transform(Mat4::orthographic_rh_gl(0.0, width as f32, height as f32, 0.0, -1.0, 1.0));
+        mesh.set_opacity(self.opacity);
+        renderer.draw_mesh_instanced(mesh);
+    }
+}
+
+/// Per-instance attributes of a batch.
+struct Instances {
+    positions: Vec<Vec2>,
+    tints: Vec<Color>,
+    transforms: Vec<InstanceTransform>,
+    uv_rects: Vec<[f32; 4]>,
+}
+
+/// Instance data for `sprites`, looking their images up with `region`.
+/// Sprites whose image is not in the atlas get an empty quad, so instance
+/// indices stay equal to sprite indices.
+fn instances(sprites: &[Sprite], region: impl Fn(SpriteId) -> Option<AtlasRegion>) -> Instances {
+    let mut instances = Instances {
+        positions: Vec::with_capacity(sprites.len()),
+        tints: Vec::with_capacity(sprites.len()),
+        transforms: Vec::with_capacity(sprites.len()),
+        uv_rects: Vec::with_capacity(sprites.len()),
+    };
+    for sprite in sprites {
+        let region = region(sprite.sprite);
+        let (width, height) = region.map_or((0.0, 0.0), |r| (r.width as f32, r.height as f32));
+        instances.positions.push(Vec2::new(sprite.x, sprite.y));
+        instances.tints.push(sprite.tint);
+        instances.transforms.push(InstanceTransform::new(width * sprite.scale, height * sprite.scale, sprite.rotation));
+        instances.uv_rects.push(region.map_or([0.0; 4], |r| r.uv_rect()));
+    }
+    instances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instances_size_quads_from_regions() {
+        let mut builder = TextureAtlas::builder();
+        let icon = builder.add("icon", image::RgbaImage::new(32, 16));
+        let missing = builder.add("missing", image::RgbaImage::new(1, 1));
+        let region = AtlasRegion { uv_x: 0.5, uv_y: 0.0, uv_width: 0.25, uv_height: 0.125, width: 32, height: 16 };
+
+        let sprites = [Sprite::new(icon, 10.0, 20.0).with_scale(0.5).with_rotation(1.0), Sprite::new(missing, 0.0, 0.0)];
+        let instances = instances(&sprites, |id| (id == icon).then_some(region));
+        assert_eq!(instances.positions, vec![Vec2::new(10.0, 20.0), Vec2::new(0.0, 0.0)]);
+        assert_eq!(instances.transforms[0], InstanceTransform::new(16.0, 8.0, 1.0));
+        assert_eq!(instances.uv_rects[0], [0.5, 0.0, 0.25, 0.125]);
+        // Unknown images keep their slot as an empty quad.
+        assert_eq!(instances.transforms[1], InstanceTransform::new(0.0, 0.0, 0.0));
+        assert_eq!(instances.tints, vec![Color::white(); 2]);
+    }
+}
@@ -0,0 +1,243 @@
+//! Triangulation of concave polygons, with or without holes.
+//!
+//! [`Polygon`](crate::graphics2d::shapes::Polygon) fills use these through
+//! [`Polygon::triangulate`](crate::graphics2d::shapes::Polygon::triangulate)
+//! and [`Polygon::with_holes`](crate::graphics2d::shapes::Polygon::with_holes);
+//! they are exposed here for callers building their own geometry from raw
+//! point rings.
+
+/// Signed area of the ring `points` (shoelace formula).
+///
+/// Positive when vertices are ordered counter-clockwise (in a Y-up coordinate
+/// system), negative when clockwise. Magnitude equals the polygon area.
+pub fn signed_area(points: &[(f32, f32)]) -> f32 {
+    let n = points.len();
+    if n < 3 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    for i in 0..n {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % n];
+        sum += x0 * y1 - x1 * y0;
+    }
+    sum * 0.5
+}
+
+/// Triangulate the (possibly concave) simple polygon `points` using ear
+/// clipping.
+///
+/// Returns a list of triangles as triples of indices into `points`. The
+/// polygon is assumed to be simple (non-self-intersecting). The algorithm
+/// normalizes the working order to counter-clockwise internally, so input
+/// may be in either winding.
+///
+/// Complexity: O(n²). Suitable for typical polygon sizes; for very large
+/// polygons consider a more advanced algorithm.
+///
+/// Returns an empty Vec for degenerate input (fewer than 3 vertices).
+pub fn triangulate(points: &[(f32, f32)]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Work in CCW order so the convexity test has a consistent sign.
+    let ccw = signed_area(points) > 0.0;
+    let mut indices: Vec<usize> = if ccw {
+        (0..n).collect()
+    } else {
+        (0..n).rev().collect()
+    };
+
+    let mut triangles: Vec<[usize; 3]> = Vec::with_capacity(n - 2);
+    // Safety bound: a simple polygon needs at most n-2 ears, but we may
+    // scan multiple vertices per removal. Cap iterations to avoid infinite
+    // loops on malformed (e.g., self-intersecting) input.
+    let max_iterations = n * n;
+    let mut iterations = 0;
+
+    while indices.len() > 3 {
+        iterations += 1;
+        if iterations > max_iterations {
+            break;
+        }
+
+        let m = indices.len();
+        let mut ear_index: Option<usize> = None;
+        for i in 0..m {
+            let a = indices[(i + m - 1) % m];
+            let b = indices[i];
+            let c = indices[(i + 1) % m];
+            if is_ear(points, a, b, c, &indices) {
+                ear_index = Some(i);
+                triangles.push([a, b, c]);
+                break;
+            }
+        }
+        match ear_index {
+            Some(i) => {
+                indices.remove(i);
+            }
+            None => break, // degenerate polygon; bail out
+        }
+    }
+
+    if indices.len() == 3 {
+        triangles.push([indices[0], indices[1], indices[2]]);
+    }
+
+    triangles
+}
+
+/// Merge `outer` and its `holes` into a single ring that [`triangulate`]
+/// fills with the area between them.
+///
+/// Each hole is cut open and joined to the outer ring by a pair of
+/// coincident "bridge" edges running from its rightmost vertex to the
+/// nearest outer vertex that can be reached without crossing an edge.
+/// Rings may have either winding; holes with fewer than 3 points are
+/// ignored. The result is counter-clockwise.
+pub fn bridge_holes(outer: Vec<(f32, f32)>, holes: Vec<Vec<(f32, f32)>>) -> Vec<(f32, f32)> {
+    let mut ring = outer;
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    let mut holes: Vec<Vec<(f32, f32)>> = holes
+        .into_iter()
+        .filter(|h| h.len() >= 3)
+        .map(|mut h| {
+            if signed_area(&h) > 0.0 {
+                h.reverse();
+            }
+            h
+        })
+        .collect();
+    let max_x = |h: &Vec<(f32, f32)>| h.iter().map(|p| p.0).fold(f32::MIN, f32::max);
+    holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+
+    for k in 0..holes.len() {
+        let hole = &holes[k];
+        let m = (0..hole.len()).max_by(|&i, &j| hole[i].0.total_cmp(&hole[j].0)).unwrap_or(0);
+        let start = hole[m];
+        let mut candidates: Vec<usize> = (0..ring.len()).collect();
+        let distance = |i: usize| (ring[i].0 - start.0).powi(2) + (ring[i].1 - start.1).powi(2);
+        candidates.sort_by(|&i, &j| distance(i).total_cmp(&distance(j)));
+        let visible = |i: usize| {
+            let end = ring[i];
+            let crosses = |points: &[(f32, f32)]| {
+                (0..points.len()).any(|e| segments_cross(start, end, points[e], points[(e + 1) % points.len()]))
+            };
+            !crosses(&ring) && !holes[k..].iter().any(|h| crosses(h))
+        };
+        let Some(bridge) = candidates.iter().copied().find(|&i| visible(i)) else {
+            continue;
+        };
+        let mut merged = Vec::with_capacity(ring.len() + hole.len() + 2);
+        merged.extend_from_slice(&ring[..=bridge]);
+        merged.extend(hole[m..].iter().chain(&hole[..=m]));
+        merged.extend_from_slice(&ring[bridge..]);
+        ring = merged;
+    }
+    ring
+}
+
+/// Triangulate `outer` minus `holes`: the merged ring from
+/// [`bridge_holes`] and the triangles indexing into it.
+pub fn triangulate_with_holes(
+    outer: Vec<(f32, f32)>,
+    holes: Vec<Vec<(f32, f32)>>,
+) -> (Vec<(f32, f32)>, Vec<[usize; 3]>) {
+    let ring = bridge_holes(outer, holes);
+    let triangles = triangulate(&ring);
+    (ring, triangles)
+}
+
+/// True if the triangle (a, b, c) is an ear: convex at b (in CCW order)
+/// and contains no other polygon vertex.
+fn is_ear(points: &[(f32, f32)], a: usize, b: usize, c: usize, indices: &[usize]) -> bool {
+    let (ax, ay) = points[a];
+    let (bx, by) = points[b];
+    let (cx, cy) = points[c];
+
+    // Convex at b iff cross product of (b-a) x (c-b) is positive in CCW.
+    let cross = (bx - ax) * (cy - by) - (by - ay) * (cx - bx);
+    if cross <= 0.0 {
+        return false;
+    }
+
+    // Reject if any other polygon vertex lies strictly inside the triangle.
+    // Copies of the corners (the ends of a hole bridge) don't count.
+    for &idx in indices {
+        if idx == a || idx == b || idx == c {
+            continue;
+        }
+        let (px, py) = points[idx];
+        if [(ax, ay), (bx, by), (cx, cy)].contains(&(px, py)) {
+            continue;
+        }
+        if point_in_triangle(px, py, ax, ay, bx, by, cx, cy) {
+            return false;
+        }
+    }
+    true
+}
+
+/// True if segments p1-p2 and q1-q2 cross at a point interior to both.
+/// Touching at an endpoint or overlapping collinearly does not count.
+fn segments_cross(p1: (f32, f32), p2: (f32, f32), q1: (f32, f32), q2: (f32, f32)) -> bool {
+    let orient = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    let (d1, d2) = (orient(q1, q2, p1), orient(q1, q2, p2));
+    let (d3, d4) = (orient(p1, p2, q1), orient(p1, p2, q2));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Returns true if point p lies inside the triangle (a, b, c), using the
+/// half-plane sign test. Points exactly on edges are treated as inside.
+fn point_in_triangle(
+    px: f32, py: f32,
+    ax: f32, ay: f32,
+    bx: f32, by: f32,
+    cx: f32, cy: f32,
+) -> bool {
+    let d1 = (px - bx) * (ay - by) - (ax - bx) * (py - by);
+    let d2 = (px - cx) * (by - cy) - (bx - cx) * (py - cy);
+    let d3 = (px - ax) * (cy - ay) - (cx - ax) * (py - ay);
+
+    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_neg && has_pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(points: &[(f32, f32)], triangles: &[[usize; 3]]) -> f32 {
+        triangles.iter().map(|t| signed_area(&t.map(|i| points[i])).abs()).sum()
+    }
+
+    #[test]
+    fn triangles_cover_a_concave_ring_exactly() {
+        // U shape, opening upward
+        let points = [(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (2.0, 3.0), (2.0, 1.0), (1.0, 1.0), (1.0, 3.0), (0.0, 3.0)];
+        let triangles = triangulate(&points);
+        assert_eq!(triangles.len(), points.len() - 2);
+        assert!((area(&points, &triangles) - 7.0).abs() < 1e-5);
+        // Every triangle keeps the ring's winding: no flipped (outside) ears
+        assert!(triangles.iter().all(|t| signed_area(&t.map(|i| points[i])) > 0.0));
+    }
+
+    #[test]
+    fn holes_in_a_concave_outline_are_left_empty() {
+        // L shape with a square hole in its lower arm
+        let outer = vec![(0.0, 0.0), (6.0, 0.0), (6.0, 2.0), (2.0, 2.0), (2.0, 6.0), (0.0, 6.0)];
+        let hole = vec![(3.0, 0.5), (4.0, 0.5), (4.0, 1.5), (3.0, 1.5)];
+        let (ring, triangles) = triangulate_with_holes(outer, vec![hole]);
+        assert_eq!(ring.len(), 6 + 4 + 2);
+        assert!((area(&ring, &triangles) - (20.0 - 1.0)).abs() < 1e-3);
+    }
+}
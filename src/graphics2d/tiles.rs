@@ -0,0 +1,549 @@
+//! Raster basemaps from XYZ ("slippy map") tiles.
+//!
+//! A [`TileLayer`] works out which `z/x/y` tiles cover the view of a
+//! [`Camera2D`], fetches the missing ones on background threads from a
+//! [`TileSource`], uploads them as textures and draws them as quads that
+//! fade in as they arrive. While a tile loads, the closest loaded ancestor
+//! is drawn in its place.
+//!
+//! World coordinates are Web Mercator meters with `y` pointing south, i.e.
+//! `(x, -y)` of the usual EPSG:3857 coordinates, matching the waypoints
+//! example, so tile rows and screen rows both grow downwards.
+//!
+//! ```ignore
+//! let source = DiskCache::new("tile-cache", HttpTileSource::new("https://tile.openstreetmap.org/{z}/{x}/{y}.png"));
+//! let mut basemap = TileLayer::new(source);
+//! app.on_render(move |renderer, camera| {
+//!     basemap.render(renderer, camera.unwrap());
+//!     // draw overlays on top
+//! });
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+
+use image::RgbaImage;
+
+use crate::core::{Camera2D, GpuTexture, Projection, Renderable, Renderer, TextureOptions, TextureWrap, Vec2, generate_texture_from_rgba};
+use crate::graphics2d::geo::EARTH_RADIUS;
+use crate::graphics2d::shapes::ShapeRenderable;
+
+/// Edge length of a tile image in pixels.
+pub const TILE_SIZE: u32 = 256;
+
+/// Width (and height) of the Web Mercator square in meters.
+const WORLD_SIZE: f64 = 2.0 * PI * EARTH_RADIUS;
+const WORLD_MIN: f64 = -PI * EARTH_RADIUS;
+
+const DEFAULT_WORKERS: usize = 4;
+const DEFAULT_CAPACITY: usize = 256;
+const DEFAULT_FADE: f64 = 0.25;
+/// How many zoom levels up to look for a stand-in while a tile loads.
+const MAX_FALLBACK_LEVELS: u8 = 6;
+
+/// A tile address: zoom level, column from the west, row from the north.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    pub fn new(z: u8, x: u32, y: u32) -> Self {
+        Self { z, x, y }
+    }
+
+    /// Edge length of tiles at zoom `z` in world units (meters).
+    pub fn world_size(z: u8) -> f64 {
+        WORLD_SIZE / (1u64 << z) as f64
+    }
+
+    /// World bounds as `(min_x, min_y, max_x, max_y)`.
+    pub fn world_bounds(&self) -> (f64, f64, f64, f64) {
+        let size = Self::world_size(self.z);
+        let min_x = WORLD_MIN + self.x as f64 * size;
+        let min_y = WORLD_MIN + self.y as f64 * size;
+        (min_x, min_y, min_x + size, min_y + size)
+    }
+
+    /// The tile one level up that contains this one.
+    pub fn parent(&self) -> Option<TileId> {
+        (self.z > 0).then(|| TileId::new(self.z - 1, self.x / 2, self.y / 2))
+    }
+}
+
+/// Zoom level whose tiles appear closest to their native size at `scale`
+/// pixels per world unit.
+pub fn zoom_for_scale(scale: f32) -> f64 {
+    (scale as f64 * WORLD_SIZE / TILE_SIZE as f64).log2()
+}
+
+/// Tiles at zoom `z` overlapping the world rectangle, ordered from the
+/// center outwards so the middle of the view loads first.
+pub fn tiles_in_bounds(z: u8, (min_x, min_y, max_x, max_y): (f64, f64, f64, f64)) -> Vec<TileId> {
+    if max_x <= WORLD_MIN || max_y <= WORLD_MIN || min_x >= -WORLD_MIN || min_y >= -WORLD_MIN {
+        return Vec::new();
+    }
+    let size = TileId::world_size(z);
+    let last = (1u64 << z) as f64 - 1.0;
+    let index = |v: f64| ((v - WORLD_MIN) / size).floor().clamp(0.0, last) as u32;
+    let (x0, x1, y0, y1) = (index(min_x), index(max_x), index(min_y), index(max_y));
+    let mut tiles: Vec<TileId> = (y0..=y1).flat_map(|y| (x0..=x1).map(move |x| TileId::new(z, x, y))).collect();
+    let (cx, cy) = ((x0 + x1) as f64 / 2.0, (y0 + y1) as f64 / 2.0);
+    tiles.sort_by(|a, b| {
+        let d = |t: &TileId| (t.x as f64 - cx).powi(2) + (t.y as f64 - cy).powi(2);
+        d(a).total_cmp(&d(b))
+    });
+    tiles
+}
+
+/// Substitute `{z}`, `{x}` and `{y}` in a URL or path template.
+pub fn fill_template(template: &str, tile: TileId) -> String {
+    template
+        .replace("{z}", &tile.z.to_string())
+        .replace("{x}", &tile.x.to_string())
+        .replace("{y}", &tile.y.to_string())
+}
+
+/// Where tile images come from. Called on the layer's worker threads.
+pub trait TileSource: Send + Sync {
+    /// The encoded image (PNG, JPEG, ...) for `tile`.
+    fn fetch(&self, tile: TileId) -> Result<Vec<u8>, String>;
+}
+
+/// Tiles read from local files, e.g. `"tiles/{z}/{x}/{y}.png"`.
+pub struct FileTileSource {
+    template: String,
+}
+
+impl FileTileSource {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self { template: template.into() }
+    }
+}
+
+impl TileSource for FileTileSource {
+    fn fetch(&self, tile: TileId) -> Result<Vec<u8>, String> {
+        let path = fill_template(&self.template, tile);
+        std::fs::read(&path).map_err(|e| format!("{path}: {e}"))
+    }
+}
+
+/// Tiles downloaded over HTTP(S) from a URL template such as
+/// `"https://tile.openstreetmap.org/{z}/{x}/{y}.png"`. Requires the
+/// `http-tiles` feature. Respect the tile server's usage policy: wrap it in
+/// a [`DiskCache`] and set an identifying user agent.
+#[cfg(feature = "http-tiles")]
+pub struct HttpTileSource {
+    template: String,
+    agent: ureq::Agent,
+    user_agent: String,
+}
+
+#[cfg(feature = "http-tiles")]
+impl HttpTileSource {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            agent: ureq::Agent::new(),
+            user_agent: concat!("wilhelm_renderer/", env!("CARGO_PKG_VERSION")).to_string(),
+        }
+    }
+
+    /// The `User-Agent` header sent with every request.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+}
+
+#[cfg(feature = "http-tiles")]
+impl TileSource for HttpTileSource {
+    fn fetch(&self, tile: TileId) -> Result<Vec<u8>, String> {
+        use std::io::Read;
+
+        let url = fill_template(&self.template, tile);
+        let response = self.agent.get(&url).set("User-Agent", &self.user_agent).call().map_err(|e| format!("{url}: {e}"))?;
+        let mut bytes = Vec::new();
+        response.into_reader().read_to_end(&mut bytes).map_err(|e| format!("{url}: {e}"))?;
+        Ok(bytes)
+    }
+}
+
+/// Keeps the tiles of another source in a directory, as
+/// `<dir>/<z>/<x>/<y>.tile`, and serves later requests from there.
+pub struct DiskCache<S> {
+    dir: PathBuf,
+    inner: S,
+}
+
+impl<S: TileSource> DiskCache<S> {
+    pub fn new(dir: impl Into<PathBuf>, inner: S) -> Self {
+        Self { dir: dir.into(), inner }
+    }
+
+    fn path(&self, tile: TileId) -> PathBuf {
+        self.dir.join(tile.z.to_string()).join(tile.x.to_string()).join(format!("{}.tile", tile.y))
+    }
+}
+
+impl<S: TileSource> TileSource for DiskCache<S> {
+    fn fetch(&self, tile: TileId) -> Result<Vec<u8>, String> {
+        let path = self.path(tile);
+        if let Ok(bytes) = std::fs::read(&path) {
+            return Ok(bytes);
+        }
+        let bytes = self.inner.fetch(tile)?;
+        // A cache that cannot be written only costs a refetch next time.
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, &bytes));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Tiles waiting for a worker. The layer replaces the queue every frame so
+/// tiles that scrolled out of view are never fetched.
+struct Queue {
+    state: Mutex<(VecDeque<TileId>, bool)>,
+    wake: Condvar,
+}
+
+impl Queue {
+    fn pop(&self) -> Option<TileId> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let (queue, closed) = &mut *state;
+            if *closed {
+                return None;
+            }
+            if let Some(tile) = queue.pop_front() {
+                return Some(tile);
+            }
+            state = self.wake.wait(state).unwrap();
+        }
+    }
+}
+
+type FetchResult<T> = (TileId, Result<T, String>);
+
+/// Fetches tiles from a [`TileSource`] and decodes them on worker threads.
+/// Shared by the raster and vector tile layers.
+pub(crate) struct TileLoader<T> {
+    queue: Arc<Queue>,
+    results: Receiver<FetchResult<T>>,
+    /// Queued or being fetched.
+    requested: HashSet<TileId>,
+    failed: HashSet<TileId>,
+    errors: Vec<(TileId, String)>,
+}
+
+impl<T: Send + 'static> TileLoader<T> {
+    pub(crate) fn new(source: impl TileSource + 'static, workers: usize, decode: fn(&[u8]) -> Result<T, String>) -> Self {
+        let source: Arc<dyn TileSource> = Arc::new(source);
+        let queue = Arc::new(Queue { state: Mutex::new((VecDeque::new(), false)), wake: Condvar::new() });
+        let (sender, results) = mpsc::channel();
+        for _ in 0..workers.max(1) {
+            let (source, queue, sender) = (Arc::clone(&source), Arc::clone(&queue), sender.clone());
+            // Detached: on drop the queue closes and workers exit after
+            // their current fetch, without blocking on slow downloads.
+            std::thread::spawn(move || {
+                while let Some(tile) = queue.pop() {
+                    let data = source.fetch(tile).and_then(|bytes| decode(&bytes));
+                    if sender.send((tile, data)).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        Self { queue, results, requested: HashSet::new(), failed: HashSet::new(), errors: Vec::new() }
+    }
+
+    /// Tiles decoded since the last call. Failures are recorded for
+    /// [`take_errors`](Self::take_errors) and not handed out.
+    pub(crate) fn receive(&mut self) -> Vec<(TileId, T)> {
+        let mut loaded = Vec::new();
+        while let Ok((tile, data)) = self.results.try_recv() {
+            self.requested.remove(&tile);
+            match data {
+                Ok(data) => loaded.push((tile, data)),
+                Err(error) => {
+                    log::warn!("tile {}/{}/{} failed to load: {error}", tile.z, tile.x, tile.y);
+                    self.failed.insert(tile);
+                    self.errors.push((tile, error));
+                }
+            }
+        }
+        loaded
+    }
+
+    /// Replace the queue with the `wanted` tiles that are not loaded, in
+    /// flight or failed.
+    pub(crate) fn request(&mut self, wanted: &[TileId], loaded: impl Fn(&TileId) -> bool) {
+        let mut state = self.queue.state.lock().unwrap();
+        for dropped in state.0.drain(..) {
+            self.requested.remove(&dropped);
+        }
+        for tile in wanted {
+            if !loaded(tile) && !self.failed.contains(tile) && self.requested.insert(*tile) {
+                state.0.push_back(*tile);
+            }
+        }
+        if !state.0.is_empty() {
+            self.queue.wake.notify_all();
+        }
+    }
+
+    pub(crate) fn take_errors(&mut self) -> Vec<(TileId, String)> {
+        std::mem::take(&mut self.errors)
+    }
+
+    pub(crate) fn retry_failed(&mut self) {
+        self.failed.clear();
+    }
+}
+
+impl<T> Drop for TileLoader<T> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().1 = true;
+        self.queue.wake.notify_all();
+    }
+}
+
+/// Tiles at the zoom level drawn for `camera` that cover its view.
+pub(crate) fn visible_tiles(camera: &Camera2D, min_zoom: u8, max_zoom: u8) -> (u8, Vec<TileId>) {
+    let z = zoom_for_scale(camera.scale()).round().clamp(min_zoom as f64, max_zoom as f64) as u8;
+    let (min_x, min_y, max_x, max_y) = camera.world_bounds();
+    (z, tiles_in_bounds(z, (min_x as f64, min_y as f64, max_x as f64, max_y as f64)))
+}
+
+/// The closest loaded ancestor of each tile in `tiles`, without duplicates,
+/// coarsest first so finer stand-ins draw on top.
+pub(crate) fn fallback_tiles<'a>(tiles: impl IntoIterator<Item = &'a TileId>, loaded: impl Fn(&TileId) -> bool) -> Vec<TileId> {
+    let mut fallbacks: Vec<TileId> = Vec::new();
+    for tile in tiles {
+        let ancestor = std::iter::successors(tile.parent(), TileId::parent).take(MAX_FALLBACK_LEVELS as usize).find(&loaded);
+        if let Some(ancestor) = ancestor.filter(|a| !fallbacks.contains(a)) {
+            fallbacks.push(ancestor);
+        }
+    }
+    fallbacks.sort_by_key(|t| t.z);
+    fallbacks
+}
+
+/// Drop the tiles least recently used before `frame` until at most
+/// `capacity` remain.
+pub(crate) fn evict_least_recent<T>(tiles: &mut HashMap<TileId, T>, capacity: usize, frame: u64, last_used: impl Fn(&T) -> u64) {
+    if tiles.len() <= capacity {
+        return;
+    }
+    let mut by_age: Vec<(u64, TileId)> = tiles.iter().map(|(id, t)| (last_used(t), *id)).collect();
+    by_age.sort_unstable_by_key(|(used, _)| *used);
+    let excess = tiles.len() - capacity;
+    for (used, id) in by_age.into_iter().take(excess) {
+        if used == frame {
+            break;
+        }
+        tiles.remove(&id);
+    }
+}
+
+/// Screen position of a tile's north-west corner and the scale from
+/// [`TILE_SIZE`] pixels to its size on screen. Tiles are drawn turned by
+/// the camera's `-rotation()` around that corner.
+pub(crate) fn tile_placement(tile: TileId, camera: &Camera2D) -> (Vec2, f32) {
+    let (min_x, min_y, ..) = tile.world_bounds();
+    let corner = camera.world_to_screen(Vec2::new(min_x as f32, min_y as f32));
+    (corner, TileId::world_size(tile.z) as f32 * camera.scale() / TILE_SIZE as f32)
+}
+
+fn decode_image(bytes: &[u8]) -> Result<RgbaImage, String> {
+    image::load_from_memory(bytes).map(|image| image.to_rgba8()).map_err(|e| e.to_string())
+}
+
+struct LoadedTile {
+    shape: ShapeRenderable,
+    /// Deleted by `flush_deleted_resources` once the tile is evicted.
+    _texture: GpuTexture,
+    loaded_at: f64,
+    last_used: u64,
+}
+
+/// A basemap of XYZ raster tiles drawn under the rest of the scene. See the
+/// [module docs](self).
+///
+/// Call [`render`](Self::render) every frame, before anything that should
+/// appear on top of the map. Decoded tiles are kept as textures up to
+/// [`set_capacity`](Self::set_capacity), least recently drawn first out.
+pub struct TileLayer {
+    loader: TileLoader<RgbaImage>,
+    tiles: HashMap<TileId, LoadedTile>,
+    min_zoom: u8,
+    max_zoom: u8,
+    capacity: usize,
+    fade: f64,
+    frame: u64,
+}
+
+impl TileLayer {
+    /// Fetch from `source` on 4 worker threads, zoom levels 0 to 19.
+    pub fn new(source: impl TileSource + 'static) -> Self {
+        Self::with_workers(source, DEFAULT_WORKERS)
+    }
+
+    /// Fetch from `source` on `workers` threads (at least one).
+    pub fn with_workers(source: impl TileSource + 'static, workers: usize) -> Self {
+        Self {
+            loader: TileLoader::new(source, workers, decode_image),
+            tiles: HashMap::new(),
+            min_zoom: 0,
+            max_zoom: 19,
+            capacity: DEFAULT_CAPACITY,
+            fade: DEFAULT_FADE,
+            frame: 0,
+        }
+    }
+
+    /// Zoom levels the source provides. Beyond `max`, its tiles are
+    /// stretched.
+    pub fn set_zoom_range(&mut self, min: u8, max: u8) -> &mut Self {
+        self.min_zoom = min.min(max);
+        self.max_zoom = max;
+        self
+    }
+
+    /// Number of tile textures to keep (default 256). Tiles in view are
+    /// always kept.
+    pub fn set_capacity(&mut self, tiles: usize) -> &mut Self {
+        self.capacity = tiles;
+        self
+    }
+
+    /// Seconds a newly loaded tile takes to fade in (default 0.25, `0` to
+    /// show tiles at once).
+    pub fn set_fade_duration(&mut self, seconds: f64) -> &mut Self {
+        self.fade = seconds.max(0.0);
+        self
+    }
+
+    /// Tile zoom level drawn for `camera`.
+    pub fn zoom_level(&self, camera: &Camera2D) -> u8 {
+        visible_tiles(camera, self.min_zoom, self.max_zoom).0
+    }
+
+    /// Tiles that failed to load since the last call. Failed tiles are not
+    /// requested again until [`retry_failed`](Self::retry_failed).
+    pub fn take_errors(&mut self) -> Vec<(TileId, String)> {
+        self.loader.take_errors()
+    }
+
+    pub fn retry_failed(&mut self) {
+        self.loader.retry_failed();
+    }
+
+    /// Number of tiles currently held as textures.
+    pub fn loaded_tiles(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Fetch and upload tiles for the current view, then draw them.
+    pub fn render(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        self.frame += 1;
+        let now = renderer.get_time();
+        for (tile, image) in self.loader.receive() {
+            let options = TextureOptions::default().wrap(TextureWrap::ClampToEdge);
+            let texture = generate_texture_from_rgba(image.width(), image.height(), image.as_raw(), &options);
+            let shape = ShapeRenderable::image_from_texture(texture, TILE_SIZE as f32, TILE_SIZE as f32);
+            self.tiles.insert(tile, LoadedTile { shape, _texture: GpuTexture::from_raw(texture), loaded_at: now, last_used: self.frame });
+        }
+
+        let (_, visible) = visible_tiles(camera, self.min_zoom, self.max_zoom);
+        self.loader.request(&visible, |t| self.tiles.contains_key(t));
+
+        // Stand-ins for tiles that are missing or still fading in.
+        let opacity = |loaded: &LoadedTile| if self.fade == 0.0 { 1.0 } else { ((now - loaded.loaded_at) / self.fade).min(1.0) as f32 };
+        let pending = visible.iter().filter(|t| self.tiles.get(t).is_none_or(|loaded| opacity(loaded) < 1.0));
+        let fallbacks = fallback_tiles(pending, |t| self.tiles.contains_key(t));
+
+        for tile in fallbacks.iter().chain(&visible) {
+            let Some(loaded) = self.tiles.get_mut(tile) else { continue };
+            let alpha = opacity(loaded);
+            let (corner, scale) = tile_placement(*tile, camera);
+            // The quad is centered; half a pixel of overlap hides seams.
+            let half = TILE_SIZE as f32 * scale / 2.0;
+            let (sin, cos) = (-camera.rotation()).sin_cos();
+            let center = (corner.x + half * (cos - sin), corner.y + half * (sin + cos));
+            loaded
+                .shape
+                .set_position(center.0, center.1)
+                .set_rotation(-camera.rotation())
+                .set_scale(scale + 0.5 / TILE_SIZE as f32)
+                .set_opacity(alpha);
+            loaded.shape.render(renderer);
+            loaded.last_used = self.frame;
+        }
+
+        evict_least_recent(&mut self.tiles, self.capacity, self.frame, |t| t.last_used);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_bounds_and_parents() {
+        let root = TileId::new(0, 0, 0);
+        assert_eq!(root.world_bounds(), (WORLD_MIN, WORLD_MIN, -WORLD_MIN, -WORLD_MIN));
+        assert_eq!(root.parent(), None);
+        let tile = TileId::new(3, 5, 2);
+        assert_eq!(tile.parent(), Some(TileId::new(2, 2, 1)));
+        let (min_x, min_y, ..) = tile.world_bounds();
+        assert!((min_x - (WORLD_MIN + 5.0 * WORLD_SIZE / 8.0)).abs() < 1e-6);
+        assert!((min_y - (WORLD_MIN + 2.0 * WORLD_SIZE / 8.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn zoom_matches_native_tile_size() {
+        // The whole world in one 256 px tile is zoom 0.
+        assert!((zoom_for_scale((TILE_SIZE as f64 / WORLD_SIZE) as f32)).abs() < 1e-3);
+        assert!((zoom_for_scale((TILE_SIZE as f64 * 1024.0 / WORLD_SIZE) as f32) - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tiles_cover_bounds_center_first() {
+        let size = TileId::world_size(2);
+        let bounds = (WORLD_MIN + 0.5 * size, WORLD_MIN + 1.5 * size, WORLD_MIN + 2.5 * size, WORLD_MIN + 1.9 * size);
+        let tiles = tiles_in_bounds(2, bounds);
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(tiles[0], TileId::new(2, 1, 1));
+        assert!(tiles.iter().all(|t| t.y == 1 && (0..=2).contains(&t.x)));
+        // Clamped at the world edge, and nothing outside it.
+        assert_eq!(tiles_in_bounds(1, (-1e9, -1e9, 1e9, 1e9)).len(), 4);
+        assert!(tiles_in_bounds(4, (-2e8, 0.0, -1e8, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn templates_and_disk_cache() {
+        assert_eq!(fill_template("https://t/{z}/{x}/{y}.png", TileId::new(7, 66, 44)), "https://t/7/66/44.png");
+
+        struct Counting(Mutex<u32>);
+        impl TileSource for Counting {
+            fn fetch(&self, tile: TileId) -> Result<Vec<u8>, String> {
+                *self.0.lock().unwrap() += 1;
+                Ok(vec![tile.z, tile.x as u8, tile.y as u8])
+            }
+        }
+        let dir = std::env::temp_dir().join(format!("wilhelm_tiles_{}", std::process::id()));
+        let cache = DiskCache::new(&dir, Counting(Mutex::new(0)));
+        let tile = TileId::new(3, 4, 5);
+        assert_eq!(cache.fetch(tile).unwrap(), vec![3, 4, 5]);
+        assert_eq!(cache.fetch(tile).unwrap(), vec![3, 4, 5]);
+        assert_eq!(*cache.inner.0.lock().unwrap(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,732 @@
+//! Mapbox Vector Tiles (MVT): decoding and client-side styled rendering.
+//!
+//! [`VectorTile::decode`] turns a tile protobuf into layers of features
+//! with points, lines or polygons in tile coordinates. A
+//! [`VectorTileLayer`] fetches tiles like the raster
+//! [`TileLayer`](super::tiles::TileLayer) and draws them with a
+//! [`VectorStyle`]: an ordered list of [`StyleRule`]s that pick features by
+//! source layer, zoom range and an optional filter, and give them fill,
+//! stroke and zoom-dependent line widths. Changing the style restyles the
+//! loaded tiles without fetching them again.
+//!
+//! ```ignore
+//! let style = VectorStyle::new()
+//!     .rule(StyleRule::new("water").fill(Color::from_rgb(0.6, 0.75, 0.9)))
+//!     .rule(StyleRule::new("transportation")
+//!         .filter(|f| f.property("class").and_then(PropertyValue::as_str) == Some("motorway"))
+//!         .stroke(Color::from_rgb(0.9, 0.5, 0.3), 1.0)
+//!         .width_stops(&[(6.0, 0.5), (14.0, 4.0)]));
+//! let source = DiskCache::new("mvt-cache", HttpTileSource::new("https://example.com/tiles/{z}/{x}/{y}.pbf"));
+//! let mut map = VectorTileLayer::new(source, style);
+//! app.on_render(move |renderer, camera| map.render(renderer, camera.unwrap()));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::core::{Camera2D, Color, Renderable, Renderer};
+use crate::graphics2d::shapes::{Anchor, Circle, Polygon, Polyline, ShapeKind, ShapeRenderable, ShapeStyle};
+use crate::graphics2d::tiles::{
+    TILE_SIZE, TileId, TileLoader, TileSource, evict_least_recent, fallback_tiles, tile_placement, visible_tiles,
+};
+
+const DEFAULT_EXTENT: u32 = 4096;
+const DEFAULT_WORKERS: usize = 4;
+const DEFAULT_CAPACITY: usize = 128;
+
+const CMD_MOVE_TO: u32 = 1;
+const CMD_LINE_TO: u32 = 2;
+const CMD_CLOSE_PATH: u32 = 7;
+
+/// Points of a decoded geometry path and whether `ClosePath` ended it.
+type Path = (Vec<(f32, f32)>, bool);
+type FeatureFilter = Box<dyn Fn(&Feature) -> bool>;
+/// Shapes in tile pixels, each with the offset to place it at.
+type PlacedShapes = Vec<(ShapeRenderable, (f32, f32))>;
+
+/// A decoded vector tile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorTile {
+    pub layers: Vec<VectorLayer>,
+}
+
+/// A named layer of a vector tile, e.g. `"water"` or `"roads"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorLayer {
+    pub name: String,
+    /// Size of the tile in the coordinates of this layer's features
+    /// (usually 4096).
+    pub extent: u32,
+    pub features: Vec<Feature>,
+}
+
+/// A feature: geometry in tile coordinates (origin at the north-west
+/// corner, `y` down) and its properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub id: Option<u64>,
+    pub properties: HashMap<String, PropertyValue>,
+    pub geometry: FeatureGeometry,
+}
+
+impl Feature {
+    pub fn property(&self, key: &str) -> Option<&PropertyValue> {
+        self.properties.get(key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureGeometry {
+    Points(Vec<(f32, f32)>),
+    LineStrings(Vec<Vec<(f32, f32)>>),
+    /// Each polygon is its exterior ring followed by its holes. Rings are
+    /// not closed by repeating the first point.
+    Polygons(Vec<Vec<Vec<(f32, f32)>>>),
+    Unknown,
+}
+
+/// A feature property value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    String(String),
+    Float(f32),
+    Double(f64),
+    Int(i64),
+    UInt(u64),
+    Bool(bool),
+}
+
+impl PropertyValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Any numeric value as `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            PropertyValue::Float(v) => Some(v as f64),
+            PropertyValue::Double(v) => Some(v),
+            PropertyValue::Int(v) => Some(v as f64),
+            PropertyValue::UInt(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+}
+
+impl VectorTile {
+    /// Decode an uncompressed MVT protobuf (servers often gzip tiles;
+    /// decompress them first).
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return Err("vector tile is gzip-compressed; decompress it before decoding".to_string());
+        }
+        let mut tile = VectorTile::default();
+        let mut reader = Protobuf::new(bytes);
+        while !reader.at_end() {
+            match reader.key()? {
+                (3, 2) => tile.layers.push(decode_layer(reader.bytes()?)?),
+                (_, wire) => reader.skip(wire)?,
+            }
+        }
+        Ok(tile)
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&VectorLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+}
+
+/// Minimal protobuf wire-format reader.
+struct Protobuf<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Protobuf<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.data.get(self.pos).ok_or("truncated varint")?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint longer than 64 bits".to_string())
+    }
+
+    /// Field number and wire type.
+    fn key(&mut self) -> Result<(u64, u8), String> {
+        let key = self.varint()?;
+        Ok((key >> 3, (key & 7) as u8))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len()).ok_or("truncated field")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// A length-delimited field.
+    fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|e| e.to_string())
+    }
+
+    /// A packed repeated `uint32` field.
+    fn packed(&mut self) -> Result<Vec<u32>, String> {
+        let mut reader = Protobuf::new(self.bytes()?);
+        let mut values = Vec::new();
+        while !reader.at_end() {
+            values.push(reader.varint()? as u32);
+        }
+        Ok(values)
+    }
+
+    fn skip(&mut self, wire: u8) -> Result<(), String> {
+        match wire {
+            0 => self.varint().map(drop),
+            1 => self.take(8).map(drop),
+            2 => self.bytes().map(drop),
+            5 => self.take(4).map(drop),
+            _ => Err(format!("unsupported wire type {wire}")),
+        }
+    }
+}
+
+fn decode_layer(bytes: &[u8]) -> Result<VectorLayer, String> {
+    let mut reader = Protobuf::new(bytes);
+    let mut name = String::new();
+    let mut extent = DEFAULT_EXTENT;
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    // Features refer to keys and values by index, which may come later.
+    let mut raw_features = Vec::new();
+    while !reader.at_end() {
+        match reader.key()? {
+            (1, 2) => name = reader.string()?,
+            (2, 2) => raw_features.push(reader.bytes()?),
+            (3, 2) => keys.push(reader.string()?),
+            (4, 2) => values.push(decode_value(reader.bytes()?)?),
+            (5, 0) => extent = reader.varint()? as u32,
+            (_, wire) => reader.skip(wire)?,
+        }
+    }
+    let features = raw_features
+        .into_iter()
+        .map(|bytes| decode_feature(bytes, &keys, &values))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("layer `{name}`: {e}"))?;
+    Ok(VectorLayer { name, extent, features })
+}
+
+fn decode_value(bytes: &[u8]) -> Result<PropertyValue, String> {
+    let mut reader = Protobuf::new(bytes);
+    let mut value = None;
+    while !reader.at_end() {
+        value = Some(match reader.key()? {
+            (1, 2) => PropertyValue::String(reader.string()?),
+            (2, 5) => PropertyValue::Float(f32::from_le_bytes(reader.take(4)?.try_into().unwrap())),
+            (3, 1) => PropertyValue::Double(f64::from_le_bytes(reader.take(8)?.try_into().unwrap())),
+            (4, 0) => PropertyValue::Int(reader.varint()? as i64),
+            (5, 0) => PropertyValue::UInt(reader.varint()?),
+            (6, 0) => PropertyValue::Int(zigzag(reader.varint()?)),
+            (7, 0) => PropertyValue::Bool(reader.varint()? != 0),
+            (_, wire) => {
+                reader.skip(wire)?;
+                continue;
+            }
+        });
+    }
+    value.ok_or_else(|| "empty property value".to_string())
+}
+
+fn decode_feature(bytes: &[u8], keys: &[String], values: &[PropertyValue]) -> Result<Feature, String> {
+    let mut reader = Protobuf::new(bytes);
+    let (mut id, mut tags, mut kind, mut commands) = (None, Vec::new(), 0, Vec::new());
+    while !reader.at_end() {
+        match reader.key()? {
+            (1, 0) => id = Some(reader.varint()?),
+            (2, 2) => tags = reader.packed()?,
+            (3, 0) => kind = reader.varint()?,
+            (4, 2) => commands = reader.packed()?,
+            (_, wire) => reader.skip(wire)?,
+        }
+    }
+    let properties = tags
+        .chunks_exact(2)
+        .map(|pair| {
+            let key = keys.get(pair[0] as usize).ok_or("property key index out of range")?;
+            let value = values.get(pair[1] as usize).ok_or("property value index out of range")?;
+            Ok((key.clone(), value.clone()))
+        })
+        .collect::<Result<_, String>>()?;
+    let paths = decode_commands(&commands)?;
+    let geometry = match kind {
+        1 => FeatureGeometry::Points(paths.into_iter().flat_map(|(points, _)| points).collect()),
+        2 => FeatureGeometry::LineStrings(paths.into_iter().map(|(points, _)| points).collect()),
+        3 => FeatureGeometry::Polygons(group_rings(paths.into_iter().map(|(points, _)| points))),
+        _ => FeatureGeometry::Unknown,
+    };
+    Ok(Feature { id, properties, geometry })
+}
+
+fn zigzag(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+/// Run the geometry command stream into paths, each starting at a `MoveTo`
+/// and flagged when closed by `ClosePath`.
+fn decode_commands(commands: &[u32]) -> Result<Vec<Path>, String> {
+    let mut paths: Vec<Path> = Vec::new();
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut i = 0;
+    while i < commands.len() {
+        let (id, count) = (commands[i] & 7, (commands[i] >> 3) as usize);
+        i += 1;
+        match id {
+            CMD_MOVE_TO | CMD_LINE_TO => {
+                let params = commands.get(i..i + 2 * count).ok_or("truncated geometry command")?;
+                i += 2 * count;
+                for pair in params.chunks_exact(2) {
+                    x += zigzag(pair[0] as u64);
+                    y += zigzag(pair[1] as u64);
+                    if id == CMD_MOVE_TO {
+                        paths.push((Vec::new(), false));
+                    }
+                    let (path, _) = paths.last_mut().ok_or("LineTo before MoveTo")?;
+                    path.push((x as f32, y as f32));
+                }
+            }
+            CMD_CLOSE_PATH => paths.last_mut().ok_or("ClosePath before MoveTo")?.1 = true,
+            _ => return Err(format!("unknown geometry command {id}")),
+        }
+    }
+    Ok(paths)
+}
+
+/// Split polygon rings into polygons: a ring with positive area in tile
+/// coordinates starts a polygon, negative rings are its holes.
+fn group_rings(rings: impl Iterator<Item = Vec<(f32, f32)>>) -> Vec<Vec<Vec<(f32, f32)>>> {
+    let mut polygons: Vec<Vec<Vec<(f32, f32)>>> = Vec::new();
+    for ring in rings {
+        let area = Polygon::new(ring.clone()).signed_area();
+        if area > 0.0 {
+            polygons.push(vec![ring]);
+        } else if area < 0.0 {
+            if let Some(polygon) = polygons.last_mut() {
+                polygon.push(ring);
+            }
+        }
+    }
+    polygons
+}
+
+/// Which features to draw and how: everything in one source layer within a
+/// zoom range that passes the filter.
+///
+/// Widths and radii are in screen pixels at the tile's native size; they
+/// follow the map when it is zoomed between tile levels.
+pub struct StyleRule {
+    layer: String,
+    min_zoom: u8,
+    max_zoom: u8,
+    filter: Option<FeatureFilter>,
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    width_stops: Vec<(f32, f32)>,
+    point_radius: f32,
+}
+
+impl StyleRule {
+    /// Match every feature of `layer` at every zoom level, drawing nothing
+    /// until a fill or stroke is set.
+    pub fn new(layer: impl Into<String>) -> Self {
+        Self {
+            layer: layer.into(),
+            min_zoom: 0,
+            max_zoom: u8::MAX,
+            filter: None,
+            fill: None,
+            stroke: None,
+            width_stops: vec![(0.0, 1.0)],
+            point_radius: 3.0,
+        }
+    }
+
+    /// Only apply at tile zoom levels `min..=max`.
+    pub fn zoom_range(mut self, min: u8, max: u8) -> Self {
+        self.min_zoom = min;
+        self.max_zoom = max;
+        self
+    }
+
+    /// Only apply to features for which `filter` returns `true`.
+    pub fn filter(mut self, filter: impl Fn(&Feature) -> bool + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Fill polygons and points.
+    pub fn fill(mut self, color: Color) -> Self {
+        self.fill = Some(color);
+        self
+    }
+
+    /// Stroke lines, polygon outlines and points.
+    pub fn stroke(mut self, color: Color, width: f32) -> Self {
+        self.stroke = Some(color);
+        self.width_stops = vec![(0.0, width)];
+        self
+    }
+
+    /// Make the stroke width depend on zoom: linear between `(zoom, width)`
+    /// stops, constant outside them.
+    pub fn width_stops(mut self, stops: &[(f32, f32)]) -> Self {
+        if !stops.is_empty() {
+            self.width_stops = stops.to_vec();
+            self.width_stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        self
+    }
+
+    /// Radius of the circles drawn for point features (default 3).
+    pub fn point_radius(mut self, radius: f32) -> Self {
+        self.point_radius = radius;
+        self
+    }
+
+    /// Stroke width at `zoom`.
+    pub fn width_at(&self, zoom: f32) -> f32 {
+        let stops = &self.width_stops;
+        let next = stops.partition_point(|(z, _)| *z <= zoom);
+        match (next.checked_sub(1).map(|i| stops[i]), stops.get(next)) {
+            (Some((z0, w0)), Some(&(z1, w1))) => w0 + (w1 - w0) * (zoom - z0) / (z1 - z0),
+            (Some((_, w)), None) | (None, Some(&(_, w))) => w,
+            (None, None) => 1.0,
+        }
+    }
+
+    fn applies(&self, layer: &str, zoom: u8, feature: &Feature) -> bool {
+        self.layer == layer
+            && (self.min_zoom..=self.max_zoom).contains(&zoom)
+            && self.filter.as_ref().is_none_or(|filter| filter(feature))
+    }
+
+    /// Append the shapes for one feature to `out`.
+    fn shapes(&self, geometry: &FeatureGeometry, zoom: u8, scale: f32, out: &mut PlacedShapes) {
+        let width = self.width_at(zoom as f32);
+        let pixels = |ring: &[(f32, f32)]| ring.iter().map(|&(x, y)| (x * scale, y * scale)).collect::<Vec<_>>();
+        let at_origin = |shape, style| (ShapeRenderable::builder(shape, style).anchor(Anchor::Custom(0.0, 0.0)).build(), (0.0, 0.0));
+        match geometry {
+            FeatureGeometry::Polygons(polygons) => {
+                for rings in polygons.iter().filter(|rings| rings[0].len() >= 3) {
+                    if let Some(fill) = self.fill {
+                        let holes = rings[1..].iter().map(|ring| pixels(ring)).collect();
+                        out.push(at_origin(ShapeKind::Polygon(Polygon::with_holes(pixels(&rings[0]), holes)), ShapeStyle::fill(fill)));
+                    }
+                    if let Some(stroke) = self.stroke {
+                        for ring in rings {
+                            let mut points = pixels(ring);
+                            points.push(points[0]);
+                            out.push(at_origin(ShapeKind::Polyline(Polyline::new(points)), ShapeStyle::stroke(stroke, width)));
+                        }
+                    }
+                }
+            }
+            FeatureGeometry::LineStrings(lines) => {
+                if let Some(stroke) = self.stroke {
+                    for line in lines.iter().filter(|line| line.len() >= 2) {
+                        out.push(at_origin(ShapeKind::Polyline(Polyline::new(pixels(line))), ShapeStyle::stroke(stroke, width)));
+                    }
+                }
+            }
+            FeatureGeometry::Points(points) => {
+                let style = match (self.fill, self.stroke) {
+                    (Some(fill), Some(stroke)) => ShapeStyle::fill_and_stroke(fill, stroke, width),
+                    (Some(fill), None) => ShapeStyle::fill(fill),
+                    (None, Some(stroke)) => ShapeStyle::stroke(stroke, width),
+                    (None, None) => return,
+                };
+                for &(x, y) in points {
+                    let shape = ShapeRenderable::from_shape(ShapeKind::Circle(Circle::new(self.point_radius)), style.clone());
+                    out.push((shape, (x * scale, y * scale)));
+                }
+            }
+            FeatureGeometry::Unknown => {}
+        }
+    }
+}
+
+/// Rules applied in order, so later rules draw on top.
+#[derive(Default)]
+pub struct VectorStyle {
+    rules: Vec<StyleRule>,
+}
+
+impl VectorStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(mut self, rule: StyleRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[StyleRule] {
+        &self.rules
+    }
+
+    /// Shapes for a tile at zoom level `zoom`, in tile pixels
+    /// (`0..TILE_SIZE`).
+    fn build(&self, tile: &VectorTile, zoom: u8) -> PlacedShapes {
+        let mut shapes = Vec::new();
+        for rule in &self.rules {
+            for layer in tile.layers.iter().filter(|l| l.name == rule.layer) {
+                let scale = TILE_SIZE as f32 / layer.extent.max(1) as f32;
+                for feature in layer.features.iter().filter(|f| rule.applies(&layer.name, zoom, f)) {
+                    rule.shapes(&feature.geometry, zoom, scale, &mut shapes);
+                }
+            }
+        }
+        shapes
+    }
+}
+
+struct StyledTile {
+    data: VectorTile,
+    /// Built on first draw after loading or a style change.
+    shapes: Option<PlacedShapes>,
+    last_used: u64,
+}
+
+/// A map drawn from vector tiles with a client-side [`VectorStyle`]. See
+/// the [module docs](self).
+///
+/// Call [`render`](Self::render) every frame. Decoded tiles are kept up to
+/// [`set_capacity`](Self::set_capacity), least recently drawn first out;
+/// while a tile loads its closest loaded ancestor is drawn instead.
+pub struct VectorTileLayer {
+    loader: TileLoader<VectorTile>,
+    style: VectorStyle,
+    tiles: HashMap<TileId, StyledTile>,
+    min_zoom: u8,
+    max_zoom: u8,
+    capacity: usize,
+    frame: u64,
+}
+
+impl VectorTileLayer {
+    /// Fetch from `source` on 4 worker threads, zoom levels 0 to 14.
+    pub fn new(source: impl TileSource + 'static, style: VectorStyle) -> Self {
+        Self {
+            loader: TileLoader::new(source, DEFAULT_WORKERS, VectorTile::decode),
+            style,
+            tiles: HashMap::new(),
+            min_zoom: 0,
+            max_zoom: 14,
+            capacity: DEFAULT_CAPACITY,
+            frame: 0,
+        }
+    }
+
+    /// Zoom levels the source provides. Beyond `max`, its tiles are scaled
+    /// up ("overzoomed").
+    pub fn set_zoom_range(&mut self, min: u8, max: u8) -> &mut Self {
+        self.min_zoom = min.min(max);
+        self.max_zoom = max;
+        self
+    }
+
+    /// Number of decoded tiles to keep (default 128). Tiles in view are
+    /// always kept.
+    pub fn set_capacity(&mut self, tiles: usize) -> &mut Self {
+        self.capacity = tiles;
+        self
+    }
+
+    /// Replace the style. Loaded tiles are restyled as they are drawn.
+    pub fn set_style(&mut self, style: VectorStyle) {
+        self.style = style;
+        for tile in self.tiles.values_mut() {
+            tile.shapes = None;
+        }
+    }
+
+    pub fn style(&self) -> &VectorStyle {
+        &self.style
+    }
+
+    /// Tiles that failed to load or decode since the last call.
+    pub fn take_errors(&mut self) -> Vec<(TileId, String)> {
+        self.loader.take_errors()
+    }
+
+    pub fn retry_failed(&mut self) {
+        self.loader.retry_failed();
+    }
+
+    /// Fetch tiles for the current view, then draw them.
+    pub fn render(&mut self, renderer: &Renderer, camera: &Camera2D) {
+        self.frame += 1;
+        for (tile, data) in self.loader.receive() {
+            self.tiles.insert(tile, StyledTile { data, shapes: None, last_used: self.frame });
+        }
+
+        let (_, visible) = visible_tiles(camera, self.min_zoom, self.max_zoom);
+        self.loader.request(&visible, |t| self.tiles.contains_key(t));
+        let missing = visible.iter().filter(|t| !self.tiles.contains_key(t));
+        let fallbacks = fallback_tiles(missing, |t| self.tiles.contains_key(t));
+
+        for tile in fallbacks.iter().chain(&visible) {
+            let Some(styled) = self.tiles.get_mut(tile) else { continue };
+            let (corner, scale) = tile_placement(*tile, camera);
+            let (sin, cos) = (-camera.rotation()).sin_cos();
+            let shapes = styled.shapes.get_or_insert_with(|| self.style.build(&styled.data, tile.z));
+            for (shape, (dx, dy)) in shapes {
+                let (dx, dy) = (*dx * scale, *dy * scale);
+                shape
+                    .set_position(corner.x + dx * cos - dy * sin, corner.y + dx * sin + dy * cos)
+                    .set_rotation(-camera.rotation())
+                    .set_scale(scale);
+                shape.render(renderer);
+            }
+            styled.last_used = self.frame;
+        }
+
+        evict_least_recent(&mut self.tiles, self.capacity, self.frame, |t| t.last_used);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn field(number: u64, payload: &[u8], out: &mut Vec<u8>) {
+        varint(number << 3 | 2, out);
+        varint(payload.len() as u64, out);
+        out.extend_from_slice(payload);
+    }
+
+    fn packed(values: &[u32]) -> Vec<u8> {
+        let mut out = Vec::new();
+        values.iter().for_each(|&v| varint(v as u64, &mut out));
+        out
+    }
+
+    fn zz(n: i32) -> u32 {
+        ((n << 1) ^ (n >> 31)) as u32
+    }
+
+    fn command(id: u32, count: u32) -> u32 {
+        (count << 3) | id
+    }
+
+    /// A layer with one polygon (square with a square hole) tagged
+    /// `class=lake` and one line.
+    fn sample_tile() -> Vec<u8> {
+        let mut polygon = Vec::new();
+        varint(1 << 3, &mut polygon);
+        varint(7, &mut polygon);
+        field(2, &packed(&[0, 0]), &mut polygon);
+        varint(3 << 3, &mut polygon);
+        varint(3, &mut polygon);
+        let geometry = [
+            command(CMD_MOVE_TO, 1), zz(0), zz(0),
+            command(CMD_LINE_TO, 3), zz(100), zz(0), zz(0), zz(100), zz(-100), zz(0),
+            command(CMD_CLOSE_PATH, 1),
+            command(CMD_MOVE_TO, 1), zz(25), zz(-75),
+            command(CMD_LINE_TO, 3), zz(0), zz(50), zz(50), zz(0), zz(0), zz(-50),
+            command(CMD_CLOSE_PATH, 1),
+        ];
+        field(4, &packed(&geometry), &mut polygon);
+
+        let mut line = Vec::new();
+        varint(3 << 3, &mut line);
+        varint(2, &mut line);
+        field(4, &packed(&[command(CMD_MOVE_TO, 1), zz(10), zz(10), command(CMD_LINE_TO, 2), zz(5), zz(0), zz(0), zz(5)]), &mut line);
+
+        let mut value = Vec::new();
+        field(1, b"lake", &mut value);
+
+        let mut layer = Vec::new();
+        field(1, b"water", &mut layer);
+        field(2, &polygon, &mut layer);
+        field(2, &line, &mut layer);
+        field(3, b"class", &mut layer);
+        field(4, &value, &mut layer);
+        varint(5 << 3, &mut layer);
+        varint(512, &mut layer);
+
+        let mut tile = Vec::new();
+        field(3, &layer, &mut tile);
+        tile
+    }
+
+    #[test]
+    fn decodes_layers_features_and_geometry() {
+        let tile = VectorTile::decode(&sample_tile()).unwrap();
+        let water = tile.layer("water").unwrap();
+        assert_eq!(water.extent, 512);
+        assert_eq!(water.features.len(), 2);
+
+        let lake = &water.features[0];
+        assert_eq!(lake.id, Some(7));
+        assert_eq!(lake.property("class").and_then(PropertyValue::as_str), Some("lake"));
+        let FeatureGeometry::Polygons(polygons) = &lake.geometry else { panic!("not a polygon") };
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 2);
+        assert_eq!(polygons[0][0], vec![(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        assert_eq!(polygons[0][1][0], (25.0, 25.0));
+
+        assert_eq!(water.features[1].geometry, FeatureGeometry::LineStrings(vec![vec![(10.0, 10.0), (15.0, 10.0), (15.0, 15.0)]]));
+    }
+
+    #[test]
+    fn rejects_malformed_tiles() {
+        let tile = sample_tile();
+        assert!(VectorTile::decode(&tile[..tile.len() - 3]).is_err());
+        assert!(VectorTile::decode(&[0x1f, 0x8b, 0x08]).unwrap_err().contains("gzip"));
+        assert!(decode_commands(&[command(CMD_LINE_TO, 1), 0, 0]).is_err());
+    }
+
+    #[test]
+    fn rules_match_layer_zoom_and_filter_and_interpolate_width() {
+        let tile = VectorTile::decode(&sample_tile()).unwrap();
+        let lake = &tile.layers[0].features[0];
+        let rule = StyleRule::new("water")
+            .zoom_range(4, 10)
+            .filter(|f| f.property("class").and_then(PropertyValue::as_str) == Some("lake"))
+            .width_stops(&[(10.0, 4.0), (6.0, 2.0)]);
+        assert!(rule.applies("water", 6, lake));
+        assert!(!rule.applies("water", 11, lake));
+        assert!(!rule.applies("roads", 6, lake));
+        assert!(!rule.applies("water", 6, &tile.layers[0].features[1]));
+        assert_eq!((rule.width_at(2.0), rule.width_at(8.0), rule.width_at(12.0)), (2.0, 3.0, 4.0));
+    }
+}
@@ -0,0 +1,341 @@
+//! Perspective camera and mouse-driven orbit controller.
+
+use std::f32::consts::FRAC_PI_2;
+
+use crate::core::engine::glfw::{GLFW_MOUSE_BUTTON_LEFT, GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS};
+use crate::core::{Mat4, Vec2, Vec3};
+
+/// A perspective camera looking from `eye` at `target`.
+///
+/// The screen size sets the aspect ratio and the pixel coordinates used by
+/// [`world_to_screen`](Self::world_to_screen), in the window's pixel units
+/// with Y down like [`Camera2D`](crate::core::Camera2D).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera3D {
+    eye: Vec3,
+    target: Vec3,
+    up: Vec3,
+    fov_y: f32,
+    near: f32,
+    far: f32,
+    screen_size: Vec2,
+}
+
+impl Camera3D {
+    /// Camera with a 45° vertical field of view and clip planes at 0.1
+    /// and 1000.
+    pub fn new(eye: Vec3, target: Vec3, screen_size: Vec2) -> Self {
+        Self {
+            eye,
+            target,
+            up: Vec3::Y,
+            fov_y: 45f32.to_radians(),
+            near: 0.1,
+            far: 1000.0,
+            screen_size,
+        }
+    }
+
+    pub fn eye(&self) -> Vec3 {
+        self.eye
+    }
+
+    pub fn set_eye(&mut self, eye: Vec3) {
+        self.eye = eye;
+    }
+
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.up
+    }
+
+    pub fn set_up(&mut self, up: Vec3) {
+        self.up = up;
+    }
+
+    /// Vertical field of view in radians.
+    pub fn fov_y(&self) -> f32 {
+        self.fov_y
+    }
+
+    pub fn set_fov_y(&mut self, radians: f32) {
+        self.fov_y = radians;
+    }
+
+    /// Near and far clip plane distances.
+    pub fn clip_planes(&self) -> (f32, f32) {
+        (self.near, self.far)
+    }
+
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+    }
+
+    pub fn screen_size(&self) -> Vec2 {
+        self.screen_size
+    }
+
+    pub fn set_screen_size(&mut self, screen_size: Vec2) {
+        self.screen_size = screen_size;
+    }
+
+    pub fn aspect(&self) -> f32 {
+        if self.screen_size.y > 0.0 { self.screen_size.x / self.screen_size.y } else { 1.0 }
+    }
+
+    pub fn view(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye, self.target, self.up)
+    }
+
+    pub fn projection(&self) -> Mat4 {
+        Mat4::perspective_rh_gl(self.fov_y, self.aspect(), self.near, self.far)
+    }
+
+    pub fn view_projection(&self) -> Mat4 {
+        self.projection() * self.view()
+    }
+
+    /// Screen position of a world point, e.g. to place 2D labels over 3D
+    /// content, or `None` if it lies outside the clip planes.
+    pub fn world_to_screen(&self, world: Vec3) -> Option<Vec2> {
+        let depth = -self.view().transform_point(world).z;
+        if depth < self.near || depth > self.far {
+            return None;
+        }
+        let ndc = self.view_projection().transform_point(world);
+        Some(Vec2::new(
+            (ndc.x + 1.0) * 0.5 * self.screen_size.x,
+            (1.0 - ndc.y) * 0.5 * self.screen_size.y,
+        ))
+    }
+}
+
+/// Pitch stays short of straight up or down, where the view's up vector
+/// would be parallel to the view direction.
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+/// Input-driven controller orbiting a [`Camera3D`] around a target point.
+///
+/// - **Left drag**: orbit (yaw around Y, pitch up and down)
+/// - **Right drag**: pan the target in the view plane
+/// - **Scroll**: move closer to or away from the target
+///
+/// Connect it to window callbacks like [`CameraController`](crate::core::CameraController):
+///
+/// ```ignore
+/// window.on_mouse_button(move |button, action, _| orbit.borrow_mut().on_mouse_button(button, action));
+/// window.on_cursor_position(move |x, y| orbit.borrow_mut().on_cursor_move(x, y));
+/// window.on_scroll(move |_, y| orbit.borrow_mut().on_scroll(y));
+/// window.on_resize(move |w, h| orbit.borrow_mut().on_resize(w as f32, h as f32));
+/// ```
+pub struct OrbitController {
+    camera: Camera3D,
+    target: Vec3,
+    /// Radians around Y, 0 looking along -Z.
+    yaw: f32,
+    /// Radians above the horizon.
+    pitch: f32,
+    distance: f32,
+    min_distance: f32,
+    max_distance: f32,
+    rotate_sensitivity: f32,
+    zoom_sensitivity: f32,
+    rotating: bool,
+    panning: bool,
+    last_cursor_pos: Vec2,
+}
+
+impl OrbitController {
+    /// Orbit `target` from `distance` away, looking down at 30° from the
+    /// +Z side.
+    pub fn new(target: Vec3, distance: f32, screen_size: Vec2) -> Self {
+        let mut controller = Self {
+            camera: Camera3D::new(target, target, screen_size),
+            target,
+            yaw: 0.0,
+            pitch: 30f32.to_radians(),
+            distance,
+            min_distance: 0.1,
+            max_distance: f32::INFINITY,
+            rotate_sensitivity: 0.01,
+            zoom_sensitivity: 1.1,
+            rotating: false,
+            panning: false,
+            last_cursor_pos: Vec2::new(0.0, 0.0),
+        };
+        controller.update_camera();
+        controller
+    }
+
+    pub fn camera(&self) -> &Camera3D {
+        &self.camera
+    }
+
+    /// Mutable camera, for field of view and clip planes. Its eye and
+    /// target are overwritten on the next orbit change.
+    pub fn camera_mut(&mut self) -> &mut Camera3D {
+        &mut self.camera
+    }
+
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+        self.update_camera();
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.clamp(self.min_distance, self.max_distance);
+        self.update_camera();
+    }
+
+    /// Yaw around the Y axis and pitch above the horizon, in radians.
+    pub fn angles(&self) -> (f32, f32) {
+        (self.yaw, self.pitch)
+    }
+
+    pub fn set_angles(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        self.update_camera();
+    }
+
+    /// Limit how close to and far from the target scrolling can go.
+    /// Defaults to 0.1 and unbounded.
+    pub fn set_distance_limits(&mut self, min: f32, max: f32) {
+        self.min_distance = min;
+        self.max_distance = max;
+        self.set_distance(self.distance);
+    }
+
+    /// Radians of orbit per pixel dragged. Default is 0.01.
+    pub fn set_rotate_sensitivity(&mut self, sensitivity: f32) {
+        self.rotate_sensitivity = sensitivity;
+    }
+
+    /// Distance factor per scroll tick. Default is 1.1.
+    pub fn set_zoom_sensitivity(&mut self, sensitivity: f32) {
+        self.zoom_sensitivity = sensitivity;
+    }
+
+    /// Handle window resize.
+    pub fn on_resize(&mut self, width: f32, height: f32) {
+        self.camera.set_screen_size(Vec2::new(width, height));
+    }
+
+    /// Handle mouse button events. Call this from `Window::on_mouse_button`.
+    pub fn on_mouse_button(&mut self, button: i32, action: i32) {
+        let pressed = action == GLFW_PRESS;
+        match button {
+            GLFW_MOUSE_BUTTON_LEFT => self.rotating = pressed,
+            GLFW_MOUSE_BUTTON_RIGHT => self.panning = pressed,
+            _ => {}
+        }
+    }
+
+    /// Handle cursor movement. Call this from `Window::on_cursor_position`.
+    pub fn on_cursor_move(&mut self, x: f64, y: f64) {
+        let cursor = Vec2::new(x as f32, y as f32);
+        let delta = Vec2::new(cursor.x - self.last_cursor_pos.x, cursor.y - self.last_cursor_pos.y);
+        self.last_cursor_pos = cursor;
+
+        if self.rotating {
+            self.set_angles(
+                self.yaw - delta.x * self.rotate_sensitivity,
+                self.pitch + delta.y * self.rotate_sensitivity,
+            );
+        } else if self.panning {
+            // World units per pixel at the target's depth
+            let height = 2.0 * self.distance * (self.camera.fov_y() * 0.5).tan();
+            let per_pixel = height / self.camera.screen_size().y.max(1.0);
+            let forward = (self.camera.target() - self.camera.eye()).normalize();
+            let right = forward.cross(Vec3::Y).normalize();
+            let up = right.cross(forward);
+            self.target = self.target - right * (delta.x * per_pixel) + up * (delta.y * per_pixel);
+            self.update_camera();
+        }
+    }
+
+    /// Handle scroll events: positive offsets move closer to the target.
+    pub fn on_scroll(&mut self, y_offset: f64) {
+        let factor = if y_offset > 0.0 { 1.0 / self.zoom_sensitivity } else { self.zoom_sensitivity };
+        self.set_distance(self.distance * factor);
+    }
+
+    fn update_camera(&mut self) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let offset = Vec3::new(sin_yaw * cos_pitch, sin_pitch, cos_yaw * cos_pitch) * self.distance;
+        self.camera.set_eye(self.target + offset);
+        self.camera.set_target(self.target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::glfw::GLFW_RELEASE;
+
+    #[test]
+    fn target_projects_to_screen_center() {
+        let camera = Camera3D::new(Vec3::new(0.0, 5.0, 10.0), Vec3::ZERO, Vec2::new(800.0, 600.0));
+        let center = camera.world_to_screen(Vec3::ZERO).unwrap();
+        assert!((center.x - 400.0).abs() < 1e-3);
+        assert!((center.y - 300.0).abs() < 1e-3);
+        // Points above the target are higher on screen (smaller Y)
+        assert!(camera.world_to_screen(Vec3::Y).unwrap().y < 300.0);
+        // Behind the camera
+        assert_eq!(camera.world_to_screen(Vec3::new(0.0, 10.0, 20.0)), None);
+    }
+
+    #[test]
+    fn orbit_keeps_distance_and_clamps_pitch() {
+        let mut orbit = OrbitController::new(Vec3::new(1.0, 0.0, 0.0), 10.0, Vec2::new(800.0, 600.0));
+        orbit.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS);
+        orbit.on_cursor_move(100.0, 10_000.0);
+        orbit.on_mouse_button(GLFW_MOUSE_BUTTON_LEFT, GLFW_RELEASE);
+
+        let eye = orbit.camera().eye();
+        assert!(((eye - orbit.target()).length() - 10.0).abs() < 1e-3);
+        assert_eq!(orbit.angles().1, MAX_PITCH);
+    }
+
+    #[test]
+    fn scroll_zooms_within_limits() {
+        let mut orbit = OrbitController::new(Vec3::ZERO, 10.0, Vec2::new(800.0, 600.0));
+        orbit.set_distance_limits(5.0, 20.0);
+        for _ in 0..20 {
+            orbit.on_scroll(1.0);
+        }
+        assert_eq!(orbit.distance(), 5.0);
+        for _ in 0..20 {
+            orbit.on_scroll(-1.0);
+        }
+        assert_eq!(orbit.distance(), 20.0);
+    }
+
+    #[test]
+    fn right_drag_pans_target_sideways() {
+        let mut orbit = OrbitController::new(Vec3::ZERO, 10.0, Vec2::new(800.0, 600.0));
+        orbit.set_angles(0.0, 0.0);
+        orbit.on_mouse_button(GLFW_MOUSE_BUTTON_RIGHT, GLFW_PRESS);
+        orbit.on_cursor_move(-50.0, 0.0);
+        // Dragging left moves the view right: the target moves to +X
+        assert!(orbit.target().x > 0.0);
+        assert!(orbit.target().y.abs() < 1e-5 && orbit.target().z.abs() < 1e-5);
+    }
+}
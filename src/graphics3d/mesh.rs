@@ -0,0 +1,338 @@
+//! Depth-tested 3D meshes and the scene drawing them.
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::core::engine::leaks::{self, Resource};
+use crate::core::engine::opengl::{
+    gl_clear_depth_buffer, gl_depth_func, gl_disable, gl_enable, gl_get_uniform_location, gl_uniform_1f,
+    gl_uniform_3f, gl_uniform_matrix_4f, GL_DEPTH_TEST, GL_LESS, GL_LINES, GL_TRIANGLES,
+};
+use crate::core::{Attribute, Color, Geometry, Mat4, Mesh, Renderer, Shader, Vec3};
+use crate::graphics3d::camera::Camera3D;
+
+/// Floats per vertex: position (3), normal (3), color (4).
+const VERTEX_SIZE: usize = 10;
+
+/// How [`MeshData`] vertices are assembled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// Every three vertices form a triangle.
+    #[default]
+    Triangles,
+    /// Every two vertices form a line, drawn unlit.
+    Lines,
+}
+
+/// Vertex data of a mesh on the CPU: one position, normal and color per
+/// vertex, in [`Topology`] order. Vertices with a zero normal are unlit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    pub topology: Topology,
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<Color>,
+}
+
+impl MeshData {
+    pub fn new(topology: Topology) -> Self {
+        Self { topology, ..Self::default() }
+    }
+
+    pub fn push_vertex(&mut self, position: Vec3, normal: Vec3, color: Color) {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.colors.push(color);
+    }
+
+    /// Append a flat-shaded triangle, its normal following the
+    /// counter-clockwise winding `a`, `b`, `c`.
+    pub fn push_triangle(&mut self, a: Vec3, b: Vec3, c: Vec3, color: Color) {
+        let normal = (b - a).cross(c - a).normalize();
+        for position in [a, b, c] {
+            self.push_vertex(position, normal, color);
+        }
+    }
+
+    /// Append all vertices of `other`, which must have the same topology.
+    pub fn append(&mut self, other: &MeshData) {
+        self.positions.extend_from_slice(&other.positions);
+        self.normals.extend_from_slice(&other.normals);
+        self.colors.extend_from_slice(&other.colors);
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check that every vertex has a normal and a color, and that the
+    /// vertices make whole triangles or lines.
+    pub fn validate(&self) -> Result<(), String> {
+        let n = self.positions.len();
+        if self.normals.len() != n || self.colors.len() != n {
+            return Err(format!(
+                "{} positions but {} normals and {} colors",
+                n,
+                self.normals.len(),
+                self.colors.len()
+            ));
+        }
+        let per_primitive = match self.topology {
+            Topology::Triangles => 3,
+            Topology::Lines => 2,
+        };
+        if !n.is_multiple_of(per_primitive) {
+            return Err(format!("{n} vertices do not make whole {:?}", self.topology));
+        }
+        Ok(())
+    }
+
+    /// Interleaved position, normal and color floats, as uploaded.
+    pub fn interleaved(&self) -> Vec<f32> {
+        let mut buffer = Vec::with_capacity(self.positions.len() * VERTEX_SIZE);
+        for ((p, n), c) in self.positions.iter().zip(&self.normals).zip(&self.colors) {
+            buffer.extend_from_slice(&[p.x, p.y, p.z, n.x, n.y, n.z]);
+            buffer.extend_from_slice(&[c.red_value(), c.green_value(), c.blue_value(), c.alpha()]);
+        }
+        buffer
+    }
+
+    /// Axis-aligned bounds `(min, max)`, or `None` when empty.
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        let first = *self.positions.first()?;
+        Some(self.positions.iter().fold((first, first), |(min, max), p| {
+            (
+                Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        }))
+    }
+}
+
+/// A directional light plus ambient term, shared by a scene's meshes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    /// Direction the light travels, from the light toward the scene.
+    pub direction: Vec3,
+    /// Fraction of the color kept on faces turned away from the light,
+    /// 0 to 1.
+    pub ambient: f32,
+}
+
+impl Default for Light {
+    /// Light from above, slightly in front and to the left.
+    fn default() -> Self {
+        Self { direction: Vec3::new(0.4, -1.0, -0.3), ambient: 0.3 }
+    }
+}
+
+thread_local! {
+    static MESH3D_SHADER: OnceCell<Rc<Shader>> = const { OnceCell::new() };
+}
+
+fn mesh3d_shader() -> Rc<Shader> {
+    MESH3D_SHADER.with(|cell| {
+        cell.get_or_init(|| {
+            let shader = Shader::compile(
+                include_str!("shaders/mesh3d.vert"),
+                include_str!("shaders/mesh3d.frag"),
+                None,
+            )
+            .expect("Failed to compile 3D mesh shader");
+            // Cached for the life of the thread, which is not a leak.
+            leaks::keep(Resource::Program, shader.program());
+            Rc::new(shader)
+        })
+        .clone()
+    })
+}
+
+fn drawing_mode(topology: Topology) -> u32 {
+    match topology {
+        Topology::Triangles => GL_TRIANGLES,
+        Topology::Lines => GL_LINES,
+    }
+}
+
+/// [`MeshData`] uploaded to the GPU, with a model transform.
+pub struct Mesh3D {
+    mesh: Mesh,
+    topology: Topology,
+    visible: bool,
+}
+
+impl Mesh3D {
+    /// Upload `data`. Panics if it fails [`MeshData::validate`].
+    pub fn new(data: &MeshData) -> Self {
+        let mut mesh3d = Self {
+            mesh: Mesh::new(mesh3d_shader(), Geometry::new(drawing_mode(data.topology))),
+            topology: data.topology,
+            visible: true,
+        };
+        mesh3d.upload(data);
+        mesh3d
+    }
+
+    /// Replace the vertex data, reusing the GPU buffer when the topology
+    /// is unchanged. Panics if `data` fails [`MeshData::validate`].
+    pub fn set_data(&mut self, data: &MeshData) {
+        if data.topology != self.topology {
+            let transform = self.mesh.transform();
+            let opacity = self.mesh.opacity();
+            *self = Self::new(data);
+            self.mesh.set_transform(transform);
+            self.mesh.set_opacity(opacity);
+            return;
+        }
+        if let Err(e) = data.validate() {
+            panic!("invalid mesh data: {e}");
+        }
+        self.mesh.geometry.update_buffer(&data.interleaved(), VERTEX_SIZE as i32);
+    }
+
+    fn upload(&mut self, data: &MeshData) {
+        if let Err(e) = data.validate() {
+            panic!("invalid mesh data: {e}");
+        }
+        let geometry = &mut self.mesh.geometry;
+        geometry.add_buffer(&data.interleaved(), VERTEX_SIZE as i32);
+        geometry.add_vertex_attribute(Attribute::new(0, 3, VERTEX_SIZE, 0));
+        geometry.add_vertex_attribute(Attribute::new(1, 3, VERTEX_SIZE, 3));
+        geometry.add_vertex_attribute(Attribute::new(2, 4, VERTEX_SIZE, 6));
+    }
+
+    /// Model matrix placing the mesh in the world. Keep scales uniform so
+    /// normals stay correct.
+    pub fn set_transform(&mut self, transform: Mat4) -> &mut Self {
+        self.mesh.set_transform(transform);
+        self
+    }
+
+    pub fn transform(&self) -> Mat4 {
+        self.mesh.transform()
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.mesh.set_opacity(opacity);
+        self
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.mesh.opacity()
+    }
+
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Draw through `camera` with depth testing against what was drawn
+    /// since the depth buffer was last cleared, e.g. by [`Scene3D::render`].
+    pub fn render(&self, renderer: &Renderer, camera: &Camera3D, light: &Light) {
+        if !self.visible {
+            return;
+        }
+        let program = self.mesh.shader.program();
+        self.mesh.shader.use_program();
+        gl_uniform_matrix_4f(gl_get_uniform_location(program, "u_view_projection"), camera.view_projection().as_array());
+        let direction = light.direction.normalize();
+        gl_uniform_3f(gl_get_uniform_location(program, "u_light_dir"), direction.x, direction.y, direction.z);
+        gl_uniform_1f(gl_get_uniform_location(program, "u_ambient"), light.ambient);
+
+        gl_enable(GL_DEPTH_TEST);
+        gl_depth_func(GL_LESS);
+        renderer.draw_mesh(&self.mesh);
+        gl_disable(GL_DEPTH_TEST);
+    }
+}
+
+/// Meshes drawn together through one camera and light, depth-tested
+/// against each other but not against 2D shapes.
+#[derive(Default)]
+pub struct Scene3D {
+    meshes: Vec<Mesh3D>,
+    light: Light,
+}
+
+impl Scene3D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mesh, returning its index.
+    pub fn add(&mut self, mesh: Mesh3D) -> usize {
+        self.meshes.push(mesh);
+        self.meshes.len() - 1
+    }
+
+    pub fn mesh(&self, index: usize) -> Option<&Mesh3D> {
+        self.meshes.get(index)
+    }
+
+    pub fn mesh_mut(&mut self, index: usize) -> Option<&mut Mesh3D> {
+        self.meshes.get_mut(index)
+    }
+
+    pub fn meshes(&self) -> &[Mesh3D] {
+        &self.meshes
+    }
+
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+
+    pub fn light(&self) -> Light {
+        self.light
+    }
+
+    pub fn set_light(&mut self, light: Light) -> &mut Self {
+        self.light = light;
+        self
+    }
+
+    /// Clear the depth buffer and draw the visible meshes in order.
+    pub fn render(&self, renderer: &Renderer, camera: &Camera3D) {
+        gl_clear_depth_buffer();
+        for mesh in &self.meshes {
+            mesh.render(renderer, camera, &self.light);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangle_normal_follows_winding() {
+        let mut data = MeshData::new(Topology::Triangles);
+        data.push_triangle(Vec3::ZERO, Vec3::X, Vec3::Y, Color::white());
+        assert_eq!(data.normals, vec![Vec3::Z; 3]);
+        assert_eq!(data.interleaved().len(), 3 * VERTEX_SIZE);
+        assert_eq!(&data.interleaved()[..VERTEX_SIZE], &[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_partial_primitives_and_missing_attributes() {
+        let mut data = MeshData::new(Topology::Lines);
+        data.push_vertex(Vec3::ZERO, Vec3::ZERO, Color::white());
+        assert!(data.validate().is_err());
+        data.push_vertex(Vec3::X, Vec3::ZERO, Color::white());
+        assert!(data.validate().is_ok());
+        data.colors.pop();
+        assert!(data.validate().is_err());
+    }
+
+    #[test]
+    fn bounds_cover_all_positions() {
+        let mut data = MeshData::new(Topology::Triangles);
+        assert_eq!(data.bounds(), None);
+        data.push_triangle(Vec3::new(-1.0, 2.0, 0.0), Vec3::new(3.0, 0.0, 1.0), Vec3::new(0.0, -4.0, 5.0), Color::white());
+        assert_eq!(data.bounds(), Some((Vec3::new(-1.0, -4.0, 0.0), Vec3::new(3.0, 2.0, 5.0))));
+    }
+}
@@ -0,0 +1,24 @@
+//! Basic 3D rendering: a perspective [`Camera3D`](camera::Camera3D) with an
+//! [`OrbitController`](camera::OrbitController), depth-tested
+//! [`Mesh3D`](mesh::Mesh3D)es lit by one directional light, and
+//! [`primitives`] to build them from.
+//!
+//! A [`Scene3D`](mesh::Scene3D) draws into the same frame as 2D shapes: 2D
+//! drawn before it ends up behind the 3D content, 2D drawn after it on top.
+//! Coordinates are right-handed with Y up.
+//!
+//! ```ignore
+//! let mut scene = Scene3D::new();
+//! scene.add(Mesh3D::new(&primitives::grid(20.0, 20, Color::from_rgb(0.3, 0.3, 0.3))));
+//! scene.add(Mesh3D::new(&primitives::heightfield(&heights, 64, 0.5, |h| colormap.sample(h))));
+//! let mut orbit = OrbitController::new(Vec3::ZERO, 30.0, Vec2::new(800.0, 600.0));
+//! // in on_render, after the 2D background layers
+//! scene.render(renderer, orbit.camera());
+//! ```
+//!
+//! Headless renderers draw into a framebuffer without a depth buffer, so
+//! meshes there are drawn in order without depth testing.
+
+pub mod camera;
+pub mod mesh;
+pub mod primitives;
@@ -0,0 +1,199 @@
+//! [`MeshData`] builders for common shapes, centered on the origin unless
+//! noted. Place them with [`Mesh3D::set_transform`](super::mesh::Mesh3D::set_transform).
+
+use std::f32::consts::{PI, TAU};
+
+use crate::core::{Color, Vec3};
+use crate::graphics2d::shapes::Polygon;
+use crate::graphics3d::mesh::{MeshData, Topology};
+
+/// A box with the given edge lengths, flat-shaded.
+pub fn cuboid(size: Vec3, color: Color) -> MeshData {
+    let h = size * 0.5;
+    let corner = |x: f32, y: f32, z: f32| Vec3::new(x * h.x, y * h.y, z * h.z);
+    let mut data = MeshData::new(Topology::Triangles);
+    // Each face as its four corners counter-clockwise seen from outside
+    let faces = [
+        [corner(-1.0, -1.0, 1.0), corner(1.0, -1.0, 1.0), corner(1.0, 1.0, 1.0), corner(-1.0, 1.0, 1.0)],
+        [corner(1.0, -1.0, -1.0), corner(-1.0, -1.0, -1.0), corner(-1.0, 1.0, -1.0), corner(1.0, 1.0, -1.0)],
+        [corner(1.0, -1.0, 1.0), corner(1.0, -1.0, -1.0), corner(1.0, 1.0, -1.0), corner(1.0, 1.0, 1.0)],
+        [corner(-1.0, -1.0, -1.0), corner(-1.0, -1.0, 1.0), corner(-1.0, 1.0, 1.0), corner(-1.0, 1.0, -1.0)],
+        [corner(-1.0, 1.0, 1.0), corner(1.0, 1.0, 1.0), corner(1.0, 1.0, -1.0), corner(-1.0, 1.0, -1.0)],
+        [corner(-1.0, -1.0, -1.0), corner(1.0, -1.0, -1.0), corner(1.0, -1.0, 1.0), corner(-1.0, -1.0, 1.0)],
+    ];
+    for [a, b, c, d] in faces {
+        data.push_triangle(a, b, c, color);
+        data.push_triangle(a, c, d, color);
+    }
+    data
+}
+
+/// A UV sphere, smooth-shaded, with `segments` slices around the Y axis
+/// and `rings` stacks from pole to pole.
+pub fn sphere(radius: f32, segments: u32, rings: u32, color: Color) -> MeshData {
+    let segments = segments.max(3);
+    let rings = rings.max(2);
+    let normal = |segment: u32, ring: u32| {
+        let (sin_theta, cos_theta) = (ring as f32 / rings as f32 * PI).sin_cos();
+        let (sin_phi, cos_phi) = (segment as f32 / segments as f32 * TAU).sin_cos();
+        Vec3::new(sin_theta * sin_phi, cos_theta, sin_theta * cos_phi)
+    };
+    let mut data = MeshData::new(Topology::Triangles);
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = normal(segment, ring);
+            let b = normal(segment, ring + 1);
+            let c = normal(segment + 1, ring + 1);
+            let d = normal(segment + 1, ring);
+            for n in [a, b, c, a, c, d] {
+                data.push_vertex(n * radius, n, color);
+            }
+        }
+    }
+    data
+}
+
+/// A square grid of lines on the XZ plane, `size` across with `divisions`
+/// cells per side.
+pub fn grid(size: f32, divisions: u32, color: Color) -> MeshData {
+    let divisions = divisions.max(1);
+    let half = size * 0.5;
+    let mut data = MeshData::new(Topology::Lines);
+    for i in 0..=divisions {
+        let t = -half + size * i as f32 / divisions as f32;
+        for (a, b) in [
+            (Vec3::new(t, 0.0, -half), Vec3::new(t, 0.0, half)),
+            (Vec3::new(-half, 0.0, t), Vec3::new(half, 0.0, t)),
+        ] {
+            data.push_vertex(a, Vec3::ZERO, color);
+            data.push_vertex(b, Vec3::ZERO, color);
+        }
+    }
+    data
+}
+
+/// Terrain from a row-major grid of heights, `columns` wide, with
+/// `spacing` between samples on the XZ plane, centered on the origin.
+/// Each vertex is colored by `color(height)`, e.g. from a
+/// [`ColorMap`](crate::core::ColorMap). Returns an empty mesh for fewer
+/// than 2×2 samples.
+pub fn heightfield(heights: &[f32], columns: usize, spacing: f32, color: impl Fn(f32) -> Color) -> MeshData {
+    let mut data = MeshData::new(Topology::Triangles);
+    if columns < 2 || heights.len() / columns < 2 {
+        return data;
+    }
+    let rows = heights.len() / columns;
+    let height = |col: usize, row: usize| heights[row * columns + col];
+    let offset = Vec3::new((columns - 1) as f32 * spacing * 0.5, 0.0, (rows - 1) as f32 * spacing * 0.5);
+    let position = |col: usize, row: usize| Vec3::new(col as f32 * spacing, height(col, row), row as f32 * spacing) - offset;
+    // Central differences, one-sided at the edges
+    let normal = |col: usize, row: usize| {
+        let (left, right) = (col.saturating_sub(1), (col + 1).min(columns - 1));
+        let (up, down) = (row.saturating_sub(1), (row + 1).min(rows - 1));
+        let dx = (height(right, row) - height(left, row)) / ((right - left) as f32 * spacing);
+        let dz = (height(col, down) - height(col, up)) / ((down - up) as f32 * spacing);
+        Vec3::new(-dx, 1.0, -dz).normalize()
+    };
+    for row in 0..rows - 1 {
+        for col in 0..columns - 1 {
+            let quad = [(col, row), (col, row + 1), (col + 1, row + 1), (col, row), (col + 1, row + 1), (col + 1, row)];
+            for (c, r) in quad {
+                data.push_vertex(position(c, r), normal(c, r), color(height(c, r)));
+            }
+        }
+    }
+    data
+}
+
+/// A 2D outline extruded upward from the ground plane to `height`, e.g.
+/// building footprints. Outline points `(x, y)` map to world `(x, z)`; the
+/// outline may be concave and in either winding.
+pub fn extrude(outline: &Polygon, height: f32, color: Color) -> MeshData {
+    let mut data = MeshData::new(Topology::Triangles);
+    let points = &outline.points;
+    let ground = |i: usize| Vec3::new(points[i].0, 0.0, points[i].1);
+    let top = |i: usize| Vec3::new(points[i].0, height, points[i].1);
+
+    for [a, b, c] in outline.triangulate() {
+        // Seen from above, (x, y) → (x, z) mirrors the winding; order each
+        // cap so its normal points away from the solid.
+        let up = (top(b) - top(a)).cross(top(c) - top(a)).y > 0.0;
+        let (b, c) = if up { (b, c) } else { (c, b) };
+        data.push_triangle(top(a), top(b), top(c), color);
+        data.push_triangle(ground(a), ground(c), ground(b), color);
+    }
+
+    // Shoelace sum of the outline in world (x, z): negative is
+    // counter-clockwise seen from above
+    let n = points.len();
+    let area: f32 = (0..n).map(|i| {
+        let (p, q) = (points[i], points[(i + 1) % n]);
+        p.0 * q.1 - q.0 * p.1
+    }).sum();
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let (i, j) = if area < 0.0 { (i, j) } else { (j, i) };
+        data.push_triangle(ground(i), ground(j), top(j), color);
+        data.push_triangle(ground(i), top(j), top(i), color);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every face normal points away from the mesh center.
+    fn assert_outward(data: &MeshData, center: Vec3) {
+        for (triangle, normals) in data.positions.chunks(3).zip(data.normals.chunks(3)) {
+            let centroid = (triangle[0] + triangle[1] + triangle[2]) * (1.0 / 3.0);
+            assert!(normals[0].dot(centroid - center) > 0.0, "inward face at {centroid:?}");
+        }
+    }
+
+    #[test]
+    fn cuboid_faces_point_outward() {
+        let data = cuboid(Vec3::new(2.0, 4.0, 6.0), Color::white());
+        assert_eq!(data.vertex_count(), 36);
+        assert_eq!(data.bounds(), Some((Vec3::new(-1.0, -2.0, -3.0), Vec3::new(1.0, 2.0, 3.0))));
+        assert_outward(&data, Vec3::ZERO);
+    }
+
+    #[test]
+    fn sphere_vertices_lie_on_radius() {
+        let data = sphere(2.0, 8, 6, Color::white());
+        assert_eq!(data.vertex_count(), 8 * 6 * 6);
+        assert!(data.positions.iter().all(|p| (p.length() - 2.0).abs() < 1e-5));
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn grid_has_two_lines_per_division() {
+        let data = grid(10.0, 4, Color::white());
+        assert_eq!(data.topology, Topology::Lines);
+        assert_eq!(data.vertex_count(), 5 * 2 * 2);
+        assert_eq!(data.bounds(), Some((Vec3::new(-5.0, 0.0, -5.0), Vec3::new(5.0, 0.0, 5.0))));
+    }
+
+    #[test]
+    fn heightfield_slopes_tilt_normals() {
+        // Rising along +X
+        let heights = [0.0, 1.0, 2.0, 0.0, 1.0, 2.0];
+        let data = heightfield(&heights, 3, 1.0, |h| Color::from_rgb(h / 2.0, 0.0, 0.0));
+        assert_eq!(data.vertex_count(), 2 * 6);
+        assert!(data.normals.iter().all(|n| n.x < 0.0 && n.y > 0.0));
+        assert_eq!(data.bounds(), Some((Vec3::new(-1.0, 0.0, -0.5), Vec3::new(1.0, 2.0, 0.5))));
+        assert!(heightfield(&heights, 6, 1.0, |_| Color::white()).positions.is_empty());
+    }
+
+    #[test]
+    fn extrusion_is_closed_and_outward_in_either_winding() {
+        let square = vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        for points in [square.clone(), square.into_iter().rev().collect()] {
+            let data = extrude(&Polygon::new(points), 2.0, Color::white());
+            // Two caps of two triangles, four walls of two triangles
+            assert_eq!(data.vertex_count(), (4 + 8) * 3);
+            assert_outward(&data, Vec3::new(0.0, 1.0, 0.0));
+        }
+    }
+}
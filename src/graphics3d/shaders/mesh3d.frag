@@ -0,0 +1,20 @@
+#version 330 core
+
+uniform vec3 u_light_dir;
+uniform float u_ambient;
+uniform float u_opacity;
+
+in vec3 v_normal;
+in vec4 v_color;
+
+out vec4 FragColor;
+
+void main() {
+    FragColor = v_color;
+    // Zero normals (grid lines) are drawn unlit
+    if (dot(v_normal, v_normal) > 0.0) {
+        float diffuse = max(dot(normalize(v_normal), -u_light_dir), 0.0);
+        FragColor.rgb *= u_ambient + (1.0 - u_ambient) * diffuse;
+    }
+    FragColor.a *= u_opacity;
+}
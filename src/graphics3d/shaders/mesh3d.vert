@@ -0,0 +1,19 @@
+#version 330 core
+
+layout(location = 0) in vec3 aPos;
+layout(location = 1) in vec3 aNormal;
+layout(location = 2) in vec4 aColor;
+
+uniform mat4 u_Transform;
+uniform mat4 u_view_projection;
+
+out vec3 v_normal;
+out vec4 v_color;
+
+void main() {
+    gl_Position = u_view_projection * u_Transform * vec4(aPos, 1.0);
+    // Model matrices are rotations, translations and uniform scales, so
+    // the upper 3x3 transforms normals correctly up to length.
+    v_normal = mat3(u_Transform) * aNormal;
+    v_color = aColor;
+}
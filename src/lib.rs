@@ -1,2 +1,10 @@
 pub mod core;
-pub mod graphics2d;
\ No newline at end of file
+#[cfg(feature = "ecs")]
+pub mod ecs;
+pub mod graphics2d;
+pub mod graphics3d;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "script")]
+pub mod script;
+pub mod ui;
\ No newline at end of file
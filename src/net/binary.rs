@@ -0,0 +1,306 @@
+//! Compact binary encoding of [`Snapshot`] streams.
+//!
+//! Every message starts with a version byte, a flags byte and the
+//! sequence number and instance count as LEB128 varints. A keyframe then
+//! holds each position as two little-endian `f32`s; a delta holds, per
+//! coordinate, the zigzag varint difference between the `f32` bit patterns
+//! of the previous and the new value, so still instances cost one byte
+//! per coordinate and positions stay exact. Colors, when flagged, follow
+//! as RGBA8; a delta without them keeps the previous colors.
+
+use crate::core::{Color, Vec2};
+use crate::net::snapshot::Snapshot;
+
+const VERSION: u8 = 1;
+const FLAG_KEYFRAME: u8 = 1;
+const FLAG_COLORS: u8 = 2;
+const DEFAULT_KEYFRAME_INTERVAL: u32 = 120;
+
+/// Positions as `f32` bits and colors as RGBA8: what both ends hold
+/// after a message.
+#[derive(Clone, Default)]
+struct State {
+    sequence: u32,
+    positions: Vec<u32>,
+    colors: Vec<[u8; 4]>,
+}
+
+impl State {
+    fn from_snapshot(snapshot: &Snapshot, sequence: u32) -> Self {
+        Self {
+            sequence,
+            positions: snapshot.positions.iter().flat_map(|p| [p.x.to_bits(), p.y.to_bits()]).collect(),
+            // Colors go out only with one per instance.
+            colors: if snapshot.colors.len() == snapshot.positions.len() {
+                snapshot.colors.iter().map(rgba8).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    fn to_snapshot(&self) -> Snapshot {
+        Snapshot {
+            positions: self.positions.chunks(2).map(|p| Vec2::new(f32::from_bits(p[0]), f32::from_bits(p[1]))).collect(),
+            colors: self.colors.iter().map(|&[r, g, b, a]| Color::from_rgba8(r, g, b, a)).collect(),
+        }
+    }
+
+    fn keyframe(&self) -> Vec<u8> {
+        let mut out = self.header(FLAG_KEYFRAME);
+        for bits in &self.positions {
+            out.extend_from_slice(&bits.to_le_bytes());
+        }
+        self.push_colors(&mut out);
+        out
+    }
+
+    fn header(&self, mut flags: u8) -> Vec<u8> {
+        if !self.colors.is_empty() {
+            flags |= FLAG_COLORS;
+        }
+        let mut out = vec![VERSION, flags];
+        write_varint(&mut out, u64::from(self.sequence));
+        write_varint(&mut out, (self.positions.len() / 2) as u64);
+        out
+    }
+
+    fn push_colors(&self, out: &mut Vec<u8>) {
+        for color in &self.colors {
+            out.extend_from_slice(color);
+        }
+    }
+}
+
+/// Turns a stream of snapshots into keyframes and deltas.
+///
+/// A delta is sent when the instance count is unchanged and colors did
+/// not disappear; otherwise, and every 120 messages so a decoder that
+/// joined late or hit an error resynchronizes, a keyframe.
+pub struct SnapshotEncoder {
+    last: Option<State>,
+    keyframe_interval: u32,
+    since_keyframe: u32,
+}
+
+impl Default for SnapshotEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnapshotEncoder {
+    pub fn new() -> Self {
+        Self { last: None, keyframe_interval: DEFAULT_KEYFRAME_INTERVAL, since_keyframe: 0 }
+    }
+
+    /// Send a keyframe every `interval` messages (at least every message).
+    pub fn set_keyframe_interval(&mut self, interval: u32) -> &mut Self {
+        self.keyframe_interval = interval.max(1);
+        self
+    }
+
+    pub fn encode(&mut self, snapshot: &Snapshot) -> Vec<u8> {
+        let sequence = self.last.as_ref().map_or(0, |last| last.sequence.wrapping_add(1));
+        let state = State::from_snapshot(snapshot, sequence);
+        let previous = self.last.as_ref().filter(|last| {
+            self.since_keyframe + 1 < self.keyframe_interval
+                && last.positions.len() == state.positions.len()
+                && (state.colors.is_empty() == last.colors.is_empty() || !state.colors.is_empty())
+        });
+        let out = match previous {
+            Some(previous) => {
+                let colors_changed = state.colors != previous.colors;
+                let mut out = state.header(0);
+                if !colors_changed {
+                    out[1] &= !FLAG_COLORS;
+                }
+                for (old, new) in previous.positions.iter().zip(&state.positions) {
+                    write_varint(&mut out, zigzag(new.wrapping_sub(*old) as i32));
+                }
+                if colors_changed {
+                    state.push_colors(&mut out);
+                }
+                self.since_keyframe += 1;
+                out
+            }
+            None => {
+                self.since_keyframe = 0;
+                state.keyframe()
+            }
+        };
+        self.last = Some(state);
+        out
+    }
+
+    /// A keyframe of the last encoded snapshot, with its sequence number,
+    /// for a decoder joining mid-stream: the next [`encode`](Self::encode)
+    /// output applies on top of it.
+    pub fn keyframe(&self) -> Option<Vec<u8>> {
+        self.last.as_ref().map(State::keyframe)
+    }
+}
+
+/// Rebuilds snapshots from [`SnapshotEncoder`] output. Deltas need the
+/// message before them; after a gap, decoding fails until the next
+/// keyframe.
+#[derive(Default)]
+pub struct SnapshotDecoder {
+    last: Option<State>,
+}
+
+impl SnapshotDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Snapshot, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        match reader.byte()? {
+            VERSION => {}
+            version => return Err(format!("unsupported snapshot version {version}")),
+        }
+        let flags = reader.byte()?;
+        let sequence = reader.varint()? as u32;
+        let count = usize::try_from(reader.varint()?).map_err(|_| "instance count too large")?;
+        if count > bytes.len() {
+            return Err("instance count exceeds the message".to_string());
+        }
+        let mut state = if flags & FLAG_KEYFRAME != 0 {
+            let mut positions = Vec::with_capacity(count * 2);
+            for _ in 0..count * 2 {
+                positions.push(u32::from_le_bytes(reader.take(4)?.try_into().unwrap()));
+            }
+            State { sequence, positions, colors: Vec::new() }
+        } else {
+            let previous = self.last.take().ok_or("delta without a keyframe")?;
+            if sequence != previous.sequence.wrapping_add(1) {
+                return Err(format!("delta {sequence} does not follow {}", previous.sequence));
+            }
+            if previous.positions.len() != count * 2 {
+                return Err("delta changes the instance count".to_string());
+            }
+            let mut positions = previous.positions;
+            for bits in &mut positions {
+                *bits = bits.wrapping_add(unzigzag(reader.varint()?) as u32);
+            }
+            State { sequence, positions, colors: previous.colors }
+        };
+        if flags & FLAG_COLORS != 0 {
+            state.colors = reader.take(count * 4)?.chunks(4).map(|c| [c[0], c[1], c[2], c[3]]).collect();
+        }
+        if reader.pos != bytes.len() {
+            return Err("trailing bytes in snapshot".to_string());
+        }
+        let snapshot = state.to_snapshot();
+        self.last = Some(state);
+        Ok(snapshot)
+    }
+}
+
+fn rgba8(color: &Color) -> [u8; 4] {
+    [color.red_value(), color.green_value(), color.blue_value(), color.alpha()].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+fn zigzag(v: i32) -> u64 {
+    u64::from(((v << 1) ^ (v >> 31)) as u32)
+}
+
+fn unzigzag(v: u64) -> i32 {
+    let v = v as u32;
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n: usize) -> Result<&'b [u8], String> {
+        let slice = self.bytes.get(self.pos..self.pos + n).ok_or("truncated snapshot")?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint too long".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(positions: &[(f32, f32)], colors: &[[u8; 4]]) -> Snapshot {
+        Snapshot {
+            positions: positions.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+            colors: colors.iter().map(|&[r, g, b, a]| Color::from_rgba8(r, g, b, a)).collect(),
+        }
+    }
+
+    #[test]
+    fn deltas_are_exact_and_small() {
+        let (mut encoder, mut decoder) = (SnapshotEncoder::new(), SnapshotDecoder::new());
+        let first = snapshot(&[(10.0, 20.0), (300.5, -4.25)], &[[255, 0, 0, 255], [0, 0, 255, 128]]);
+        let key = encoder.encode(&first);
+        assert_eq!(key[1], FLAG_KEYFRAME | FLAG_COLORS);
+        assert_eq!(decoder.decode(&key).unwrap().positions, first.positions);
+
+        let second = snapshot(&[(10.0, 20.0), (301.0, -4.0)], &[[255, 0, 0, 255], [0, 0, 255, 128]]);
+        let delta = encoder.encode(&second);
+        assert_eq!(delta[1], 0, "colors unchanged, not a keyframe");
+        assert!(delta.len() < 16, "delta is {} bytes", delta.len());
+        let decoded = decoder.decode(&delta).unwrap();
+        assert_eq!(decoded.positions, second.positions);
+        assert_eq!(rgba8(&decoded.colors[1]), [0, 0, 255, 128]);
+    }
+
+    #[test]
+    fn late_decoders_join_with_a_keyframe() {
+        let mut encoder = SnapshotEncoder::new();
+        encoder.encode(&snapshot(&[(1.0, 1.0)], &[]));
+        let mut late = SnapshotDecoder::new();
+        assert!(late.decode(&encoder.encode(&snapshot(&[(2.0, 1.0)], &[]))).is_err());
+        late.decode(&encoder.keyframe().unwrap()).unwrap();
+        let next = late.decode(&encoder.encode(&snapshot(&[(3.0, 1.0)], &[]))).unwrap();
+        assert_eq!(next.positions, vec![Vec2::new(3.0, 1.0)]);
+    }
+
+    #[test]
+    fn count_changes_and_interval_force_keyframes() {
+        let mut encoder = SnapshotEncoder::new();
+        encoder.set_keyframe_interval(2);
+        encoder.encode(&snapshot(&[(1.0, 1.0)], &[]));
+        assert_eq!(encoder.encode(&snapshot(&[(1.0, 1.0)], &[]))[1], 0);
+        assert_eq!(encoder.encode(&snapshot(&[(1.0, 1.0)], &[]))[1], FLAG_KEYFRAME);
+        assert_eq!(encoder.encode(&snapshot(&[(1.0, 1.0), (2.0, 2.0)], &[]))[1], FLAG_KEYFRAME);
+    }
+
+    #[test]
+    fn zigzag_round_trips_extremes() {
+        for v in [0, 1, -1, i32::MAX, i32::MIN] {
+            assert_eq!(unzigzag(zigzag(v)), v);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::graphics2d::shapes::ShapeRenderable;
+use crate::net::binary::SnapshotDecoder;
+use crate::net::snapshot::Snapshot;
+use crate::net::websocket::{Message, WebSocket};
+
+/// How long a blocked read waits before checking for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MIN_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// State shared with the connection thread.
+#[derive(Default)]
+struct Shared {
+    /// Latest snapshot and a counter bumped on each new one.
+    latest: Mutex<(u64, Option<Arc<Snapshot>>)>,
+    connected: AtomicBool,
+    closed: AtomicBool,
+    error: Mutex<Option<String>>,
+}
+
+/// Receives [`Snapshot`]s from a WebSocket server on a background thread
+/// and hands the newest one to the render loop. Text messages are read
+/// as JSON, binary ones as [`SnapshotEncoder`](super::SnapshotEncoder)
+/// output.
+///
+/// Only the latest snapshot is kept: a slow frame skips stale ones rather
+/// than queueing them. Dropped connections are retried with exponential
+/// backoff (0.25 s doubling up to 8 s) until the client is dropped.
+///
+/// ```ignore
+/// let mut stream = StreamClient::connect("ws://localhost:9001")?;
+/// app.on_pre_render(move |shapes, _| {
+///     stream.apply_to(&mut shapes[0]);
+/// });
+/// ```
+pub struct StreamClient {
+    shared: Arc<Shared>,
+    /// Counter of the last snapshot handed out.
+    seen: u64,
+}
+
+impl StreamClient {
+    /// Start connecting to a `ws://` URL in the background. Fails only
+    /// for URLs that can never connect; unreachable servers are retried.
+    pub fn connect(url: &str) -> Result<Self, String> {
+        if !url.starts_with("ws://") {
+            return Err(format!("unsupported stream URL (expected ws://): {url}"));
+        }
+        let shared = Arc::new(Shared::default());
+        let (url, thread_shared) = (url.to_string(), Arc::clone(&shared));
+        std::thread::spawn(move || run(&url, &thread_shared));
+        Ok(Self { shared, seen: 0 })
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Relaxed)
+    }
+
+    /// Why the last connection attempt or connection failed, if one did.
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.error.lock().unwrap().clone()
+    }
+
+    /// The snapshot received since the last call, if any.
+    pub fn take_new(&mut self) -> Option<Arc<Snapshot>> {
+        let latest = self.shared.latest.lock().unwrap();
+        if latest.0 == self.seen {
+            return None;
+        }
+        self.seen = latest.0;
+        latest.1.clone()
+    }
+
+    /// Apply the snapshot received since the last call to `shape`'s
+    /// instances. Returns whether there was one.
+    pub fn apply_to(&mut self, shape: &mut ShapeRenderable) -> bool {
+        match self.take_new() {
+            Some(snapshot) => {
+                snapshot.apply_to(shape);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for StreamClient {
+    fn drop(&mut self) {
+        // The thread notices within a poll interval or backoff step.
+        self.shared.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Connect, read and reconnect until the client is dropped.
+fn run(url: &str, shared: &Shared) {
+    let mut backoff = MIN_BACKOFF;
+    while !shared.closed.load(Ordering::Relaxed) {
+        let result = WebSocket::connect(url, POLL_INTERVAL).and_then(|mut socket| {
+            shared.connected.store(true, Ordering::Relaxed);
+            backoff = MIN_BACKOFF;
+            receive(&mut socket, shared)
+        });
+        shared.connected.store(false, Ordering::Relaxed);
+        if let Err(e) = result {
+            *shared.error.lock().unwrap() = Some(e.to_string());
+        }
+        let mut waited = Duration::ZERO;
+        while waited < backoff && !shared.closed.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            waited += POLL_INTERVAL;
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+fn receive(socket: &mut WebSocket, shared: &Shared) -> std::io::Result<()> {
+    let mut decoder = SnapshotDecoder::new();
+    while !shared.closed.load(Ordering::Relaxed) {
+        let snapshot = match socket.read_message()? {
+            Some(Message::Text(text)) => Snapshot::from_json(&text),
+            Some(Message::Binary(bytes)) => decoder.decode(&bytes),
+            None => continue,
+        };
+        match snapshot {
+            Ok(snapshot) => {
+                let mut latest = shared.latest.lock().unwrap();
+                *latest = (latest.0 + 1, Some(Arc::new(snapshot)));
+            }
+            Err(e) => *shared.error.lock().unwrap() = Some(format!("bad snapshot: {e}")),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,253 @@
+//! Instance data from a process on the same machine, over a Unix domain
+//! socket.
+//!
+//! Frames are raw little-endian data with no text or delta encoding: the
+//! instance count as a `u32`, a flags byte, each position as two `f32`s
+//! and, when flagged, each color as four `f32`s.
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::{Color, Vec2};
+use crate::graphics2d::shapes::ShapeRenderable;
+use crate::net::snapshot::Snapshot;
+
+const FLAG_COLORS: u8 = 1;
+/// Frames claiming more instances are rejected rather than allocated.
+const MAX_INSTANCES: usize = 1 << 24;
+/// How long a blocked accept or read waits before checking for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// State shared with the reader thread: the front buffer and a counter
+/// bumped each time it is swapped.
+#[derive(Default)]
+struct Shared {
+    front: Mutex<(u64, Snapshot)>,
+    connected: AtomicBool,
+    closed: AtomicBool,
+    error: Mutex<Option<String>>,
+}
+
+/// Receives instance frames from an [`IpcSender`] in another process.
+///
+/// A background thread reads each frame into a back buffer and swaps it
+/// with the front buffer once complete, so the render loop never sees a
+/// half-written frame and, once the buffers have grown, nothing is
+/// allocated per frame. Only the newest frame is kept. One producer is
+/// served at a time; when it disconnects the next one can connect.
+///
+/// ```ignore
+/// let mut source = IpcReceiver::bind("/tmp/sim.sock")?;
+/// app.on_pre_render(move |shapes, _| {
+///     source.apply_to(&mut shapes[0]);
+/// });
+/// ```
+pub struct IpcReceiver {
+    shared: Arc<Shared>,
+    path: PathBuf,
+    /// Counter of the last frame handed out.
+    seen: u64,
+}
+
+impl IpcReceiver {
+    /// Listen on a socket at `path`. A stale socket left there by an
+    /// earlier run is replaced; any other existing file is an error.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            while !thread_shared.closed.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        thread_shared.connected.store(true, Ordering::Relaxed);
+                        let result = stream
+                            .set_nonblocking(false)
+                            .and_then(|_| stream.set_read_timeout(Some(POLL_INTERVAL)))
+                            .and_then(|_| receive(stream, &thread_shared));
+                        thread_shared.connected.store(false, Ordering::Relaxed);
+                        if let Err(e) = result {
+                            *thread_shared.error.lock().unwrap() = Some(e.to_string());
+                        }
+                    }
+                    Err(_) => std::thread::sleep(POLL_INTERVAL),
+                }
+            }
+        });
+        Ok(Self { shared, path, seen: 0 })
+    }
+
+    /// Whether a producer is connected.
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Relaxed)
+    }
+
+    /// Why the last producer connection failed, if one did.
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.error.lock().unwrap().clone()
+    }
+
+    /// Call `f` with the frame received since the last call, if any.
+    /// Returns whether there was one. The reader thread waits for `f`
+    /// before publishing the next frame, so keep it short.
+    pub fn with_new(&mut self, f: impl FnOnce(&Snapshot)) -> bool {
+        let front = self.shared.front.lock().unwrap();
+        if front.0 == self.seen {
+            return false;
+        }
+        self.seen = front.0;
+        f(&front.1);
+        true
+    }
+
+    /// Apply the frame received since the last call to `shape`'s
+    /// instances. Returns whether there was one.
+    pub fn apply_to(&mut self, shape: &mut ShapeRenderable) -> bool {
+        self.with_new(|snapshot| snapshot.apply_to(shape))
+    }
+}
+
+impl Drop for IpcReceiver {
+    fn drop(&mut self) {
+        // The thread notices within a poll interval.
+        self.shared.closed.store(true, Ordering::Relaxed);
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Read frames until the producer disconnects or the receiver is dropped.
+fn receive(mut stream: UnixStream, shared: &Shared) -> io::Result<()> {
+    let mut back = Snapshot::default();
+    let mut bytes = Vec::new();
+    loop {
+        let mut header = [0u8; 5];
+        if !read_full(&mut stream, &mut header, shared)? {
+            return Ok(());
+        }
+        let count = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        if count > MAX_INSTANCES {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("frame of {count} instances")));
+        }
+        let colors = header[4] & FLAG_COLORS != 0;
+        bytes.resize(count * if colors { 24 } else { 8 }, 0);
+        if !read_full(&mut stream, &mut bytes, shared)? {
+            return Ok(());
+        }
+        let (positions, color_bytes) = bytes.split_at(count * 8);
+        let mut floats = positions.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap()));
+        back.positions.clear();
+        back.positions.extend((0..count).map(|_| Vec2::new(floats.next().unwrap(), floats.next().unwrap())));
+        let mut floats = color_bytes.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap()));
+        back.colors.clear();
+        if colors {
+            back.colors.extend((0..count).map(|_| {
+                let mut c = [0.0; 4];
+                c.iter_mut().for_each(|v| *v = floats.next().unwrap());
+                Color::from_rgba(c[0], c[1], c[2], c[3])
+            }));
+        }
+        let mut front = shared.front.lock().unwrap();
+        std::mem::swap(&mut front.1, &mut back);
+        front.0 += 1;
+    }
+}
+
+/// Fill `buf`, waiting out read timeouts. `Ok(false)` on a clean end of
+/// stream before the first byte, or when the receiver is dropped.
+fn read_full(stream: &mut UnixStream, buf: &mut [u8], shared: &Shared) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        if shared.closed.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        match stream.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Sends instance frames to an [`IpcReceiver`] from the producing process.
+pub struct IpcSender {
+    stream: UnixStream,
+    /// Reused frame buffer.
+    buffer: Vec<u8>,
+}
+
+impl IpcSender {
+    /// Connect to the socket an [`IpcReceiver`] is bound to.
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self { stream: UnixStream::connect(path)?, buffer: Vec::new() })
+    }
+
+    /// Send one frame. Colors go out only with one per position; pass an
+    /// empty slice to leave the receiver's instance colors alone.
+    pub fn send(&mut self, positions: &[Vec2], colors: &[Color]) -> io::Result<()> {
+        if positions.len() > MAX_INSTANCES {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "too many instances for one frame"));
+        }
+        let with_colors = !colors.is_empty() && colors.len() == positions.len();
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&(positions.len() as u32).to_le_bytes());
+        self.buffer.push(if with_colors { FLAG_COLORS } else { 0 });
+        for p in positions {
+            self.buffer.extend_from_slice(&p.x.to_le_bytes());
+            self.buffer.extend_from_slice(&p.y.to_le_bytes());
+        }
+        if with_colors {
+            for c in colors {
+                for v in [c.red_value(), c.green_value(), c.blue_value(), c.alpha()] {
+                    self.buffer.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+        self.stream.write_all(&self.buffer)
+    }
+
+    pub fn send_snapshot(&mut self, snapshot: &Snapshot) -> io::Result<()> {
+        self.send(&snapshot.positions, &snapshot.colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn frames_arrive_whole_and_newest_first() {
+        let path = std::env::temp_dir().join(format!("wilhelm-ipc-{}.sock", std::process::id()));
+        let mut receiver = IpcReceiver::bind(&path).unwrap();
+        let mut sender = IpcSender::connect(&path).unwrap();
+        sender.send(&[Vec2::new(1.0, 2.0)], &[]).unwrap();
+        let positions = [Vec2::new(3.0, 4.0), Vec2::new(-5.5, 6.25)];
+        sender.send(&positions, &[Color::from_rgba(1.0, 0.0, 0.5, 1.0); 2]).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut received = Snapshot::default();
+        while received.positions.len() != 2 {
+            receiver.with_new(|s| received = s.clone());
+            assert!(Instant::now() < deadline, "no frame received: {:?}", receiver.last_error());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(received.positions, positions);
+        assert_eq!(received.colors[1].blue_value(), 0.5);
+        assert!(!receiver.with_new(|_| {}), "a frame is handed out once");
+        drop(receiver);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,30 @@
+//! Streaming instance snapshots over WebSocket, enabled with the `net`
+//! feature.
+//!
+//! A simulation process publishes [`Snapshot`]s of instance positions and
+//! colors with a [`StreamServer`]; viewers subscribe with a
+//! [`StreamClient`] and apply the newest one each frame. Snapshots travel
+//! as compact binary deltas by default, or as JSON for other consumers
+//! (see [`WireFormat`]). Only plain `ws://` connections are supported.
+//!
+//! A producer on the same machine can skip WebSocket framing and delta
+//! encoding with [`IpcSender`] and [`IpcReceiver`], which pass raw frames
+//! over a Unix domain socket (Unix only).
+
+mod binary;
+mod client;
+#[cfg(unix)]
+mod ipc;
+mod json;
+#[cfg(feature = "serde")]
+pub(crate) mod remote;
+mod server;
+mod snapshot;
+mod websocket;
+
+pub use binary::{SnapshotDecoder, SnapshotEncoder};
+pub use client::StreamClient;
+#[cfg(unix)]
+pub use ipc::{IpcReceiver, IpcSender};
+pub use server::{StreamServer, WireFormat};
+pub use snapshot::Snapshot;
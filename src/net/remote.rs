@@ -0,0 +1,205 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::core::SceneShape;
+use crate::graphics2d::shapes::ShapeStyle;
+
+/// How long a blocked accept or read waits before checking for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// One remote control request, a line of JSON tagged by `"cmd"`.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub(crate) enum Command {
+    AddShape { shape: SceneShape },
+    RemoveShape { index: usize },
+    SetStyle { index: usize, style: ShapeStyle },
+    SetPosition { index: usize, x: f32, y: f32 },
+    MoveCamera { x: Option<f32>, y: Option<f32>, scale: Option<f32> },
+    Screenshot { path: String },
+}
+
+/// A command waiting for the render loop, and where its reply goes.
+pub(crate) struct Request {
+    pub(crate) command: Command,
+    pub(crate) reply: Sender<Result<Value, String>>,
+}
+
+/// Accepts remote control connections for an [`App`](crate::core::App);
+/// see [`App::enable_remote_control`](crate::core::App::enable_remote_control).
+///
+/// Connections are read on background threads and their commands run on
+/// the render loop between frames, so a command sees the same state as
+/// the callbacks do.
+pub(crate) struct RemoteControl {
+    address: SocketAddr,
+    requests: Receiver<Request>,
+    closed: Arc<AtomicBool>,
+}
+
+impl RemoteControl {
+    /// Listen on `address`, which must be a loopback address since
+    /// clients are not authenticated.
+    pub(crate) fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if let Some(address) = addresses.iter().find(|a| !a.ip().is_loopback()) {
+            let message = format!("remote control only listens on loopback addresses, not {address}");
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        let listener = TcpListener::bind(&addresses[..])?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let (sender, requests) = mpsc::channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_closed = Arc::clone(&closed);
+        std::thread::spawn(move || {
+            while !thread_closed.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let (sender, closed) = (sender.clone(), Arc::clone(&thread_closed));
+                        std::thread::spawn(move || {
+                            // A failed connection only loses that client.
+                            let _ = serve(stream, &sender, &closed);
+                        });
+                    }
+                    Err(_) => std::thread::sleep(POLL_INTERVAL),
+                }
+            }
+        });
+        Ok(Self { address, requests, closed })
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Requests received since the last call.
+    pub(crate) fn pending(&self) -> impl Iterator<Item = Request> + '_ {
+        self.requests.try_iter()
+    }
+}
+
+impl Drop for RemoteControl {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Where a screenshot named `name` goes in `dir`. Only plain `.png` file
+/// names are accepted, so a client cannot write elsewhere.
+pub(crate) fn screenshot_path(dir: Option<&Path>, name: &str) -> Result<PathBuf, String> {
+    let dir = dir.ok_or("screenshots are disabled; see App::set_remote_screenshot_dir")?;
+    let file = Path::new(name);
+    let plain = matches!(file.components().collect::<Vec<_>>()[..], [Component::Normal(_)]);
+    if !plain || file.extension().is_none_or(|extension| extension != "png") {
+        return Err(format!("screenshot path must be a file name ending in .png, not {name:?}"));
+    }
+    Ok(dir.join(file))
+}
+
+/// Answer one connection line by line until it closes or sends a line
+/// that is not a command.
+fn serve(stream: TcpStream, sender: &Sender<Request>, closed: &AtomicBool) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while !closed.load(Ordering::Relaxed) {
+        match reader.read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            // Keep the partial line and wait for the rest.
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => continue,
+            Err(e) => return Err(e),
+        }
+        if line.trim().is_empty() {
+            line.clear();
+            continue;
+        }
+        let result = match serde_json::from_str(&line) {
+            Ok(command) => {
+                let (reply, response) = mpsc::channel();
+                if sender.send(Request { command, reply }).is_err() {
+                    return Ok(());
+                }
+                response.recv().unwrap_or_else(|_| Err("the renderer stopped".to_string()))
+            }
+            Err(e) => Err(format!("invalid command: {e}")),
+        };
+        line.clear();
+        let invalid = matches!(&result, Err(error) if error.starts_with("invalid command"));
+        let mut response = match result {
+            Ok(Value::Object(mut fields)) => {
+                fields.insert("ok".to_string(), Value::Bool(true));
+                Value::Object(fields)
+            }
+            Ok(_) => json!({ "ok": true }),
+            Err(error) => json!({ "ok": false, "error": error }),
+        }
+        .to_string();
+        response.push('\n');
+        writer.write_all(response.as_bytes())?;
+        if invalid {
+            // Not a client speaking the protocol, e.g. a browser's HTTP
+            // request; do not run anything else it sends.
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse_from_json_lines() {
+        let command = serde_json::from_str(r#"{"cmd": "set_position", "index": 2, "x": 10.0, "y": -4.5}"#).unwrap();
+        assert!(matches!(command, Command::SetPosition { index: 2, x, y } if x == 10.0 && y == -4.5));
+        let command = serde_json::from_str(r#"{"cmd": "add_shape", "shape": {"kind": {"Circle": {"radius": 5.0}}}}"#).unwrap();
+        assert!(matches!(command, Command::AddShape { .. }));
+        let command = serde_json::from_str(r#"{"cmd": "move_camera", "scale": 2.0}"#).unwrap();
+        assert!(matches!(command, Command::MoveCamera { x: None, y: None, scale: Some(_) }));
+        assert!(serde_json::from_str::<Command>(r#"{"cmd": "explode"}"#).is_err());
+    }
+
+    #[test]
+    fn replies_come_back_on_the_same_line_order() {
+        let control = RemoteControl::bind("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(control.local_addr()).unwrap();
+        stream.write_all(b"{\"cmd\": \"remove_shape\", \"index\": 7}\nnot json\n").unwrap();
+        let request = loop {
+            if let Some(request) = control.pending().next() {
+                break request;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert!(matches!(request.command, Command::RemoveShape { index: 7 }));
+        request.reply.send(Ok(json!({ "index": 7 }))).unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let first: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert_eq!(first, json!({ "ok": true, "index": 7 }));
+        let second: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert_eq!(second["ok"], json!(false));
+    }
+
+    #[test]
+    fn only_loopback_and_plain_screenshot_names_are_accepted() {
+        assert_eq!(RemoteControl::bind("0.0.0.0:0").err().unwrap().kind(), ErrorKind::InvalidInput);
+        let dir = Path::new("shots");
+        assert_eq!(screenshot_path(Some(dir), "frame.png"), Ok(dir.join("frame.png")));
+        for name in ["../frame.png", "/tmp/frame.png", "~/.bashrc", "sub/frame.png", "frame.txt", ".."] {
+            assert!(screenshot_path(Some(dir), name).is_err(), "{name}");
+        }
+        assert!(screenshot_path(None, "frame.png").is_err());
+    }
+}
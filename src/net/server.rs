@@ -0,0 +1,192 @@
+use std::io;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::graphics2d::shapes::ShapeRenderable;
+use crate::net::binary::SnapshotEncoder;
+use crate::net::snapshot::Snapshot;
+use crate::net::websocket::{Message, WebSocket};
+
+/// How long the accept thread sleeps between checks for new clients.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+/// A client that cannot take a message this fast is dropped.
+const SEND_TIMEOUT: Duration = Duration::from_millis(200);
+const DEFAULT_RATE: f32 = 30.0;
+
+/// How a [`StreamServer`] encodes snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    /// [`SnapshotEncoder`] keyframes and deltas, a fraction of the size of
+    /// JSON and far cheaper to decode. The default.
+    Binary,
+    /// [`Snapshot::to_json`] text, for consumers other than
+    /// [`StreamClient`](super::StreamClient).
+    Json,
+}
+
+/// An accepted client and whether it still needs a keyframe.
+struct Client {
+    socket: WebSocket,
+    joined: bool,
+}
+
+/// Broadcasts [`Snapshot`]s to every connected [`StreamClient`](super::StreamClient),
+/// so a headless simulation can feed any number of viewers.
+///
+/// Clients are accepted on a background thread; sends happen on the
+/// caller's thread. [`publish`](Self::publish) is meant to be called every
+/// frame and sends at most [`max_rate`](Self::set_max_rate) times a second.
+/// Clients that disconnect or stall are dropped on the next send. A
+/// simulation without a window or GL context builds its own snapshots
+/// and calls [`broadcast`](Self::broadcast).
+///
+/// ```ignore
+/// let mut server = StreamServer::bind("0.0.0.0:9001")?;
+/// app.on_pre_render(move |shapes, _| {
+///     server.publish(&shapes[0]);
+/// });
+/// ```
+pub struct StreamServer {
+    address: SocketAddr,
+    clients: Arc<Mutex<Vec<Client>>>,
+    closed: Arc<AtomicBool>,
+    format: WireFormat,
+    encoder: SnapshotEncoder,
+    interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+impl StreamServer {
+    /// Listen for WebSocket clients on `address`, e.g. `"0.0.0.0:9001"`
+    /// (port 0 picks a free port; see [`local_addr`](Self::local_addr)).
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+        let (thread_clients, thread_closed) = (Arc::clone(&clients), Arc::clone(&closed));
+        std::thread::spawn(move || {
+            while !thread_closed.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        // A failed handshake only loses that client.
+                        let socket = stream.set_nonblocking(false).and_then(|_| WebSocket::accept(stream, SEND_TIMEOUT));
+                        if let Ok(socket) = socket {
+                            thread_clients.lock().unwrap().push(Client { socket, joined: false });
+                        }
+                    }
+                    // No pending client, or a transient accept error.
+                    Err(_) => std::thread::sleep(ACCEPT_INTERVAL),
+                }
+            }
+        });
+        Ok(Self {
+            address,
+            clients,
+            closed,
+            format: WireFormat::Binary,
+            encoder: SnapshotEncoder::new(),
+            interval: Duration::from_secs_f32(1.0 / DEFAULT_RATE),
+            last_sent: None,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Most snapshots per second [`publish`](Self::publish) sends (30 by
+    /// default). Values of zero or less remove the limit.
+    pub fn set_max_rate(&mut self, hz: f32) -> &mut Self {
+        self.interval = if hz > 0.0 { Duration::from_secs_f32(1.0 / hz) } else { Duration::ZERO };
+        self
+    }
+
+    pub fn set_format(&mut self, format: WireFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Broadcast `shape`'s instance positions and colors, unless the last
+    /// send was less than one rate interval ago. Returns whether it sent.
+    pub fn publish(&mut self, shape: &ShapeRenderable) -> bool {
+        let now = Instant::now();
+        if self.last_sent.is_some_and(|last| now.duration_since(last) < self.interval) {
+            return false;
+        }
+        self.last_sent = Some(now);
+        self.broadcast(&Snapshot::from_shape(shape));
+        true
+    }
+
+    /// Send `snapshot` to every client now, ignoring the rate limit.
+    pub fn broadcast(&mut self, snapshot: &Snapshot) {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.is_empty() {
+            return;
+        }
+        match self.format {
+            WireFormat::Json => {
+                let message = Message::Text(snapshot.to_json());
+                clients.retain_mut(|client| client.socket.send(&message).is_ok());
+            }
+            WireFormat::Binary => {
+                // Clients that joined since the last send start from a
+                // keyframe of the same snapshot the others get a delta of.
+                let message = Message::Binary(self.encoder.encode(snapshot));
+                let keyframe = clients.iter().any(|c| !c.joined).then(|| self.encoder.keyframe()).flatten().map(Message::Binary);
+                clients.retain_mut(|client| {
+                    let message = if client.joined { &message } else { keyframe.as_ref().unwrap_or(&message) };
+                    client.joined = true;
+                    client.socket.send(message).is_ok()
+                });
+            }
+        }
+    }
+}
+
+impl Drop for StreamServer {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Vec2;
+    use crate::net::StreamClient;
+
+    /// Broadcast snapshots moving right by one each time until the client
+    /// has seen one, and return it.
+    fn first_received(format: WireFormat) -> Snapshot {
+        let mut server = StreamServer::bind("127.0.0.1:0").unwrap();
+        server.set_format(format);
+        let mut client = StreamClient::connect(&format!("ws://{}", server.local_addr())).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        for x in 0.. {
+            server.broadcast(&Snapshot { positions: vec![Vec2::new(x as f32, 4.0)], colors: Vec::new() });
+            std::thread::sleep(Duration::from_millis(20));
+            if let Some(received) = client.take_new() {
+                assert_eq!(server.client_count(), 1);
+                return (*received).clone();
+            }
+            assert!(Instant::now() < deadline, "no snapshot received: {:?}", client.last_error());
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn clients_receive_broadcast_snapshots() {
+        for format in [WireFormat::Binary, WireFormat::Json] {
+            assert_eq!(first_received(format).positions[0].y, 4.0);
+        }
+    }
+}
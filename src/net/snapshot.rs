@@ -0,0 +1,108 @@
+use crate::core::{Color, Vec2};
+use crate::graphics2d::shapes::ShapeRenderable;
+use crate::net::json::Value;
+
+/// One frame of streamed instance data: positions and optional
+/// per-instance colors.
+///
+/// As JSON, colors are RGBA components in `[0, 1]` and may be omitted:
+///
+/// ```text
+/// {"positions": [[120.0, 80.5], [300.0, 42.0]], "colors": [[1.0, 0.5, 0.0, 1.0], [0.2, 0.4, 1.0, 1.0]]}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub positions: Vec<Vec2>,
+    pub colors: Vec<Color>,
+}
+
+impl Snapshot {
+    /// The instance positions and colors `shape` was last given.
+    pub fn from_shape(shape: &ShapeRenderable) -> Self {
+        Self { positions: shape.instance_positions().to_vec(), colors: shape.instance_colors().to_vec() }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = Value::parse(text)?;
+        let positions = numbers::<2>(value.get("positions").ok_or("snapshot has no \"positions\"")?)?
+            .into_iter()
+            .map(|p| Vec2::new(p[0], p[1]))
+            .collect();
+        let colors = match value.get("colors") {
+            Some(colors) => numbers::<4>(colors)?.into_iter().map(|c| Color::from_rgba(c[0], c[1], c[2], c[3])).collect(),
+            None => Vec::new(),
+        };
+        Ok(Self { positions, colors })
+    }
+
+    pub fn to_json(&self) -> String {
+        let tuple = |values: &[f32]| Value::Array(values.iter().map(|&v| Value::Number(v as f64)).collect());
+        let mut members = vec![(
+            "positions".to_string(),
+            Value::Array(self.positions.iter().map(|p| tuple(&[p.x, p.y])).collect()),
+        )];
+        if !self.colors.is_empty() {
+            let colors = self.colors.iter().map(|c| tuple(&[c.red_value(), c.green_value(), c.blue_value(), c.alpha()]));
+            members.push(("colors".to_string(), Value::Array(colors.collect())));
+        }
+        Value::Object(members).to_string()
+    }
+
+    /// Draw `shape` once per position, with the per-instance colors if
+    /// there are any.
+    pub fn apply_to(&self, shape: &mut ShapeRenderable) {
+        if shape.instance_positions().is_empty() {
+            shape.create_multiple_instances(self.positions.len());
+        }
+        shape.set_instance_positions(&self.positions);
+        if !self.colors.is_empty() {
+            shape.set_instance_colors(&self.colors);
+        }
+    }
+}
+
+/// An array of `N`-number arrays.
+fn numbers<const N: usize>(value: &Value) -> Result<Vec<[f32; N]>, String> {
+    let items = value.as_array().ok_or("expected an array")?;
+    items
+        .iter()
+        .map(|item| {
+            let mut tuple = [0.0; N];
+            match item.as_array() {
+                Some(values) if values.len() == N => {
+                    for (out, v) in tuple.iter_mut().zip(values) {
+                        *out = v.as_f64().ok_or("expected a number")? as f32;
+                    }
+                    Ok(tuple)
+                }
+                _ => Err(format!("expected arrays of {N} numbers")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let snapshot = Snapshot {
+            positions: vec![Vec2::new(1.5, -2.0), Vec2::new(300.0, 40.25)],
+            colors: vec![Color::from_rgba(1.0, 0.5, 0.0, 1.0), Color::from_rgba(0.0, 0.25, 1.0, 0.5)],
+        };
+        let parsed = Snapshot::from_json(&snapshot.to_json()).unwrap();
+        assert_eq!(parsed.positions, snapshot.positions);
+        assert_eq!(parsed.colors[1].alpha(), 0.5);
+        assert_eq!(parsed.colors[0].green_value(), 0.5);
+    }
+
+    #[test]
+    fn colors_are_optional_and_shapes_checked() {
+        let parsed = Snapshot::from_json(r#"{"positions": [[1, 2]]}"#).unwrap();
+        assert_eq!(parsed.positions, vec![Vec2::new(1.0, 2.0)]);
+        assert!(parsed.colors.is_empty());
+        assert!(Snapshot::from_json(r#"{"positions": [[1, 2, 3]]}"#).is_err());
+        assert!(Snapshot::from_json(r#"{"colors": []}"#).is_err());
+    }
+}
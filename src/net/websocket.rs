@@ -0,0 +1,376 @@
+//! A minimal WebSocket (RFC 6455) endpoint over a plain TCP stream:
+//! the client and server opening handshakes, unfragmented sends, and
+//! reads that reassemble fragmented messages and answer pings. `wss://`
+//! is not supported.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, SystemTime};
+
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Largest message accepted, to bound memory on a bad stream.
+const MAX_MESSAGE: usize = 64 << 20;
+
+/// A received frame with its payload unmasked.
+#[derive(Debug, PartialEq)]
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+pub(crate) struct WebSocket {
+    stream: TcpStream,
+    /// Clients mask what they send; servers must not.
+    client: bool,
+    /// Received bytes not yet parsed into frames.
+    pending: Vec<u8>,
+    /// Opcode and payload of a fragmented message being reassembled.
+    fragments: Option<(u8, Vec<u8>)>,
+}
+
+impl WebSocket {
+    /// Connect to a `ws://host[:port][/path]` URL and complete the
+    /// client handshake. Reads time out after `timeout`, so
+    /// [`read_message`](Self::read_message) returns regularly.
+    pub(crate) fn connect(url: &str, timeout: Duration) -> io::Result<Self> {
+        let (host, port, path) = parse_url(url).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let stream = TcpStream::connect((host.as_str(), port))?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(timeout))?;
+        let key = base64(&random_bytes::<16>());
+        let request = format!(
+            "GET {path} HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        let mut socket = Self { stream, client: true, pending: Vec::new(), fragments: None };
+        socket.stream.write_all(request.as_bytes())?;
+        let response = socket.read_head()?;
+        let mut lines = response.lines();
+        let status = lines.next().unwrap_or_default();
+        if status.split_whitespace().nth(1) != Some("101") {
+            return Err(io::Error::other(format!("handshake refused: {status}")));
+        }
+        if header(&response, "sec-websocket-accept") != Some(accept_key(&key).as_str()) {
+            return Err(io::Error::other("handshake returned a wrong Sec-WebSocket-Accept"));
+        }
+        Ok(socket)
+    }
+
+    /// Complete the server handshake on an accepted connection. Writes
+    /// time out after `timeout`, so a stalled client cannot block sends
+    /// for long.
+    pub(crate) fn accept(stream: TcpStream, timeout: Duration) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        let mut socket = Self { stream, client: false, pending: Vec::new(), fragments: None };
+        let request = socket.read_head()?;
+        let upgrade = header(&request, "upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        let Some(key) = header(&request, "sec-websocket-key").filter(|_| upgrade) else {
+            let _ = socket.stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+            return Err(invalid("not a WebSocket upgrade request"));
+        };
+        let response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {}\r\n\r\n",
+            accept_key(key)
+        );
+        socket.stream.write_all(response.as_bytes())?;
+        Ok(socket)
+    }
+
+    /// Read the HTTP head up to the blank line, keeping any bytes after
+    /// it for the frame reader.
+    fn read_head(&mut self) -> io::Result<String> {
+        let mut buf = [0u8; 1024];
+        loop {
+            if let Some(end) = self.pending.windows(4).position(|w| w == b"\r\n\r\n") {
+                let head = String::from_utf8_lossy(&self.pending[..end]).into_owned();
+                self.pending.drain(..end + 4);
+                return Ok(head);
+            }
+            if self.pending.len() > 16 * 1024 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "HTTP head too long"));
+            }
+            match self.stream.read(&mut buf)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => self.pending.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+
+    pub(crate) fn send(&mut self, message: &Message) -> io::Result<()> {
+        match message {
+            Message::Text(text) => self.send_frame(OP_TEXT, text.as_bytes()),
+            Message::Binary(bytes) => self.send_frame(OP_BINARY, bytes),
+        }
+    }
+
+    fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mask = self.client.then(random_bytes::<4>);
+        self.stream.write_all(&encode_frame(opcode, payload, mask))
+    }
+
+    /// The next message, or `None` if the read timed out first. Pings
+    /// are answered on the way; a close frame or a protocol error ends
+    /// the connection with an error.
+    pub(crate) fn read_message(&mut self) -> io::Result<Option<Message>> {
+        let mut buf = [0u8; 16 * 1024];
+        loop {
+            while let Some((frame, used)) = decode_frame(&self.pending)? {
+                self.pending.drain(..used);
+                if let Some(message) = self.on_frame(frame)? {
+                    return Ok(Some(message));
+                }
+            }
+            match self.stream.read(&mut buf) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => self.pending.extend_from_slice(&buf[..n]),
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn on_frame(&mut self, Frame { fin, opcode, payload }: Frame) -> io::Result<Option<Message>> {
+        let (opcode, payload) = match opcode {
+            OP_PING => {
+                self.send_frame(OP_PONG, &payload)?;
+                return Ok(None);
+            }
+            OP_PONG => return Ok(None),
+            OP_CLOSE => {
+                let _ = self.send_frame(OP_CLOSE, &payload);
+                return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "closed by peer"));
+            }
+            OP_CONTINUATION => {
+                let Some((first, mut data)) = self.fragments.take() else {
+                    return Err(invalid("continuation frame without a message"));
+                };
+                if data.len() + payload.len() > MAX_MESSAGE {
+                    return Err(invalid("message too large"));
+                }
+                data.extend_from_slice(&payload);
+                if !fin {
+                    self.fragments = Some((first, data));
+                    return Ok(None);
+                }
+                (first, data)
+            }
+            OP_TEXT | OP_BINARY if !fin => {
+                self.fragments = Some((opcode, payload));
+                return Ok(None);
+            }
+            OP_TEXT | OP_BINARY => (opcode, payload),
+            _ => return Err(invalid("unknown opcode")),
+        };
+        Ok(Some(if opcode == OP_TEXT {
+            Message::Text(String::from_utf8(payload).map_err(|_| invalid("text message is not UTF-8"))?)
+        } else {
+            Message::Binary(payload)
+        }))
+    }
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/// Split a `ws://` URL into host, port (80 by default) and path.
+fn parse_url(url: &str) -> Result<(String, u16, String), String> {
+    let rest = url.strip_prefix("ws://").ok_or_else(|| format!("not a ws:// URL: {url}"))?;
+    let (authority, path) = rest.find('/').map_or((rest, "/"), |i| (&rest[..i], &rest[i..]));
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, port.parse().map_err(|_| format!("bad port in {url}"))?),
+        _ => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(format!("no host in {url}"));
+    }
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+/// Value of an HTTP header, matched case-insensitively.
+fn header<'h>(head: &'h str, name: &str) -> Option<&'h str> {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// The `Sec-WebSocket-Accept` value for a handshake key.
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{key}{HANDSHAKE_GUID}").as_bytes()))
+}
+
+fn encode_frame(opcode: u8, payload: &[u8], mask: Option<[u8; 4]>) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode);
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len @ 0..=125 => frame.push(mask_bit | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(mask_bit | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(key) => {
+            frame.extend_from_slice(&key);
+            frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]));
+        }
+        None => frame.extend_from_slice(payload),
+    }
+    frame
+}
+
+/// A complete frame at the start of `data` and the bytes it used, or
+/// `None` if more data is needed.
+fn decode_frame(data: &[u8]) -> io::Result<Option<(Frame, usize)>> {
+    if data.len() < 2 {
+        return Ok(None);
+    }
+    let (fin, opcode) = (data[0] & 0x80 != 0, data[0] & 0x0F);
+    let masked = data[1] & 0x80 != 0;
+    let (len, mut offset) = match data[1] & 0x7F {
+        126 if data.len() >= 4 => (u16::from_be_bytes([data[2], data[3]]) as usize, 4),
+        127 if data.len() >= 10 => {
+            let len = u64::from_be_bytes(data[2..10].try_into().unwrap());
+            (usize::try_from(len).unwrap_or(usize::MAX), 10)
+        }
+        126 | 127 => return Ok(None),
+        len => (len as usize, 2),
+    };
+    if len > MAX_MESSAGE {
+        return Err(invalid("frame too large"));
+    }
+    let key = if masked {
+        let Some(key) = data.get(offset..offset + 4) else { return Ok(None) };
+        offset += 4;
+        Some([key[0], key[1], key[2], key[3]])
+    } else {
+        None
+    };
+    let Some(payload) = data.get(offset..offset + len) else { return Ok(None) };
+    let payload = match key {
+        Some(key) => payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]).collect(),
+        None => payload.to_vec(),
+    };
+    Ok(Some((Frame { fin, opcode, payload }, offset + len)))
+}
+
+/// Unpredictable enough for handshake keys and frame masks, which only
+/// guard against caching proxies.
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    for chunk in bytes.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos());
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes()[..chunk.len()]);
+    }
+    bytes
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = u32::from(chunk[0]) << 16 | u32::from(*chunk.get(1).unwrap_or(&0)) << 8 | u32::from(*chunk.get(2).unwrap_or(&0));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, temp);
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+    let mut digest = [0u8; 20];
+    for (out, word) in digest.chunks_mut(4).zip(h) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_matches_the_rfc_example() {
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(base64(b"ab"), "YWI=");
+    }
+
+    #[test]
+    fn frames_round_trip_masked_and_unmasked() {
+        let payload: Vec<u8> = (0..300u32).map(|i| i as u8).collect();
+        for mask in [None, Some([1, 2, 3, 4])] {
+            let frame = encode_frame(OP_BINARY, &payload, mask);
+            assert_eq!(decode_frame(&frame[..frame.len() - 1]).unwrap(), None);
+            let expected = Frame { fin: true, opcode: OP_BINARY, payload: payload.clone() };
+            assert_eq!(decode_frame(&frame).unwrap(), Some((expected, frame.len())));
+        }
+    }
+
+    #[test]
+    fn urls_split_into_host_port_and_path() {
+        assert_eq!(parse_url("ws://localhost:9001/balls").unwrap(), ("localhost".to_string(), 9001, "/balls".to_string()));
+        assert_eq!(parse_url("ws://example.com").unwrap(), ("example.com".to_string(), 80, "/".to_string()));
+        assert!(parse_url("wss://example.com").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Rhai scripts that drive an [`App`](crate::core::App) at runtime,
+//! enabled with the `script` feature.
+//!
+//! A script loaded with [`App::load_script`](crate::core::App::load_script)
+//! runs its top-level statements and then `init()`, if defined. Each frame
+//! calls `update(time, dt)`, and shapes marked with `clickable(index)`
+//! call `on_click(index)`, `on_hover_enter(index)` and
+//! `on_hover_exit(index)`. All hooks are optional.
+//!
+//! Shapes are addressed by their index in the app, like
+//! [`App::shapes_mut`](crate::core::App::shapes_mut). Numbers may be
+//! integers or decimals; colors are CSS names (`"tomato"`) or hex
+//! (`"#ff8800"`, `"#ff880080"`).
+//!
+//! | Function | |
+//! |---|---|
+//! | `add_rect(x, y, w, h, fill)` | add a filled rectangle, returns its index |
+//! | `add_circle(x, y, r, fill)` | add a filled circle, returns its index |
+//! | `add_line(x1, y1, x2, y2, color, width)` | add a line, returns its index |
+//! | `shape_count()` | number of shapes |
+//! | `position(i)` | `[x, y]` of a shape |
+//! | `set_position(i, x, y)`, `set_rotation(i, radians)`, `set_scale(i, s)` | move a shape |
+//! | `set_fill(i, color)`, `set_stroke(i, color)`, `set_opacity(i, a)`, `set_visible(i, bool)` | restyle a shape |
+//! | `clickable(i)` | send the shape's mouse events to the hooks |
+//!
+//! ```text
+//! let dot = add_circle(100, 100, 12, "orange");
+//! clickable(dot);
+//!
+//! fn update(time, dt) {
+//!     set_position(0, 100 + 50 * sin(time), 100);
+//! }
+//!
+//! fn on_click(i) {
+//!     set_fill(i, "steelblue");
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Scope, AST, INT};
+
+use crate::core::Color;
+use crate::graphics2d::shapes::{Circle, Line, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// What the registered functions work on while a hook runs: the app's
+/// shapes, lent for the call, and the shapes made clickable.
+#[derive(Default)]
+struct Host {
+    shapes: Vec<ShapeRenderable>,
+    clickable: Vec<usize>,
+}
+
+/// A compiled script and its global variables.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    host: Rc<RefCell<Host>>,
+}
+
+impl Script {
+    /// Compile a script. Nothing runs until it is loaded into an app.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let host = Rc::new(RefCell::new(Host::default()));
+        let mut engine = Engine::new();
+        register(&mut engine, &host);
+        let ast = engine.compile(source).map_err(|e| format!("script error: {e}"))?;
+        Ok(Self { engine, ast, scope: Scope::new(), host })
+    }
+
+    /// Read and compile a script file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Self::new(&source).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Whether the script defines `name` taking `arity` arguments.
+    pub fn has_function(&self, name: &str, arity: usize) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name && f.params.len() == arity)
+    }
+
+    /// Run the top-level statements with `shapes` lent to the script.
+    pub(crate) fn run(&mut self, shapes: &mut Vec<ShapeRenderable>) -> Result<Vec<usize>, String> {
+        let (engine, ast, scope) = (&self.engine, &self.ast, &mut self.scope);
+        lend(&self.host, shapes, || engine.run_ast_with_scope(scope, ast).map_err(|e| e.to_string()))
+    }
+
+    /// Call hook `name` if the script defines it, with `shapes` lent to
+    /// the script. Returns the indices made clickable during the call.
+    pub(crate) fn call(&mut self, shapes: &mut Vec<ShapeRenderable>, name: &str, args: impl FuncArgs) -> Result<Vec<usize>, String> {
+        let mut values = Vec::new();
+        args.parse(&mut values);
+        if !self.has_function(name, values.len()) {
+            return Ok(Vec::new());
+        }
+        let (engine, ast, scope) = (&self.engine, &self.ast, &mut self.scope);
+        lend(&self.host, shapes, || {
+            // The top-level statements ran once, in `run`.
+            let options = CallFnOptions::new().eval_ast(false);
+            engine.call_fn_with_options::<Dynamic>(options, scope, ast, name, values).map(drop).map_err(|e| format!("in `{name}`: {e}"))
+        })
+    }
+}
+
+/// Move `shapes` into the host for the duration of `f`, then back.
+fn lend(host: &RefCell<Host>, shapes: &mut Vec<ShapeRenderable>, f: impl FnOnce() -> Result<(), String>) -> Result<Vec<usize>, String> {
+    std::mem::swap(&mut host.borrow_mut().shapes, shapes);
+    let result = f();
+    let mut host = host.borrow_mut();
+    std::mem::swap(&mut host.shapes, shapes);
+    let clickable = std::mem::take(&mut host.clickable);
+    result.map(|_| clickable)
+}
+
+fn register(engine: &mut Engine, host: &Rc<RefCell<Host>>) {
+    let h = Rc::clone(host);
+    engine.register_fn("add_rect", move |x: Dynamic, y: Dynamic, w: Dynamic, h_: Dynamic, fill: &str| -> ScriptResult<INT> {
+        let kind = ShapeKind::Rectangle(Rectangle::new(num(w)?, num(h_)?));
+        add(&h, kind, ShapeStyle::fill(color(fill)?), num(x)?, num(y)?)
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("add_circle", move |x: Dynamic, y: Dynamic, r: Dynamic, fill: &str| -> ScriptResult<INT> {
+        add(&h, ShapeKind::Circle(Circle::new(num(r)?)), ShapeStyle::fill(color(fill)?), num(x)?, num(y)?)
+    });
+    let h = Rc::clone(host);
+    engine.register_fn(
+        "add_line",
+        move |x1: Dynamic, y1: Dynamic, x2: Dynamic, y2: Dynamic, stroke: &str, width: Dynamic| -> ScriptResult<INT> {
+            let (x1, y1) = (num(x1)?, num(y1)?);
+            let kind = ShapeKind::Line(Line::new((x1, y1), (num(x2)?, num(y2)?)));
+            let style = ShapeStyle { fill: None, stroke_color: Some(color(stroke)?), stroke_width: Some(num(width)?), ..ShapeStyle::default() };
+            add(&h, kind, style, x1, y1)
+        },
+    );
+    let h = Rc::clone(host);
+    engine.register_fn("shape_count", move || h.borrow().shapes.len() as INT);
+    let h = Rc::clone(host);
+    engine.register_fn("position", move |i: INT| -> ScriptResult<Array> {
+        with_shape(&h, i, |shape| {
+            let (x, y) = shape.position();
+            vec![Dynamic::from_float(x.into()), Dynamic::from_float(y.into())]
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_position", move |i: INT, x: Dynamic, y: Dynamic| -> ScriptResult<()> {
+        let (x, y) = (num(x)?, num(y)?);
+        with_shape(&h, i, |shape| {
+            shape.set_position(x, y);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_rotation", move |i: INT, angle: Dynamic| -> ScriptResult<()> {
+        let angle = num(angle)?;
+        with_shape(&h, i, |shape| {
+            shape.set_rotation(angle);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_scale", move |i: INT, scale: Dynamic| -> ScriptResult<()> {
+        let scale = num(scale)?;
+        with_shape(&h, i, |shape| {
+            shape.set_scale(scale);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_fill", move |i: INT, fill: &str| -> ScriptResult<()> {
+        let fill = color(fill)?;
+        with_shape(&h, i, |shape| {
+            shape.set_fill_color(fill);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_stroke", move |i: INT, stroke: &str| -> ScriptResult<()> {
+        let stroke = color(stroke)?;
+        with_shape(&h, i, |shape| {
+            shape.set_stroke_color(stroke);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_opacity", move |i: INT, opacity: Dynamic| -> ScriptResult<()> {
+        let opacity = num(opacity)?;
+        with_shape(&h, i, |shape| {
+            shape.set_opacity(opacity);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("set_visible", move |i: INT, visible: bool| -> ScriptResult<()> {
+        with_shape(&h, i, |shape| {
+            shape.set_visible(visible);
+        })
+    });
+    let h = Rc::clone(host);
+    engine.register_fn("clickable", move |i: INT| -> ScriptResult<()> {
+        with_shape(&h, i, |_| ())?;
+        h.borrow_mut().clickable.push(i as usize);
+        Ok(())
+    });
+}
+
+fn add(host: &RefCell<Host>, kind: ShapeKind, style: ShapeStyle, x: f32, y: f32) -> ScriptResult<INT> {
+    let mut shape = ShapeRenderable::from_shape(kind, style);
+    shape.set_position(x, y);
+    let shapes = &mut host.borrow_mut().shapes;
+    shapes.push(shape);
+    Ok(shapes.len() as INT - 1)
+}
+
+fn with_shape<T>(host: &RefCell<Host>, index: INT, f: impl FnOnce(&mut ShapeRenderable) -> T) -> ScriptResult<T> {
+    let shapes = &mut host.borrow_mut().shapes;
+    let count = shapes.len();
+    let shape = usize::try_from(index).ok().and_then(|i| shapes.get_mut(i));
+    shape.map(f).ok_or_else(|| format!("no shape at index {index} (there are {count})").into())
+}
+
+/// An integer or decimal argument as `f32`.
+fn num(value: Dynamic) -> ScriptResult<f32> {
+    match value.as_float() {
+        Ok(v) => Ok(v as f32),
+        Err(_) => value.as_int().map(|v| v as f32).map_err(|t| format!("expected a number, got {t}").into()),
+    }
+}
+
+fn color(text: &str) -> ScriptResult<Color> {
+    parse_color(text).ok_or_else(|| format!("unknown color `{text}`").into())
+}
+
+/// A CSS color name or `#rrggbb` / `#rrggbbaa` hex.
+fn parse_color(text: &str) -> Option<Color> {
+    let Some(hex) = text.trim().strip_prefix('#') else {
+        return Color::by_name(text);
+    };
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { byte(6)? } else { 255 };
+    Some(Color::from_rgba8(byte(0)?, byte(2)?, byte(4)?, alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_parse_from_names_and_hex() {
+        assert_eq!(parse_color("#FF8000").map(|c| c.to_hex()), Some("#FF8000".to_string()));
+        assert_eq!(parse_color("#ff800080").map(|c| (c.alpha() * 255.0).round()), Some(128.0));
+        assert_eq!(parse_color("steel_blue").map(|c| c.to_hex()), Some("#4682B4".to_string()));
+        assert!(parse_color("#12345").is_none());
+        assert!(parse_color("not a color").is_none());
+    }
+
+    #[test]
+    fn hooks_are_found_by_name_and_arity() {
+        let script = Script::new("fn update(time, dt) {} fn on_click(i) {} let speed = 2;").unwrap();
+        assert!(script.has_function("update", 2));
+        assert!(script.has_function("on_click", 1));
+        assert!(!script.has_function("on_click", 0));
+        assert!(!script.has_function("init", 0));
+        assert!(Script::new("fn broken( {").is_err());
+    }
+
+    #[test]
+    fn hooks_run_against_the_lent_shapes() {
+        let mut script = Script::new("fn tick() { shape_count() }").unwrap();
+        let mut shapes = Vec::new();
+        assert_eq!(script.run(&mut shapes), Ok(Vec::new()));
+        assert_eq!(script.call(&mut shapes, "tick", ()), Ok(Vec::new()));
+        assert_eq!(script.call(&mut shapes, "missing", ()), Ok(Vec::new()));
+        let error = Script::new("fn bad() { set_fill(3, \"red\") }").unwrap().call(&mut shapes, "bad", ()).unwrap_err();
+        assert!(error.contains("no shape at index 3"), "{error}");
+    }
+}
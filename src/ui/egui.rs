@@ -0,0 +1,309 @@
+//! [egui](https://github.com/emilk/egui) panels, sliders and debug UIs
+//! drawn over the scene, enabled with the `egui` feature.
+//!
+//! [`EguiOverlay`] turns the window's input events into egui input and
+//! paints egui's output with the crate's GL layer, so no second windowing
+//! or rendering stack is needed. With an [`App`](crate::core::App), use
+//! [`App::on_egui`](crate::core::App::on_egui), which feeds the overlay
+//! and draws it after `on_render`. Hosts driving the renderer themselves
+//! call [`handle_input`](EguiOverlay::handle_input) and
+//! [`run`](EguiOverlay::run).
+//!
+//! Text input is derived from key events with a US layout, since the
+//! window reports keys rather than characters.
+
+use std::collections::HashMap;
+
+pub use egui;
+use egui::epaint::{ImageDelta, Primitive};
+use egui::{
+    ClippedPrimitive, Context, Event, Key, Modifiers, MouseWheelUnit, PointerButton, Pos2, RawInput, Rect,
+    TextureFilter, TextureId, TextureWrapMode, TexturesDelta,
+};
+
+use crate::core::engine::glfw::{
+    GLFW_KEY_BACKSPACE, GLFW_KEY_DELETE, GLFW_KEY_DOWN, GLFW_KEY_END, GLFW_KEY_ENTER, GLFW_KEY_ESCAPE, GLFW_KEY_F1,
+    GLFW_KEY_F12, GLFW_KEY_HOME, GLFW_KEY_INSERT, GLFW_KEY_LEFT, GLFW_KEY_PAGE_DOWN, GLFW_KEY_PAGE_UP, GLFW_KEY_RIGHT,
+    GLFW_KEY_SPACE, GLFW_KEY_TAB, GLFW_KEY_UP, GLFW_MOD_ALT, GLFW_MOD_CONTROL, GLFW_MOD_SHIFT, GLFW_MOD_SUPER,
+    GLFW_PRESS, GLFW_RELEASE,
+};
+use crate::core::engine::opengl::{
+    gl_bind_texture, gl_delete_texture, gl_gen_texture, gl_pixel_storei, gl_tex_image_2d, gl_tex_parameteri,
+    gl_tex_sub_image_2d, GLuint, GL_CLAMP_TO_EDGE, GL_LINEAR, GL_MIRRORED_REPEAT, GL_NEAREST, GL_REPEAT, GL_RGBA,
+    GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_TEXTURE_MIN_FILTER, GL_TEXTURE_WRAP_S, GL_TEXTURE_WRAP_T,
+    GL_UNPACK_ALIGNMENT, GL_UNSIGNED_BYTE,
+};
+use crate::core::{InputEvent, Renderer};
+use crate::ui::input::key_char;
+use crate::ui::painter::UiPainter;
+
+/// An egui context wired to the window's input and the crate's renderer.
+///
+/// ```ignore
+/// let mut overlay = EguiOverlay::new();
+/// // for each window input event
+/// overlay.handle_input(event);
+/// // each frame, after drawing the scene
+/// overlay.run(renderer, |ctx| {
+///     egui::Window::new("Layers").show(ctx, |ui| ui.checkbox(&mut show_grid, "Grid"));
+/// });
+/// ```
+pub struct EguiOverlay {
+    context: Context,
+    events: Vec<Event>,
+    modifiers: Modifiers,
+    pointer: Pos2,
+    /// Created on the first `run`, when a GL context is known to be current.
+    painter: Option<UiPainter>,
+    textures: HashMap<TextureId, GLuint>,
+}
+
+impl Default for EguiOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EguiOverlay {
+    pub fn new() -> Self {
+        Self {
+            context: Context::default(),
+            events: Vec::new(),
+            modifiers: Modifiers::default(),
+            pointer: Pos2::ZERO,
+            painter: None,
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Whether egui is using the pointer (over a panel, or dragging a
+    /// widget), so the scene should ignore it, e.g. through the flag
+    /// returned by [`App::enable_camera`](crate::core::App::enable_camera).
+    pub fn wants_pointer(&self) -> bool {
+        self.context.wants_pointer_input()
+    }
+
+    /// Whether a text field has keyboard focus.
+    pub fn wants_keyboard(&self) -> bool {
+        self.context.wants_keyboard_input()
+    }
+
+    /// Queue a window input event for the next [`run`](Self::run).
+    pub fn handle_input(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::CursorPosition { x, y } => {
+                self.pointer = Pos2::new(x as f32, y as f32);
+                self.events.push(Event::PointerMoved(self.pointer));
+            }
+            InputEvent::MouseButton { button, action, mods } => {
+                let Some(button) = pointer_button(button) else { return };
+                self.modifiers = modifiers(mods);
+                self.events.push(Event::PointerButton {
+                    pos: self.pointer,
+                    button,
+                    pressed: action == GLFW_PRESS,
+                    modifiers: self.modifiers,
+                });
+            }
+            InputEvent::Scroll { x_offset, y_offset } => self.events.push(Event::MouseWheel {
+                unit: MouseWheelUnit::Line,
+                delta: egui::vec2(x_offset as f32, y_offset as f32),
+                modifiers: self.modifiers,
+            }),
+            InputEvent::Key { key, action, mods, .. } => {
+                self.modifiers = modifiers(mods);
+                let pressed = action != GLFW_RELEASE;
+                if let Some(key) = egui_key(key) {
+                    if pressed && self.modifiers.command {
+                        match key {
+                            Key::C => self.events.push(Event::Copy),
+                            Key::X => self.events.push(Event::Cut),
+                            _ => {}
+                        }
+                    }
+                    self.events.push(Event::Key { key, physical_key: None, pressed, repeat: false, modifiers: self.modifiers });
+                }
+                if pressed && !(self.modifiers.ctrl || self.modifiers.alt || self.modifiers.mac_cmd) {
+                    if let Some(c) = key_char(key, self.modifiers.shift) {
+                        self.events.push(Event::Text(c.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run one egui pass with the queued input and draw its output over
+    /// whatever is in the framebuffer.
+    pub fn run(&mut self, renderer: &Renderer, ui: impl FnMut(&Context)) {
+        let (width, height) = renderer.window_handle.size();
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (fb_width, fb_height) = renderer.viewport_size();
+        let pixels_per_point = fb_width as f32 / width as f32;
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(width as f32, height as f32))),
+            time: Some(renderer.get_time()),
+            modifiers: self.modifiers,
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        self.context.set_pixels_per_point(pixels_per_point);
+        let output = self.context.run(input, ui);
+        let primitives = self.context.tessellate(output.shapes, output.pixels_per_point);
+
+        self.set_textures(&output.textures_delta);
+        let painter = self.painter.get_or_insert_with(UiPainter::new);
+        for ClippedPrimitive { clip_rect, primitive } in &primitives {
+            let Primitive::Mesh(mesh) = primitive else { continue };
+            let Some(&texture) = self.textures.get(&mesh.texture_id) else { continue };
+            let clip = [clip_rect.min.x, clip_rect.min.y, clip_rect.max.x, clip_rect.max.y];
+            let vertices = mesh.indices.iter().map(|&i| {
+                let v = &mesh.vertices[i as usize];
+                ([v.pos.x, v.pos.y], [v.uv.x, v.uv.y], v.color.to_array())
+            });
+            painter.add_triangles(texture, clip, vertices);
+        }
+        painter.paint((width as f32, height as f32), (fb_width, fb_height), true);
+        self.free_textures(&output.textures_delta);
+    }
+
+    /// Apply new and changed textures before painting.
+    fn set_textures(&mut self, delta: &TexturesDelta) {
+        for (id, image) in &delta.set {
+            let texture = *self.textures.entry(*id).or_insert_with(gl_gen_texture);
+            upload(texture, image);
+        }
+    }
+
+    /// Release the textures egui no longer uses, after painting.
+    fn free_textures(&mut self, delta: &TexturesDelta) {
+        for id in &delta.free {
+            if let Some(texture) = self.textures.remove(id) {
+                gl_delete_texture(texture);
+            }
+        }
+    }
+}
+
+impl Drop for EguiOverlay {
+    fn drop(&mut self) {
+        for texture in self.textures.values() {
+            gl_delete_texture(*texture);
+        }
+    }
+}
+
+/// Upload a whole texture or a patch of it as RGBA8.
+fn upload(texture: GLuint, delta: &ImageDelta) {
+    let egui::ImageData::Color(image) = &delta.image;
+    let pixels: Vec<u8> = image.pixels.iter().flat_map(|c| c.to_array()).collect();
+    let [width, height] = image.size;
+    gl_bind_texture(GL_TEXTURE_2D, texture);
+    gl_pixel_storei(GL_UNPACK_ALIGNMENT, 1);
+    match delta.pos {
+        None => {
+            let filter = |f: TextureFilter| match f {
+                TextureFilter::Nearest => GL_NEAREST,
+                TextureFilter::Linear => GL_LINEAR,
+            };
+            let wrap = match delta.options.wrap_mode {
+                TextureWrapMode::ClampToEdge => GL_CLAMP_TO_EDGE,
+                TextureWrapMode::Repeat => GL_REPEAT,
+                TextureWrapMode::MirroredRepeat => GL_MIRRORED_REPEAT,
+            };
+            gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, filter(delta.options.minification));
+            gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, filter(delta.options.magnification));
+            gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, wrap);
+            gl_tex_parameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, wrap);
+            gl_tex_image_2d(
+                GL_TEXTURE_2D,
+                0,
+                GL_RGBA,
+                width as i32,
+                height as i32,
+                0,
+                GL_RGBA as u32,
+                GL_UNSIGNED_BYTE,
+                pixels.as_ptr().cast(),
+            );
+        }
+        Some([x, y]) => gl_tex_sub_image_2d(
+            GL_TEXTURE_2D,
+            0,
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+            GL_RGBA as u32,
+            GL_UNSIGNED_BYTE,
+            pixels.as_ptr().cast(),
+        ),
+    }
+    gl_bind_texture(GL_TEXTURE_2D, 0);
+}
+
+fn pointer_button(button: i32) -> Option<PointerButton> {
+    Some(match button {
+        0 => PointerButton::Primary,
+        1 => PointerButton::Secondary,
+        2 => PointerButton::Middle,
+        3 => PointerButton::Extra1,
+        4 => PointerButton::Extra2,
+        _ => return None,
+    })
+}
+
+fn modifiers(mods: i32) -> Modifiers {
+    let (ctrl, sup) = (mods & GLFW_MOD_CONTROL != 0, mods & GLFW_MOD_SUPER != 0);
+    let mac = cfg!(target_os = "macos");
+    Modifiers {
+        alt: mods & GLFW_MOD_ALT != 0,
+        ctrl,
+        shift: mods & GLFW_MOD_SHIFT != 0,
+        mac_cmd: mac && sup,
+        command: if mac { sup } else { ctrl },
+    }
+}
+
+/// The egui key for a GLFW key code. Letters and digits share their
+/// ASCII codes.
+fn egui_key(key: i32) -> Option<Key> {
+    Some(match key {
+        GLFW_KEY_ESCAPE => Key::Escape,
+        GLFW_KEY_ENTER => Key::Enter,
+        GLFW_KEY_TAB => Key::Tab,
+        GLFW_KEY_BACKSPACE => Key::Backspace,
+        GLFW_KEY_INSERT => Key::Insert,
+        GLFW_KEY_DELETE => Key::Delete,
+        GLFW_KEY_RIGHT => Key::ArrowRight,
+        GLFW_KEY_LEFT => Key::ArrowLeft,
+        GLFW_KEY_DOWN => Key::ArrowDown,
+        GLFW_KEY_UP => Key::ArrowUp,
+        GLFW_KEY_PAGE_UP => Key::PageUp,
+        GLFW_KEY_PAGE_DOWN => Key::PageDown,
+        GLFW_KEY_HOME => Key::Home,
+        GLFW_KEY_END => Key::End,
+        GLFW_KEY_SPACE => Key::Space,
+        GLFW_KEY_F1..=GLFW_KEY_F12 => return Key::from_name(&format!("F{}", key - GLFW_KEY_F1 + 1)),
+        0x30..=0x39 | 0x41..=0x5A => return Key::from_name(&char::from(key as u8).to_string()),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_map_to_egui_keys() {
+        assert_eq!(egui_key(0x41), Some(Key::A));
+        assert_eq!(egui_key(0x37), Some(Key::Num7));
+        assert_eq!(egui_key(GLFW_KEY_F1 + 4), Some(Key::F5));
+        assert_eq!(egui_key(GLFW_KEY_BACKSPACE), Some(Key::Backspace));
+    }
+}
@@ -0,0 +1,230 @@
+//! [Dear ImGui](https://github.com/ocornut/imgui) windows drawn over the
+//! scene, enabled with the `imgui` feature.
+//!
+//! [`ImguiOverlay`] plays the part of ImGui's platform and renderer
+//! backends: it feeds the window's input events to ImGui and draws its
+//! draw lists with the crate's GL layer, so the raw window pointer from
+//! `Window::glfw_window_ptr` is not needed. With an
+//! [`App`](crate::core::App), use [`App::on_imgui`](crate::core::App::on_imgui),
+//! which feeds the overlay and draws it after `on_render`. Hosts driving
+//! the renderer themselves call [`handle_input`](ImguiOverlay::handle_input)
+//! and [`frame`](ImguiOverlay::frame).
+//!
+//! Text input is derived from key events with a US layout, since the
+//! window reports keys rather than characters. ImGui allows one context at
+//! a time, so only one overlay may exist.
+
+use std::time::Duration;
+
+pub use imgui;
+use imgui::{BackendFlags, Context, DrawCmd, DrawCmdParams, Key, MouseButton, TextureId, Ui};
+
+use crate::core::engine::glfw::{
+    GLFW_KEY_BACKSPACE, GLFW_KEY_DELETE, GLFW_KEY_DOWN, GLFW_KEY_END, GLFW_KEY_ENTER, GLFW_KEY_ESCAPE, GLFW_KEY_F1,
+    GLFW_KEY_F12, GLFW_KEY_HOME, GLFW_KEY_INSERT, GLFW_KEY_LEFT, GLFW_KEY_PAGE_DOWN, GLFW_KEY_PAGE_UP, GLFW_KEY_RIGHT,
+    GLFW_KEY_SPACE, GLFW_KEY_TAB, GLFW_KEY_UP, GLFW_MOD_ALT, GLFW_MOD_CONTROL, GLFW_MOD_SHIFT, GLFW_MOD_SUPER,
+    GLFW_RELEASE,
+};
+use crate::core::engine::opengl::{gl_delete_texture, GLuint};
+use crate::core::{generate_texture_from_rgba, InputEvent, Renderer, TextureOptions};
+use crate::ui::input::key_char;
+use crate::ui::painter::UiPainter;
+
+/// An ImGui context wired to the window's input and the crate's renderer.
+///
+/// ```ignore
+/// let mut overlay = ImguiOverlay::new();
+/// // for each window input event
+/// overlay.handle_input(event);
+/// // each frame, after drawing the scene
+/// overlay.frame(renderer, |ui| {
+///     ui.window("Layers").build(|| ui.checkbox("Grid", &mut show_grid));
+/// });
+/// ```
+pub struct ImguiOverlay {
+    context: Context,
+    /// Modifier keys held, as GLFW `mods` bits.
+    mods: i32,
+    /// Created on the first `frame`, when a GL context is known to be
+    /// current.
+    painter: Option<UiPainter>,
+    font_texture: Option<GLuint>,
+    last_time: Option<f64>,
+}
+
+impl Default for ImguiOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImguiOverlay {
+    /// Create the overlay. Panics if another ImGui context is active.
+    pub fn new() -> Self {
+        let mut context = Context::create();
+        context.set_ini_filename(None);
+        context.io_mut().backend_flags.insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+        Self { context, mods: 0, painter: None, font_texture: None, last_time: None }
+    }
+
+    /// The ImGui context, for styles, fonts and IO settings. Fonts added
+    /// after the first [`frame`](Self::frame) are not uploaded.
+    pub fn context_mut(&mut self) -> &mut Context {
+        &mut self.context
+    }
+
+    /// Whether ImGui is using the pointer (over a window, or dragging a
+    /// widget), so the scene should ignore it.
+    pub fn wants_pointer(&self) -> bool {
+        self.context.io().want_capture_mouse
+    }
+
+    /// Whether a text field has keyboard focus.
+    pub fn wants_keyboard(&self) -> bool {
+        self.context.io().want_capture_keyboard
+    }
+
+    /// Queue a window input event for the next [`frame`](Self::frame).
+    pub fn handle_input(&mut self, event: InputEvent) {
+        let io = self.context.io_mut();
+        match event {
+            InputEvent::CursorPosition { x, y } => io.add_mouse_pos_event([x as f32, y as f32]),
+            InputEvent::MouseButton { button, action, .. } => {
+                if let Some(button) = mouse_button(button) {
+                    io.add_mouse_button_event(button, action != GLFW_RELEASE);
+                }
+            }
+            InputEvent::Scroll { x_offset, y_offset } => io.add_mouse_wheel_event([x_offset as f32, y_offset as f32]),
+            InputEvent::Key { key, action, mods, .. } => {
+                if mods != self.mods {
+                    for (bit, modifier) in [
+                        (GLFW_MOD_CONTROL, Key::ModCtrl),
+                        (GLFW_MOD_SHIFT, Key::ModShift),
+                        (GLFW_MOD_ALT, Key::ModAlt),
+                        (GLFW_MOD_SUPER, Key::ModSuper),
+                    ] {
+                        if (mods ^ self.mods) & bit != 0 {
+                            io.add_key_event(modifier, mods & bit != 0);
+                        }
+                    }
+                    self.mods = mods;
+                }
+                let pressed = action != GLFW_RELEASE;
+                if let Some(key) = imgui_key(key) {
+                    io.add_key_event(key, pressed);
+                }
+                if pressed && mods & (GLFW_MOD_CONTROL | GLFW_MOD_ALT | GLFW_MOD_SUPER) == 0 {
+                    if let Some(c) = key_char(key, mods & GLFW_MOD_SHIFT != 0) {
+                        io.add_input_character(c);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Build one ImGui frame with the queued input and draw it over
+    /// whatever is in the framebuffer.
+    pub fn frame(&mut self, renderer: &Renderer, ui: impl FnOnce(&Ui)) {
+        let (width, height) = renderer.window_handle.size();
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (fb_width, fb_height) = renderer.viewport_size();
+        let now = renderer.get_time();
+        let delta = self.last_time.map_or(1.0 / 60.0, |last| (now - last).max(1e-6));
+        self.last_time = Some(now);
+
+        if self.font_texture.is_none() {
+            let fonts = self.context.fonts();
+            let atlas = fonts.build_rgba32_texture();
+            let texture = generate_texture_from_rgba(atlas.width, atlas.height, atlas.data, &TextureOptions::default());
+            fonts.tex_id = TextureId::new(texture as usize);
+            self.font_texture = Some(texture);
+        }
+
+        let io = self.context.io_mut();
+        io.display_size = [width as f32, height as f32];
+        io.display_framebuffer_scale = [fb_width as f32 / width as f32, fb_height as f32 / height as f32];
+        io.update_delta_time(Duration::from_secs_f64(delta));
+        ui(self.context.new_frame());
+        let draw_data = self.context.render();
+
+        let painter = self.painter.get_or_insert_with(UiPainter::new);
+        for list in draw_data.draw_lists() {
+            let (vertices, indices) = (list.vtx_buffer(), list.idx_buffer());
+            for command in list.commands() {
+                let DrawCmd::Elements { count, cmd_params } = command else { continue };
+                let DrawCmdParams { clip_rect, texture_id, vtx_offset, idx_offset } = cmd_params;
+                let triangles = indices[idx_offset..idx_offset + count].iter().map(|&i| {
+                    let v = &vertices[vtx_offset + i as usize];
+                    (v.pos, v.uv, v.col)
+                });
+                painter.add_triangles(texture_id.id() as GLuint, clip_rect, triangles);
+            }
+        }
+        painter.paint((width as f32, height as f32), (fb_width, fb_height), false);
+    }
+}
+
+impl Drop for ImguiOverlay {
+    fn drop(&mut self) {
+        if let Some(texture) = self.font_texture {
+            gl_delete_texture(texture);
+        }
+    }
+}
+
+fn mouse_button(button: i32) -> Option<MouseButton> {
+    Some(match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Right,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Extra1,
+        4 => MouseButton::Extra2,
+        _ => return None,
+    })
+}
+
+/// The ImGui key for a GLFW key code. Letters and digits share their
+/// ASCII codes.
+fn imgui_key(key: i32) -> Option<Key> {
+    // Digits, letters and function keys are contiguous in ImGui's key
+    // list, which starts at `Tab`.
+    let nth = |first: Key, n: i32| Key::VARIANTS[first as usize - Key::Tab as usize + n as usize];
+    Some(match key {
+        GLFW_KEY_ESCAPE => Key::Escape,
+        GLFW_KEY_ENTER => Key::Enter,
+        GLFW_KEY_TAB => Key::Tab,
+        GLFW_KEY_BACKSPACE => Key::Backspace,
+        GLFW_KEY_INSERT => Key::Insert,
+        GLFW_KEY_DELETE => Key::Delete,
+        GLFW_KEY_RIGHT => Key::RightArrow,
+        GLFW_KEY_LEFT => Key::LeftArrow,
+        GLFW_KEY_DOWN => Key::DownArrow,
+        GLFW_KEY_UP => Key::UpArrow,
+        GLFW_KEY_PAGE_UP => Key::PageUp,
+        GLFW_KEY_PAGE_DOWN => Key::PageDown,
+        GLFW_KEY_HOME => Key::Home,
+        GLFW_KEY_END => Key::End,
+        GLFW_KEY_SPACE => Key::Space,
+        GLFW_KEY_F1..=GLFW_KEY_F12 => nth(Key::F1, key - GLFW_KEY_F1),
+        0x30..=0x39 => nth(Key::Alpha0, key - 0x30),
+        0x41..=0x5A => nth(Key::A, key - 0x41),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_map_to_imgui_keys() {
+        assert_eq!(imgui_key(0x41), Some(Key::A));
+        assert_eq!(imgui_key(0x5A), Some(Key::Z));
+        assert_eq!(imgui_key(0x37), Some(Key::Alpha7));
+        assert_eq!(imgui_key(GLFW_KEY_F1 + 4), Some(Key::F5));
+        assert_eq!(imgui_key(GLFW_KEY_F12), Some(Key::F12));
+        assert_eq!(imgui_key(GLFW_KEY_BACKSPACE), Some(Key::Backspace));
+    }
+}
@@ -0,0 +1,28 @@
+//! Window input helpers shared by the UI toolkits.
+
+/// The character a printable GLFW key types on a US layout.
+pub(crate) fn key_char(key: i32, shift: bool) -> Option<char> {
+    const UNSHIFTED: &str = "',-./0123456789;=[\\]`";
+    const SHIFTED: &str = "\"<_>?)!@#$%^&*(:+{|}~";
+    let c = char::from(u8::try_from(key).ok()?);
+    match c {
+        ' ' => Some(' '),
+        'A'..='Z' => Some(if shift { c } else { c.to_ascii_lowercase() }),
+        _ => UNSHIFTED.find(c).map(|i| if shift { SHIFTED.as_bytes()[i] as char } else { c }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::glfw::GLFW_KEY_ENTER;
+
+    #[test]
+    fn printable_keys_type_us_layout_characters() {
+        assert_eq!(key_char(0x41, false), Some('a'));
+        assert_eq!(key_char(0x41, true), Some('A'));
+        assert_eq!(key_char(0x32, true), Some('@'));
+        assert_eq!(key_char('/' as i32, false), Some('/'));
+        assert_eq!(key_char(GLFW_KEY_ENTER, false), None);
+    }
+}
@@ -0,0 +1,10 @@
+//! Immediate-mode UI toolkits drawn over the scene, each behind a feature.
+
+#[cfg(feature = "egui")]
+pub mod egui;
+#[cfg(feature = "imgui")]
+pub mod imgui;
+#[cfg(any(feature = "egui", feature = "imgui"))]
+mod input;
+#[cfg(any(feature = "egui", feature = "imgui"))]
+mod painter;
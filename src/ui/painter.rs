@@ -0,0 +1,116 @@
+use crate::core::engine::opengl::{
+    gl_active_texture, gl_bind_texture, gl_bind_vertex_array, gl_blend_func, gl_disable, gl_draw_arrays, gl_enable,
+    gl_get_uniform_location, gl_scissor, gl_uniform_2f, GLuint, GL_BLEND, GL_ONE, GL_ONE_MINUS_SRC_ALPHA,
+    GL_SCISSOR_TEST, GL_SRC_ALPHA, GL_TEXTURE0, GL_TEXTURE_2D, GL_TRIANGLES,
+};
+use crate::core::{Attribute, Geometry, Shader};
+
+/// Floats per vertex: position, texture coordinate, color.
+const VERTEX_FLOATS: usize = 8;
+
+/// A UI vertex: position in points (origin top-left), texture coordinate
+/// and RGBA color.
+pub(crate) type UiVertex = ([f32; 2], [f32; 2], [u8; 4]);
+
+/// Draws the clipped, textured triangle lists UI toolkits produce.
+///
+/// Collect a frame's triangles with [`add_triangles`](Self::add_triangles),
+/// then [`paint`](Self::paint) them in one buffer upload. Created lazily
+/// by the toolkits, since it needs a current GL context.
+pub(crate) struct UiPainter {
+    shader: Shader,
+    geometry: Geometry,
+    vertices: Vec<f32>,
+    /// First vertex, vertex count, texture and clip rectangle (points,
+    /// `[min_x, min_y, max_x, max_y]`).
+    draws: Vec<(i32, i32, GLuint, [f32; 4])>,
+}
+
+impl UiPainter {
+    pub(crate) fn new() -> Self {
+        let shader = Shader::compile(include_str!("shaders/ui.vert"), include_str!("shaders/ui.frag"), None)
+            .expect("UI shader failed to compile");
+        let mut geometry = Geometry::new(GL_TRIANGLES);
+        geometry.add_buffer(&[0.0; VERTEX_FLOATS], VERTEX_FLOATS as i32);
+        geometry.add_vertex_attribute(Attribute::new(0, 2, VERTEX_FLOATS, 0));
+        geometry.add_vertex_attribute(Attribute::new(1, 2, VERTEX_FLOATS, 2));
+        geometry.add_vertex_attribute(Attribute::new(2, 4, VERTEX_FLOATS, 4));
+        Self { shader, geometry, vertices: Vec::new(), draws: Vec::new() }
+    }
+
+    /// Queue a triangle list (three vertices per triangle) sampling
+    /// `texture`, clipped to `clip`.
+    pub(crate) fn add_triangles(&mut self, texture: GLuint, clip: [f32; 4], vertices: impl IntoIterator<Item = UiVertex>) {
+        let first = self.vertices.len() / VERTEX_FLOATS;
+        for (pos, uv, [r, g, b, a]) in vertices {
+            self.vertices.extend_from_slice(&[
+                pos[0],
+                pos[1],
+                uv[0],
+                uv[1],
+                r as f32 / 255.0,
+                g as f32 / 255.0,
+                b as f32 / 255.0,
+                a as f32 / 255.0,
+            ]);
+        }
+        let count = self.vertices.len() / VERTEX_FLOATS - first;
+        if count > 0 {
+            self.draws.push((first as i32, count as i32, texture, clip));
+        }
+    }
+
+    /// Draw and clear the queued triangles over a `screen` of that many
+    /// points, `framebuffer` pixels in size. `premultiplied` selects the
+    /// blending for colors with premultiplied alpha (egui) or straight
+    /// alpha (ImGui).
+    pub(crate) fn paint(&mut self, screen: (f32, f32), framebuffer: (i32, i32), premultiplied: bool) {
+        if self.draws.is_empty() {
+            return;
+        }
+        let pixels_per_point = framebuffer.0 as f32 / screen.0;
+        self.geometry.update_buffer(&self.vertices, VERTEX_FLOATS as i32);
+
+        self.shader.use_program();
+        self.geometry.bind();
+        let size_loc = gl_get_uniform_location(self.shader.program(), "u_screen_size");
+        gl_uniform_2f(size_loc, screen.0, screen.1);
+        gl_enable(GL_BLEND);
+        gl_blend_func(if premultiplied { GL_ONE } else { GL_SRC_ALPHA }, GL_ONE_MINUS_SRC_ALPHA);
+        gl_enable(GL_SCISSOR_TEST);
+        gl_active_texture(GL_TEXTURE0);
+        for &(first, count, texture, clip) in &self.draws {
+            let Some((x, y, w, h)) = scissor_rect(clip, pixels_per_point, framebuffer) else { continue };
+            gl_scissor(x, y, w, h);
+            gl_bind_texture(GL_TEXTURE_2D, texture);
+            gl_draw_arrays(GL_TRIANGLES, first, count);
+        }
+        gl_disable(GL_SCISSOR_TEST);
+        gl_blend_func(GL_SRC_ALPHA, GL_ONE_MINUS_SRC_ALPHA);
+        gl_bind_texture(GL_TEXTURE_2D, 0);
+        gl_bind_vertex_array(0);
+
+        self.vertices.clear();
+        self.draws.clear();
+    }
+}
+
+/// `clip` (points, Y down) as a GL scissor box (pixels, Y up), clamped to
+/// the framebuffer; `None` if nothing is left.
+fn scissor_rect(clip: [f32; 4], pixels_per_point: f32, (fb_width, fb_height): (i32, i32)) -> Option<(i32, i32, i32, i32)> {
+    let [min_x, min_y, max_x, max_y] = clip.map(|v| (v * pixels_per_point).round() as i32);
+    let (min_x, min_y) = (min_x.clamp(0, fb_width), min_y.clamp(0, fb_height));
+    let (max_x, max_y) = (max_x.clamp(min_x, fb_width), max_y.clamp(min_y, fb_height));
+    (max_x > min_x && max_y > min_y).then_some((min_x, fb_height - max_y, max_x - min_x, max_y - min_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scissor_flips_to_framebuffer_pixels_and_clamps() {
+        assert_eq!(scissor_rect([10.0, 20.0, 110.0, 70.0], 2.0, (400, 300)), Some((20, 160, 200, 100)));
+        assert_eq!(scissor_rect([500.0, 0.0, 600.0, 10.0], 1.0, (400, 300)), None);
+    }
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+in vec2 TexCoord;
+in vec4 vColor;
+out vec4 FragColor;
+
+uniform sampler2D u_texture;
+
+void main() {
+    // Premultiplied (egui) or straight (ImGui) alpha, matched by the blend function
+    FragColor = vColor * texture(u_texture, TexCoord);
+}
@@ -0,0 +1,16 @@
+#version 330 core
+
+uniform vec2 u_screen_size;                   // viewport size in points
+
+layout(location = 0) in vec2 aPos;            // points, origin top-left
+layout(location = 1) in vec2 aTexCoord;
+layout(location = 2) in vec4 aColor;          // sRGBA, premultiplied for egui
+
+out vec2 TexCoord;
+out vec4 vColor;
+
+void main() {
+    gl_Position = vec4(2.0 * aPos.x / u_screen_size.x - 1.0, 1.0 - 2.0 * aPos.y / u_screen_size.y, 0.0, 1.0);
+    TexCoord = aTexCoord;
+    vColor = aColor;
+}
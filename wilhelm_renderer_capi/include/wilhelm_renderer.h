@@ -0,0 +1,59 @@
+/*
+ * C API for embedding wilhelm_renderer.
+ *
+ * Link against libwilhelm_renderer_capi (shared or static) built by
+ * `cargo build --release -p wilhelm_renderer_capi`. Functions taking an app
+ * or a shape index return -1 for a null app, an unknown index or invalid
+ * arguments, and 0 on success. All calls must come from the thread that
+ * created the app.
+ */
+#ifndef WILHELM_RENDERER_H
+#define WILHELM_RENDERER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An RGBA color with components in [0, 1]. */
+typedef struct WrColor {
+    float r;
+    float g;
+    float b;
+    float a;
+} WrColor;
+
+/* A window and the shapes drawn in it. */
+typedef struct WrApp WrApp;
+
+/* Open a window. Returns NULL if title is NULL or not valid UTF-8. */
+WrApp* wr_app_create(const char* title, int width, int height, WrColor background);
+
+/* Destroy an app and close its window. NULL is ignored. */
+void wr_app_destroy(WrApp* app);
+
+/* Render one frame, swap buffers and poll events. Returns 1 while the window
+ * is open, 0 once it should close. */
+int wr_app_run_frame(WrApp* app);
+
+/* Add shapes; each returns the new shape's index. Rectangles are placed by
+ * their top-left corner, circles by their center. Polyline points are
+ * count interleaved x, y window coordinates (count >= 2). */
+int wr_app_add_rectangle(WrApp* app, float x, float y, float width, float height, WrColor fill);
+int wr_app_add_circle(WrApp* app, float x, float y, float radius, WrColor fill);
+int wr_app_add_polyline(WrApp* app, const float* xy, size_t count, WrColor stroke, float width);
+
+int wr_shape_set_position(WrApp* app, int shape, float x, float y);
+int wr_shape_set_fill_color(WrApp* app, int shape, WrColor fill);
+int wr_shape_set_visible(WrApp* app, int shape, int visible);
+
+/* Draw the shape once per position (count interleaved x, y floats) instead
+ * of at its own position. Call again each frame to animate. */
+int wr_shape_set_instance_positions(WrApp* app, int shape, const float* xy, size_t count);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WILHELM_RENDERER_H */
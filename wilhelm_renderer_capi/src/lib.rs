@@ -0,0 +1,195 @@
+//! A C ABI over [`wilhelm_renderer`], built as a shared and a static
+//! library, so C, C++ or Python (through `ctypes`) hosts can embed the
+//! renderer as a visualization window.
+//!
+//! The declarations are in `include/wilhelm_renderer.h`. A `WrApp` owns a
+//! window and its shapes; shapes are addressed by the index `wr_app_add_*`
+//! returns. Functions taking an app or a shape index return `-1` for a
+//! null app, an unknown index or invalid arguments, and `0` on success.
+//! All calls must come from the thread that created the app.
+
+use std::ffi::{c_char, c_int, CStr};
+
+use wilhelm_renderer::core::{App, Color, Vec2, Window};
+use wilhelm_renderer::graphics2d::shapes::{
+    Circle, Polyline, Rectangle, ShapeKind, ShapeRenderable, ShapeStyle,
+};
+
+/// An RGBA color with components in `[0, 1]`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct WrColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl From<WrColor> for Color {
+    fn from(c: WrColor) -> Self {
+        Color::from_rgba(c.r, c.g, c.b, c.a)
+    }
+}
+
+/// Opaque handle to an [`App`] and its window.
+pub struct WrApp {
+    app: App<'static>,
+}
+
+impl WrApp {
+    fn shape(&mut self, index: c_int) -> Option<&mut ShapeRenderable> {
+        usize::try_from(index).ok().and_then(|i| self.app.shapes_mut().get_mut(i))
+    }
+
+    fn add(&mut self, shape: ShapeRenderable) -> c_int {
+        c_int::try_from(self.app.add_shape(shape)).unwrap_or(-1)
+    }
+}
+
+/// Turn `count` interleaved `x, y` pairs into points.
+///
+/// # Safety
+/// `xy` must point to `2 * count` floats, or be null with `count == 0`.
+unsafe fn points<'p>(xy: *const f32, count: usize) -> Option<&'p [[f32; 2]]> {
+    if count == 0 {
+        return Some(&[]);
+    }
+    if xy.is_null() {
+        return None;
+    }
+    Some(std::slice::from_raw_parts(xy.cast::<[f32; 2]>(), count))
+}
+
+/// Open a window and create an app drawing into it. Returns null if
+/// `title` is not valid UTF-8.
+///
+/// # Safety
+/// `title` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_create(title: *const c_char, width: c_int, height: c_int, background: WrColor) -> *mut WrApp {
+    if title.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(title) = CStr::from_ptr(title).to_str() else { return std::ptr::null_mut() };
+    let window = Window::new(title, width, height, background.into());
+    Box::into_raw(Box::new(WrApp { app: App::new(window) }))
+}
+
+/// Destroy an app and close its window. Null is ignored.
+///
+/// # Safety
+/// `app` must come from [`wr_app_create`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_destroy(app: *mut WrApp) {
+    if !app.is_null() {
+        drop(Box::from_raw(app));
+    }
+}
+
+/// Render one frame, swap buffers and poll events. Returns `1` while the
+/// window is open, `0` once it should close and `-1` for a null app.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_run_frame(app: *mut WrApp) -> c_int {
+    match app.as_mut() {
+        Some(app) => c_int::from(app.app.run_frame()),
+        None => -1,
+    }
+}
+
+/// Add a filled rectangle with its top-left corner at `(x, y)`. Returns
+/// its shape index.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_add_rectangle(app: *mut WrApp, x: f32, y: f32, width: f32, height: f32, fill: WrColor) -> c_int {
+    let Some(app) = app.as_mut() else { return -1 };
+    let mut shape = ShapeRenderable::from_shape(ShapeKind::Rectangle(Rectangle::new(width, height)), ShapeStyle::fill(fill.into()));
+    shape.set_position(x, y);
+    app.add(shape)
+}
+
+/// Add a filled circle centered on `(x, y)`. Returns its shape index.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_add_circle(app: *mut WrApp, x: f32, y: f32, radius: f32, fill: WrColor) -> c_int {
+    let Some(app) = app.as_mut() else { return -1 };
+    let mut shape = ShapeRenderable::from_shape(ShapeKind::Circle(Circle::new(radius)), ShapeStyle::fill(fill.into()));
+    shape.set_position(x, y);
+    app.add(shape)
+}
+
+/// Add a polyline through `count` window points given as interleaved
+/// `x, y` floats. Returns its shape index.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`]; `xy` must point to
+/// `2 * count` floats.
+#[no_mangle]
+pub unsafe extern "C" fn wr_app_add_polyline(app: *mut WrApp, xy: *const f32, count: usize, stroke: WrColor, width: f32) -> c_int {
+    let (Some(app), Some(points)) = (app.as_mut(), points(xy, count)) else { return -1 };
+    if points.len() < 2 {
+        return -1;
+    }
+    let points = points.iter().map(|&[x, y]| (x, y)).collect();
+    let shape = ShapeRenderable::from_shape(ShapeKind::Polyline(Polyline::new(points)), ShapeStyle::stroke(stroke.into(), width));
+    app.add(shape)
+}
+
+/// Move a shape.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_shape_set_position(app: *mut WrApp, shape: c_int, x: f32, y: f32) -> c_int {
+    let Some(shape) = app.as_mut().and_then(|app| app.shape(shape)) else { return -1 };
+    shape.set_position(x, y);
+    0
+}
+
+/// Change a shape's fill color.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_shape_set_fill_color(app: *mut WrApp, shape: c_int, fill: WrColor) -> c_int {
+    let Some(shape) = app.as_mut().and_then(|app| app.shape(shape)) else { return -1 };
+    shape.set_fill_color(fill.into());
+    0
+}
+
+/// Show (`visible != 0`) or hide a shape.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`].
+#[no_mangle]
+pub unsafe extern "C" fn wr_shape_set_visible(app: *mut WrApp, shape: c_int, visible: c_int) -> c_int {
+    let Some(shape) = app.as_mut().and_then(|app| app.shape(shape)) else { return -1 };
+    shape.set_visible(visible != 0);
+    0
+}
+
+/// Draw the shape once per position, `count` interleaved `x, y` floats,
+/// instead of at its own position. Call again each frame to animate;
+/// with `count == 0` the shape is drawn once at its position again.
+///
+/// # Safety
+/// `app` must be null or come from [`wr_app_create`]; `xy` must point to
+/// `2 * count` floats.
+#[no_mangle]
+pub unsafe extern "C" fn wr_shape_set_instance_positions(app: *mut WrApp, shape: c_int, xy: *const f32, count: usize) -> c_int {
+    let (Some(shape), Some(points)) = (app.as_mut().and_then(|app| app.shape(shape)), points(xy, count)) else {
+        return -1;
+    };
+    if shape.instance_positions().is_empty() {
+        shape.create_multiple_instances(count);
+    }
+    let positions: Vec<Vec2> = points.iter().map(|&[x, y]| Vec2::new(x, y)).collect();
+    shape.set_instance_positions(&positions);
+    0
+}
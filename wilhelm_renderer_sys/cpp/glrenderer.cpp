@@ -1,5 +1,17 @@
 #include "glrenderer.h"
 
+// Native handles for raw-window-handle. Linux builds GLFW with both X11
+// and Wayland and picks one at runtime.
+#if defined(_WIN32)
+#define GLFW_EXPOSE_NATIVE_WIN32
+#elif defined(__APPLE__)
+#define GLFW_EXPOSE_NATIVE_COCOA
+#else
+#define GLFW_EXPOSE_NATIVE_X11
+#define GLFW_EXPOSE_NATIVE_WAYLAND
+#endif
+#include <GLFW/glfw3native.h>
+
 extern "C"
 {
 
@@ -8,6 +20,12 @@ extern "C"
     }
 
 
+    int _glfwInit()
+    {
+        glfwSetErrorCallback(glfwErrorCallback);
+        return glfwInit();
+    }
+
     GLFWwindow *_glfwCreateWindow(const char *title, int width, int height, GLFWframebuffersizefun callback)
     {
         glfwSetErrorCallback(glfwErrorCallback);
@@ -34,7 +52,7 @@ extern "C"
         //glfwWindowHint(GLFW_DECORATED, GLFW_FALSE);        
 
 #ifdef __APPLE__
-        glfwWindowHint(GLFW_OPENGL_FORWARD_COMPAT, GL_TRUE);
+        glfwWindowHint(GLFW_OPENGL_FORWARD_COMPAT, GLFW_TRUE);
 #endif
 
         GLFWwindow *window = glfwCreateWindow(width, height, title, nullptr, nullptr);
@@ -47,30 +65,25 @@ extern "C"
         }
         glfwMakeContextCurrent(window);
         glfwSetFramebufferSizeCallback(window, callback);
-
-        // glad: load all OpenGL function pointers
-        if (!gladLoadGLLoader((GLADloadproc)glfwGetProcAddress))
-        {
-            std::cerr << "Failed to initialize GLAD" << std::endl;
-            glfwDestroyWindow(window);
-            glfwTerminate();
-            return nullptr;
-        }
-
-        // Enable MSAA (glEnable must come AFTER context is current and GLAD is loaded)
-        glEnable(GL_MULTISAMPLE);
-        
-        int fb_width, fb_height;
-        glfwGetFramebufferSize(window, &fb_width, &fb_height);
-        glViewport(0, 0, fb_width, fb_height);
+        // GL functions are loaded on the Rust side, through _glfwGetProcAddress.
         return window;
     }
 
+    void *_glfwGetProcAddress(const char *name)
+    {
+        return (void *)glfwGetProcAddress(name);
+    }
+
     void _glfwGetWindowContentScale(GLFWwindow *window, float* xscale, float* yscale)
     {
         glfwGetWindowContentScale(window, xscale, yscale);
     }
 
+    void _glfwGetFramebufferSize(GLFWwindow *window, int* width, int* height)
+    {
+        glfwGetFramebufferSize(window, width, height);
+    }
+
     void _glfwWindowHint(int hint, int value)
     {
         glfwWindowHint(hint, value);
@@ -147,276 +160,69 @@ extern "C"
         glfwGetWindowSize(window, width, height);
     }
 
-    void _glClearColor(GLfloat x, GLfloat y, GLfloat z, GLfloat a)
-    {
-        glClearColor(x, y, z, a);
-        // Clean the back buffer and assign the new color to it
-        glClear(GL_COLOR_BUFFER_BIT);
-    }
-
-    void _glViewPort(GLint x, GLint y, GLsizei width, GLsizei height)
-    {
-        glViewport(x, y, width, height);
-    }
-
-    void _glGetIntegerv(GLenum pname, GLint *data)
-    {
-        glGetIntegerv(pname, data);
-    }
-
-    GLuint _glGenBuffer()
-    {
-        unsigned int VBO;
-        glGenBuffers(1, &VBO);
-        return VBO;
-    }
-
-    void _glGenBuffers(GLsizei n, GLuint *buffers)
-    {
-        glGenBuffers(n, buffers);
-    }
-
-    void _glDeleteBuffer(GLuint buffer)
-    {
-        glDeleteBuffers(1, &buffer);
-    }
-
-    void _glBindBuffer(GLenum target, GLuint buffer)
-    {
-        glBindBuffer(target, buffer);
-    }
-
-    void _glBufferData(GLenum mode, GLsizeiptr size, const GLvoid *data, GLenum usage)
-    {
-        glBufferData(mode, size, data, usage);
-    }
-
-    void _glBufferSubData(GLenum target, GLintptr offset, GLsizeiptr size, const GLvoid *data)
-    {
-        glBufferSubData(target, offset, size, data);
-    }
-
-    GLuint _glGenVertexArray()
-    {
-        unsigned int VAO;
-        glGenVertexArrays(1, &VAO);
-        return VAO;
-    }
-
-    void _glDeleteVertexArray(GLuint vao)
-    {
-        glDeleteVertexArrays(1, &vao);
-    }
-
-    void _glBindVertexArray(GLuint array)
-    {
-        glBindVertexArray(array);
-    }
-
-    void _glVertexAttribPointer(GLuint index, GLint size, GLenum type, GLboolean normalized, GLsizei stride, GLsizei offset)
-    {
-        glVertexAttribPointer(index, size, type, normalized, stride, (void *)offset);
-    }
-
-    void _glEnableVertexAttribArray(GLuint index)
-    {
-        glEnableVertexAttribArray(index);
-    }
-
-    GLint _glGenTexture()
-    {
-        unsigned int texture;
-        glGenTextures(1, &texture);
-        return texture;
-    }
-
-    void _glActiveTexture(GLenum unit)
-    {
-        glActiveTexture(unit);
-    }
-
-    void _glBindTexture(GLenum target, GLuint texture)
-    {
-        glBindTexture(target, texture);
-    }
-
-    void _glTexParameteri(GLenum target, GLenum pname, GLint param)
-    {
-        glTexParameteri(target, pname, param);
-    }
-
-    void _glTexImage2D(GLenum target, GLint level, GLint internalformat, GLsizei width, GLsizei height, GLint border, GLenum format, GLenum type, const void *data)
-    {
-        glTexImage2D(target, level, internalformat, width, height, border, format, type, data);
-        GLenum error = glGetError();
-        if (error == GL_INVALID_OPERATION)
-        {
-            printf("OpenGL error: %d\n", error);
-        }
-        else
-        {
-            std::cout << "glTextImage2D called successfully" << std::endl;
-        }
-    }
-
-    void _glTexSubImage2D(GLenum target, GLint level, GLint xoffset, GLint yoffset, GLsizei width, GLsizei height, GLenum format, GLenum type, const void *data)
-    {
-        glTexSubImage2D(target, level, xoffset, yoffset, width, height, format, type, data);
-    }
-
-    void _glGenerateMipmap(GLenum target)
-    {
-        glGenerateMipmap(target);
-    }
-
-    void _glPixelStorei(GLenum pname, GLint param)
-    {
-        glPixelStorei(pname, param);
-    }
-
-    void _glDeleteTexture(GLuint texture)
-    {
-        glDeleteTextures(1, &texture);
-    }
-
-    GLuint _glCreateShader(GLenum shaderType)
-    {
-        return glCreateShader(shaderType);
-    }
-
-    void _glShaderSource(GLuint shader, GLchar *source)
+    int _glfwGetPlatform()
     {
-        glShaderSource(shader, 1, &source, NULL);
+        return glfwGetPlatform();
     }
 
-    void _glCompileShader(GLuint shader)
+    // Native window and display handles. Each returns null (0) on
+    // platforms it does not apply to.
+    void *_glfwGetWin32Window(GLFWwindow *window)
     {
-        glCompileShader(shader);
-#ifndef NDEBUG
-        int success;
-        char infoLog[512];
-        glGetShaderiv(shader, GL_COMPILE_STATUS, &success);
-        if (!success)
-        {
-            glGetShaderInfoLog(shader, 512, NULL, infoLog);
-            std::cout << "ERROR::SHADER::VERTEX::COMPILATION_FAILED\n"
-                      << infoLog << std::endl;
-        }
-        else
-        {
-            std::cout << "shader " << shader << " compiled successfully" << std::endl;
-        }
+#if defined(_WIN32)
+        return glfwGetWin32Window(window);
+#else
+        (void)window;
+        return nullptr;
 #endif
     }
 
-    void _glDeleteShader(GLuint shader)
-    {
-        glDeleteShader(shader);
-    }
-
-    void _glGetShaderiv(GLuint shader, GLenum pname, GLint *params)
-    {
-        glGetShaderiv(shader, pname, params);
-    }
-
-    GLuint _glCreateProgram()
-    {
-        return glCreateProgram();
-    }
-
-    void _glAttachShader(GLuint program, GLuint shader)
-    {
-        glAttachShader(program, shader);
-    }
-
-    void _glLinkProgram(GLuint program)
-    {
-        glLinkProgram(program);
-    }
-
-    void _glDeleteProgram(GLuint program)
-    {
-        glDeleteProgram(program);
-    }
-
-    void _glUseProgram(GLuint program)
-    {
-        glUseProgram(program);
-    }
-
-    void _glDrawArrays(GLenum mode, GLint first, GLsizei count)
+    void *_glfwGetCocoaView(GLFWwindow *window)
     {
-        glDrawArrays(mode, first, count);
-    }
-
-    void _glDrawArraysInstanced(GLenum mode, GLint first, GLsizei count, GLsizei instancecount)
-    {
-        glDrawArraysInstanced(mode, first, count, instancecount);
-    }
-
-    void _glVertexAttribDivisor(GLuint index, GLuint divisor)
-    {
-        glVertexAttribDivisor(index, divisor);
-    }
-
-    void _glVertexAttrib4f(GLuint index, GLfloat v0, GLfloat v1, GLfloat v2, GLfloat v3)
-    {
-        glVertexAttrib4f(index, v0, v1, v2, v3);
-    }
-
-    void _glDrawElements(GLenum mode, GLsizei count, GLenum type, GLuint offset)
-    {
-        glDrawElements(mode, count, type, (void *)(offset));
-    }
-
-    GLint _glGetUniformLocation(GLuint program, GLchar *name)
-    {
-        return glGetUniformLocation(program, name);
-    }
-
-    void _glUniform1f(GLint location, GLfloat v0)
-    {
-        glUniform1f(location, v0);
-    }
-
-    void _glUniform2f(GLint location, GLfloat v0, GLfloat v1)
-    {
-        glUniform2f(location, v0, v1);
-    }
-
-    void _glUniform3f(GLint location, GLfloat v0, GLfloat v1, GLfloat v2)
-    {
-        glUniform3f(location, v0, v1, v2);
-    }
-
-    void _glUniform4f(GLint location, GLfloat v0, GLfloat v1, GLfloat v2, GLfloat v3)
-    {
-        glUniform4f(location, v0, v1, v2, v3);
-    }
-
-    void _glUniformMatrix4fv(GLint location, GLsizei count, GLboolean transpose, const GLfloat *value)
-    {
-        glUniformMatrix4fv(location, count, transpose, value);
+#if defined(__APPLE__)
+        return (void *)glfwGetCocoaView(window);
+#else
+        (void)window;
+        return nullptr;
+#endif
     }
 
-    void _glPointSize(GLfloat size)
+    unsigned long _glfwGetX11Window(GLFWwindow *window)
     {
-        glPointSize(size);
+#if !defined(_WIN32) && !defined(__APPLE__)
+        return glfwGetX11Window(window);
+#else
+        (void)window;
+        return 0;
+#endif
     }
 
-    void _glEnable(GLenum cap)
+    void *_glfwGetX11Display()
     {
-        glEnable(cap);
+#if !defined(_WIN32) && !defined(__APPLE__)
+        return glfwGetX11Display();
+#else
+        return nullptr;
+#endif
     }
 
-    void _glBlendFunc(GLenum sfactor, GLenum dfactor)
+    void *_glfwGetWaylandWindow(GLFWwindow *window)
     {
-        glBlendFunc(sfactor, dfactor);
+#if !defined(_WIN32) && !defined(__APPLE__)
+        return glfwGetWaylandWindow(window);
+#else
+        (void)window;
+        return nullptr;
+#endif
     }
 
-    int _glfwGetPlatform()
+    void *_glfwGetWaylandDisplay()
     {
-        return glfwGetPlatform();
+#if !defined(_WIN32) && !defined(__APPLE__)
+        return glfwGetWaylandDisplay();
+#else
+        return nullptr;
+#endif
     }
 
     // ============ FreeType ============
@@ -459,6 +265,11 @@ extern "C"
         return FT_Load_Char(face, char_code, load_flags);
     }
 
+    unsigned int _ft_get_char_index(FT_Face face, unsigned long char_code)
+    {
+        return FT_Get_Char_Index(face, char_code);
+    }
+
     void _ft_get_glyph_metrics(FT_Face face, FT_GlyphMetrics *metrics)
     {
         FT_GlyphSlot glyph = face->glyph;
@@ -40,6 +40,7 @@ unsafe extern "C" {
     pub fn _ft_done_face(face: FT_Face);
     pub fn _ft_set_pixel_sizes(face: FT_Face, width: c_uint, height: c_uint) -> c_int;
     pub fn _ft_load_char(face: FT_Face, char_code: c_ulong, load_flags: c_int) -> c_int;
+    pub fn _ft_get_char_index(face: FT_Face, char_code: c_ulong) -> c_uint;
 
     pub fn _ft_get_glyph_metrics(face: FT_Face, metrics: *mut GlyphMetrics);
     pub fn _ft_get_glyph_bitmap(face: FT_Face) -> *const c_uchar;
@@ -8,9 +8,11 @@ use std::ffi::c_void;
 use std::os::raw::c_char;
 use std::os::raw::c_double;
 use std::os::raw::c_int;
+use std::os::raw::c_ulong;
 
 pub const GLFW_SAMPLES: i32 = 0x0002100D;
 pub const GLFW_SCALE_TO_MONITOR: i32 = 0x0002200C;
+pub const GLFW_VISIBLE: i32 = 0x00020004;
 
 pub const GLFW_PLATFORM_WIN32: i32 = 0x00060001;
 pub const GLFW_PLATFORM_COCOA: i32 = 0x00060002;
@@ -95,6 +97,8 @@ pub const GLFW_KEY_RIGHT_ALT: i32 = 346;
 pub const GLFW_KEY_RIGHT_SUPER: i32 = 347;
 
 unsafe extern "C" {
+    pub fn _glfwInit() -> c_int;
+
     pub fn _glfwCreateWindow(
         title: *const c_char,
         width: c_int,
@@ -108,6 +112,8 @@ unsafe extern "C" {
         yscale: *mut c_float,
     );
 
+    pub fn _glfwGetFramebufferSize(window: *const GLFWwindow, width: *mut c_int, height: *mut c_int);
+
     pub fn _glfwWindowHint(hint: c_int, value: c_int);
 
     pub fn _glfwSetWindowUserPointer(window: *const GLFWwindow, pointer: *const c_void);
@@ -133,4 +139,19 @@ unsafe extern "C" {
     pub fn _glfwGetWindowSize(window: *const GLFWwindow, width: *mut c_int, height: *mut c_int);
 
     pub fn _glfwGetPlatform() -> c_int;
+    /// Resolve a GL function of the current context; a [`GLloadproc`](crate::opengl::GLloadproc).
+    pub fn _glfwGetProcAddress(name: *const c_char) -> *const c_void;
+
+    /// `HWND` on Windows, null elsewhere.
+    pub fn _glfwGetWin32Window(window: *const GLFWwindow) -> *mut c_void;
+    /// `NSView` on macOS, null elsewhere.
+    pub fn _glfwGetCocoaView(window: *const GLFWwindow) -> *mut c_void;
+    /// X11 `Window` id, 0 off X11.
+    pub fn _glfwGetX11Window(window: *const GLFWwindow) -> c_ulong;
+    /// X11 `Display*`, null off X11.
+    pub fn _glfwGetX11Display() -> *mut c_void;
+    /// Wayland `wl_surface*`, null off Wayland.
+    pub fn _glfwGetWaylandWindow(window: *const GLFWwindow) -> *mut c_void;
+    /// Wayland `wl_display*`, null off Wayland.
+    pub fn _glfwGetWaylandDisplay() -> *mut c_void;
 }
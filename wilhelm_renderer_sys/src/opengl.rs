@@ -1,10 +1,23 @@
-//! Raw OpenGL FFI bindings.
+//! Raw OpenGL bindings.
 //!
-//! All extern declarations, C-compatible types, and OpenGL constants
-//! used by `wilhelm_renderer`. Safe Rust wrappers live in the upper
-//! crate.
+//! C-compatible types, OpenGL constants and the `_gl*` entry points used
+//! by `wilhelm_renderer`. The entry points call GL function pointers
+//! resolved at runtime by [`_glLoad`], so any GL function can be bound by
+//! adding one line to the `gl_functions!` list below. Safe Rust wrappers
+//! live in the upper crate.
+//!
+//! # Safety
+//!
+//! Every `_gl*` function other than [`_glLoad`] needs a context current on
+//! the calling thread whose functions were loaded by `_glLoad`, and
+//! pointer arguments must be valid for what the GL function reads or
+//! writes. Calling a function the context does not provide panics.
+
+// The entry points mirror GL's names and parameter lists.
+#![allow(non_snake_case, clippy::missing_safety_doc, clippy::too_many_arguments)]
 
-use std::ffi::{c_char, c_float, c_int, c_long, c_uint, c_void};
+use std::ffi::{c_char, c_float, c_int, c_long, c_uint, c_void, CStr};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
 
 pub type GLenum = c_uint;
 pub type GLsizei = c_int;
@@ -14,10 +27,13 @@ pub type GLint = c_int;
 pub type GLuint = c_uint;
 pub type GLfloat = c_float;
 pub type GLvoid = c_void;
+pub type GLbitfield = c_uint;
+pub type GLsync = *mut c_void;
 
 /// A 2D vector with guaranteed C-compatible memory layout.
 /// Used for uploading vertex data to OpenGL.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Vec2 {
     pub x: f32,
@@ -50,9 +66,25 @@ pub const GL_FRAGMENT_SHADER: u32 = 0x8B30;
 pub const GL_VERTEX_SHADER: u32 = 0x8B31;
 pub const GL_GEOMETRY_SHADER: u32 = 0x8DD9;
 pub const GL_COMPILE_STATUS: u32 = 0x8B81;
+pub const GL_LINK_STATUS: u32 = 0x8B82;
+pub const GL_INFO_LOG_LENGTH: u32 = 0x8B84;
 
 pub const GL_STATIC_DRAW: u32 = 0x88E4;
 pub const GL_DYNAMIC_DRAW: u32 = 0x88E8;
+pub const GL_STREAM_DRAW: u32 = 0x88E0;
+pub const GL_STREAM_READ: u32 = 0x88E1;
+pub const GL_PIXEL_PACK_BUFFER: u32 = 0x88EB;
+pub const GL_PIXEL_UNPACK_BUFFER: u32 = 0x88EC;
+pub const GL_MAP_READ_BIT: GLbitfield = 0x0001;
+pub const GL_MAP_WRITE_BIT: GLbitfield = 0x0002;
+pub const GL_MAP_INVALIDATE_RANGE_BIT: GLbitfield = 0x0004;
+pub const GL_MAP_INVALIDATE_BUFFER_BIT: GLbitfield = 0x0008;
+pub const GL_MAP_UNSYNCHRONIZED_BIT: GLbitfield = 0x0020;
+pub const GL_SYNC_FLUSH_COMMANDS_BIT: GLbitfield = 0x0001;
+pub const GL_ALREADY_SIGNALED: u32 = 0x911A;
+pub const GL_TIMEOUT_EXPIRED: u32 = 0x911B;
+pub const GL_CONDITION_SATISFIED: u32 = 0x911C;
+pub const GL_WAIT_FAILED: u32 = 0x911D;
 pub const GL_FLOAT: u32 = 0x1406;
 pub const GL_UNSIGNED_INT: u32 = 0x1405;
 pub const GL_UNSIGNED_BYTE: u32 = 0x1401;
@@ -65,8 +97,14 @@ pub const GL_TRIANGLE_STRIP: u32 = 0x0005;
 pub const GL_VIEWPORT: u32 = 0x0BA2;
 pub const GL_TEXTURE_2D: u32 = 0x0DE1;
 pub const GL_RED: u32 = 0x1903;
+pub const GL_R8: GLint = 0x8229;
 pub const GL_CULL_FACE: u32 = 0x0B44;
+pub const GL_DEPTH_TEST: u32 = 0x0B71;
+pub const GL_LESS: u32 = 0x0201;
+pub const GL_LEQUAL: u32 = 0x0203;
 pub const GL_BLEND: u32 = 0x0BE2;
+pub const GL_SCISSOR_TEST: u32 = 0x0C11;
+pub const GL_ONE: u32 = 1;
 pub const GL_SRC_ALPHA: u32 = 0x0302;
 pub const GL_ONE_MINUS_SRC_ALPHA: u32 = 0x0303;
 pub const GL_TEXTURE0: u32 = 0x84C0;
@@ -74,106 +112,599 @@ pub const GL_TEXTURE0: u32 = 0x84C0;
 pub const GL_TEXTURE_WRAP_S: u32 = 0x2802;
 pub const GL_TEXTURE_WRAP_T: u32 = 0x2803;
 pub const GL_REPEAT: GLint = 0x2901;
+pub const GL_MIRRORED_REPEAT: GLint = 0x8370;
 pub const GL_CLAMP_TO_EDGE: GLint = 0x812F;
 pub const GL_TEXTURE_MAG_FILTER: u32 = 0x2800;
 pub const GL_TEXTURE_MIN_FILTER: u32 = 0x2801;
+pub const GL_NEAREST: GLint = 0x2600;
 pub const GL_LINEAR: GLint = 0x2601;
+pub const GL_NEAREST_MIPMAP_NEAREST: GLint = 0x2700;
+pub const GL_LINEAR_MIPMAP_NEAREST: GLint = 0x2701;
+pub const GL_NEAREST_MIPMAP_LINEAR: GLint = 0x2702;
 pub const GL_LINEAR_MIPMAP_LINEAR: GLint = 0x2703;
 pub const GL_RGB: GLint = 0x1907;
 pub const GL_RGBA: GLint = 0x1908;
 pub const GL_MULTISAMPLE: GLuint = 0x809D;
 pub const GL_SAMPLES: GLuint = 0x80A9;
 pub const GL_UNPACK_ALIGNMENT: GLenum = 0x0CF5;
+pub const GL_FRAMEBUFFER: GLenum = 0x8D40;
+pub const GL_COLOR_ATTACHMENT0: GLenum = 0x8CE0;
+pub const GL_FRAMEBUFFER_COMPLETE: GLenum = 0x8CD5;
+pub const GL_FRAMEBUFFER_BINDING: GLenum = 0x8CA6;
+pub const GL_VENDOR: GLenum = 0x1F00;
+pub const GL_RENDERER: GLenum = 0x1F01;
+pub const GL_VERSION: GLenum = 0x1F02;
+pub const GL_EXTENSIONS: GLenum = 0x1F03;
+pub const GL_SHADING_LANGUAGE_VERSION: GLenum = 0x8B8C;
+pub const GL_NUM_EXTENSIONS: GLenum = 0x821D;
+pub const GL_MAX_TEXTURE_SIZE: GLenum = 0x0D33;
+pub const GL_MAX_SAMPLES: GLenum = 0x8D57;
+
+/// Resolves an OpenGL function by name, as passed to [`_glLoad`].
+pub type GLloadproc = Option<unsafe extern "C" fn(name: *const c_char) -> *const c_void>;
+
+const GL_COLOR_BUFFER_BIT: GLbitfield = 0x4000;
+const GL_DEPTH_BUFFER_BIT: GLbitfield = 0x0100;
+const GL_INVALID_OPERATION: GLenum = 0x0502;
+const GL_SYNC_GPU_COMMANDS_COMPLETE: GLenum = 0x9117;
+
+/// Declares the GL functions to resolve. Each becomes a pointer in
+/// `FUNCTIONS` and a `gl::name` function calling through it.
+macro_rules! gl_functions {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        struct Functions {
+            $($name: AtomicPtr<c_void>,)*
+        }
+
+        static FUNCTIONS: Functions = Functions {
+            $($name: AtomicPtr::new(std::ptr::null_mut()),)*
+        };
+
+        /// Resolve every function through `loader`, returning the names of
+        /// those it could not find.
+        unsafe fn load_functions(loader: unsafe extern "C" fn(*const c_char) -> *const c_void) -> Vec<&'static str> {
+            let mut missing = Vec::new();
+            $(
+                let function = unsafe { loader(concat!(stringify!($name), "\0").as_ptr().cast()) };
+                if function.is_null() {
+                    missing.push(stringify!($name));
+                }
+                FUNCTIONS.$name.store(function as *mut c_void, Ordering::Relaxed);
+            )*
+            missing
+        }
+
+        mod gl {
+            use super::*;
+            $(
+                #[inline]
+                pub(super) unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                    let function = FUNCTIONS.$name.load(Ordering::Relaxed);
+                    assert!(!function.is_null(), concat!(stringify!($name), " is not loaded"));
+                    // SAFETY: resolved by the loader under this name, with
+                    // the signature declared in `gl_functions!`.
+                    let function: unsafe extern "system" fn($($ty),*) $(-> $ret)? = unsafe { std::mem::transmute(function) };
+                    unsafe { function($($arg),*) }
+                }
+            )*
+        }
+    };
+}
+
+gl_functions! {
+    glGetString(name: GLenum) -> *const u8;
+    glGetStringi(name: GLenum, index: GLuint) -> *const u8;
+    glGetError() -> GLenum;
+    glGetIntegerv(pname: GLenum, data: *mut GLint);
+    glClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
+    glClear(mask: GLbitfield);
+    glViewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+    glScissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+    glEnable(cap: GLenum);
+    glDisable(cap: GLenum);
+    glBlendFunc(sfactor: GLenum, dfactor: GLenum);
+    glDepthFunc(func: GLenum);
+    glDepthMask(flag: u8);
+    glPointSize(size: GLfloat);
+
+    glCreateShader(shader_type: GLenum) -> GLuint;
+    glShaderSource(shader: GLuint, count: GLsizei, string: *const *const GLchar, length: *const GLint);
+    glCompileShader(shader: GLuint);
+    glGetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint);
+    glGetShaderInfoLog(shader: GLuint, max_length: GLsizei, length: *mut GLsizei, info_log: *mut GLchar);
+    glDeleteShader(shader: GLuint);
+    glCreateProgram() -> GLuint;
+    glAttachShader(program: GLuint, shader: GLuint);
+    glLinkProgram(program: GLuint);
+    glDeleteProgram(program: GLuint);
+    glGetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint);
+    glGetProgramInfoLog(program: GLuint, max_length: GLsizei, length: *mut GLsizei, info_log: *mut GLchar);
+    glUseProgram(program: GLuint);
+    glGetUniformLocation(program: GLuint, name: *const GLchar) -> GLint;
+    glUniform1f(location: GLint, v0: GLfloat);
+    glUniform2f(location: GLint, v0: GLfloat, v1: GLfloat);
+    glUniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat);
+    glUniform4f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat);
+    glUniformMatrix4fv(location: GLint, count: GLsizei, transpose: u8, value: *const GLfloat);
+
+    glGenBuffers(n: GLsizei, buffers: *mut GLuint);
+    glDeleteBuffers(n: GLsizei, buffers: *const GLuint);
+    glBindBuffer(target: GLenum, buffer: GLuint);
+    glBufferData(target: GLenum, size: isize, data: *const GLvoid, usage: GLenum);
+    glBufferSubData(target: GLenum, offset: isize, size: isize, data: *const GLvoid);
+    glMapBufferRange(target: GLenum, offset: isize, length: isize, access: GLbitfield) -> *mut GLvoid;
+    glUnmapBuffer(target: GLenum) -> u8;
+    glFenceSync(condition: GLenum, flags: GLbitfield) -> GLsync;
+    glClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: u64) -> GLenum;
+    glDeleteSync(sync: GLsync);
+
+    glGenVertexArrays(n: GLsizei, arrays: *mut GLuint);
+    glDeleteVertexArrays(n: GLsizei, arrays: *const GLuint);
+    glBindVertexArray(array: GLuint);
+    glVertexAttribPointer(index: GLuint, size: GLint, data_type: GLenum, normalized: u8, stride: GLsizei, pointer: *const GLvoid);
+    glEnableVertexAttribArray(index: GLuint);
+    glVertexAttribDivisor(index: GLuint, divisor: GLuint);
+    glVertexAttrib4f(index: GLuint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat);
+
+    glGenTextures(n: GLsizei, textures: *mut GLuint);
+    glDeleteTextures(n: GLsizei, textures: *const GLuint);
+    glActiveTexture(unit: GLenum);
+    glBindTexture(target: GLenum, texture: GLuint);
+    glTexParameteri(target: GLenum, pname: GLenum, param: GLint);
+    glTexImage2D(target: GLenum, level: GLint, internalformat: GLint, width: GLsizei, height: GLsizei, border: GLint, format: GLenum, data_type: GLenum, data: *const GLvoid);
+    glTexSubImage2D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint, width: GLsizei, height: GLsizei, format: GLenum, data_type: GLenum, data: *const GLvoid);
+    glGenerateMipmap(target: GLenum);
+    glPixelStorei(pname: GLenum, param: GLint);
+
+    glGenFramebuffers(n: GLsizei, framebuffers: *mut GLuint);
+    glDeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint);
+    glBindFramebuffer(target: GLenum, framebuffer: GLuint);
+    glFramebufferTexture2D(target: GLenum, attachment: GLenum, textarget: GLenum, texture: GLuint, level: GLint);
+    glCheckFramebufferStatus(target: GLenum) -> GLenum;
+    glReadPixels(x: GLint, y: GLint, width: GLsizei, height: GLsizei, format: GLenum, data_type: GLenum, data: *mut GLvoid);
+
+    glDrawArrays(mode: GLenum, first: GLint, count: GLsizei);
+    glDrawArraysInstanced(mode: GLenum, first: GLint, count: GLsizei, instancecount: GLsizei);
+    glDrawElements(mode: GLenum, count: GLsizei, element_type: GLenum, indices: *const GLvoid);
+}
+
+/// Set by [`_glLoad`] when the loaded context is OpenGL ES, which has no
+/// glPointSize and no GL_MULTISAMPLE toggle.
+static GLES_CONTEXT: AtomicBool = AtomicBool::new(false);
+
+/// Resolve the GL functions of the context current on the calling thread
+/// through `loader` (e.g. `glfwGetProcAddress` or `eglGetProcAddress`), or
+/// the platform's GL library when `None`. Returns 0 if functions the
+/// renderer needs are missing.
+pub unsafe fn _glLoad(loader: GLloadproc) -> c_int {
+    let missing = unsafe { load_functions(loader.unwrap_or(platform::get_proc_address)) };
+    if missing.contains(&"glGetString") {
+        // Without it there is no context to query; call nothing else.
+        log::error!("failed to load OpenGL functions: {}", missing.join(", "));
+        return 0;
+    }
+    let version = unsafe { gl::glGetString(GL_VERSION) };
+    let gles = !version.is_null() && unsafe { CStr::from_ptr(version.cast()) }.to_bytes().starts_with(b"OpenGL ES");
+    GLES_CONTEXT.store(gles, Ordering::Relaxed);
+    if !version.is_null() {
+        log::info!("loaded {}", unsafe { CStr::from_ptr(version.cast()) }.to_string_lossy());
+    }
+    // OpenGL ES has no glPointSize; shaders write gl_PointSize instead.
+    let missing: Vec<_> = missing.into_iter().filter(|&name| !(gles && name == "glPointSize")).collect();
+    if !missing.is_empty() {
+        log::error!("failed to load OpenGL functions: {}", missing.join(", "));
+        return 0;
+    }
+    if !gles {
+        unsafe { gl::glEnable(GL_MULTISAMPLE) };
+    }
+    1
+}
+
+/// The platform's own lookup, for contexts created without a loader.
+mod platform {
+    use std::ffi::{c_char, c_void};
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub(super) unsafe extern "C" fn get_proc_address(name: *const c_char) -> *const c_void {
+        unsafe extern "C" {
+            fn glXGetProcAddressARB(name: *const u8) -> *const c_void;
+        }
+        unsafe { glXGetProcAddressARB(name.cast()) }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) unsafe extern "C" fn get_proc_address(name: *const c_char) -> *const c_void {
+        use std::sync::OnceLock;
+        unsafe extern "C" {
+            fn dlopen(path: *const c_char, mode: i32) -> *mut c_void;
+            fn dlsym(handle: *mut c_void, name: *const c_char) -> *const c_void;
+        }
+        const RTLD_LAZY: i32 = 1;
+        static LIBRARY: OnceLock<usize> = OnceLock::new();
+        let library = *LIBRARY.get_or_init(|| unsafe {
+            dlopen(c"/System/Library/Frameworks/OpenGL.framework/OpenGL".as_ptr(), RTLD_LAZY) as usize
+        });
+        if library == 0 {
+            return std::ptr::null();
+        }
+        unsafe { dlsym(library as *mut c_void, name) }
+    }
+
+    #[cfg(windows)]
+    pub(super) unsafe extern "C" fn get_proc_address(name: *const c_char) -> *const c_void {
+        #[link(name = "opengl32")]
+        unsafe extern "system" {
+            fn wglGetProcAddress(name: *const c_char) -> *const c_void;
+        }
+        unsafe extern "system" {
+            fn GetModuleHandleA(name: *const c_char) -> *mut c_void;
+            fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *const c_void;
+        }
+        let function = unsafe { wglGetProcAddress(name) };
+        // wglGetProcAddress only knows extensions and post-1.1 functions,
+        // and signals failure with small sentinel values as well as null.
+        if !matches!(function as isize, -1..=3) {
+            return function;
+        }
+        unsafe { GetProcAddress(GetModuleHandleA(c"opengl32.dll".as_ptr()), name) }
+    }
+}
+
+/// A NUL-terminated string owned by GL, or null for an unknown `name`.
+pub unsafe fn _glGetString(name: GLenum) -> *const c_char {
+    unsafe { gl::glGetString(name).cast() }
+}
+
+/// Entry `index` of an indexed string such as `GL_EXTENSIONS`.
+pub unsafe fn _glGetStringi(name: GLenum, index: GLuint) -> *const c_char {
+    unsafe { gl::glGetStringi(name, index).cast() }
+}
+
+pub unsafe fn _glIsES() -> c_int {
+    GLES_CONTEXT.load(Ordering::Relaxed) as c_int
+}
+
+/// Set the clear color and clear the color buffer with it.
+pub unsafe fn _glClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    unsafe {
+        gl::glClearColor(red, green, blue, alpha);
+        gl::glClear(GL_COLOR_BUFFER_BIT);
+    }
+}
+
+/// Clear the depth buffer to the default clear depth, the far plane.
+pub unsafe fn _glClearDepthBuffer() {
+    unsafe { gl::glClear(GL_DEPTH_BUFFER_BIT) }
+}
+
+pub unsafe fn _glViewPort(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    unsafe { gl::glViewport(x, y, width, height) }
+}
+
+pub unsafe fn _glScissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    unsafe { gl::glScissor(x, y, width, height) }
+}
+
+pub unsafe fn _glGetIntegerv(pname: GLenum, data: *mut GLvoid) {
+    unsafe { gl::glGetIntegerv(pname, data.cast()) }
+}
+
+pub unsafe fn _glCreateShader(shaderType: GLenum) -> GLuint {
+    unsafe { gl::glCreateShader(shaderType) }
+}
+
+pub unsafe fn _glShaderSource(shader: GLuint, source: *const c_char) {
+    unsafe { gl::glShaderSource(shader, 1, &source, std::ptr::null()) }
+}
+
+/// Compile `shader`; debug builds print the info log if it fails.
+pub unsafe fn _glCompileShader(shader: GLuint) {
+    unsafe { gl::glCompileShader(shader) };
+    #[cfg(debug_assertions)]
+    {
+        const GL_COMPILE_STATUS: GLenum = 0x8B81;
+        let mut success = 0;
+        unsafe { gl::glGetShaderiv(shader, GL_COMPILE_STATUS, &mut success) };
+        if success == 0 {
+            let mut length = 0;
+            unsafe { gl::glGetShaderiv(shader, GL_INFO_LOG_LENGTH, &mut length) };
+            let mut info_log = vec![0u8; length.max(1) as usize];
+            unsafe { gl::glGetShaderInfoLog(shader, info_log.len() as GLsizei, std::ptr::null_mut(), info_log.as_mut_ptr().cast()) };
+            let info_log = CStr::from_bytes_until_nul(&info_log).map(CStr::to_string_lossy).unwrap_or_default();
+            log::error!("shader {shader} failed to compile:\n{info_log}");
+        }
+    }
+}
+
+pub unsafe fn _glDeleteShader(shader: GLuint) {
+    unsafe { gl::glDeleteShader(shader) }
+}
+
+pub unsafe fn _glGetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint) {
+    unsafe { gl::glGetShaderiv(shader, pname, params) }
+}
+
+/// Write up to `max_length` bytes of the shader's info log, nul-terminated,
+/// to `info_log`.
+pub unsafe fn _glGetShaderInfoLog(shader: GLuint, max_length: GLsizei, info_log: *mut c_char) {
+    unsafe { gl::glGetShaderInfoLog(shader, max_length, std::ptr::null_mut(), info_log.cast()) }
+}
+
+pub unsafe fn _glCreateProgram() -> GLuint {
+    unsafe { gl::glCreateProgram() }
+}
+
+pub unsafe fn _glAttachShader(program: GLuint, shader: GLuint) {
+    unsafe { gl::glAttachShader(program, shader) }
+}
+
+pub unsafe fn _glLinkProgram(program: GLuint) {
+    unsafe { gl::glLinkProgram(program) }
+}
+
+pub unsafe fn _glDeleteProgram(program: GLuint) {
+    unsafe { gl::glDeleteProgram(program) }
+}
+
+pub unsafe fn _glGetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint) {
+    unsafe { gl::glGetProgramiv(program, pname, params) }
+}
+
+/// Write up to `max_length` bytes of the program's info log, nul-terminated,
+/// to `info_log`.
+pub unsafe fn _glGetProgramInfoLog(program: GLuint, max_length: GLsizei, info_log: *mut c_char) {
+    unsafe { gl::glGetProgramInfoLog(program, max_length, std::ptr::null_mut(), info_log.cast()) }
+}
+
+pub unsafe fn _glUseProgram(program: GLuint) {
+    unsafe { gl::glUseProgram(program) }
+}
+
+pub unsafe fn _glGenBuffer() -> GLuint {
+    let mut buffer = 0;
+    unsafe { gl::glGenBuffers(1, &mut buffer) };
+    buffer
+}
+
+pub unsafe fn _glGenBuffers(n: GLsizei, buffers: *mut GLuint) {
+    unsafe { gl::glGenBuffers(n, buffers) }
+}
+
+pub unsafe fn _glBindBuffer(target: GLenum, buffer: GLuint) {
+    unsafe { gl::glBindBuffer(target, buffer) }
+}
+
+pub unsafe fn _glBufferData(target: GLenum, size: GLsizeiptr, data: *const GLvoid, usage: GLenum) {
+    unsafe { gl::glBufferData(target, size as isize, data, usage) }
+}
+
+pub unsafe fn _glBufferSubData(target: GLenum, offset: GLsizeiptr, size: GLsizeiptr, data: *const GLvoid) {
+    unsafe { gl::glBufferSubData(target, offset as isize, size as isize, data) }
+}
+
+pub unsafe fn _glDeleteBuffer(buffer: GLuint) {
+    unsafe { gl::glDeleteBuffers(1, &buffer) }
+}
+
+pub unsafe fn _glMapBufferRange(target: GLenum, offset: GLsizeiptr, length: GLsizeiptr, access: GLbitfield) -> *mut GLvoid {
+    unsafe { gl::glMapBufferRange(target, offset as isize, length as isize, access) }
+}
+
+/// Returns 0 if the buffer contents were lost while mapped.
+pub unsafe fn _glUnmapBuffer(target: GLenum) -> GLint {
+    unsafe { gl::glUnmapBuffer(target) as GLint }
+}
+
+/// Fence the commands issued so far (`GL_SYNC_GPU_COMMANDS_COMPLETE`).
+pub unsafe fn _glFenceSync() -> GLsync {
+    unsafe { gl::glFenceSync(GL_SYNC_GPU_COMMANDS_COMPLETE, 0) }
+}
+
+pub unsafe fn _glClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: u64) -> GLenum {
+    unsafe { gl::glClientWaitSync(sync, flags, timeout) }
+}
+
+pub unsafe fn _glDeleteSync(sync: GLsync) {
+    unsafe { gl::glDeleteSync(sync) }
+}
+
+pub unsafe fn _glGenVertexArray() -> GLuint {
+    let mut vao = 0;
+    unsafe { gl::glGenVertexArrays(1, &mut vao) };
+    vao
+}
+
+pub unsafe fn _glDeleteVertexArray(vao: GLuint) {
+    unsafe { gl::glDeleteVertexArrays(1, &vao) }
+}
+
+pub unsafe fn _glBindVertexArray(VAO: GLuint) {
+    unsafe { gl::glBindVertexArray(VAO) }
+}
+
+/// `offset` is a byte offset into the bound array buffer.
+pub unsafe fn _glVertexAttribPointer(
+    index: GLuint,
+    size: GLint,
+    dataType: GLenum,
+    normalize: GLboolean,
+    stride: GLsizei,
+    offset: GLsizei,
+) {
+    unsafe { gl::glVertexAttribPointer(index, size, dataType, normalize as u8, stride, offset as usize as *const GLvoid) }
+}
+
+pub unsafe fn _glActiveTexture(unit: GLenum) {
+    unsafe { gl::glActiveTexture(unit) }
+}
+
+pub unsafe fn _glGenTexture() -> GLuint {
+    let mut texture = 0;
+    unsafe { gl::glGenTextures(1, &mut texture) };
+    texture
+}
+
+pub unsafe fn _glBindTexture(target: GLenum, texture: GLuint) {
+    unsafe { gl::glBindTexture(target, texture) }
+}
+
+pub unsafe fn _glTexParameteri(target: GLenum, pname: GLenum, param: GLint) {
+    unsafe { gl::glTexParameteri(target, pname, param) }
+}
+
+pub unsafe fn _glGenerateMipmap(target: GLenum) {
+    unsafe { gl::glGenerateMipmap(target) }
+}
+
+/// Upload a texture image, printing `GL_INVALID_OPERATION` errors.
+pub unsafe fn _glTexImage2D(
+    target: GLenum,
+    level: GLint,
+    internalformat: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    border: GLint,
+    format: GLenum,
+    dataType: GLenum,
+    data: *const GLvoid,
+) {
+    unsafe { gl::glTexImage2D(target, level, internalformat, width, height, border, format, dataType, data) };
+    let error = unsafe { gl::glGetError() };
+    if error == GL_INVALID_OPERATION {
+        log::error!("glTexImage2D failed with GL_INVALID_OPERATION ({error:#x})");
+    }
+}
+
+pub unsafe fn _glTexSubImage2D(
+    target: GLenum,
+    level: GLint,
+    xoffset: GLint,
+    yoffset: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    dataType: GLenum,
+    data: *const GLvoid,
+) {
+    unsafe { gl::glTexSubImage2D(target, level, xoffset, yoffset, width, height, format, dataType, data) }
+}
+
+pub unsafe fn _glPixelStorei(pname: GLenum, param: GLint) {
+    unsafe { gl::glPixelStorei(pname, param) }
+}
+
+pub unsafe fn _glDeleteTexture(texture: GLuint) {
+    unsafe { gl::glDeleteTextures(1, &texture) }
+}
+
+pub unsafe fn _glGenFramebuffer() -> GLuint {
+    let mut framebuffer = 0;
+    unsafe { gl::glGenFramebuffers(1, &mut framebuffer) };
+    framebuffer
+}
+
+pub unsafe fn _glBindFramebuffer(target: GLenum, framebuffer: GLuint) {
+    unsafe { gl::glBindFramebuffer(target, framebuffer) }
+}
+
+pub unsafe fn _glFramebufferTexture2D(target: GLenum, attachment: GLenum, textarget: GLenum, texture: GLuint, level: GLint) {
+    unsafe { gl::glFramebufferTexture2D(target, attachment, textarget, texture, level) }
+}
+
+pub unsafe fn _glCheckFramebufferStatus(target: GLenum) -> GLenum {
+    unsafe { gl::glCheckFramebufferStatus(target) }
+}
+
+pub unsafe fn _glDeleteFramebuffer(framebuffer: GLuint) {
+    unsafe { gl::glDeleteFramebuffers(1, &framebuffer) }
+}
+
+pub unsafe fn _glReadPixels(
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    dataType: GLenum,
+    data: *mut GLvoid,
+) {
+    unsafe { gl::glReadPixels(x, y, width, height, format, dataType, data) }
+}
+
+pub unsafe fn _glEnableVertexAttribArray(index: GLuint) {
+    unsafe { gl::glEnableVertexAttribArray(index) }
+}
+
+pub unsafe fn _glDrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
+    unsafe { gl::glDrawArrays(mode, first, count) }
+}
+
+pub unsafe fn _glDrawArraysInstanced(mode: GLenum, first: GLint, count: GLsizei, instancecount: GLsizei) {
+    unsafe { gl::glDrawArraysInstanced(mode, first, count, instancecount) }
+}
+
+pub unsafe fn _glVertexAttribDivisor(index: GLuint, divisor: GLuint) {
+    unsafe { gl::glVertexAttribDivisor(index, divisor) }
+}
+
+pub unsafe fn _glVertexAttrib4f(index: GLuint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat) {
+    unsafe { gl::glVertexAttrib4f(index, v0, v1, v2, v3) }
+}
+
+/// `offset` is a byte offset into the bound element array buffer.
+pub unsafe fn _glDrawElements(mode: GLenum, count: GLsizei, element_type: GLenum, offset: GLuint) {
+    unsafe { gl::glDrawElements(mode, count, element_type, offset as usize as *const GLvoid) }
+}
+
+pub unsafe fn _glGetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+    unsafe { gl::glGetUniformLocation(program, name) }
+}
+
+pub unsafe fn _glUniform1f(location: GLint, v0: GLfloat) {
+    unsafe { gl::glUniform1f(location, v0) }
+}
+
+pub unsafe fn _glUniform2f(location: GLint, v0: GLfloat, v1: GLfloat) {
+    unsafe { gl::glUniform2f(location, v0, v1) }
+}
+
+pub unsafe fn _glUniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+    unsafe { gl::glUniform3f(location, v0, v1, v2) }
+}
+
+pub unsafe fn _glUniform4f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat) {
+    unsafe { gl::glUniform4f(location, v0, v1, v2, v3) }
+}
+
+pub unsafe fn _glUniformMatrix4fv(location: GLint, count: GLsizei, transpose: GLboolean, value: *const GLfloat) {
+    unsafe { gl::glUniformMatrix4fv(location, count, transpose as u8, value) }
+}
+
+/// Set the rasterized point size; a no-op on OpenGL ES, where GLSL ES
+/// shaders write `gl_PointSize` instead.
+pub unsafe fn _glPointSize(size: GLfloat) {
+    if !GLES_CONTEXT.load(Ordering::Relaxed) {
+        unsafe { gl::glPointSize(size) }
+    }
+}
+
+pub unsafe fn _glEnable(cap: GLenum) {
+    unsafe { gl::glEnable(cap) }
+}
+
+pub unsafe fn _glDisable(cap: GLenum) {
+    unsafe { gl::glDisable(cap) }
+}
+
+pub unsafe fn _glBlendFunc(sfactor: GLenum, dfactor: GLenum) {
+    unsafe { gl::glBlendFunc(sfactor, dfactor) }
+}
+
+pub unsafe fn _glDepthFunc(func: GLenum) {
+    unsafe { gl::glDepthFunc(func) }
+}
 
-unsafe extern "C" {
-    pub fn _glClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
-    pub fn _glViewPort(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
-    pub fn _glGetIntegerv(pname: GLenum, data: *mut GLvoid);
-    pub fn _glCreateShader(shaderType: GLenum) -> GLuint;
-    pub fn _glShaderSource(shader: GLuint, source: *const c_char);
-    pub fn _glCompileShader(shader: GLuint);
-    pub fn _glDeleteShader(shader: GLuint);
-    pub fn _glGetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint);
-    pub fn _glCreateProgram() -> GLuint;
-    pub fn _glAttachShader(program: GLuint, shader: GLuint);
-    pub fn _glLinkProgram(program: GLuint);
-    pub fn _glDeleteProgram(program: GLuint);
-    pub fn _glUseProgram(program: GLuint);
-    pub fn _glGenBuffer() -> GLuint;
-    pub fn _glGenBuffers(n: GLsizei, buffers: *mut GLuint);
-    pub fn _glBindBuffer(target: GLenum, buffer: GLuint);
-    pub fn _glBufferData(target: GLenum, size: GLsizeiptr, data: *const GLvoid, usage: GLenum);
-    pub fn _glBufferSubData(
-        target: GLenum,
-        offset: GLsizeiptr,
-        size: GLsizeiptr,
-        data: *const GLvoid,
-    );
-    pub fn _glDeleteBuffer(buffer: GLuint);
-    pub fn _glGenVertexArray() -> GLuint;
-    pub fn _glDeleteVertexArray(vao: GLuint);
-    pub fn _glBindVertexArray(VAO: GLuint);
-    pub fn _glVertexAttribPointer(
-        index: GLuint,
-        size: GLint,
-        dataType: GLenum,
-        normalize: GLboolean,
-        stride: GLsizei,
-        offset: GLsizei,
-    );
-    pub fn _glActiveTexture(unit: GLenum);
-    pub fn _glGenTexture() -> GLuint;
-    pub fn _glBindTexture(target: GLenum, texture: GLuint);
-    pub fn _glTexParameteri(target: GLenum, pname: GLenum, param: GLint);
-    pub fn _glGenerateMipmap(target: GLenum);
-    pub fn _glTexImage2D(
-        target: GLenum,
-        level: GLint,
-        internalformat: GLint,
-        width: GLsizei,
-        height: GLsizei,
-        border: GLint,
-        format: GLenum,
-        dataType: GLenum,
-        data: *const GLvoid,
-    );
-    pub fn _glTexSubImage2D(
-        target: GLenum,
-        level: GLint,
-        xoffset: GLint,
-        yoffset: GLint,
-        width: GLsizei,
-        height: GLsizei,
-        format: GLenum,
-        dataType: GLenum,
-        data: *const GLvoid,
-    );
-    pub fn _glPixelStorei(pname: GLenum, param: GLint);
-    pub fn _glDeleteTexture(texture: GLuint);
-    pub fn _glEnableVertexAttribArray(index: GLuint);
-    pub fn _glDrawArrays(mode: GLenum, first: GLint, count: GLsizei);
-    pub fn _glDrawArraysInstanced(
-        mode: GLenum,
-        first: GLint,
-        count: GLsizei,
-        instancecount: GLsizei,
-    );
-    pub fn _glVertexAttribDivisor(index: GLuint, divisor: GLuint);
-    pub fn _glVertexAttrib4f(index: GLuint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat);
-    pub fn _glDrawElements(mode: GLenum, count: GLsizei, element_type: GLenum, offset: GLuint);
-
-    pub fn _glGetUniformLocation(program: GLuint, name: *const GLchar) -> GLint;
-    pub fn _glUniform1f(location: GLint, v0: GLfloat);
-    pub fn _glUniform2f(location: GLint, v0: GLfloat, v1: GLfloat);
-    pub fn _glUniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat);
-    pub fn _glUniform4f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat);
-    pub fn _glUniformMatrix4fv(
-        location: GLint,
-        count: GLsizei,
-        transpose: GLboolean,
-        value: *const GLfloat,
-    );
-    pub fn _glPointSize(size: GLfloat);
-    pub fn _glEnable(cap: GLenum);
-    pub fn _glBlendFunc(sfactor: GLenum, dfactor: GLenum);
+pub unsafe fn _glDepthMask(flag: GLboolean) {
+    unsafe { gl::glDepthMask(flag as u8) }
 }